@@ -0,0 +1,40 @@
+//! Synthetic telemetry shared by the benchmarks: a fleet of sites and devices,
+//! each publishing a few JSON readings.
+
+use mqtop::mqtt::MqttMessage;
+
+pub const SITES: usize = 10;
+pub const DEVICES_PER_SITE: usize = 50;
+pub const READINGS: [&str; 4] = ["telemetry", "status", "power", "env"];
+
+/// Every topic in the fleet, `site/<n>/device/<n>/<reading>`
+pub fn topics() -> Vec<String> {
+    let mut topics = Vec::with_capacity(SITES * DEVICES_PER_SITE * READINGS.len());
+    for site in 0..SITES {
+        for device in 0..DEVICES_PER_SITE {
+            for reading in READINGS {
+                topics.push(format!("site/{}/device/{}/{}", site, device, reading));
+            }
+        }
+    }
+    topics
+}
+
+pub fn payload(seq: usize) -> Vec<u8> {
+    format!(
+        r#"{{"temperature":{:.1},"humidity":{},"power":{{"watts":{},"voltage":230.1}},"status":"ok","seq":{}}}"#,
+        20.0 + (seq % 50) as f64 / 10.0,
+        40 + seq % 20,
+        100 + seq % 400,
+        seq
+    )
+    .into_bytes()
+}
+
+/// `count` messages round-robin over the fleet's topics
+pub fn messages(count: usize) -> Vec<MqttMessage> {
+    let topics = topics();
+    (0..count)
+        .map(|seq| MqttMessage::new(topics[seq % topics.len()].clone(), payload(seq), 0, false))
+        .collect()
+}
@@ -0,0 +1,71 @@
+//! End-to-end benchmark: a burst of messages through `App` as the MQTT client
+//! delivers them, then one frame rendered, as each UI tick does.
+//!
+//! ```bash
+//! cargo bench --bench pipeline
+//! ```
+
+mod common;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use mqtop::{app::App, config::Config, mqtt::MqttEvent, paths::Paths, ui};
+use ratatui::{backend::TestBackend, Terminal};
+
+const CONFIG: &str = r##"
+[mqtt]
+active_server = "bench"
+
+[[mqtt.servers]]
+name = "bench"
+host = "localhost"
+port = 1883
+client_id = "mqtop-bench"
+subscribe_topic = "#"
+"##;
+
+fn new_app(dir: &tempfile::TempDir) -> App {
+    let config = Config::parse(CONFIG).expect("bench config");
+    let paths = Paths {
+        config_dir: dir.path().to_path_buf(),
+        state_dir: dir.path().to_path_buf(),
+        cache_dir: dir.path().to_path_buf(),
+        profile: None,
+    };
+    let mut app = App::new(config, paths.config_file(), &paths);
+    app.metric_tracker.track(
+        "temp".into(),
+        "site/+/device/+/telemetry".into(),
+        "temperature".into(),
+    );
+    app
+}
+
+/// Messages per second of traffic, delivered in one tick
+fn feed(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("pipeline");
+    group.sample_size(20);
+
+    for rate in [1_000, 10_000, 50_000] {
+        let messages = common::messages(rate);
+        group.throughput(Throughput::Elements(rate as u64));
+        group.bench_with_input(BenchmarkId::new("feed_and_render", rate), &rate, |b, _| {
+            let mut app = new_app(&dir);
+            let mut terminal = Terminal::new(TestBackend::new(160, 48)).unwrap();
+            b.iter_batched(
+                || messages.clone(),
+                |messages| {
+                    for message in messages {
+                        app.handle_mqtt_event(MqttEvent::Message(message));
+                    }
+                    terminal.draw(|frame| ui::render(frame, &mut app)).unwrap();
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, feed);
+criterion_main!(benches);
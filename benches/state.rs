@@ -0,0 +1,122 @@
+//! Benchmarks for the per-message state updates: topic tree, message buffer
+//! and metric extraction.
+//!
+//! ```bash
+//! cargo bench --bench state
+//! ```
+
+mod common;
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use mqtop::state::{get_numeric_fields, MessageBuffer, MetricTracker, TopicTree};
+
+fn topic_tree(c: &mut Criterion) {
+    let topics = common::topics();
+    let mut group = c.benchmark_group("topic_tree");
+    group.throughput(Throughput::Elements(topics.len() as u64));
+
+    group.bench_function("insert_new", |b| {
+        b.iter_batched(
+            TopicTree::new,
+            |mut tree| {
+                for topic in &topics {
+                    tree.insert(topic, 120);
+                }
+                tree
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let mut tree = TopicTree::new();
+    for topic in &topics {
+        tree.insert(topic, 120);
+    }
+    group.bench_function("insert_existing", |b| {
+        b.iter(|| {
+            for topic in &topics {
+                tree.insert(black_box(topic), 120);
+            }
+        })
+    });
+
+    let collapsed = HashSet::new();
+    group.bench_function("visible_collapsed", |b| {
+        b.iter(|| tree.get_visible_topics(black_box(&collapsed)))
+    });
+
+    let expanded: HashSet<String> = tree.expandable_paths_from("").into_iter().collect();
+    group.bench_function("visible_expanded", |b| {
+        b.iter(|| tree.get_visible_topics(black_box(&expanded)))
+    });
+    group.finish();
+}
+
+fn message_buffer(c: &mut Criterion) {
+    let messages = common::messages(10_000);
+    let mut group = c.benchmark_group("message_buffer");
+    group.throughput(Throughput::Elements(messages.len() as u64));
+
+    // Full buffers, so every push also evicts the oldest message of its topic
+    let mut buffer = MessageBuffer::new(5);
+    for message in &messages {
+        buffer.push(message.clone());
+    }
+    group.bench_function("push", |b| {
+        b.iter_batched(
+            || messages.clone(),
+            |messages| {
+                for message in messages {
+                    buffer.push(message);
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn metrics(c: &mut Criterion) {
+    let messages = common::messages(2_000);
+    let mut group = c.benchmark_group("metrics");
+    group.throughput(Throughput::Elements(messages.len() as u64));
+
+    let mut tracker = MetricTracker::new(Duration::from_secs(3600), 360);
+    tracker.track(
+        "temp".into(),
+        "site/+/device/+/telemetry".into(),
+        "temperature".into(),
+    );
+    tracker.track("watts".into(), "site/#".into(), "power.watts".into());
+    tracker.track(
+        "humidity".into(),
+        "site/0/device/+/env".into(),
+        "humidity".into(),
+    );
+    group.bench_function("process_message", |b| {
+        b.iter(|| {
+            for message in &messages {
+                black_box(tracker.process_message(&message.topic, &message.payload));
+            }
+        })
+    });
+
+    let values: Vec<serde_json::Value> = messages
+        .iter()
+        .map(|message| serde_json::from_slice(&message.payload).unwrap())
+        .collect();
+    group.bench_function("numeric_fields", |b| {
+        b.iter(|| {
+            for value in &values {
+                black_box(get_numeric_fields(value));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, topic_tree, message_buffer, metrics);
+criterion_main!(benches);
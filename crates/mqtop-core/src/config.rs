@@ -0,0 +1,255 @@
+//! Broker connection settings: the `[mqtt]` and `[nats]` sections of the
+//! config file, and what the clients need to connect.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConfig {
+    pub active_server: String,
+    #[serde(default)]
+    pub servers: Vec<MqttServerConfig>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NatsConfig {
+    pub active_server: String,
+    #[serde(default)]
+    pub servers: Vec<NatsServerConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttServerConfig {
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub use_tls: bool,
+    /// Path to custom CA certificate (PEM format)
+    pub ca_cert: Option<String>,
+    /// Path to client certificate for mTLS (PEM format)
+    pub client_cert: Option<String>,
+    /// Path to client private key for mTLS (PEM format)
+    pub client_key: Option<String>,
+    /// Skip TLS certificate verification (insecure, for testing only)
+    #[serde(default)]
+    pub tls_insecure: bool,
+    pub client_id: String,
+    /// If true, use client_id exactly as specified (no auto-generated suffix)
+    /// If false and client_id is empty, generates "mqtop-{timestamp}"
+    /// If false and client_id is set, appends "-{timestamp}" for reconnect safety
+    #[serde(default)]
+    pub use_exact_client_id: bool,
+    /// Username for MQTT auth (defaults to client_id if not set)
+    pub username: Option<String>,
+    /// Token for authentication (goes in password field)
+    pub token: Option<String>,
+    #[serde(default = "default_subscribe_topic")]
+    pub subscribe_topic: String,
+    /// QoS level for subscriptions (0, 1, or 2)
+    #[serde(default = "default_qos")]
+    pub subscribe_qos: u8,
+    #[serde(default = "default_keep_alive")]
+    pub keep_alive_secs: u64,
+    /// MQTT protocol version (3 = 3.1.1, 5 = 5.0)
+    #[serde(default = "default_mqtt_version")]
+    pub mqtt_version: u8,
+    /// Clean session - if true, broker discards previous session state
+    #[serde(default = "default_clean_session")]
+    pub clean_session: bool,
+    /// Last Will and Testament topic (optional)
+    pub lwt_topic: Option<String>,
+    /// Last Will and Testament payload (optional)
+    pub lwt_payload: Option<String>,
+    /// Last Will QoS (0, 1, or 2)
+    #[serde(default)]
+    pub lwt_qos: u8,
+    /// Last Will retain flag
+    #[serde(default)]
+    pub lwt_retain: bool,
+    /// Client tuning preset (low_latency, balanced, high_throughput)
+    #[serde(default)]
+    pub preset: ClientPreset,
+    /// Override the preset's inflight window (unacked outgoing QoS 1/2 messages)
+    pub inflight: Option<u16>,
+    /// Override the preset's max packet size in bytes
+    pub max_packet_size: Option<usize>,
+    /// Override the preset's request channel capacity
+    pub request_channel_capacity: Option<usize>,
+}
+
+/// Presets for the MQTT client's inflight window, packet size and request queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientPreset {
+    /// Small window and queue so nothing waits behind a backlog
+    LowLatency,
+    #[default]
+    Balanced,
+    /// Large window, packets and queue for busy brokers
+    HighThroughput,
+}
+
+/// Effective MQTT client limits after applying a preset and overrides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientTuning {
+    pub inflight: u16,
+    pub max_packet_size: usize,
+    pub request_channel_capacity: usize,
+}
+
+impl ClientPreset {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClientPreset::LowLatency => "low latency",
+            ClientPreset::Balanced => "balanced",
+            ClientPreset::HighThroughput => "high throughput",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ClientPreset::LowLatency => ClientPreset::Balanced,
+            ClientPreset::Balanced => ClientPreset::HighThroughput,
+            ClientPreset::HighThroughput => ClientPreset::LowLatency,
+        }
+    }
+
+    pub fn tuning(&self) -> ClientTuning {
+        match self {
+            ClientPreset::LowLatency => ClientTuning {
+                inflight: 10,
+                max_packet_size: 256 * 1024,
+                request_channel_capacity: 64,
+            },
+            ClientPreset::Balanced => ClientTuning {
+                inflight: 100,
+                max_packet_size: 1024 * 1024,
+                request_channel_capacity: 1000,
+            },
+            ClientPreset::HighThroughput => ClientTuning {
+                inflight: 1000,
+                max_packet_size: 16 * 1024 * 1024,
+                request_channel_capacity: 10_000,
+            },
+        }
+    }
+}
+
+impl MqttConfig {
+    pub fn active_index(&self) -> Option<usize> {
+        self.servers
+            .iter()
+            .position(|server| server.name == self.active_server)
+    }
+
+    pub fn active_server(&self) -> Option<&MqttServerConfig> {
+        self.active_index().and_then(|idx| self.servers.get(idx))
+    }
+
+    pub fn active_server_mut(&mut self) -> Option<&mut MqttServerConfig> {
+        let idx = self.active_index()?;
+        self.servers.get_mut(idx)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsServerConfig {
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_nats_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub use_tls: bool,
+    /// Path to custom CA certificate (PEM format)
+    pub ca_cert: Option<String>,
+    /// Skip TLS certificate verification (insecure, for testing only)
+    #[serde(default)]
+    pub tls_insecure: bool,
+    /// Username for NATS auth (optional)
+    pub username: Option<String>,
+    /// Token for NATS auth (optional; also used as password for user/pass setups)
+    pub token: Option<String>,
+    /// Optional NATS creds file (JWT/NKey)
+    pub creds_file: Option<String>,
+    #[serde(default = "default_nats_subscribe_subject")]
+    pub subscribe_subject: String,
+}
+
+impl NatsConfig {
+    pub fn active_index(&self) -> Option<usize> {
+        self.servers
+            .iter()
+            .position(|server| server.name == self.active_server)
+    }
+
+    pub fn active_server(&self) -> Option<&NatsServerConfig> {
+        self.active_index().and_then(|idx| self.servers.get(idx))
+    }
+}
+
+impl MqttServerConfig {
+    /// Get the username, defaulting to client_id if not set
+    pub fn get_username(&self) -> &str {
+        self.username.as_deref().unwrap_or(&self.client_id)
+    }
+
+    /// Get the token, returning empty string if none set
+    pub fn get_token(&self) -> &str {
+        self.token.as_deref().unwrap_or("")
+    }
+
+    /// Client limits from the preset, with any per-server overrides applied
+    pub fn tuning(&self) -> ClientTuning {
+        let preset = self.preset.tuning();
+        ClientTuning {
+            inflight: self.inflight.unwrap_or(preset.inflight),
+            max_packet_size: self.max_packet_size.unwrap_or(preset.max_packet_size),
+            request_channel_capacity: self
+                .request_channel_capacity
+                .unwrap_or(preset.request_channel_capacity),
+        }
+    }
+}
+
+impl NatsServerConfig {
+    pub fn get_username(&self) -> &str {
+        self.username.as_deref().unwrap_or("")
+    }
+
+    pub fn get_token(&self) -> &str {
+        self.token.as_deref().unwrap_or("")
+    }
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_nats_port() -> u16 {
+    4222
+}
+
+fn default_subscribe_topic() -> String {
+    "#".to_string()
+}
+
+fn default_nats_subscribe_subject() -> String {
+    ">".to_string()
+}
+
+fn default_keep_alive() -> u64 {
+    30
+}
+
+fn default_qos() -> u8 {
+    1
+}
+
+fn default_mqtt_version() -> u8 {
+    3 // 3 = MQTT 3.1.1, 5 = MQTT 5.0
+}
+
+fn default_clean_session() -> bool {
+    true
+}
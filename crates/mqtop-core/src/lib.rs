@@ -0,0 +1,51 @@
+//! The engine behind mqtop, without the terminal UI: broker clients that
+//! turn a connection into a stream of events, and the state that mqtop
+//! builds from those events.
+//!
+//! - [`mqtt::MqttClient`] and [`nats::NatsClient`] connect with a server from
+//!   [`config`] and send every message, state change and error to an
+//!   `mpsc` channel as an [`mqtt::MqttEvent`].
+//! - [`state`] holds what is derived from messages: the
+//!   [`TopicTree`](state::TopicTree), per-topic history in a
+//!   [`MessageBuffer`](state::MessageBuffer), rolling
+//!   [`Stats`](state::Stats), and trackers for devices, latency, metrics,
+//!   schemas and watchdogs. None of them do I/O or need a runtime, so a
+//!   frontend feeds them from its own loop.
+//! - [`numbers::NumberFormat`] writes counts, byte sizes, rates and metric
+//!   values in one configurable style.
+//!
+//! A minimal frontend that counts topics:
+//!
+//! ```no_run
+//! use mqtop_core::config::MqttServerConfig;
+//! use mqtop_core::mqtt::{MqttClient, MqttEvent};
+//! use mqtop_core::state::{MessageBuffer, Stats, TopicTree};
+//!
+//! # async fn run(server: MqttServerConfig) -> anyhow::Result<()> {
+//! let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+//! let client = MqttClient::connect(server, tx).await?;
+//! client.subscribe().await?;
+//!
+//! let mut tree = TopicTree::new();
+//! let mut buffer = MessageBuffer::new(100);
+//! let mut stats = Stats::new(10);
+//! while let Some(event) = rx.recv().await {
+//!     if let MqttEvent::Message(message) = event {
+//!         tree.insert(&message.topic, message.payload.len());
+//!         stats.record_message(message.payload.len());
+//!         buffer.push(message);
+//!         println!("{} topics, {:.0} msg/s", tree.topic_count(), stats.messages_per_second());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `mqtop` binary is one such frontend; it re-exports these modules so
+//! its own paths (`mqtop::state`, `mqtop::numbers`, ...) keep working.
+
+pub mod config;
+pub mod mqtt;
+pub mod nats;
+pub mod numbers;
+pub mod state;
@@ -0,0 +1,258 @@
+//! Limits and features a broker advertises in its MQTT 5 CONNACK. mqtop talks
+//! MQTT 3.1.1, whose CONNACK carries none of them, so a short-lived MQTT 5
+//! connection asks, under its own client ID so the real session is untouched.
+//! The answer is kept for the rest of the run, so reconnects don't ask again.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use rumqttc::tokio_rustls::rustls::ClientConfig;
+use rumqttc::v5::mqttbytes::v5::{ConnAckProperties, Packet};
+use rumqttc::v5::{AsyncClient, ConnectionError, Event, MqttOptions};
+use rumqttc::{TlsConfiguration, Transport};
+
+use crate::config::MqttServerConfig;
+use crate::mqtt::subscription::parse_shared;
+
+/// How long the capability probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Capabilities probed earlier in this run, by broker and user
+static PROBED: Mutex<Vec<(String, BrokerCapabilities)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokerCapabilities {
+    pub max_packet_size: Option<u32>,
+    pub topic_alias_max: Option<u16>,
+    pub receive_max: Option<u16>,
+    /// Highest QoS the broker accepts (2 when not advertised)
+    pub max_qos: u8,
+    pub retain_available: bool,
+    pub wildcard_available: bool,
+    pub shared_available: bool,
+    /// Keep-alive the broker imposes instead of the requested one
+    pub server_keep_alive: Option<u16>,
+}
+
+/// What MQTT 5 assumes when the CONNACK leaves a property out
+impl Default for BrokerCapabilities {
+    fn default() -> Self {
+        Self {
+            max_packet_size: None,
+            topic_alias_max: None,
+            receive_max: None,
+            max_qos: 2,
+            retain_available: true,
+            wildcard_available: true,
+            shared_available: true,
+            server_keep_alive: None,
+        }
+    }
+}
+
+impl BrokerCapabilities {
+    /// Read the CONNACK properties; absent ones take their MQTT 5 defaults
+    pub fn from_properties(properties: Option<&ConnAckProperties>) -> Self {
+        let available = |flag: Option<u8>| flag != Some(0);
+        match properties {
+            Some(p) => Self {
+                max_packet_size: p.max_packet_size,
+                topic_alias_max: p.topic_alias_max,
+                receive_max: p.receive_max,
+                max_qos: p.max_qos.unwrap_or(2),
+                retain_available: available(p.retain_available),
+                wildcard_available: available(p.wildcard_subscription_available),
+                shared_available: available(p.shared_subscription_available),
+                server_keep_alive: p.server_keep_alive,
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Where the server's settings ask for more than the broker allows
+    pub fn conflicts(&self, server: &MqttServerConfig) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        let packet_size = server.tuning().max_packet_size;
+        if let Some(max) = self.max_packet_size {
+            if packet_size > max as usize {
+                conflicts.push(format!("packet size {} > broker's {}", packet_size, max));
+            }
+        }
+        let topic = &server.subscribe_topic;
+        if parse_shared(topic).is_some() {
+            if !self.shared_available {
+                conflicts.push("broker has no shared subscriptions".to_string());
+            }
+        } else if !self.wildcard_available && (topic.contains('#') || topic.contains('+')) {
+            conflicts.push("broker has no wildcard subscriptions".to_string());
+        }
+        if server.subscribe_qos > self.max_qos {
+            conflicts.push(format!(
+                "subscribe QoS {} above the broker's maximum {}",
+                server.subscribe_qos, self.max_qos
+            ));
+        }
+        if server.lwt_topic.as_deref().is_some_and(|t| !t.is_empty()) {
+            if server.lwt_qos > self.max_qos {
+                conflicts.push(format!(
+                    "last will QoS {} above the broker's maximum {}",
+                    server.lwt_qos, self.max_qos
+                ));
+            }
+            if server.lwt_retain && !self.retain_available {
+                conflicts.push("retained last will, but broker has no retain".to_string());
+            }
+        }
+        if let Some(keep_alive) = self.server_keep_alive {
+            if keep_alive as u64 != server.keep_alive_secs {
+                conflicts.push(format!(
+                    "broker overrides keep-alive {}s with {}s",
+                    server.keep_alive_secs, keep_alive
+                ));
+            }
+        }
+        conflicts
+    }
+}
+
+/// The server's capabilities, probing only if they were not learned before
+/// in this run. Reconnecting after a drop or coming back from away then
+/// doesn't log in a second time.
+pub async fn probe_once(
+    server: &MqttServerConfig,
+    client_id: &str,
+    tls: Option<Arc<ClientConfig>>,
+) -> Result<BrokerCapabilities> {
+    if let Some(capabilities) = cached(server) {
+        return Ok(capabilities);
+    }
+    let capabilities = probe(server, client_id, tls).await?;
+    remember(server, capabilities.clone());
+    Ok(capabilities)
+}
+
+/// Same broker, user and transport: the same answer
+fn cache_key(server: &MqttServerConfig) -> String {
+    format!(
+        "{}:{} {} tls={}",
+        server.host,
+        server.port,
+        server.get_username(),
+        server.use_tls
+    )
+}
+
+fn cached(server: &MqttServerConfig) -> Option<BrokerCapabilities> {
+    let key = cache_key(server);
+    let probed = PROBED.lock().ok()?;
+    probed
+        .iter()
+        .find(|(probed_key, _)| *probed_key == key)
+        .map(|(_, capabilities)| capabilities.clone())
+}
+
+fn remember(server: &MqttServerConfig, capabilities: BrokerCapabilities) {
+    let key = cache_key(server);
+    if let Ok(mut probed) = PROBED.lock() {
+        probed.retain(|(probed_key, _)| *probed_key != key);
+        probed.push((key, capabilities));
+    }
+}
+
+/// Connect with MQTT 5 as `<client_id>-caps`, read the CONNACK and leave.
+/// Fails for brokers that only speak MQTT 3.1.1.
+pub async fn probe(
+    server: &MqttServerConfig,
+    client_id: &str,
+    tls: Option<Arc<ClientConfig>>,
+) -> Result<BrokerCapabilities> {
+    let mut options = MqttOptions::new(format!("{}-caps", client_id), &server.host, server.port);
+    options.set_credentials(server.get_username(), server.get_token());
+    options.set_keep_alive(Duration::from_secs(server.keep_alive_secs.max(5)));
+    if let Some(config) = tls {
+        options.set_transport(Transport::tls_with_config(TlsConfiguration::Rustls(config)));
+    }
+    let (client, mut eventloop) = AsyncClient::new(options, 1);
+
+    let connack = tokio::time::timeout(PROBE_TIMEOUT, async {
+        loop {
+            match eventloop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(connack))) => return Ok(connack),
+                Ok(_) => {}
+                Err(ConnectionError::ConnectionRefused(code)) => {
+                    bail!("MQTT 5 connect refused: {:?}", code)
+                }
+                Err(e) => bail!("MQTT 5 connect failed: {}", e),
+            }
+        }
+    })
+    .await;
+    let _ = client.try_disconnect();
+    let _ = tokio::time::timeout(Duration::from_millis(500), eventloop.poll()).await;
+
+    match connack {
+        Ok(connack) => Ok(BrokerCapabilities::from_properties(
+            connack?.properties.as_ref(),
+        )),
+        Err(_) => bail!("MQTT 5 connect timed out"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(extra: &str) -> MqttServerConfig {
+        serde_json::from_str(&format!(
+            r#"{{"name": "lab", "host": "broker.lab", "client_id": "", "subscribe_topic": "sensors/#"{}}}"#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_defaults_when_not_advertised() {
+        let caps = BrokerCapabilities::from_properties(None);
+        assert_eq!(caps.max_qos, 2);
+        assert!(caps.retain_available && caps.wildcard_available && caps.shared_available);
+        assert!(caps.conflicts(&server("")).is_empty());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let caps = BrokerCapabilities {
+            max_packet_size: Some(1024),
+            max_qos: 1,
+            wildcard_available: false,
+            retain_available: false,
+            ..BrokerCapabilities::default()
+        };
+
+        let conflicts = caps.conflicts(&server(
+            r#", "subscribe_qos": 2, "lwt_topic": "status", "lwt_retain": true"#,
+        ));
+        assert_eq!(conflicts.len(), 4, "{:?}", conflicts);
+        assert!(conflicts[0].starts_with("packet size"));
+        assert_eq!(conflicts[1], "broker has no wildcard subscriptions");
+
+        let conflicts = caps.conflicts(&server(r#", "subscribe_qos": 1, "max_packet_size": 512"#));
+        assert_eq!(conflicts, vec!["broker has no wildcard subscriptions"]);
+    }
+
+    #[test]
+    fn test_probed_capabilities_are_reused() {
+        let caps = BrokerCapabilities {
+            receive_max: Some(20),
+            ..BrokerCapabilities::default()
+        };
+        let lab = server(r#", "port": 18830"#);
+        remember(&lab, caps.clone());
+        assert_eq!(cached(&lab), Some(caps));
+        assert_eq!(cached(&server(r#", "port": 18831"#)), None);
+        assert_eq!(
+            cached(&server(r#", "port": 18830, "username": "other""#)),
+            None
+        );
+    }
+}
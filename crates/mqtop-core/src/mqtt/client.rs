@@ -1,19 +1,25 @@
 #![allow(dead_code)]
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use rumqttc::tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
 use rumqttc::{
-    AsyncClient, Event, LastWill, MqttOptions, Packet, QoS, TlsConfiguration, Transport,
+    mqttbytes, AsyncClient, ConnectionError, Event, LastWill, MqttOptions, Outgoing, Packet, QoS,
+    StateError, SubscribeReasonCode, TlsConfiguration, Transport,
 };
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
 use crate::config::MqttServerConfig;
+use crate::mqtt::delivery::{Delivery, DeliveryTracker, ACK_TIMEOUT};
+use crate::mqtt::details::ConnectionDetails;
 use crate::mqtt::message::MqttMessage;
 use crate::mqtt::resilience::{BackoffStrategy, ConnectionHealth};
+use crate::mqtt::subscription::parse_shared;
+use crate::mqtt::tls_session::{self, TlsSession};
 
 /// Connection state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,12 +36,50 @@ pub enum MqttEvent {
     Message(MqttMessage),
     StateChange(ConnectionState),
     Error(String),
+    /// Keep-alive round trip completed (PINGREQ -> PINGRESP)
+    PingRtt(Duration),
+    /// Keep-alive ping got no response in time
+    PingMissed,
+    /// QoS 1/2 publish acknowledged by the broker, or still waiting after the timeout
+    Delivery(Delivery),
+    /// CONNACK said whether the broker kept a session for this client ID;
+    /// its queued messages arrive before `Subscribed`
+    SessionPresent(bool),
+    /// The broker accepted the subscription
+    Subscribed,
+    /// Resolved address, client ID and broker limits, probed after CONNACK
+    Connection(ConnectionDetails),
 }
 
 pub struct MqttClient {
     client: AsyncClient,
     config: Arc<MqttServerConfig>,
     health: Arc<RwLock<ConnectionHealth>>,
+    deliveries: Arc<Mutex<DeliveryTracker>>,
+    /// Set by `disconnect`, so the event loop stops instead of reconnecting
+    stopping: Arc<AtomicBool>,
+}
+
+/// Forward the broker's PUBACK/PUBCOMP for a tracked publish
+fn report_ack(
+    deliveries: &Mutex<DeliveryTracker>,
+    pkid: u16,
+    event_tx: &mpsc::UnboundedSender<MqttEvent>,
+) {
+    let delivery = deliveries
+        .lock()
+        .ok()
+        .and_then(|mut deliveries| deliveries.acked(pkid, Instant::now()));
+    if let Some(delivery) = delivery {
+        info!(
+            "{} for {} (QoS {}) after {:?}",
+            delivery.ack_name(),
+            delivery.topic,
+            delivery.qos,
+            delivery.elapsed
+        );
+        let _ = event_tx.send(MqttEvent::Delivery(delivery));
+    }
 }
 
 impl MqttClient {
@@ -85,18 +129,35 @@ impl MqttClient {
         }
 
         // Configure TLS if enabled
-        if config.use_tls {
-            let transport = Self::build_tls_transport(&config)?;
-            mqttoptions.set_transport(transport);
-        }
-
-        // Increase capacity for high-throughput scenarios
-        mqttoptions.set_inflight(100);
-
-        // Increase max packet size for large payloads (default is 10KB, set to 1MB)
-        mqttoptions.set_max_packet_size(1024 * 1024, 1024 * 1024);
-
-        let (client, mut eventloop) = AsyncClient::new(mqttoptions, 1000);
+        let tls_config = if config.use_tls {
+            let tls_config = Self::build_tls_config(&config)?;
+            mqttoptions.set_transport(Transport::tls_with_config(TlsConfiguration::Rustls(
+                Arc::clone(&tls_config),
+            )));
+            Some(tls_config)
+        } else {
+            None
+        };
+        let details = ConnectionDetails::new(
+            unique_client_id.clone(),
+            Duration::from_secs(config.keep_alive_secs),
+        );
+
+        // Inflight window, packet limit and request queue from the server's preset
+        let tuning = config.tuning();
+        info!(
+            "Client tuning ({}): inflight {}, max packet {} bytes, request channel {}",
+            config.preset.label(),
+            tuning.inflight,
+            tuning.max_packet_size,
+            tuning.request_channel_capacity
+        );
+        mqttoptions.set_inflight(tuning.inflight);
+        mqttoptions.set_max_packet_size(tuning.max_packet_size, tuning.max_packet_size);
+
+        let (client, mut eventloop) =
+            AsyncClient::new(mqttoptions, tuning.request_channel_capacity);
+        let max_packet_size = tuning.max_packet_size;
 
         let health_clone = Arc::clone(&health);
         let event_tx_clone = event_tx.clone();
@@ -109,28 +170,58 @@ impl MqttClient {
         let client_clone = client.clone();
         let use_exact_client_id = config.use_exact_client_id;
         let keep_alive_secs = config.keep_alive_secs;
+        let probe_config = Arc::clone(&config);
+        // Probed after the first CONNACK only, then resent on each reconnect
+        let probed = Arc::new(tokio::sync::OnceCell::new());
+        let deliveries = Arc::new(Mutex::new(DeliveryTracker::default()));
+        let deliveries_clone = Arc::clone(&deliveries);
+        let stopping = Arc::new(AtomicBool::new(false));
+        let stopping_clone = Arc::clone(&stopping);
+
+        // Announce before the event loop starts, so a fast CONNACK's Connected wins
+        let _ = event_tx.send(MqttEvent::StateChange(ConnectionState::Connecting));
 
-        // Spawn the event loop handler
-        tokio::spawn(async move {
+        // Spawn the event loop handler, recording what its TLS handshakes negotiate
+        let tls_session = TlsSession::default();
+        tokio::spawn(tls_session.clone().scope(async move {
+            let mut ping_sent: Option<Instant> = None;
             loop {
                 match eventloop.poll().await {
                     Ok(notification) => {
                         match notification {
                             Event::Incoming(Packet::Publish(publish)) => {
-                                let msg = MqttMessage::new(
+                                let mut msg = MqttMessage::new(
                                     publish.topic.to_string(),
-                                    publish.payload.to_vec(),
+                                    publish.payload,
                                     publish.qos as u8,
                                     publish.retain,
                                 );
+                                msg.dup = publish.dup;
                                 let _ = event_tx_clone.send(MqttEvent::Message(msg));
                             }
                             Event::Incoming(Packet::ConnAck(connack)) => {
                                 info!("Connected to MQTT broker: {:?}", connack);
                                 health_clone.write().await.record_success();
+                                let _ = event_tx_clone
+                                    .send(MqttEvent::SessionPresent(connack.session_present));
                                 let _ = event_tx_clone
                                     .send(MqttEvent::StateChange(ConnectionState::Connected));
 
+                                let details = details.clone();
+                                let probed = Arc::clone(&probed);
+                                let server = Arc::clone(&probe_config);
+                                let tls_config = tls_config.clone();
+                                let event_tx = event_tx_clone.clone();
+                                let negotiated = tls_session.get();
+                                tokio::spawn(async move {
+                                    let mut details = probed
+                                        .get_or_init(|| details.probe(&server, tls_config))
+                                        .await
+                                        .clone();
+                                    details.tls = negotiated;
+                                    let _ = event_tx.send(MqttEvent::Connection(details));
+                                });
+
                                 // Subscribe after connection is established
                                 info!(
                                     "Subscribing to: {} with QoS {:?}",
@@ -149,9 +240,44 @@ impl MqttClient {
                             }
                             Event::Incoming(Packet::SubAck(suback)) => {
                                 info!("Subscription acknowledged: {:?}", suback);
+                                if suback
+                                    .return_codes
+                                    .iter()
+                                    .any(|code| matches!(code, SubscribeReasonCode::Failure))
+                                {
+                                    warn!("Broker rejected subscription to {}", subscribe_topic);
+                                    let kind = if parse_shared(&subscribe_topic).is_some() {
+                                        "shared subscription"
+                                    } else {
+                                        "subscription"
+                                    };
+                                    let _ = event_tx_clone.send(MqttEvent::Error(format!(
+                                        "SubAck Failure for {} {}",
+                                        kind, subscribe_topic
+                                    )));
+                                } else {
+                                    let _ = event_tx_clone.send(MqttEvent::Subscribed);
+                                }
                             }
                             Event::Incoming(Packet::PingResp) => {
                                 debug!("Ping response received");
+                                if let Some(sent) = ping_sent.take() {
+                                    let _ = event_tx_clone.send(MqttEvent::PingRtt(sent.elapsed()));
+                                }
+                            }
+                            Event::Outgoing(Outgoing::PingReq) => {
+                                ping_sent = Some(Instant::now());
+                            }
+                            Event::Outgoing(Outgoing::Publish(pkid)) => {
+                                if let Ok(mut deliveries) = deliveries_clone.lock() {
+                                    deliveries.sent(pkid, Instant::now());
+                                }
+                            }
+                            Event::Incoming(Packet::PubAck(ack)) => {
+                                report_ack(&deliveries_clone, ack.pkid, &event_tx_clone);
+                            }
+                            Event::Incoming(Packet::PubComp(comp)) => {
+                                report_ack(&deliveries_clone, comp.pkid, &event_tx_clone);
                             }
                             Event::Outgoing(_) => {
                                 // Outgoing events, usually not interesting
@@ -162,7 +288,31 @@ impl MqttClient {
                         }
                     }
                     Err(e) => {
-                        let error_str = format!("{:?}", e);
+                        ping_sent = None;
+                        // A replacement client may already be reporting its own state
+                        if stopping_clone.load(Ordering::Relaxed) {
+                            info!("Disconnected from MQTT broker");
+                            break;
+                        }
+                        if matches!(e, ConnectionError::MqttState(StateError::AwaitPingResp)) {
+                            let _ = event_tx_clone.send(MqttEvent::PingMissed);
+                        }
+                        let error_str = match &e {
+                            ConnectionError::MqttState(StateError::Deserialization(
+                                mqttbytes::Error::PayloadSizeLimitExceeded(size),
+                            )) => format!(
+                                "Oversized packet: incoming {} bytes exceeds max_packet_size {}",
+                                size, max_packet_size
+                            ),
+                            ConnectionError::MqttState(StateError::OutgoingPacketTooLarge {
+                                pkt_size,
+                                max,
+                            }) => format!(
+                                "Oversized packet: outgoing {} bytes exceeds max_packet_size {}",
+                                pkt_size, max
+                            ),
+                            _ => format!("{:?}", e),
+                        };
                         error!("MQTT connection error: {}", error_str);
 
                         let mut health = health_clone.write().await;
@@ -205,16 +355,45 @@ impl MqttClient {
                     }
                 }
             }
+        }));
+
+        // Report publishes the broker hasn't acknowledged in time
+        let deliveries_weak = Arc::downgrade(&deliveries);
+        let timeout_tx = event_tx.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                interval.tick().await;
+                let Some(deliveries) = deliveries_weak.upgrade() else {
+                    break;
+                };
+                let expired = match deliveries.lock() {
+                    Ok(mut deliveries) => deliveries.expire(Instant::now(), ACK_TIMEOUT),
+                    Err(_) => break,
+                };
+                for delivery in expired {
+                    warn!(
+                        "No {} for {} (QoS {}) after {:?}",
+                        delivery.ack_name(),
+                        delivery.topic,
+                        delivery.qos,
+                        delivery.elapsed
+                    );
+                    if timeout_tx.send(MqttEvent::Delivery(delivery)).is_err() {
+                        return;
+                    }
+                }
+            }
         });
 
         let mqtt_client = Self {
             client,
             config,
             health,
+            deliveries,
+            stopping,
         };
 
-        let _ = event_tx.send(MqttEvent::StateChange(ConnectionState::Connecting));
-
         Ok(mqtt_client)
     }
 
@@ -243,12 +422,30 @@ impl MqttClient {
 
     /// Publish a message
     pub async fn publish(&self, topic: &str, payload: &[u8], qos: QoS, retain: bool) -> Result<()> {
-        self.client.publish(topic, qos, retain, payload).await?;
+        // Rejecting here keeps an oversized publish from dropping the connection
+        let max_packet_size = self.config.tuning().max_packet_size;
+        if payload.len() + topic.len() > max_packet_size {
+            bail!(
+                "Oversized packet: {} bytes exceeds max_packet_size {}",
+                payload.len() + topic.len(),
+                max_packet_size
+            );
+        }
+        if let Ok(mut deliveries) = self.deliveries.lock() {
+            deliveries.queue(topic, qos as u8);
+        }
+        if let Err(err) = self.client.publish(topic, qos, retain, payload).await {
+            if let Ok(mut deliveries) = self.deliveries.lock() {
+                deliveries.unqueue();
+            }
+            return Err(err.into());
+        }
         Ok(())
     }
 
     /// Disconnect from the broker
     pub async fn disconnect(&self) -> Result<()> {
+        self.stopping.store(true, Ordering::Relaxed);
         self.client.disconnect().await?;
         Ok(())
     }
@@ -273,8 +470,8 @@ impl MqttClient {
         self.health.read().await.last_error().map(|s| s.to_string())
     }
 
-    /// Build TLS transport based on configuration
-    fn build_tls_transport(config: &MqttServerConfig) -> Result<Transport> {
+    /// Build the rustls client config for the server's TLS settings
+    fn build_tls_config(config: &MqttServerConfig) -> Result<Arc<ClientConfig>> {
         use rustls_pemfile::{certs, private_key};
         use std::io::BufReader;
 
@@ -304,8 +501,8 @@ impl MqttClient {
             }
         }
 
-        // Build client config
-        let builder = ClientConfig::builder().with_root_certificates(root_store);
+        // Build client config, with suites that note which one the broker picks
+        let builder = Self::tls_builder()?.with_root_certificates(root_store);
 
         let client_config = if let (Some(cert_path), Some(key_path)) =
             (&config.client_cert, &config.client_key)
@@ -337,7 +534,7 @@ impl MqttClient {
         let client_config = if config.tls_insecure {
             warn!("TLS certificate verification disabled - INSECURE!");
             // Create a new config that skips verification
-            let mut dangerous_config = ClientConfig::builder()
+            let mut dangerous_config = Self::tls_builder()?
                 .dangerous()
                 .with_custom_certificate_verifier(Arc::new(InsecureCertVerifier))
                 .with_no_client_auth();
@@ -347,9 +544,13 @@ impl MqttClient {
             client_config
         };
 
-        Ok(Transport::tls_with_config(TlsConfiguration::Rustls(
-            Arc::new(client_config),
-        )))
+        Ok(Arc::new(client_config))
+    }
+
+    fn tls_builder() -> Result<rustls::ConfigBuilder<ClientConfig, rustls::WantsVerifier>> {
+        ClientConfig::builder_with_provider(tls_session::crypto_provider())
+            .with_safe_default_protocol_versions()
+            .context("Failed to configure TLS versions")
     }
 
     /// Generate a client_id for MQTT connection
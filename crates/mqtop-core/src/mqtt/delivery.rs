@@ -0,0 +1,239 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How long a QoS 1/2 publish may wait for PUBACK/PUBCOMP before it is reported
+pub const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timed-out publishes remembered for a late ack; older ones are forgotten
+const LATE_ACK_LIMIT: usize = 100;
+
+/// Outcome of a QoS 1/2 publish
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Broker acknowledged (PUBACK for QoS 1, PUBCOMP for QoS 2)
+    Confirmed,
+    /// No acknowledgement within [`ACK_TIMEOUT`]
+    TimedOut,
+    /// Acknowledged after it had already been reported as timed out
+    ConfirmedLate,
+}
+
+/// Acknowledgement feedback for one publish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    pub topic: String,
+    pub qos: u8,
+    pub status: DeliveryStatus,
+    /// Time since the packet was written to the connection
+    pub elapsed: Duration,
+}
+
+impl Delivery {
+    /// Packet that completes the handshake for this QoS
+    pub fn ack_name(&self) -> &'static str {
+        if self.qos >= 2 {
+            "PUBCOMP"
+        } else {
+            "PUBACK"
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InFlight {
+    topic: String,
+    qos: u8,
+    sent_at: Instant,
+}
+
+/// Matches publishes to the packet ids rumqttc assigns them.
+///
+/// `AsyncClient::publish` doesn't return the packet id, but requests are
+/// written in the order they were queued, so each new `Outgoing::Publish`
+/// belongs to the oldest queued publish. Packet ids already in flight are
+/// retransmissions after a reconnect and keep their original entry.
+///
+/// Once reported as timed out, a publish moves to a short list kept only to
+/// recognise a late ack, so a broker that never acks can't grow the tracker.
+#[derive(Debug, Default)]
+pub struct DeliveryTracker {
+    queued: VecDeque<(String, u8)>,
+    in_flight: HashMap<u16, InFlight>,
+    /// Timed out, oldest first, at most [`LATE_ACK_LIMIT`]
+    timed_out: VecDeque<(u16, InFlight)>,
+}
+
+impl DeliveryTracker {
+    /// A publish was handed to the client
+    pub fn queue(&mut self, topic: &str, qos: u8) {
+        self.queued.push_back((topic.to_string(), qos));
+    }
+
+    /// The client refused the last queued publish
+    pub fn unqueue(&mut self) {
+        self.queued.pop_back();
+    }
+
+    /// `Outgoing::Publish(pkid)`: the packet was written to the connection
+    pub fn sent(&mut self, pkid: u16, now: Instant) {
+        if pkid != 0 && (self.in_flight.contains_key(&pkid) || self.is_timed_out(pkid)) {
+            return;
+        }
+        let Some((topic, qos)) = self.queued.pop_front() else {
+            return;
+        };
+        if qos > 0 {
+            self.in_flight.insert(
+                pkid,
+                InFlight {
+                    topic,
+                    qos,
+                    sent_at: now,
+                },
+            );
+        }
+    }
+
+    /// PUBACK (QoS 1) or PUBCOMP (QoS 2) for `pkid`
+    pub fn acked(&mut self, pkid: u16, now: Instant) -> Option<Delivery> {
+        let (entry, status) = match self.in_flight.remove(&pkid) {
+            Some(entry) => (entry, DeliveryStatus::Confirmed),
+            None => {
+                let index = self.timed_out.iter().position(|(id, _)| *id == pkid)?;
+                let (_, entry) = self.timed_out.remove(index)?;
+                (entry, DeliveryStatus::ConfirmedLate)
+            }
+        };
+        Some(Delivery {
+            topic: entry.topic,
+            qos: entry.qos,
+            status,
+            elapsed: now.saturating_duration_since(entry.sent_at),
+        })
+    }
+
+    /// Publishes that passed `timeout` without an ack, each reported once.
+    /// The latest [`LATE_ACK_LIMIT`] of them stay tracked so a late ack is
+    /// still noticed.
+    pub fn expire(&mut self, now: Instant, timeout: Duration) -> Vec<Delivery> {
+        let mut pkids: Vec<u16> = self
+            .in_flight
+            .iter()
+            .filter(|(_, entry)| now.duration_since(entry.sent_at) >= timeout)
+            .map(|(pkid, _)| *pkid)
+            .collect();
+        pkids.sort_by_key(|pkid| self.in_flight[pkid].sent_at);
+
+        let mut expired = Vec::with_capacity(pkids.len());
+        for pkid in pkids {
+            let Some(entry) = self.in_flight.remove(&pkid) else {
+                continue;
+            };
+            expired.push(Delivery {
+                topic: entry.topic.clone(),
+                qos: entry.qos,
+                status: DeliveryStatus::TimedOut,
+                elapsed: now.duration_since(entry.sent_at),
+            });
+            self.timed_out.push_back((pkid, entry));
+        }
+        while self.timed_out.len() > LATE_ACK_LIMIT {
+            self.timed_out.pop_front();
+        }
+        expired
+    }
+
+    fn is_timed_out(&self, pkid: u16) -> bool {
+        self.timed_out.iter().any(|(id, _)| *id == pkid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acks_follow_queue_order() {
+        let mut tracker = DeliveryTracker::default();
+        let start = Instant::now();
+        tracker.queue("plain", 0);
+        tracker.queue("cmd/a", 1);
+        tracker.queue("cmd/b", 2);
+
+        tracker.sent(0, start);
+        tracker.sent(1, start);
+        tracker.sent(2, start);
+        // QoS 0 isn't acknowledged
+        assert!(tracker.acked(0, start).is_none());
+
+        let ack = tracker.acked(2, start + Duration::from_millis(40)).unwrap();
+        assert_eq!(ack.topic, "cmd/b");
+        assert_eq!(ack.ack_name(), "PUBCOMP");
+        assert_eq!(ack.status, DeliveryStatus::Confirmed);
+        assert_eq!(ack.elapsed, Duration::from_millis(40));
+
+        // Unknown or repeated acks are ignored
+        assert!(tracker.acked(2, start).is_none());
+        assert!(tracker.acked(9, start).is_none());
+    }
+
+    #[test]
+    fn test_retransmit_keeps_entry() {
+        let mut tracker = DeliveryTracker::default();
+        let start = Instant::now();
+        tracker.queue("cmd/a", 1);
+        tracker.sent(1, start);
+        tracker.queue("cmd/b", 1);
+
+        // Resent after reconnect with the same packet id
+        tracker.sent(1, start + Duration::from_secs(1));
+        tracker.sent(2, start + Duration::from_secs(1));
+
+        assert_eq!(tracker.acked(1, start).unwrap().topic, "cmd/a");
+        assert_eq!(tracker.acked(2, start).unwrap().topic, "cmd/b");
+    }
+
+    #[test]
+    fn test_timeout_then_late_ack() {
+        let mut tracker = DeliveryTracker::default();
+        let start = Instant::now();
+        tracker.queue("cmd/a", 1);
+        tracker.sent(1, start);
+
+        assert!(tracker
+            .expire(start + Duration::from_secs(5), ACK_TIMEOUT)
+            .is_empty());
+        let expired = tracker.expire(start + ACK_TIMEOUT, ACK_TIMEOUT);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].status, DeliveryStatus::TimedOut);
+        // Reported once
+        assert!(tracker
+            .expire(start + ACK_TIMEOUT * 2, ACK_TIMEOUT)
+            .is_empty());
+
+        let late = tracker.acked(1, start + ACK_TIMEOUT * 2).unwrap();
+        assert_eq!(late.status, DeliveryStatus::ConfirmedLate);
+        assert!(tracker.acked(1, start + ACK_TIMEOUT * 2).is_none());
+    }
+
+    #[test]
+    fn test_unacked_publishes_are_bounded() {
+        let mut tracker = DeliveryTracker::default();
+        let start = Instant::now();
+        let count = LATE_ACK_LIMIT as u16 + 50;
+        for pkid in 1..=count {
+            tracker.queue("cmd/a", 1);
+            tracker.sent(pkid, start + Duration::from_millis(pkid as u64));
+        }
+
+        let expired = tracker.expire(start + ACK_TIMEOUT * 2, ACK_TIMEOUT);
+        assert_eq!(expired.len(), count as usize);
+        assert!(tracker.in_flight.is_empty());
+        assert_eq!(tracker.timed_out.len(), LATE_ACK_LIMIT);
+
+        // The oldest are forgotten, the latest can still be confirmed late
+        assert!(tracker.acked(1, start).is_none());
+        let late = tracker.acked(count, start + ACK_TIMEOUT * 3).unwrap();
+        assert_eq!(late.status, DeliveryStatus::ConfirmedLate);
+    }
+}
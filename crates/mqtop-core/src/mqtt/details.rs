@@ -0,0 +1,61 @@
+//! What a broker connection ended up using: the address the host resolved to,
+//! the client ID with any suffix, the TLS version and cipher suite the live
+//! connection negotiated, and what the broker advertises over MQTT 5.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rumqttc::tokio_rustls::rustls::ClientConfig;
+use tracing::debug;
+
+use crate::config::MqttServerConfig;
+use crate::mqtt::capabilities::{self, BrokerCapabilities};
+use crate::mqtt::tls_session::NegotiatedTls;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionDetails {
+    /// Client ID sent in CONNECT, including a generated suffix
+    pub client_id: String,
+    pub keep_alive: Duration,
+    /// Address the host name resolved to
+    pub address: Option<SocketAddr>,
+    /// None when the broker does not answer MQTT 5
+    pub capabilities: Option<BrokerCapabilities>,
+    /// Version and cipher suite of the live connection; None without TLS
+    pub tls: Option<NegotiatedTls>,
+}
+
+impl ConnectionDetails {
+    pub fn new(client_id: String, keep_alive: Duration) -> Self {
+        Self {
+            client_id,
+            keep_alive,
+            address: None,
+            capabilities: None,
+            tls: None,
+        }
+    }
+
+    /// Resolve the host, then ask for the broker's limits over MQTT 5 unless
+    /// they are already known
+    pub async fn probe(
+        mut self,
+        server: &MqttServerConfig,
+        tls: Option<Arc<ClientConfig>>,
+    ) -> Self {
+        let (host, port) = (server.host.as_str(), server.port);
+        self.address = match tokio::net::lookup_host((host, port)).await {
+            Ok(mut addresses) => addresses.next(),
+            Err(e) => {
+                debug!("Resolving {} failed: {}", host, e);
+                None
+            }
+        };
+        match capabilities::probe_once(server, &self.client_id, tls).await {
+            Ok(capabilities) => self.capabilities = Some(capabilities),
+            Err(e) => debug!("Capability probe of {} failed: {:#}", host, e),
+        }
+        self
+    }
+}
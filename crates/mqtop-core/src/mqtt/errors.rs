@@ -0,0 +1,258 @@
+/// A connection error translated into plain language with a suggested fix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorInfo {
+    /// Short human-readable description
+    pub summary: String,
+    /// Suggested fix, if the error is recognised
+    pub hint: Option<&'static str>,
+    /// Original error text from the client library
+    pub raw: String,
+}
+
+/// Known error patterns, matched case-insensitively against the raw error text.
+/// The first matching rule wins, so more specific patterns come first.
+const RULES: &[(&[&str], &str, &str)] = &[
+    (
+        &[
+            "badusernamepassword",
+            "bad user name or password",
+            "authorization violation",
+        ],
+        "Authentication failed",
+        "Check username and token in the server settings",
+    ),
+    (
+        &["notauthorized", "not authorized"],
+        "Not authorized to connect",
+        "The broker rejected these credentials; check the account's permissions",
+    ),
+    (
+        &["oversized packet"],
+        "Message larger than max_packet_size",
+        "Raise max_packet_size or use preset = \"high_throughput\" for this server",
+    ),
+    (
+        &["failure for shared subscription"],
+        "Broker rejected the shared subscription",
+        "The broker may not support $share; drop the $share/<group>/ prefix",
+    ),
+    (
+        &[
+            "permissions violation",
+            "suback failure",
+            "subscription rejected",
+        ],
+        "Subscription denied by broker ACL",
+        "Subscribe to a topic this account may read, or ask for ACL access",
+    ),
+    (
+        &["badclientid", "identifier rejected"],
+        "Client ID rejected",
+        "Use a shorter client ID or set use_exact_client_id = false",
+    ),
+    (
+        &["refusedprotocolversion", "unacceptable protocol version"],
+        "Protocol version not supported by broker",
+        "Check the MQTT version setting for this server",
+    ),
+    (
+        &["serviceunavailable", "server unavailable"],
+        "Broker unavailable",
+        "The broker is up but refusing clients; try again later",
+    ),
+    (
+        &[
+            "notvalidforname",
+            "certnotvalidforname",
+            "hostname mismatch",
+        ],
+        "TLS certificate does not match host name",
+        "Connect using the host name on the certificate, not an IP or alias",
+    ),
+    (
+        &["unknownissuer", "unknown ca", "unknown issuer"],
+        "TLS certificate issuer not trusted",
+        "Set ca_cert to the broker's CA file, or tls_insecure = true for testing",
+    ),
+    (
+        &[
+            "invalidcertificate(expired",
+            "invalid peer certificate: expired",
+            "certexpired",
+            "certificate has expired",
+            "certificate expired",
+        ],
+        "TLS certificate expired",
+        "Renew the broker certificate or check the system clock",
+    ),
+    (
+        &[
+            "invalidcertificate",
+            "invalid peer certificate",
+            "badcertificate",
+            "bad certificate",
+            "novalidcertinchain",
+        ],
+        "TLS certificate rejected",
+        "Check ca_cert, client_cert and client_key paths in the server settings",
+    ),
+    (
+        &["awaitpingresp", "ping timeout", "stale connection"],
+        "Keep-alive timeout: broker stopped answering pings",
+        "Network may be dropping packets; try a shorter keep_alive_secs",
+    ),
+    (
+        &[
+            "failed to lookup address",
+            "name or service not known",
+            "nodename nor servname",
+            "no such host",
+        ],
+        "Host name could not be resolved",
+        "Check the host name and DNS settings",
+    ),
+    (
+        &["connectionrefused", "connection refused"],
+        "Connection refused",
+        "Check host and port, and whether the broker is running",
+    ),
+    (
+        &["networktimeout", "timed out", "timedout"],
+        "Connection timed out",
+        "Check network reachability and firewall rules for this port",
+    ),
+    (
+        &["connectionreset", "connection reset", "connectionaborted"],
+        "Connection reset by broker",
+        "Another client may be using the same client ID, or the broker restarted",
+    ),
+    (
+        &["unexpectedeof", "connectionclosed", "connection closed"],
+        "Broker closed the connection",
+        "Often caused by TLS/plain-text port mismatch or rejected credentials",
+    ),
+    (
+        &[
+            "payloadsizelimitexceeded",
+            "packet too large",
+            "maximum payload",
+        ],
+        "Message larger than the allowed packet size",
+        "Raise max_packet_size or use preset = \"high_throughput\" for this server",
+    ),
+];
+
+/// Map a raw client error string to a human-readable summary and hint
+pub fn classify_error(raw: &str) -> ErrorInfo {
+    let lower = raw.to_lowercase();
+
+    for (patterns, summary, hint) in RULES {
+        if patterns.iter().any(|p| lower.contains(p)) {
+            return ErrorInfo {
+                summary: summary.to_string(),
+                hint: Some(hint),
+                raw: raw.to_string(),
+            };
+        }
+    }
+
+    ErrorInfo {
+        summary: raw.to_string(),
+        hint: None,
+        raw: raw.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bad_credentials() {
+        let info = classify_error("ConnectionRefused(BadUserNamePassword)");
+        assert_eq!(info.summary, "Authentication failed");
+        assert!(info.hint.is_some());
+    }
+
+    #[test]
+    fn test_nats_authorization() {
+        let info = classify_error("NATS server error: -ERR 'Authorization Violation'");
+        assert_eq!(info.summary, "Authentication failed");
+    }
+
+    #[test]
+    fn test_tls_hostname_mismatch() {
+        let info = classify_error(
+            "Tls(TLS(Io(Custom { kind: InvalidData, error: InvalidCertificate(NotValidForName) })))",
+        );
+        assert_eq!(info.summary, "TLS certificate does not match host name");
+    }
+
+    #[test]
+    fn test_tls_certificate_expired() {
+        for raw in [
+            "Tls(TLS(Io(Custom { kind: InvalidData, error: InvalidCertificate(Expired) })))",
+            "TLS error: invalid peer certificate: Expired",
+            "SSL routines: certificate verify failed: certificate has expired",
+        ] {
+            assert_eq!(
+                classify_error(raw).summary,
+                "TLS certificate expired",
+                "{}",
+                raw
+            );
+        }
+        let info = classify_error("Tls(TLS(AlertReceived(BadCertificate)))");
+        assert_eq!(info.summary, "TLS certificate rejected");
+    }
+
+    #[test]
+    fn test_not_tls_errors() {
+        // "expired" and "certificate" on their own say nothing about TLS
+        for raw in [
+            "NATS server error: -ERR 'User Authentication Expired'",
+            "Failed to read /etc/mqtop/certificates/ca.pem: No such file or directory",
+        ] {
+            assert!(!classify_error(raw).summary.starts_with("TLS"), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_oversized_packet() {
+        let info =
+            classify_error("Oversized packet: incoming 4096 bytes exceeds max_packet_size 1024");
+        assert_eq!(info.summary, "Message larger than max_packet_size");
+        assert!(info.hint.is_some());
+    }
+
+    #[test]
+    fn test_shared_subscription_rejected() {
+        let info = classify_error("SubAck Failure for shared subscription $share/g/#");
+        assert_eq!(info.summary, "Broker rejected the shared subscription");
+
+        let info = classify_error("SubAck Failure for subscription sensors/#");
+        assert_eq!(info.summary, "Subscription denied by broker ACL");
+    }
+
+    #[test]
+    fn test_keep_alive_timeout() {
+        let info = classify_error("MqttState(AwaitPingResp)");
+        assert!(info.summary.starts_with("Keep-alive timeout"));
+    }
+
+    #[test]
+    fn test_connection_refused() {
+        let info = classify_error(
+            "Io(Os { code: 111, kind: ConnectionRefused, message: \"Connection refused\" })",
+        );
+        assert_eq!(info.summary, "Connection refused");
+    }
+
+    #[test]
+    fn test_unknown_error_passes_through() {
+        let info = classify_error("Something odd happened");
+        assert_eq!(info.summary, "Something odd happened");
+        assert_eq!(info.hint, None);
+        assert_eq!(info.raw, "Something odd happened");
+    }
+}
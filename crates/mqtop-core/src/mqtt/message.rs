@@ -0,0 +1,83 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+
+/// Payloads above this size are shown truncated and without JSON formatting
+pub const LARGE_PAYLOAD_BYTES: usize = 64 * 1024;
+
+/// Represents a received MQTT message
+#[derive(Debug, Clone)]
+pub struct MqttMessage {
+    pub topic: String,
+    /// Shared with the client's receive buffer; clones don't copy it
+    pub payload: Bytes,
+    pub qos: u8,
+    pub retain: bool,
+    /// Redelivery of a QoS 1 or 2 message the broker may have sent before
+    pub dup: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl MqttMessage {
+    pub fn new(topic: String, payload: impl Into<Bytes>, qos: u8, retain: bool) -> Self {
+        Self {
+            topic,
+            payload: payload.into(),
+            qos,
+            retain,
+            dup: false,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Try to parse payload as UTF-8 string
+    pub fn payload_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.payload).ok()
+    }
+
+    /// Try to parse payload as JSON and pretty-print it
+    pub fn payload_json_pretty(&self) -> Option<String> {
+        let s = self.payload_str()?;
+        let value: serde_json::Value = serde_json::from_str(s).ok()?;
+        serde_json::to_string_pretty(&value).ok()
+    }
+
+    /// Get payload as hex string
+    pub fn payload_hex(&self) -> String {
+        hex(&self.payload)
+    }
+
+    /// Get payload size in bytes
+    pub fn payload_size(&self) -> usize {
+        self.payload.len()
+    }
+
+    /// Whether the payload is too large to format in full
+    pub fn is_large(&self) -> bool {
+        self.payload.len() > LARGE_PAYLOAD_BYTES
+    }
+
+    /// First `limit` bytes of the payload as text, cut back to a char boundary.
+    /// Returns `None` if that prefix is not UTF-8.
+    pub fn payload_str_prefix(&self, limit: usize) -> Option<&str> {
+        let head = &self.payload[..self.payload.len().min(limit)];
+        match std::str::from_utf8(head) {
+            Ok(s) => Some(s),
+            // Only the last character was cut in half
+            Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).ok(),
+            Err(_) => None,
+        }
+    }
+
+    /// First `limit` bytes of the payload as a hex string
+    pub fn payload_hex_prefix(&self, limit: usize) -> String {
+        hex(&self.payload[..self.payload.len().min(limit)])
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,17 @@
+pub mod capabilities;
+pub mod client;
+pub mod delivery;
+pub mod details;
+pub mod errors;
+pub mod message;
+pub mod resilience;
+pub mod subscription;
+pub mod tls_session;
+
+pub use capabilities::BrokerCapabilities;
+pub use client::{ConnectionState, MqttClient, MqttEvent};
+pub use delivery::DeliveryStatus;
+pub use details::ConnectionDetails;
+pub use errors::{classify_error, ErrorInfo};
+pub use message::{MqttMessage, LARGE_PAYLOAD_BYTES};
+pub use tls_session::NegotiatedTls;
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 /// Backoff strategy for reconnection attempts
 #[derive(Debug, Clone)]
@@ -90,6 +91,41 @@ impl BackoffStrategy {
     }
 }
 
+/// How far back `ConnectionHealth::quality` looks
+pub const QUALITY_WINDOW: Duration = Duration::from_secs(600);
+
+/// Average ping round trip above which the link counts as fair
+const FAIR_PING: Duration = Duration::from_millis(200);
+/// Average ping round trip above which the link counts as poor
+const POOR_PING: Duration = Duration::from_secs(1);
+
+/// Link quality over the last `QUALITY_WINDOW`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionQuality {
+    Good,
+    Fair,
+    Poor,
+}
+
+impl ConnectionQuality {
+    /// Signal bars for the header
+    pub fn bars(&self) -> &'static str {
+        match self {
+            ConnectionQuality::Good => "▂▄▆",
+            ConnectionQuality::Fair => "▂▄ ",
+            ConnectionQuality::Poor => "▂  ",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionQuality::Good => "good",
+            ConnectionQuality::Fair => "fair",
+            ConnectionQuality::Poor => "poor",
+        }
+    }
+}
+
 /// Tracks connection health and manages reconnection state
 #[derive(Debug)]
 pub struct ConnectionHealth {
@@ -103,6 +139,10 @@ pub struct ConnectionHealth {
     backoff: BackoffStrategy,
     /// Last error message
     last_error: Option<String>,
+    /// When an established connection was lost, within `QUALITY_WINDOW`
+    disconnects: VecDeque<Instant>,
+    /// Keep-alive round trips within `QUALITY_WINDOW` (None when missed)
+    pings: VecDeque<(Instant, Option<Duration>)>,
 }
 
 impl ConnectionHealth {
@@ -113,6 +153,8 @@ impl ConnectionHealth {
             total_reconnects: 0,
             backoff,
             last_error: None,
+            disconnects: VecDeque::new(),
+            pings: VecDeque::new(),
         }
     }
 
@@ -128,6 +170,11 @@ impl ConnectionHealth {
 
     /// Record a connection failure
     pub fn record_failure(&mut self, error: String) {
+        if self.consecutive_failures == 0 && self.total_connections > 0 {
+            let now = Instant::now();
+            self.disconnects.push_back(now);
+            self.expire(now);
+        }
         self.consecutive_failures += 1;
         self.last_error = Some(error);
     }
@@ -166,6 +213,74 @@ impl ConnectionHealth {
     pub fn is_healthy(&self) -> bool {
         self.consecutive_failures == 0
     }
+
+    /// Record a keep-alive round trip, or None for a missed ping
+    pub fn record_ping(&mut self, rtt: Option<Duration>, now: Instant) {
+        self.pings.push_back((now, rtt));
+        self.expire(now);
+    }
+
+    /// Established connections lost within `QUALITY_WINDOW` of `now`
+    pub fn recent_disconnects(&self, now: Instant) -> usize {
+        self.disconnects
+            .iter()
+            .filter(|at| now.duration_since(**at) < QUALITY_WINDOW)
+            .count()
+    }
+
+    /// Average round trip of the answered pings within `QUALITY_WINDOW` of `now`
+    pub fn recent_ping_avg(&self, now: Instant) -> Option<Duration> {
+        let rtts: Vec<Duration> = self
+            .pings
+            .iter()
+            .filter(|(at, _)| now.duration_since(*at) < QUALITY_WINDOW)
+            .filter_map(|(_, rtt)| *rtt)
+            .collect();
+        if rtts.is_empty() {
+            return None;
+        }
+        Some(rtts.iter().sum::<Duration>() / rtts.len() as u32)
+    }
+
+    /// Quality from drops and ping round trips within `QUALITY_WINDOW` of
+    /// `now`. None until the first connection.
+    pub fn quality(&self, now: Instant) -> Option<ConnectionQuality> {
+        if self.total_connections == 0 {
+            return None;
+        }
+        let disconnects = self.recent_disconnects(now);
+        let missed = self
+            .pings
+            .iter()
+            .filter(|(at, rtt)| now.duration_since(*at) < QUALITY_WINDOW && rtt.is_none())
+            .count();
+        let avg = self.recent_ping_avg(now).unwrap_or_default();
+        Some(if disconnects >= 3 || missed >= 3 || avg >= POOR_PING {
+            ConnectionQuality::Poor
+        } else if disconnects > 0 || missed > 0 || avg >= FAIR_PING {
+            ConnectionQuality::Fair
+        } else {
+            ConnectionQuality::Good
+        })
+    }
+
+    /// Forget drops and pings older than `QUALITY_WINDOW`
+    fn expire(&mut self, now: Instant) {
+        while self
+            .disconnects
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= QUALITY_WINDOW)
+        {
+            self.disconnects.pop_front();
+        }
+        while self
+            .pings
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= QUALITY_WINDOW)
+        {
+            self.pings.pop_front();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -317,4 +432,39 @@ mod tests {
         health.record_failure("e2".to_string());
         assert!(!health.should_reconnect()); // Exceeded limit
     }
+
+    #[test]
+    fn test_connection_quality() {
+        let mut health = ConnectionHealth::new(BackoffStrategy::default());
+        let now = Instant::now();
+        assert_eq!(health.quality(now), None);
+
+        health.record_success();
+        health.record_ping(Some(Duration::from_millis(20)), now);
+        assert_eq!(health.quality(now), Some(ConnectionQuality::Good));
+
+        // Failed attempts while reconnecting count as one drop
+        health.record_failure("connection reset".to_string());
+        health.record_failure("refused".to_string());
+        health.record_success();
+        let now = Instant::now();
+        assert_eq!(health.recent_disconnects(now), 1);
+        assert_eq!(health.quality(now), Some(ConnectionQuality::Fair));
+
+        for _ in 0..2 {
+            health.record_failure("connection reset".to_string());
+            health.record_success();
+        }
+        assert_eq!(health.quality(now), Some(ConnectionQuality::Poor));
+
+        // Drops age out of the window
+        let later = Instant::now() + QUALITY_WINDOW;
+        assert_eq!(health.recent_disconnects(later), 0);
+        health.record_ping(Some(Duration::from_millis(1500)), later);
+        assert_eq!(
+            health.recent_ping_avg(later),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(health.quality(later), Some(ConnectionQuality::Poor));
+    }
 }
@@ -0,0 +1,132 @@
+use anyhow::{bail, Result};
+
+use crate::state::metric_tracker::topic_matches;
+
+/// Prefix of a shared subscription: `$share/<group>/<filter>`
+const SHARE_PREFIX: &str = "$share/";
+
+/// A parsed `$share/<group>/<filter>` subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedSubscription<'a> {
+    /// Consumer group the broker load-balances messages across
+    pub group: &'a str,
+    /// Topic filter inside the share
+    pub filter: &'a str,
+}
+
+/// Split a shared subscription into group and filter.
+/// Returns `None` for ordinary topic filters.
+pub fn parse_shared(topic: &str) -> Option<SharedSubscription<'_>> {
+    let rest = topic.strip_prefix(SHARE_PREFIX)?;
+    let (group, filter) = rest.split_once('/').unwrap_or((rest, ""));
+    Some(SharedSubscription { group, filter })
+}
+
+/// The topic filter messages will match, without any `$share/<group>/` prefix
+pub fn effective_filter(topic: &str) -> &str {
+    parse_shared(topic).map_or(topic, |shared| shared.filter)
+}
+
+/// Validate an MQTT subscription, including `$share/<group>/<filter>` syntax
+pub fn validate_subscription(topic: &str) -> Result<()> {
+    if let Some(shared) = parse_shared(topic) {
+        if shared.group.is_empty() {
+            bail!("Shared subscription needs a group: $share/<group>/<filter>");
+        }
+        if shared.group.contains(['+', '#']) {
+            bail!("Share group '{}' cannot contain wildcards", shared.group);
+        }
+        if shared.filter.is_empty() {
+            bail!("Shared subscription needs a topic filter after the group");
+        }
+        return validate_filter(shared.filter);
+    }
+    validate_filter(topic)
+}
+
+/// Validate MQTT topic filter wildcards: `+` and `#` must fill a whole level,
+/// and `#` may only be the last level
+pub fn validate_filter(filter: &str) -> Result<()> {
+    if filter.is_empty() {
+        bail!("Topic filter cannot be empty");
+    }
+
+    let levels: Vec<&str> = filter.split('/').collect();
+    for (i, level) in levels.iter().enumerate() {
+        if level.contains('#') && (*level != "#" || i != levels.len() - 1) {
+            bail!("'#' must be the last level on its own: {}", filter);
+        }
+        if level.contains('+') && *level != "+" {
+            bail!("'+' must occupy a whole level: {}", filter);
+        }
+    }
+    Ok(())
+}
+
+/// Whether messages on `topic` reach a subscription to `filter`
+pub fn covers(filter: &str, topic: &str) -> bool {
+    topic_matches(effective_filter(filter), topic)
+}
+
+/// Whether a subscription to `filter` receives anything in the `branch`
+/// subtree, the branch itself included
+pub fn reaches(filter: &str, branch: &str, separator: char) -> bool {
+    let mut filter_levels = effective_filter(filter).split(separator);
+    for level in branch.split(separator) {
+        match filter_levels.next() {
+            Some("#" | ">") => return true,
+            Some("+" | "*") => {}
+            Some(filter_level) if filter_level == level => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shared() {
+        assert_eq!(
+            parse_shared("$share/workers/sensors/#"),
+            Some(SharedSubscription {
+                group: "workers",
+                filter: "sensors/#",
+            })
+        );
+        assert_eq!(parse_shared("sensors/#"), None);
+        assert_eq!(effective_filter("$share/g/a/+/b"), "a/+/b");
+        assert_eq!(effective_filter("a/+/b"), "a/+/b");
+    }
+
+    #[test]
+    fn test_validate_subscription() {
+        assert!(validate_subscription("#").is_ok());
+        assert!(validate_subscription("sensors/+/temp").is_ok());
+        assert!(validate_subscription("$share/workers/sensors/#").is_ok());
+
+        assert!(validate_subscription("sensors/#/temp").is_err());
+        assert!(validate_subscription("sensors/te#").is_err());
+        assert!(validate_subscription("sensors/te+mp").is_err());
+        assert!(validate_subscription("$share/workers").is_err());
+        assert!(validate_subscription("$share//sensors/#").is_err());
+        assert!(validate_subscription("$share/wor+kers/sensors").is_err());
+    }
+
+    #[test]
+    fn test_coverage() {
+        assert!(covers("#", "dev/42/ack"));
+        assert!(covers("$share/g/dev/+/ack", "dev/42/ack"));
+        assert!(!covers("dev/+/telemetry", "dev/42/ack"));
+
+        // A filter inside a branch still receives part of it
+        assert!(reaches("dev/42/telemetry", "dev/42", '/'));
+        assert!(reaches("dev/+/#", "dev", '/'));
+        assert!(reaches("dev/#", "dev/42/ack", '/'));
+        assert!(!reaches("dev/42", "dev/42/ack", '/'));
+        assert!(!reaches("dev/+/telemetry", "dev/42/ack", '/'));
+        assert!(reaches("dev.*.ack", "dev.42", '.'));
+    }
+}
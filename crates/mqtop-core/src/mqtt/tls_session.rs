@@ -0,0 +1,227 @@
+//! The TLS version and cipher suite negotiated on the live broker connection.
+//! rumqttc keeps its stream to itself, so the cipher suites handed to rustls
+//! are wrapped: when the handshake sets up keys with one of them, it is
+//! recorded for the event loop task that ran the handshake.
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rumqttc::tokio_rustls::rustls::crypto::cipher::{
+    AeadKey, Iv, KeyBlockShape, MessageDecrypter, MessageEncrypter, Tls12AeadAlgorithm,
+    Tls13AeadAlgorithm, UnsupportedOperationError,
+};
+use rumqttc::tokio_rustls::rustls::crypto::{ring, CipherSuiteCommon, CryptoProvider};
+use rumqttc::tokio_rustls::rustls::{
+    CipherSuite, ConnectionTrafficSecrets, ProtocolVersion, SupportedCipherSuite, Tls12CipherSuite,
+    Tls13CipherSuite,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedTls {
+    pub version: ProtocolVersion,
+    pub cipher_suite: CipherSuite,
+}
+
+impl NegotiatedTls {
+    /// "1.3" rather than rustls' `TLSv1_3`
+    pub fn version_label(&self) -> String {
+        match self.version {
+            ProtocolVersion::TLSv1_2 => "1.2".to_string(),
+            ProtocolVersion::TLSv1_3 => "1.3".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+
+    /// IANA name of the suite without the `TLS_`/`TLS13_` prefix
+    pub fn cipher_label(&self) -> String {
+        let name = self
+            .cipher_suite
+            .as_str()
+            .map_or_else(|| format!("{:?}", self.cipher_suite), str::to_string);
+        name.trim_start_matches("TLS13_")
+            .trim_start_matches("TLS_")
+            .to_string()
+    }
+}
+
+/// Where the event loop's handshakes leave what they negotiated
+#[derive(Debug, Clone, Default)]
+pub struct TlsSession(Arc<Mutex<Option<NegotiatedTls>>>);
+
+tokio::task_local! {
+    static SESSION: TlsSession;
+}
+
+impl TlsSession {
+    /// Run `future` (the event loop) so its handshakes record into this session
+    pub async fn scope<F: std::future::Future>(self, future: F) -> F::Output {
+        SESSION.scope(self, future).await
+    }
+
+    /// What the latest handshake negotiated
+    pub fn get(&self) -> Option<NegotiatedTls> {
+        self.0.lock().ok().and_then(|session| *session)
+    }
+
+    fn record(suite: CipherSuite, version: ProtocolVersion) {
+        // Handshakes outside a scope, like the capability probe, are not recorded
+        let _ = SESSION.try_with(|session| {
+            if let Ok(mut negotiated) = session.0.lock() {
+                *negotiated = Some(NegotiatedTls {
+                    version,
+                    cipher_suite: suite,
+                });
+            }
+        });
+    }
+}
+
+/// rustls' ring provider with every cipher suite recording when it is used
+pub fn crypto_provider() -> Arc<CryptoProvider> {
+    static PROVIDER: OnceLock<Arc<CryptoProvider>> = OnceLock::new();
+    PROVIDER
+        .get_or_init(|| {
+            let mut provider = ring::default_provider();
+            provider.cipher_suites = provider
+                .cipher_suites
+                .into_iter()
+                .map(recording_suite)
+                .collect();
+            Arc::new(provider)
+        })
+        .clone()
+}
+
+/// Wrap a suite's AEAD. Built once per process, so leaking gives the
+/// `'static` references rustls wants.
+fn recording_suite(suite: SupportedCipherSuite) -> SupportedCipherSuite {
+    let common = |common: &CipherSuiteCommon| CipherSuiteCommon {
+        suite: common.suite,
+        hash_provider: common.hash_provider,
+        confidentiality_limit: common.confidentiality_limit,
+        integrity_limit: common.integrity_limit,
+    };
+    match suite {
+        SupportedCipherSuite::Tls13(inner) => {
+            let aead_alg = Box::leak(Box::new(Recording {
+                suite: inner.common.suite,
+                inner: inner.aead_alg,
+            }));
+            SupportedCipherSuite::Tls13(Box::leak(Box::new(Tls13CipherSuite {
+                common: common(&inner.common),
+                hkdf_provider: inner.hkdf_provider,
+                aead_alg,
+                quic: inner.quic,
+            })))
+        }
+        SupportedCipherSuite::Tls12(inner) => {
+            let aead_alg = Box::leak(Box::new(Recording {
+                suite: inner.common.suite,
+                inner: inner.aead_alg,
+            }));
+            SupportedCipherSuite::Tls12(Box::leak(Box::new(Tls12CipherSuite {
+                common: common(&inner.common),
+                prf_provider: inner.prf_provider,
+                kx: inner.kx,
+                sign: inner.sign,
+                aead_alg,
+            })))
+        }
+    }
+}
+
+/// An AEAD that notes its suite whenever a connection takes keys from it
+struct Recording<A: ?Sized + 'static> {
+    suite: CipherSuite,
+    inner: &'static A,
+}
+
+impl Tls13AeadAlgorithm for Recording<dyn Tls13AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageEncrypter> {
+        TlsSession::record(self.suite, ProtocolVersion::TLSv1_3);
+        self.inner.encrypter(key, iv)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageDecrypter> {
+        TlsSession::record(self.suite, ProtocolVersion::TLSv1_3);
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_len(&self) -> usize {
+        self.inner.key_len()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: Iv,
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv)
+    }
+}
+
+impl Tls12AeadAlgorithm for Recording<dyn Tls12AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: &[u8], extra: &[u8]) -> Box<dyn MessageEncrypter> {
+        TlsSession::record(self.suite, ProtocolVersion::TLSv1_2);
+        self.inner.encrypter(key, iv, extra)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: &[u8]) -> Box<dyn MessageDecrypter> {
+        TlsSession::record(self.suite, ProtocolVersion::TLSv1_2);
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_block_shape(&self) -> KeyBlockShape {
+        self.inner.key_block_shape()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: &[u8],
+        explicit: &[u8],
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv, explicit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aes_256() -> &'static Tls13CipherSuite {
+        crypto_provider()
+            .cipher_suites
+            .iter()
+            .find_map(|suite| match suite {
+                SupportedCipherSuite::Tls13(suite)
+                    if suite.common.suite == CipherSuite::TLS13_AES_256_GCM_SHA384 =>
+                {
+                    Some(*suite)
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_records_suite_in_scope() {
+        let suite = aes_256();
+        let session = TlsSession::default();
+        suite
+            .aead_alg
+            .encrypter(AeadKey::from([0; 32]), Iv::new([0; 12]));
+        assert_eq!(session.get(), None);
+
+        session
+            .clone()
+            .scope(async {
+                suite
+                    .aead_alg
+                    .encrypter(AeadKey::from([0; 32]), Iv::new([0; 12]));
+            })
+            .await;
+        let negotiated = session.get().unwrap();
+        assert_eq!(negotiated.version_label(), "1.3");
+        assert_eq!(negotiated.cipher_label(), "AES_256_GCM_SHA384");
+    }
+}
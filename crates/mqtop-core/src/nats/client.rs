@@ -4,6 +4,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Context, Result};
+use bytes::Bytes;
 use tokio::io::{
     AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
 };
@@ -566,9 +567,9 @@ async fn read_loop<R: AsyncRead + Unpin>(
             reader.read_exact(&mut crlf).await?;
 
             let payload = if hdr_len <= buf.len() {
-                buf[hdr_len..].to_vec()
+                Bytes::from(buf).slice(hdr_len..)
             } else {
-                Vec::new()
+                Bytes::new()
             };
             let msg = MqttMessage::new(subject, payload, 0, false);
             let _ = event_tx.send(MqttEvent::Message(msg));
@@ -798,7 +799,7 @@ mod tests {
         while let Ok(ev) = event_rx.try_recv() {
             if let MqttEvent::Message(msg) = ev {
                 assert_eq!(msg.topic, "sensors.temp");
-                assert_eq!(msg.payload, b"hello");
+                assert_eq!(&msg.payload[..], b"hello");
                 found_msg = true;
             }
         }
@@ -0,0 +1,245 @@
+//! How counts, byte sizes, rates and metric values are written, `[ui.numbers]`
+
+use serde::{Deserialize, Serialize};
+
+/// Abbreviation suffixes with their scale and default decimal places
+const SUFFIXES: [(f64, &str, usize); 3] = [(1e3, "k", 1), (1e6, "M", 2), (1e9, "G", 2)];
+
+/// Units for byte sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ByteUnits {
+    /// KiB, MiB and GiB of 1024
+    #[default]
+    Binary,
+    /// kB, MB and GB of 1000
+    Si,
+}
+
+/// Decimal and thousands separators
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberLocale {
+    /// 1,234.5
+    #[default]
+    En,
+    /// 1.234,5
+    De,
+    /// 1 234,5
+    Fr,
+    /// 1'234.5
+    Ch,
+}
+
+impl NumberLocale {
+    /// Decimal separator and thousands separator
+    fn separators(&self) -> (char, char) {
+        match self {
+            NumberLocale::En => ('.', ','),
+            NumberLocale::De => (',', '.'),
+            NumberLocale::Fr => (',', ' '),
+            NumberLocale::Ch => ('.', '\''),
+        }
+    }
+}
+
+/// Number style shared by the stats, tree, message list and metric displays
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NumberFormat {
+    /// Shorten large numbers to 12.3k and 4.56M
+    #[serde(default = "default_true")]
+    pub abbreviate: bool,
+    /// Group thousands in numbers written in full
+    #[serde(default = "default_true")]
+    pub thousands_separator: bool,
+    /// Decimal places; unset picks them by magnitude
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<usize>,
+    #[serde(default)]
+    pub byte_units: ByteUnits,
+    #[serde(default)]
+    pub locale: NumberLocale,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            abbreviate: true,
+            thousands_separator: true,
+            precision: None,
+            byte_units: ByteUnits::default(),
+            locale: NumberLocale::default(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// A count of messages, topics and the like: 950, 12.3k, 4.56M
+    pub fn count(&self, count: u64) -> String {
+        let value = count as f64;
+        match self.abbreviated(value, None) {
+            Some(short) => short,
+            None => self.fixed(value, 0),
+        }
+    }
+
+    /// A measured value such as a tracked metric. `precision` overrides the
+    /// global decimal places; whole numbers otherwise show no decimals.
+    pub fn value(&self, value: f64, precision: Option<usize>) -> String {
+        if let Some(short) = self.abbreviated(value, precision) {
+            return short;
+        }
+        let decimals = precision
+            .or(self.precision)
+            .unwrap_or(if value.fract() == 0.0 { 0 } else { 2 });
+        self.fixed(value, decimals)
+    }
+
+    /// Events per second, without the unit: 0.25, 4.5, 1.2k
+    pub fn rate(&self, rate: f64) -> String {
+        if rate == 0.0 && self.precision.is_none() {
+            return "0".to_string();
+        }
+        if let Some(short) = self.abbreviated(rate, None) {
+            return short;
+        }
+        let decimals = self.precision.unwrap_or(if rate >= 1.0 { 1 } else { 2 });
+        self.fixed(rate, decimals)
+    }
+
+    /// A size in bytes: 512 B, 1.50 KiB (or 1.54 kB with SI units)
+    pub fn bytes(&self, bytes: u64) -> String {
+        let (base, units) = match self.byte_units {
+            ByteUnits::Binary => (1024.0, ["KiB", "MiB", "GiB", "TiB"]),
+            ByteUnits::Si => (1000.0, ["kB", "MB", "GB", "TB"]),
+        };
+        let mut value = bytes as f64;
+        if value < base {
+            return format!("{} B", self.fixed(value, 0));
+        }
+        let decimals = self.precision.unwrap_or(2);
+        let mut unit = 0;
+        value /= base;
+        // Move up while rounding would print a full unit of the next size
+        while unit + 1 < units.len() && rounded(value, decimals) >= base {
+            value /= base;
+            unit += 1;
+        }
+        format!("{} {}", self.fixed(value, decimals), units[unit])
+    }
+
+    /// 12.3k or 4.56M when abbreviating and the value reaches a thousand
+    fn abbreviated(&self, value: f64, precision: Option<usize>) -> Option<String> {
+        if !self.abbreviate || value.abs() < 1_000.0 {
+            return None;
+        }
+        let mut index = SUFFIXES
+            .iter()
+            .rposition(|(scale, _, _)| value.abs() >= *scale)
+            .unwrap_or(0);
+        loop {
+            let (scale, suffix, decimals) = SUFFIXES[index];
+            let decimals = precision.or(self.precision).unwrap_or(decimals);
+            let scaled = value / scale;
+            // 999_950 rounds to 1000.0k, which is 1.0M
+            if index + 1 < SUFFIXES.len() && rounded(scaled.abs(), decimals) >= 1_000.0 {
+                index += 1;
+                continue;
+            }
+            return Some(format!("{}{}", self.fixed(scaled, decimals), suffix));
+        }
+    }
+
+    /// Fixed decimal places with the locale's separators
+    fn fixed(&self, value: f64, decimals: usize) -> String {
+        let (decimal_sep, thousands_sep) = self.locale.separators();
+        let formatted = format!("{:.*}", decimals, value);
+        let (sign, digits) = match formatted.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", formatted.as_str()),
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+
+        let mut out = String::from(sign);
+        for (i, digit) in whole.chars().enumerate() {
+            if self.thousands_separator && i > 0 && (whole.len() - i) % 3 == 0 {
+                out.push(thousands_sep);
+            }
+            out.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            out.push(decimal_sep);
+            out.push_str(fraction);
+        }
+        out
+    }
+}
+
+/// `value` as it prints with `decimals` places
+fn rounded(value: f64, decimals: usize) -> f64 {
+    format!("{:.*}", decimals, value).parse().unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let numbers = NumberFormat::default();
+        assert_eq!(numbers.count(950), "950");
+        assert_eq!(numbers.count(12_345), "12.3k");
+        assert_eq!(numbers.count(4_560_000), "4.56M");
+        assert_eq!(numbers.value(21.0, None), "21");
+        assert_eq!(numbers.value(21.456, None), "21.46");
+        assert_eq!(numbers.value(21.456, Some(1)), "21.5");
+        assert_eq!(numbers.rate(0.0), "0");
+        assert_eq!(numbers.rate(0.5), "0.50");
+        assert_eq!(numbers.rate(5.5), "5.5");
+        assert_eq!(numbers.rate(1500.0), "1.5k");
+        assert_eq!(numbers.bytes(500), "500 B");
+        assert_eq!(numbers.bytes(1536), "1.50 KiB");
+        assert_eq!(numbers.bytes(1_572_864), "1.50 MiB");
+        assert_eq!(numbers.bytes(1_610_612_736), "1.50 GiB");
+    }
+
+    #[test]
+    fn test_full_numbers_and_locales() {
+        let mut numbers = NumberFormat {
+            abbreviate: false,
+            ..NumberFormat::default()
+        };
+        assert_eq!(numbers.count(1_234_567), "1,234,567");
+        assert_eq!(numbers.value(-12_345.678, None), "-12,345.68");
+
+        numbers.locale = NumberLocale::De;
+        assert_eq!(numbers.value(1_234.5, Some(1)), "1.234,5");
+        numbers.locale = NumberLocale::Ch;
+        assert_eq!(numbers.count(1_000), "1'000");
+        numbers.thousands_separator = false;
+        assert_eq!(numbers.count(1_000), "1000");
+
+        numbers.byte_units = ByteUnits::Si;
+        numbers.precision = Some(1);
+        assert_eq!(numbers.bytes(1_500), "1.5 kB");
+    }
+
+    #[test]
+    fn test_rounding_rolls_over_to_next_unit() {
+        let numbers = NumberFormat::default();
+        assert_eq!(numbers.count(999_949), "999.9k");
+        assert_eq!(numbers.count(999_950), "1.00M");
+        assert_eq!(numbers.value(-999_950.0, None), "-1.00M");
+        assert_eq!(numbers.count(999_995_000), "1.00G");
+        assert_eq!(numbers.value(999_950.0, Some(0)), "1M");
+        assert_eq!(numbers.bytes(1_048_575), "1.00 MiB");
+        assert_eq!(numbers.bytes(1_048_570), "1,023.99 KiB");
+    }
+}
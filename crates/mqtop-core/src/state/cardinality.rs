@@ -0,0 +1,113 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Spots branches whose number of children explodes, such as a buggy
+/// publisher putting a timestamp or request ID into the topic
+#[derive(Debug)]
+pub struct CardinalityWatch {
+    /// New children allowed per branch within the window (0 = off)
+    limit: usize,
+    window: Duration,
+    /// When each branch gained its recent children
+    growth: HashMap<String, VecDeque<Instant>>,
+    /// Branches already reported
+    flagged: HashSet<String>,
+}
+
+impl CardinalityWatch {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            growth: HashMap::new(),
+            flagged: HashSet::new(),
+        }
+    }
+
+    /// Record that `branch` gained a child. Returns the number of children it
+    /// gained within the window the first time that exceeds the limit.
+    pub fn record(&mut self, branch: &str, now: Instant) -> Option<usize> {
+        // The root has no branch to collapse or hide
+        if self.limit == 0 || branch.is_empty() || self.flagged.contains(branch) {
+            return None;
+        }
+        let times = self.growth.entry(branch.to_string()).or_default();
+        times.push_back(now);
+        while times
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) > self.window)
+        {
+            times.pop_front();
+        }
+        let added = times.len();
+        if added <= self.limit {
+            return None;
+        }
+        self.growth.remove(branch);
+        self.flagged.insert(branch.to_string());
+        Some(added)
+    }
+
+    /// Forget growth older than the window
+    pub fn expire(&mut self, now: Instant) {
+        let window = self.window;
+        self.growth.retain(|_, times| {
+            times
+                .back()
+                .is_some_and(|t| now.saturating_duration_since(*t) <= window)
+        });
+    }
+
+    pub fn is_flagged(&self, branch: &str) -> bool {
+        self.flagged.contains(branch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_branch_once_over_limit() {
+        let start = Instant::now();
+        let mut watch = CardinalityWatch::new(3, Duration::from_secs(60));
+        for i in 0..3 {
+            assert_eq!(
+                watch.record("logs/dev1", start + Duration::from_secs(i)),
+                None
+            );
+        }
+        assert_eq!(
+            watch.record("logs/dev1", start + Duration::from_secs(3)),
+            Some(4)
+        );
+        assert!(watch.is_flagged("logs/dev1"));
+        assert_eq!(
+            watch.record("logs/dev1", start + Duration::from_secs(4)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_slow_growth_stays_under_limit() {
+        let start = Instant::now();
+        let mut watch = CardinalityWatch::new(3, Duration::from_secs(60));
+        for i in 0..20 {
+            let at = start + Duration::from_secs(i * 30);
+            assert_eq!(watch.record("fleet", at), None);
+        }
+        watch.expire(start + Duration::from_secs(2000));
+        assert!(watch.growth.is_empty());
+    }
+
+    #[test]
+    fn test_disabled_and_root_ignored() {
+        let now = Instant::now();
+        let mut off = CardinalityWatch::new(0, Duration::from_secs(60));
+        let mut watch = CardinalityWatch::new(1, Duration::from_secs(60));
+        for _ in 0..5 {
+            assert_eq!(off.record("logs", now), None);
+            assert_eq!(watch.record("", now), None);
+        }
+    }
+}
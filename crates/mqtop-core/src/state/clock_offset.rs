@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Topic some brokers publish their wall clock on
+pub const BROKER_TIME_TOPIC: &str = "$SYS/broker/time";
+
+/// Payload samples needed before trusting a payload-based estimate
+const MIN_PAYLOAD_SAMPLES: usize = 5;
+/// Smallest positive offset attributed to clock skew rather than transit time
+const PAYLOAD_SKEW_THRESHOLD_MS: i64 = 1000;
+/// A broker time reading is preferred over payload samples for this long
+const BROKER_TIME_MAX_AGE: Duration = Duration::from_secs(120);
+
+/// Where the current clock offset estimate comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetSource {
+    /// `$SYS/broker/time`
+    Broker,
+    /// Timestamps embedded in payloads
+    Payload,
+}
+
+impl OffsetSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OffsetSource::Broker => "broker time",
+            OffsetSource::Payload => "payload timestamps",
+        }
+    }
+}
+
+/// Estimates how far the local clock is ahead of the broker or devices.
+///
+/// A positive offset means the local clock is ahead. With broker time the
+/// offset is read directly; otherwise it is the smallest receive-minus-payload
+/// delta seen, which bounds the skew once transit time is negligible.
+#[derive(Debug)]
+pub struct ClockOffset {
+    /// Latest broker reading: offset in ms and when it was taken
+    broker: Option<(i64, Instant)>,
+    /// Recent receive-minus-payload deltas in ms
+    payload_deltas: VecDeque<i64>,
+    max_samples: usize,
+}
+
+impl ClockOffset {
+    pub fn new(max_samples: usize) -> Self {
+        Self {
+            broker: None,
+            payload_deltas: VecDeque::with_capacity(max_samples),
+            max_samples,
+        }
+    }
+
+    /// Record the broker's clock as read from a `$SYS/broker/time` message
+    pub fn record_broker_time(&mut self, broker_ms: i64, local_ms: i64) {
+        self.broker = Some((local_ms - broker_ms, Instant::now()));
+    }
+
+    /// Record the difference between receive time and a payload timestamp
+    pub fn record_payload_delta(&mut self, delta_ms: i64) {
+        if self.payload_deltas.len() >= self.max_samples {
+            self.payload_deltas.pop_front();
+        }
+        self.payload_deltas.push_back(delta_ms);
+    }
+
+    /// Current offset in ms and its source, if the clocks look skewed
+    pub fn estimate(&self) -> Option<(i64, OffsetSource)> {
+        if let Some((offset, at)) = self.broker {
+            if at.elapsed() < BROKER_TIME_MAX_AGE {
+                return Some((offset, OffsetSource::Broker));
+            }
+        }
+
+        if self.payload_deltas.len() < MIN_PAYLOAD_SAMPLES {
+            return None;
+        }
+        let min = *self.payload_deltas.iter().min()?;
+        // A message can't arrive before it was sent; a large minimum delay is skew too
+        (!(0..=PAYLOAD_SKEW_THRESHOLD_MS).contains(&min)).then_some((min, OffsetSource::Payload))
+    }
+
+    /// Offset to subtract from receive-minus-payload deltas (0 when unknown)
+    pub fn correction_ms(&self) -> i64 {
+        self.estimate().map_or(0, |(offset, _)| offset)
+    }
+}
+
+impl Default for ClockOffset {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+/// Parse a timestamp as epoch seconds, epoch millis, or RFC 3339, in unix millis
+pub fn parse_timestamp_ms(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if let Ok(n) = s.parse::<i64>() {
+        return Some(epoch_to_ms(n));
+    }
+    if let Ok(f) = s.parse::<f64>() {
+        // Fractional epoch seconds
+        return Some((f * 1000.0).round() as i64);
+    }
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.timestamp_millis())
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                .map(|dt| dt.and_utc().timestamp_millis())
+        })
+        .ok()
+}
+
+/// Treat large values as milliseconds and small ones as seconds
+pub fn epoch_to_ms(ts: i64) -> i64 {
+    if ts > 1_000_000_000_000 {
+        ts
+    } else {
+        ts * 1000
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broker_offset_preferred() {
+        let mut clock = ClockOffset::new(10);
+        for _ in 0..10 {
+            clock.record_payload_delta(-3000);
+        }
+        assert_eq!(clock.estimate(), Some((-3000, OffsetSource::Payload)));
+
+        clock.record_broker_time(1_000_000, 1_002_500);
+        assert_eq!(clock.estimate(), Some((2500, OffsetSource::Broker)));
+    }
+
+    #[test]
+    fn test_payload_offset_needs_skew() {
+        let mut clock = ClockOffset::new(10);
+        for delta in [40, 25, 60, 30, 55] {
+            clock.record_payload_delta(delta);
+        }
+        // Plausible transit times: no correction
+        assert_eq!(clock.estimate(), None);
+        assert_eq!(clock.correction_ms(), 0);
+
+        clock.record_payload_delta(-800);
+        assert_eq!(clock.correction_ms(), -800);
+    }
+
+    #[test]
+    fn test_parse_timestamp_ms() {
+        assert_eq!(parse_timestamp_ms("1700000000"), Some(1_700_000_000_000));
+        assert_eq!(parse_timestamp_ms("1700000000123"), Some(1_700_000_000_123));
+        assert_eq!(parse_timestamp_ms("1700000000.5"), Some(1_700_000_000_500));
+        assert_eq!(
+            parse_timestamp_ms("2023-11-14T22:13:20Z"),
+            Some(1_700_000_000_000)
+        );
+        assert_eq!(
+            parse_timestamp_ms("2023-11-14 22:13:20"),
+            Some(1_700_000_000_000)
+        );
+        assert_eq!(parse_timestamp_ms("soon"), None);
+    }
+}
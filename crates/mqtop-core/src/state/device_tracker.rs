@@ -4,6 +4,13 @@
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
+use serde::{Deserialize, Serialize};
+
+use super::metric_tracker::topic_matches;
+
+/// Devices silent for longer than this are stale
+const STALE_THRESHOLD: Duration = Duration::from_secs(300);
+
 /// Tracks device health based on telemetry message frequency
 #[derive(Debug)]
 pub struct DeviceTracker {
@@ -15,6 +22,76 @@ pub struct DeviceTracker {
     healthy_threshold: f64,
     /// Threshold for warning status (messages per minute)
     warning_threshold: f64,
+    /// Topics where devices announce whether they are online
+    availability: Vec<AvailabilityTopic>,
+}
+
+/// A topic where devices announce their availability, usually their last
+/// will, e.g. `+/status` carrying `online` or `offline`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailabilityTopic {
+    /// Topic pattern; the device id is the level matched by its first
+    /// single-level wildcard (`+` or `*`)
+    pub pattern: String,
+    /// Payload announcing the device is up
+    #[serde(default = "default_online_payload")]
+    pub online: String,
+    /// Payload announcing the device is down
+    #[serde(default = "default_offline_payload")]
+    pub offline: String,
+}
+
+fn default_online_payload() -> String {
+    "online".to_string()
+}
+
+fn default_offline_payload() -> String {
+    "offline".to_string()
+}
+
+impl AvailabilityTopic {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            online: default_online_payload(),
+            offline: default_offline_payload(),
+        }
+    }
+
+    /// The device a topic belongs to, if it matches the pattern
+    pub fn device_id(&self, topic: &str) -> Option<String> {
+        if !topic_matches(&self.pattern, topic) {
+            return None;
+        }
+        let separators = ['/', '.'];
+        let level = self
+            .pattern
+            .split(separators)
+            .position(|part| part == "+" || part == "*")?;
+        topic.split(separators).nth(level).map(str::to_string)
+    }
+
+    /// Whether a payload announces the device online (`Some(true)`) or
+    /// offline. Plain text is compared case-insensitively; JSON like
+    /// `{"state": "online"}` is read from its `state` or `status` field.
+    pub fn parse(&self, payload: &[u8]) -> Option<bool> {
+        let text = std::str::from_utf8(payload).ok()?.trim();
+        let value = match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(serde_json::Value::Object(map)) => map
+                .get("state")
+                .or_else(|| map.get("status"))?
+                .as_str()?
+                .to_string(),
+            _ => text.to_string(),
+        };
+        if value.eq_ignore_ascii_case(&self.online) {
+            Some(true)
+        } else if value.eq_ignore_ascii_case(&self.offline) {
+            Some(false)
+        } else {
+            None
+        }
+    }
 }
 
 /// Health status of a device
@@ -28,6 +105,21 @@ pub enum HealthStatus {
     Stale,
     /// Never received messages or just discovered
     Unknown,
+    /// Announced offline on its availability topic
+    Offline,
+}
+
+impl HealthStatus {
+    /// Short word for the status, for when color alone can't carry it
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthStatus::Healthy => "ok",
+            HealthStatus::Warning => "slow",
+            HealthStatus::Stale => "stale",
+            HealthStatus::Unknown => "new",
+            HealthStatus::Offline => "offline",
+        }
+    }
 }
 
 /// Health information for a single device
@@ -49,6 +141,8 @@ pub struct DeviceHealth {
     pub last_payload_size: usize,
     /// Topics this device sends on
     pub topics: Vec<String>,
+    /// Last announcement on an availability topic: online or not
+    pub online: Option<bool>,
 }
 
 impl DeviceHealth {
@@ -62,6 +156,7 @@ impl DeviceHealth {
             status: HealthStatus::Unknown,
             last_payload_size: 0,
             topics: Vec::new(),
+            online: None,
         }
     }
 
@@ -104,14 +199,30 @@ impl DeviceTracker {
             rate_window: Duration::from_secs(60),
             healthy_threshold: 1.0, // At least 1 msg/min
             warning_threshold: 0.1, // At least 1 msg/10min
+            availability: Vec::new(),
         }
     }
 
+    /// Read device availability from these topics
+    pub fn set_availability_topics(&mut self, topics: Vec<AvailabilityTopic>) {
+        self.availability = topics;
+    }
+
     /// Process a message and update device tracking
-    pub fn process_message(&mut self, topic: &str, payload_size: usize) {
-        // Extract device ID from topic
+    pub fn process_message(&mut self, topic: &str, payload: &[u8]) {
+        let payload_size = payload.len();
+        let availability = self.availability.iter().find_map(|rule| {
+            let device_id = rule.device_id(topic)?;
+            Some((device_id, rule.parse(payload)))
+        });
+
+        // Extract device ID from the availability topic, or else the topic
         // Pattern: telemetry/{device_id}/...
-        if let Some(device_id) = extract_device_id(topic) {
+        let device_id = match &availability {
+            Some((device_id, _)) => Some(device_id.clone()),
+            None => extract_device_id(topic),
+        };
+        if let Some(device_id) = device_id {
             let device_type = extract_device_type(topic);
             let rate_window = self.rate_window;
 
@@ -125,6 +236,10 @@ impl DeviceTracker {
             device.last_payload_size = payload_size;
             device.recent_messages.push_back(Instant::now());
 
+            if let Some((_, Some(online))) = availability {
+                device.online = Some(online);
+            }
+
             // Set device type if found
             if device.device_type.is_none() {
                 device.device_type = device_type;
@@ -141,31 +256,28 @@ impl DeviceTracker {
                 .unwrap_or(Instant::now());
             device.recent_messages.retain(|t| *t > cutoff);
 
-            // Update status inline to avoid borrow issues
-            let rate = device.messages_per_minute(rate_window);
-            let stale_threshold = Duration::from_secs(300); // 5 minutes
-
-            device.status = if device.time_since_last() > stale_threshold {
-                HealthStatus::Stale
-            } else if rate >= self.healthy_threshold {
-                HealthStatus::Healthy
-            } else if rate >= self.warning_threshold {
-                HealthStatus::Warning
-            } else if device.message_count > 0 {
-                HealthStatus::Warning
-            } else {
-                HealthStatus::Unknown
-            };
+            self.update_device_status(&device_id);
         }
     }
 
+    /// Insert a device with existing history (used when restoring a session)
+    pub fn insert_device(&mut self, device: DeviceHealth) {
+        self.devices.insert(device.device_id.clone(), device);
+    }
+
     /// Update health status for a device
     fn update_device_status(&mut self, device_id: &str) {
         if let Some(device) = self.devices.get_mut(device_id) {
             let rate = device.messages_per_minute(self.rate_window);
-            let stale_threshold = Duration::from_secs(300); // 5 minutes
 
-            device.status = if device.time_since_last() > stale_threshold {
+            // An announcement beats the heuristics: offline is offline at
+            // once, and a device the broker reports online is not stale
+            // just for being quiet
+            device.status = if device.online == Some(false) {
+                HealthStatus::Offline
+            } else if device.online == Some(true) && rate < self.warning_threshold {
+                HealthStatus::Healthy
+            } else if device.time_since_last() > STALE_THRESHOLD {
                 HealthStatus::Stale
             } else if rate >= self.healthy_threshold {
                 HealthStatus::Healthy
@@ -204,12 +316,41 @@ impl DeviceTracker {
                 HealthStatus::Warning => warning += 1,
                 HealthStatus::Stale => stale += 1,
                 HealthStatus::Unknown => unknown += 1,
+                HealthStatus::Offline => {}
             }
         }
 
         (healthy, warning, stale, unknown)
     }
 
+    /// Devices that announced they are offline
+    pub fn offline_count(&self) -> usize {
+        self.devices
+            .values()
+            .filter(|device| device.status == HealthStatus::Offline)
+            .count()
+    }
+
+    /// Rate-window timestamps held across all devices
+    pub fn sample_count(&self) -> usize {
+        self.devices.values().map(|d| d.recent_messages.len()).sum()
+    }
+
+    /// Drop rate-window timestamps that fell out of the window for devices that
+    /// went quiet. Returns the number of timestamps dropped.
+    pub fn prune_samples(&mut self) -> usize {
+        let now = Instant::now();
+        let cutoff = now.checked_sub(self.rate_window).unwrap_or(now);
+        let mut dropped = 0;
+        for device in self.devices.values_mut() {
+            while device.recent_messages.front().is_some_and(|t| *t <= cutoff) {
+                device.recent_messages.pop_front();
+                dropped += 1;
+            }
+        }
+        dropped
+    }
+
     /// Update all device statuses (call periodically)
     pub fn update_all_statuses(&mut self) {
         let device_ids: Vec<String> = self.devices.keys().cloned().collect();
@@ -282,6 +423,80 @@ mod tests {
         assert_eq!(extract_device_id("random/topic"), None);
     }
 
+    #[test]
+    fn test_prune_samples() {
+        let mut tracker = DeviceTracker::new();
+        tracker.process_message("devices/dev1/status", &[0; 10]);
+
+        let old = Instant::now()
+            .checked_sub(Duration::from_secs(120))
+            .unwrap();
+        let device = tracker.devices.get_mut("dev1").unwrap();
+        device.recent_messages.push_front(old);
+        assert_eq!(tracker.sample_count(), 2);
+
+        assert_eq!(tracker.prune_samples(), 1);
+        assert_eq!(tracker.sample_count(), 1);
+    }
+
+    #[test]
+    fn test_silent_device_turns_stale() {
+        let mut tracker = DeviceTracker::new();
+        tracker.process_message("devices/dev1/status", &[0; 10]);
+        assert_eq!(tracker.devices["dev1"].status, HealthStatus::Healthy);
+
+        let device = tracker.devices.get_mut("dev1").unwrap();
+        device.last_seen = Instant::now()
+            .checked_sub(STALE_THRESHOLD + Duration::from_secs(1))
+            .unwrap();
+        device.recent_messages.clear();
+
+        tracker.update_all_statuses();
+        assert_eq!(tracker.devices["dev1"].status, HealthStatus::Stale);
+    }
+
+    #[test]
+    fn test_availability_topic() {
+        let rule = AvailabilityTopic::new("+/status");
+        assert_eq!(
+            rule.device_id("sensor-1/status"),
+            Some("sensor-1".to_string())
+        );
+        assert_eq!(rule.device_id("sensor-1/state"), None);
+        assert_eq!(rule.parse(b"online"), Some(true));
+        assert_eq!(rule.parse(b" OFFLINE\n"), Some(false));
+        assert_eq!(rule.parse(br#"{"state":"offline"}"#), Some(false));
+        assert_eq!(rule.parse(b"rebooting"), None);
+
+        let nats = AvailabilityTopic::new("hub.*.lwt");
+        assert_eq!(nats.device_id("hub.gw-2.lwt"), Some("gw-2".to_string()));
+    }
+
+    #[test]
+    fn test_offline_announcement_beats_heuristics() {
+        let mut tracker = DeviceTracker::new();
+        tracker.set_availability_topics(vec![AvailabilityTopic::new("+/status")]);
+
+        tracker.process_message("sensor-1/status", b"online");
+        let device = tracker.devices.get_mut("sensor-1").unwrap();
+        assert_eq!(device.online, Some(true));
+        // Quiet for long, but its availability topic still says online
+        device.last_seen = Instant::now()
+            .checked_sub(STALE_THRESHOLD + Duration::from_secs(1))
+            .unwrap();
+        device.recent_messages.clear();
+        tracker.update_all_statuses();
+        assert_eq!(tracker.devices["sensor-1"].status, HealthStatus::Healthy);
+
+        tracker.process_message("sensor-1/status", b"offline");
+        assert_eq!(tracker.devices["sensor-1"].status, HealthStatus::Offline);
+        assert_eq!(tracker.offline_count(), 1);
+
+        // Unrecognised payloads leave the announced state alone
+        tracker.process_message("sensor-1/status", b"rebooting");
+        assert_eq!(tracker.devices["sensor-1"].status, HealthStatus::Offline);
+    }
+
     #[test]
     fn test_extract_device_type() {
         assert_eq!(
@@ -298,9 +513,9 @@ mod tests {
     fn test_device_tracking() {
         let mut tracker = DeviceTracker::new();
 
-        tracker.process_message("telemetry/device1/meter/zap/json", 100);
-        tracker.process_message("telemetry/device1/meter/zap/json", 150);
-        tracker.process_message("telemetry/device2/inverter/data", 200);
+        tracker.process_message("telemetry/device1/meter/zap/json", &[0; 100]);
+        tracker.process_message("telemetry/device1/meter/zap/json", &[0; 150]);
+        tracker.process_message("telemetry/device2/inverter/data", &[0; 200]);
 
         assert_eq!(tracker.device_count(), 2);
 
@@ -320,7 +535,7 @@ mod tests {
 
         // Process messages to make device healthy
         for _ in 0..10 {
-            tracker.process_message("telemetry/device1/meter/data", 100);
+            tracker.process_message("telemetry/device1/meter/data", &[0; 100]);
         }
 
         let devices = tracker.get_devices();
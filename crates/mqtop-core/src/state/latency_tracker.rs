@@ -4,6 +4,12 @@
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+use super::clock_offset::{epoch_to_ms, parse_timestamp_ms, ClockOffset};
+use super::payload::ParsedPayload;
+
+/// Payload timestamps further than this from the local clock are ignored
+const MAX_PAYLOAD_DELTA_MS: i64 = 3_600_000;
+
 /// Tracks message latency and inter-arrival times
 #[derive(Debug)]
 pub struct LatencyTracker {
@@ -25,6 +31,8 @@ pub struct LatencyTracker {
     pub max_payload_latency: Option<Duration>,
     pub total_payload_latency: Duration,
     pub payload_latency_count: u64,
+    /// Estimated skew between the local clock and the broker/devices
+    pub clock: ClockOffset,
 }
 
 impl LatencyTracker {
@@ -42,11 +50,17 @@ impl LatencyTracker {
             max_payload_latency: None,
             total_payload_latency: Duration::ZERO,
             payload_latency_count: 0,
+            clock: ClockOffset::new(max_samples),
         }
     }
 
     /// Record a message arrival
     pub fn record_message(&mut self, payload: &[u8]) {
+        self.record_payload(&ParsedPayload::new(payload));
+    }
+
+    /// Like `record_message`, reusing a payload other trackers may have parsed
+    pub fn record_payload(&mut self, payload: &ParsedPayload) {
         let now = Instant::now();
 
         // Calculate inter-arrival time
@@ -86,9 +100,10 @@ impl LatencyTracker {
         }
     }
 
-    /// Try to extract a timestamp from JSON payload and calculate latency
-    fn extract_payload_latency(&self, payload: &[u8]) -> Option<Duration> {
-        let json: serde_json::Value = serde_json::from_slice(payload).ok()?;
+    /// Try to extract a timestamp from JSON payload and calculate latency,
+    /// corrected for the estimated clock offset
+    fn extract_payload_latency(&mut self, payload: &ParsedPayload) -> Option<Duration> {
+        let json = payload.json()?;
 
         // Try common timestamp field names
         let timestamp = json
@@ -98,27 +113,26 @@ impl LatencyTracker {
             .or_else(|| json.get("t"))?;
 
         let ts_millis = match timestamp {
-            serde_json::Value::Number(n) => {
-                let ts = n.as_i64()?;
-                // Handle both seconds and milliseconds
-                if ts > 1_000_000_000_000 {
-                    ts // Already milliseconds
-                } else {
-                    ts * 1000 // Convert seconds to milliseconds
-                }
-            }
-            serde_json::Value::String(s) => {
-                // Try to parse ISO 8601 or epoch
-                s.parse::<i64>().ok()?
-            }
+            // Handle both seconds and milliseconds
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(ts) => epoch_to_ms(ts),
+                None => (n.as_f64()? * 1000.0).round() as i64,
+            },
+            // ISO 8601 or epoch
+            serde_json::Value::String(s) => parse_timestamp_ms(s)?,
             _ => return None,
         };
 
         let now_millis = chrono::Utc::now().timestamp_millis();
-        let latency_millis = now_millis - ts_millis;
+        let delta_millis = now_millis - ts_millis;
+        if delta_millis.abs() >= MAX_PAYLOAD_DELTA_MS {
+            return None;
+        }
+        self.clock.record_payload_delta(delta_millis);
+        let latency_millis = delta_millis - self.clock.correction_ms();
 
         // Only accept reasonable latencies (0 to 1 hour)
-        if latency_millis >= 0 && latency_millis < 3_600_000 {
+        if latency_millis >= 0 && latency_millis < MAX_PAYLOAD_DELTA_MS {
             Some(Duration::from_millis(latency_millis as u64))
         } else {
             None
@@ -153,6 +167,26 @@ impl LatencyTracker {
         &self.payload_latencies
     }
 
+    /// Samples currently held for sparklines
+    pub fn sample_count(&self) -> usize {
+        self.inter_arrival_times.len() + self.payload_latencies.len()
+    }
+
+    /// Drop sparkline samples once no message has arrived for `max_age`.
+    /// Running min/max/avg stats are kept. Returns the number of samples dropped.
+    pub fn prune_stale(&mut self, max_age: Duration) -> usize {
+        let stale = self
+            .last_message_time
+            .is_some_and(|last| last.elapsed() > max_age);
+        if !stale {
+            return 0;
+        }
+        let dropped = self.sample_count();
+        self.inter_arrival_times.clear();
+        self.payload_latencies.clear();
+        dropped
+    }
+
     /// Format duration for display
     pub fn format_duration(d: Duration) -> String {
         let millis = d.as_millis();
@@ -0,0 +1,389 @@
+#![allow(dead_code)]
+#![allow(clippy::unwrap_or_default)]
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use chrono::{DateTime, Utc};
+
+use crate::mqtt::MqttMessage;
+
+/// A buffered message plus any identical messages that followed it
+#[derive(Debug)]
+pub struct BufferedMessage {
+    pub message: MqttMessage,
+    /// Identical payloads received after this one, folded into this entry
+    pub repeats: u32,
+    /// When the last repeat arrived
+    pub last_seen: DateTime<Utc>,
+    payload_hash: u64,
+}
+
+impl BufferedMessage {
+    fn new(message: MqttMessage) -> Self {
+        Self {
+            payload_hash: hash_payload(&message.payload),
+            last_seen: message.timestamp,
+            repeats: 0,
+            message,
+        }
+    }
+
+    fn is_repeat_of(&self, message: &MqttMessage, hash: u64) -> bool {
+        self.payload_hash == hash
+            && self.message.qos == message.qos
+            && self.message.retain == message.retain
+            && self.message.payload == message.payload
+    }
+}
+
+fn hash_payload(payload: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    payload.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A bounded ring buffer that stores the last N messages per topic.
+/// Prevents memory exhaustion under high message rates.
+/// Consecutive identical payloads on a topic are stored once with a repeat count.
+#[derive(Debug)]
+pub struct MessageBuffer {
+    /// Messages per topic
+    buffers: HashMap<String, VecDeque<BufferedMessage>>,
+    /// Maximum messages to keep per topic
+    max_per_topic: usize,
+    /// Topics kept to a different limit; None keeps every message
+    overrides: HashMap<String, Option<usize>>,
+    /// Total messages currently stored
+    total_stored: usize,
+    /// Repeats currently folded into stored messages
+    total_repeats: u64,
+}
+
+impl MessageBuffer {
+    pub fn new(max_per_topic: usize) -> Self {
+        Self {
+            buffers: HashMap::new(),
+            max_per_topic,
+            overrides: HashMap::new(),
+            total_stored: 0,
+            total_repeats: 0,
+        }
+    }
+
+    /// Add a message to the buffer
+    pub fn push(&mut self, message: MqttMessage) {
+        let hash = hash_payload(&message.payload);
+        if let Some(latest) = self
+            .buffers
+            .get_mut(&message.topic)
+            .and_then(|buf| buf.back_mut())
+        {
+            if latest.is_repeat_of(&message, hash) {
+                latest.repeats = latest.repeats.saturating_add(1);
+                latest.last_seen = message.timestamp;
+                self.total_repeats += 1;
+                return;
+            }
+        }
+
+        let limit = self.limit_for(&message.topic);
+        let topic = message.topic.clone();
+        let buffer = self.buffers.entry(topic).or_insert_with(VecDeque::new);
+
+        // Remove oldest if at capacity
+        if limit.is_some_and(|limit| buffer.len() >= limit) {
+            if let Some(evicted) = buffer.pop_front() {
+                self.total_repeats = self.total_repeats.saturating_sub(evicted.repeats as u64);
+            }
+            self.total_stored = self.total_stored.saturating_sub(1);
+        }
+
+        buffer.push_back(BufferedMessage::new(message));
+        self.total_stored += 1;
+    }
+
+    /// Add a message restored from a session, with the repeats folded into it
+    pub fn restore(&mut self, message: MqttMessage, repeats: u32, last_seen: DateTime<Utc>) {
+        let topic = message.topic.clone();
+        self.push(message);
+        if let Some(latest) = self.buffers.get_mut(&topic).and_then(|buf| buf.back_mut()) {
+            latest.repeats = latest.repeats.saturating_add(repeats);
+            latest.last_seen = latest.last_seen.max(last_seen);
+            self.total_repeats += repeats as u64;
+        }
+    }
+
+    /// Messages kept for a topic; None when unlimited
+    pub fn limit_for(&self, topic: &str) -> Option<usize> {
+        self.overrides
+            .get(topic)
+            .copied()
+            .unwrap_or(Some(self.max_per_topic))
+    }
+
+    /// Keep `limit` messages for a topic instead of the default (None for all
+    /// of them), dropping the oldest if it already holds more
+    pub fn set_limit(&mut self, topic: &str, limit: Option<usize>) {
+        self.overrides.insert(topic.to_string(), limit);
+        self.trim(topic);
+    }
+
+    /// Go back to the default limit for a topic
+    pub fn reset_limit(&mut self, topic: &str) {
+        self.overrides.remove(topic);
+        self.trim(topic);
+    }
+
+    /// Whether a topic has a limit of its own
+    pub fn has_override(&self, topic: &str) -> bool {
+        self.overrides.contains_key(topic)
+    }
+
+    fn trim(&mut self, topic: &str) {
+        let Some(limit) = self.limit_for(topic) else {
+            return;
+        };
+        let Some(buffer) = self.buffers.get_mut(topic) else {
+            return;
+        };
+        while buffer.len() > limit {
+            if let Some(evicted) = buffer.pop_front() {
+                self.total_repeats = self.total_repeats.saturating_sub(evicted.repeats as u64);
+                self.total_stored = self.total_stored.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Get messages for a specific topic (newest first)
+    pub fn get_messages(&self, topic: &str) -> Vec<&MqttMessage> {
+        self.buffers
+            .get(topic)
+            .map(|buf| buf.iter().rev().map(|entry| &entry.message).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get buffered entries with repeat counts for a topic (newest first)
+    pub fn get_entries(&self, topic: &str) -> Vec<&BufferedMessage> {
+        self.buffers
+            .get(topic)
+            .map(|buf| buf.iter().rev().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the most recent message for a topic
+    pub fn get_latest(&self, topic: &str) -> Option<&MqttMessage> {
+        self.buffers.get(topic)?.back().map(|entry| &entry.message)
+    }
+
+    /// Get message count for a topic
+    pub fn count_for_topic(&self, topic: &str) -> usize {
+        self.buffers.get(topic).map(|b| b.len()).unwrap_or(0)
+    }
+
+    /// Get total messages stored across all topics
+    pub fn total_stored(&self) -> usize {
+        self.total_stored
+    }
+
+    /// Payload bytes held across all topics
+    pub fn payload_bytes(&self) -> usize {
+        self.buffers
+            .values()
+            .flat_map(|buf| buf.iter())
+            .map(|entry| entry.message.payload.len())
+            .sum()
+    }
+
+    /// Payload bytes held for a topic
+    pub fn topic_payload_bytes(&self, topic: &str) -> usize {
+        self.buffers
+            .get(topic)
+            .map(|buf| buf.iter().map(|entry| entry.message.payload.len()).sum())
+            .unwrap_or(0)
+    }
+
+    /// Drop topics whose buffers are empty. Returns the number removed.
+    pub fn remove_empty(&mut self) -> usize {
+        let before = self.buffers.len();
+        self.buffers.retain(|_, buf| !buf.is_empty());
+        before - self.buffers.len()
+    }
+
+    /// Get identical messages folded into stored ones instead of copied
+    pub fn total_repeats(&self) -> u64 {
+        self.total_repeats
+    }
+
+    /// Get number of topics with messages
+    pub fn topic_count(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Get all topics that have buffered messages
+    pub fn topics(&self) -> Vec<&str> {
+        self.buffers.keys().map(|t| t.as_str()).collect()
+    }
+
+    /// Clear all messages
+    pub fn clear(&mut self) {
+        self.buffers.clear();
+        self.total_stored = 0;
+        self.total_repeats = 0;
+    }
+
+    /// Clear messages for a specific topic
+    pub fn clear_topic(&mut self, topic: &str) {
+        if let Some(buffer) = self.buffers.remove(topic) {
+            self.total_stored = self.total_stored.saturating_sub(buffer.len());
+            let repeats: u64 = buffer.iter().map(|entry| entry.repeats as u64).sum();
+            self.total_repeats = self.total_repeats.saturating_sub(repeats);
+        }
+    }
+
+    /// Get all recent messages across all topics (newest first, limited)
+    pub fn get_recent_all(&self, limit: usize) -> Vec<&MqttMessage> {
+        let mut all_messages: Vec<_> = self
+            .buffers
+            .values()
+            .flat_map(|buf| buf.iter().map(|entry| &entry.message))
+            .collect();
+
+        // Sort by timestamp descending
+        all_messages.sort_by_key(|m| std::cmp::Reverse(m.timestamp));
+
+        all_messages.into_iter().take(limit).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_message(topic: &str, payload: &str) -> MqttMessage {
+        MqttMessage::new(topic.to_string(), payload.as_bytes().to_vec(), 0, false)
+    }
+
+    #[test]
+    fn test_push_and_get() {
+        let mut buffer = MessageBuffer::new(10);
+
+        buffer.push(make_message("test/topic", "message1"));
+        buffer.push(make_message("test/topic", "message2"));
+
+        let messages = buffer.get_messages("test/topic");
+        assert_eq!(messages.len(), 2);
+
+        // Newest first
+        assert_eq!(messages[0].payload_str().unwrap(), "message2");
+        assert_eq!(messages[1].payload_str().unwrap(), "message1");
+    }
+
+    #[test]
+    fn test_ring_buffer_behavior() {
+        let mut buffer = MessageBuffer::new(3); // Only keep 3 messages
+
+        buffer.push(make_message("topic", "msg1"));
+        buffer.push(make_message("topic", "msg2"));
+        buffer.push(make_message("topic", "msg3"));
+        buffer.push(make_message("topic", "msg4")); // Should evict msg1
+
+        let messages = buffer.get_messages("topic");
+        assert_eq!(messages.len(), 3);
+
+        // msg1 should be gone, newest first
+        assert_eq!(messages[0].payload_str().unwrap(), "msg4");
+        assert_eq!(messages[2].payload_str().unwrap(), "msg2");
+    }
+
+    #[test]
+    fn test_multiple_topics() {
+        let mut buffer = MessageBuffer::new(5);
+
+        buffer.push(make_message("topic/a", "a1"));
+        buffer.push(make_message("topic/b", "b1"));
+        buffer.push(make_message("topic/a", "a2"));
+
+        assert_eq!(buffer.count_for_topic("topic/a"), 2);
+        assert_eq!(buffer.count_for_topic("topic/b"), 1);
+        assert_eq!(buffer.topic_count(), 2);
+        assert_eq!(buffer.total_stored(), 3);
+    }
+
+    #[test]
+    fn test_get_latest() {
+        let mut buffer = MessageBuffer::new(10);
+
+        buffer.push(make_message("topic", "first"));
+        buffer.push(make_message("topic", "second"));
+        buffer.push(make_message("topic", "latest"));
+
+        let latest = buffer.get_latest("topic").unwrap();
+        assert_eq!(latest.payload_str().unwrap(), "latest");
+    }
+
+    #[test]
+    fn test_repeats_folded() {
+        let mut buffer = MessageBuffer::new(10);
+
+        buffer.push(make_message("topic", "on"));
+        buffer.push(make_message("topic", "on"));
+        buffer.push(make_message("topic", "on"));
+        buffer.push(make_message("topic", "off"));
+        buffer.push(make_message("topic", "on"));
+
+        let entries = buffer.get_entries("topic");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].repeats, 0);
+        assert_eq!(entries[2].repeats, 2);
+        assert_eq!(buffer.total_stored(), 3);
+        assert_eq!(buffer.total_repeats(), 2);
+
+        // Same payload with a different retain flag is not a repeat
+        buffer.push(MqttMessage::new("topic".into(), b"on".to_vec(), 0, true));
+        assert_eq!(buffer.count_for_topic("topic"), 4);
+
+        buffer.clear_topic("topic");
+        assert_eq!(buffer.total_repeats(), 0);
+    }
+
+    #[test]
+    fn test_topic_limit_override() {
+        let mut buffer = MessageBuffer::new(2);
+        buffer.set_limit("debug", Some(4));
+        buffer.set_limit("all", None);
+        for i in 0..6 {
+            buffer.push(make_message("debug", &i.to_string()));
+            buffer.push(make_message("all", &i.to_string()));
+            buffer.push(make_message("other", &i.to_string()));
+        }
+        assert_eq!(buffer.count_for_topic("debug"), 4);
+        assert_eq!(buffer.count_for_topic("all"), 6);
+        assert_eq!(buffer.count_for_topic("other"), 2);
+        assert_eq!(buffer.limit_for("all"), None);
+
+        // Back to the default drops the oldest
+        buffer.reset_limit("all");
+        assert_eq!(buffer.count_for_topic("all"), 2);
+        assert_eq!(
+            buffer.get_latest("all").unwrap().payload_str().unwrap(),
+            "5"
+        );
+        assert!(!buffer.has_override("all"));
+        assert_eq!(buffer.total_stored(), 8);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut buffer = MessageBuffer::new(10);
+
+        buffer.push(make_message("a", "1"));
+        buffer.push(make_message("b", "2"));
+
+        buffer.clear();
+
+        assert_eq!(buffer.total_stored(), 0);
+        assert_eq!(buffer.topic_count(), 0);
+    }
+}
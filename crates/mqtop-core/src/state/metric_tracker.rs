@@ -0,0 +1,872 @@
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::payload::ParsedPayload;
+
+/// Tracks numeric metrics from JSON payloads over time
+#[derive(Debug)]
+pub struct MetricTracker {
+    /// Tracked metrics by label
+    metrics: HashMap<String, TrackedMetric>,
+    /// Charts comparing several metrics, in creation order
+    charts: Vec<MetricChart>,
+    /// How long history is kept
+    window: Duration,
+    /// Samples closer together than this share a point
+    bucket: Duration,
+}
+
+/// How a tracked metric is shown. `Log` only rescales the sparkline; `Delta`
+/// and `Rate` replace the values, which suits counters that only increase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricDisplay {
+    #[default]
+    Absolute,
+    Log,
+    /// Change since the previous sample
+    Delta,
+    /// Change per second since the previous sample
+    Rate,
+}
+
+impl MetricDisplay {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MetricDisplay::Absolute => "absolute",
+            MetricDisplay::Log => "log",
+            MetricDisplay::Delta => "delta",
+            MetricDisplay::Rate => "rate",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            MetricDisplay::Absolute => MetricDisplay::Log,
+            MetricDisplay::Log => MetricDisplay::Delta,
+            MetricDisplay::Delta => MetricDisplay::Rate,
+            MetricDisplay::Rate => MetricDisplay::Absolute,
+        }
+    }
+
+    /// Whether the shown values differ from the recorded ones
+    pub fn transforms_values(&self) -> bool {
+        matches!(self, MetricDisplay::Delta | MetricDisplay::Rate)
+    }
+}
+
+/// One point of a metric's history: a single sample, or every sample that
+/// arrived within one bucket width, as min, max and sum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricPoint {
+    /// When the first and the latest sample arrived
+    pub first_at: Instant,
+    pub last_at: Instant,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub count: u64,
+    /// Latest sample, for deltas and rates of counters
+    pub last: f64,
+}
+
+impl MetricPoint {
+    pub fn new(value: f64, at: Instant) -> Self {
+        Self {
+            first_at: at,
+            last_at: at,
+            min: value,
+            max: value,
+            sum: value,
+            count: 1,
+            last: value,
+        }
+    }
+
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    fn add(&mut self, value: f64, at: Instant) {
+        self.merge(&MetricPoint::new(value, at));
+    }
+
+    /// Fold in a later point
+    fn merge(&mut self, later: &MetricPoint) {
+        self.last_at = later.last_at;
+        self.min = self.min.min(later.min);
+        self.max = self.max.max(later.max);
+        self.sum += later.sum;
+        self.count += later.count;
+        self.last = later.last;
+    }
+}
+
+#[derive(Debug)]
+pub struct TrackedMetric {
+    /// Display label
+    pub label: String,
+    /// Topic pattern to match
+    pub topic_pattern: String,
+    /// JSON field path (e.g., "W" or "data.power"), or the regex source
+    /// for a text metric
+    pub field_path: String,
+    /// For payloads that aren't JSON: the number is taken from the `value`
+    /// capture group, or else the first one
+    pub regex: Option<Regex>,
+    /// History, oldest first
+    pub data: VecDeque<MetricPoint>,
+    /// Running stats
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub count: u64,
+    /// How values are shown
+    pub display: MetricDisplay,
+    /// Decimal places of shown values, overriding `[ui.numbers]`
+    pub precision: Option<usize>,
+}
+
+impl TrackedMetric {
+    pub fn new(label: String, topic_pattern: String, field_path: String) -> Self {
+        Self {
+            label,
+            topic_pattern,
+            field_path,
+            regex: None,
+            data: VecDeque::new(),
+            min: f64::MAX,
+            max: f64::MIN,
+            sum: 0.0,
+            count: 0,
+            display: MetricDisplay::default(),
+            precision: None,
+        }
+    }
+
+    /// Record a sample, merging it into the latest point when that started
+    /// less than `bucket` ago, and drop history older than `window`
+    pub fn record(&mut self, value: f64, window: Duration, bucket: Duration) {
+        self.record_at(value, Instant::now(), window, bucket);
+    }
+
+    pub fn record_at(&mut self, value: f64, at: Instant, window: Duration, bucket: Duration) {
+        match self.data.back_mut() {
+            Some(point) if at.saturating_duration_since(point.first_at) < bucket => {
+                point.add(value, at)
+            }
+            _ => self.data.push_back(MetricPoint::new(value, at)),
+        }
+        self.expire(at, window);
+
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// A metric read from text payloads such as `OK temp=23.4` with a regex
+    /// like `temp=([-\d.]+)`
+    pub fn with_regex(label: String, topic_pattern: String, pattern: &str) -> Result<Self> {
+        let regex = compile_metric_regex(pattern)?;
+        let mut metric = Self::new(label, topic_pattern, pattern.to_string());
+        metric.regex = Some(regex);
+        Ok(metric)
+    }
+
+    /// The number in a payload, from its JSON field or regex capture
+    fn extract(&self, json: Option<&serde_json::Value>, payload: &[u8]) -> Option<f64> {
+        match &self.regex {
+            Some(regex) => capture_number(regex, std::str::from_utf8(payload).ok()?),
+            None => extract_numeric(json?, &self.field_path),
+        }
+    }
+
+    pub fn avg(&self) -> f64 {
+        if self.count > 0 {
+            self.sum / self.count as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn latest(&self) -> Option<f64> {
+        self.data.back().map(|point| point.last)
+    }
+
+    /// Drop points whose latest sample is older than `window`.
+    /// Returns how many were dropped.
+    pub fn expire(&mut self, now: Instant, window: Duration) -> usize {
+        let before = self.data.len();
+        while self
+            .data
+            .front()
+            .is_some_and(|point| now.saturating_duration_since(point.last_at) > window)
+        {
+            self.data.pop_front();
+        }
+        before - self.data.len()
+    }
+
+    /// The whole history in at most `points` points, merging neighbours
+    /// evenly when there are more
+    pub fn history(&self, points: usize) -> Vec<MetricPoint> {
+        let len = self.data.len();
+        if len <= points {
+            return self.data.iter().copied().collect();
+        }
+        (0..points)
+            .filter_map(|i| {
+                let mut group = self.data.range(i * len / points..(i + 1) * len / points);
+                let mut point = *group.next()?;
+                group.for_each(|later| point.merge(later));
+                Some(point)
+            })
+            .collect()
+    }
+
+    /// Latest value as shown: the recorded value, or the latest delta or rate
+    pub fn display_latest(&self) -> Option<f64> {
+        if self.display.transforms_values() {
+            let skip = self.data.len().saturating_sub(2);
+            let latest: Vec<_> = self.data.iter().skip(skip).copied().collect();
+            self.display_points(&latest).pop().map(|(_, v)| v)
+        } else {
+            self.latest()
+        }
+    }
+
+    /// The whole history as shown in at most `width` values, oldest first,
+    /// for sparkline rendering
+    pub fn sparkline_data(&self, width: usize) -> Vec<f64> {
+        self.shown(width).into_iter().map(|(_, v)| v).collect()
+    }
+
+    /// The whole history as shown in at most `points` points, as seconds
+    /// before `now` (negative) and value, for charts
+    pub fn chart_data(&self, points: usize, now: Instant) -> Vec<(f64, f64)> {
+        self.shown(points)
+            .into_iter()
+            .map(|(at, v)| (-now.saturating_duration_since(at).as_secs_f64(), v))
+            .collect()
+    }
+
+    fn shown(&self, points: usize) -> Vec<(Instant, f64)> {
+        let extra = usize::from(self.display.transforms_values());
+        self.display_points(&self.history(points + extra))
+    }
+
+    /// Point averages, with log values as `log10(1 + |v|)` keeping the sign,
+    /// or the change in latest sample between neighbouring points
+    fn display_points(&self, points: &[MetricPoint]) -> Vec<(Instant, f64)> {
+        let pairs = points.windows(2).map(|pair| (pair[0], pair[1]));
+        match self.display {
+            MetricDisplay::Absolute => points
+                .iter()
+                .map(|point| (point.last_at, point.avg()))
+                .collect(),
+            MetricDisplay::Log => points
+                .iter()
+                .map(|point| {
+                    let v = point.avg();
+                    (point.last_at, v.signum() * (v.abs() + 1.0).log10())
+                })
+                .collect(),
+            MetricDisplay::Delta => pairs.map(|(a, b)| (b.last_at, b.last - a.last)).collect(),
+            MetricDisplay::Rate => pairs
+                .filter_map(|(a, b)| {
+                    let secs = b.last_at.duration_since(a.last_at).as_secs_f64();
+                    (secs > 0.0).then(|| (b.last_at, (b.last - a.last) / secs))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Tracked metrics drawn together on one chart, e.g. the power of three
+/// inverters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricChart {
+    pub name: String,
+    /// Labels of the metrics on the chart, in legend order
+    pub metrics: Vec<String>,
+    /// Scale each series to its own range instead of sharing the y-axis
+    #[serde(default)]
+    pub normalized: bool,
+}
+
+impl MetricTracker {
+    /// Keep `window` of history per metric in about `points` points.
+    /// Samples arriving faster than that are merged into min/max/avg buckets;
+    /// with `points` at 0 every sample is kept.
+    pub fn new(window: Duration, points: usize) -> Self {
+        let bucket = match u32::try_from(points) {
+            Ok(0) => Duration::ZERO,
+            Ok(points) => window / points,
+            Err(_) => Duration::ZERO,
+        };
+        Self {
+            metrics: HashMap::new(),
+            charts: Vec::new(),
+            window,
+            bucket,
+        }
+    }
+
+    /// Add a new metric to track
+    pub fn track(&mut self, label: String, topic_pattern: String, field_path: String) {
+        self.metrics.insert(
+            label.clone(),
+            TrackedMetric::new(label, topic_pattern, field_path),
+        );
+    }
+
+    /// Track a number captured by a regex from text payloads
+    pub fn track_regex(
+        &mut self,
+        label: String,
+        topic_pattern: String,
+        pattern: &str,
+    ) -> Result<()> {
+        let metric = TrackedMetric::with_regex(label.clone(), topic_pattern, pattern)?;
+        self.metrics.insert(label, metric);
+        Ok(())
+    }
+
+    /// Insert a metric with existing history (used when restoring a session)
+    pub fn insert(&mut self, mut metric: TrackedMetric) {
+        metric.expire(Instant::now(), self.window);
+        self.metrics.insert(metric.label.clone(), metric);
+    }
+
+    /// Stop tracking a metric
+    pub fn untrack(&mut self, label: &str) {
+        self.metrics.remove(label);
+        for chart in &mut self.charts {
+            chart.metrics.retain(|metric| metric != label);
+        }
+        self.charts.retain(|chart| !chart.metrics.is_empty());
+    }
+
+    pub fn charts(&self) -> &[MetricChart] {
+        &self.charts
+    }
+
+    /// Add a tracked metric to a chart, creating the chart if needed
+    pub fn add_to_chart(&mut self, chart: &str, label: &str) {
+        if !self.metrics.contains_key(label) {
+            return;
+        }
+        let index = match self.charts.iter().position(|c| c.name == chart) {
+            Some(index) => index,
+            None => {
+                self.charts.push(MetricChart {
+                    name: chart.to_string(),
+                    metrics: Vec::new(),
+                    normalized: false,
+                });
+                self.charts.len() - 1
+            }
+        };
+        let metrics = &mut self.charts[index].metrics;
+        if !metrics.iter().any(|metric| metric == label) {
+            metrics.push(label.to_string());
+        }
+    }
+
+    /// Restore a chart, keeping only metrics that are tracked
+    pub fn insert_chart(&mut self, mut chart: MetricChart) {
+        chart
+            .metrics
+            .retain(|label| self.metrics.contains_key(label));
+        if !chart.metrics.is_empty() {
+            self.charts.retain(|c| c.name != chart.name);
+            self.charts.push(chart);
+        }
+    }
+
+    pub fn remove_chart(&mut self, name: &str) {
+        self.charts.retain(|chart| chart.name != name);
+    }
+
+    /// Switch a chart between a shared y-axis and per-series scaling.
+    /// Returns whether it is now normalized.
+    pub fn toggle_normalized(&mut self, name: &str) -> Option<bool> {
+        let chart = self.charts.iter_mut().find(|chart| chart.name == name)?;
+        chart.normalized = !chart.normalized;
+        Some(chart.normalized)
+    }
+
+    /// Process a message and update any matching metrics.
+    /// Returns the (label, value) pairs recorded.
+    pub fn process_message(&mut self, topic: &str, payload: &[u8]) -> Vec<(String, f64)> {
+        self.process_payload(topic, &ParsedPayload::new(payload))
+    }
+
+    /// Like `process_message`, reusing a payload other trackers may have parsed
+    pub fn process_payload(&mut self, topic: &str, payload: &ParsedPayload) -> Vec<(String, f64)> {
+        let mut recorded = Vec::new();
+        if self.metrics.is_empty() {
+            return recorded;
+        }
+
+        for metric in self.metrics.values_mut() {
+            // Check if topic matches pattern
+            if !topic_matches(&metric.topic_pattern, topic) {
+                continue;
+            }
+
+            // Text metrics don't need the payload parsed
+            let json = match &metric.regex {
+                Some(_) => None,
+                None => payload.json(),
+            };
+            if let Some(value) = metric.extract(json, payload.bytes()) {
+                metric.record(value, self.window, self.bucket);
+                recorded.push((metric.label.clone(), value));
+            }
+        }
+        recorded
+    }
+
+    /// Get all tracked metrics, by label
+    pub fn get_metrics(&self) -> Vec<&TrackedMetric> {
+        let mut metrics: Vec<_> = self.metrics.values().collect();
+        metrics.sort_by(|a, b| a.label.cmp(&b.label));
+        metrics
+    }
+
+    /// Change how a metric is shown
+    pub fn set_display(&mut self, label: &str, display: MetricDisplay) {
+        if let Some(metric) = self.metrics.get_mut(label) {
+            metric.display = display;
+        }
+    }
+
+    pub fn set_precision(&mut self, label: &str, precision: Option<usize>) {
+        if let Some(metric) = self.metrics.get_mut(label) {
+            metric.precision = precision;
+        }
+    }
+
+    /// Get a specific metric
+    pub fn get_metric(&self, label: &str) -> Option<&TrackedMetric> {
+        self.metrics.get(label)
+    }
+
+    /// Drop history older than the window, for metrics that have gone quiet.
+    /// Returns how many points were dropped.
+    pub fn expire(&mut self, now: Instant) -> usize {
+        self.metrics
+            .values_mut()
+            .map(|metric| metric.expire(now, self.window))
+            .sum()
+    }
+
+    /// Data points held across all tracked metrics
+    pub fn point_count(&self) -> usize {
+        self.metrics.values().map(|m| m.data.len()).sum()
+    }
+
+    /// Check if any metrics are being tracked
+    pub fn has_metrics(&self) -> bool {
+        !self.metrics.is_empty()
+    }
+}
+
+/// Check if a topic matches a pattern.
+///
+/// Supports both MQTT and NATS wildcard semantics:
+/// - Single level: `+` (MQTT) or `*` (NATS)
+/// - Multi level: `#` (MQTT) or `>` (NATS)
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    if pattern == "#" || pattern == ">" {
+        return true;
+    }
+
+    let separator = if pattern.contains('/') {
+        '/'
+    } else if pattern.contains('.') {
+        '.'
+    } else if topic.contains('/') {
+        '/'
+    } else if topic.contains('.') {
+        '.'
+    } else {
+        '/'
+    };
+
+    let pattern_parts: Vec<&str> = pattern.split(separator).collect();
+    let topic_parts: Vec<&str> = topic.split(separator).collect();
+
+    let mut pi = 0;
+    let mut ti = 0;
+
+    while pi < pattern_parts.len() && ti < topic_parts.len() {
+        match pattern_parts[pi] {
+            "#" | ">" => return true, // multi-level wildcard matches everything after
+            "+" | "*" => {
+                // + matches single level
+                pi += 1;
+                ti += 1;
+            }
+            p if p == topic_parts[ti] => {
+                pi += 1;
+                ti += 1;
+            }
+            _ => return false,
+        }
+    }
+
+    // Allow trailing multi-level wildcard to match zero segments (e.g. "telemetry/#" vs "telemetry")
+    if pi + 1 == pattern_parts.len() && matches!(pattern_parts[pi], "#" | ">") {
+        return true;
+    }
+
+    pi == pattern_parts.len() && ti == topic_parts.len()
+}
+
+/// Extract a numeric value from JSON using a field path
+fn extract_numeric(json: &serde_json::Value, path: &str) -> Option<f64> {
+    let parts: Vec<&str> = path.split('.').collect();
+    let mut current = json;
+
+    for part in parts {
+        current = current.get(part)?;
+    }
+
+    match current {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Compile a metric regex, which needs a group capturing the number
+pub fn compile_metric_regex(pattern: &str) -> Result<Regex> {
+    let regex = Regex::new(pattern)?;
+    if regex.captures_len() < 2 {
+        bail!(r"Regex needs a capture group around the number, e.g. temp=([-\d.]+)");
+    }
+    Ok(regex)
+}
+
+/// The number a metric regex would record from `text`, or an error if the
+/// regex is invalid
+pub fn capture_value(pattern: &str, text: &str) -> Result<Option<f64>> {
+    Ok(capture_number(&compile_metric_regex(pattern)?, text))
+}
+
+/// A short name for a regex metric: the last word before its capture group,
+/// e.g. `temp` for `temp=(-?\d+)`
+pub fn regex_metric_name(pattern: &str) -> String {
+    let prefix = &pattern[..pattern.find('(').unwrap_or(pattern.len())];
+    prefix
+        .replace(r"\s", " ")
+        .rsplit(|c: char| !c.is_alphanumeric() && c != '_')
+        .find(|word| word.chars().any(|c| c.is_alphabetic()))
+        .unwrap_or("value")
+        .to_string()
+}
+
+fn capture_number(regex: &Regex, text: &str) -> Option<f64> {
+    let captures = regex.captures(text)?;
+    let number = captures.name("value").or_else(|| captures.get(1))?;
+    number.as_str().trim().parse().ok()
+}
+
+/// Regexes for the numbers in a text payload, with the value each captures.
+/// Each is anchored on the text just before its number, e.g. `temp=` in
+/// `OK temp=23.4`, or the previous word when the number stands alone.
+pub fn suggest_regexes(text: &str) -> Vec<(String, f64)> {
+    const NUMBER: &str = r"(-?\d+(?:\.\d+)?)";
+    let numbers = Regex::new(NUMBER).expect("valid number regex");
+    let mut suggestions: Vec<(String, f64)> = Vec::new();
+
+    for found in numbers.find_iter(text) {
+        let before = &text[..found.start()];
+        let token = before
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        let anchor = if !token.is_empty() {
+            regex::escape(token)
+        } else {
+            match before.split_whitespace().last() {
+                Some(word) => format!(r"{}\s+", regex::escape(word)),
+                None => "^\\s*".to_string(),
+            }
+        };
+        let pattern = format!("{}{}", anchor, NUMBER);
+        let Some(value) = Regex::new(&pattern)
+            .ok()
+            .and_then(|regex| capture_number(&regex, text))
+        else {
+            continue;
+        };
+        if !suggestions.iter().any(|(existing, _)| *existing == pattern) {
+            suggestions.push((pattern, value));
+        }
+    }
+    suggestions
+}
+
+/// Get all numeric field paths from a JSON value
+pub fn get_numeric_fields(json: &serde_json::Value) -> Vec<(String, f64)> {
+    let mut fields = Vec::new();
+    collect_numeric_fields(json, "", &mut fields);
+    fields
+}
+
+fn collect_numeric_fields(json: &serde_json::Value, prefix: &str, fields: &mut Vec<(String, f64)>) {
+    match json {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                collect_numeric_fields(value, &path, fields);
+            }
+        }
+        serde_json::Value::Number(n) => {
+            if let Some(v) = n.as_f64() {
+                fields.push((prefix.to_string(), v));
+            }
+        }
+        serde_json::Value::String(s) => {
+            if let Ok(v) = s.parse::<f64>() {
+                fields.push((prefix.to_string(), v));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_matches() {
+        assert!(topic_matches("#", "any/topic/here"));
+        assert!(topic_matches("telemetry/#", "telemetry/device/sensor"));
+        assert!(topic_matches(
+            "telemetry/+/sensor",
+            "telemetry/device1/sensor"
+        ));
+        assert!(!topic_matches(
+            "telemetry/+/sensor",
+            "telemetry/device1/other"
+        ));
+        assert!(topic_matches("exact/match", "exact/match"));
+        assert!(!topic_matches("exact/match", "exact/other"));
+
+        // NATS-style patterns
+        assert!(topic_matches(">", "any.subject.here"));
+        assert!(topic_matches("telemetry.>", "telemetry.device.sensor"));
+        assert!(topic_matches("telemetry.*.sensor", "telemetry.device1.sensor"));
+        assert!(!topic_matches("telemetry.*.sensor", "telemetry.device1.other"));
+        assert!(topic_matches("exact.match", "exact.match"));
+        assert!(!topic_matches("exact.match", "exact.other"));
+    }
+
+    #[test]
+    fn test_extract_numeric() {
+        let json: serde_json::Value = serde_json::json!({
+            "W": 1500,
+            "data": {
+                "power": 1234.5
+            },
+            "string_num": "42.5"
+        });
+
+        assert_eq!(extract_numeric(&json, "W"), Some(1500.0));
+        assert_eq!(extract_numeric(&json, "data.power"), Some(1234.5));
+        assert_eq!(extract_numeric(&json, "string_num"), Some(42.5));
+        assert_eq!(extract_numeric(&json, "nonexistent"), None);
+    }
+
+    #[test]
+    fn test_get_numeric_fields() {
+        let json: serde_json::Value = serde_json::json!({
+            "W": 1500,
+            "V": 230.5,
+            "type": "meter",
+            "data": {
+                "power": 1234
+            }
+        });
+
+        let fields = get_numeric_fields(&json);
+        assert!(fields.iter().any(|(k, _)| k == "W"));
+        assert!(fields.iter().any(|(k, _)| k == "V"));
+        assert!(fields.iter().any(|(k, _)| k == "data.power"));
+        assert!(!fields.iter().any(|(k, _)| k == "type"));
+    }
+
+    fn metric_with(samples: &[(u64, f64)], bucket: Duration) -> (TrackedMetric, Instant) {
+        let start = Instant::now();
+        let mut metric = TrackedMetric::new("t".into(), "a/b".into(), "v".into());
+        for (secs, value) in samples {
+            let at = start + Duration::from_secs(*secs);
+            metric.record_at(*value, at, Duration::from_secs(3600), bucket);
+        }
+        (metric, start)
+    }
+
+    #[test]
+    fn test_fast_samples_share_buckets() {
+        let samples: Vec<_> = (0..100).map(|i| (i, i as f64)).collect();
+        let (metric, _) = metric_with(&samples, Duration::from_secs(10));
+
+        assert_eq!(metric.data.len(), 10);
+        let first = metric.data[0];
+        assert_eq!(
+            (first.min, first.max, first.avg(), first.last),
+            (0.0, 9.0, 4.5, 9.0)
+        );
+        assert_eq!(metric.latest(), Some(99.0));
+        assert_eq!(metric.count, 100);
+    }
+
+    #[test]
+    fn test_old_history_expires() {
+        let samples = [(0, 1.0), (1800, 2.0), (3000, 3.0)];
+        let (mut metric, start) = metric_with(&samples, Duration::ZERO);
+        assert_eq!(metric.data.len(), 3);
+
+        let dropped = metric.expire(start + Duration::from_secs(5000), Duration::from_secs(3600));
+        assert_eq!(dropped, 1);
+        assert_eq!(metric.data.front().unwrap().last, 2.0);
+    }
+
+    #[test]
+    fn test_history_spans_the_window() {
+        let samples = [(0, 5.0), (10, 5.0), (20, 7.5), (30, -2.0)];
+        let (metric, _) = metric_with(&samples, Duration::ZERO);
+        assert_eq!(metric.sparkline_data(10), vec![5.0, 5.0, 7.5, -2.0]);
+        assert_eq!(metric.sparkline_data(2), vec![5.0, 2.75]);
+        let merged = metric.history(2);
+        assert_eq!((merged[1].min, merged[1].max), (-2.0, 7.5));
+    }
+
+    #[test]
+    fn test_display_modes() {
+        let (mut metric, start) =
+            metric_with(&[(0, 100.0), (2, 104.0), (4, 110.0)], Duration::ZERO);
+
+        metric.display = MetricDisplay::Delta;
+        assert_eq!(metric.sparkline_data(5), vec![4.0, 6.0]);
+        assert_eq!(metric.display_latest(), Some(6.0));
+        let now = start + Duration::from_secs(4);
+        assert_eq!(metric.chart_data(5, now), vec![(-2.0, 4.0), (0.0, 6.0)]);
+
+        metric.display = MetricDisplay::Rate;
+        assert_eq!(metric.sparkline_data(5), vec![2.0, 3.0]);
+
+        metric.display = MetricDisplay::Log;
+        assert!((metric.sparkline_data(5)[2] - 111f64.log10()).abs() < 1e-9);
+        assert_eq!(metric.display_latest(), Some(110.0));
+    }
+
+    #[test]
+    fn test_charts_follow_tracked_metrics() {
+        let mut tracker = MetricTracker::new(Duration::from_secs(3600), 360);
+        for label in ["inv1", "inv2"] {
+            tracker.track(label.into(), format!("solar/{}", label), "W".into());
+        }
+        tracker.add_to_chart("Inverters", "inv1");
+        tracker.add_to_chart("Inverters", "inv2");
+        tracker.add_to_chart("Inverters", "inv1");
+        tracker.add_to_chart("Inverters", "missing");
+        assert_eq!(tracker.charts()[0].metrics, ["inv1", "inv2"]);
+        assert_eq!(tracker.toggle_normalized("Inverters"), Some(true));
+
+        tracker.untrack("inv1");
+        assert_eq!(tracker.charts()[0].metrics, ["inv2"]);
+        tracker.untrack("inv2");
+        assert!(tracker.charts().is_empty());
+    }
+
+    #[test]
+    fn test_regex_metrics_read_text_payloads() {
+        let mut tracker = MetricTracker::new(Duration::from_secs(3600), 0);
+        tracker
+            .track_regex("temp".into(), "legacy/+".into(), r"temp=([-\d.]+)")
+            .unwrap();
+        tracker
+            .track_regex("rssi".into(), "legacy/+".into(), r"rssi (?<value>-?\d+)dBm")
+            .unwrap();
+        tracker.track("json".into(), "legacy/+".into(), "temp".into());
+        assert!(tracker
+            .track_regex("bad".into(), "#".into(), r"temp=[-\d.]+")
+            .is_err());
+
+        let recorded = tracker.process_message("legacy/a", b"OK temp=23.4 rssi -71dBm");
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(tracker.get_metric("temp").unwrap().latest(), Some(23.4));
+        assert_eq!(tracker.get_metric("rssi").unwrap().latest(), Some(-71.0));
+
+        tracker.process_message("legacy/a", br#"{"temp": 19.5}"#);
+        assert_eq!(tracker.get_metric("json").unwrap().latest(), Some(19.5));
+    }
+
+    #[test]
+    fn test_suggest_regexes() {
+        let suggestions = suggest_regexes("OK temp=23.4 hum=41 12 V");
+        let patterns: Vec<_> = suggestions.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(
+            patterns,
+            [
+                r"temp=(-?\d+(?:\.\d+)?)",
+                r"hum=(-?\d+(?:\.\d+)?)",
+                r"hum=41\s+(-?\d+(?:\.\d+)?)",
+            ]
+        );
+        let values: Vec<_> = suggestions.iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, [23.4, 41.0, 12.0]);
+        assert_eq!(
+            suggest_regexes("21.5")[0],
+            (r"^\s*(-?\d+(?:\.\d+)?)".to_string(), 21.5)
+        );
+    }
+
+    #[test]
+    fn test_regex_metric_helpers() {
+        assert_eq!(
+            capture_value(r"temp=([\d.]+)", "temp=4.5").unwrap(),
+            Some(4.5)
+        );
+        assert_eq!(capture_value(r"temp=([\d.]+)", "hum=40").unwrap(), None);
+        assert!(capture_value(r"temp=(", "temp=4.5").is_err());
+
+        assert_eq!(regex_metric_name(r"temp=(-?\d+)"), "temp");
+        assert_eq!(regex_metric_name(r"hum=41\s+(-?\d+)"), "hum");
+        assert_eq!(regex_metric_name(r"^\s*(-?\d+)"), "value");
+    }
+
+    #[test]
+    fn test_metric_tracking() {
+        let mut tracker = MetricTracker::new(Duration::from_secs(3600), 360);
+        tracker.track(
+            "Power".to_string(),
+            "telemetry/#".to_string(),
+            "W".to_string(),
+        );
+
+        let payload = br#"{"W": 1500, "V": 230}"#;
+        tracker.process_message("telemetry/device1/meter", payload);
+
+        let metric = tracker.get_metric("Power").unwrap();
+        assert_eq!(metric.latest(), Some(1500.0));
+        assert_eq!(metric.count, 1);
+    }
+}
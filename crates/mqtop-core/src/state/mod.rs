@@ -0,0 +1,42 @@
+pub mod cardinality;
+pub mod clock_offset;
+pub mod device_tracker;
+pub mod latency_tracker;
+pub mod message_buffer;
+pub mod metric_tracker;
+pub mod payload;
+pub mod ping_tracker;
+pub mod response_watcher;
+pub mod schema_tracker;
+pub mod state_tracker;
+pub mod stats;
+pub mod topic_moves;
+pub mod topic_templates;
+pub mod topic_totals;
+pub mod topic_tree;
+pub mod watchdog;
+
+pub use cardinality::CardinalityWatch;
+pub use clock_offset::{parse_timestamp_ms, BROKER_TIME_TOPIC};
+pub use device_tracker::{AvailabilityTopic, DeviceHealth, DeviceTracker, HealthStatus};
+pub use latency_tracker::LatencyTracker;
+pub use message_buffer::{BufferedMessage, MessageBuffer};
+pub use metric_tracker::{
+    capture_value, compile_metric_regex, get_numeric_fields, regex_metric_name, suggest_regexes,
+    MetricChart, MetricDisplay, MetricTracker,
+};
+pub use payload::ParsedPayload;
+pub use ping_tracker::PingTracker;
+pub use response_watcher::{ResponseEvent, ResponseOutcome, ResponseSpec, ResponseWatcher};
+pub use schema_tracker::{SchemaBaseline, SchemaTracker};
+pub use state_tracker::{
+    extract_state, get_state_fields, text_state, StateKind, StateTimeline, StateTracker,
+};
+pub use stats::Stats;
+pub use topic_moves::{looks_like_id, TopicMove, TopicMoveDetector};
+pub use topic_templates::{parameter_name, TopicTemplates};
+pub use topic_totals::{
+    PrefixSummary, PrefixTrend, TopicTotal, TopicTotals, TrafficCount, TrafficSummary,
+};
+pub use topic_tree::{is_system_topic, TopicFlags, TopicInfo, TopicTree, TreeExportFormat};
+pub use watchdog::{format_window, next_watchdog_step, WatchdogMonitor};
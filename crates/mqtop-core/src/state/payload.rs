@@ -0,0 +1,54 @@
+use std::cell::OnceCell;
+
+/// A message payload whose JSON is parsed at most once, on first use, and
+/// shared by every tracker that looks at the message
+#[derive(Debug)]
+pub struct ParsedPayload<'a> {
+    bytes: &'a [u8],
+    json: OnceCell<Option<serde_json::Value>>,
+}
+
+impl<'a> ParsedPayload<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            json: OnceCell::new(),
+        }
+    }
+
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The payload as JSON, or `None` if it isn't valid JSON
+    pub fn json(&self) -> Option<&serde_json::Value> {
+        self.json
+            .get_or_init(|| serde_json::from_slice(self.bytes).ok())
+            .as_ref()
+    }
+
+    /// Whether the payload has been parsed yet
+    pub fn is_parsed(&self) -> bool {
+        self.json.get().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_once_on_demand() {
+        let payload = ParsedPayload::new(br#"{"power": 42}"#);
+        assert!(!payload.is_parsed());
+        let first = payload.json().unwrap() as *const _;
+        assert!(payload.is_parsed());
+        assert_eq!(payload.json().unwrap() as *const _, first);
+        assert_eq!(payload.json().unwrap()["power"], 42);
+
+        let text = ParsedPayload::new(b"ON");
+        assert!(text.json().is_none());
+        assert!(text.is_parsed());
+        assert_eq!(text.bytes(), b"ON");
+    }
+}
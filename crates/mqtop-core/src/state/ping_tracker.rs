@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Round-trip time above which a ping is considered slow
+pub const SLOW_PING_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// Tracks broker keep-alive round-trip times (PINGREQ -> PINGRESP)
+#[derive(Debug)]
+pub struct PingTracker {
+    /// Recent round-trip samples (oldest first)
+    samples: VecDeque<Duration>,
+    /// Max samples to keep
+    max_samples: usize,
+    /// Pings that got no response before the keep-alive deadline
+    pub missed: u64,
+    /// Whether the most recent ping was missed (cleared by the next response)
+    pub last_missed: bool,
+}
+
+impl PingTracker {
+    pub fn new(max_samples: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(max_samples),
+            max_samples,
+            missed: 0,
+            last_missed: false,
+        }
+    }
+
+    /// Record a completed ping round trip
+    pub fn record_rtt(&mut self, rtt: Duration) {
+        if self.samples.len() >= self.max_samples {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rtt);
+        self.last_missed = false;
+    }
+
+    /// Record a ping that timed out without a response
+    pub fn record_missed(&mut self) {
+        self.missed += 1;
+        self.last_missed = true;
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied()
+    }
+
+    pub fn avg(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let total: Duration = self.samples.iter().sum();
+        Some(total / self.samples.len() as u32)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether the latest ping was slow or missed
+    pub fn is_degraded(&self) -> bool {
+        self.last_missed || self.last().is_some_and(|rtt| rtt > SLOW_PING_THRESHOLD)
+    }
+
+    /// Most recent RTTs in milliseconds, for sparkline rendering
+    pub fn sparkline_data(&self, width: usize) -> Vec<f64> {
+        let skip = self.samples.len().saturating_sub(width);
+        self.samples
+            .iter()
+            .skip(skip)
+            .map(|rtt| rtt.as_secs_f64() * 1000.0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_rtt() {
+        let mut tracker = PingTracker::new(3);
+        assert_eq!(tracker.avg(), None);
+
+        tracker.record_rtt(Duration::from_millis(10));
+        tracker.record_rtt(Duration::from_millis(20));
+        tracker.record_rtt(Duration::from_millis(30));
+        tracker.record_rtt(Duration::from_millis(40));
+
+        assert_eq!(tracker.sample_count(), 3);
+        assert_eq!(tracker.last(), Some(Duration::from_millis(40)));
+        assert_eq!(tracker.avg(), Some(Duration::from_millis(30)));
+        assert_eq!(tracker.max(), Some(Duration::from_millis(40)));
+        assert_eq!(tracker.sparkline_data(2), vec![30.0, 40.0]);
+    }
+
+    #[test]
+    fn test_degraded() {
+        let mut tracker = PingTracker::new(10);
+        tracker.record_rtt(Duration::from_millis(20));
+        assert!(!tracker.is_degraded());
+
+        tracker.record_rtt(Duration::from_millis(800));
+        assert!(tracker.is_degraded());
+
+        tracker.record_rtt(Duration::from_millis(20));
+        tracker.record_missed();
+        assert!(tracker.is_degraded());
+        assert_eq!(tracker.missed, 1);
+
+        tracker.record_rtt(Duration::from_millis(20));
+        assert!(!tracker.is_degraded());
+    }
+}
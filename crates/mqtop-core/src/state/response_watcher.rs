@@ -0,0 +1,224 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+
+use super::metric_tracker::topic_matches;
+
+/// Wait used when the response field gives no timeout
+pub const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Response expected for a publish: a topic pattern and how long to wait
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseSpec {
+    pub pattern: String,
+    pub timeout: Duration,
+}
+
+impl ResponseSpec {
+    /// Parse the publish dialog's response field, `PATTERN [TIMEOUT]` such as
+    /// `dev/42/ack 5s`. An empty field means no response is expected.
+    pub fn parse(input: &str) -> Result<Option<Self>> {
+        let mut words = input.split_whitespace();
+        let Some(pattern) = words.next() else {
+            return Ok(None);
+        };
+        let timeout = match words.next() {
+            Some(timeout) => {
+                Duration::from_millis(parse_delay(timeout).context("Response timeout")?)
+            }
+            None => DEFAULT_RESPONSE_TIMEOUT,
+        };
+        if words.next().is_some() {
+            bail!("Use a topic pattern and optional timeout, e.g. dev/42/ack 5s");
+        }
+        if timeout.is_zero() {
+            bail!("Response timeout must be above zero");
+        }
+        Ok(Some(Self {
+            pattern: pattern.to_string(),
+            timeout,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseOutcome {
+    /// First message on a matching topic
+    Received { topic: String },
+    /// Nothing matched within the timeout
+    TimedOut,
+}
+
+/// A resolved watch, reported once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseEvent {
+    /// Number shown next to the publish and its response in the log
+    pub id: u64,
+    pub request_topic: String,
+    pub pattern: String,
+    pub outcome: ResponseOutcome,
+    /// Time since the publish went out
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+struct Watch {
+    id: u64,
+    request_topic: String,
+    spec: ResponseSpec,
+    started: Instant,
+}
+
+/// Publishes waiting for a response, for command/ack style device protocols
+#[derive(Debug, Default)]
+pub struct ResponseWatcher {
+    watches: Vec<Watch>,
+    next_id: u64,
+}
+
+impl ResponseWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start waiting for a response to a publish on `request_topic`
+    pub fn watch(&mut self, request_topic: &str, spec: ResponseSpec, now: Instant) -> u64 {
+        self.next_id += 1;
+        self.watches.push(Watch {
+            id: self.next_id,
+            request_topic: request_topic.to_string(),
+            spec,
+            started: now,
+        });
+        self.next_id
+    }
+
+    /// Resolve every watch whose pattern matches `topic`. The publish's own
+    /// topic is skipped so the broker echoing the request back doesn't count.
+    pub fn on_message(&mut self, topic: &str, now: Instant) -> Vec<ResponseEvent> {
+        let mut events = Vec::new();
+        self.watches.retain(|watch| {
+            let matched = topic != watch.request_topic && topic_matches(&watch.spec.pattern, topic);
+            if matched {
+                events.push(watch.resolve(
+                    ResponseOutcome::Received {
+                        topic: topic.to_string(),
+                    },
+                    now,
+                ));
+            }
+            !matched
+        });
+        events
+    }
+
+    /// Resolve watches that passed their timeout
+    pub fn expire(&mut self, now: Instant) -> Vec<ResponseEvent> {
+        let mut events = Vec::new();
+        self.watches.retain(|watch| {
+            let expired = now.saturating_duration_since(watch.started) >= watch.spec.timeout;
+            if expired {
+                events.push(watch.resolve(ResponseOutcome::TimedOut, now));
+            }
+            !expired
+        });
+        events
+    }
+
+    pub fn pending(&self) -> usize {
+        self.watches.len()
+    }
+}
+
+impl Watch {
+    fn resolve(&self, outcome: ResponseOutcome, now: Instant) -> ResponseEvent {
+        ResponseEvent {
+            id: self.id,
+            request_topic: self.request_topic.clone(),
+            pattern: self.spec.pattern.clone(),
+            outcome,
+            elapsed: now.saturating_duration_since(self.started),
+        }
+    }
+}
+
+/// `250ms`, `2s`, `1.5s` or `1m` in milliseconds
+pub fn parse_delay(input: &str) -> Result<u64> {
+    let split = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let (value, unit) = input.split_at(split);
+    let value: f64 = value
+        .parse()
+        .with_context(|| format!("Bad duration '{}', use e.g. 500ms or 2s", input))?;
+    let scale = match unit {
+        "ms" => 1.0,
+        "" | "s" => 1000.0,
+        "m" => 60_000.0,
+        _ => bail!("Unknown duration unit '{}' (use ms, s or m)", unit),
+    };
+    Ok((value * scale).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(input: &str) -> ResponseSpec {
+        ResponseSpec::parse(input).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(ResponseSpec::parse("  ").unwrap(), None);
+        assert_eq!(spec("dev/+/ack").timeout, DEFAULT_RESPONSE_TIMEOUT);
+        let parsed = spec("dev/42/ack 2.5s");
+        assert_eq!(parsed.pattern, "dev/42/ack");
+        assert_eq!(parsed.timeout, Duration::from_millis(2500));
+        let err = ResponseSpec::parse("dev/42/ack soon").unwrap_err();
+        assert!(format!("{:#}", err)
+            .starts_with("Response timeout: Bad duration 'soon', use e.g. 500ms or 2s"));
+        assert!(ResponseSpec::parse("dev/42/ack 5s extra").is_err());
+        assert!(ResponseSpec::parse("dev/42/ack 0s").is_err());
+    }
+
+    #[test]
+    fn test_first_matching_response() {
+        let mut watcher = ResponseWatcher::new();
+        let start = Instant::now();
+        let id = watcher.watch("dev/42/cmd", spec("dev/42/#"), start);
+
+        // The request echoed back is not its response
+        assert!(watcher.on_message("dev/42/cmd", start).is_empty());
+        assert!(watcher.on_message("dev/7/ack", start).is_empty());
+
+        let later = start + Duration::from_millis(120);
+        let events = watcher.on_message("dev/42/ack", later);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, id);
+        assert_eq!(
+            events[0].outcome,
+            ResponseOutcome::Received {
+                topic: "dev/42/ack".to_string()
+            }
+        );
+        assert_eq!(events[0].elapsed, Duration::from_millis(120));
+
+        // Only the first response is reported
+        assert!(watcher.on_message("dev/42/ack", later).is_empty());
+        assert_eq!(watcher.pending(), 0);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut watcher = ResponseWatcher::new();
+        let start = Instant::now();
+        watcher.watch("dev/42/cmd", spec("dev/42/ack 5s"), start);
+
+        assert!(watcher.expire(start + Duration::from_secs(4)).is_empty());
+        let events = watcher.expire(start + Duration::from_secs(5));
+        assert_eq!(events[0].outcome, ResponseOutcome::TimedOut);
+        assert!(watcher.expire(start + Duration::from_secs(9)).is_empty());
+        assert!(watcher.on_message("dev/42/ack", start).is_empty());
+    }
+}
@@ -1,6 +1,16 @@
 #![allow(dead_code)]
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::payload::ParsedPayload;
+
+/// Baseline file format version, bumped on incompatible changes
+pub const SCHEMA_BASELINE_VERSION: u32 = 1;
 
 /// Tracks JSON schema changes for topics
 #[derive(Debug, Default)]
@@ -11,17 +21,25 @@ pub struct SchemaTracker {
     changes: VecDeque<SchemaChange>,
     /// Max changes to keep
     max_changes: usize,
+    /// Expected schemas loaded from a baseline file
+    baseline: HashMap<String, Schema>,
+    /// Topics whose latest schema differs from the baseline, with the differences
+    drift: BTreeMap<String, Vec<SchemaChange>>,
+    /// Payloads per topic that should have been JSON but failed to parse
+    parse_failures: HashMap<String, u64>,
 }
 
 /// Represents a JSON schema (simplified)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Schema {
     /// Field paths with their types (e.g., "data.power" -> "number")
     pub fields: HashMap<String, FieldType>,
 }
 
 /// Simple field type classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FieldType {
     Null,
     Boolean,
@@ -49,23 +67,77 @@ pub enum ChangeType {
     TypeChanged,
 }
 
+/// Schemas exported from a [`SchemaTracker`], used as the expected shape of
+/// each topic when checking live traffic for drift
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaBaseline {
+    pub version: u32,
+    pub saved_at: DateTime<Utc>,
+    pub schemas: BTreeMap<String, Schema>,
+}
+
+impl SchemaBaseline {
+    /// Default file name for a new baseline, e.g. `mqtop-schema-20240101-120000.json`
+    pub fn default_filename() -> String {
+        format!(
+            "mqtop-schema-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        )
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read schema baseline from {:?}", path))?;
+        let baseline: Self =
+            serde_json::from_str(&contents).context("Failed to parse schema baseline")?;
+
+        if baseline.version > SCHEMA_BASELINE_VERSION {
+            bail!(
+                "Schema baseline version {} is newer than supported version {}",
+                baseline.version,
+                SCHEMA_BASELINE_VERSION
+            );
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(self).context("Failed to serialize schema baseline")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write schema baseline to {:?}", path))
+    }
+}
+
 impl SchemaTracker {
     pub fn new() -> Self {
         Self {
             schemas: HashMap::new(),
             changes: VecDeque::new(),
             max_changes: 50,
+            baseline: HashMap::new(),
+            drift: BTreeMap::new(),
+            parse_failures: HashMap::new(),
         }
     }
 
     /// Process a message and detect schema changes
     pub fn process_message(&mut self, topic: &str, payload: &[u8]) -> Vec<SchemaChange> {
-        let json: serde_json::Value = match serde_json::from_slice(payload) {
-            Ok(v) => v,
-            Err(_) => return Vec::new(),
+        self.process_payload(topic, &ParsedPayload::new(payload))
+    }
+
+    /// Like `process_message`, reusing a payload other trackers may have parsed
+    pub fn process_payload(&mut self, topic: &str, payload: &ParsedPayload) -> Vec<SchemaChange> {
+        let Some(json) = payload.json() else {
+            // Plain-text topics are fine; a JSON topic or JSON-looking payload is not
+            if self.schemas.contains_key(topic) || looks_like_json(payload.bytes()) {
+                *self.parse_failures.entry(topic.to_string()).or_default() += 1;
+            }
+            return Vec::new();
         };
 
-        let new_schema = Schema::from_json(&json);
+        let new_schema = Schema::from_json(json);
         let mut detected_changes = Vec::new();
 
         if let Some(old_schema) = self.schemas.get(topic) {
@@ -81,6 +153,15 @@ impl SchemaTracker {
             }
         }
 
+        if let Some(expected) = self.baseline.get(topic) {
+            let diff = self.compare_schemas(topic, expected, &new_schema);
+            if diff.is_empty() {
+                self.drift.remove(topic);
+            } else {
+                self.drift.insert(topic.to_string(), diff);
+            }
+        }
+
         // Update stored schema
         self.schemas.insert(topic.to_string(), new_schema);
 
@@ -163,6 +244,68 @@ impl SchemaTracker {
     pub fn clear_changes(&mut self) {
         self.changes.clear();
     }
+
+    /// Forget observed schemas and drift, keeping the baseline
+    pub fn clear(&mut self) {
+        self.schemas.clear();
+        self.changes.clear();
+        self.drift.clear();
+        self.parse_failures.clear();
+    }
+
+    /// Snapshot the current schemas for use as a baseline
+    pub fn export_baseline(&self) -> SchemaBaseline {
+        SchemaBaseline {
+            version: SCHEMA_BASELINE_VERSION,
+            saved_at: Utc::now(),
+            schemas: self
+                .schemas
+                .iter()
+                .map(|(topic, schema)| (topic.clone(), schema.clone()))
+                .collect(),
+        }
+    }
+
+    /// Compare future messages against `baseline` instead of only the previous message
+    pub fn set_baseline(&mut self, baseline: SchemaBaseline) {
+        self.baseline = baseline.schemas.into_iter().collect();
+        self.drift.clear();
+    }
+
+    /// Number of topics in the loaded baseline
+    pub fn baseline_len(&self) -> usize {
+        self.baseline.len()
+    }
+
+    /// Topics deviating from the baseline, with their differences
+    pub fn drifted(&self) -> &BTreeMap<String, Vec<SchemaChange>> {
+        &self.drift
+    }
+
+    pub fn is_drifted(&self, topic: &str) -> bool {
+        self.drift.contains_key(topic)
+    }
+
+    /// Payloads on `topic` that failed JSON parsing
+    pub fn parse_failures(&self, topic: &str) -> u64 {
+        self.parse_failures.get(topic).copied().unwrap_or(0)
+    }
+
+    /// Failed payloads and the number of topics they came from
+    pub fn parse_failure_totals(&self) -> (u64, usize) {
+        (
+            self.parse_failures.values().sum(),
+            self.parse_failures.len(),
+        )
+    }
+}
+
+/// Whether a payload starts like a JSON object or array
+fn looks_like_json(payload: &[u8]) -> bool {
+    matches!(
+        payload.iter().find(|b| !b.is_ascii_whitespace()),
+        Some(b'{') | Some(b'[')
+    )
 }
 
 impl Schema {
@@ -303,6 +446,56 @@ mod tests {
         assert_eq!(changes3[0].field_path, "new_field");
     }
 
+    #[test]
+    fn test_baseline_drift() {
+        let mut reference = SchemaTracker::new();
+        reference.process_message("dev/1", br#"{"power": 10, "ok": true}"#);
+        let baseline = reference.export_baseline();
+        let json = serde_json::to_string(&baseline).unwrap();
+        let baseline: SchemaBaseline = serde_json::from_str(&json).unwrap();
+
+        let mut tracker = SchemaTracker::new();
+        tracker.set_baseline(baseline);
+        assert_eq!(tracker.baseline_len(), 1);
+
+        // Field renamed by a firmware update: drift even on the first message
+        tracker.process_message("dev/1", br#"{"power_w": 10, "ok": true}"#);
+        let drift = &tracker.drifted()["dev/1"];
+        assert_eq!(drift.len(), 2);
+        assert!(drift
+            .iter()
+            .any(|c| c.change_type == ChangeType::FieldRemoved && c.field_path == "power"));
+
+        // Unchanged follow-up messages keep the flag
+        tracker.process_message("dev/1", br#"{"power_w": 11, "ok": true}"#);
+        assert!(tracker.is_drifted("dev/1"));
+
+        // Back to the baseline shape clears it; unknown topics are ignored
+        tracker.process_message("dev/1", br#"{"power": 12, "ok": false}"#);
+        tracker.process_message("dev/2", br#"{"other": 1}"#);
+        assert!(tracker.drifted().is_empty());
+    }
+
+    #[test]
+    fn test_parse_failures() {
+        let mut tracker = SchemaTracker::new();
+
+        // Plain text is not a failure
+        tracker.process_message("status", b"online");
+        assert_eq!(tracker.parse_failures("status"), 0);
+
+        // Truncated JSON is, even on a new topic
+        tracker.process_message("dev/1", br#"{"power": 1"#);
+        assert_eq!(tracker.parse_failures("dev/1"), 1);
+
+        // Garbage on a topic that carried JSON before
+        tracker.process_message("dev/2", br#"{"power": 1}"#);
+        tracker.process_message("dev/2", &[0xff, 0x00, 0x12]);
+        assert_eq!(tracker.parse_failures("dev/2"), 1);
+
+        assert_eq!(tracker.parse_failure_totals(), (2, 2));
+    }
+
     #[test]
     fn test_type_change_detection() {
         let mut tracker = SchemaTracker::new();
@@ -0,0 +1,444 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::metric_tracker::topic_matches;
+use super::payload::ParsedPayload;
+
+/// Longer payloads and fields are text, not a state
+const MAX_STATE_LEN: usize = 32;
+
+/// States counted as up in the uptime percentage
+const UP_STATES: [&str; 12] = [
+    "online",
+    "on",
+    "true",
+    "1",
+    "up",
+    "ok",
+    "connected",
+    "running",
+    "active",
+    "healthy",
+    "available",
+    "ready",
+];
+
+/// States counted as down in the uptime percentage
+const DOWN_STATES: [&str; 12] = [
+    "offline",
+    "off",
+    "false",
+    "0",
+    "down",
+    "error",
+    "disconnected",
+    "stopped",
+    "inactive",
+    "failed",
+    "unavailable",
+    "lost",
+];
+
+/// Whether a state means up, down, or neither (e.g. "idle")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateKind {
+    Up,
+    Down,
+    Other,
+}
+
+impl StateKind {
+    pub fn of(state: &str) -> Self {
+        let state = state.to_ascii_lowercase();
+        if UP_STATES.contains(&state.as_str()) {
+            StateKind::Up
+        } else if DOWN_STATES.contains(&state.as_str()) {
+            StateKind::Down
+        } else {
+            StateKind::Other
+        }
+    }
+}
+
+/// A non-numeric value over time, such as a `status` field or the
+/// online/offline of a last-will topic, kept as the times it changed
+#[derive(Debug, Clone)]
+pub struct StateTimeline {
+    /// Display label
+    pub label: String,
+    /// Topic pattern to match
+    pub topic_pattern: String,
+    /// JSON field path, or empty for the whole payload
+    pub field_path: String,
+    /// When each state began, oldest first
+    pub changes: VecDeque<(Instant, String)>,
+    /// Messages seen, whether or not they changed the state
+    pub count: u64,
+}
+
+impl StateTimeline {
+    pub fn new(label: String, topic_pattern: String, field_path: String) -> Self {
+        Self {
+            label,
+            topic_pattern,
+            field_path,
+            changes: VecDeque::new(),
+            count: 0,
+        }
+    }
+
+    pub fn record(&mut self, state: String, window: Duration) {
+        self.record_at(state, Instant::now(), window);
+    }
+
+    pub fn record_at(&mut self, state: String, at: Instant, window: Duration) {
+        self.count += 1;
+        if self.current() != Some(state.as_str()) {
+            self.changes.push_back((at, state));
+        }
+        self.expire(at, window);
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.changes.back().map(|(_, state)| state.as_str())
+    }
+
+    /// Drop changes that ended before the window. The state in force at the
+    /// start of the window is kept, starting there. Returns how many were dropped.
+    pub fn expire(&mut self, now: Instant, window: Duration) -> usize {
+        let Some(start) = now.checked_sub(window) else {
+            return 0;
+        };
+        let mut dropped = 0;
+        while self.changes.len() > 1 && self.changes[1].0 <= start {
+            self.changes.pop_front();
+            dropped += 1;
+        }
+        if let Some((began, _)) = self.changes.front_mut() {
+            *began = (*began).max(start);
+        }
+        dropped
+    }
+
+    /// Time spent in each state from the first change until `now`, longest first
+    pub fn durations(&self, now: Instant) -> Vec<(String, Duration)> {
+        let mut totals: Vec<(String, Duration)> = Vec::new();
+        let ends = self.changes.iter().skip(1).map(|(at, _)| *at).chain([now]);
+        for ((began, state), ended) in self.changes.iter().zip(ends) {
+            let spent = ended.saturating_duration_since(*began);
+            match totals.iter_mut().find(|(s, _)| s == state) {
+                Some((_, total)) => *total += spent,
+                None => totals.push((state.clone(), spent)),
+            }
+        }
+        totals.sort_by_key(|(_, spent)| std::cmp::Reverse(*spent));
+        totals
+    }
+
+    /// Percentage of time spent up, out of the time spent up or down.
+    /// `None` until an up or down state has lasted a moment.
+    pub fn uptime(&self, now: Instant) -> Option<f64> {
+        let mut up = Duration::ZERO;
+        let mut down = Duration::ZERO;
+        for (state, spent) in self.durations(now) {
+            match StateKind::of(&state) {
+                StateKind::Up => up += spent,
+                StateKind::Down => down += spent,
+                StateKind::Other => {}
+            }
+        }
+        let total = up + down;
+        (!total.is_zero()).then(|| up.as_secs_f64() / total.as_secs_f64() * 100.0)
+    }
+
+    /// The state in each of `width` columns from the first change until
+    /// `now`, for drawing a strip
+    pub fn strip(&self, width: usize, now: Instant) -> Vec<&str> {
+        let Some((first, _)) = self.changes.front() else {
+            return Vec::new();
+        };
+        let span = now.saturating_duration_since(*first);
+        let mut changes = self.changes.iter().peekable();
+        let mut state = "";
+        (0..width)
+            .map(|column| {
+                // State at the end of the column's slice of time
+                let at = *first + span.mul_f64((column + 1) as f64 / width as f64);
+                while let Some((_, next)) = changes.next_if(|(began, _)| *began <= at) {
+                    state = next.as_str();
+                }
+                state
+            })
+            .collect()
+    }
+}
+
+/// Tracks state timelines from message payloads
+#[derive(Debug)]
+pub struct StateTracker {
+    /// Timelines by label
+    timelines: HashMap<String, StateTimeline>,
+    /// How long history is kept
+    window: Duration,
+}
+
+impl StateTracker {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            timelines: HashMap::new(),
+            window,
+        }
+    }
+
+    /// Track a JSON field's state, or the whole payload's when `field_path`
+    /// is empty
+    pub fn track(&mut self, label: String, topic_pattern: String, field_path: String) {
+        self.timelines.insert(
+            label.clone(),
+            StateTimeline::new(label, topic_pattern, field_path),
+        );
+    }
+
+    /// Insert a timeline with existing history (used when restoring a session)
+    pub fn insert(&mut self, mut timeline: StateTimeline) {
+        timeline.expire(Instant::now(), self.window);
+        self.timelines.insert(timeline.label.clone(), timeline);
+    }
+
+    pub fn untrack(&mut self, label: &str) {
+        self.timelines.remove(label);
+    }
+
+    /// Record the state of every timeline matching a message
+    pub fn process_message(&mut self, topic: &str, payload: &[u8]) {
+        self.process_payload(topic, &ParsedPayload::new(payload));
+    }
+
+    /// Like `process_message`, reusing a payload other trackers may have parsed
+    pub fn process_payload(&mut self, topic: &str, payload: &ParsedPayload) {
+        for timeline in self.timelines.values_mut() {
+            if !topic_matches(&timeline.topic_pattern, topic) {
+                continue;
+            }
+            let state = extract_state(payload.json(), payload.bytes(), &timeline.field_path);
+            if let Some(state) = state {
+                timeline.record(state, self.window);
+            }
+        }
+    }
+
+    /// All timelines, by label
+    pub fn get_timelines(&self) -> Vec<&StateTimeline> {
+        let mut timelines: Vec<_> = self.timelines.values().collect();
+        timelines.sort_by(|a, b| a.label.cmp(&b.label));
+        timelines
+    }
+
+    pub fn get_timeline(&self, label: &str) -> Option<&StateTimeline> {
+        self.timelines.get(label)
+    }
+
+    /// Drop changes older than the window. Returns how many were dropped.
+    pub fn expire(&mut self, now: Instant) -> usize {
+        self.timelines
+            .values_mut()
+            .map(|timeline| timeline.expire(now, self.window))
+            .sum()
+    }
+
+    /// State changes held across all timelines
+    pub fn change_count(&self) -> usize {
+        self.timelines.values().map(|t| t.changes.len()).sum()
+    }
+
+    pub fn has_states(&self) -> bool {
+        !self.timelines.is_empty()
+    }
+}
+
+/// The state in a payload: a JSON field, or with an empty path the whole
+/// payload, either a JSON scalar or short plain text
+pub fn extract_state(
+    json: Option<&serde_json::Value>,
+    payload: &[u8],
+    path: &str,
+) -> Option<String> {
+    let Some(json) = json else {
+        return path.is_empty().then(|| text_state(payload)).flatten();
+    };
+    let mut current = json;
+    for part in path.split('.').filter(|part| !part.is_empty()) {
+        current = current.get(part)?;
+    }
+    scalar_state(current)
+}
+
+/// A plain-text payload as a state: one short line
+pub fn text_state(payload: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(payload).ok()?.trim();
+    let valid = !text.is_empty() && text.len() <= MAX_STATE_LEN && !text.contains('\n');
+    valid.then(|| text.to_string())
+}
+
+fn scalar_state(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) if !s.is_empty() && s.len() <= MAX_STATE_LEN => {
+            Some(s.clone())
+        }
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Field paths in a JSON value that hold states (booleans and short
+/// non-numeric strings), with their current values. A scalar payload has
+/// an empty path.
+pub fn get_state_fields(json: &serde_json::Value) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    collect_state_fields(json, "", &mut fields);
+    fields
+}
+
+fn collect_state_fields(
+    json: &serde_json::Value,
+    prefix: &str,
+    fields: &mut Vec<(String, String)>,
+) {
+    match json {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                collect_state_fields(value, &path, fields);
+            }
+        }
+        serde_json::Value::String(s) if s.parse::<f64>().is_ok() => {}
+        serde_json::Value::Number(_) if !prefix.is_empty() => {}
+        value => {
+            if let Some(state) = scalar_state(value) {
+                fields.push((prefix.to_string(), state));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_timeline_records_changes_only() {
+        let start = Instant::now();
+        let mut timeline = StateTimeline::new("door".into(), "door".into(), String::new());
+        for (secs, state) in [
+            (0, "online"),
+            (10, "online"),
+            (30, "offline"),
+            (40, "online"),
+        ] {
+            timeline.record_at(state.into(), start + Duration::from_secs(secs), HOUR);
+        }
+
+        assert_eq!(timeline.count, 4);
+        assert_eq!(timeline.changes.len(), 3);
+        assert_eq!(timeline.current(), Some("online"));
+
+        let now = start + Duration::from_secs(100);
+        let durations = timeline.durations(now);
+        assert_eq!(durations[0], ("online".into(), Duration::from_secs(90)));
+        assert_eq!(durations[1], ("offline".into(), Duration::from_secs(10)));
+        assert_eq!(timeline.uptime(now), Some(90.0));
+    }
+
+    #[test]
+    fn test_uptime_ignores_other_states() {
+        let start = Instant::now();
+        let mut timeline = StateTimeline::new("pump".into(), "pump".into(), "status".into());
+        timeline.record_at("idle".into(), start, HOUR);
+        assert_eq!(timeline.uptime(start + Duration::from_secs(10)), None);
+
+        timeline.record_at("running".into(), start + Duration::from_secs(10), HOUR);
+        timeline.record_at("error".into(), start + Duration::from_secs(40), HOUR);
+        let uptime = timeline.uptime(start + Duration::from_secs(50)).unwrap();
+        assert_eq!(uptime, 75.0);
+    }
+
+    #[test]
+    fn test_expire_keeps_state_at_window_start() {
+        let start = Instant::now();
+        let window = Duration::from_secs(60);
+        let mut timeline = StateTimeline::new("door".into(), "door".into(), String::new());
+        timeline.record_at("open".into(), start, window);
+        timeline.record_at("closed".into(), start + Duration::from_secs(10), window);
+        timeline.record_at("open".into(), start + Duration::from_secs(100), window);
+
+        // "closed" began before the window but was still in force at its start
+        assert_eq!(timeline.changes.len(), 2);
+        assert_eq!(timeline.changes[0].0, start + Duration::from_secs(40));
+        assert_eq!(timeline.changes[0].1, "closed");
+    }
+
+    #[test]
+    fn test_strip_spans_history() {
+        let start = Instant::now();
+        let mut timeline = StateTimeline::new("door".into(), "door".into(), String::new());
+        timeline.record_at("on".into(), start, HOUR);
+        timeline.record_at("off".into(), start + Duration::from_secs(50), HOUR);
+
+        let strip = timeline.strip(4, start + Duration::from_secs(100));
+        assert_eq!(strip, ["on", "off", "off", "off"]);
+        assert!(StateTimeline::new("x".into(), "x".into(), String::new())
+            .strip(4, start)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_tracker_reads_fields_and_text() {
+        let mut tracker = StateTracker::new(HOUR);
+        tracker.track("status".into(), "pumps/+/state".into(), "status".into());
+        tracker.track("lwt".into(), "pumps/1/lwt".into(), String::new());
+
+        tracker.process_message("pumps/1/state", br#"{"status":"running","rpm":900}"#);
+        tracker.process_message("pumps/1/lwt", b"offline\n");
+        tracker.process_message("pumps/1/lwt", b"{\"long\":\"json object\"}");
+
+        assert_eq!(
+            tracker.get_timeline("status").unwrap().current(),
+            Some("running")
+        );
+        let lwt = tracker.get_timeline("lwt").unwrap();
+        assert_eq!(lwt.current(), Some("offline"));
+        assert_eq!(lwt.count, 1);
+    }
+
+    #[test]
+    fn test_state_fields() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"status":"running","online":true,"power":"12.5","rpm":900,"meta":{"mode":"auto"}}"#,
+        )
+        .unwrap();
+        let mut fields = get_state_fields(&json);
+        fields.sort();
+        assert_eq!(
+            fields,
+            [
+                ("meta.mode".to_string(), "auto".to_string()),
+                ("online".to_string(), "true".to_string()),
+                ("status".to_string(), "running".to_string()),
+            ]
+        );
+        assert_eq!(
+            get_state_fields(&serde_json::json!(1)),
+            [(String::new(), "1".to_string())]
+        );
+        assert_eq!(StateKind::of("Online"), StateKind::Up);
+        assert_eq!(StateKind::of("OFF"), StateKind::Down);
+        assert_eq!(StateKind::of("idle"), StateKind::Other);
+    }
+}
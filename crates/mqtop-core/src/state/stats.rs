@@ -0,0 +1,491 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Per-prefix bandwidth, bucketed by second for rolling-window rates
+#[derive(Debug, Default)]
+struct PrefixBandwidth {
+    total_bytes: u64,
+    total_messages: u64,
+    /// (seconds since stats start, bytes received in that second)
+    buckets: VecDeque<(u64, u64)>,
+}
+
+/// Bandwidth used by one first-level topic prefix
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixUsage {
+    pub prefix: String,
+    pub bytes_per_second: f64,
+    pub total_bytes: u64,
+    pub total_messages: u64,
+    /// Share of all inbound bytes (0.0 - 1.0)
+    pub share: f64,
+}
+
+/// Tracks message statistics with rolling window calculations
+#[derive(Debug)]
+pub struct Stats {
+    /// Window size for rate calculations
+    window: Duration,
+    /// Timestamps of recent messages (for rate calculation)
+    message_times: VecDeque<Instant>,
+    /// Byte sizes of recent messages
+    message_sizes: VecDeque<usize>,
+    /// Total messages received (all time)
+    total_messages: u64,
+    /// Total bytes received (all time)
+    total_bytes: u64,
+    /// Timestamps and sizes of recent published messages
+    outbound: VecDeque<(Instant, usize)>,
+    /// Total messages published (all time)
+    total_messages_out: u64,
+    /// Total bytes published (all time)
+    total_bytes_out: u64,
+    /// Inbound bandwidth by first-level topic prefix
+    prefixes: HashMap<String, PrefixBandwidth>,
+    /// Inbound messages by topic, bucketed by second like the prefixes
+    topics: HashMap<String, VecDeque<(u64, u64)>>,
+    /// Start time for uptime calculation
+    start_time: Instant,
+}
+
+impl Stats {
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window: Duration::from_secs(window_secs),
+            message_times: VecDeque::new(),
+            message_sizes: VecDeque::new(),
+            total_messages: 0,
+            total_bytes: 0,
+            outbound: VecDeque::new(),
+            total_messages_out: 0,
+            total_bytes_out: 0,
+            prefixes: HashMap::new(),
+            topics: HashMap::new(),
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Record a new message
+    pub fn record_message(&mut self, payload_size: usize) {
+        let now = Instant::now();
+
+        self.message_times.push_back(now);
+        self.message_sizes.push_back(payload_size);
+        self.total_messages += 1;
+        self.total_bytes += payload_size as u64;
+
+        // Prune old entries outside the window
+        self.prune_old_entries(now);
+    }
+
+    /// Attribute an inbound message to its first-level topic prefix
+    pub fn record_prefix(&mut self, prefix: &str, payload_size: usize) {
+        let second = self.start_time.elapsed().as_secs();
+        let window_secs = self.window.as_secs().max(1);
+
+        let entry = self.prefixes.entry(prefix.to_string()).or_default();
+        entry.total_bytes += payload_size as u64;
+        entry.total_messages += 1;
+        add_to_bucket(&mut entry.buckets, second, window_secs, payload_size as u64);
+    }
+
+    /// Count an inbound message towards its topic's current rate
+    pub fn record_topic(&mut self, topic: &str) {
+        let second = self.start_time.elapsed().as_secs();
+        let window_secs = self.window.as_secs().max(1);
+
+        let buckets = match self.topics.get_mut(topic) {
+            Some(buckets) => buckets,
+            None => self.topics.entry(topic.to_string()).or_default(),
+        };
+        add_to_bucket(buckets, second, window_secs, 1);
+    }
+
+    /// The topic receiving the most messages per second over the window,
+    /// with its rate. Ties go to the alphabetically first topic.
+    pub fn busiest_topic(&self) -> Option<(&str, f64)> {
+        let second = self.start_time.elapsed().as_secs();
+        let window_secs = self.window.as_secs().max(1);
+
+        self.topics
+            .iter()
+            .map(|(topic, buckets)| {
+                let count: u64 = buckets
+                    .iter()
+                    .filter(|(bucket, _)| bucket + window_secs > second)
+                    .map(|(_, count)| count)
+                    .sum();
+                (topic.as_str(), count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+            .map(|(topic, count)| (topic, count as f64 / window_secs as f64))
+    }
+
+    /// Record a message published by us
+    pub fn record_outbound(&mut self, payload_size: usize) {
+        let now = Instant::now();
+        self.outbound.push_back((now, payload_size));
+        self.total_messages_out += 1;
+        self.total_bytes_out += payload_size as u64;
+
+        let cutoff = now.checked_sub(self.window).unwrap_or(now);
+        while self.outbound.front().is_some_and(|&(t, _)| t < cutoff) {
+            self.outbound.pop_front();
+        }
+    }
+
+    fn prune_old_entries(&mut self, now: Instant) {
+        let cutoff = now.checked_sub(self.window).unwrap_or(now);
+
+        while let Some(&time) = self.message_times.front() {
+            if time < cutoff {
+                self.message_times.pop_front();
+                self.message_sizes.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Get messages per second (averaged over window)
+    pub fn messages_per_second(&self) -> f64 {
+        self.prune_and_calculate_rate()
+    }
+
+    fn prune_and_calculate_rate(&self) -> f64 {
+        if self.message_times.is_empty() {
+            return 0.0;
+        }
+
+        let now = Instant::now();
+        let cutoff = now.checked_sub(self.window).unwrap_or(now);
+
+        let count = self.message_times.iter().filter(|&&t| t >= cutoff).count();
+
+        count as f64 / self.window.as_secs_f64()
+    }
+
+    /// Get bytes per second (averaged over window)
+    pub fn bytes_per_second(&self) -> f64 {
+        if self.message_times.is_empty() {
+            return 0.0;
+        }
+
+        let now = Instant::now();
+        let cutoff = now.checked_sub(self.window).unwrap_or(now);
+
+        let bytes: usize = self
+            .message_times
+            .iter()
+            .zip(self.message_sizes.iter())
+            .filter(|(&t, _)| t >= cutoff)
+            .map(|(_, &s)| s)
+            .sum();
+
+        bytes as f64 / self.window.as_secs_f64()
+    }
+
+    /// Get published bytes per second (averaged over window)
+    pub fn bytes_out_per_second(&self) -> f64 {
+        let now = Instant::now();
+        let cutoff = now.checked_sub(self.window).unwrap_or(now);
+
+        let bytes: usize = self
+            .outbound
+            .iter()
+            .filter(|(t, _)| *t >= cutoff)
+            .map(|(_, s)| s)
+            .sum();
+
+        bytes as f64 / self.window.as_secs_f64()
+    }
+
+    /// Get total messages published
+    pub fn total_messages_out(&self) -> u64 {
+        self.total_messages_out
+    }
+
+    /// Get total bytes published
+    pub fn total_bytes_out(&self) -> u64 {
+        self.total_bytes_out
+    }
+
+    /// Topic prefixes using the most inbound bandwidth, busiest first.
+    /// Ranked by current rate, then by all-time bytes.
+    pub fn top_prefixes(&self, n: usize) -> Vec<PrefixUsage> {
+        let second = self.start_time.elapsed().as_secs();
+        let window_secs = self.window.as_secs().max(1);
+        let total_bytes = self.prefixes.values().map(|p| p.total_bytes).sum::<u64>();
+
+        let mut usage: Vec<PrefixUsage> = self
+            .prefixes
+            .iter()
+            .map(|(prefix, bw)| {
+                let window_bytes: u64 = bw
+                    .buckets
+                    .iter()
+                    .filter(|(bucket, _)| bucket + window_secs > second)
+                    .map(|(_, bytes)| bytes)
+                    .sum();
+                PrefixUsage {
+                    prefix: prefix.clone(),
+                    bytes_per_second: window_bytes as f64 / window_secs as f64,
+                    total_bytes: bw.total_bytes,
+                    total_messages: bw.total_messages,
+                    share: if total_bytes > 0 {
+                        bw.total_bytes as f64 / total_bytes as f64
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+
+        usage.sort_by(|a, b| {
+            b.bytes_per_second
+                .total_cmp(&a.bytes_per_second)
+                .then(b.total_bytes.cmp(&a.total_bytes))
+                .then(a.prefix.cmp(&b.prefix))
+        });
+        usage.truncate(n);
+        usage
+    }
+
+    /// Get total messages received
+    pub fn total_messages(&self) -> u64 {
+        self.total_messages
+    }
+
+    /// Get total bytes received
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Get uptime
+    pub fn uptime(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Get formatted uptime string
+    pub fn uptime_string(&self) -> String {
+        let duration = self.uptime();
+        let secs = duration.as_secs();
+
+        if secs < 60 {
+            format!("{}s", secs)
+        } else if secs < 3600 {
+            format!("{}m {}s", secs / 60, secs % 60)
+        } else {
+            format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+        }
+    }
+
+    /// Format bytes in human-readable form. Frontends that follow the user's
+    /// number settings use [`NumberFormat::bytes`](crate::numbers::NumberFormat::bytes).
+    pub fn format_bytes(bytes: u64) -> String {
+        const KB: u64 = 1024;
+        const MB: u64 = KB * 1024;
+        const GB: u64 = MB * 1024;
+
+        if bytes >= GB {
+            format!("{:.2} GB", bytes as f64 / GB as f64)
+        } else if bytes >= MB {
+            format!("{:.2} MB", bytes as f64 / MB as f64)
+        } else if bytes >= KB {
+            format!("{:.2} KB", bytes as f64 / KB as f64)
+        } else {
+            format!("{} B", bytes)
+        }
+    }
+
+    /// Format rate in human-readable form. Frontends that follow the user's
+    /// number settings use [`NumberFormat::rate`](crate::numbers::NumberFormat::rate).
+    pub fn format_rate(rate: f64) -> String {
+        if rate >= 1000.0 {
+            format!("{:.1}k/s", rate / 1000.0)
+        } else if rate >= 1.0 {
+            format!("{:.1}/s", rate)
+        } else {
+            format!("{:.2}/s", rate)
+        }
+    }
+
+    /// Restore all-time totals and uptime (used when loading a session).
+    /// The rolling window and per-prefix and per-topic rates start empty, so
+    /// rates read zero until new messages arrive.
+    pub fn restore_totals(&mut self, total_messages: u64, total_bytes: u64, uptime: Duration) {
+        let now = Instant::now();
+        self.message_times.clear();
+        self.message_sizes.clear();
+        self.total_messages = total_messages;
+        self.total_bytes = total_bytes;
+        self.outbound.clear();
+        self.total_messages_out = 0;
+        self.total_bytes_out = 0;
+        self.prefixes.clear();
+        self.topics.clear();
+        self.start_time = now.checked_sub(uptime).unwrap_or(now);
+    }
+
+    /// Reset all statistics
+    pub fn reset(&mut self) {
+        self.message_times.clear();
+        self.message_sizes.clear();
+        self.total_messages = 0;
+        self.total_bytes = 0;
+        self.outbound.clear();
+        self.total_messages_out = 0;
+        self.total_bytes_out = 0;
+        self.prefixes.clear();
+        self.topics.clear();
+        self.start_time = Instant::now();
+    }
+}
+
+/// Add `amount` to the bucket for `second` and drop buckets that left the window
+fn add_to_bucket(buckets: &mut VecDeque<(u64, u64)>, second: u64, window_secs: u64, amount: u64) {
+    match buckets.back_mut() {
+        Some((bucket, total)) if *bucket == second => *total += amount,
+        _ => buckets.push_back((second, amount)),
+    }
+    while buckets
+        .front()
+        .is_some_and(|(bucket, _)| bucket + window_secs <= second)
+    {
+        buckets.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_message() {
+        let mut stats = Stats::new(10);
+
+        stats.record_message(100);
+        stats.record_message(200);
+
+        assert_eq!(stats.total_messages(), 2);
+        assert_eq!(stats.total_bytes(), 300);
+    }
+
+    #[test]
+    fn test_rate_calculation() {
+        let mut stats = Stats::new(1); // 1 second window
+
+        // Record 10 messages
+        for _ in 0..10 {
+            stats.record_message(50);
+        }
+
+        // Rate should be approximately 10/s
+        let rate = stats.messages_per_second();
+        assert!(rate > 0.0, "Rate should be positive");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(Stats::format_bytes(500), "500 B");
+        assert_eq!(Stats::format_bytes(1536), "1.50 KB");
+        assert_eq!(Stats::format_bytes(1_572_864), "1.50 MB");
+        assert_eq!(Stats::format_bytes(1_610_612_736), "1.50 GB");
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(Stats::format_rate(0.5), "0.50/s");
+        assert_eq!(Stats::format_rate(5.5), "5.5/s");
+        assert_eq!(Stats::format_rate(1500.0), "1.5k/s");
+    }
+
+    #[test]
+    fn test_uptime_string() {
+        let stats = Stats::new(10);
+        // Just verify it doesn't panic
+        let uptime = stats.uptime_string();
+        assert!(!uptime.is_empty());
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut stats = Stats::new(10);
+
+        stats.record_message(100);
+        stats.record_message(100);
+
+        assert_eq!(stats.total_messages(), 2);
+
+        stats.reset();
+
+        assert_eq!(stats.total_messages(), 0);
+        assert_eq!(stats.total_bytes(), 0);
+    }
+
+    #[test]
+    fn test_outbound() {
+        let mut stats = Stats::new(10);
+        stats.record_message(100);
+        stats.record_outbound(40);
+        stats.record_outbound(60);
+
+        assert_eq!(stats.total_bytes(), 100);
+        assert_eq!(stats.total_messages_out(), 2);
+        assert_eq!(stats.total_bytes_out(), 100);
+        assert_eq!(stats.bytes_out_per_second(), 10.0);
+    }
+
+    #[test]
+    fn test_top_prefixes() {
+        let mut stats = Stats::new(10);
+        stats.record_prefix("sensors", 300);
+        stats.record_prefix("sensors", 300);
+        stats.record_prefix("alarms", 100);
+        stats.record_prefix("status", 200);
+
+        let top = stats.top_prefixes(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].prefix, "sensors");
+        assert_eq!(top[0].total_bytes, 600);
+        assert_eq!(top[0].total_messages, 2);
+        assert_eq!(top[0].bytes_per_second, 60.0);
+        assert!((top[0].share - 0.6667).abs() < 0.001);
+        assert_eq!(top[1].prefix, "status");
+
+        stats.reset();
+        assert!(stats.top_prefixes(5).is_empty());
+    }
+
+    #[test]
+    fn test_busiest_topic() {
+        let mut stats = Stats::new(10);
+        assert_eq!(stats.busiest_topic(), None);
+
+        stats.record_topic("sensors/b");
+        stats.record_topic("sensors/a");
+        assert_eq!(stats.busiest_topic(), Some(("sensors/a", 0.1)));
+
+        stats.record_topic("sensors/b");
+        assert_eq!(stats.busiest_topic(), Some(("sensors/b", 0.2)));
+
+        stats.reset();
+        assert_eq!(stats.busiest_topic(), None);
+    }
+
+    #[test]
+    fn test_restore_totals() {
+        let mut stats = Stats::new(10);
+        stats.record_message(100);
+        stats.record_topic("sensors/a");
+
+        stats.restore_totals(500, 64_000, Duration::from_secs(120));
+
+        assert_eq!(stats.total_messages(), 500);
+        assert_eq!(stats.total_bytes(), 64_000);
+        assert!(stats.uptime() >= Duration::from_secs(120));
+        assert_eq!(stats.messages_per_second(), 0.0);
+        assert_eq!(stats.busiest_topic(), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Messages on the old topic before it counts as established
+const MIN_OLD_MESSAGES: u64 = 3;
+/// Quiet time before the old topic counts as abandoned, at least; slow
+/// publishers get three of their usual intervals
+const MIN_QUIET: Duration = Duration::from_secs(30);
+/// Moves kept, newest last
+const MAX_MOVES: usize = 50;
+
+/// A device whose traffic moved to a topic under a new prefix, e.g. from
+/// `telemetry/dev-0042/power` to `v2/telemetry/dev-0042/power`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicMove {
+    /// The ID level both topics share
+    pub device_id: String,
+    /// Latest topic under the old prefix
+    pub from: String,
+    /// Topic under the new prefix
+    pub to: String,
+    pub at: Instant,
+}
+
+/// Where one device ID has been seen: the levels before it
+#[derive(Debug)]
+struct Route {
+    prefix: String,
+    /// Latest topic with this prefix
+    topic: String,
+    first_seen: Instant,
+    last_seen: Instant,
+    count: u64,
+}
+
+impl Route {
+    /// Whether the route has been quiet for longer than its own rhythm
+    fn abandoned(&self, now: Instant) -> bool {
+        let gaps = self.count.saturating_sub(1).max(1) as u32;
+        let interval = (self.last_seen - self.first_seen) / gaps;
+        now.saturating_duration_since(self.last_seen) > (interval * 3).max(MIN_QUIET)
+    }
+}
+
+/// Spots topic-scheme migrations, such as a firmware rollout that moves a
+/// device's traffic to new topics: an ID-like level that shows up under a new
+/// prefix after its established prefix went quiet.
+#[derive(Debug, Default)]
+pub struct TopicMoveDetector {
+    /// Prefixes seen per ID-like level
+    routes: HashMap<String, Vec<Route>>,
+    /// Moves found so far, oldest first
+    moves: Vec<TopicMove>,
+}
+
+impl TopicMoveDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a message and return the moves it reveals
+    pub fn process_message(
+        &mut self,
+        topic: &str,
+        separator: char,
+        now: Instant,
+    ) -> Vec<TopicMove> {
+        let levels: Vec<&str> = topic.split(separator).collect();
+        let mut found = Vec::new();
+
+        for (index, level) in levels.iter().enumerate() {
+            if !looks_like_id(level) {
+                continue;
+            }
+            let prefix = levels[..index].join(&separator.to_string());
+            let routes = self.routes.entry(level.to_string()).or_default();
+            let current = match routes.iter().position(|route| route.prefix == prefix) {
+                Some(current) => {
+                    let route = &mut routes[current];
+                    route.last_seen = now;
+                    route.count += 1;
+                    if route.topic != topic {
+                        route.topic = topic.to_string();
+                    }
+                    current
+                }
+                None => {
+                    routes.push(Route {
+                        prefix,
+                        topic: topic.to_string(),
+                        first_seen: now,
+                        last_seen: now,
+                        count: 1,
+                    });
+                    routes.len() - 1
+                }
+            };
+
+            let new = &routes[current];
+            for old in routes.iter().filter(|old| old.prefix != new.prefix) {
+                let moved = old.count >= MIN_OLD_MESSAGES
+                    && old.last_seen < new.first_seen
+                    && old.abandoned(now);
+                let known = self
+                    .moves
+                    .iter()
+                    .any(|m| m.device_id == *level && m.from == old.topic && m.to == new.topic);
+                if moved && !known {
+                    let found_move = TopicMove {
+                        device_id: level.to_string(),
+                        from: old.topic.clone(),
+                        to: new.topic.clone(),
+                        at: now,
+                    };
+                    self.moves.push(found_move.clone());
+                    found.push(found_move);
+                }
+            }
+        }
+
+        let excess = self.moves.len().saturating_sub(MAX_MOVES);
+        self.moves.drain(..excess);
+        found
+    }
+
+    /// Moves found so far, newest first
+    pub fn moves(&self) -> impl Iterator<Item = &TopicMove> {
+        self.moves.iter().rev()
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.moves.len()
+    }
+}
+
+/// Serial numbers, MACs, UUIDs and the like: at least two digits, or long hex
+pub fn looks_like_id(level: &str) -> bool {
+    let digits = level.chars().filter(char::is_ascii_digit).count();
+    let id_chars = level
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'));
+    id_chars && (digits >= 2 || (level.len() >= 8 && level.chars().all(|c| c.is_ascii_hexdigit())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(start: Instant, secs: u64) -> Instant {
+        start + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_detects_move_to_new_prefix() {
+        let start = Instant::now();
+        let mut detector = TopicMoveDetector::new();
+        for i in 0..5 {
+            let at = secs(start, i * 10);
+            assert!(detector
+                .process_message("telemetry/dev-0042/power", '/', at)
+                .is_empty());
+        }
+        // Firmware update: the same device now publishes under v2/
+        assert!(detector
+            .process_message("v2/telemetry/dev-0042/power", '/', secs(start, 60))
+            .is_empty());
+        let moves = detector.process_message("v2/telemetry/dev-0042/power", '/', secs(start, 100));
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].device_id, "dev-0042");
+        assert_eq!(moves[0].from, "telemetry/dev-0042/power");
+        assert_eq!(moves[0].to, "v2/telemetry/dev-0042/power");
+
+        // Flagged once
+        assert!(detector
+            .process_message("v2/telemetry/dev-0042/power", '/', secs(start, 110))
+            .is_empty());
+        assert_eq!(detector.move_count(), 1);
+    }
+
+    #[test]
+    fn test_parallel_topics_are_not_moves() {
+        let start = Instant::now();
+        let mut detector = TopicMoveDetector::new();
+        // Telemetry every 10 s and a status every 10 minutes, both ongoing
+        for i in 0..200 {
+            let at = secs(start, i * 10);
+            detector.process_message("telemetry/dev-0042/power", '/', at);
+            if i % 60 == 0 {
+                detector.process_message("status/dev-0042", '/', at);
+            }
+        }
+        assert_eq!(detector.move_count(), 0);
+    }
+
+    #[test]
+    fn test_slow_publisher_gets_longer_grace() {
+        let start = Instant::now();
+        let mut detector = TopicMoveDetector::new();
+        // Reports every 5 minutes, then a second topic appears in between
+        for i in 0..4 {
+            detector.process_message("meters/mtr-17/energy", '/', secs(start, i * 300));
+        }
+        detector.process_message("meters/v2/mtr-17/energy", '/', secs(start, 960));
+        let moves = detector.process_message("meters/v2/mtr-17/energy", '/', secs(start, 1000));
+        assert!(moves.is_empty());
+        let moves = detector.process_message("meters/v2/mtr-17/energy", '/', secs(start, 1900));
+        assert_eq!(moves.len(), 1);
+    }
+
+    #[test]
+    fn test_looks_like_id() {
+        assert!(looks_like_id("dev-0042"));
+        assert!(looks_like_id("deadbeef"));
+        assert!(!looks_like_id("telemetry"));
+        assert!(!looks_like_id("v2"));
+    }
+}
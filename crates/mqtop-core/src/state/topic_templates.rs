@@ -0,0 +1,175 @@
+use std::collections::{HashMap, HashSet};
+
+use super::topic_moves::looks_like_id;
+
+/// Distinct values a level needs, with the rest of the topic unchanged,
+/// before it counts as a parameter
+const MIN_VARIANTS: usize = 5;
+
+/// Topic templates learned from observed topics, such as
+/// `telemetry/{telemetryId}/meter/{meterId}/json`. A level is a parameter when
+/// it looks like an ID, or when at least five topics differ only in that level.
+#[derive(Debug, Default)]
+pub struct TopicTemplates {
+    /// Template of each topic
+    templates: HashMap<String, String>,
+    /// Topics of each template with parameters
+    instances: HashMap<String, Vec<String>>,
+}
+
+impl TopicTemplates {
+    pub fn learn(topics: &[String], separator: char) -> Self {
+        let split: Vec<Vec<&str>> = topics
+            .iter()
+            .map(|topic| topic.split(separator).collect())
+            .collect();
+        let mut params: Vec<Vec<bool>> = split
+            .iter()
+            .map(|levels| levels.iter().map(|level| looks_like_id(level)).collect())
+            .collect();
+
+        let depth_limit = split.iter().map(Vec::len).max().unwrap_or(0);
+        for depth in 0..depth_limit {
+            // Topics alike except at `depth`, with parameters found so far masked
+            let mut groups: HashMap<Vec<&str>, Vec<usize>> = HashMap::new();
+            for (i, levels) in split.iter().enumerate() {
+                if levels.len() <= depth || params[i][depth] {
+                    continue;
+                }
+                let key = levels
+                    .iter()
+                    .enumerate()
+                    .map(|(d, level)| {
+                        if d == depth || params[i][d] {
+                            ""
+                        } else {
+                            *level
+                        }
+                    })
+                    .collect();
+                groups.entry(key).or_default().push(i);
+            }
+            for members in groups.values() {
+                let variants: HashSet<&str> = members.iter().map(|&i| split[i][depth]).collect();
+                if variants.len() >= MIN_VARIANTS {
+                    for &i in members {
+                        params[i][depth] = true;
+                    }
+                }
+            }
+        }
+
+        let mut learned = Self::default();
+        for ((topic, levels), params) in topics.iter().zip(&split).zip(&params) {
+            let template = template_of(levels, params, separator);
+            if params.contains(&true) {
+                learned
+                    .instances
+                    .entry(template.clone())
+                    .or_default()
+                    .push(topic.clone());
+            }
+            learned.templates.insert(topic.clone(), template);
+        }
+        learned
+    }
+
+    /// The template a topic belongs to; itself if it has no parameters or
+    /// wasn't seen when learning
+    pub fn template_for<'a>(&'a self, topic: &'a str) -> &'a str {
+        self.templates.get(topic).map_or(topic, String::as_str)
+    }
+
+    /// Topics grouped under a template
+    pub fn instances(&self, template: &str) -> &[String] {
+        self.instances.get(template).map_or(&[], Vec::as_slice)
+    }
+
+    /// Templates with parameters
+    pub fn template_count(&self) -> usize {
+        self.instances.len()
+    }
+}
+
+/// Levels with parameters named after the level before them, as in
+/// `sites/{siteId}/meters/{meterId}`
+fn template_of(levels: &[&str], params: &[bool], separator: char) -> String {
+    let mut names: Vec<String> = Vec::new();
+    let templated: Vec<String> = levels
+        .iter()
+        .enumerate()
+        .map(|(depth, level)| {
+            if !params[depth] {
+                return level.to_string();
+            }
+            let previous = match depth.checked_sub(1) {
+                Some(d) if !params[d] => levels[d],
+                _ => "",
+            };
+            let name = parameter_name(previous, &names);
+            names.push(name.clone());
+            format!("{{{}}}", name)
+        })
+        .collect();
+    templated.join(&separator.to_string())
+}
+
+/// Name for a parameter after `previous` (e.g. `meterId` after `meters`),
+/// numbered if `taken` already has it
+pub fn parameter_name(previous: &str, taken: &[String]) -> String {
+    let base = match previous.strip_suffix('s').unwrap_or(previous) {
+        "" => "id".to_string(),
+        singular if singular.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            format!("{}Id", singular)
+        }
+        _ => "id".to_string(),
+    };
+    let mut name = base.clone();
+    let mut n = 2;
+    while taken.contains(&name) {
+        name = format!("{}{}", base, n);
+        n += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_learns_id_and_clustered_levels() {
+        let mut topics = Vec::new();
+        for device in ["dev-0042", "dev-0043", "dev-0044"] {
+            for sensor in ["power", "voltage", "current", "frequency", "energy"] {
+                topics.push(format!("telemetry/{}/meter/{}/json", device, sensor));
+            }
+        }
+        topics.push("status/online".to_string());
+        topics.push("home/kitchen/light".to_string());
+        topics.push("home/kitchen/door".to_string());
+
+        let templates = TopicTemplates::learn(&topics, '/');
+        let template = "telemetry/{telemetryId}/meter/{meterId}/json";
+        assert_eq!(
+            templates.template_for("telemetry/dev-0042/meter/power/json"),
+            template
+        );
+        assert_eq!(templates.instances(template).len(), 15);
+        assert_eq!(templates.template_count(), 1);
+        // Too few siblings to be a parameter
+        assert_eq!(
+            templates.template_for("home/kitchen/light"),
+            "home/kitchen/light"
+        );
+        assert_eq!(templates.template_for("unseen/topic"), "unseen/topic");
+    }
+
+    #[test]
+    fn test_parameter_name() {
+        assert_eq!(parameter_name("meters", &[]), "meterId");
+        assert_eq!(parameter_name("", &[]), "id");
+        assert_eq!(parameter_name("v2-api", &[]), "id");
+        assert_eq!(parameter_name("", &["id".to_string()]), "id2");
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Topic totals file format version, bumped on incompatible changes
+pub const TOPIC_TOTALS_VERSION: u32 = 1;
+/// A prefix's share of traffic must change by this factor to count as grown
+/// or shrunk
+const TREND_FACTOR: f64 = 1.5;
+
+/// Counters for one topic, accumulated across runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicTotal {
+    pub messages: u64,
+    pub bytes: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// One prefix's traffic over a day or period
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrafficCount {
+    pub messages: u64,
+    pub bytes: u64,
+}
+
+impl TrafficCount {
+    fn add(&mut self, other: TrafficCount) {
+        self.messages += other.messages;
+        self.bytes += other.bytes;
+    }
+}
+
+/// How a prefix's share of traffic compares to the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PrefixTrend {
+    /// In the baseline, silent now
+    Vanished,
+    /// Silent in the baseline
+    New,
+    Grew,
+    Shrank,
+    Steady,
+}
+
+impl PrefixTrend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PrefixTrend::Vanished => "vanished",
+            PrefixTrend::New => "new",
+            PrefixTrend::Grew => "grew",
+            PrefixTrend::Shrank => "shrank",
+            PrefixTrend::Steady => "steady",
+        }
+    }
+}
+
+/// One prefix in a [`TrafficSummary`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixSummary {
+    pub prefix: String,
+    /// Traffic in the current period
+    pub current: TrafficCount,
+    /// Average baseline traffic per period of the same length
+    pub baseline: f64,
+    /// Share of all messages, now and in the baseline (0.0 to 1.0)
+    pub share: f64,
+    pub baseline_share: f64,
+    pub trend: PrefixTrend,
+}
+
+/// The latest period's traffic per prefix against the days before it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrafficSummary {
+    /// Days in the current period, ending today
+    pub period_days: u32,
+    /// Baseline days that have saved traffic
+    pub baseline_days: u32,
+    /// Changed prefixes first, then by current traffic
+    pub prefixes: Vec<PrefixSummary>,
+}
+
+/// Per-topic message and byte counters for one server, saved between runs so
+/// "top talkers" rankings cover days of sessions instead of just this one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicTotals {
+    pub version: u32,
+    #[serde(default)]
+    topics: HashMap<String, TopicTotal>,
+    /// Traffic per first-level prefix per local day
+    #[serde(default)]
+    days: BTreeMap<NaiveDate, HashMap<String, TrafficCount>>,
+}
+
+impl Default for TopicTotals {
+    fn default() -> Self {
+        Self {
+            version: TOPIC_TOTALS_VERSION,
+            topics: HashMap::new(),
+            days: BTreeMap::new(),
+        }
+    }
+}
+
+impl TopicTotals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load saved totals; a missing file starts empty
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read topic totals from {:?}", path))?;
+        let totals: Self =
+            serde_json::from_str(&contents).context("Failed to parse topic totals")?;
+
+        if totals.version > TOPIC_TOTALS_VERSION {
+            bail!(
+                "Topic totals version {} is newer than supported version {}",
+                totals.version,
+                TOPIC_TOTALS_VERSION
+            );
+        }
+
+        Ok(totals)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+        let contents = serde_json::to_string(self).context("Failed to serialize topic totals")?;
+        // Write a temp file and rename it over the old one, so a crash midway
+        // can't leave a truncated file that fails to load
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, contents)
+            .with_context(|| format!("Failed to write topic totals to {:?}", temp))?;
+        std::fs::rename(&temp, path)
+            .with_context(|| format!("Failed to replace topic totals at {:?}", path))
+    }
+
+    /// Count a message on `topic`, whose first level is `prefix`
+    pub fn record(&mut self, topic: &str, prefix: &str, bytes: usize, at: DateTime<Utc>) {
+        let day = self
+            .days
+            .entry(at.with_timezone(&Local).date_naive())
+            .or_default();
+        let count = match day.get_mut(prefix) {
+            Some(count) => count,
+            None => day.entry(prefix.to_string()).or_default(),
+        };
+        count.add(TrafficCount {
+            messages: 1,
+            bytes: bytes as u64,
+        });
+
+        match self.topics.get_mut(topic) {
+            Some(total) => {
+                total.messages += 1;
+                total.bytes += bytes as u64;
+                total.last_seen = at;
+            }
+            None => {
+                self.topics.insert(
+                    topic.to_string(),
+                    TopicTotal {
+                        messages: 1,
+                        bytes: bytes as u64,
+                        first_seen: at,
+                        last_seen: at,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Forget topics last seen, and days that ended, before `cutoff`.
+    /// Returns how many topics were dropped.
+    pub fn expire(&mut self, cutoff: DateTime<Utc>) -> usize {
+        let before = self.topics.len();
+        self.topics.retain(|_, total| total.last_seen >= cutoff);
+        let first_day = cutoff.with_timezone(&Local).date_naive();
+        self.days.retain(|day, _| *day >= first_day);
+        before - self.topics.len()
+    }
+
+    /// Compare the `period_days` ending on `today` with the `baseline_days`
+    /// before them, by each prefix's share of messages, so a half-over day
+    /// can still be compared with full ones
+    pub fn summary(
+        &self,
+        today: NaiveDate,
+        period_days: u32,
+        baseline_days: u32,
+    ) -> TrafficSummary {
+        let period_start = today - chrono::Duration::days(i64::from(period_days) - 1);
+        let baseline_start = period_start - chrono::Duration::days(i64::from(baseline_days));
+
+        let mut current: HashMap<&str, TrafficCount> = HashMap::new();
+        let mut baseline: HashMap<&str, TrafficCount> = HashMap::new();
+        let mut saved_baseline_days = 0;
+        for (day, prefixes) in self.days.range(baseline_start..=today) {
+            let target = if *day >= period_start {
+                &mut current
+            } else {
+                saved_baseline_days += 1;
+                &mut baseline
+            };
+            for (prefix, count) in prefixes {
+                target.entry(prefix.as_str()).or_default().add(*count);
+            }
+        }
+
+        let total = |counts: &HashMap<&str, TrafficCount>| -> u64 {
+            counts.values().map(|count| count.messages).sum()
+        };
+        let (current_total, baseline_total) = (total(&current), total(&baseline));
+
+        let mut names: Vec<&str> = current.keys().chain(baseline.keys()).copied().collect();
+        names.sort_unstable();
+        names.dedup();
+        let mut prefixes: Vec<PrefixSummary> = names
+            .into_iter()
+            .map(|prefix| {
+                let now = current.get(prefix).copied().unwrap_or_default();
+                let before = baseline.get(prefix).map_or(0, |count| count.messages);
+                let share = share_of(now.messages, current_total);
+                let baseline_share = share_of(before, baseline_total);
+                let trend = if saved_baseline_days == 0 {
+                    PrefixTrend::Steady
+                } else if now.messages == 0 {
+                    PrefixTrend::Vanished
+                } else if before == 0 {
+                    PrefixTrend::New
+                } else if share >= baseline_share * TREND_FACTOR {
+                    PrefixTrend::Grew
+                } else if share * TREND_FACTOR <= baseline_share {
+                    PrefixTrend::Shrank
+                } else {
+                    PrefixTrend::Steady
+                };
+                PrefixSummary {
+                    prefix: prefix.to_string(),
+                    current: now,
+                    baseline: if saved_baseline_days == 0 {
+                        0.0
+                    } else {
+                        before as f64 * f64::from(period_days) / f64::from(saved_baseline_days)
+                    },
+                    share,
+                    baseline_share,
+                    trend,
+                }
+            })
+            .collect();
+        prefixes.sort_by(|a, b| {
+            a.trend
+                .cmp(&b.trend)
+                .then_with(|| b.current.messages.cmp(&a.current.messages))
+                .then_with(|| a.prefix.cmp(&b.prefix))
+        });
+
+        TrafficSummary {
+            period_days,
+            baseline_days: saved_baseline_days,
+            prefixes,
+        }
+    }
+
+    /// The `limit` topics with the most messages, busiest first
+    pub fn top(&self, limit: usize) -> Vec<(&str, &TopicTotal)> {
+        let mut top: Vec<_> = self
+            .topics
+            .iter()
+            .map(|(topic, total)| (topic.as_str(), total))
+            .collect();
+        top.sort_by(|a, b| b.1.messages.cmp(&a.1.messages).then_with(|| a.0.cmp(b.0)));
+        top.truncate(limit);
+        top
+    }
+
+    /// When the oldest counter started
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        self.topics.values().map(|total| total.first_seen).min()
+    }
+
+    pub fn len(&self) -> usize {
+        self.topics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.topics.is_empty()
+    }
+}
+
+fn share_of(messages: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        messages as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_ranks_and_expires() {
+        let start = Utc::now();
+        let mut totals = TopicTotals::new();
+        for _ in 0..3 {
+            totals.record("meters/1/power", "meters", 10, start);
+        }
+        totals.record("meters/2/power", "meters", 500, start + Duration::days(2));
+        totals.record("old/topic", "old", 1, start - Duration::days(40));
+
+        let top = totals.top(2);
+        assert_eq!(top[0].0, "meters/1/power");
+        assert_eq!(top[0].1.messages, 3);
+        assert_eq!(top[0].1.bytes, 30);
+        assert_eq!(top[1].0, "meters/2/power");
+        assert_eq!(totals.since(), Some(start - Duration::days(40)));
+
+        assert_eq!(totals.expire(start - Duration::days(30)), 1);
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals.since(), Some(start));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "mqtop-topic-totals-{}/server.json",
+            std::process::id()
+        ));
+        assert!(TopicTotals::load_from(&path).unwrap().is_empty());
+
+        let mut totals = TopicTotals::new();
+        totals.record("a/b", "a", 4, Utc::now());
+        totals.save_to(&path).unwrap();
+        let loaded = TopicTotals::load_from(&path).unwrap();
+        assert_eq!(loaded.top(1)[0].1, totals.top(1)[0].1);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_summary_flags_changed_prefixes() {
+        let today = Local::now().date_naive();
+        let noon = |days_ago: i64| {
+            (today - Duration::days(days_ago))
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let mut totals = TopicTotals::new();
+        let mut send = |topic: &str, count: usize, days_ago: i64| {
+            let prefix = topic.split('/').next().unwrap();
+            for _ in 0..count {
+                totals.record(topic, prefix, 10, noon(days_ago));
+            }
+        };
+        // A week of steady meters, alarms and a legacy bridge
+        for days_ago in 1..=7 {
+            send("meters/1/power", 80, days_ago);
+            send("alarms/1", 10, days_ago);
+            send("legacy/bridge", 10, days_ago);
+        }
+        // Today the bridge is gone and alarms have tripled
+        send("meters/1/power", 40, 0);
+        send("alarms/1", 20, 0);
+        send("debug/dump", 5, 0);
+
+        let summary = totals.summary(today, 1, 7);
+        assert_eq!(summary.baseline_days, 7);
+        let trend = |prefix: &str| {
+            let found = summary
+                .prefixes
+                .iter()
+                .find(|p| p.prefix == prefix)
+                .unwrap();
+            found.trend
+        };
+        assert_eq!(trend("legacy"), PrefixTrend::Vanished);
+        assert_eq!(trend("debug"), PrefixTrend::New);
+        assert_eq!(trend("alarms"), PrefixTrend::Grew);
+        assert_eq!(trend("meters"), PrefixTrend::Steady);
+        assert_eq!(summary.prefixes[0].prefix, "legacy");
+        assert_eq!(summary.prefixes[0].baseline, 10.0);
+
+        // Without earlier days there is nothing to compare with
+        let weekly = totals.summary(today, 8, 7);
+        assert_eq!(weekly.baseline_days, 0);
+        assert!(weekly
+            .prefixes
+            .iter()
+            .all(|p| p.trend == PrefixTrend::Steady));
+    }
+}
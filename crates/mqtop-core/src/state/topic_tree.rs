@@ -0,0 +1,1103 @@
+#![allow(dead_code)]
+
+use std::collections::{HashMap, HashSet};
+
+use super::topic_templates::TopicTemplates;
+
+/// A trie-based data structure for storing hierarchical topics efficiently.
+/// Provides O(k) lookup where k is the number of topic levels.
+#[derive(Debug)]
+pub struct TopicTree {
+    root: TopicNode,
+    total_topics: usize,
+    separator: char,
+}
+
+#[derive(Debug, Default)]
+struct TopicNode {
+    /// Child nodes keyed by topic segment
+    children: HashMap<String, TopicNode>,
+    /// Whether this node represents a complete topic (has received messages)
+    is_topic: bool,
+    /// Message count for this topic
+    message_count: u64,
+    /// Total bytes received on this topic
+    bytes_received: u64,
+    /// Last message timestamp (unix millis)
+    last_message_time: Option<i64>,
+    /// Distinct topic levels folded into this node by [`TopicTree::grouped`]
+    instances: usize,
+    flags: TopicFlags,
+}
+
+/// Delivery properties seen on one topic's messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TopicFlags {
+    /// Messages that arrived with the retain flag
+    pub retained: u64,
+    /// Messages redelivered with the DUP flag
+    pub duplicates: u64,
+    /// Highest QoS received
+    pub max_qos: u8,
+}
+
+impl TopicFlags {
+    fn merge(&mut self, other: TopicFlags) {
+        self.retained += other.retained;
+        self.duplicates += other.duplicates;
+        self.max_qos = self.max_qos.max(other.max_qos);
+    }
+}
+
+/// Output format for [`TopicTree::export`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeExportFormat {
+    /// Indented text outline
+    Text,
+    /// Nested JSON objects
+    Json,
+    /// Graphviz DOT graph
+    Dot,
+}
+
+impl TreeExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TreeExportFormat::Text => "txt",
+            TreeExportFormat::Json => "json",
+            TreeExportFormat::Dot => "dot",
+        }
+    }
+}
+
+/// Represents a topic in the tree for display
+#[derive(Debug, Clone)]
+pub struct TopicInfo {
+    pub full_path: String,
+    pub segment: String,
+    pub depth: usize,
+    pub is_expanded: bool,
+    pub has_children: bool,
+    pub message_count: u64,
+    pub bytes_received: u64,
+    pub last_message_time: Option<i64>,
+    /// Most recent message time in this topic or any topic below it
+    pub latest_activity: Option<i64>,
+    /// Levels folded into this template parameter in a grouped tree, else 0
+    pub instances: usize,
+    pub flags: TopicFlags,
+}
+
+impl TopicInfo {
+    /// Every message on this topic was a retained one
+    pub fn retained_only(&self) -> bool {
+        self.message_count > 0 && self.flags.retained >= self.message_count
+    }
+}
+
+impl TopicNode {
+    fn topic_count(&self) -> usize {
+        usize::from(self.is_topic)
+            + self
+                .children
+                .values()
+                .map(TopicNode::topic_count)
+                .sum::<usize>()
+    }
+
+    fn latest_activity(&self) -> Option<i64> {
+        self.children
+            .values()
+            .filter_map(|child| child.latest_activity())
+            .chain(self.last_message_time)
+            .max()
+    }
+
+    /// Zero the counters of this topic, and of every topic below it with
+    /// `subtree`, collecting the paths reset
+    fn reset_counters(
+        &mut self,
+        path: &str,
+        separator: char,
+        subtree: bool,
+        reset: &mut Vec<String>,
+    ) {
+        if self.is_topic {
+            self.message_count = 0;
+            self.bytes_received = 0;
+            self.flags = TopicFlags::default();
+            reset.push(path.to_string());
+        }
+        if subtree {
+            for (segment, child) in &mut self.children {
+                let full_path = format!("{}{}{}", path, separator, segment);
+                child.reset_counters(&full_path, separator, true, reset);
+            }
+        }
+    }
+
+    /// Remove topics last seen before `cutoff` and branches left empty,
+    /// collecting the removed topic paths
+    fn prune(&mut self, path: &str, separator: char, cutoff: i64, removed: &mut Vec<String>) {
+        self.children.retain(|segment, child| {
+            let full_path = if path.is_empty() {
+                segment.clone()
+            } else {
+                format!("{}{}{}", path, separator, segment)
+            };
+
+            child.prune(&full_path, separator, cutoff, removed);
+
+            if child.is_topic && child.last_message_time.is_some_and(|t| t < cutoff) {
+                child.is_topic = false;
+                child.message_count = 0;
+                child.bytes_received = 0;
+                child.last_message_time = None;
+                child.flags = TopicFlags::default();
+                removed.push(full_path);
+            }
+
+            child.is_topic || !child.children.is_empty()
+        });
+    }
+}
+
+impl TopicTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_separator(separator: char) -> Self {
+        Self {
+            separator,
+            ..Self::default()
+        }
+    }
+
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    /// Insert or update a topic in the tree.
+    /// Returns the branch that gained a child, if the topic added one.
+    pub fn insert(&mut self, topic: &str, payload_size: usize) -> Option<String> {
+        let segments: Vec<&str> = topic.split(self.separator).collect();
+        let mut current = &mut self.root;
+        let mut grown = None;
+
+        for (depth, segment) in segments.iter().enumerate() {
+            if grown.is_none() && !current.children.contains_key(*segment) {
+                grown = Some(segments[..depth].join(&self.separator.to_string()));
+            }
+            current = current.children.entry(segment.to_string()).or_default();
+        }
+
+        if !current.is_topic {
+            current.is_topic = true;
+            self.total_topics += 1;
+        }
+
+        current.message_count += 1;
+        current.bytes_received += payload_size as u64;
+        current.last_message_time = Some(chrono::Utc::now().timestamp_millis());
+        grown
+    }
+
+    /// Note the retain and DUP flags and QoS of a message on an inserted topic
+    pub fn record_flags(&mut self, topic: &str, qos: u8, retain: bool, dup: bool) {
+        if let Some(node) = self.find_node_mut(topic) {
+            node.flags.retained += u64::from(retain);
+            node.flags.duplicates += u64::from(dup);
+            node.flags.max_qos = node.flags.max_qos.max(qos);
+        }
+    }
+
+    /// Delivery flags seen on a topic
+    pub fn topic_flags(&self, topic: &str) -> Option<TopicFlags> {
+        self.find_node(topic)
+            .filter(|node| node.is_topic)
+            .map(|node| node.flags)
+    }
+
+    /// Insert a topic with previously recorded counters (used when restoring a session)
+    pub fn restore_topic(
+        &mut self,
+        topic: &str,
+        message_count: u64,
+        bytes_received: u64,
+        last_message_time: Option<i64>,
+    ) {
+        let mut current = &mut self.root;
+        for segment in topic.split(self.separator) {
+            current = current.children.entry(segment.to_string()).or_default();
+        }
+
+        if !current.is_topic {
+            current.is_topic = true;
+            self.total_topics += 1;
+        }
+
+        current.message_count = message_count;
+        current.bytes_received = bytes_received;
+        current.last_message_time = last_message_time;
+    }
+
+    /// A copy with every topic folded into its template, so the instances of
+    /// `sites/{siteId}/power` share one node with their combined counters
+    pub fn grouped(&self, templates: &TopicTemplates) -> TopicTree {
+        let separator = self.separator.to_string();
+        let mut grouped = TopicTree::with_separator(self.separator);
+        let mut variants: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for topic in self.get_all_topics() {
+            let Some(node) = self.find_node(&topic) else {
+                continue;
+            };
+            let template = templates.template_for(&topic);
+            let levels: Vec<&str> = topic.split(self.separator).collect();
+            let mut current = &mut grouped.root;
+            let mut path = Vec::new();
+            for (depth, segment) in template.split(self.separator).enumerate() {
+                path.push(segment);
+                variants
+                    .entry(path.join(&separator))
+                    .or_default()
+                    .insert(levels[..=depth].join(&separator));
+                current = current.children.entry(segment.to_string()).or_default();
+            }
+
+            if !current.is_topic {
+                current.is_topic = true;
+                grouped.total_topics += 1;
+            }
+            current.message_count += node.message_count;
+            current.bytes_received += node.bytes_received;
+            current.last_message_time = current.last_message_time.max(node.last_message_time);
+            current.flags.merge(node.flags);
+        }
+
+        for (path, paths) in variants {
+            if let Some(node) = grouped.find_node_mut(&path) {
+                node.instances = paths.len();
+            }
+        }
+        grouped
+    }
+
+    /// Get the total number of unique topics
+    pub fn topic_count(&self) -> usize {
+        self.total_topics
+    }
+
+    /// Topics under `$` roots such as `$SYS`, which brokers publish about
+    /// themselves
+    pub fn system_topic_count(&self) -> usize {
+        self.root
+            .children
+            .iter()
+            .filter(|(segment, _)| is_system_topic(segment))
+            .map(|(_, child)| child.topic_count())
+            .sum()
+    }
+
+    /// Get total message count across all topics
+    pub fn total_messages(&self) -> u64 {
+        self.count_messages(&self.root)
+    }
+
+    fn count_messages(&self, node: &TopicNode) -> u64 {
+        let mut count = node.message_count;
+        for child in node.children.values() {
+            count += self.count_messages(child);
+        }
+        count
+    }
+
+    /// Get flattened list of topics for display (respecting expanded state)
+    pub fn get_visible_topics(
+        &self,
+        expanded: &std::collections::HashSet<String>,
+    ) -> Vec<TopicInfo> {
+        let mut result = Vec::new();
+        self.collect_visible(&self.root, "", 0, expanded, &mut result);
+        result
+    }
+
+    fn collect_visible(
+        &self,
+        node: &TopicNode,
+        path: &str,
+        depth: usize,
+        expanded: &std::collections::HashSet<String>,
+        result: &mut Vec<TopicInfo>,
+    ) {
+        // Sort children for consistent display, `$` roots after application topics
+        let mut children: Vec<_> = node.children.iter().collect();
+        children.sort_by(|a, b| {
+            (depth == 0 && is_system_topic(a.0))
+                .cmp(&(depth == 0 && is_system_topic(b.0)))
+                .then_with(|| a.0.cmp(b.0))
+        });
+
+        for (segment, child) in children {
+            let full_path = if path.is_empty() {
+                segment.clone()
+            } else {
+                format!("{}{}{}", path, self.separator, segment)
+            };
+
+            let is_expanded = expanded.contains(&full_path);
+            let has_children = !child.children.is_empty();
+
+            result.push(TopicInfo {
+                full_path: full_path.clone(),
+                segment: segment.clone(),
+                depth,
+                is_expanded,
+                has_children,
+                message_count: child.message_count,
+                bytes_received: child.bytes_received,
+                last_message_time: child.last_message_time,
+                latest_activity: child.latest_activity(),
+                instances: child.instances,
+                flags: child.flags,
+            });
+
+            // Only recurse if expanded
+            if is_expanded {
+                self.collect_visible(child, &full_path, depth + 1, expanded, result);
+            }
+        }
+    }
+
+    /// Zero the message and byte counters of a topic, or with `subtree` of
+    /// every topic under it too. Returns the topics reset.
+    pub fn reset_counters(&mut self, topic: &str, subtree: bool) -> Vec<String> {
+        let separator = self.separator;
+        let mut reset = Vec::new();
+        if let Some(node) = self.find_node_mut(topic) {
+            node.reset_counters(topic, separator, subtree, &mut reset);
+        }
+        reset
+    }
+
+    /// Remove topics with no messages since `cutoff` (unix millis), returning their paths.
+    /// Topics without a recorded time (e.g. restored from a session) are kept.
+    pub fn prune_inactive(&mut self, cutoff: i64) -> Vec<String> {
+        let mut removed = Vec::new();
+        self.root.prune("", self.separator, cutoff, &mut removed);
+        self.total_topics = self.total_topics.saturating_sub(removed.len());
+        removed
+    }
+
+    /// Get all topics matching a pattern (simple glob with *)
+    pub fn search(&self, pattern: &str) -> Vec<String> {
+        let mut results = Vec::new();
+        self.search_recursive(
+            &self.root,
+            "",
+            pattern.to_lowercase().as_str(),
+            &mut results,
+        );
+        results
+    }
+
+    fn search_recursive(
+        &self,
+        node: &TopicNode,
+        path: &str,
+        pattern: &str,
+        results: &mut Vec<String>,
+    ) {
+        for (segment, child) in &node.children {
+            let full_path = if path.is_empty() {
+                segment.clone()
+            } else {
+                format!("{}{}{}", path, self.separator, segment)
+            };
+
+            // Simple substring match (case-insensitive)
+            if full_path.to_lowercase().contains(pattern) && child.is_topic {
+                results.push(full_path.clone());
+            }
+
+            self.search_recursive(child, &full_path, pattern, results);
+        }
+    }
+
+    /// Get stats for a specific topic
+    pub fn get_topic_stats(&self, topic: &str) -> Option<(u64, u64, Option<i64>)> {
+        let current = self.find_node(topic)?;
+
+        if current.is_topic {
+            Some((
+                current.message_count,
+                current.bytes_received,
+                current.last_message_time,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Get all expandable paths under a topic (including itself)
+    pub fn expandable_paths_from(&self, topic: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let Some(node) = self.find_node(topic) else {
+            return result;
+        };
+
+        if !topic.is_empty() && !node.children.is_empty() {
+            result.push(topic.to_string());
+        }
+
+        self.collect_expandable(node, topic, &mut result);
+        result
+    }
+
+    fn find_node(&self, topic: &str) -> Option<&TopicNode> {
+        let mut current = &self.root;
+        if topic.is_empty() {
+            return Some(current);
+        }
+
+        for segment in topic.split(self.separator) {
+            current = current.children.get(segment)?;
+        }
+
+        Some(current)
+    }
+
+    fn find_node_mut(&mut self, topic: &str) -> Option<&mut TopicNode> {
+        let mut current = &mut self.root;
+        for segment in topic.split(self.separator) {
+            current = current.children.get_mut(segment)?;
+        }
+        Some(current)
+    }
+
+    fn collect_expandable(&self, node: &TopicNode, path: &str, result: &mut Vec<String>) {
+        for (segment, child) in &node.children {
+            let full_path = if path.is_empty() {
+                segment.clone()
+            } else {
+                format!("{}{}{}", path, self.separator, segment)
+            };
+
+            if !child.children.is_empty() {
+                result.push(full_path.clone());
+                self.collect_expandable(child, &full_path, result);
+            }
+        }
+    }
+
+    /// Completions for a partly typed topic: children of the typed parent path
+    /// whose name starts with the last segment, sorted. A `wildcard` segment in
+    /// the parent matches any level. Completions with children below them end
+    /// in the separator so the next Tab continues down the tree.
+    pub fn complete(&self, input: &str, wildcard: char) -> Vec<String> {
+        let (parent, partial) = match input.rfind(self.separator) {
+            Some(idx) => (Some(&input[..idx]), &input[idx + 1..]),
+            None => (None, input),
+        };
+
+        let mut nodes = vec![&self.root];
+        for segment in parent.into_iter().flat_map(|p| p.split(self.separator)) {
+            nodes = if segment.len() == 1 && segment.starts_with(wildcard) {
+                nodes
+                    .iter()
+                    .flat_map(|node| node.children.values())
+                    .collect()
+            } else {
+                nodes
+                    .iter()
+                    .filter_map(|node| node.children.get(segment))
+                    .collect()
+            };
+        }
+
+        let prefix = parent.map_or(String::new(), |p| format!("{}{}", p, self.separator));
+        let mut completions: Vec<String> = nodes
+            .iter()
+            .flat_map(|node| &node.children)
+            .filter(|(segment, _)| segment.starts_with(partial))
+            .map(|(segment, child)| {
+                if child.children.is_empty() {
+                    format!("{}{}", prefix, segment)
+                } else {
+                    format!("{}{}{}", prefix, segment, self.separator)
+                }
+            })
+            .collect();
+        completions.sort();
+        completions.dedup();
+        completions
+    }
+
+    /// Get all topic paths in the tree (sorted alphabetically), regardless of UI expansion state
+    pub fn get_all_topics(&self) -> Vec<String> {
+        let mut result = Vec::new();
+        self.collect_all_topics(&self.root, "", &mut result);
+        result.sort();
+        result
+    }
+
+    fn collect_all_topics(&self, node: &TopicNode, path: &str, result: &mut Vec<String>) {
+        for (segment, child) in &node.children {
+            let full_path = if path.is_empty() {
+                segment.clone()
+            } else {
+                format!("{}{}{}", path, self.separator, segment)
+            };
+
+            if child.is_topic {
+                result.push(full_path.clone());
+            }
+
+            self.collect_all_topics(child, &full_path, result);
+        }
+    }
+
+    /// Export the hierarchy with per-topic stats.
+    /// Only topics accepted by `include` (and the branches leading to them) are written.
+    pub fn export(&self, format: TreeExportFormat, include: &dyn Fn(&str) -> bool) -> String {
+        match format {
+            TreeExportFormat::Text => {
+                let mut output = String::new();
+                self.export_text(&self.root, "", 0, include, &mut output);
+                output
+            }
+            TreeExportFormat::Json => {
+                serde_json::to_string_pretty(&self.export_value(include)).unwrap_or_default()
+            }
+            TreeExportFormat::Dot => {
+                let mut output = String::from(
+                    "digraph topics {\n  rankdir=LR;\n  node [shape=box, fontname=\"monospace\"];\n",
+                );
+                self.export_dot(&self.root, "", include, &mut output);
+                output.push_str("}\n");
+                output
+            }
+        }
+    }
+
+    /// The hierarchy as the nested JSON objects written by the JSON export
+    pub fn export_value(&self, include: &dyn Fn(&str) -> bool) -> serde_json::Value {
+        self.export_json(&self.root, "", include).into()
+    }
+
+    /// Sorted children that contain at least one included topic, with their full paths
+    fn exported_children<'a>(
+        &self,
+        node: &'a TopicNode,
+        path: &str,
+        include: &dyn Fn(&str) -> bool,
+    ) -> Vec<(String, &'a String, &'a TopicNode)> {
+        let mut children: Vec<_> = node
+            .children
+            .iter()
+            .map(|(segment, child)| {
+                let full_path = if path.is_empty() {
+                    segment.clone()
+                } else {
+                    format!("{}{}{}", path, self.separator, segment)
+                };
+                (full_path, segment, child)
+            })
+            .filter(|(full_path, _, child)| self.subtree_included(child, full_path, include))
+            .collect();
+        children.sort_by(|a, b| a.1.cmp(b.1));
+        children
+    }
+
+    fn subtree_included(
+        &self,
+        node: &TopicNode,
+        path: &str,
+        include: &dyn Fn(&str) -> bool,
+    ) -> bool {
+        if node.is_topic && include(path) {
+            return true;
+        }
+        node.children.iter().any(|(segment, child)| {
+            let full_path = format!("{}{}{}", path, self.separator, segment);
+            self.subtree_included(child, &full_path, include)
+        })
+    }
+
+    fn export_text(
+        &self,
+        node: &TopicNode,
+        path: &str,
+        depth: usize,
+        include: &dyn Fn(&str) -> bool,
+        output: &mut String,
+    ) {
+        for (full_path, segment, child) in self.exported_children(node, path, include) {
+            output.push_str(&"  ".repeat(depth));
+            output.push_str(segment);
+            if child.is_topic && include(&full_path) {
+                output.push_str(&format!(
+                    "  [{} msgs, {} bytes{}]",
+                    child.message_count,
+                    child.bytes_received,
+                    format_last_seen(child.last_message_time)
+                        .map(|t| format!(", last {}", t))
+                        .unwrap_or_default()
+                ));
+            }
+            output.push('\n');
+            self.export_text(child, &full_path, depth + 1, include, output);
+        }
+    }
+
+    fn export_json(
+        &self,
+        node: &TopicNode,
+        path: &str,
+        include: &dyn Fn(&str) -> bool,
+    ) -> Vec<serde_json::Value> {
+        self.exported_children(node, path, include)
+            .into_iter()
+            .map(|(full_path, segment, child)| {
+                let mut value = serde_json::json!({
+                    "name": segment,
+                    "path": full_path,
+                });
+                if child.is_topic && include(&full_path) {
+                    value["message_count"] = child.message_count.into();
+                    value["bytes_received"] = child.bytes_received.into();
+                    if let Some(last) = format_last_seen(child.last_message_time) {
+                        value["last_message"] = last.into();
+                    }
+                }
+                let children = self.export_json(child, &full_path, include);
+                if !children.is_empty() {
+                    value["children"] = children.into();
+                }
+                value
+            })
+            .collect()
+    }
+
+    fn export_dot(
+        &self,
+        node: &TopicNode,
+        path: &str,
+        include: &dyn Fn(&str) -> bool,
+        output: &mut String,
+    ) {
+        for (full_path, segment, child) in self.exported_children(node, path, include) {
+            let label = if child.is_topic && include(&full_path) {
+                format!("{}\\n{} msgs", dot_escape(segment), child.message_count)
+            } else {
+                dot_escape(segment)
+            };
+            output.push_str(&format!(
+                "  \"{}\" [label=\"{}\"];\n",
+                dot_escape(&full_path),
+                label
+            ));
+            if !path.is_empty() {
+                output.push_str(&format!(
+                    "  \"{}\" -> \"{}\";\n",
+                    dot_escape(path),
+                    dot_escape(&full_path)
+                ));
+            }
+            self.export_dot(child, &full_path, include, output);
+        }
+    }
+
+    /// Clear all data
+    pub fn clear(&mut self) {
+        self.root = TopicNode::default();
+        self.total_topics = 0;
+    }
+}
+
+/// Broker topics such as `$SYS/...`; MQTT keeps them out of `#` and `+`
+/// subscriptions, and they say nothing about the application
+pub fn is_system_topic(topic: &str) -> bool {
+    topic.starts_with('$')
+}
+
+fn format_last_seen(millis: Option<i64>) -> Option<String> {
+    let time = chrono::DateTime::from_timestamp_millis(millis?)?;
+    Some(time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Default for TopicTree {
+    fn default() -> Self {
+        Self {
+            root: TopicNode::default(),
+            total_topics: 0,
+            separator: '/',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_prune_inactive() {
+        let mut tree = TopicTree::new();
+        tree.restore_topic("old/a", 1, 10, Some(1_000));
+        tree.restore_topic("old/b", 1, 10, Some(2_000));
+        tree.restore_topic("mixed", 1, 10, Some(1_000));
+        tree.restore_topic("mixed/fresh", 1, 10, Some(9_000));
+        tree.restore_topic("unknown", 1, 10, None);
+
+        let mut removed = tree.prune_inactive(5_000);
+        removed.sort();
+
+        assert_eq!(removed, vec!["mixed", "old/a", "old/b"]);
+        assert_eq!(tree.topic_count(), 2);
+        assert_eq!(tree.get_all_topics(), vec!["mixed/fresh", "unknown"]);
+
+        // The emptied branch is gone, the parent of a live topic stays
+        let visible = tree.get_visible_topics(&HashSet::new());
+        let roots: Vec<_> = visible.iter().map(|t| t.segment.as_str()).collect();
+        assert_eq!(roots, vec!["mixed", "unknown"]);
+        assert_eq!(visible[0].latest_activity, Some(9_000));
+    }
+
+    #[test]
+    fn test_insert_and_count() {
+        let mut tree = TopicTree::new();
+
+        tree.insert("sensors/temp/living_room", 10);
+        tree.insert("sensors/temp/bedroom", 15);
+        tree.insert("sensors/humidity/living_room", 8);
+
+        assert_eq!(tree.topic_count(), 3);
+        assert_eq!(tree.total_messages(), 3);
+    }
+
+    #[test]
+    fn test_insert_reports_grown_branch() {
+        let mut tree = TopicTree::new();
+
+        assert_eq!(tree.insert("sensors/temp", 10), Some(String::new()));
+        assert_eq!(tree.insert("sensors/temp", 10), None);
+        assert_eq!(
+            tree.insert("sensors/humidity/kitchen", 8),
+            Some("sensors".to_string())
+        );
+        // A branch that only held children becoming a topic adds no child
+        assert_eq!(tree.insert("sensors/humidity", 8), None);
+    }
+
+    #[test]
+    fn test_topic_flags() {
+        let mut tree = TopicTree::new();
+        tree.insert("config/lamp", 10);
+        tree.record_flags("config/lamp", 1, true, false);
+        tree.insert("config/lamp", 10);
+        tree.record_flags("config/lamp", 2, true, true);
+        tree.insert("state/lamp", 2);
+        tree.record_flags("state/lamp", 0, false, false);
+
+        let expanded: HashSet<String> = ["config".to_string(), "state".to_string()].into();
+        let topics = tree.get_visible_topics(&expanded);
+        let lamp = topics
+            .iter()
+            .find(|t| t.full_path == "config/lamp")
+            .unwrap();
+        assert_eq!(
+            lamp.flags,
+            TopicFlags {
+                retained: 2,
+                duplicates: 1,
+                max_qos: 2
+            }
+        );
+        assert!(lamp.retained_only());
+        let state = topics.iter().find(|t| t.full_path == "state/lamp").unwrap();
+        assert!(!state.retained_only());
+        assert_eq!(tree.topic_flags("config"), None);
+
+        tree.reset_counters("config/lamp", false);
+        assert_eq!(tree.topic_flags("config/lamp"), Some(TopicFlags::default()));
+    }
+
+    #[test]
+    fn test_multiple_messages_same_topic() {
+        let mut tree = TopicTree::new();
+
+        tree.insert("sensors/temp", 10);
+        tree.insert("sensors/temp", 12);
+        tree.insert("sensors/temp", 11);
+
+        assert_eq!(tree.topic_count(), 1);
+        assert_eq!(tree.total_messages(), 3);
+
+        let stats = tree.get_topic_stats("sensors/temp").unwrap();
+        assert_eq!(stats.0, 3); // message_count
+        assert_eq!(stats.1, 33); // bytes_received
+    }
+
+    #[test]
+    fn test_hierarchical_structure() {
+        let mut tree = TopicTree::new();
+
+        tree.insert("a/b/c", 1);
+        tree.insert("a/b/d", 1);
+        tree.insert("a/e", 1);
+
+        let mut expanded = HashSet::new();
+        let visible = tree.get_visible_topics(&expanded);
+
+        // Only top level visible when nothing expanded
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].segment, "a");
+        assert!(visible[0].has_children);
+
+        // Expand "a"
+        expanded.insert("a".to_string());
+        let visible = tree.get_visible_topics(&expanded);
+
+        // Now a, a/b, a/e visible
+        assert_eq!(visible.len(), 3);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut tree = TopicTree::new();
+
+        tree.insert("sensors/temperature/room1", 1);
+        tree.insert("sensors/temperature/room2", 1);
+        tree.insert("sensors/humidity/room1", 1);
+        tree.insert("devices/light/kitchen", 1);
+
+        let results = tree.search("temp");
+        assert_eq!(results.len(), 2);
+
+        let results = tree.search("room1");
+        assert_eq!(results.len(), 2);
+
+        let results = tree.search("kitchen");
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_case_insensitive_search() {
+        let mut tree = TopicTree::new();
+        tree.insert("Sensors/Temperature", 1);
+
+        let results = tree.search("sensors");
+        assert_eq!(results.len(), 1);
+
+        let results = tree.search("TEMPERATURE");
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_system_topics_listed_last() {
+        let mut tree = TopicTree::new();
+        tree.insert("$SYS/broker/uptime", 10);
+        tree.insert("$SYS/broker/clients/connected", 2);
+        tree.insert("sensors/temp", 10);
+        tree.insert("alarms", 10);
+
+        let roots: Vec<String> = tree
+            .get_visible_topics(&HashSet::new())
+            .into_iter()
+            .map(|topic| topic.full_path)
+            .collect();
+        assert_eq!(roots, vec!["alarms", "sensors", "$SYS"]);
+        assert_eq!(tree.topic_count(), 4);
+        assert_eq!(tree.system_topic_count(), 2);
+        assert!(is_system_topic("$SYS/broker/uptime"));
+        assert!(!is_system_topic("sensors/$temp"));
+    }
+
+    #[test]
+    fn test_get_all_topics_empty_tree() {
+        let tree = TopicTree::new();
+        let topics = tree.get_all_topics();
+        assert!(topics.is_empty());
+    }
+
+    #[test]
+    fn test_get_all_topics_single() {
+        let mut tree = TopicTree::new();
+        tree.insert("sensors/temp", 10);
+
+        let topics = tree.get_all_topics();
+        assert_eq!(topics, vec!["sensors/temp"]);
+    }
+
+    #[test]
+    fn test_get_all_topics_nested() {
+        let mut tree = TopicTree::new();
+        tree.insert("a/b/c", 1);
+        tree.insert("a/b/d", 1);
+        tree.insert("a/e", 1);
+        tree.insert("x/y", 1);
+
+        let topics = tree.get_all_topics();
+        assert_eq!(topics, vec!["a/b/c", "a/b/d", "a/e", "x/y"]);
+
+        // Intermediate nodes without messages should not appear
+        assert!(!topics.contains(&"a".to_string()));
+        assert!(!topics.contains(&"a/b".to_string()));
+    }
+
+    #[test]
+    fn test_get_all_topics_nats_separator() {
+        let mut tree = TopicTree::with_separator('.');
+        tree.insert("sensors.temp.kitchen", 1);
+
+        assert_eq!(tree.get_all_topics(), vec!["sensors.temp.kitchen"]);
+    }
+
+    #[test]
+    fn test_restore_topic() {
+        let mut tree = TopicTree::new();
+        tree.restore_topic("sensors/temp", 42, 1024, Some(1_700_000_000_000));
+
+        assert_eq!(tree.topic_count(), 1);
+        assert_eq!(
+            tree.get_topic_stats("sensors/temp"),
+            Some((42, 1024, Some(1_700_000_000_000)))
+        );
+    }
+
+    #[test]
+    fn test_grouped_by_template() {
+        let mut tree = TopicTree::new();
+        let mut topics = Vec::new();
+        for site in 1..=5 {
+            let topic = format!("sites/site-{}/power", site);
+            tree.restore_topic(&topic, 10, 100, Some(site));
+            topics.push(topic);
+        }
+        tree.restore_topic("status", 1, 2, None);
+        topics.push("status".to_string());
+
+        let grouped = tree.grouped(&TopicTemplates::learn(&topics, '/'));
+        assert_eq!(grouped.topic_count(), 2);
+        assert_eq!(
+            grouped.get_topic_stats("sites/{siteId}/power"),
+            Some((50, 500, Some(5)))
+        );
+
+        let expanded: HashSet<String> = ["sites".to_string()].into();
+        let visible = grouped.get_visible_topics(&expanded);
+        assert_eq!(visible[1].full_path, "sites/{siteId}");
+        assert_eq!(visible[1].instances, 5);
+    }
+
+    fn export_fixture() -> TopicTree {
+        let mut tree = TopicTree::new();
+        tree.restore_topic("sensors/temp/kitchen", 3, 30, Some(0));
+        tree.restore_topic("sensors/humidity", 1, 5, None);
+        tree.restore_topic("alerts", 2, 8, None);
+        tree
+    }
+
+    #[test]
+    fn test_export_text_outline() {
+        let tree = export_fixture();
+        let text = tree.export(TreeExportFormat::Text, &|_| true);
+
+        assert_eq!(
+            text,
+            "alerts  [2 msgs, 8 bytes]\n\
+             sensors\n\
+             \x20 humidity  [1 msgs, 5 bytes]\n\
+             \x20 temp\n\
+             \x20   kitchen  [3 msgs, 30 bytes, last 1970-01-01T00:00:00Z]\n"
+        );
+    }
+
+    #[test]
+    fn test_export_respects_include() {
+        let tree = export_fixture();
+        let text = tree.export(TreeExportFormat::Text, &|t| t.starts_with("sensors/temp"));
+
+        assert!(text.contains("kitchen"));
+        assert!(!text.contains("alerts"));
+        assert!(!text.contains("humidity"));
+    }
+
+    #[test]
+    fn test_export_json() {
+        let tree = export_fixture();
+        let json: serde_json::Value =
+            serde_json::from_str(&tree.export(TreeExportFormat::Json, &|_| true)).unwrap();
+
+        assert_eq!(json[0]["name"], "alerts");
+        assert_eq!(json[0]["message_count"], 2);
+        assert_eq!(json[1]["path"], "sensors");
+        assert!(json[1].get("message_count").is_none());
+        assert_eq!(
+            json[1]["children"][1]["children"][0]["path"],
+            "sensors/temp/kitchen"
+        );
+    }
+
+    #[test]
+    fn test_export_dot() {
+        let tree = export_fixture();
+        let dot = tree.export(TreeExportFormat::Dot, &|_| true);
+
+        assert!(dot.starts_with("digraph topics {"));
+        assert!(dot.contains("\"sensors/temp\" -> \"sensors/temp/kitchen\";"));
+        assert!(dot.contains("\"alerts\" [label=\"alerts\\n2 msgs\"];"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_reset_counters() {
+        let mut tree = TopicTree::new();
+        tree.insert("sensors", 4);
+        tree.insert("sensors/temp", 2);
+        tree.insert("sensors/temp/kitchen", 3);
+        tree.insert("alerts", 1);
+
+        assert_eq!(tree.reset_counters("sensors", false), vec!["sensors"]);
+        assert_eq!(tree.get_topic_stats("sensors").unwrap().0, 0);
+        assert_eq!(tree.get_topic_stats("sensors/temp").unwrap().0, 1);
+
+        let mut reset = tree.reset_counters("sensors", true);
+        reset.sort();
+        assert_eq!(
+            reset,
+            vec!["sensors", "sensors/temp", "sensors/temp/kitchen"]
+        );
+        assert_eq!(tree.total_messages(), 1);
+        assert_eq!(tree.topic_count(), 4);
+        assert!(tree.reset_counters("missing", true).is_empty());
+    }
+
+    #[test]
+    fn test_complete() {
+        let mut tree = TopicTree::new();
+        tree.insert("sensors/dev-0042/temp", 1);
+        tree.insert("sensors/dev-0042/humidity", 1);
+        tree.insert("sensors/dev-0043/temp", 1);
+        tree.insert("status", 1);
+
+        assert_eq!(tree.complete("s", '+'), vec!["sensors/", "status"]);
+        assert_eq!(
+            tree.complete("sensors/dev", '+'),
+            vec!["sensors/dev-0042/", "sensors/dev-0043/"]
+        );
+        assert_eq!(
+            tree.complete("sensors/dev-0042/", '+'),
+            vec!["sensors/dev-0042/humidity", "sensors/dev-0042/temp"]
+        );
+        // Wildcard levels match every child
+        assert_eq!(tree.complete("sensors/+/t", '+'), vec!["sensors/+/temp"]);
+        assert!(tree.complete("missing/", '+').is_empty());
+    }
+}
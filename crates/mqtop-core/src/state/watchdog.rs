@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Watchdog windows offered when cycling with `w` (seconds, 0 = off)
+pub const WATCHDOG_STEPS: &[u64] = &[0, 30, 60, 300, 900, 3600];
+
+/// Next watchdog window after `current` in [`WATCHDOG_STEPS`]
+pub fn next_watchdog_step(current: u64) -> u64 {
+    WATCHDOG_STEPS
+        .iter()
+        .copied()
+        .find(|&step| step > current)
+        .unwrap_or(0)
+}
+
+/// Short label for a watchdog window, e.g. `30s`, `5m`, `1h`
+pub fn format_window(secs: u64) -> String {
+    if secs >= 3600 && secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Raises alerts when watched topics stop receiving messages
+#[derive(Debug, Default)]
+pub struct WatchdogMonitor {
+    /// When each watchdog started counting (unix millis), used until a message arrives
+    armed_at: HashMap<String, i64>,
+    /// Topics currently silent past their window
+    silent: HashSet<String>,
+}
+
+impl WatchdogMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check all watchdogs and return topics that went silent since the last check.
+    /// Topics recover automatically once a newer message arrives.
+    pub fn check(
+        &mut self,
+        watchdogs: &BTreeMap<String, u64>,
+        last_seen: impl Fn(&str) -> Option<i64>,
+        now_ms: i64,
+    ) -> Vec<String> {
+        self.armed_at
+            .retain(|topic, _| watchdogs.contains_key(topic));
+        self.silent.retain(|topic| watchdogs.contains_key(topic));
+
+        let mut newly_silent = Vec::new();
+        for (topic, &window_secs) in watchdogs {
+            let armed = *self.armed_at.entry(topic.clone()).or_insert(now_ms);
+            let last = last_seen(topic).map_or(armed, |t| t.max(armed));
+            let silent = now_ms - last > (window_secs as i64).saturating_mul(1000);
+
+            if silent {
+                if self.silent.insert(topic.clone()) {
+                    newly_silent.push(topic.clone());
+                }
+            } else {
+                self.silent.remove(topic);
+            }
+        }
+        newly_silent
+    }
+
+    /// Restart all windows from `now_ms`, e.g. after reconnecting
+    pub fn rearm(&mut self, now_ms: i64) {
+        for armed in self.armed_at.values_mut() {
+            *armed = now_ms;
+        }
+        self.silent.clear();
+    }
+
+    pub fn is_silent(&self, topic: &str) -> bool {
+        self.silent.contains(topic)
+    }
+
+    pub fn silent_count(&self) -> usize {
+        self.silent.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_watchdog_step() {
+        assert_eq!(next_watchdog_step(0), 30);
+        assert_eq!(next_watchdog_step(30), 60);
+        assert_eq!(next_watchdog_step(3600), 0);
+        assert_eq!(next_watchdog_step(45), 60);
+    }
+
+    #[test]
+    fn test_format_window() {
+        assert_eq!(format_window(30), "30s");
+        assert_eq!(format_window(300), "5m");
+        assert_eq!(format_window(3600), "1h");
+        assert_eq!(format_window(90), "90s");
+    }
+
+    #[test]
+    fn test_watchdog_alerts_once_and_recovers() {
+        let mut monitor = WatchdogMonitor::new();
+        let mut watchdogs = BTreeMap::new();
+        watchdogs.insert("dev/1".to_string(), 30);
+
+        let mut last: Option<i64> = None;
+
+        // Armed at t=0 with no messages yet
+        assert!(monitor.check(&watchdogs, |_| last, 0).is_empty());
+        assert!(monitor.check(&watchdogs, |_| last, 20_000).is_empty());
+
+        // Silent past the window: alert once
+        assert_eq!(monitor.check(&watchdogs, |_| last, 31_000), vec!["dev/1"]);
+        assert!(monitor.check(&watchdogs, |_| last, 40_000).is_empty());
+        assert!(monitor.is_silent("dev/1"));
+
+        // A message arrives: recovered
+        last = Some(41_000);
+        assert!(monitor.check(&watchdogs, |_| last, 42_000).is_empty());
+        assert!(!monitor.is_silent("dev/1"));
+        assert_eq!(monitor.silent_count(), 0);
+    }
+
+    #[test]
+    fn test_watchdog_removed() {
+        let mut monitor = WatchdogMonitor::new();
+        let mut watchdogs = BTreeMap::new();
+        watchdogs.insert("dev/1".to_string(), 30);
+
+        monitor.check(&watchdogs, |_| None, 0);
+        monitor.check(&watchdogs, |_| None, 60_000);
+        assert!(monitor.is_silent("dev/1"));
+
+        watchdogs.clear();
+        monitor.check(&watchdogs, |_| None, 61_000);
+        assert!(!monitor.is_silent("dev/1"));
+    }
+}
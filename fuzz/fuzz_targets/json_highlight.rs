@@ -0,0 +1,34 @@
+//! The payload highlighter gets whatever the broker sends, JSON or not.
+//! It must not panic and must not drop or reorder characters.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mqtop::ui::syntax_highlight_json;
+
+fn check(text: &str) {
+    let highlighted = syntax_highlight_json(text);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(highlighted.lines.len(), lines.len());
+    for (line, expected) in highlighted.lines.iter().zip(lines) {
+        let rendered: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(rendered, expected);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    check(text);
+    // The message view highlights pretty-printed JSON when the payload parses
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+        if let Ok(pretty) = serde_json::to_string_pretty(&value) {
+            check(&pretty);
+        }
+    }
+});
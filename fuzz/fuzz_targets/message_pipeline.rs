@@ -0,0 +1,103 @@
+//! Arbitrary messages through the whole app: topic tree, buffers, trackers,
+//! payload formatting and rendering, with a few navigation keys mixed in.
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use mqtop::app::App;
+use mqtop::config::Config;
+use mqtop::mqtt::{ConnectionState, MqttEvent, MqttMessage};
+use mqtop::paths::Paths;
+use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+const CONFIG: &str = r##"
+[mqtt]
+active_server = "fuzz"
+
+[[mqtt.servers]]
+name = "fuzz"
+host = "localhost"
+port = 1883
+client_id = "mqtop-fuzz"
+subscribe_topic = "#"
+"##;
+
+/// Keys that only move around and change views; nothing that writes files,
+/// touches the clipboard or quits
+const KEYS: [KeyCode; 11] = [
+    KeyCode::Down,
+    KeyCode::Up,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Esc,
+    KeyCode::Char('p'),
+    KeyCode::Char('H'),
+    KeyCode::Char(' '),
+    KeyCode::Char('?'),
+];
+
+#[derive(Arbitrary, Debug)]
+enum Step {
+    Message {
+        topic: String,
+        payload: Vec<u8>,
+        qos: u8,
+        retain: bool,
+    },
+    Key(u8),
+    Render,
+}
+
+fn paths() -> &'static Paths {
+    static DIR: OnceLock<tempfile::TempDir> = OnceLock::new();
+    static PATHS: OnceLock<Paths> = OnceLock::new();
+    PATHS.get_or_init(|| {
+        let dir = DIR.get_or_init(|| tempfile::tempdir().unwrap());
+        Paths {
+            config_dir: dir.path().to_path_buf(),
+            state_dir: dir.path().to_path_buf(),
+            cache_dir: dir.path().to_path_buf(),
+            profile: None,
+        }
+    })
+}
+
+fuzz_target!(|input: (u8, u8, Vec<Step>)| {
+    let (width, height, steps) = input;
+    let paths = paths();
+    let config = Config::parse(CONFIG).unwrap();
+    let mut app = App::new(config, paths.config_file(), paths);
+    app.handle_mqtt_event(MqttEvent::StateChange(ConnectionState::Connected));
+    let mut terminal = Terminal::new(TestBackend::new(width as u16, height as u16)).unwrap();
+
+    for step in steps.into_iter().take(64) {
+        match step {
+            Step::Message {
+                topic,
+                payload,
+                qos,
+                retain,
+            } => {
+                let message = MqttMessage::new(topic, payload, qos % 3, retain);
+                app.handle_mqtt_event(MqttEvent::Message(message));
+            }
+            Step::Key(index) => {
+                app.handle_key(KEYS[index as usize % KEYS.len()], KeyModifiers::NONE);
+            }
+            Step::Render => {
+                terminal
+                    .draw(|frame| mqtop::ui::render(frame, &mut app))
+                    .unwrap();
+            }
+        }
+    }
+    terminal
+        .draw(|frame| mqtop::ui::render(frame, &mut app))
+        .unwrap();
+});
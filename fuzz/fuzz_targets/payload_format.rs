@@ -0,0 +1,29 @@
+//! Text, JSON and hex views of an arbitrary payload, including the truncated
+//! previews used for large messages.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mqtop::mqtt::MqttMessage;
+
+fuzz_target!(|input: (u16, Vec<u8>)| {
+    let (limit, payload) = input;
+    let limit = limit as usize;
+    let message = MqttMessage::new("fuzz/topic".to_string(), payload, 0, false);
+
+    let _ = message.payload_str();
+    let _ = message.payload_json_pretty();
+
+    // Two hex digits per byte, separated by single spaces
+    let hex = message.payload_hex();
+    let len = message.payload.len();
+    assert_eq!(hex.len(), (len * 3).saturating_sub(1));
+
+    let prefix = message.payload_hex_prefix(limit);
+    assert_eq!(prefix.len(), (len.min(limit) * 3).saturating_sub(1));
+
+    if let Some(text) = message.payload_str_prefix(limit) {
+        assert!(text.len() <= limit);
+        assert!(message.payload.starts_with(text.as_bytes()));
+    }
+});
@@ -0,0 +1,26 @@
+//! Schema tracking on arbitrary payloads: field extraction, change detection,
+//! and checking live traffic against an exported baseline.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mqtop::state::schema_tracker::Schema;
+use mqtop::state::SchemaTracker;
+
+fuzz_target!(|messages: Vec<(String, Vec<u8>)>| {
+    let mut tracker = SchemaTracker::new();
+    for (topic, payload) in &messages {
+        let _ = tracker.process_message(topic, payload);
+        if let Ok(value) = serde_json::from_slice::<serde_json::Value>(payload) {
+            let _ = Schema::from_json(&value);
+        }
+    }
+
+    let baseline = tracker.export_baseline();
+    let mut checked = SchemaTracker::new();
+    checked.set_baseline(baseline);
+    for (topic, payload) in messages.iter().rev() {
+        let _ = checked.process_message(topic, payload);
+    }
+    let _ = checked.drifted();
+});
@@ -0,0 +1,17 @@
+//! Wildcard matching for MQTT (`+`, `#`) and NATS (`*`, `>`) patterns,
+//! used for metrics, filters and watchdogs on untrusted topic names.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mqtop::state::metric_tracker::topic_matches;
+
+fuzz_target!(|input: (&str, &str)| {
+    let (pattern, topic) = input;
+    let _ = topic_matches(pattern, topic);
+
+    // Every topic matches itself and the catch-all patterns
+    assert!(topic_matches(topic, topic));
+    assert!(topic_matches("#", topic));
+    assert!(topic_matches(">", topic));
+});
@@ -1,20 +1,56 @@
 #![allow(dead_code)]
 
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
 use crossterm::event::{KeyCode, KeyModifiers};
 
+use crate::asyncapi::{self, AsyncApiSpec, ObservedTopic, SpecCoverage};
+use crate::away::{AwayBatch, AwayPhase, AwaySession};
 use crate::broker::BrokerKind;
-use crate::config::{Config, MqttServerConfig, NatsServerConfig, CONFIG_BACKUP_LIMIT};
-use crate::mqtt::{ConnectionState, MqttEvent, MqttMessage};
-use crate::persistence::{Bookmark, UserData};
-use crate::state::metric_tracker::topic_matches;
+use crate::config::{
+    ClientPreset, Config, Feature, MqttServerConfig, NatsServerConfig, UiConfig,
+    CONFIG_BACKUP_LIMIT,
+};
+use crate::content_type;
+use crate::friendly_names::FriendlyNames;
+use crate::input::InputCursor;
+use crate::lint::PublishPreview;
+use crate::logging::LogBuffer;
+use crate::metric_sink::{MetricSample, MetricSink};
+use crate::mqtt::resilience::{BackoffStrategy, ConnectionHealth};
+use crate::mqtt::subscription::{covers, effective_filter, reaches, validate_subscription};
+use crate::mqtt::{
+    classify_error, ConnectionDetails, ConnectionState, DeliveryStatus, ErrorInfo, MqttEvent,
+    MqttMessage, LARGE_PAYLOAD_BYTES,
+};
+use crate::paths::Paths;
+use crate::persistence::{
+    bookmark_rows, normalize_folder, Bookmark, BookmarkRow, BookmarkSet, InputHistories,
+    RecentPublish, UserData,
+};
+use crate::pipe::TopicPipe;
+use crate::process_stats::ProcessMonitor;
+use crate::scenario::{Scenario, ScenarioRun};
+use crate::schedule::ActiveSchedule;
+use crate::session::{
+    DeviceSnapshot, MessageSnapshot, MetricSnapshot, SessionSnapshot, StateSnapshot, StatsSnapshot,
+    TopicSnapshot, SESSION_VERSION,
+};
+use crate::state::metric_tracker::{topic_matches, MetricDisplay};
 use crate::state::{
-    get_numeric_fields, DeviceTracker, LatencyTracker, MessageBuffer, MetricTracker, SchemaTracker,
-    Stats, TopicInfo, TopicTree,
+    capture_value, format_window, get_numeric_fields, get_state_fields, is_system_topic,
+    next_watchdog_step, parse_timestamp_ms, regex_metric_name, suggest_regexes, text_state,
+    BufferedMessage, CardinalityWatch, DeviceHealth, DeviceTracker, LatencyTracker, MessageBuffer,
+    MetricTracker, ParsedPayload, PingTracker, ResponseEvent, ResponseOutcome, ResponseSpec,
+    ResponseWatcher, SchemaBaseline, SchemaTracker, StateTracker, Stats, TopicInfo,
+    TopicMoveDetector, TopicTemplates, TopicTotals, TopicTree, TrafficSummary, TreeExportFormat,
+    WatchdogMonitor, BROKER_TIME_TOPIC,
 };
+use crate::text;
 
 /// Current UI panel focus
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,6 +58,7 @@ pub enum Panel {
     TopicTree,
     Messages,
     Stats,
+    Devices,
 }
 
 /// Input mode
@@ -30,10 +67,57 @@ pub enum InputMode {
     Normal,
     Search,
     MetricSelect,
+    MetricDetail,
+    MetricChart,
     Filter,
     ServerManager,
     Publish,
     BookmarkManager,
+    SessionLoad,
+    TreeExport,
+    LogView,
+    SubscribeWarning,
+    CardinalityAlert,
+    StarredPicker,
+    Pipe,
+    Scenarios,
+    Note,
+    ApiDocs,
+    TrafficSummary,
+    Breadcrumb,
+    MessageColumns,
+    AwayBatch,
+    ServerSwitch,
+    Kiosk,
+}
+
+/// Full-screen pages cycled on a wall display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KioskPage {
+    Overview,
+    Devices,
+    Busiest,
+    Metrics,
+}
+
+impl KioskPage {
+    pub fn title(&self) -> &'static str {
+        match self {
+            KioskPage::Overview => "Overview",
+            KioskPage::Devices => "Devices",
+            KioskPage::Busiest => "Busiest topic",
+            KioskPage::Metrics => "Metrics",
+        }
+    }
+}
+
+/// The tree as it was when the filter dialog opened, restored on Esc
+#[derive(Debug, Clone)]
+struct FilterBefore {
+    filter: Option<String>,
+    selected_topic: Option<String>,
+    selected_index: usize,
+    scroll: usize,
 }
 
 /// Filter mode for topic tree
@@ -43,12 +127,26 @@ pub enum FilterMode {
     Starred,
 }
 
+/// Which part of the screen a screenshot captures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotScope {
+    Screen,
+    FocusedPanel,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PendingServerSwitch {
     pub kind: BrokerKind,
     pub index: usize,
 }
 
+/// Pipe to start for a topic (consumed by the main loop)
+#[derive(Debug, Clone)]
+pub struct PendingPipe {
+    pub topic: String,
+    pub target: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct ActiveServerInfo {
     pub kind: BrokerKind,
@@ -57,6 +155,90 @@ pub struct ActiveServerInfo {
     pub port: u16,
 }
 
+/// Data collected from a server, kept while another one is shown
+#[derive(Debug)]
+pub struct ParkedServer {
+    topic_tree: TopicTree,
+    message_buffer: MessageBuffer,
+    stats: Stats,
+    metric_tracker: MetricTracker,
+    state_tracker: StateTracker,
+    device_tracker: DeviceTracker,
+    latency_tracker: LatencyTracker,
+    expanded_topics: HashSet<String>,
+    selected_topic: Option<String>,
+}
+
+impl ParkedServer {
+    pub fn topic_count(&self) -> usize {
+        self.topic_tree.topic_count()
+    }
+}
+
+/// Time after connecting before the subscribe rate is judged
+const SUBSCRIBE_WARN_SETTLE: Duration = Duration::from_secs(3);
+/// How long after connecting a high subscribe rate still triggers a warning
+const SUBSCRIBE_WARN_WINDOW: Duration = Duration::from_secs(30);
+
+/// Shown when a catch-all subscription floods the session
+#[derive(Debug, Clone)]
+pub struct SubscribeWarning {
+    /// Messages per second since connecting
+    pub rate: f64,
+    /// Narrower subscriptions built from the busiest prefixes
+    pub suggestions: Vec<String>,
+}
+
+/// Shown when a branch suddenly gains many child topics
+#[derive(Debug, Clone)]
+pub struct CardinalityAlert {
+    pub branch: String,
+    /// Children added within the window
+    pub added: usize,
+}
+
+/// How often dead topics are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10);
+/// How often device health is re-evaluated without new messages
+const DEVICE_STATUS_INTERVAL: Duration = Duration::from_secs(1);
+/// How often housekeeping trims internal sample buffers
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(30);
+/// How often topic templates are relearned while the tree is grouped by them
+const TEMPLATE_INTERVAL: Duration = Duration::from_secs(5);
+/// How often the followed topic may change, so the Messages panel stays readable
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(5);
+/// How long a watchdog alert keeps its topic shown over the busiest one
+const FOLLOW_ALERT_HOLD: Duration = Duration::from_secs(60);
+/// Wait before trying again when a kiosk display fails to connect
+const KIOSK_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+/// Pause in typing before the filter being typed is applied to the tree
+const FILTER_PREVIEW_DELAY: Duration = Duration::from_millis(150);
+/// Largest count prefix typed before a motion
+const MAX_COUNT: usize = 99_999;
+/// Traffic summary periods against their baselines, in days
+const DAILY_SUMMARY: (u32, u32) = (1, 7);
+const WEEKLY_SUMMARY: (u32, u32) = (7, 21);
+/// Share of `ui.high_load_rate` the rate must fall below to leave high load mode
+const HIGH_LOAD_EXIT: f64 = 0.75;
+/// Messages listed per topic in high load mode
+const HIGH_LOAD_MESSAGES: usize = 20;
+/// Latency sparkline samples are dropped after this long without messages
+const LATENCY_SAMPLE_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// Sizes of internal structures, refreshed by housekeeping for the debug stats
+#[derive(Debug, Clone, Default)]
+pub struct InternalMetrics {
+    pub topics: usize,
+    pub buffered_messages: usize,
+    pub buffered_bytes: usize,
+    pub devices: usize,
+    pub device_samples: usize,
+    pub metric_points: usize,
+    pub latency_samples: usize,
+    /// Samples and empty buffers dropped since startup
+    pub pruned: u64,
+}
+
 /// Application state
 pub struct App {
     /// Configuration
@@ -65,6 +247,12 @@ pub struct App {
     pub config_path: PathBuf,
     /// User data (persisted)
     pub user_data: UserData,
+    /// Where user data is saved (the profile's state dir)
+    pub user_data_path: PathBuf,
+    /// Profile chosen with --profile
+    pub profile: Option<String>,
+    /// Publish, filter and search history for this run (saved with `persist_history`)
+    pub history: InputHistories,
     /// Topic tree
     pub topic_tree: TopicTree,
     /// Message buffer
@@ -85,6 +273,7 @@ pub struct App {
     pub filter_mode: FilterMode,
     /// Search query
     pub search_query: String,
+    pub search_cursor: InputCursor,
     /// Search results
     pub search_results: Vec<String>,
     /// Selected search result index
@@ -93,12 +282,31 @@ pub struct App {
     pub search_scroll: usize,
     /// Connection state
     pub connection_state: ConnectionState,
-    /// Last error message
-    pub last_error: Option<String>,
+    /// Last connection error, classified with a suggested fix
+    pub last_error: Option<ErrorInfo>,
     /// Whether app should quit
     pub should_quit: bool,
     /// Scroll offset for topic tree
     pub tree_scroll: usize,
+    /// Highlighted tree row and its topic when last drawn, to follow the
+    /// topic when rows above it come and go
+    pub tree_anchor: Option<(usize, String)>,
+    /// Moving through the tree previews rows instead of selecting them (`v`)
+    pub peek: bool,
+    /// Count typed before a motion (`25j`, `10G`)
+    pub pending_count: Option<usize>,
+    /// Keep selecting the busiest topic, or the latest alert (`a`)
+    pub follow_busiest: bool,
+    /// Select each new topic as it appears (`n`)
+    pub follow_newest: bool,
+    /// Topic of the latest watchdog alert, shown first while following
+    follow_alert: Option<(String, Instant)>,
+    next_follow: Instant,
+    /// Page shown in kiosk mode, an index into `kiosk_pages()`
+    pub kiosk_page: usize,
+    pub kiosk_next_page: Instant,
+    /// When a kiosk display that failed to connect tries again
+    pub kiosk_retry_at: Option<Instant>,
     /// Scroll offset for messages
     pub message_scroll: usize,
     /// Scroll offset for stats panel
@@ -115,20 +323,45 @@ pub struct App {
     pub status_message: Option<(String, std::time::Instant)>,
     /// Metric tracker
     pub metric_tracker: MetricTracker,
+    /// Non-numeric states tracked as timelines
+    pub state_tracker: StateTracker,
     /// Device health tracker
     pub device_tracker: DeviceTracker,
     /// Latency tracker
     pub latency_tracker: LatencyTracker,
+    /// Broker keep-alive ping RTT tracker
+    pub ping_tracker: PingTracker,
+    /// Drops and ping round trips behind the header's quality bars
+    pub connection_health: ConnectionHealth,
+    /// What the current broker connection uses, once probed
+    pub connection_details: Option<ConnectionDetails>,
     /// Schema change tracker
     pub schema_tracker: SchemaTracker,
+    /// Devices whose traffic moved to a new topic prefix
+    pub topic_moves: TopicMoveDetector,
     /// Available numeric fields for metric selection
     pub available_fields: Vec<(String, f64)>,
+    /// States offered in metric selection mode, after the numeric fields
+    pub available_states: Vec<(String, String)>,
     /// Selected field index in metric selection mode
     pub metric_select_index: usize,
+    /// Text of a non-JSON message being picked from; `available_fields` then
+    /// holds regexes instead of field paths
+    pub metric_select_text: Option<String>,
+    /// Regex being edited in metric selection mode
+    pub regex_input: Option<String>,
+    pub regex_cursor: InputCursor,
     /// Topic filter pattern (MQTT wildcard syntax)
     pub topic_filter: Option<String>,
     /// Filter input buffer
     pub filter_input: String,
+    pub filter_cursor: InputCursor,
+    /// When the filter being typed is next applied to the tree
+    pub filter_preview_at: Option<Instant>,
+    /// Filter and tree position before the dialog opened, restored on Esc
+    filter_before: Option<FilterBefore>,
+    /// Topics matching the filter being typed, out of all topics
+    pub filter_matches: (usize, usize),
     /// Pending server switch selection
     pub pending_server_switch: Option<PendingServerSwitch>,
     /// Server manager selection index
@@ -147,6 +380,139 @@ pub struct App {
     pub pending_publish: Option<PendingPublish>,
     /// Bookmark manager state
     pub bookmark_manager: BookmarkManagerState,
+    /// Session file path input buffer
+    pub session_input: String,
+    pub session_cursor: InputCursor,
+    /// File the current view was loaded from (None when showing live data)
+    pub session_source: Option<String>,
+    /// Request to drop the broker connection (consumed by the main loop)
+    pub pending_disconnect: bool,
+    /// Request to reconnect to the active server, keeping the collected data
+    /// (consumed by the main loop)
+    pub pending_reconnect: bool,
+    /// Persistent session left on the broker with `Z`
+    pub away: Option<AwaySession>,
+    /// Request to connect with the away session (consumed by the main loop)
+    pub pending_away_connect: bool,
+    /// Messages queued while away, shown once back
+    pub away_batch: Option<AwayBatch>,
+    pub away_batch_index: usize,
+    pub server_switch_index: usize,
+    /// Data of servers switched away from, keyed by `server_key`
+    pub parked_servers: HashMap<String, ParkedServer>,
+    /// Connected read-only viewers (None when sharing is disabled)
+    pub share_viewers: Option<usize>,
+    /// Where --web or --api listens, e.g. `web :8080` (None when both are off)
+    pub web_label: Option<String>,
+    /// Screen area of the focused panel in the last rendered frame
+    pub focused_panel_area: Option<ratatui::layout::Rect>,
+    /// Screenshot to capture after the next frame is drawn
+    pub pending_screenshot: Option<ScreenshotScope>,
+    /// Recent log lines for the log view
+    pub log_buffer: LogBuffer,
+    /// Lines scrolled up from the newest entry in the log view
+    pub log_scroll: usize,
+    /// Topic documentation loaded with `--asyncapi`
+    pub asyncapi: Option<AsyncApiSpec>,
+    /// Names shown for device IDs, loaded with `--names` or fetched
+    pub friendly_names: FriendlyNames,
+    /// Observed topics checked against the AsyncAPI spec
+    pub spec_coverage: SpecCoverage,
+    /// Lines scrolled down in the API docs popup
+    pub api_docs_scroll: usize,
+    /// Traffic summary compares the last week instead of today
+    pub traffic_summary_weekly: bool,
+    /// Lines scrolled down in the traffic summary
+    pub traffic_summary_scroll: usize,
+    /// Last level of the selected topic's prefix picked in the breadcrumb
+    pub breadcrumb_depth: usize,
+    /// Connect time and message count, while the subscribe rate is being watched
+    subscribe_watch: Option<(Instant, u64)>,
+    /// Whether the subscribe rate check already ran for this server
+    subscribe_checked: bool,
+    /// Active high-rate subscription warning
+    pub subscribe_warning: Option<SubscribeWarning>,
+    /// Subscription narrowed from the subscribe warning for this session
+    /// only, with the server it applies to; the saved config keeps its own
+    pub narrowed_subscription: Option<(String, String)>,
+    /// Branches whose number of children explodes
+    pub cardinality_watch: CardinalityWatch,
+    pub cardinality_alert: Option<CardinalityAlert>,
+    /// Topics removed from the tree after exceeding the TTL
+    pub pruned_topics: u64,
+    /// List broker `$` topics in the tree
+    pub show_system_topics: bool,
+    /// Show the tree grouped by learned topic templates
+    pub group_templates: bool,
+    /// Templates learned from the tree, while grouping by them
+    pub topic_templates: TopicTemplates,
+    /// The tree folded into `topic_templates`
+    grouped_tree: TopicTree,
+    /// When templates were last learned
+    last_template_learn: Instant,
+    /// Per-topic totals across runs (with `persist_topic_stats`)
+    pub topic_totals: TopicTotals,
+    /// Where `topic_totals` is saved; None when not persisting
+    pub topic_totals_path: Option<PathBuf>,
+    /// The profile's directories, for per-server files
+    paths: Paths,
+    /// When dead topics were last pruned
+    last_prune: Instant,
+    /// Topics (and subtrees) hidden from the tree for this session only
+    pub hidden_topics: HashSet<String>,
+    /// Selected row in the starred topics picker
+    pub starred_picker_index: usize,
+    /// Selected metric in the tracked metrics dialog
+    pub metric_detail_index: usize,
+    /// Chart name being typed in the tracked metrics dialog, when adding to a chart
+    pub chart_name_input: Option<String>,
+    pub chart_name_cursor: InputCursor,
+    /// JSON field path being typed in the message columns dialog
+    pub column_field_input: Option<String>,
+    pub column_field_cursor: InputCursor,
+    /// Chart shown in the chart view
+    pub metric_chart_index: usize,
+    /// Selected row in the Devices panel
+    pub device_selected_index: usize,
+    /// Show only starred devices in the Devices panel
+    pub device_filter_starred: bool,
+    /// Silence alerts for watched starred topics
+    pub watchdog: WatchdogMonitor,
+    /// Publishes waiting for a response on a topic pattern
+    pub response_watcher: ResponseWatcher,
+    /// Started with --debug: show internal metrics in Stats
+    pub debug: bool,
+    /// Internal sizes from the last housekeeping run
+    pub internals: InternalMetrics,
+    /// mqtop's own CPU and memory use, sampled with --debug
+    pub process: ProcessMonitor,
+    /// Broker events waiting when the last tick began
+    pub event_backlog: usize,
+    /// Time taken to draw the last frame
+    pub frame_time: Duration,
+    /// Above `ui.high_load_rate`: expensive rendering is skipped
+    pub high_load: bool,
+    /// When housekeeping last ran
+    last_housekeeping: Instant,
+    /// When device statuses were last re-evaluated
+    last_device_refresh: Instant,
+    /// Live push of tracked metric samples, when configured
+    pub metric_sink: Option<MetricSink>,
+    /// Pipe target input buffer
+    pub pipe_input: String,
+    pub pipe_cursor: InputCursor,
+    /// Note input buffer for the selected topic
+    pub note_input: String,
+    pub note_cursor: InputCursor,
+    /// Pipe to start (consumed by the main loop)
+    pub pending_pipe: Option<PendingPipe>,
+    /// Active pipe of a topic's payloads to a file or command
+    pub topic_pipe: Option<TopicPipe>,
+    /// Bookmarks publishing on a schedule
+    pub bookmark_schedules: Vec<ActiveSchedule>,
+    pub scenario_manager: ScenarioManagerState,
+    /// Scenario currently publishing its steps
+    pub scenario_run: Option<ScenarioRun>,
 }
 
 #[derive(Debug, Clone)]
@@ -155,7 +521,7 @@ pub struct ServerEditState {
     pub is_new: bool,
     pub index: usize,
     pub field: ServerField,
-    pub cursor: usize,
+    pub cursor: InputCursor,
     // Basic connection
     pub name: String,
     pub host: String,
@@ -178,6 +544,7 @@ pub struct ServerEditState {
     pub keep_alive_secs: String,
     // Session
     pub clean_session: bool,
+    pub preset: ClientPreset,
     // Last Will
     pub lwt_topic: String,
     pub lwt_payload: String,
@@ -191,7 +558,7 @@ pub struct NatsServerEditState {
     pub is_new: bool,
     pub index: usize,
     pub field: NatsServerField,
-    pub cursor: usize,
+    pub cursor: InputCursor,
     // Basic connection
     pub name: String,
     pub host: String,
@@ -246,6 +613,7 @@ pub enum ServerField {
     KeepAlive,
     // Session
     CleanSession,
+    Preset,
     // LWT
     LwtTopic,
     LwtPayload,
@@ -268,7 +636,7 @@ impl Default for ServerEditState {
             is_new: false,
             index: 0,
             field: ServerField::Name,
-            cursor: 0,
+            cursor: InputCursor::default(),
             name: String::new(),
             host: String::new(),
             port: String::new(),
@@ -285,6 +653,7 @@ impl Default for ServerEditState {
             subscribe_qos: String::new(),
             keep_alive_secs: String::new(),
             clean_session: true,
+            preset: ClientPreset::default(),
             lwt_topic: String::new(),
             lwt_payload: String::new(),
             lwt_qos: String::new(),
@@ -300,7 +669,7 @@ impl Default for NatsServerEditState {
             is_new: false,
             index: 0,
             field: NatsServerField::Name,
-            cursor: 0,
+            cursor: InputCursor::default(),
             name: String::new(),
             host: String::new(),
             port: String::new(),
@@ -322,14 +691,16 @@ pub enum PublishField {
     Payload,
     Qos,
     Retain,
+    Response,
 }
 
 impl PublishField {
-    pub const ALL: [PublishField; 4] = [
+    pub const ALL: [PublishField; 5] = [
         PublishField::Topic,
         PublishField::Payload,
         PublishField::Qos,
         PublishField::Retain,
+        PublishField::Response,
     ];
 
     pub fn label(&self) -> &'static str {
@@ -338,6 +709,7 @@ impl PublishField {
             PublishField::Payload => "Payload",
             PublishField::Qos => "QoS",
             PublishField::Retain => "Retain",
+            PublishField::Response => "Response",
         }
     }
 }
@@ -347,11 +719,15 @@ impl PublishField {
 pub struct PublishEditState {
     pub active: bool,
     pub field: PublishField,
-    pub cursor: usize,
+    pub cursor: InputCursor,
     pub topic: String,
     pub payload: String,
     pub qos: u8,
     pub retain: bool,
+    /// Response to wait for after sending, `PATTERN [TIMEOUT]`
+    pub response: String,
+    /// Dry-run lint and diff, shown instead of sending until confirmed
+    pub preview: Option<PublishPreview>,
 }
 
 impl Default for PublishEditState {
@@ -359,11 +735,13 @@ impl Default for PublishEditState {
         Self {
             active: false,
             field: PublishField::Topic,
-            cursor: 0,
+            cursor: InputCursor::default(),
             topic: String::new(),
             payload: String::new(),
             qos: 0,
             retain: false,
+            response: String::new(),
+            preview: None,
         }
     }
 }
@@ -375,13 +753,31 @@ pub struct PendingPublish {
     pub payload: Vec<u8>,
     pub qos: u8,
     pub retain: bool,
+    /// Response to watch for once the publish is sent
+    pub response: Option<ResponseSpec>,
 }
 
 /// State for bookmark manager
 #[derive(Debug, Clone, Default)]
 pub struct BookmarkManagerState {
+    /// Selected row of the folder tree
     pub selected_index: usize,
     pub editing: Option<BookmarkEditState>,
+    /// Folder paths folded shut
+    pub collapsed: HashSet<String>,
+    /// Prompt shown under the list, with its input
+    pub prompt: Option<BookmarkPrompt>,
+    pub prompt_input: String,
+    pub prompt_cursor: InputCursor,
+}
+
+/// What the bookmark manager's prompt line is asking for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkPrompt {
+    /// Path of a bookmark file to import
+    Import,
+    /// Schedule for the bookmark at this index
+    Schedule(usize),
 }
 
 /// State for editing a bookmark
@@ -390,7 +786,7 @@ pub struct BookmarkEditState {
     pub is_new: bool,
     pub index: usize,
     pub field: BookmarkField,
-    pub cursor: usize,
+    pub cursor: InputCursor,
     pub name: String,
     pub topic: String,
     pub payload: String,
@@ -405,7 +801,7 @@ impl Default for BookmarkEditState {
             is_new: true,
             index: 0,
             field: BookmarkField::Name,
-            cursor: 0,
+            cursor: InputCursor::default(),
             name: String::new(),
             topic: String::new(),
             payload: String::new(),
@@ -416,6 +812,25 @@ impl Default for BookmarkEditState {
     }
 }
 
+/// State for the scenario manager
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioManagerState {
+    pub selected_index: usize,
+    pub editing: Option<ScenarioEditState>,
+}
+
+/// Scenario being edited: a name and its steps as text, one step per line
+#[derive(Debug, Clone)]
+pub struct ScenarioEditState {
+    pub is_new: bool,
+    pub index: usize,
+    /// Editing the steps rather than the name
+    pub on_steps: bool,
+    pub cursor: InputCursor,
+    pub name: String,
+    pub steps: String,
+}
+
 /// Field in bookmark edit dialog
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BookmarkField {
@@ -440,7 +855,7 @@ impl BookmarkField {
     pub fn label(&self) -> &'static str {
         match self {
             BookmarkField::Name => "Name",
-            BookmarkField::Category => "Category",
+            BookmarkField::Category => "Folder",
             BookmarkField::Topic => "Topic",
             BookmarkField::Payload => "Payload",
             BookmarkField::Qos => "QoS",
@@ -450,7 +865,7 @@ impl BookmarkField {
 }
 
 impl ServerField {
-    pub const ALL: [ServerField; 20] = [
+    pub const ALL: [ServerField; 21] = [
         // Basic
         ServerField::Name,
         ServerField::Host,
@@ -473,6 +888,7 @@ impl ServerField {
         ServerField::KeepAlive,
         // Session
         ServerField::CleanSession,
+        ServerField::Preset,
         // LWT
         ServerField::LwtTopic,
         ServerField::LwtPayload,
@@ -498,6 +914,7 @@ impl ServerField {
             ServerField::SubscribeQos => "Sub QoS",
             ServerField::KeepAlive => "Keep Alive",
             ServerField::CleanSession => "Clean Sess",
+            ServerField::Preset => "Tuning",
             ServerField::LwtTopic => "LWT Topic",
             ServerField::LwtPayload => "LWT Payload",
             ServerField::LwtQos => "LWT QoS",
@@ -512,6 +929,7 @@ impl ServerField {
                 | ServerField::TlsInsecure
                 | ServerField::UseExactClientId
                 | ServerField::CleanSession
+                | ServerField::Preset
                 | ServerField::LwtRetain
         )
     }
@@ -552,15 +970,37 @@ impl NatsServerField {
 }
 
 impl App {
-    pub fn new(config: Config, config_path: PathBuf) -> Self {
+    pub fn new(config: Config, config_path: PathBuf, paths: &Paths) -> Self {
         let message_buffer_size = config.ui.message_buffer_size;
         let stats_window = config.ui.stats_window_secs;
-        let user_data = UserData::load();
+        let metric_tracker = new_metric_tracker(&config.ui);
+        let state_tracker = new_state_tracker(&config.ui);
+        let device_tracker = new_device_tracker(&config.ui);
+        let cardinality_watch = new_cardinality_watch(&config.ui);
+        let show_system_topics = config.ui.show_system_topics;
+        let group_templates = config.ui.group_topic_templates;
+        let follow_busiest = config.ui.follow_busiest || config.ui.kiosk;
+        let kiosk_page_interval = Duration::from_secs(config.ui.kiosk_page_secs.max(1));
+        let input_mode = if config.ui.kiosk {
+            InputMode::Kiosk
+        } else {
+            InputMode::Normal
+        };
+        let user_data_path = paths.user_data_file();
+        let user_data = UserData::load_from(user_data_path.clone()).unwrap_or_default();
+        let history = if config.ui.persist_history {
+            user_data.history.clone()
+        } else {
+            InputHistories::default()
+        };
 
         Self {
             config,
             config_path,
             user_data,
+            user_data_path,
+            profile: paths.profile.clone(),
+            history,
             topic_tree: TopicTree::new(),
             message_buffer: MessageBuffer::new(message_buffer_size),
             stats: Stats::new(stats_window),
@@ -568,9 +1008,10 @@ impl App {
             selected_message_index: 0,
             expanded_topics: HashSet::new(),
             focused_panel: Panel::TopicTree,
-            input_mode: InputMode::Normal,
+            input_mode,
             filter_mode: FilterMode::All,
             search_query: String::new(),
+            search_cursor: InputCursor::default(),
             search_results: Vec::new(),
             search_result_index: 0,
             search_scroll: 0,
@@ -578,6 +1019,16 @@ impl App {
             last_error: None,
             should_quit: false,
             tree_scroll: 0,
+            tree_anchor: None,
+            peek: false,
+            pending_count: None,
+            follow_busiest,
+            follow_newest: false,
+            follow_alert: None,
+            next_follow: Instant::now(),
+            kiosk_page: 0,
+            kiosk_next_page: Instant::now() + kiosk_page_interval,
+            kiosk_retry_at: None,
             message_scroll: 0,
             stats_scroll: 0,
             selected_topic: None,
@@ -585,14 +1036,27 @@ impl App {
             show_david_easter_egg: false,
             payload_mode: PayloadMode::Auto,
             status_message: None,
-            metric_tracker: MetricTracker::new(100), // Keep last 100 data points
-            device_tracker: DeviceTracker::new(),
+            metric_tracker,
+            state_tracker,
+            device_tracker,
             latency_tracker: LatencyTracker::new(100),
+            ping_tracker: PingTracker::new(60),
+            connection_health: ConnectionHealth::new(BackoffStrategy::default()),
+            connection_details: None,
             schema_tracker: SchemaTracker::new(),
+            topic_moves: TopicMoveDetector::new(),
             available_fields: Vec::new(),
+            available_states: Vec::new(),
             metric_select_index: 0,
+            metric_select_text: None,
+            regex_input: None,
+            regex_cursor: InputCursor::default(),
             topic_filter: None,
             filter_input: String::new(),
+            filter_cursor: InputCursor::default(),
+            filter_preview_at: None,
+            filter_before: None,
+            filter_matches: (0, 0),
             pending_server_switch: None,
             server_manager_index: 0,
             server_manager_kind: BrokerKind::Mqtt,
@@ -602,12 +1066,91 @@ impl App {
             publish_edit: PublishEditState::default(),
             pending_publish: None,
             bookmark_manager: BookmarkManagerState::default(),
+            session_input: String::new(),
+            session_cursor: InputCursor::default(),
+            session_source: None,
+            pending_disconnect: false,
+            pending_reconnect: false,
+            away: None,
+            pending_away_connect: false,
+            away_batch: None,
+            away_batch_index: 0,
+            server_switch_index: 0,
+            parked_servers: HashMap::new(),
+            share_viewers: None,
+            web_label: None,
+            focused_panel_area: None,
+            pending_screenshot: None,
+            log_buffer: LogBuffer::default(),
+            log_scroll: 0,
+            asyncapi: None,
+            friendly_names: FriendlyNames::default(),
+            spec_coverage: SpecCoverage::default(),
+            api_docs_scroll: 0,
+            traffic_summary_weekly: false,
+            traffic_summary_scroll: 0,
+            breadcrumb_depth: 0,
+            subscribe_watch: None,
+            subscribe_checked: false,
+            subscribe_warning: None,
+            narrowed_subscription: None,
+            cardinality_watch,
+            cardinality_alert: None,
+            pruned_topics: 0,
+            show_system_topics,
+            group_templates,
+            topic_templates: TopicTemplates::default(),
+            grouped_tree: TopicTree::new(),
+            last_template_learn: Instant::now(),
+            topic_totals: TopicTotals::new(),
+            topic_totals_path: None,
+            paths: paths.clone(),
+            last_prune: Instant::now(),
+            hidden_topics: HashSet::new(),
+            starred_picker_index: 0,
+            metric_detail_index: 0,
+            chart_name_input: None,
+            chart_name_cursor: InputCursor::default(),
+            column_field_input: None,
+            column_field_cursor: InputCursor::default(),
+            metric_chart_index: 0,
+            device_selected_index: 0,
+            device_filter_starred: false,
+            watchdog: WatchdogMonitor::new(),
+            response_watcher: ResponseWatcher::new(),
+            debug: false,
+            internals: InternalMetrics::default(),
+            process: ProcessMonitor::new(),
+            event_backlog: 0,
+            frame_time: Duration::ZERO,
+            high_load: false,
+            last_housekeeping: Instant::now(),
+            last_device_refresh: Instant::now(),
+            metric_sink: None,
+            pipe_input: String::new(),
+            pipe_cursor: InputCursor::default(),
+            note_input: String::new(),
+            note_cursor: InputCursor::default(),
+            pending_pipe: None,
+            topic_pipe: None,
+            bookmark_schedules: Vec::new(),
+            scenario_manager: ScenarioManagerState::default(),
+            scenario_run: None,
         }
     }
 
     /// Save user data to disk
+    /// Add to the input history, saving it when `persist_history` is on
+    fn record_history(&mut self, record: impl FnOnce(&mut InputHistories)) {
+        record(&mut self.history);
+        if self.config.ui.persist_history {
+            self.user_data.history = self.history.clone();
+            self.save_user_data();
+        }
+    }
+
     pub fn save_user_data(&self) {
-        if let Err(e) = self.user_data.save() {
+        if let Err(e) = self.user_data.save_to(self.user_data_path.clone()) {
             tracing::error!("Failed to save user data: {:?}", e);
         }
     }
@@ -617,6 +1160,18 @@ impl App {
         self.status_message = Some((msg.to_string(), std::time::Instant::now()));
     }
 
+    /// Whether a capability group is enabled, explaining in the status bar when not
+    fn allow(&mut self, feature: Feature) -> bool {
+        let allowed = self.config.features.allows(feature);
+        if !allowed {
+            self.set_status(&format!(
+                "{} is disabled by [features] config",
+                feature.label()
+            ));
+        }
+        allowed
+    }
+
     /// Get status message if not expired (3 seconds)
     pub fn get_status(&self) -> Option<&str> {
         self.status_message.as_ref().and_then(|(msg, time)| {
@@ -632,6 +1187,9 @@ impl App {
     pub fn toggle_star(&mut self) {
         if let Some(topic) = &self.selected_topic.clone() {
             let starred = self.user_data.toggle_star(topic);
+            if !starred {
+                self.user_data.watchdogs.remove(topic);
+            }
             self.set_status(if starred {
                 "★ Starred"
             } else {
@@ -646,267 +1204,2336 @@ impl App {
         self.user_data.is_starred(topic)
     }
 
-    /// Reset tree selection and scroll when visible topic list changes
-    fn reset_tree_selection(&mut self) {
-        self.selected_topic_index = 0;
-        self.tree_scroll = 0;
-        self.update_selected_topic();
-    }
-
-    /// Toggle filter mode
-    pub fn toggle_filter_mode(&mut self) {
-        self.filter_mode = match self.filter_mode {
-            FilterMode::All => FilterMode::Starred,
-            FilterMode::Starred => FilterMode::All,
+    /// Edit the note attached to the selected topic
+    fn open_note(&mut self) {
+        let Some(topic) = &self.selected_topic else {
+            self.set_status("No topic selected");
+            return;
         };
-        self.reset_tree_selection();
-        self.set_status(match self.filter_mode {
-            FilterMode::All => "Showing all topics",
-            FilterMode::Starred => "Showing starred only",
-        });
-    }
-
-    /// Process an MQTT event
-    pub fn handle_mqtt_event(&mut self, event: MqttEvent) {
-        match event {
-            MqttEvent::Message(msg) => {
-                self.stats.record_message(msg.payload_size());
-                self.topic_tree.insert(&msg.topic, msg.payload_size());
-                // Process for metric tracking
-                self.metric_tracker
-                    .process_message(&msg.topic, &msg.payload);
-                // Process for device health tracking
-                self.device_tracker
-                    .process_message(&msg.topic, msg.payload_size());
-                // Process for latency tracking
-                self.latency_tracker.record_message(&msg.payload);
-                // Process for schema tracking (silent - no notifications)
-                let _ = self
-                    .schema_tracker
-                    .process_message(&msg.topic, &msg.payload);
-                self.message_buffer.push(msg);
-            }
-            MqttEvent::StateChange(state) => {
-                self.connection_state = state;
-                if state == ConnectionState::Connected {
-                    self.last_error = None;
-                }
-            }
-            MqttEvent::Error(err) => {
-                self.last_error = Some(err);
-            }
-        }
-    }
-
-    /// Handle keyboard input
-    pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
-        match self.input_mode {
-            InputMode::Search => self.handle_search_input(code, modifiers),
-            InputMode::Normal => self.handle_normal_input(code, modifiers),
-            InputMode::MetricSelect => self.handle_metric_select_input(code, modifiers),
-            InputMode::Filter => self.handle_filter_input(code, modifiers),
-            InputMode::ServerManager => self.handle_server_manager_input(code, modifiers),
-            InputMode::Publish => self.handle_publish_input(code, modifiers),
-            InputMode::BookmarkManager => self.handle_bookmark_manager_input(code, modifiers),
-        }
+        self.note_input = self.user_data.note(topic).unwrap_or_default().to_string();
+        self.note_cursor = InputCursor::end_of(&self.note_input);
+        self.input_mode = InputMode::Note;
     }
 
-    fn handle_metric_select_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+    fn handle_note_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
         match code {
             KeyCode::Esc => {
                 self.input_mode = InputMode::Normal;
-                self.available_fields.clear();
+                self.note_input.clear();
             }
             KeyCode::Enter => {
-                if let Some((field, _)) = self.available_fields.get(self.metric_select_index) {
-                    if let Some(topic) = &self.selected_topic {
-                        // Create a wildcard pattern to match similar topics
-                        // e.g., telemetry/device123/meter/zap/json -> telemetry/+/meter/+/json
-                        let sep = self.topic_tree.separator();
-                        let pattern =
-                            create_wildcard_pattern(topic, sep, self.connected_broker_kind.wildcard_single());
-                        let label = format!("{} ({})", field, short_topic(topic, sep));
-                        self.metric_tracker
-                            .track(label.clone(), pattern, field.clone());
-                        self.set_status(&format!("Tracking: {}", field));
-                    }
-                }
                 self.input_mode = InputMode::Normal;
-                self.available_fields.clear();
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if !self.available_fields.is_empty() {
-                    self.metric_select_index =
-                        (self.metric_select_index + 1) % self.available_fields.len();
+                let note = std::mem::take(&mut self.note_input);
+                if let Some(topic) = self.selected_topic.clone() {
+                    self.user_data.set_note(&topic, &note);
+                    self.save_user_data();
+                    self.set_status(if note.trim().is_empty() {
+                        "Note removed"
+                    } else {
+                        "Note saved"
+                    });
                 }
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if !self.available_fields.is_empty() {
-                    self.metric_select_index = self
-                        .metric_select_index
-                        .checked_sub(1)
-                        .unwrap_or(self.available_fields.len() - 1);
-                }
+            _ => {
+                self.note_cursor
+                    .handle_key(&mut self.note_input, code, modifiers);
             }
-            _ => {}
         }
     }
 
-    /// Enter metric selection mode
-    pub fn enter_metric_select(&mut self) {
-        // Get the current message's JSON fields
-        let messages = self.get_current_messages();
-        if let Some(msg) = messages.first() {
-            if let Ok(json) = serde_json::from_slice::<serde_json::Value>(&msg.payload) {
-                self.available_fields = get_numeric_fields(&json);
-                if !self.available_fields.is_empty() {
-                    self.input_mode = InputMode::MetricSelect;
-                    self.metric_select_index = 0;
-                } else {
-                    self.set_status("No numeric fields found");
-                }
-            } else {
-                self.set_status("Payload is not JSON");
-            }
-        } else {
-            self.set_status("No message selected");
+    /// Cycle the silence watchdog window on the selected starred topic
+    pub fn cycle_watchdog(&mut self) {
+        let Some(topic) = self.selected_topic.clone() else {
+            return;
+        };
+        if !self.user_data.is_starred(&topic) {
+            self.set_status("Star the topic first to watch it");
+            return;
         }
-    }
-
-    /// Remove a tracked metric
-    pub fn remove_metric(&mut self, label: &str) {
-        self.metric_tracker.untrack(label);
-        self.set_status(&format!("Stopped tracking: {}", label));
-    }
 
-    /// Copy current topic to clipboard
-    pub fn copy_topic(&mut self) {
-        if let Some(topic) = &self.selected_topic {
-            match arboard::Clipboard::new() {
-                Ok(mut clipboard) => {
-                    if clipboard.set_text(topic.clone()).is_ok() {
-                        self.set_status("Topic copied to clipboard");
-                    } else {
-                        self.set_status("Failed to copy topic");
-                    }
-                }
-                Err(_) => self.set_status("Clipboard unavailable"),
-            }
+        let current = self.user_data.watchdogs.get(&topic).copied().unwrap_or(0);
+        let next = next_watchdog_step(current);
+        if next == 0 {
+            self.user_data.watchdogs.remove(&topic);
+            self.set_status("Watchdog off");
         } else {
-            self.set_status("No topic selected");
+            self.user_data.watchdogs.insert(topic, next);
+            self.set_status(&format!(
+                "Watchdog: alert after {} of silence",
+                format_window(next)
+            ));
         }
+        self.save_user_data();
     }
 
-    /// Copy current payload to clipboard
-    pub fn copy_payload(&mut self) {
-        let messages = self.get_current_messages();
-        if let Some(msg) = messages.first() {
-            let payload = self.format_payload(msg);
-            match arboard::Clipboard::new() {
-                Ok(mut clipboard) => {
-                    if clipboard.set_text(payload).is_ok() {
-                        self.set_status("Payload copied to clipboard");
-                    } else {
-                        self.set_status("Failed to copy payload");
-                    }
-                }
-                Err(_) => self.set_status("Clipboard unavailable"),
-            }
-        } else {
-            self.set_status("No message to copy");
+    /// Raise alerts for watched starred topics that went silent
+    pub fn check_watchdogs(&mut self) {
+        if self.connection_state != ConnectionState::Connected {
+            return;
+        }
+        let watchdogs = &self.user_data.watchdogs;
+        let tree = &self.topic_tree;
+        let newly_silent = self.watchdog.check(
+            watchdogs,
+            |topic| tree.get_topic_stats(topic).and_then(|(_, _, last)| last),
+            chrono::Utc::now().timestamp_millis(),
+        );
+
+        for topic in newly_silent {
+            let window = self.user_data.watchdogs.get(&topic).copied().unwrap_or(0);
+            tracing::warn!("Watchdog: no messages on {} for {}s", topic, window);
+            self.set_status(&format!(
+                "⚠ No messages on {} for {}",
+                topic,
+                format_window(window)
+            ));
+            self.follow_alert = Some((topic, Instant::now()));
+            self.next_follow = Instant::now();
         }
     }
 
-    /// Export topics and their latest messages to a text file.
-    /// Respects the active filter when one is set, exports all topics otherwise.
-    pub fn export_topics(&mut self) {
-        let all_topics = self.topic_tree.get_all_topics();
-        let filter_active = self.topic_filter.is_some();
-        let topics: Vec<String> = if let Some(ref pattern) = self.topic_filter {
-            all_topics
-                .into_iter()
-                .filter(|t| topic_matches(pattern, t))
-                .collect()
+    /// Start or stop following the busiest topic
+    fn toggle_follow_busiest(&mut self) {
+        self.follow_busiest = !self.follow_busiest;
+        if self.follow_busiest {
+            self.follow_newest = false;
+            self.next_follow = Instant::now();
+            self.set_status("Following the busiest topic and alerts (a to stop)");
         } else {
-            all_topics
-        };
+            self.set_status("Stopped following");
+        }
+    }
 
-        if topics.is_empty() {
-            self.set_status("No topics to export");
+    /// While following, select the topic of a recent watchdog alert, else the
+    /// topic with the highest current message rate
+    pub fn follow_busiest_topic(&mut self) {
+        if !self.follow_busiest
+            || self.peek
+            || !matches!(self.input_mode, InputMode::Normal | InputMode::Kiosk)
+            || Instant::now() < self.next_follow
+        {
             return;
         }
+        self.next_follow = Instant::now() + FOLLOW_INTERVAL;
 
-        let now = chrono::Local::now();
-        let filename = format!("mqtop-export-{}.txt", now.format("%Y%m%d-%H%M%S"));
+        let alert = self
+            .follow_alert
+            .as_ref()
+            .filter(|(_, raised)| raised.elapsed() < FOLLOW_ALERT_HOLD)
+            .map(|(topic, _)| topic.clone());
+        let Some(topic) = alert.or_else(|| {
+            self.stats
+                .busiest_topic()
+                .map(|(topic, _)| topic.to_string())
+        }) else {
+            return;
+        };
+        if self.selected_topic.as_ref() != Some(&topic) {
+            self.show_topic(&topic);
+        }
+    }
 
-        let mut output = String::new();
-        output.push_str(&format!(
-            "# mqtop export - {}\n",
-            now.format("%Y-%m-%d %H:%M:%S")
-        ));
-        if let Some(ref pattern) = self.topic_filter {
-            output.push_str(&format!(
-                "# {} topics (filter: {})\n\n",
-                topics.len(),
-                pattern
-            ));
+    /// Start or stop selecting new topics as they appear
+    fn toggle_follow_newest(&mut self) {
+        self.follow_newest = !self.follow_newest;
+        if self.follow_newest {
+            self.follow_busiest = false;
+            self.set_status("Following new topics (n to stop)");
         } else {
-            output.push_str(&format!("# {} topics\n\n", topics.len()));
+            self.set_status("Stopped following new topics");
         }
+    }
 
-        for topic in &topics {
-            output.push_str(&format!("--- {} ---\n", topic));
-            if let Some(msg) = self.message_buffer.get_latest(topic) {
-                if let Some(pretty) = msg.payload_json_pretty() {
-                    output.push_str(&pretty);
-                } else if let Some(text) = msg.payload_str() {
-                    output.push_str(text);
-                } else {
-                    output.push_str(&format!("(binary {} bytes)", msg.payload_size()));
-                }
-            } else {
-                output.push_str("(no message)");
-            }
-            output.push_str("\n\n");
+    /// Select a topic and show its messages, expanding its parents, without
+    /// moving the focus
+    fn show_topic(&mut self, topic: &str) {
+        self.selected_topic = Some(topic.to_string());
+        self.expand_to_topic(topic);
+        self.selected_message_index = 0;
+        self.message_scroll = 0;
+    }
+
+    /// Pages a kiosk display cycles through, leaving out empty ones
+    pub fn kiosk_pages(&self) -> Vec<KioskPage> {
+        let mut pages = vec![KioskPage::Overview];
+        if self.device_tracker.device_count() > 0 {
+            pages.push(KioskPage::Devices);
         }
+        pages.push(KioskPage::Busiest);
+        if !self.metric_tracker.get_metrics().is_empty() {
+            pages.push(KioskPage::Metrics);
+        }
+        pages
+    }
 
-        let status_filter = if filter_active { " (filtered)" } else { "" };
-        match std::fs::write(&filename, &output) {
-            Ok(_) => self.set_status(&format!(
-                "Exported {} topics{} to {}",
-                topics.len(),
-                status_filter,
-                filename
-            )),
-            Err(e) => self.set_status(&format!("Export failed: {}", e)),
+    pub fn current_kiosk_page(&self) -> KioskPage {
+        let pages = self.kiosk_pages();
+        pages[self.kiosk_page % pages.len()]
+    }
+
+    /// Move to another kiosk page, `step` pages on, restarting its timer
+    fn turn_kiosk_page(&mut self, step: isize) {
+        let count = self.kiosk_pages().len() as isize;
+        let current = (self.kiosk_page as isize) % count;
+        self.kiosk_page = (current + step).rem_euclid(count) as usize;
+        self.kiosk_next_page =
+            Instant::now() + Duration::from_secs(self.config.ui.kiosk_page_secs.max(1));
+    }
+
+    /// Show the next kiosk page once the current one had its time, and try
+    /// connecting again after a failed attempt
+    pub fn advance_kiosk(&mut self) {
+        if self.input_mode != InputMode::Kiosk {
+            return;
+        }
+        if Instant::now() >= self.kiosk_next_page {
+            self.turn_kiosk_page(1);
+        }
+        if self.kiosk_retry_at.is_some_and(|at| Instant::now() >= at) {
+            self.kiosk_retry_at = None;
+            self.pending_reconnect = true;
         }
     }
 
-    fn handle_filter_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+    /// Keep a kiosk display running after a failed connect, retrying later
+    pub fn kiosk_connect_failed(&mut self, err: &anyhow::Error) {
+        tracing::warn!("Connect failed, retrying: {:#}", err);
+        self.set_status(&format!(
+            "Connect failed, retrying in {}s: {:#}",
+            KIOSK_RETRY_INTERVAL.as_secs(),
+            err
+        ));
+        self.kiosk_retry_at = Some(Instant::now() + KIOSK_RETRY_INTERVAL);
+    }
+
+    fn handle_kiosk_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
         match code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.turn_kiosk_page(-1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => self.turn_kiosk_page(1),
             KeyCode::Esc => {
                 self.input_mode = InputMode::Normal;
-                self.filter_input.clear();
+                self.set_status("Left kiosk mode");
             }
-            KeyCode::Enter => {
-                if self.filter_input.is_empty() {
-                    self.topic_filter = None;
-                    self.set_status("Filter cleared");
-                } else {
-                    self.topic_filter = Some(self.filter_input.clone());
+            _ => {}
+        }
+    }
+
+    /// Re-evaluate device health so devices that stop publishing turn stale
+    pub fn refresh_device_statuses(&mut self) {
+        if self.last_device_refresh.elapsed() < DEVICE_STATUS_INTERVAL {
+            return;
+        }
+        self.last_device_refresh = Instant::now();
+        self.device_tracker.update_all_statuses();
+    }
+
+    /// Periodically trim sample buffers that only shrink when messages arrive,
+    /// and refresh the internal size metrics
+    pub fn run_housekeeping(&mut self) {
+        if self.last_housekeeping.elapsed() < HOUSEKEEPING_INTERVAL {
+            return;
+        }
+        self.last_housekeeping = Instant::now();
+
+        self.cardinality_watch.expire(Instant::now());
+        let pruned = self.device_tracker.prune_samples()
+            + self.latency_tracker.prune_stale(LATENCY_SAMPLE_MAX_AGE)
+            + self.metric_tracker.expire(Instant::now())
+            + self.state_tracker.expire(Instant::now())
+            + self.message_buffer.remove_empty();
+        if pruned > 0 {
+            tracing::debug!("Housekeeping dropped {} stale entries", pruned);
+        }
+
+        self.save_topic_totals();
+
+        self.internals = InternalMetrics {
+            topics: self.topic_tree.topic_count(),
+            buffered_messages: self.message_buffer.total_stored(),
+            buffered_bytes: self.message_buffer.payload_bytes(),
+            devices: self.device_tracker.device_count(),
+            device_samples: self.device_tracker.sample_count(),
+            metric_points: self.metric_tracker.point_count(),
+            latency_samples: self.latency_tracker.sample_count(),
+            pruned: self.internals.pruned + pruned as u64,
+        };
+    }
+
+    /// Enter high load mode above `ui.high_load_rate` and leave it once the
+    /// rate drops well below, so a rate hovering at the limit doesn't flicker
+    pub fn update_load_mode(&mut self) {
+        let threshold = self.config.ui.high_load_rate as f64;
+        let rate = self.stats.messages_per_second();
+        if !self.high_load && threshold > 0.0 && rate > threshold {
+            self.high_load = true;
+            self.set_status(&format!(
+                "High load ({}/s): rendering reduced",
+                self.config.ui.numbers.rate(rate)
+            ));
+        } else if self.high_load && (threshold == 0.0 || rate < threshold * HIGH_LOAD_EXIT) {
+            self.high_load = false;
+            self.set_status("Load back to normal: full rendering restored");
+        }
+    }
+
+    /// Watchdog window for a topic in seconds, if one is set
+    pub fn watchdog_for(&self, topic: &str) -> Option<u64> {
+        self.user_data.watchdogs.get(topic).copied()
+    }
+
+    /// Starred topics in display order
+    pub fn starred_topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self.user_data.starred_topics.iter().cloned().collect();
+        topics.sort();
+        topics
+    }
+
+    /// Devices with starred ones first, then most recently seen
+    pub fn devices_by_priority(&self, starred_only: bool) -> Vec<&DeviceHealth> {
+        let mut devices: Vec<&DeviceHealth> = self
+            .device_tracker
+            .get_devices()
+            .into_iter()
+            .filter(|d| !starred_only || self.user_data.is_device_starred(&d.device_id))
+            .collect();
+        // Stable sort keeps the most-recent-first order within each group
+        devices.sort_by_key(|d| !self.user_data.is_device_starred(&d.device_id));
+        devices
+    }
+
+    /// Devices shown in the Devices panel
+    pub fn visible_devices(&self) -> Vec<&DeviceHealth> {
+        self.devices_by_priority(self.device_filter_starred)
+    }
+
+    fn selected_device_id(&self) -> Option<String> {
+        self.visible_devices()
+            .get(self.device_selected_index)
+            .map(|d| d.device_id.clone())
+    }
+
+    fn toggle_device_star(&mut self) {
+        if let Some(device_id) = self.selected_device_id() {
+            let starred = self.user_data.toggle_device_star(&device_id);
+            self.set_status(if starred {
+                "★ Device starred"
+            } else {
+                "☆ Device unstarred"
+            });
+            self.save_user_data();
+            // Starring reorders the list; keep the same device selected
+            if let Some(index) = self
+                .visible_devices()
+                .iter()
+                .position(|d| d.device_id == device_id)
+            {
+                self.device_selected_index = index;
+            } else {
+                self.clamp_device_selection();
+            }
+        }
+    }
+
+    fn toggle_device_filter(&mut self) {
+        self.device_filter_starred = !self.device_filter_starred;
+        self.device_selected_index = 0;
+        self.set_status(if self.device_filter_starred {
+            "Showing starred devices"
+        } else {
+            "Showing all devices"
+        });
+    }
+
+    fn clamp_device_selection(&mut self) {
+        let count = self.visible_devices().len();
+        self.device_selected_index = self.device_selected_index.min(count.saturating_sub(1));
+    }
+
+    /// Show messages from the selected device's first topic
+    fn open_selected_device(&mut self) {
+        let topic = self
+            .visible_devices()
+            .get(self.device_selected_index)
+            .and_then(|d| d.topics.first().cloned());
+        if let Some(topic) = topic {
+            self.jump_to_topic(&topic);
+        }
+    }
+
+    /// Select a topic in the tree (expanding its parents) and show its messages
+    pub fn jump_to_topic(&mut self, topic: &str) {
+        self.selected_topic = Some(topic.to_string());
+        self.expand_to_topic(topic);
+        self.selected_message_index = 0;
+        self.message_scroll = 0;
+        self.focused_panel = Panel::Messages;
+    }
+
+    fn handle_starred_picker_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let starred = self.starred_topics();
+        match code {
+            KeyCode::Esc | KeyCode::Char('\'') | KeyCode::Char('q') => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.starred_picker_index = self.starred_picker_index.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.starred_picker_index + 1 < starred.len() => {
+                self.starred_picker_index += 1;
+            }
+            KeyCode::Enter => {
+                if let Some(topic) = starred.get(self.starred_picker_index) {
+                    self.input_mode = InputMode::Normal;
+                    self.jump_to_topic(topic);
+                }
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if let Some(topic) = starred.get(index) {
+                    self.input_mode = InputMode::Normal;
+                    self.jump_to_topic(topic);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Hide the selected topic and its subtree from the tree.
+    /// The subscription is unchanged; `remember` also saves it to user data.
+    pub fn hide_selected_topic(&mut self, remember: bool) {
+        if let Some(topic) = self.selected_topic.clone() {
+            self.hide_topic(topic, remember);
+        }
+    }
+
+    fn hide_topic(&mut self, topic: String, remember: bool) {
+        if remember {
+            self.user_data.hidden_topics.insert(topic.clone());
+            self.save_user_data();
+            self.set_status(&format!("Hidden {} (remembered)", topic));
+        } else {
+            self.hidden_topics.insert(topic.clone());
+            self.set_status(&format!("Hidden {} for this session", topic));
+        }
+
+        let visible = self.get_visible_topics().len();
+        self.selected_topic_index = self.selected_topic_index.min(visible.saturating_sub(1));
+        self.update_selected_topic();
+    }
+
+    /// Clear the buffered messages and counters of the selected topic, or with
+    /// `subtree` of everything under it, leaving the rest of the session alone
+    pub fn clear_selected_topic(&mut self, subtree: bool) {
+        let Some(topic) = self.selected_topic.clone() else {
+            self.set_status("No topic selected");
+            return;
+        };
+        let topics = match self.selected_instances() {
+            [] => vec![topic.clone()],
+            instances => instances.to_vec(),
+        };
+        let mut cleared = Vec::new();
+        for topic in &topics {
+            cleared.extend(self.topic_tree.reset_counters(topic, subtree));
+        }
+        for topic in &cleared {
+            self.message_buffer.clear_topic(topic);
+        }
+        if self.group_templates {
+            self.learn_topic_templates();
+        }
+        self.selected_message_index = 0;
+        self.message_scroll = 0;
+
+        if subtree {
+            self.set_status(&format!("Cleared {} topics under {}", cleared.len(), topic));
+        } else if cleared.is_empty() {
+            self.set_status(&format!(
+                "{} has no messages of its own; U clears below it",
+                topic
+            ));
+        } else {
+            self.set_status(&format!("Cleared {}", topic));
+        }
+    }
+
+    /// Show all hidden topics again, including remembered ones
+    pub fn unhide_all_topics(&mut self) {
+        let count = self.hidden_topic_count();
+        if count == 0 {
+            self.set_status("No hidden topics");
+            return;
+        }
+        self.hidden_topics.clear();
+        if !self.user_data.hidden_topics.is_empty() {
+            self.user_data.hidden_topics.clear();
+            self.save_user_data();
+        }
+        self.set_status(&format!("Restored {} hidden topics", count));
+    }
+
+    /// Topics not counting broker `$` topics
+    pub fn application_topic_count(&self) -> usize {
+        self.topic_tree.topic_count() - self.topic_tree.system_topic_count()
+    }
+
+    /// Show or hide broker `$` topics in the tree
+    pub fn toggle_system_topics(&mut self) {
+        self.show_system_topics = !self.show_system_topics;
+        let count = self.topic_tree.system_topic_count();
+        self.reset_tree_selection();
+        self.set_status(&if self.show_system_topics {
+            format!("Showing {} $ topics below application topics", count)
+        } else {
+            format!("Hiding {} $ topics", count)
+        });
+    }
+
+    /// Switch the tree between topics and learned topic templates
+    pub fn toggle_template_grouping(&mut self) {
+        self.group_templates = !self.group_templates;
+        self.learn_topic_templates();
+        self.reset_tree_selection();
+        let count = self.topic_templates.template_count();
+        self.set_status(&if self.group_templates {
+            format!(
+                "Grouped by {} learned template{}",
+                count,
+                if count == 1 { "" } else { "s" }
+            )
+        } else {
+            "Showing all topics ungrouped".to_string()
+        });
+    }
+
+    /// Relearn topic templates every few seconds while the tree is grouped
+    pub fn refresh_topic_templates(&mut self) {
+        if self.group_templates && self.last_template_learn.elapsed() >= TEMPLATE_INTERVAL {
+            self.learn_topic_templates();
+        }
+    }
+
+    fn learn_topic_templates(&mut self) {
+        self.last_template_learn = Instant::now();
+        if !self.group_templates {
+            self.topic_templates = TopicTemplates::default();
+            self.grouped_tree = TopicTree::new();
+            return;
+        }
+        let topics = self.topic_tree.get_all_topics();
+        self.topic_templates = TopicTemplates::learn(&topics, self.topic_tree.separator());
+        self.grouped_tree = self.topic_tree.grouped(&self.topic_templates);
+    }
+
+    pub fn hidden_topic_count(&self) -> usize {
+        self.hidden_topics.len() + self.user_data.hidden_topics.len()
+    }
+
+    /// Whether a topic is hidden, directly or through a hidden parent
+    pub fn is_hidden(&self, topic: &str) -> bool {
+        let separator = self.topic_tree.separator();
+        self.hidden_topics
+            .iter()
+            .chain(self.user_data.hidden_topics.iter())
+            .any(|hidden| {
+                topic
+                    .strip_prefix(hidden.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(separator))
+            })
+    }
+
+    /// Reset tree selection and scroll when visible topic list changes
+    fn reset_tree_selection(&mut self) {
+        self.selected_topic_index = 0;
+        self.tree_scroll = 0;
+        self.tree_anchor = None;
+        self.update_selected_topic();
+    }
+
+    /// Toggle filter mode
+    pub fn toggle_filter_mode(&mut self) {
+        self.filter_mode = match self.filter_mode {
+            FilterMode::All => FilterMode::Starred,
+            FilterMode::Starred => FilterMode::All,
+        };
+        self.reset_tree_selection();
+        self.set_status(match self.filter_mode {
+            FilterMode::All => "Showing all topics",
+            FilterMode::Starred => "Showing starred only",
+        });
+    }
+
+    /// Process an MQTT event
+    pub fn handle_mqtt_event(&mut self, event: MqttEvent) {
+        match event {
+            MqttEvent::Message(msg) => {
+                // Queued while away: kept apart from live traffic
+                if let Some(away) = self
+                    .away
+                    .as_mut()
+                    .filter(|away| away.phase == AwayPhase::Replaying)
+                {
+                    away.messages.push(msg);
+                    return;
+                }
+                // Broker `$` topics stay out of the traffic stats and device counts
+                let system = is_system_topic(&msg.topic);
+                let separator = self.connected_broker_kind.topic_separator();
+                let prefix = msg.topic.split(separator).next().unwrap_or_default();
+                if !system {
+                    self.stats.record_message(msg.payload_size());
+                    self.stats.record_prefix(prefix, msg.payload_size());
+                    self.stats.record_topic(&msg.topic);
+                    if self.topic_totals_path.is_some() {
+                        self.topic_totals.record(
+                            &msg.topic,
+                            prefix,
+                            msg.payload_size(),
+                            msg.timestamp,
+                        );
+                    }
+                }
+                let topics_before = self.topic_tree.topic_count();
+                let grown = self.topic_tree.insert(&msg.topic, msg.payload_size());
+                if self.follow_newest
+                    && !system
+                    && !self.peek
+                    && self.input_mode == InputMode::Normal
+                    && self.topic_tree.topic_count() > topics_before
+                {
+                    self.show_topic(&msg.topic);
+                }
+                self.topic_tree
+                    .record_flags(&msg.topic, msg.qos, msg.retain, msg.dup);
+                if let Some(branch) = grown.filter(|_| !system) {
+                    if let Some(added) = self.cardinality_watch.record(&branch, Instant::now()) {
+                        self.report_cardinality(branch, added);
+                    }
+                }
+                if let Some(spec) = &self.asyncapi {
+                    self.spec_coverage.observe(spec, &msg.topic);
+                }
+                // Parsed at most once, shared by the trackers below
+                let payload = ParsedPayload::new(&msg.payload);
+                // Process for metric tracking
+                let recorded = self.metric_tracker.process_payload(&msg.topic, &payload);
+                self.state_tracker.process_payload(&msg.topic, &payload);
+                if let Some(sink) = &self.metric_sink {
+                    let timestamp_ms = msg.timestamp.timestamp_millis();
+                    for (label, value) in recorded {
+                        sink.send(MetricSample {
+                            label,
+                            value,
+                            timestamp_ms,
+                        });
+                    }
+                }
+                if !system {
+                    // Process for device health and latency tracking
+                    self.device_tracker
+                        .process_message(&msg.topic, &msg.payload);
+                    self.latency_tracker.record_payload(&payload);
+                }
+                if msg.topic == BROKER_TIME_TOPIC {
+                    if let Some(broker_ms) = msg.payload_str().and_then(parse_timestamp_ms) {
+                        self.latency_tracker
+                            .clock
+                            .record_broker_time(broker_ms, chrono::Utc::now().timestamp_millis());
+                    }
+                }
+                // Process for schema tracking; only baseline drift is announced
+                let was_drifted = self.schema_tracker.is_drifted(&msg.topic);
+                let _ = self.schema_tracker.process_payload(&msg.topic, &payload);
+                if !was_drifted && self.schema_tracker.is_drifted(&msg.topic) {
+                    self.set_status(&format!("Schema drift from baseline on {}", msg.topic));
+                }
+                let moves = if system {
+                    Vec::new()
+                } else {
+                    self.topic_moves
+                        .process_message(&msg.topic, separator, Instant::now())
+                };
+                for moved in moves {
+                    tracing::warn!(
+                        "Topic move: {} from {} to {}",
+                        moved.device_id,
+                        moved.from,
+                        moved.to
+                    );
+                    self.set_status(&format!("Topic moved: {} → {}", moved.from, moved.to));
+                }
+                for event in self.response_watcher.on_message(&msg.topic, Instant::now()) {
+                    self.report_response(event, Some(&msg));
+                }
+                if let Some(pipe) = &self.topic_pipe {
+                    if pipe.matches(&msg.topic) && !pipe.send(&msg.payload) {
+                        self.topic_pipe = None;
+                        self.set_status("Pipe closed (D: log)");
+                    }
+                }
+                self.message_buffer.push(msg);
+            }
+            MqttEvent::StateChange(state) => {
+                self.connection_state = state;
+                if state == ConnectionState::Reconnecting {
+                    let error = match &self.last_error {
+                        Some(info) => info.summary.clone(),
+                        None => "Connection lost".to_string(),
+                    };
+                    self.connection_health.record_failure(error);
+                }
+                if state == ConnectionState::Connected {
+                    self.connection_health.record_success();
+                    self.last_error = None;
+                    self.watchdog.rearm(chrono::Utc::now().timestamp_millis());
+                    if !self.subscribe_checked {
+                        self.subscribe_watch = Some((Instant::now(), self.stats.total_messages()));
+                    }
+                }
+            }
+            MqttEvent::Error(err) => {
+                self.last_error = Some(classify_error(&err));
+            }
+            MqttEvent::PingRtt(rtt) => {
+                self.ping_tracker.record_rtt(rtt);
+                self.connection_health
+                    .record_ping(Some(rtt), Instant::now());
+            }
+            MqttEvent::SessionPresent(present) => self.on_session_present(present),
+            MqttEvent::Subscribed => self.on_subscribed(),
+            MqttEvent::Connection(details) => self.on_connection_details(details),
+            MqttEvent::PingMissed => {
+                self.ping_tracker.record_missed();
+                self.connection_health.record_ping(None, Instant::now());
+                self.set_status("Broker did not answer keep-alive ping");
+            }
+            MqttEvent::Delivery(delivery) => {
+                let elapsed = delivery.elapsed.as_millis();
+                self.set_status(&match delivery.status {
+                    DeliveryStatus::Confirmed => format!(
+                        "Delivered to {} (QoS {} {} in {} ms)",
+                        delivery.topic,
+                        delivery.qos,
+                        delivery.ack_name(),
+                        elapsed
+                    ),
+                    DeliveryStatus::ConfirmedLate => format!(
+                        "Late {} for {} after {} ms",
+                        delivery.ack_name(),
+                        delivery.topic,
+                        elapsed
+                    ),
+                    DeliveryStatus::TimedOut => format!(
+                        "No {} for {} after {}s - broker may not have accepted it",
+                        delivery.ack_name(),
+                        delivery.topic,
+                        delivery.elapsed.as_secs()
+                    ),
+                });
+            }
+        }
+    }
+
+    /// Handle keyboard input
+    pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        // Ctrl+V pastes into whichever text field has focus
+        if modifiers.contains(KeyModifiers::CONTROL)
+            && code == KeyCode::Char('v')
+            && self.input_mode != InputMode::Normal
+        {
+            match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+                Ok(pasted) => self.handle_paste(&pasted),
+                Err(_) => self.set_status("Clipboard is empty or unavailable"),
+            }
+            return;
+        }
+
+        match self.input_mode {
+            InputMode::Search => self.handle_search_input(code, modifiers),
+            InputMode::Normal => self.handle_normal_input(code, modifiers),
+            InputMode::MetricSelect => self.handle_metric_select_input(code, modifiers),
+            InputMode::MetricDetail => self.handle_metric_detail_input(code, modifiers),
+            InputMode::MetricChart => self.handle_metric_chart_input(code, modifiers),
+            InputMode::Filter => self.handle_filter_input(code, modifiers),
+            InputMode::ServerManager => self.handle_server_manager_input(code, modifiers),
+            InputMode::Publish => self.handle_publish_input(code, modifiers),
+            InputMode::BookmarkManager => self.handle_bookmark_manager_input(code, modifiers),
+            InputMode::Scenarios => self.handle_scenario_input(code, modifiers),
+            InputMode::SessionLoad => self.handle_session_load_input(code, modifiers),
+            InputMode::TreeExport => self.handle_tree_export_input(code, modifiers),
+            InputMode::LogView => self.handle_log_view_input(code, modifiers),
+            InputMode::ApiDocs => self.handle_api_docs_input(code, modifiers),
+            InputMode::TrafficSummary => self.handle_traffic_summary_input(code, modifiers),
+            InputMode::Breadcrumb => self.handle_breadcrumb_input(code, modifiers),
+            InputMode::MessageColumns => self.handle_message_columns_input(code, modifiers),
+            InputMode::AwayBatch => self.handle_away_batch_input(code, modifiers),
+            InputMode::ServerSwitch => self.handle_server_switch_input(code, modifiers),
+            InputMode::Kiosk => self.handle_kiosk_input(code, modifiers),
+            InputMode::SubscribeWarning => self.handle_subscribe_warning_input(code, modifiers),
+            InputMode::CardinalityAlert => self.handle_cardinality_alert_input(code, modifiers),
+            InputMode::StarredPicker => self.handle_starred_picker_input(code, modifiers),
+            InputMode::Pipe => self.handle_pipe_input(code, modifiers),
+            InputMode::Note => self.handle_note_input(code, modifiers),
+        }
+    }
+
+    /// Insert pasted text (bracketed paste or Ctrl+V) into the focused field.
+    /// Payload fields keep line breaks; everything else gets a single line.
+    pub fn handle_paste(&mut self, pasted: &str) {
+        match self.input_mode {
+            InputMode::Search => {
+                self.search_cursor
+                    .paste(&mut self.search_query, pasted, false);
+                self.update_search_results();
+            }
+            InputMode::Filter => {
+                self.filter_cursor
+                    .paste(&mut self.filter_input, pasted, false);
+                self.filter_preview_at = Some(Instant::now() + FILTER_PREVIEW_DELAY);
+            }
+            InputMode::SessionLoad => {
+                self.session_cursor
+                    .paste(&mut self.session_input, pasted, false)
+            }
+            InputMode::Pipe => self.pipe_cursor.paste(&mut self.pipe_input, pasted, false),
+            InputMode::Note => self.note_cursor.paste(&mut self.note_input, pasted, false),
+            InputMode::MetricSelect => {
+                if let Some(regex) = &mut self.regex_input {
+                    self.regex_cursor.paste(regex, pasted, false);
+                }
+            }
+            InputMode::MetricDetail => {
+                if let Some(name) = &mut self.chart_name_input {
+                    self.chart_name_cursor.paste(name, pasted, false);
+                }
+            }
+            InputMode::MessageColumns => {
+                if let Some(field) = &mut self.column_field_input {
+                    self.column_field_cursor.paste(field, pasted, false);
+                }
+            }
+            InputMode::Publish => {
+                let edit = &mut self.publish_edit;
+                edit.preview = None;
+                match edit.field {
+                    PublishField::Topic => edit.cursor.paste(&mut edit.topic, pasted, false),
+                    PublishField::Payload => edit.cursor.paste(&mut edit.payload, pasted, true),
+                    PublishField::Response => edit.cursor.paste(&mut edit.response, pasted, false),
+                    PublishField::Qos | PublishField::Retain => {}
+                }
+            }
+            InputMode::BookmarkManager => {
+                let manager = &mut self.bookmark_manager;
+                if manager.prompt.is_some() {
+                    manager
+                        .prompt_cursor
+                        .paste(&mut manager.prompt_input, pasted, false);
+                } else if let Some(editing) = &mut manager.editing {
+                    let (value, multiline) = match editing.field {
+                        BookmarkField::Name => (&mut editing.name, false),
+                        BookmarkField::Category => (&mut editing.category, false),
+                        BookmarkField::Topic => (&mut editing.topic, false),
+                        BookmarkField::Payload => (&mut editing.payload, true),
+                        BookmarkField::Qos | BookmarkField::Retain => return,
+                    };
+                    editing.cursor.paste(value, pasted, multiline);
+                }
+            }
+            InputMode::Scenarios => {
+                if let Some(editing) = &mut self.scenario_manager.editing {
+                    if editing.on_steps {
+                        editing.cursor.paste(&mut editing.steps, pasted, true);
+                    } else {
+                        editing.cursor.paste(&mut editing.name, pasted, false);
+                    }
+                }
+            }
+            InputMode::ServerManager => {
+                if self.server_edit.active && !self.server_edit.field.is_checkbox() {
+                    let mut cursor = self.server_edit.cursor;
+                    cursor.paste(self.server_edit_mut_field(), pasted, false);
+                    self.server_edit.cursor = cursor;
+                } else if self.nats_server_edit.active && !self.nats_server_edit.field.is_checkbox()
+                {
+                    let mut cursor = self.nats_server_edit.cursor;
+                    cursor.paste(self.nats_server_edit_mut_field(), pasted, false);
+                    self.nats_server_edit.cursor = cursor;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_metric_select_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if self.regex_input.is_some() {
+            self.handle_regex_input(code, modifiers);
+            return;
+        }
+        match code {
+            KeyCode::Esc => self.close_metric_select(),
+            KeyCode::Enter => {
+                let index = self.metric_select_index;
+                if let Some((field, _)) = self.available_fields.get(index) {
+                    let field = field.clone();
+                    self.track_selected_field(&field);
+                } else if let Some((field, _)) = self
+                    .available_states
+                    .get(index - self.available_fields.len())
+                {
+                    let field = field.clone();
+                    self.track_selected_state(&field);
+                }
+                self.close_metric_select();
+            }
+            KeyCode::Char('e')
+                if self.metric_select_text.is_some()
+                    && self.metric_select_index < self.available_fields.len() =>
+            {
+                let regex = self
+                    .available_fields
+                    .get(self.metric_select_index)
+                    .map(|(regex, _)| regex.clone())
+                    .unwrap_or_default();
+                self.regex_cursor = InputCursor::end_of(&regex);
+                self.regex_input = Some(regex);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let count = self.available_fields.len() + self.available_states.len();
+                if count > 0 {
+                    self.metric_select_index = (self.metric_select_index + 1) % count;
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let count = self.available_fields.len() + self.available_states.len();
+                if count > 0 {
+                    self.metric_select_index =
+                        self.metric_select_index.checked_sub(1).unwrap_or(count - 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_regex_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(regex) = &mut self.regex_input else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.regex_input = None,
+            KeyCode::Enter => {
+                let regex = regex.clone();
+                let text = self.metric_select_text.as_deref().unwrap_or_default();
+                match capture_value(&regex, text) {
+                    Ok(Some(_)) => {
+                        self.track_selected_field(&regex);
+                        self.close_metric_select();
+                    }
+                    Ok(None) => self.set_status("Regex doesn't match this message"),
+                    Err(e) => self.set_status(&format!("Invalid regex: {}", e)),
+                }
+            }
+            _ => {
+                self.regex_cursor.handle_key(regex, code, modifiers);
+            }
+        }
+    }
+
+    /// Track a JSON field, or a regex for a text message, on topics like the
+    /// selected one
+    fn track_selected_field(&mut self, field: &str) {
+        let Some(topic) = &self.selected_topic else {
+            return;
+        };
+        // Create a wildcard pattern to match similar topics
+        // e.g., telemetry/device123/meter/zap/json -> telemetry/+/meter/+/json
+        let sep = self.topic_tree.separator();
+        let pattern =
+            create_wildcard_pattern(topic, sep, self.connected_broker_kind.wildcard_single());
+        if self.metric_select_text.is_some() {
+            let name = regex_metric_name(field);
+            let label = format!("{} ({})", name, short_topic(topic, sep));
+            match self.metric_tracker.track_regex(label, pattern, field) {
+                Ok(()) => self.set_status(&format!("Tracking: {}", name)),
+                Err(e) => self.set_status(&format!("Invalid regex: {}", e)),
+            }
+        } else {
+            let label = format!("{} ({})", field, short_topic(topic, sep));
+            self.metric_tracker.track(label, pattern, field.to_string());
+            self.set_status(&format!("Tracking: {}", field));
+        }
+    }
+
+    /// Track a JSON field's state, or the whole payload's when `field` is
+    /// empty. States follow the exact topic, so each device keeps its own
+    /// timeline.
+    fn track_selected_state(&mut self, field: &str) {
+        let Some(topic) = self.selected_topic.clone() else {
+            return;
+        };
+        let sep = self.topic_tree.separator();
+        let label = if field.is_empty() {
+            short_topic(&topic, sep)
+        } else {
+            format!("{} ({})", field, short_topic(&topic, sep))
+        };
+        self.state_tracker
+            .track(label.clone(), topic, field.to_string());
+        self.set_status(&format!("Tracking state: {}", label));
+    }
+
+    fn close_metric_select(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.available_fields.clear();
+        self.available_states.clear();
+        self.metric_select_text = None;
+        self.regex_input = None;
+    }
+
+    /// Enter metric selection mode
+    pub fn enter_metric_select(&mut self) {
+        // Get the current message's JSON fields, or regexes for the numbers
+        // in a text message
+        let Some(payload) = self
+            .get_current_messages()
+            .first()
+            .map(|msg| msg.payload.clone())
+        else {
+            self.set_status("No message selected");
+            return;
+        };
+        if let Ok(json) = serde_json::from_slice::<serde_json::Value>(&payload) {
+            self.available_fields = get_numeric_fields(&json);
+            self.available_states = get_state_fields(&json);
+            self.metric_select_text = None;
+        } else if let Ok(text) = std::str::from_utf8(&payload) {
+            self.available_fields = suggest_regexes(text);
+            // A single word such as an LWT's online/offline; longer text
+            // is more likely a reading with numbers in it
+            self.available_states = text_state(&payload)
+                .filter(|state| !state.contains(char::is_whitespace))
+                .map(|state| (String::new(), state))
+                .into_iter()
+                .collect();
+            self.metric_select_text = Some(text.to_string());
+        } else {
+            self.set_status("Payload is neither JSON nor text");
+            return;
+        }
+        if !self.available_fields.is_empty() || !self.available_states.is_empty() {
+            self.input_mode = InputMode::MetricSelect;
+            self.metric_select_index = 0;
+        } else {
+            self.metric_select_text = None;
+            self.set_status("No numeric fields or states found");
+        }
+    }
+
+    /// Open the tracked metrics dialog
+    pub fn open_metric_detail(&mut self) {
+        if self.metric_tracker.has_metrics() || self.state_tracker.has_states() {
+            self.metric_detail_index = 0;
+            self.input_mode = InputMode::MetricDetail;
+        } else {
+            self.set_status("No tracked metrics - press 'm' on a message to track one");
+        }
+    }
+
+    fn handle_metric_detail_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if self.chart_name_input.is_some() {
+            self.handle_chart_name_input(code, modifiers);
+            return;
+        }
+        // States are listed after the metrics
+        let metrics = self.metric_tracker.get_metrics().len();
+        let count = metrics + self.state_tracker.get_timelines().len();
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('M') => {
+                self.input_mode = InputMode::Normal;
+                return;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.metric_detail_index = self.metric_detail_index.saturating_sub(1);
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.metric_detail_index + 1 < count {
+                    self.metric_detail_index += 1;
+                }
+                return;
+            }
+            _ => {}
+        }
+        let untrack = matches!(code, KeyCode::Char('x') | KeyCode::Delete);
+        if let Some(index) = self.metric_detail_index.checked_sub(metrics) {
+            let label = self
+                .state_tracker
+                .get_timelines()
+                .get(index)
+                .map(|timeline| timeline.label.clone());
+            if let Some(label) = label.filter(|_| untrack) {
+                self.state_tracker.untrack(&label);
+                self.set_status(&format!("Stopped tracking: {}", label));
+                if count == 1 {
+                    self.input_mode = InputMode::Normal;
+                }
+                self.metric_detail_index = self.metric_detail_index.min(count.saturating_sub(2));
+            }
+            return;
+        }
+        let Some((label, display)) = self
+            .metric_tracker
+            .get_metrics()
+            .get(self.metric_detail_index)
+            .map(|metric| (metric.label.clone(), metric.display))
+        else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        let mode = match code {
+            KeyCode::Enter | KeyCode::Char(' ') => Some(display.next()),
+            KeyCode::Char('a') => Some(MetricDisplay::Absolute),
+            KeyCode::Char('l') => Some(MetricDisplay::Log),
+            KeyCode::Char('d') => Some(MetricDisplay::Delta),
+            KeyCode::Char('r') => Some(MetricDisplay::Rate),
+            KeyCode::Char('c') => {
+                // Offer the latest chart, so adding several metrics is one key each
+                let name = self
+                    .metric_tracker
+                    .charts()
+                    .last()
+                    .map(|chart| chart.name.clone())
+                    .unwrap_or_default();
+                self.chart_name_cursor = InputCursor::end_of(&name);
+                self.chart_name_input = Some(name);
+                None
+            }
+            KeyCode::Char('v') => {
+                self.open_metric_chart();
+                None
+            }
+            // Decimal places, or `.` to follow [ui.numbers]
+            KeyCode::Char(c @ ('0'..='9' | '.')) => {
+                let precision = c.to_digit(10).map(|digits| digits as usize);
+                self.metric_tracker.set_precision(&label, precision);
+                self.set_status(&match precision {
+                    Some(digits) => format!("{}: {} decimals", label, digits),
+                    None => format!("{}: default decimals", label),
+                });
+                None
+            }
+            _ if untrack => {
+                self.remove_metric(&label);
+                if count == 1 {
+                    self.input_mode = InputMode::Normal;
+                }
+                self.metric_detail_index = self.metric_detail_index.min(count.saturating_sub(2));
+                None
+            }
+            _ => None,
+        };
+        if let Some(mode) = mode {
+            self.metric_tracker.set_display(&label, mode);
+            self.set_status(&format!("{}: {}", label, mode.label()));
+        }
+    }
+
+    fn handle_chart_name_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(name) = &mut self.chart_name_input else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.chart_name_input = None,
+            KeyCode::Enter => {
+                let name = name.trim().to_string();
+                self.chart_name_input = None;
+                let label = self
+                    .metric_tracker
+                    .get_metrics()
+                    .get(self.metric_detail_index)
+                    .map(|metric| metric.label.clone());
+                if let Some(label) = label.filter(|_| !name.is_empty()) {
+                    self.metric_tracker.add_to_chart(&name, &label);
+                    self.set_status(&format!("Added {} to chart {}", label, name));
+                }
+            }
+            _ => {
+                self.chart_name_cursor.handle_key(name, code, modifiers);
+            }
+        }
+    }
+
+    /// Show the metric charts, starting with the latest one
+    pub fn open_metric_chart(&mut self) {
+        let charts = self.metric_tracker.charts().len();
+        if charts == 0 {
+            self.set_status("No charts - press 'c' on a metric to add it to one");
+            return;
+        }
+        self.metric_chart_index = charts - 1;
+        self.input_mode = InputMode::MetricChart;
+    }
+
+    fn handle_metric_chart_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let charts = self.metric_tracker.charts();
+        let Some(name) = charts
+            .get(self.metric_chart_index)
+            .map(|chart| chart.name.clone())
+        else {
+            self.input_mode = InputMode::MetricDetail;
+            return;
+        };
+        let count = charts.len();
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('v') => {
+                self.input_mode = InputMode::MetricDetail;
+            }
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => {
+                self.metric_chart_index = (self.metric_chart_index + count - 1) % count;
+            }
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
+                self.metric_chart_index = (self.metric_chart_index + 1) % count;
+            }
+            KeyCode::Char('n') => {
+                if let Some(normalized) = self.metric_tracker.toggle_normalized(&name) {
+                    self.set_status(if normalized {
+                        "Each series scaled to its own range"
+                    } else {
+                        "Series share the y-axis"
+                    });
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                self.metric_tracker.remove_chart(&name);
+                self.set_status(&format!("Removed chart {}", name));
+                if count == 1 {
+                    self.input_mode = InputMode::MetricDetail;
+                }
+                self.metric_chart_index = self.metric_chart_index.min(count.saturating_sub(2));
+            }
+            _ => {}
+        }
+    }
+
+    /// Remove a tracked metric
+    pub fn remove_metric(&mut self, label: &str) {
+        self.metric_tracker.untrack(label);
+        self.set_status(&format!("Stopped tracking: {}", label));
+    }
+
+    /// Copy current topic to clipboard
+    pub fn copy_topic(&mut self) {
+        if let Some(topic) = self.selected_topic.clone() {
+            self.copy_text(topic, "Topic");
+        } else {
+            self.set_status("No topic selected");
+        }
+    }
+
+    /// Keep more of the selected topic's messages: ten times the default, a
+    /// hundred times, all of them, then back to the default
+    fn cycle_buffer_limit(&mut self) {
+        let Some(topic) = self.selected_topic.clone() else {
+            self.set_status("No topic selected");
+            return;
+        };
+        let default = self.config.ui.message_buffer_size;
+        let buffer = &mut self.message_buffer;
+        let status = if !buffer.has_override(&topic) {
+            buffer.set_limit(&topic, Some(default * 10));
+            format!("Keeping {} messages for {}", default * 10, topic)
+        } else {
+            match buffer.limit_for(&topic) {
+                Some(limit) if limit < default * 100 => {
+                    buffer.set_limit(&topic, Some(default * 100));
+                    format!("Keeping {} messages for {}", default * 100, topic)
+                }
+                Some(_) => {
+                    buffer.set_limit(&topic, None);
+                    format!(
+                        "Keeping every message for {}; memory grows until K resets it",
+                        topic
+                    )
+                }
+                None => {
+                    buffer.reset_limit(&topic);
+                    format!("Keeping the default {} messages for {}", default, topic)
+                }
+            }
+        };
+        self.set_status(&status);
+    }
+
+    fn copy_text(&mut self, text: String, what: &str) {
+        match arboard::Clipboard::new() {
+            Ok(mut clipboard) => {
+                if clipboard.set_text(text).is_ok() {
+                    self.set_status(&format!("{} copied to clipboard", what));
+                } else {
+                    self.set_status(&format!("Failed to copy {}", what.to_lowercase()));
+                }
+            }
+            Err(_) => self.set_status("Clipboard unavailable"),
+        }
+    }
+
+    fn handle_message_columns_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if let Some(field) = &mut self.column_field_input {
+            match code {
+                KeyCode::Esc => self.column_field_input = None,
+                KeyCode::Enter => {
+                    let field = field.trim().to_string();
+                    self.column_field_input = None;
+                    self.config.ui.message_columns.field = (!field.is_empty()).then_some(field);
+                }
+                _ => {
+                    self.column_field_cursor.handle_key(field, code, modifiers);
+                }
+            }
+            return;
+        }
+
+        let columns = &mut self.config.ui.message_columns;
+        match code {
+            KeyCode::Esc | KeyCode::Char('I') => self.input_mode = InputMode::Normal,
+            KeyCode::Char('t') => columns.timestamp = columns.timestamp.next(),
+            KeyCode::Char('q') => columns.qos = !columns.qos,
+            KeyCode::Char('r') => columns.retain = !columns.retain,
+            KeyCode::Char('s') => columns.size = !columns.size,
+            KeyCode::Char('f') => {
+                let field = columns.field.clone().unwrap_or_default();
+                self.column_field_cursor = InputCursor::end_of(&field);
+                self.column_field_input = Some(field);
+            }
+            KeyCode::Char('x') => columns.field = None,
+            KeyCode::Char('a') => self.config.ui.times = self.config.ui.times.next(),
+            KeyCode::Char('z') => self.config.ui.timezone = self.config.ui.timezone.next(),
+            _ => {}
+        }
+    }
+
+    /// Step away from the broker, leaving a persistent session that queues
+    /// QoS 1+ messages, or come back and collect them
+    fn toggle_away(&mut self) {
+        if self.connected_broker_kind != BrokerKind::Mqtt {
+            self.set_status("Persistent sessions need an MQTT broker");
+            return;
+        }
+        match self.away.as_ref().map(|away| away.phase) {
+            None => {
+                let Some(server) = self.active_mqtt_server() else {
+                    self.set_status("No MQTT server configured");
+                    return;
+                };
+                if self.connection_state != ConnectionState::Connected {
+                    self.set_status("Connect first; the session is left from a live connection");
+                    return;
+                }
+                let away = AwaySession::leave(server, chrono::Utc::now());
+                self.set_status(&format!(
+                    "Opening a persistent session as {}...",
+                    away.client_id
+                ));
+                self.away = Some(away);
+                self.pending_away_connect = true;
+            }
+            Some(AwayPhase::Away) => {
+                if let Some(away) = &mut self.away {
+                    away.phase = AwayPhase::Returning;
+                }
+                self.pending_away_connect = true;
+                self.set_status("Reconnecting to collect queued messages...");
+            }
+            Some(_) => self.set_status("Still switching sessions, try again in a moment"),
+        }
+    }
+
+    /// Active server's settings for the away session (consumed by the main loop)
+    pub fn away_server(&self) -> Option<MqttServerConfig> {
+        let away = self.away.as_ref()?;
+        Some(away.server_config(&self.session_mqtt_server()?))
+    }
+
+    fn on_session_present(&mut self, present: bool) {
+        let Some(away) = self.away.as_mut() else {
+            return;
+        };
+        match away.phase {
+            AwayPhase::Returning if present => away.phase = AwayPhase::Replaying,
+            AwayPhase::Returning => {
+                let status = format!(
+                    "The broker kept no session for {}; nothing was queued",
+                    away.client_id
+                );
+                self.away = None;
+                self.set_status(&status);
+            }
+            // The clean connection replaced the kept session; back to the
+            // server's own settings
+            AwayPhase::Discarding => {
+                self.away = None;
+                self.pending_reconnect = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// The away connection could not be made: give up on the session, and
+    /// reconnect normally if it was only being discarded
+    pub fn away_connect_failed(&mut self, err: &anyhow::Error) {
+        let Some(away) = self.away.take() else {
+            return;
+        };
+        if away.phase == AwayPhase::Discarding {
+            self.pending_reconnect = true;
+            self.set_status(&format!(
+                "Could not drop the session for {}: {:#}",
+                away.client_id, err
+            ));
+        } else {
+            self.set_status(&format!("Persistent session failed: {:#}", err));
+        }
+    }
+
+    /// Keep the probed connection details and warn where the server's settings
+    /// ask for more than the broker advertises
+    fn on_connection_details(&mut self, details: ConnectionDetails) {
+        let conflicts = match (&details.capabilities, self.active_mqtt_server()) {
+            (Some(capabilities), Some(server)) => capabilities.conflicts(server),
+            _ => Vec::new(),
+        };
+        for conflict in &conflicts {
+            tracing::warn!("Broker limit: {}", conflict);
+        }
+        if let Some(first) = conflicts.first() {
+            self.set_status(&format!("Broker limit: {}", first));
+        }
+        self.connection_details = Some(details);
+    }
+
+    /// The subscription is in place: leave once it is part of the session,
+    /// or, coming back, everything queued has arrived and the session can go
+    fn on_subscribed(&mut self) {
+        let now = chrono::Utc::now();
+        match self.away.as_ref().map(|away| away.phase) {
+            Some(AwayPhase::Leaving) => {
+                let Some(away) = &mut self.away else {
+                    return;
+                };
+                away.phase = AwayPhase::Away;
+                away.since = now;
+                let status = format!(
+                    "Away since {}: the broker queues QoS 1+ messages for {}. Z to come back",
+                    self.config.ui.timezone.format(now, "%H:%M:%S"),
+                    away.client_id
+                );
+                self.pending_disconnect = true;
+                self.set_status(&status);
+            }
+            Some(AwayPhase::Replaying) => {
+                let Some(away) = &mut self.away else {
+                    return;
+                };
+                let batch = away.finish(now);
+                self.pending_away_connect = true;
+                self.set_status(&format!(
+                    "{} messages arrived while you were away",
+                    batch.messages.len()
+                ));
+                self.away_batch = Some(batch);
+                self.away_batch_index = 0;
+                if self.input_mode == InputMode::Normal {
+                    self.input_mode = InputMode::AwayBatch;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_away_batch_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let count = self
+            .away_batch
+            .as_ref()
+            .map_or(0, |batch| batch.messages.len());
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => self.input_mode = InputMode::Normal,
+            KeyCode::Down | KeyCode::Char('j') if self.away_batch_index + 1 < count => {
+                self.away_batch_index += 1;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.away_batch_index = self.away_batch_index.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                let topic = self
+                    .away_batch
+                    .as_ref()
+                    .and_then(|batch| batch.messages.get(self.away_batch_index))
+                    .map(|msg| msg.topic.clone());
+                if let Some(topic) = topic {
+                    self.input_mode = InputMode::Normal;
+                    self.jump_to_topic(&topic);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Pick a prefix of the selected topic to copy, filter by or go to
+    fn open_breadcrumb(&mut self) {
+        let Some(topic) = &self.selected_topic else {
+            self.set_status("No topic selected");
+            return;
+        };
+        self.breadcrumb_depth = topic.split(self.topic_tree.separator()).count() - 1;
+        self.input_mode = InputMode::Breadcrumb;
+    }
+
+    /// The selected topic up to the level picked in the breadcrumb
+    pub fn breadcrumb_prefix(&self) -> Option<String> {
+        let topic = self.selected_topic.as_ref()?;
+        let separator = self.topic_tree.separator();
+        let levels: Vec<&str> = topic
+            .split(separator)
+            .take(self.breadcrumb_depth + 1)
+            .collect();
+        Some(levels.join(&separator.to_string()))
+    }
+
+    fn handle_breadcrumb_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let Some(prefix) = self.breadcrumb_prefix() else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        match code {
+            KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('q') => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.breadcrumb_depth = self.breadcrumb_depth.saturating_sub(1);
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                let separator = self.topic_tree.separator();
+                let levels = self
+                    .selected_topic
+                    .as_ref()
+                    .map_or(1, |topic| topic.split(separator).count());
+                self.breadcrumb_depth = (self.breadcrumb_depth + 1).min(levels - 1);
+            }
+            KeyCode::Char('y') => {
+                self.input_mode = InputMode::Normal;
+                self.copy_text(prefix, "Prefix");
+            }
+            KeyCode::Char('f') => {
+                self.input_mode = InputMode::Normal;
+                self.filter_to_subtree(prefix);
+            }
+            KeyCode::Enter => {
+                self.input_mode = InputMode::Normal;
+                self.selected_topic = Some(prefix.clone());
+                self.expand_to_topic(&prefix);
+                self.selected_message_index = 0;
+                self.message_scroll = 0;
+                self.focused_panel = Panel::TopicTree;
+            }
+            _ => {}
+        }
+    }
+
+    /// Copy current payload to clipboard
+    pub fn copy_payload(&mut self) {
+        let messages = self.get_current_messages();
+        if let Some(msg) = messages.first() {
+            let payload = self.format_payload(msg);
+            match arboard::Clipboard::new() {
+                Ok(mut clipboard) => {
+                    if clipboard.set_text(payload).is_ok() {
+                        self.set_status("Payload copied to clipboard");
+                    } else {
+                        self.set_status("Failed to copy payload");
+                    }
+                }
+                Err(_) => self.set_status("Clipboard unavailable"),
+            }
+        } else {
+            self.set_status("No message to copy");
+        }
+    }
+
+    /// Export topics and their latest messages to a text file.
+    /// Respects the active filter when one is set, exports all topics otherwise.
+    pub fn export_topics(&mut self) {
+        if !self.allow(Feature::Recording) {
+            return;
+        }
+        let all_topics = self.topic_tree.get_all_topics();
+        let filter_active = self.topic_filter.is_some();
+        let topics: Vec<String> = if let Some(ref pattern) = self.topic_filter {
+            all_topics
+                .into_iter()
+                .filter(|t| topic_matches(pattern, t))
+                .collect()
+        } else {
+            all_topics
+        };
+
+        if topics.is_empty() {
+            self.set_status("No topics to export");
+            return;
+        }
+
+        let now = chrono::Local::now();
+        let filename = self
+            .paths
+            .cache_dir
+            .join(format!("mqtop-export-{}.txt", now.format("%Y%m%d-%H%M%S")));
+
+        let mut output = String::new();
+        output.push_str(&format!(
+            "# mqtop export - {}\n",
+            now.format("%Y-%m-%d %H:%M:%S")
+        ));
+        if let Some(ref pattern) = self.topic_filter {
+            output.push_str(&format!(
+                "# {} topics (filter: {})\n\n",
+                topics.len(),
+                pattern
+            ));
+        } else {
+            output.push_str(&format!("# {} topics\n\n", topics.len()));
+        }
+
+        for topic in &topics {
+            output.push_str(&format!("--- {} ---\n", topic));
+            if let Some(msg) = self.message_buffer.get_latest(topic) {
+                if let Some(pretty) = msg.payload_json_pretty() {
+                    output.push_str(&pretty);
+                } else if let Some(text) = msg.payload_str() {
+                    output.push_str(text);
+                } else {
+                    output.push_str(&format!("(binary {} bytes)", msg.payload_size()));
+                }
+            } else {
+                output.push_str("(no message)");
+            }
+            output.push_str("\n\n");
+        }
+
+        let status_filter = if filter_active { " (filtered)" } else { "" };
+        match write_new_file(&filename, &output) {
+            Ok(_) => self.set_status(&format!(
+                "Exported {} topics{} to {}",
+                topics.len(),
+                status_filter,
+                filename.display()
+            )),
+            Err(e) => self.set_status(&format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Export the topic hierarchy with per-topic stats.
+    /// Respects the active topic filter and starred-only mode.
+    pub fn export_tree(&mut self, format: TreeExportFormat) {
+        if !self.allow(Feature::Recording) {
+            return;
+        }
+        let starred_only = self.filter_mode == FilterMode::Starred;
+        let include = |topic: &str| {
+            let starred_ok = !starred_only || self.user_data.is_starred(topic);
+            let filter_ok = match &self.topic_filter {
+                Some(pattern) => topic_matches(pattern, topic),
+                None => true,
+            };
+            starred_ok && filter_ok
+        };
+        let output = self.topic_tree.export(format, &include);
+
+        if output.trim().is_empty() || output.trim() == "[]" {
+            self.set_status("No topics to export");
+            return;
+        }
+
+        let filename = self.paths.cache_dir.join(format!(
+            "mqtop-tree-{}.{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        ));
+        let subset = if starred_only || self.topic_filter.is_some() {
+            " (filtered)"
+        } else {
+            ""
+        };
+        match write_new_file(&filename, &output) {
+            Ok(_) => self.set_status(&format!(
+                "Exported topic tree{} to {}",
+                subset,
+                filename.display()
+            )),
+            Err(e) => self.set_status(&format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Save the schemas seen so far for later use as a drift baseline
+    pub fn export_schema_baseline(&mut self) {
+        let baseline = self.schema_tracker.export_baseline();
+        if baseline.schemas.is_empty() {
+            self.set_status("No JSON topics seen yet");
+            return;
+        }
+        let dir = &self.paths.cache_dir;
+        let filename = dir.join(SchemaBaseline::default_filename());
+        let result = std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {:?}", dir))
+            .and_then(|_| baseline.save_to(&filename));
+        match result {
+            Ok(()) => self.set_status(&format!(
+                "Saved schema baseline of {} topics to {}",
+                baseline.schemas.len(),
+                filename.display()
+            )),
+            Err(e) => self.set_status(&format!("Export failed: {:#}", e)),
+        }
+    }
+
+    /// Write a skeleton AsyncAPI document describing the topics seen so far
+    pub fn export_asyncapi(&mut self) {
+        let topics = self.topic_tree.get_all_topics();
+        let observed: Vec<ObservedTopic> = topics
+            .iter()
+            .map(|topic| ObservedTopic {
+                topic,
+                schema: self.schema_tracker.get_schema(topic),
+                example: self
+                    .message_buffer
+                    .get_latest(topic)
+                    .map(|msg| &msg.payload[..]),
+            })
+            .collect();
+        if observed.is_empty() {
+            self.set_status("No topics to export");
+            return;
+        }
+        let title = match self.active_server_info() {
+            Some(server) => format!("{} ({}:{})", server.name, server.host, server.port),
+            None => "Observed topics".to_string(),
+        };
+        let filename = self
+            .paths
+            .cache_dir
+            .join(asyncapi::default_export_filename());
+        let result =
+            asyncapi::generate(&title, self.topic_tree.separator(), &observed).and_then(|yaml| {
+                write_new_file(&filename, &yaml)
+                    .with_context(|| format!("Failed to write {}", filename.display()))
+            });
+        match result {
+            Ok(()) => self.set_status(&format!(
+                "Saved AsyncAPI skeleton of {} topics to {}",
+                observed.len(),
+                filename.display()
+            )),
+            Err(e) => self.set_status(&format!("Export failed: {:#}", e)),
+        }
+    }
+
+    /// Check live traffic against a saved schema baseline
+    pub fn load_schema_baseline(&mut self, path: &Path) -> Result<()> {
+        let baseline = SchemaBaseline::load_from(path)?;
+        tracing::info!(
+            "Loaded schema baseline of {} topics from {:?}",
+            baseline.schemas.len(),
+            path
+        );
+        self.schema_tracker.set_baseline(baseline);
+        Ok(())
+    }
+
+    pub fn load_asyncapi(&mut self, path: &Path) -> Result<()> {
+        let spec = AsyncApiSpec::load_from(path)?;
+        tracing::info!(
+            "Loaded AsyncAPI spec '{}' with {} channels from {:?}",
+            spec.title,
+            spec.channels.len(),
+            path
+        );
+        self.asyncapi = Some(spec);
+        self.spec_coverage = SpecCoverage::default();
+        Ok(())
+    }
+
+    pub fn load_friendly_names(&mut self, path: &Path) -> Result<()> {
+        let names = FriendlyNames::load_from(path)?;
+        tracing::info!("Loaded {} friendly names from {:?}", names.len(), path);
+        self.friendly_names.extend(names);
+        Ok(())
+    }
+
+    /// A topic or device ID as displayed, with friendly names for its levels
+    pub fn friendly_label<'a>(&self, topic: &'a str) -> Cow<'a, str> {
+        self.friendly_names.label_topic(
+            topic,
+            self.topic_tree.separator(),
+            self.config.ui.replace_ids,
+        )
+    }
+
+    /// Show the AsyncAPI documentation for the selected topic
+    fn open_api_docs(&mut self) {
+        if self.asyncapi.is_none() {
+            self.set_status("No AsyncAPI spec loaded (start with --asyncapi <file>)");
+        } else if self.selected_topic.is_none() {
+            self.set_status("No topic selected");
+        } else {
+            self.api_docs_scroll = 0;
+            self.input_mode = InputMode::ApiDocs;
+        }
+    }
+
+    fn handle_api_docs_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('A') | KeyCode::Char('q') => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.api_docs_scroll = self.api_docs_scroll.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.api_docs_scroll += 1,
+            KeyCode::PageUp => self.api_docs_scroll = self.api_docs_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.api_docs_scroll += 10,
+            KeyCode::Home | KeyCode::Char('g') => self.api_docs_scroll = 0,
+            _ => {}
+        }
+    }
+
+    /// Show today's or this week's traffic per prefix against earlier days
+    fn open_traffic_summary(&mut self) {
+        if !self.config.ui.persist_topic_stats {
+            self.set_status("Traffic summaries need persist_topic_stats = true under [ui]");
+        } else if self.topic_totals_path.is_none() {
+            self.set_status("Connect to a server to see its traffic summary");
+        } else {
+            self.traffic_summary_scroll = 0;
+            self.input_mode = InputMode::TrafficSummary;
+        }
+    }
+
+    /// The summary shown in the traffic summary popup
+    pub fn traffic_summary(&self) -> TrafficSummary {
+        let (period, baseline) = if self.traffic_summary_weekly {
+            WEEKLY_SUMMARY
+        } else {
+            DAILY_SUMMARY
+        };
+        self.topic_totals
+            .summary(chrono::Local::now().date_naive(), period, baseline)
+    }
+
+    fn handle_traffic_summary_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('V') | KeyCode::Char('q') => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Char('w') => {
+                self.traffic_summary_weekly = !self.traffic_summary_weekly;
+                self.traffic_summary_scroll = 0;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.traffic_summary_scroll = self.traffic_summary_scroll.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.traffic_summary_scroll += 1,
+            KeyCode::PageUp => {
+                self.traffic_summary_scroll = self.traffic_summary_scroll.saturating_sub(10)
+            }
+            KeyCode::PageDown => self.traffic_summary_scroll += 10,
+            KeyCode::Home | KeyCode::Char('g') => self.traffic_summary_scroll = 0,
+            _ => {}
+        }
+    }
+
+    fn handle_tree_export_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let format = match code {
+            KeyCode::Char('t') => TreeExportFormat::Text,
+            KeyCode::Char('j') => TreeExportFormat::Json,
+            KeyCode::Char('d') => TreeExportFormat::Dot,
+            KeyCode::Char('s') => {
+                self.input_mode = InputMode::Normal;
+                self.export_schema_baseline();
+                return;
+            }
+            KeyCode::Char('a') => {
+                self.input_mode = InputMode::Normal;
+                self.export_asyncapi();
+                return;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                return;
+            }
+            _ => return,
+        };
+        self.input_mode = InputMode::Normal;
+        self.export_tree(format);
+    }
+
+    fn handle_log_view_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let max_scroll = self.log_buffer.len().saturating_sub(1);
+        match code {
+            KeyCode::Esc | KeyCode::Char('D') | KeyCode::Char('q') => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.log_scroll = (self.log_scroll + 1).min(max_scroll);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.log_scroll = self.log_scroll.saturating_sub(1);
+            }
+            KeyCode::PageUp => self.log_scroll = (self.log_scroll + 10).min(max_scroll),
+            KeyCode::PageDown => self.log_scroll = self.log_scroll.saturating_sub(10),
+            KeyCode::Home | KeyCode::Char('g') => self.log_scroll = max_scroll,
+            KeyCode::End | KeyCode::Char('G') => self.log_scroll = 0,
+            _ => {}
+        }
+    }
+
+    /// Unix millis before which a topic counts as dead, if a TTL is configured
+    pub fn topic_ttl_cutoff(&self) -> Option<i64> {
+        let ttl_mins = self.config.ui.topic_ttl_mins;
+        (ttl_mins > 0).then(|| {
+            chrono::Utc::now().timestamp_millis() - (ttl_mins as i64).saturating_mul(60_000)
+        })
+    }
+
+    /// Periodically drop topics past their TTL, with their buffered messages
+    pub fn prune_dead_topics(&mut self) {
+        if !self.config.ui.prune_dead_topics || self.last_prune.elapsed() < PRUNE_INTERVAL {
+            return;
+        }
+        self.last_prune = Instant::now();
+        let Some(cutoff) = self.topic_ttl_cutoff() else {
+            return;
+        };
+
+        let removed = self.topic_tree.prune_inactive(cutoff);
+        if removed.is_empty() {
+            return;
+        }
+        for topic in &removed {
+            self.message_buffer.clear_topic(topic);
+            self.expanded_topics.remove(topic);
+        }
+        self.pruned_topics += removed.len() as u64;
+        tracing::debug!("Pruned {} dead topics", removed.len());
+
+        // Keep the selected topic if it survived, else select what took its row
+        let visible = self.get_visible_topics();
+        let kept = self.selected_topic.as_ref().and_then(|selected| {
+            visible
+                .iter()
+                .position(|topic| &topic.full_path == selected)
+        });
+        self.tree_anchor = None;
+        match kept {
+            Some(index) => self.selected_topic_index = index,
+            None if visible.is_empty() => {
+                self.selected_topic_index = 0;
+                self.selected_topic = None;
+            }
+            None => {
+                self.selected_topic_index = self.selected_topic_index.min(visible.len() - 1);
+                self.update_selected_topic();
+            }
+        }
+    }
+
+    /// Active server's subscription pattern
+    fn active_subscription(&self) -> Option<&str> {
+        match self.connected_broker_kind {
+            BrokerKind::Mqtt => self.active_mqtt_server().map(|s| {
+                self.narrowed_for(BrokerKind::Mqtt, &s.name)
+                    .unwrap_or(effective_filter(&s.subscribe_topic))
+            }),
+            BrokerKind::Nats => self.active_nats_server().map(|s| {
+                self.narrowed_for(BrokerKind::Nats, &s.name)
+                    .unwrap_or(s.subscribe_subject.as_str())
+            }),
+        }
+    }
+
+    /// Starred topics and bookmark topics the active subscription never
+    /// receives, tagged with where they come from, after the number checked.
+    /// `None` when there is nothing to check.
+    pub fn subscription_gaps(&self) -> Option<(usize, Vec<(String, &'static str)>)> {
+        let filter = self.active_subscription()?;
+        let separator = self.topic_tree.separator();
+        let mut checked = 0;
+        let mut gaps = Vec::new();
+        for topic in self.starred_topics() {
+            checked += 1;
+            if !reaches(filter, &topic, separator) {
+                gaps.push((topic, "starred"));
+            }
+        }
+        let mut seen = HashSet::new();
+        for bookmark in &self.user_data.bookmarks {
+            if !seen.insert(bookmark.topic.as_str()) {
+                continue;
+            }
+            checked += 1;
+            if !covers(filter, &bookmark.topic) {
+                gaps.push((bookmark.topic.clone(), "bookmark"));
+            }
+        }
+        (checked > 0).then_some((checked, gaps))
+    }
+
+    /// Warn once per server when a catch-all subscription starts faster than
+    /// `ui.subscribe_warn_rate`, suggesting narrower subscriptions
+    pub fn check_subscribe_rate(&mut self) {
+        let threshold = self.config.ui.subscribe_warn_rate;
+        let Some((since, base)) = self.subscribe_watch else {
+            return;
+        };
+        let elapsed = since.elapsed();
+        if elapsed < SUBSCRIBE_WARN_SETTLE || self.input_mode != InputMode::Normal {
+            return;
+        }
+
+        let kind = self.connected_broker_kind;
+        let catch_all = self.active_subscription() == Some(kind.default_subscribe_pattern());
+        if threshold == 0 || !catch_all || elapsed > SUBSCRIBE_WARN_WINDOW {
+            self.subscribe_watch = None;
+            self.subscribe_checked = true;
+            return;
+        }
+
+        let received = self.stats.total_messages().saturating_sub(base);
+        let rate = received as f64 / elapsed.as_secs_f64();
+        if rate < threshold as f64 {
+            return;
+        }
+
+        let suggestions = self
+            .stats
+            .top_prefixes(5)
+            .into_iter()
+            .filter(|usage| !usage.prefix.is_empty())
+            .map(|usage| {
+                format!(
+                    "{}{}{}",
+                    usage.prefix,
+                    kind.topic_separator(),
+                    kind.wildcard_multi()
+                )
+            })
+            .collect();
+        self.subscribe_warning = Some(SubscribeWarning { rate, suggestions });
+        self.subscribe_watch = None;
+        self.subscribe_checked = true;
+        self.input_mode = InputMode::SubscribeWarning;
+    }
+
+    fn handle_subscribe_warning_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('k') => {
+                self.subscribe_warning = None;
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                let topic = self
+                    .subscribe_warning
+                    .as_ref()
+                    .and_then(|w| w.suggestions.get(index))
+                    .cloned();
+                if let Some(topic) = topic {
+                    self.subscribe_warning = None;
+                    self.input_mode = InputMode::Normal;
+                    if let Err(e) = self.narrow_subscription(&topic) {
+                        self.set_status(&format!("Failed to narrow subscription: {}", e));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Offer to collapse or hide a branch that gained too many children,
+    /// or just note it when another dialog is open
+    fn report_cardinality(&mut self, branch: String, added: usize) {
+        let window = self.config.ui.cardinality_window_secs;
+        tracing::warn!("{} gained {} topics in {}s", branch, added, window);
+        if self.input_mode == InputMode::Normal {
+            self.cardinality_alert = Some(CardinalityAlert { branch, added });
+            self.input_mode = InputMode::CardinalityAlert;
+        } else {
+            self.set_status(&format!(
+                "Topic explosion: {} gained {} topics in {}s",
+                branch, added, window
+            ));
+        }
+    }
+
+    fn handle_cardinality_alert_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let Some(alert) = self.cardinality_alert.clone() else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        match code {
+            KeyCode::Char('c') => {
+                for path in self.topic_tree.expandable_paths_from(&alert.branch) {
+                    self.expanded_topics.remove(&path);
+                }
+                self.set_status(&format!("Collapsed {}", alert.branch));
+            }
+            KeyCode::Char('-') => self.hide_topic(alert.branch, false),
+            KeyCode::Char('_') => self.hide_topic(alert.branch, true),
+            KeyCode::Esc => {}
+            _ => return,
+        }
+        self.cardinality_alert = None;
+        self.input_mode = InputMode::Normal;
+        let visible = self.get_visible_topics().len();
+        self.selected_topic_index = self.selected_topic_index.min(visible.saturating_sub(1));
+        self.update_selected_topic();
+    }
+
+    /// Resubscribe the active server to `topic` for this session, leaving
+    /// the saved config alone
+    fn narrow_subscription(&mut self, topic: &str) -> Result<()> {
+        let kind = self.connected_broker_kind;
+        let (index, name) = match kind {
+            BrokerKind::Mqtt => {
+                let index = self
+                    .config
+                    .mqtt
+                    .active_index()
+                    .context("No active MQTT server")?;
+                (index, &self.config.mqtt.servers[index].name)
+            }
+            BrokerKind::Nats => {
+                let index = self
+                    .config
+                    .nats
+                    .active_index()
+                    .context("No active NATS server")?;
+                (index, &self.config.nats.servers[index].name)
+            }
+        };
+        self.narrowed_subscription = Some((server_key(kind, name), topic.to_string()));
+        self.pending_server_switch = Some(PendingServerSwitch { kind, index });
+        self.set_status(&format!(
+            "Resubscribing to {} for this session (config unchanged)",
+            topic
+        ));
+        Ok(())
+    }
+
+    /// Subscription narrowed for this session on the given server
+    fn narrowed_for(&self, kind: BrokerKind, name: &str) -> Option<&str> {
+        self.narrowed_subscription
+            .as_ref()
+            .filter(|(server, _)| *server == server_key(kind, name))
+            .map(|(_, topic)| topic.as_str())
+    }
+
+    /// The active MQTT server's settings to connect with, including a
+    /// subscription narrowed for this session
+    pub fn session_mqtt_server(&self) -> Option<MqttServerConfig> {
+        let mut server = self.active_mqtt_server()?.clone();
+        if let Some(topic) = self.narrowed_for(BrokerKind::Mqtt, &server.name) {
+            server.subscribe_topic = topic.to_string();
+        }
+        Some(server)
+    }
+
+    /// The active NATS server's settings to connect with, including a
+    /// subject narrowed for this session
+    pub fn session_nats_server(&self) -> Option<NatsServerConfig> {
+        let mut server = self.active_nats_server()?.clone();
+        if let Some(subject) = self.narrowed_for(BrokerKind::Nats, &server.name) {
+            server.subscribe_subject = subject.to_string();
+        }
+        Some(server)
+    }
+
+    /// Write a captured frame as plain text and ANSI files, and copy the text to the clipboard
+    pub fn save_screenshot(&mut self, text: &str, ansi: &str) {
+        let stem = self.paths.cache_dir.join(format!(
+            "mqtop-screenshot-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let text_path = stem.with_extension("txt");
+        let ansi_path = stem.with_extension("ans");
+
+        if let Err(e) =
+            write_new_file(&text_path, text).and_then(|_| write_new_file(&ansi_path, ansi))
+        {
+            self.set_status(&format!("Screenshot failed: {}", e));
+            return;
+        }
+
+        let copied = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text.to_string()))
+            .is_ok();
+        self.set_status(&format!(
+            "Screenshot saved to {} (.txt/.ans){}",
+            stem.display(),
+            if copied { ", copied to clipboard" } else { "" }
+        ));
+    }
+
+    fn handle_filter_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.filter_input.clear();
+                self.filter_preview_at = None;
+                if let Some(before) = self.filter_before.take() {
+                    self.restore_filter_before(before);
+                }
+            }
+            KeyCode::Enter => {
+                self.filter_preview_at = None;
+                self.filter_before = None;
+                if self.filter_input.is_empty() {
+                    self.topic_filter = None;
+                    self.set_status("Filter cleared");
+                } else {
+                    self.topic_filter = Some(self.filter_input.clone());
                     self.set_status(&format!("Filter: {}", self.filter_input));
+                    let filter = self.filter_input.clone();
+                    self.record_history(|history| history.filters.push(filter));
                 }
                 self.input_mode = InputMode::Normal;
                 self.filter_input.clear();
                 self.reset_tree_selection();
             }
-            KeyCode::Backspace => {
-                self.filter_input.pop();
+            KeyCode::Up | KeyCode::Down => {
+                let entry = if code == KeyCode::Up {
+                    self.history.filters.older(&self.filter_input)
+                } else {
+                    self.history.filters.newer()
+                };
+                if let Some(filter) = entry {
+                    self.filter_cursor = InputCursor::end_of(&filter);
+                    self.filter_input = filter;
+                }
+            }
+            KeyCode::Tab => {
+                let completed = self.complete_topic(&self.filter_input, self.filter_cursor);
+                if let Some(completed) = completed {
+                    self.filter_cursor = InputCursor::end_of(&completed);
+                    self.filter_input = completed;
+                }
             }
-            KeyCode::Char(c) => {
-                self.filter_input.push(c);
+            _ => {
+                self.filter_cursor
+                    .handle_key(&mut self.filter_input, code, modifiers);
             }
-            _ => {}
+        }
+        if self.input_mode == InputMode::Filter {
+            self.filter_preview_at = Some(Instant::now() + FILTER_PREVIEW_DELAY);
+        }
+    }
+
+    /// Apply the filter being typed to the tree once typing pauses
+    pub fn preview_filter(&mut self) {
+        if matches!(self.filter_preview_at, Some(at) if Instant::now() >= at) {
+            self.apply_filter_preview();
+        }
+    }
+
+    /// Show the tree filtered by the pattern being typed and count the
+    /// topics it matches
+    fn apply_filter_preview(&mut self) {
+        self.filter_preview_at = None;
+        let filter = (!self.filter_input.is_empty()).then(|| self.filter_input.clone());
+        let tree = if self.group_templates {
+            &self.grouped_tree
+        } else {
+            &self.topic_tree
+        };
+        let mut topics = tree.get_all_topics();
+        topics.retain(|topic| self.shows_topic(topic));
+        let matches = match &filter {
+            Some(pattern) => topics.iter().filter(|t| topic_matches(pattern, t)).count(),
+            None => topics.len(),
+        };
+        self.filter_matches = (matches, topics.len());
+        if filter != self.topic_filter {
+            self.topic_filter = filter;
+            self.reset_tree_selection();
+        }
+    }
+
+    /// Put back the filter, selection and scroll a cancelled filter dialog
+    /// started from, following the selected topic if rows moved meanwhile
+    fn restore_filter_before(&mut self, before: FilterBefore) {
+        self.topic_filter = before.filter;
+        self.tree_anchor = None;
+        let visible = self.get_visible_topics();
+        let kept = before.selected_topic.as_ref().and_then(|selected| {
+            visible
+                .iter()
+                .position(|topic| &topic.full_path == selected)
+        });
+        match kept {
+            Some(index) => {
+                self.selected_topic_index = index;
+                self.selected_topic = before.selected_topic;
+            }
+            None => {
+                self.selected_topic_index =
+                    before.selected_index.min(visible.len().saturating_sub(1));
+                self.update_selected_topic();
+            }
+        }
+        self.tree_scroll = before.scroll;
+    }
+
+    /// Topics from the live tree that complete `value`, while its cursor is at the end
+    pub fn topic_completions(&self, value: &str, cursor: InputCursor) -> Vec<String> {
+        if cursor.pos() != value.len() || cursor.selection(value).is_some() {
+            return Vec::new();
+        }
+        self.topic_tree
+            .complete(value, self.connected_broker_kind.wildcard_single())
+    }
+
+    /// `value` extended to the longest prefix shared by its completions, or
+    /// `None` when Tab has nothing to add
+    fn complete_topic(&self, value: &str, cursor: InputCursor) -> Option<String> {
+        let completions = self.topic_completions(value, cursor);
+        let (first, rest) = completions.split_first()?;
+        let mut common = first.as_str();
+        for other in rest {
+            let len = common
+                .char_indices()
+                .zip(other.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(other.len()), |((idx, _), _)| idx);
+            common = &common[..len];
+        }
+        (common.len() > value.len()).then(|| common.to_string())
+    }
+
+    /// Filter the tree to `prefix` and everything under it, keeping the
+    /// current topic selected
+    fn filter_to_subtree(&mut self, prefix: String) {
+        let filter = format!(
+            "{}{}{}",
+            prefix,
+            self.topic_tree.separator(),
+            self.connected_broker_kind.wildcard_multi()
+        );
+        self.expanded_topics.insert(prefix);
+        self.topic_filter = Some(filter.clone());
+        self.set_status(&format!("Filter: {} (F to clear)", filter));
+        match self.selected_topic.clone() {
+            Some(topic) => self.expand_to_topic(&topic),
+            None => self.reset_tree_selection(),
         }
     }
 
@@ -933,34 +3560,127 @@ impl App {
         self.set_status("Server manager");
     }
 
+    /// Quick switcher over the configured servers, numbered MQTT first
+    pub fn open_server_switch(&mut self) {
+        let servers = self.switchable_servers();
+        if servers.len() < 2 {
+            self.set_status("No other server configured - press 'S' to add one");
+            return;
+        }
+        let active = self.active_server_info();
+        self.server_switch_index = servers
+            .iter()
+            .position(|(_, server)| {
+                active
+                    .as_ref()
+                    .is_some_and(|a| a.kind == server.kind && a.name == server.name)
+            })
+            .unwrap_or_default();
+        self.input_mode = InputMode::ServerSwitch;
+    }
+
+    /// Every configured server with its index in its own protocol's list
+    pub fn switchable_servers(&self) -> Vec<(usize, ActiveServerInfo)> {
+        let mqtt = self.config.mqtt.servers.iter().enumerate().map(|(i, s)| {
+            let info = ActiveServerInfo {
+                kind: BrokerKind::Mqtt,
+                name: s.name.clone(),
+                host: s.host.clone(),
+                port: s.port,
+            };
+            (i, info)
+        });
+        let nats = self.config.nats.servers.iter().enumerate().map(|(i, s)| {
+            let info = ActiveServerInfo {
+                kind: BrokerKind::Nats,
+                name: s.name.clone(),
+                host: s.host.clone(),
+                port: s.port,
+            };
+            (i, info)
+        });
+        mqtt.chain(nats).collect()
+    }
+
+    fn handle_server_switch_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+        let servers = self.switchable_servers();
+        let pick = match code {
+            KeyCode::Esc | KeyCode::Char('@') | KeyCode::Char('q') => {
+                self.input_mode = InputMode::Normal;
+                None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.server_switch_index = self.server_switch_index.saturating_sub(1);
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.server_switch_index + 1 < servers.len() {
+                    self.server_switch_index += 1;
+                }
+                None
+            }
+            KeyCode::Enter => servers.get(self.server_switch_index),
+            KeyCode::Char(c @ '1'..='9') => servers.get(c as usize - '1' as usize),
+            _ => None,
+        };
+        let Some((index, server)) = pick else {
+            return;
+        };
+
+        self.input_mode = InputMode::Normal;
+        let current = self.active_server_info();
+        if self.session_source.is_none()
+            && current.is_some_and(|c| c.kind == server.kind && c.name == server.name)
+        {
+            self.set_status(&format!("Already on {}", server.name));
+            return;
+        }
+        self.pending_server_switch = Some(PendingServerSwitch {
+            kind: server.kind,
+            index: *index,
+        });
+    }
+
     /// Open publish dialog with empty fields
     pub fn open_publish_dialog(&mut self) {
+        if !self.allow(Feature::Publish) {
+            return;
+        }
         self.publish_edit = PublishEditState {
             active: true,
             field: PublishField::Topic,
-            cursor: 0,
+            cursor: InputCursor::default(),
             topic: self.selected_topic.clone().unwrap_or_default(),
             payload: String::new(),
             qos: 0,
             retain: false,
+            response: String::new(),
+            preview: None,
         };
-        self.publish_edit.cursor = self.publish_edit.topic.len();
+        self.publish_edit.cursor = InputCursor::end_of(&self.publish_edit.topic);
+        self.history.publishes.reset();
         self.input_mode = InputMode::Publish;
     }
 
     /// Copy current message to publish dialog
     pub fn copy_message_to_publish(&mut self) {
+        if !self.allow(Feature::Publish) {
+            return;
+        }
         let messages = self.get_current_messages();
         if let Some(msg) = messages.first() {
             self.publish_edit = PublishEditState {
                 active: true,
                 field: PublishField::Topic,
-                cursor: msg.topic.len(),
+                cursor: InputCursor::end_of(&msg.topic),
                 topic: msg.topic.clone(),
                 payload: self.format_payload(msg),
                 qos: msg.qos,
                 retain: msg.retain,
+                response: String::new(),
+                preview: None,
             };
+            self.history.publishes.reset();
             self.input_mode = InputMode::Publish;
             self.set_status("Message copied to publish");
         } else {
@@ -974,6 +3694,27 @@ impl App {
             self.save_publish_as_bookmark();
             return;
         }
+        // Ctrl+D toggles the dry-run preview
+        if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('d') {
+            if self.publish_edit.preview.take().is_none() {
+                self.preview_publish();
+            }
+            return;
+        }
+        if let Some(preview) = &mut self.publish_edit.preview {
+            match code {
+                KeyCode::Enter => self.send_publish_edit(),
+                KeyCode::Esc => self.publish_edit.preview = None,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    preview.scroll = preview.scroll.saturating_sub(1)
+                }
+                KeyCode::Down | KeyCode::Char('j') => preview.scroll += 1,
+                KeyCode::PageUp => preview.scroll = preview.scroll.saturating_sub(10),
+                KeyCode::PageDown => preview.scroll += 10,
+                _ => {}
+            }
+            return;
+        }
 
         match code {
             KeyCode::Esc => {
@@ -981,44 +3722,30 @@ impl App {
                 self.publish_edit.active = false;
             }
             KeyCode::Enter => {
-                // Validate and create pending publish
-                if self.publish_edit.topic.trim().is_empty() {
-                    self.set_status("Topic cannot be empty");
-                    return;
+                if self.config.ui.preview_publish {
+                    self.preview_publish();
+                } else {
+                    self.send_publish_edit();
                 }
-                self.pending_publish = Some(PendingPublish {
-                    topic: self.publish_edit.topic.trim().to_string(),
-                    payload: self.publish_edit.payload.as_bytes().to_vec(),
-                    qos: self.publish_edit.qos,
-                    retain: self.publish_edit.retain,
-                });
-                self.input_mode = InputMode::Normal;
-                self.publish_edit.active = false;
             }
+            KeyCode::Up | KeyCode::Down => self.publish_history(code == KeyCode::Up),
             KeyCode::Tab => {
+                if self.publish_edit.field == PublishField::Topic {
+                    let edit = &self.publish_edit;
+                    if let Some(completed) = self.complete_topic(&edit.topic, edit.cursor) {
+                        self.publish_edit.cursor = InputCursor::end_of(&completed);
+                        self.publish_edit.topic = completed;
+                        return;
+                    }
+                }
                 self.publish_edit.field = self.next_publish_field(self.publish_edit.field);
-                self.publish_edit.cursor = self.publish_field_value(self.publish_edit.field).len();
+                self.publish_edit.cursor =
+                    InputCursor::end_of(&self.publish_field_value(self.publish_edit.field));
             }
             KeyCode::BackTab => {
                 self.publish_edit.field = self.prev_publish_field(self.publish_edit.field);
-                self.publish_edit.cursor = self.publish_field_value(self.publish_edit.field).len();
-            }
-            KeyCode::Left => {
-                if self.publish_edit.cursor > 0 {
-                    self.publish_edit.cursor -= 1;
-                }
-            }
-            KeyCode::Right => {
-                let max = self.publish_field_value(self.publish_edit.field).len();
-                if self.publish_edit.cursor < max {
-                    self.publish_edit.cursor += 1;
-                }
-            }
-            KeyCode::Home => {
-                self.publish_edit.cursor = 0;
-            }
-            KeyCode::End => {
-                self.publish_edit.cursor = self.publish_field_value(self.publish_edit.field).len();
+                self.publish_edit.cursor =
+                    InputCursor::end_of(&self.publish_field_value(self.publish_edit.field));
             }
             // QoS field: 0, 1, 2 to set directly, space to cycle
             KeyCode::Char('0') if self.publish_edit.field == PublishField::Qos => {
@@ -1037,79 +3764,115 @@ impl App {
             KeyCode::Char(' ') if self.publish_edit.field == PublishField::Retain => {
                 self.publish_edit.retain = !self.publish_edit.retain;
             }
-            KeyCode::Backspace => {
-                if matches!(
-                    self.publish_edit.field,
-                    PublishField::Topic | PublishField::Payload
-                ) {
-                    self.publish_edit_backspace();
-                }
-            }
-            KeyCode::Delete => {
+            _ => {
                 if matches!(
                     self.publish_edit.field,
-                    PublishField::Topic | PublishField::Payload
+                    PublishField::Topic | PublishField::Payload | PublishField::Response
                 ) {
-                    self.publish_edit_delete();
+                    let mut cursor = self.publish_edit.cursor;
+                    cursor.handle_key(self.publish_edit_mut_field(), code, modifiers);
+                    self.publish_edit.cursor = cursor;
                 }
             }
-            KeyCode::Char(c) => {
-                if matches!(
-                    self.publish_edit.field,
-                    PublishField::Topic | PublishField::Payload
-                ) {
-                    self.publish_edit_insert(c);
-                }
-            }
-            _ => {}
         }
     }
 
-    fn publish_edit_mut_field(&mut self) -> &mut String {
-        match self.publish_edit.field {
-            PublishField::Topic => &mut self.publish_edit.topic,
-            PublishField::Payload => &mut self.publish_edit.payload,
-            _ => &mut self.publish_edit.topic, // dummy for non-text fields
+    /// Validate the publish dialog and queue it for sending
+    fn send_publish_edit(&mut self) {
+        if self.publish_edit.topic.trim().is_empty() {
+            self.publish_edit.preview = None;
+            self.set_status("Topic cannot be empty");
+            return;
+        }
+        let response = match ResponseSpec::parse(&self.publish_edit.response) {
+            Ok(response) => response,
+            Err(err) => {
+                self.publish_edit.preview = None;
+                self.publish_edit.field = PublishField::Response;
+                self.publish_edit.cursor = InputCursor::end_of(&self.publish_edit.response);
+                self.set_status(&format!("Response: {}", err));
+                return;
+            }
+        };
+        let recent = self.recent_publish();
+        self.record_history(|history| history.publishes.push(recent));
+        self.pending_publish = Some(PendingPublish {
+            topic: self.publish_edit.topic.trim().to_string(),
+            payload: self.publish_edit.payload.as_bytes().to_vec(),
+            qos: self.publish_edit.qos,
+            retain: self.publish_edit.retain,
+            response,
+        });
+        self.input_mode = InputMode::Normal;
+        self.publish_edit.active = false;
+        self.publish_edit.preview = None;
+    }
+
+    fn recent_publish(&self) -> RecentPublish {
+        RecentPublish {
+            topic: self.publish_edit.topic.trim().to_string(),
+            payload: self.publish_edit.payload.clone(),
+            qos: self.publish_edit.qos,
+            retain: self.publish_edit.retain,
         }
     }
 
-    fn publish_edit_insert(&mut self, ch: char) {
-        let mut cursor = self.publish_edit.cursor;
-        let value = self.publish_edit_mut_field();
-        if cursor > value.len() {
-            cursor = value.len();
+    /// Fill the publish dialog from history, older or newer
+    fn publish_history(&mut self, older: bool) {
+        let entry = if older {
+            let current = self.recent_publish();
+            self.history.publishes.older(&current)
+        } else {
+            self.history.publishes.newer()
+        };
+        if let Some(recent) = entry {
+            let edit = &mut self.publish_edit;
+            edit.topic = recent.topic;
+            edit.payload = recent.payload;
+            edit.qos = recent.qos;
+            edit.retain = recent.retain;
+            let value = self.publish_field_value(self.publish_edit.field);
+            self.publish_edit.cursor = InputCursor::end_of(&value);
         }
-        value.insert(cursor, ch);
-        self.publish_edit.cursor = cursor.saturating_add(1);
     }
 
-    fn publish_edit_backspace(&mut self) {
-        let mut cursor = self.publish_edit.cursor;
-        let value = self.publish_edit_mut_field();
-        if cursor == 0 || value.is_empty() {
+    /// Lint the publish dialog's payload and diff it against the topic's last message
+    fn preview_publish(&mut self) {
+        let topic = self.publish_edit.topic.trim();
+        if topic.is_empty() {
+            self.set_status("Topic cannot be empty");
             return;
         }
-        if cursor > value.len() {
-            cursor = value.len();
-        }
-        let remove_at = cursor.saturating_sub(1);
-        value.remove(remove_at);
-        self.publish_edit.cursor = cursor.saturating_sub(1);
+        // Only MQTT brokers enforce a packet size limit we know about
+        let max_bytes = match self.connected_broker_kind {
+            BrokerKind::Mqtt => self
+                .active_mqtt_server()
+                .map(|server| server.tuning().max_packet_size.saturating_sub(topic.len())),
+            BrokerKind::Nats => None,
+        };
+        self.publish_edit.preview = Some(PublishPreview::build(
+            &self.publish_edit.payload,
+            self.publish_edit.retain,
+            self.message_buffer.get_latest(topic),
+            self.schema_tracker.get_schema(topic),
+            max_bytes,
+        ));
     }
 
-    fn publish_edit_delete(&mut self) {
-        let cursor = self.publish_edit.cursor;
-        let value = self.publish_edit_mut_field();
-        if value.is_empty() || cursor >= value.len() {
-            return;
+    fn publish_edit_mut_field(&mut self) -> &mut String {
+        match self.publish_edit.field {
+            PublishField::Topic => &mut self.publish_edit.topic,
+            PublishField::Payload => &mut self.publish_edit.payload,
+            PublishField::Response => &mut self.publish_edit.response,
+            _ => &mut self.publish_edit.topic, // dummy for non-text fields
         }
-        value.remove(cursor);
     }
 
     pub fn publish_field_value(&self, field: PublishField) -> String {
         match field {
             PublishField::Topic => self.publish_edit.topic.clone(),
             PublishField::Payload => self.publish_edit.payload.clone(),
+            PublishField::Response => self.publish_edit.response.clone(),
             PublishField::Qos => self.publish_edit.qos.to_string(),
             PublishField::Retain => if self.publish_edit.retain {
                 "on"
@@ -1138,7 +3901,21 @@ impl App {
         PublishField::ALL[prev]
     }
 
-    fn handle_search_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+    /// Step through search history, older or newer
+    fn search_history(&mut self, older: bool) {
+        let entry = if older {
+            self.history.searches.older(&self.search_query)
+        } else {
+            self.history.searches.newer()
+        };
+        if let Some(query) = entry {
+            self.search_cursor = InputCursor::end_of(&query);
+            self.search_query = query;
+            self.update_search_results();
+        }
+    }
+
+    fn handle_search_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
         match code {
             KeyCode::Esc => {
                 self.input_mode = InputMode::Normal;
@@ -1147,6 +3924,10 @@ impl App {
                 self.search_scroll = 0;
             }
             KeyCode::Enter => {
+                if !self.search_query.is_empty() {
+                    let query = self.search_query.clone();
+                    self.record_history(|history| history.searches.push(query));
+                }
                 if !self.search_results.is_empty() {
                     if let Some(topic) = self.search_results.get(self.search_result_index).cloned()
                     {
@@ -1159,22 +3940,15 @@ impl App {
                 self.search_results.clear();
                 self.search_scroll = 0;
             }
-            KeyCode::Backspace => {
-                self.search_query.pop();
-                self.update_search_results();
+            // History: Ctrl+P/Ctrl+N, or Up/Down while there are no results to move through
+            KeyCode::Char('p') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search_history(true)
             }
-            KeyCode::Char(c) => {
-                self.search_query.push(c);
-                // Easter egg: check for "david" (case-insensitive)
-                if self.search_query.eq_ignore_ascii_case("david") {
-                    self.show_david_easter_egg = true;
-                    self.input_mode = InputMode::Normal;
-                    self.search_query.clear();
-                    self.search_results.clear();
-                    self.search_scroll = 0;
-                    return;
-                }
-                self.update_search_results();
+            KeyCode::Char('n') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search_history(false)
+            }
+            KeyCode::Up | KeyCode::Down if self.search_results.is_empty() => {
+                self.search_history(code == KeyCode::Up)
             }
             KeyCode::Down => {
                 if self.search_result_index + 1 < self.search_results.len() {
@@ -1213,7 +3987,24 @@ impl App {
                     self.ensure_search_visible();
                 }
             }
-            _ => {}
+            _ => {
+                if !self
+                    .search_cursor
+                    .handle_key(&mut self.search_query, code, modifiers)
+                {
+                    return;
+                }
+                // Easter egg: check for "david" (case-insensitive)
+                if self.search_query.eq_ignore_ascii_case("david") {
+                    self.show_david_easter_egg = true;
+                    self.input_mode = InputMode::Normal;
+                    self.search_query.clear();
+                    self.search_results.clear();
+                    self.search_scroll = 0;
+                    return;
+                }
+                self.update_search_results();
+            }
         }
     }
 
@@ -1234,6 +4025,20 @@ impl App {
             }
         }
 
+        // Count prefixes for motions, like vim
+        if let KeyCode::Char(digit @ '0'..='9') = code {
+            if self.push_count_digit(digit) {
+                return;
+            }
+        } else if let Some(count) = self.pending_count.take() {
+            if self.apply_count(code, count) {
+                return;
+            }
+            if let Some(panel) = Self::count_panel(count) {
+                self.focused_panel = panel;
+            }
+        }
+
         match code {
             // Quit
             KeyCode::Char('q') => self.should_quit = true,
@@ -1244,7 +4049,9 @@ impl App {
             // Search
             KeyCode::Char('/') => {
                 self.input_mode = InputMode::Search;
+                self.history.searches.reset();
                 self.search_query.clear();
+                self.search_cursor = InputCursor::default();
                 self.search_results.clear();
                 self.search_result_index = 0;
                 self.search_scroll = 0;
@@ -1253,9 +4060,6 @@ impl App {
             // Panel navigation
             KeyCode::Tab => self.next_panel(),
             KeyCode::BackTab => self.prev_panel(),
-            KeyCode::Char('1') => self.focused_panel = Panel::TopicTree,
-            KeyCode::Char('2') => self.focused_panel = Panel::Messages,
-            KeyCode::Char('3') => self.focused_panel = Panel::Stats,
 
             // Payload mode toggle
             KeyCode::Char('p') => self.cycle_payload_mode(),
@@ -1267,24 +4071,63 @@ impl App {
             KeyCode::Char('c') => self.stats.reset(),
 
             // Star current topic
+            KeyCode::Char('s') if self.focused_panel == Panel::Devices => self.toggle_device_star(),
             KeyCode::Char('s') => self.toggle_star(),
 
             // Toggle starred filter
+            KeyCode::Char('*') if self.focused_panel == Panel::Devices => {
+                self.toggle_device_filter()
+            }
             KeyCode::Char('*') => self.toggle_filter_mode(),
+            KeyCode::Char('w') => self.cycle_watchdog(),
+            KeyCode::Char('\'') => {
+                if self.user_data.starred_topics.is_empty() {
+                    self.set_status("No starred topics - press 's' to star one");
+                } else {
+                    self.starred_picker_index = 0;
+                    self.input_mode = InputMode::StarredPicker;
+                }
+            }
+
+            // Hide noisy topics from the tree
+            KeyCode::Char('-') => self.hide_selected_topic(false),
+            KeyCode::Char('_') => self.hide_selected_topic(true),
+            KeyCode::Char('=') => self.unhide_all_topics(),
+
+            // Start a topic over without clearing the whole session
+            KeyCode::Char('u') => self.clear_selected_topic(false),
+            KeyCode::Char('U') => self.clear_selected_topic(true),
 
             // Track metric from current message
             KeyCode::Char('m') => self.enter_metric_select(),
+            KeyCode::Char('M') => self.open_metric_detail(),
 
             // Copy to clipboard
             KeyCode::Char('y') => self.copy_topic(),
             KeyCode::Char('Y') => self.copy_payload(),
+            KeyCode::Char('C') => self.cycle_palette(),
+            KeyCode::Char('K') => self.cycle_buffer_limit(),
 
             // Topic filter
             KeyCode::Char('f') => {
                 self.input_mode = InputMode::Filter;
+                self.history.filters.reset();
                 self.filter_input = self.topic_filter.clone().unwrap_or_default();
+                self.filter_cursor = InputCursor::end_of(&self.filter_input);
+                self.filter_before = Some(FilterBefore {
+                    filter: self.topic_filter.clone(),
+                    selected_topic: self.selected_topic.clone(),
+                    selected_index: self.selected_topic_index,
+                    scroll: self.tree_scroll,
+                });
+                self.apply_filter_preview();
             }
             KeyCode::Char('F') => self.clear_filter(),
+            KeyCode::Char('o') => {
+                if let Some(topic) = self.selected_topic.clone() {
+                    self.filter_to_subtree(topic);
+                }
+            }
 
             // Navigation (vim-style + arrows)
             KeyCode::Down | KeyCode::Char('j') => self.move_down(),
@@ -1293,8 +4136,13 @@ impl App {
             KeyCode::Right | KeyCode::Char('l') => self.expand_or_right(),
             KeyCode::Char('L') => self.expand_branch(),
             KeyCode::Char('H') => self.collapse_branch(),
+            KeyCode::Char('}') => self.move_to_sibling(true),
+            KeyCode::Char('{') => self.move_to_sibling(false),
+            KeyCode::Char('(') => self.move_to_parent(),
+            KeyCode::Char(')') => self.move_to_first_child(),
 
             // Expand/collapse
+            KeyCode::Enter if self.focused_panel == Panel::Devices => self.open_selected_device(),
             KeyCode::Enter => self.toggle_expand(),
 
             // Page navigation
@@ -1303,12 +4151,53 @@ impl App {
             KeyCode::Home | KeyCode::Char('g') => self.goto_top(),
             KeyCode::End | KeyCode::Char('G') => self.goto_bottom(),
             KeyCode::Char('S') => self.open_server_manager(),
+            KeyCode::Char('@') => self.open_server_switch(),
 
             // Open bookmark manager
             KeyCode::Char('B') => self.open_bookmark_manager(),
+            KeyCode::Char('R') => self.open_scenarios(),
+            KeyCode::Char('N') => self.open_note(),
+            KeyCode::Char('A') => self.open_api_docs(),
+            KeyCode::Char('V') => self.open_traffic_summary(),
+            KeyCode::Char('b') => self.open_breadcrumb(),
+            KeyCode::Char('I') => self.input_mode = InputMode::MessageColumns,
+            KeyCode::Char('Z') => self.toggle_away(),
+            KeyCode::Char('$') => self.toggle_system_topics(),
+            KeyCode::Char('v') => self.toggle_peek(),
+            KeyCode::Char('a') => self.toggle_follow_busiest(),
+            KeyCode::Char('n') => self.toggle_follow_newest(),
+            KeyCode::Char('z') => self.toggle_template_grouping(),
 
             // Export topics to file
             KeyCode::Char('E') => self.export_topics(),
+            KeyCode::Char('T') if self.allow(Feature::Recording) => {
+                self.input_mode = InputMode::TreeExport
+            }
+
+            // Log view
+            KeyCode::Char('D') => {
+                self.input_mode = InputMode::LogView;
+                self.log_scroll = 0;
+            }
+
+            // Screenshots of the whole screen or focused panel
+            KeyCode::Char('X') if self.allow(Feature::Recording) => {
+                self.pending_screenshot = Some(ScreenshotScope::Screen)
+            }
+            KeyCode::Char('x') if self.allow(Feature::Recording) => {
+                self.pending_screenshot = Some(ScreenshotScope::FocusedPanel)
+            }
+
+            // Stream the selected topic to a file or command
+            KeyCode::Char('|') => self.toggle_pipe(),
+
+            // Session snapshots
+            KeyCode::Char('W') => self.save_session(),
+            KeyCode::Char('O') => {
+                self.input_mode = InputMode::SessionLoad;
+                self.session_input.clear();
+                self.session_cursor = InputCursor::default();
+            }
 
             // Escape closes overlays
             KeyCode::Esc => {
@@ -1327,18 +4216,29 @@ impl App {
         self.focused_panel = match self.focused_panel {
             Panel::TopicTree => Panel::Messages,
             Panel::Messages => Panel::Stats,
-            Panel::Stats => Panel::TopicTree,
+            Panel::Stats => Panel::Devices,
+            Panel::Devices => Panel::TopicTree,
         };
     }
 
     fn prev_panel(&mut self) {
         self.focused_panel = match self.focused_panel {
-            Panel::TopicTree => Panel::Stats,
+            Panel::TopicTree => Panel::Devices,
             Panel::Messages => Panel::TopicTree,
             Panel::Stats => Panel::Messages,
+            Panel::Devices => Panel::Stats,
         };
     }
 
+    fn cycle_palette(&mut self) {
+        self.config.ui.palette = self.config.ui.palette.next();
+        let label = self.config.ui.palette.label();
+        self.set_status(&format!(
+            "Palette: {} (set palette in [ui] to keep it)",
+            label
+        ));
+    }
+
     fn cycle_payload_mode(&mut self) {
         self.payload_mode = match self.payload_mode {
             PayloadMode::Auto => PayloadMode::Raw,
@@ -1358,7 +4258,7 @@ impl App {
                 }
             }
             Panel::Messages => {
-                let count = self.get_current_messages().len();
+                let count = self.listed_message_count();
                 if count > 0 && self.selected_message_index < count - 1 {
                     self.selected_message_index += 1;
                 }
@@ -1366,6 +4266,12 @@ impl App {
             Panel::Stats => {
                 self.stats_scroll = self.stats_scroll.saturating_add(1);
             }
+            Panel::Devices => {
+                let count = self.visible_devices().len();
+                if self.device_selected_index + 1 < count {
+                    self.device_selected_index += 1;
+                }
+            }
         }
     }
 
@@ -1385,6 +4291,9 @@ impl App {
             Panel::Stats => {
                 self.stats_scroll = self.stats_scroll.saturating_sub(1);
             }
+            Panel::Devices => {
+                self.device_selected_index = self.device_selected_index.saturating_sub(1);
+            }
         }
     }
 
@@ -1449,6 +4358,96 @@ impl App {
         }
     }
 
+    /// Move to the next (or previous) row under the same parent, skipping
+    /// over expanded subtrees. Rows are matched by path, so a filter that
+    /// hides the parent can't turn a cousin into a sibling.
+    fn move_to_sibling(&mut self, forward: bool) {
+        if self.focused_panel != Panel::TopicTree {
+            return;
+        }
+        let visible = self.get_visible_topics();
+        let Some(current) = visible.get(self.selected_topic_index) else {
+            return;
+        };
+        let parent = self.parent_path(&current.full_path);
+        let is_sibling = |index: &usize| self.parent_path(&visible[*index].full_path) == parent;
+        let sibling = if forward {
+            (self.selected_topic_index + 1..visible.len()).find(is_sibling)
+        } else {
+            (0..self.selected_topic_index).rev().find(is_sibling)
+        };
+        match sibling {
+            Some(index) => {
+                self.selected_topic_index = index;
+                self.update_selected_topic();
+            }
+            None => self.set_status(if forward {
+                "No next sibling"
+            } else {
+                "No previous sibling"
+            }),
+        }
+    }
+
+    /// Move to the row's parent, if it is shown
+    fn move_to_parent(&mut self) {
+        if self.focused_panel != Panel::TopicTree {
+            return;
+        }
+        let visible = self.get_visible_topics();
+        let Some(current) = visible.get(self.selected_topic_index) else {
+            return;
+        };
+        let parent = self.parent_path(&current.full_path).and_then(|parent| {
+            visible[..self.selected_topic_index]
+                .iter()
+                .rposition(|topic| topic.full_path == parent)
+        });
+        match parent {
+            Some(index) => {
+                self.selected_topic_index = index;
+                self.update_selected_topic();
+            }
+            None => self.set_status("No parent"),
+        }
+    }
+
+    /// Move to the row's first shown child, expanding it if needed
+    fn move_to_first_child(&mut self) {
+        if self.focused_panel != Panel::TopicTree {
+            return;
+        }
+        let visible = self.get_visible_topics();
+        let Some(topic) = visible.get(self.selected_topic_index) else {
+            return;
+        };
+        let path = topic.full_path.clone();
+        let expanded = topic.has_children && self.expanded_topics.insert(path.clone());
+        let prefix = format!("{}{}", path, self.topic_tree.separator());
+        let child = self
+            .get_visible_topics()
+            .iter()
+            .position(|topic| topic.full_path.starts_with(&prefix));
+        match child {
+            Some(index) => {
+                self.selected_topic_index = index;
+                self.update_selected_topic();
+            }
+            None => {
+                if expanded {
+                    self.expanded_topics.remove(&path);
+                }
+                self.set_status("No children");
+            }
+        }
+    }
+
+    /// The path one level up, or `None` for a root
+    fn parent_path<'a>(&self, path: &'a str) -> Option<&'a str> {
+        path.rsplit_once(self.topic_tree.separator())
+            .map(|(parent, _)| parent)
+    }
+
     fn collapse_branch(&mut self) {
         if self.focused_panel != Panel::TopicTree {
             return;
@@ -1479,6 +4478,97 @@ impl App {
         }
     }
 
+    /// Add a digit to the count prefix. Returns false for a leading `0`,
+    /// which is not a count.
+    fn push_count_digit(&mut self, digit: char) -> bool {
+        let value = digit as usize - '0' as usize;
+        match self.pending_count {
+            Some(count) => {
+                self.pending_count = Some((count * 10 + value).min(MAX_COUNT));
+                true
+            }
+            None if value == 0 => false,
+            None => {
+                self.pending_count = Some(value);
+                true
+            }
+        }
+    }
+
+    /// The panel a lone `1`-`4` jumps to once the next key turns out not to
+    /// be a motion
+    fn count_panel(count: usize) -> Option<Panel> {
+        match count {
+            1 => Some(Panel::TopicTree),
+            2 => Some(Panel::Messages),
+            3 => Some(Panel::Stats),
+            4 => Some(Panel::Devices),
+            _ => None,
+        }
+    }
+
+    /// Run a motion `count` times in the focused panel. Returns false if the
+    /// key is not a motion, dropping the count.
+    fn apply_count(&mut self, code: KeyCode, count: usize) -> bool {
+        let row = self.current_row();
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => self.goto_row(row.saturating_add(count)),
+            KeyCode::Up | KeyCode::Char('k') => self.goto_row(row.saturating_sub(count).max(1)),
+            KeyCode::PageDown => self.goto_row(row.saturating_add(count * 10)),
+            KeyCode::PageUp => self.goto_row(row.saturating_sub(count * 10).max(1)),
+            KeyCode::Home | KeyCode::End | KeyCode::Char('g') | KeyCode::Char('G') => {
+                self.goto_row(count)
+            }
+            KeyCode::Char(c @ ('{' | '}')) => {
+                for _ in 0..count {
+                    let index = self.selected_topic_index;
+                    self.move_to_sibling(c == '}');
+                    if self.selected_topic_index == index {
+                        break;
+                    }
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// The focused list's 1-based row
+    fn current_row(&self) -> usize {
+        let index = match self.focused_panel {
+            Panel::TopicTree => self.selected_topic_index,
+            Panel::Messages => self.selected_message_index,
+            Panel::Stats => self.stats_scroll,
+            Panel::Devices => self.device_selected_index,
+        };
+        index.saturating_add(1)
+    }
+
+    /// Go to a 1-based row of the focused list, clamped to its length
+    fn goto_row(&mut self, row: usize) {
+        let index = row.saturating_sub(1);
+        match self.focused_panel {
+            Panel::TopicTree => {
+                let visible = self.get_visible_topics();
+                if !visible.is_empty() {
+                    self.selected_topic_index = index.min(visible.len() - 1);
+                    self.update_selected_topic();
+                }
+            }
+            Panel::Messages => {
+                let count = self.listed_message_count();
+                self.selected_message_index = index.min(count.saturating_sub(1));
+            }
+            Panel::Stats => {
+                self.stats_scroll = index; // Will be clamped during rendering
+            }
+            Panel::Devices => {
+                let count = self.visible_devices().len();
+                self.device_selected_index = index.min(count.saturating_sub(1));
+            }
+        }
+    }
+
     fn page_down(&mut self) {
         for _ in 0..10 {
             self.move_down();
@@ -1503,6 +4593,9 @@ impl App {
             Panel::Stats => {
                 self.stats_scroll = 0;
             }
+            Panel::Devices => {
+                self.device_selected_index = 0;
+            }
         }
     }
 
@@ -1516,7 +4609,7 @@ impl App {
                 }
             }
             Panel::Messages => {
-                let count = self.get_current_messages().len();
+                let count = self.listed_message_count();
                 if count > 0 {
                     self.selected_message_index = count - 1;
                 }
@@ -1524,10 +4617,51 @@ impl App {
             Panel::Stats => {
                 self.stats_scroll = usize::MAX; // Will be clamped during rendering
             }
+            Panel::Devices => {
+                self.device_selected_index = self.visible_devices().len().saturating_sub(1);
+            }
+        }
+    }
+
+    /// Peek at rows while moving through the tree, leaving the selection and
+    /// the Messages panel alone. Turning it off goes back to the selected topic.
+    fn toggle_peek(&mut self) {
+        self.peek = !self.peek;
+        if self.peek {
+            self.focused_panel = Panel::TopicTree;
+            self.set_status("Peek: moving previews topics, Enter selects (v to stop)");
+            return;
+        }
+        let visible = self.get_visible_topics();
+        if let Some(index) = visible
+            .iter()
+            .position(|topic| Some(&topic.full_path) == self.selected_topic.as_ref())
+        {
+            self.selected_topic_index = index;
+        }
+        self.set_status("Peek off");
+    }
+
+    /// The topic to show with the latest message at or below a tree row: the
+    /// row's own topic, else the most recently updated one beneath it
+    pub fn peek_topic(&self, path: &str) -> Option<String> {
+        if self.message_buffer.get_latest(path).is_some() {
+            return Some(path.to_string());
         }
+        let prefix = format!("{}{}", path, self.topic_tree.separator());
+        self.message_buffer
+            .topics()
+            .into_iter()
+            .filter(|topic| topic.starts_with(&prefix))
+            .filter_map(|topic| Some((self.message_buffer.get_latest(topic)?.timestamp, topic)))
+            .max()
+            .map(|(_, topic)| topic.to_string())
     }
 
     fn update_selected_topic(&mut self) {
+        if self.peek {
+            return;
+        }
         let visible = self.get_visible_topics();
         if let Some(topic) = visible.get(self.selected_topic_index) {
             self.selected_topic = Some(topic.full_path.clone());
@@ -1542,6 +4676,12 @@ impl App {
             self.search_scroll = 0;
         } else {
             self.search_results = self.topic_tree.search(&self.search_query);
+            // Topics whose note mentions the query come after name matches
+            for topic in self.user_data.search_notes(&self.search_query) {
+                if !self.search_results.contains(&topic) {
+                    self.search_results.push(topic);
+                }
+            }
             self.search_result_index = 0;
             self.search_scroll = 0;
         }
@@ -1584,11 +4724,13 @@ impl App {
             }
         }
 
-        // Update selected index
+        // Update selected index; the tree must not pull it back to the
+        // topic that used to sit there
         let visible = self.get_visible_topics();
         for (i, t) in visible.iter().enumerate() {
             if t.full_path == topic {
                 self.selected_topic_index = i;
+                self.tree_anchor = None;
                 break;
             }
         }
@@ -1596,16 +4738,13 @@ impl App {
 
     /// Get visible topics for rendering
     pub fn get_visible_topics(&self) -> Vec<TopicInfo> {
-        let topics = self.topic_tree.get_visible_topics(&self.expanded_topics);
-
-        // Apply starred filter
-        let topics = match self.filter_mode {
-            FilterMode::All => topics,
-            FilterMode::Starred => topics
-                .into_iter()
-                .filter(|t| self.user_data.is_starred(&t.full_path))
-                .collect(),
+        let tree = if self.group_templates {
+            &self.grouped_tree
+        } else {
+            &self.topic_tree
         };
+        let mut topics = tree.get_visible_topics(&self.expanded_topics);
+        topics.retain(|t| self.shows_topic(&t.full_path));
 
         // Apply topic pattern filter
         if let Some(pattern) = &self.topic_filter {
@@ -1618,6 +4757,14 @@ impl App {
         }
     }
 
+    /// Whether the tree shows `topic` before any pattern filter: system
+    /// topics only when enabled, no hidden subtrees, starred only if asked
+    fn shows_topic(&self, topic: &str) -> bool {
+        (self.show_system_topics || !is_system_topic(topic))
+            && !self.is_hidden(topic)
+            && (self.filter_mode == FilterMode::All || self.user_data.is_starred(topic))
+    }
+
     pub fn active_mqtt_server(&self) -> Option<&MqttServerConfig> {
         self.config.mqtt.active_server()
     }
@@ -1644,6 +4791,10 @@ impl App {
     }
 
     pub fn reset_for_server_switch(&mut self, kind: BrokerKind, server_index: usize) -> Result<()> {
+        let previous = self
+            .active_server_info()
+            .filter(|_| self.session_source.is_none())
+            .map(|info| server_key(info.kind, &info.name));
         let server = match kind {
             BrokerKind::Mqtt => self
                 .config
@@ -1667,39 +4818,380 @@ impl App {
             BrokerKind::Mqtt => {
                 self.config.mqtt.active_server = server.clone();
             }
-            BrokerKind::Nats => {
-                self.config.nats.active_server = server.clone();
+            BrokerKind::Nats => {
+                self.config.nats.active_server = server.clone();
+            }
+        }
+        self.save_config()?;
+
+        // Keep what the previous server collected for when it is switched back to
+        let key = server_key(kind, &server);
+        if let Some(previous) = previous.filter(|previous| *previous != key) {
+            let parked = self.park_collected_data();
+            self.parked_servers.insert(previous, parked);
+        }
+        self.clear_collected_data(kind);
+
+        match self.parked_servers.remove(&key) {
+            Some(parked) => {
+                let topics = parked.topic_count();
+                self.restore_collected_data(parked);
+                self.set_status(&format!(
+                    "Switched to {} ({}), {} topics kept",
+                    server,
+                    kind.label(),
+                    topics
+                ));
+            }
+            None => self.set_status(&format!("Switched to {} ({})", server, kind.label())),
+        }
+        Ok(())
+    }
+
+    /// Data parked for a server, if it was shown before
+    pub fn parked_server(&self, kind: BrokerKind, name: &str) -> Option<&ParkedServer> {
+        self.parked_servers.get(&server_key(kind, name))
+    }
+
+    /// Move the collected data out, leaving fresh state behind
+    fn park_collected_data(&mut self) -> ParkedServer {
+        let ui = &self.config.ui;
+        ParkedServer {
+            topic_tree: std::mem::take(&mut self.topic_tree),
+            message_buffer: std::mem::replace(
+                &mut self.message_buffer,
+                MessageBuffer::new(ui.message_buffer_size),
+            ),
+            stats: std::mem::replace(&mut self.stats, Stats::new(ui.stats_window_secs)),
+            metric_tracker: std::mem::replace(&mut self.metric_tracker, new_metric_tracker(ui)),
+            state_tracker: std::mem::replace(&mut self.state_tracker, new_state_tracker(ui)),
+            device_tracker: std::mem::replace(&mut self.device_tracker, new_device_tracker(ui)),
+            latency_tracker: std::mem::replace(&mut self.latency_tracker, LatencyTracker::new(100)),
+            expanded_topics: std::mem::take(&mut self.expanded_topics),
+            selected_topic: self.selected_topic.take(),
+        }
+    }
+
+    fn restore_collected_data(&mut self, parked: ParkedServer) {
+        self.topic_tree = parked.topic_tree;
+        self.message_buffer = parked.message_buffer;
+        self.stats = parked.stats;
+        self.metric_tracker = parked.metric_tracker;
+        self.state_tracker = parked.state_tracker;
+        self.device_tracker = parked.device_tracker;
+        self.latency_tracker = parked.latency_tracker;
+        self.expanded_topics = parked.expanded_topics;
+        self.selected_topic = parked.selected_topic;
+        self.learn_topic_templates();
+    }
+
+    /// Drop all collected data and selection state, ready for a new data source
+    fn clear_collected_data(&mut self, kind: BrokerKind) {
+        self.save_topic_totals();
+        self.connected_broker_kind = kind;
+        self.topic_tree = TopicTree::with_separator(kind.topic_separator());
+        self.message_buffer.clear();
+        self.stats.reset();
+        self.metric_tracker = new_metric_tracker(&self.config.ui);
+        self.state_tracker = new_state_tracker(&self.config.ui);
+        self.device_tracker = new_device_tracker(&self.config.ui);
+        self.cardinality_watch = new_cardinality_watch(&self.config.ui);
+        self.cardinality_alert = None;
+        self.follow_alert = None;
+        self.device_selected_index = 0;
+        self.latency_tracker = LatencyTracker::new(100);
+        self.ping_tracker = PingTracker::new(60);
+        self.connection_health = ConnectionHealth::new(BackoffStrategy::default());
+        self.connection_details = None;
+        self.schema_tracker.clear();
+        self.topic_moves = TopicMoveDetector::new();
+        self.selected_topic_index = 0;
+        self.selected_message_index = 0;
+        self.selected_topic = None;
+        self.expanded_topics.clear();
+        self.stats_scroll = 0;
+        self.message_scroll = 0;
+        self.tree_scroll = 0;
+        self.tree_anchor = None;
+        self.session_source = None;
+        self.subscribe_watch = None;
+        self.subscribe_checked = false;
+        self.subscribe_warning = None;
+        self.pruned_topics = 0;
+        self.away = None;
+        self.learn_topic_templates();
+        self.load_topic_totals();
+    }
+
+    /// Switch to the active server's saved topic totals, when persisting them
+    fn load_topic_totals(&mut self) {
+        self.topic_totals = TopicTotals::new();
+        self.topic_totals_path = None;
+        if !self.config.ui.persist_topic_stats {
+            return;
+        }
+        let Some(server) = self.active_server_info() else {
+            return;
+        };
+        let path = self
+            .paths
+            .topic_totals_file(server.kind.label(), &server.name);
+        match TopicTotals::load_from(&path) {
+            Ok(mut totals) => {
+                let days = self.config.ui.topic_stats_days as i64;
+                totals.expire(chrono::Utc::now() - chrono::Duration::days(days));
+                self.topic_totals = totals;
+                self.topic_totals_path = Some(path);
+            }
+            Err(e) => {
+                // Leave the file alone rather than overwrite it with a fresh count
+                tracing::warn!("Topic totals not loaded: {:#}", e);
+                self.set_status(&format!("Topic totals not loaded: {:#}", e));
+            }
+        }
+    }
+
+    /// Save topic totals for the current server, if persisting them
+    pub fn save_topic_totals(&self) {
+        let Some(path) = &self.topic_totals_path else {
+            return;
+        };
+        if let Err(e) = self.topic_totals.save_to(path) {
+            tracing::warn!("Failed to save topic totals: {:#}", e);
+        }
+    }
+
+    /// Capture the current view as a session snapshot
+    pub fn session_snapshot(&self) -> SessionSnapshot {
+        let now = Instant::now();
+        let limit = self.config.ui.session_messages_per_topic;
+
+        let topics = self
+            .topic_tree
+            .get_all_topics()
+            .into_iter()
+            .filter_map(|topic| {
+                let (message_count, bytes_received, last_message_time) =
+                    self.topic_tree.get_topic_stats(&topic)?;
+                Some(TopicSnapshot {
+                    topic,
+                    message_count,
+                    bytes_received,
+                    last_message_time,
+                })
+            })
+            .collect();
+
+        let mut messages = Vec::new();
+        for topic in self.message_buffer.topics() {
+            let buffered = self.message_buffer.get_entries(topic);
+            let keep = if limit == 0 { buffered.len() } else { limit };
+            // Buffer is newest first; store oldest first so replaying restores order
+            messages.extend(
+                buffered
+                    .into_iter()
+                    .take(keep)
+                    .rev()
+                    .map(MessageSnapshot::from_entry),
+            );
+        }
+
+        let mut expanded_topics: Vec<String> = self.expanded_topics.iter().cloned().collect();
+        expanded_topics.sort();
+
+        SessionSnapshot {
+            version: SESSION_VERSION,
+            saved_at: chrono::Utc::now(),
+            broker: self.connected_broker_kind,
+            server: self.active_server_info().map(|s| s.name),
+            selected_topic: self.selected_topic.clone(),
+            expanded_topics,
+            topic_filter: self.topic_filter.clone(),
+            stats: StatsSnapshot {
+                total_messages: self.stats.total_messages(),
+                total_bytes: self.stats.total_bytes(),
+                uptime_secs: self.stats.uptime().as_secs(),
+            },
+            topics,
+            messages,
+            metrics: self
+                .metric_tracker
+                .get_metrics()
+                .into_iter()
+                .map(|m| MetricSnapshot::from_metric(m, now))
+                .collect(),
+            charts: self.metric_tracker.charts().to_vec(),
+            states: self
+                .state_tracker
+                .get_timelines()
+                .into_iter()
+                .map(|t| StateSnapshot::from_timeline(t, now))
+                .collect(),
+            devices: self
+                .device_tracker
+                .get_devices()
+                .into_iter()
+                .map(|d| DeviceSnapshot::from_device(d, now))
+                .collect(),
+        }
+    }
+
+    /// Save the current view to a session file in the state dir
+    pub fn save_session(&mut self) {
+        if !self.allow(Feature::Recording) {
+            return;
+        }
+        let dir = self.paths.sessions_dir();
+        let filename = dir.join(SessionSnapshot::default_filename());
+        let snapshot = self.session_snapshot();
+        let result = std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create directory {:?}", dir))
+            .and_then(|_| snapshot.save_to(&filename));
+        match result {
+            Ok(()) => self.set_status(&format!(
+                "Saved session ({} topics) to {}",
+                snapshot.topics.len(),
+                filename.display()
+            )),
+            Err(e) => self.set_status(&format!("Session save failed: {}", e)),
+        }
+    }
+
+    /// Replace the current view with a saved session.
+    /// The broker connection is dropped so the snapshot is not mixed with live traffic.
+    pub fn load_session(&mut self, path: &Path) -> Result<()> {
+        let path = &self.paths.session_file(path);
+        let snapshot = SessionSnapshot::load_from(path)?;
+        let messages = snapshot
+            .messages
+            .into_iter()
+            .map(|message| {
+                let (repeats, last_seen) = (message.repeats, message.last_seen);
+                message
+                    .into_message()
+                    .map(|message| (message, repeats, last_seen))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let now = Instant::now();
+
+        self.clear_collected_data(snapshot.broker);
+        // A replayed session is not new traffic
+        self.topic_totals_path = None;
+        self.pending_disconnect = true;
+
+        for topic in &snapshot.topics {
+            self.topic_tree.restore_topic(
+                &topic.topic,
+                topic.message_count,
+                topic.bytes_received,
+                topic.last_message_time,
+            );
+        }
+        for (message, repeats, last_seen) in messages {
+            let last_seen = last_seen.unwrap_or(message.timestamp);
+            self.message_buffer.restore(message, repeats, last_seen);
+        }
+        for metric in snapshot.metrics {
+            self.metric_tracker.insert(metric.into_metric(now));
+        }
+        for chart in snapshot.charts {
+            self.metric_tracker.insert_chart(chart);
+        }
+        for state in snapshot.states {
+            self.state_tracker.insert(state.into_timeline(now));
+        }
+        for device in snapshot.devices {
+            self.device_tracker.insert_device(device.into_device(now));
+        }
+        self.device_tracker.update_all_statuses();
+        self.stats.restore_totals(
+            snapshot.stats.total_messages,
+            snapshot.stats.total_bytes,
+            Duration::from_secs(snapshot.stats.uptime_secs),
+        );
+
+        self.expanded_topics = snapshot.expanded_topics.into_iter().collect();
+        self.topic_filter = snapshot.topic_filter;
+        if let Some(topic) = snapshot.selected_topic {
+            self.expand_to_topic(&topic);
+        }
+        self.update_selected_topic();
+
+        let source = path.display().to_string();
+        self.set_status(&format!(
+            "Loaded session {} ({} topics, saved {})",
+            source,
+            snapshot.topics.len(),
+            snapshot
+                .saved_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+        ));
+        self.session_source = Some(source);
+        Ok(())
+    }
+
+    /// Stop the active pipe, or prompt for a target for the selected topic
+    fn toggle_pipe(&mut self) {
+        if let Some(pipe) = self.topic_pipe.take() {
+            self.set_status(&format!("Stopped piping {}", pipe.topic));
+        } else if self.selected_topic.is_none() {
+            self.set_status("No topic selected");
+        } else if self.allow(Feature::Recording) {
+            self.input_mode = InputMode::Pipe;
+            self.pipe_input.clear();
+            self.pipe_cursor = InputCursor::default();
+        }
+    }
+
+    fn handle_pipe_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.pipe_input.clear();
+            }
+            KeyCode::Enter => {
+                self.input_mode = InputMode::Normal;
+                let target = std::mem::take(&mut self.pipe_input);
+                if let Some(topic) = self.selected_topic.clone() {
+                    self.pending_pipe = Some(PendingPipe { topic, target });
+                }
+            }
+            _ => {
+                self.pipe_cursor
+                    .handle_key(&mut self.pipe_input, code, modifiers);
             }
         }
-        self.save_config()?;
-
-        self.connected_broker_kind = kind;
-        self.topic_tree = TopicTree::with_separator(kind.topic_separator());
-        self.message_buffer.clear();
-        self.stats.reset();
-        self.metric_tracker = MetricTracker::new(100);
-        self.device_tracker = DeviceTracker::new();
-        self.latency_tracker = LatencyTracker::new(100);
-        self.schema_tracker = SchemaTracker::new();
-        self.selected_topic_index = 0;
-        self.selected_message_index = 0;
-        self.selected_topic = None;
-        self.expanded_topics.clear();
-        self.stats_scroll = 0;
-        self.message_scroll = 0;
-        self.tree_scroll = 0;
+    }
 
-        self.set_status(&format!("Switched to {} ({})", server, kind.label()));
-        Ok(())
+    fn handle_session_load_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.session_input.clear();
+            }
+            KeyCode::Enter => {
+                let path = PathBuf::from(self.session_input.trim());
+                self.input_mode = InputMode::Normal;
+                self.session_input.clear();
+                if let Err(e) = self.load_session(&path) {
+                    self.set_status(&format!("Session load failed: {}", e));
+                }
+            }
+            _ => {
+                self.session_cursor
+                    .handle_key(&mut self.session_input, code, modifiers);
+            }
+        }
     }
 
-    pub fn handle_server_manager_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+    pub fn handle_server_manager_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
         if self.server_edit.active {
-            self.handle_server_edit_input(code);
+            self.handle_server_edit_input(code, modifiers);
             return;
         }
         if self.nats_server_edit.active {
-            self.handle_nats_server_edit_input(code);
+            self.handle_nats_server_edit_input(code, modifiers);
             return;
         }
 
@@ -1719,18 +5211,17 @@ impl App {
                     self.server_manager_index += 1;
                 }
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.server_manager_index > 0 {
-                    self.server_manager_index -= 1;
-                }
+            KeyCode::Up | KeyCode::Char('k') if self.server_manager_index > 0 => {
+                self.server_manager_index -= 1;
             }
-            KeyCode::Char('a') => {
+            KeyCode::Char('r') => self.regenerate_client_id(),
+            KeyCode::Char('a') if self.allow(Feature::ServerEditing) => {
                 match self.server_manager_kind {
                     BrokerKind::Mqtt => self.start_server_edit(None),
                     BrokerKind::Nats => self.start_nats_server_edit(None),
                 }
             }
-            KeyCode::Char('e') => {
+            KeyCode::Char('e') if self.allow(Feature::ServerEditing) => {
                 match self.server_manager_kind {
                     BrokerKind::Mqtt => {
                         if !self.config.mqtt.servers.is_empty() {
@@ -1797,6 +5288,30 @@ impl App {
         }
     }
 
+    /// Reconnect to the active MQTT server under a freshly suffixed client ID,
+    /// for when the broker still holds a stuck session under the old one
+    fn regenerate_client_id(&mut self) {
+        if self.connected_broker_kind != BrokerKind::Mqtt || self.session_source.is_some() {
+            self.set_status("Only a live MQTT connection has a client ID to regenerate");
+            return;
+        }
+        let Some(server) = self.active_mqtt_server() else {
+            return;
+        };
+        if server.use_exact_client_id {
+            self.set_status("Client ID is used exactly (use_exact_client_id)");
+            return;
+        }
+        if self.away.is_some() {
+            self.set_status("Come back with 'Z' before regenerating the client ID");
+            return;
+        }
+        self.pending_reconnect = true;
+        self.connection_details = None;
+        self.input_mode = InputMode::Normal;
+        self.set_status("Reconnecting with a fresh client ID");
+    }
+
     fn toggle_server_manager_kind(&mut self) {
         self.server_manager_kind = match self.server_manager_kind {
             BrokerKind::Mqtt => BrokerKind::Nats,
@@ -1822,6 +5337,9 @@ impl App {
     }
 
     fn delete_selected_server(&mut self) {
+        if !self.allow(Feature::ServerEditing) {
+            return;
+        }
         let total_servers = self.config.mqtt.servers.len() + self.config.nats.servers.len();
         if total_servers <= 1 {
             self.set_status("Cannot delete last server");
@@ -1944,7 +5462,7 @@ impl App {
         self.server_edit.active = true;
         self.server_edit.is_new = index.is_none();
         self.server_edit.field = ServerField::Name;
-        self.server_edit.cursor = 0;
+        self.server_edit.cursor = InputCursor::default();
         if let Some(index) = index {
             let server = &self.config.mqtt.servers[index];
             self.server_edit.index = index;
@@ -1970,12 +5488,14 @@ impl App {
             self.server_edit.keep_alive_secs = server.keep_alive_secs.to_string();
             // Session
             self.server_edit.clean_session = server.clean_session;
+            self.server_edit.preset = server.preset;
             // LWT
             self.server_edit.lwt_topic = server.lwt_topic.clone().unwrap_or_default();
             self.server_edit.lwt_payload = server.lwt_payload.clone().unwrap_or_default();
             self.server_edit.lwt_qos = server.lwt_qos.to_string();
             self.server_edit.lwt_retain = server.lwt_retain;
-            self.server_edit.cursor = self.server_edit_field_value(self.server_edit.field).len();
+            self.server_edit.cursor =
+                InputCursor::end_of(&self.server_edit_field_value(self.server_edit.field));
         } else {
             self.server_edit.index = self.config.mqtt.servers.len();
             // Basic
@@ -2000,16 +5520,18 @@ impl App {
             self.server_edit.keep_alive_secs = "30".to_string();
             // Session
             self.server_edit.clean_session = true;
+            self.server_edit.preset = ClientPreset::default();
             // LWT
             self.server_edit.lwt_topic.clear();
             self.server_edit.lwt_payload.clear();
             self.server_edit.lwt_qos = "0".to_string();
             self.server_edit.lwt_retain = false;
-            self.server_edit.cursor = self.server_edit_field_value(self.server_edit.field).len();
+            self.server_edit.cursor =
+                InputCursor::end_of(&self.server_edit_field_value(self.server_edit.field));
         }
     }
 
-    fn handle_server_edit_input(&mut self, code: KeyCode) {
+    fn handle_server_edit_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
         match code {
             KeyCode::Esc => {
                 self.server_edit.active = false;
@@ -2025,30 +5547,12 @@ impl App {
             KeyCode::Tab => {
                 self.server_edit.field = self.next_server_field(self.server_edit.field);
                 self.server_edit.cursor =
-                    self.server_edit_field_value(self.server_edit.field).len();
+                    InputCursor::end_of(&self.server_edit_field_value(self.server_edit.field));
             }
             KeyCode::BackTab => {
                 self.server_edit.field = self.prev_server_field(self.server_edit.field);
                 self.server_edit.cursor =
-                    self.server_edit_field_value(self.server_edit.field).len();
-            }
-            KeyCode::Left => {
-                if self.server_edit.cursor > 0 {
-                    self.server_edit.cursor -= 1;
-                }
-            }
-            KeyCode::Right => {
-                let max = self.server_edit_field_value(self.server_edit.field).len();
-                if self.server_edit.cursor < max {
-                    self.server_edit.cursor += 1;
-                }
-            }
-            KeyCode::Home => {
-                self.server_edit.cursor = 0;
-            }
-            KeyCode::End => {
-                self.server_edit.cursor =
-                    self.server_edit_field_value(self.server_edit.field).len();
+                    InputCursor::end_of(&self.server_edit_field_value(self.server_edit.field));
             }
             KeyCode::Char(' ') if self.server_edit.field == ServerField::UseTls => {
                 self.server_edit.use_tls = !self.server_edit.use_tls;
@@ -2062,25 +5566,19 @@ impl App {
             KeyCode::Char(' ') if self.server_edit.field == ServerField::CleanSession => {
                 self.server_edit.clean_session = !self.server_edit.clean_session;
             }
+            KeyCode::Char(' ') if self.server_edit.field == ServerField::Preset => {
+                self.server_edit.preset = self.server_edit.preset.next();
+            }
             KeyCode::Char(' ') if self.server_edit.field == ServerField::LwtRetain => {
                 self.server_edit.lwt_retain = !self.server_edit.lwt_retain;
             }
-            KeyCode::Backspace => {
-                if !self.server_edit.field.is_checkbox() {
-                    self.server_edit_backspace();
-                }
-            }
-            KeyCode::Delete => {
-                if !self.server_edit.field.is_checkbox() {
-                    self.server_edit_delete();
-                }
-            }
-            KeyCode::Char(c) => {
+            _ => {
                 if !self.server_edit.field.is_checkbox() {
-                    self.server_edit_insert(c);
+                    let mut cursor = self.server_edit.cursor;
+                    cursor.handle_key(self.server_edit_mut_field(), code, modifiers);
+                    self.server_edit.cursor = cursor;
                 }
             }
-            _ => {}
         }
     }
 
@@ -2102,6 +5600,7 @@ impl App {
             ServerField::SubscribeQos => &mut self.server_edit.subscribe_qos,
             ServerField::KeepAlive => &mut self.server_edit.keep_alive_secs,
             ServerField::CleanSession => &mut self.server_edit.host, // dummy, not used for checkbox
+            ServerField::Preset => &mut self.server_edit.host,       // dummy, not used for toggle
             ServerField::LwtTopic => &mut self.server_edit.lwt_topic,
             ServerField::LwtPayload => &mut self.server_edit.lwt_payload,
             ServerField::LwtQos => &mut self.server_edit.lwt_qos,
@@ -2109,43 +5608,6 @@ impl App {
         }
     }
 
-    fn server_edit_insert(&mut self, ch: char) {
-        let mut cursor = self.server_edit.cursor;
-        let value = self.server_edit_mut_field();
-        if cursor > value.len() {
-            cursor = value.len();
-        }
-        value.insert(cursor, ch);
-        self.server_edit.cursor = cursor.saturating_add(1);
-    }
-
-    fn server_edit_backspace(&mut self) {
-        let mut cursor = self.server_edit.cursor;
-        let value = self.server_edit_mut_field();
-        if cursor == 0 || value.is_empty() {
-            return;
-        }
-        if cursor > value.len() {
-            cursor = value.len();
-        }
-        let remove_at = cursor.saturating_sub(1);
-        value.remove(remove_at);
-        self.server_edit.cursor = cursor.saturating_sub(1);
-    }
-
-    fn server_edit_delete(&mut self) {
-        let cursor = self.server_edit.cursor;
-        let value = self.server_edit_mut_field();
-        if value.is_empty() {
-            return;
-        }
-        if cursor >= value.len() {
-            return;
-        }
-        value.remove(cursor);
-        self.server_edit.cursor = cursor;
-    }
-
     pub fn server_edit_field_value(&self, field: ServerField) -> String {
         match field {
             ServerField::Name => self.server_edit.name.clone(),
@@ -2194,6 +5656,15 @@ impl App {
                     "off (persistent)".to_string()
                 }
             }
+            ServerField::Preset => {
+                let tuning = self.server_edit.preset.tuning();
+                format!(
+                    "{} (inflight {}, {} KB packets)",
+                    self.server_edit.preset.label(),
+                    tuning.inflight,
+                    tuning.max_packet_size / 1024
+                )
+            }
             ServerField::LwtTopic => self.server_edit.lwt_topic.clone(),
             ServerField::LwtPayload => self.server_edit.lwt_payload.clone(),
             ServerField::LwtQos => self.server_edit.lwt_qos.clone(),
@@ -2246,6 +5717,12 @@ impl App {
             .unwrap_or(1)
             .min(2); // Clamp to 0-2
         let lwt_qos: u8 = self.server_edit.lwt_qos.trim().parse().unwrap_or(0).min(2);
+        // Per-server overrides are only editable in config.toml; keep them across edits
+        let existing = if self.server_edit.is_new {
+            None
+        } else {
+            self.config.mqtt.servers.get(self.server_edit.index)
+        };
 
         let server = MqttServerConfig {
             name: self.server_edit.name.trim().to_string(),
@@ -2301,6 +5778,10 @@ impl App {
             },
             lwt_qos,
             lwt_retain: self.server_edit.lwt_retain,
+            preset: self.server_edit.preset,
+            inflight: existing.and_then(|s| s.inflight),
+            max_packet_size: existing.and_then(|s| s.max_packet_size),
+            request_channel_capacity: existing.and_then(|s| s.request_channel_capacity),
         };
 
         // Name and host are required. Client ID is optional (auto-generated if empty)
@@ -2313,6 +5794,8 @@ impl App {
             return Err(anyhow!("Client ID required when ID Suffix is 'none'"));
         }
 
+        validate_subscription(&server.subscribe_topic)?;
+
         if self
             .config
             .mqtt
@@ -2359,7 +5842,7 @@ impl App {
         self.nats_server_edit.active = true;
         self.nats_server_edit.is_new = index.is_none();
         self.nats_server_edit.field = NatsServerField::Name;
-        self.nats_server_edit.cursor = 0;
+        self.nats_server_edit.cursor = InputCursor::default();
 
         if let Some(index) = index {
             let server = &self.config.nats.servers[index];
@@ -2376,9 +5859,9 @@ impl App {
             self.nats_server_edit.creds_file = server.creds_file.clone().unwrap_or_default();
             self.nats_server_edit.subscribe_subject = server.subscribe_subject.clone();
 
-            self.nats_server_edit.cursor = self
-                .nats_server_edit_field_value(self.nats_server_edit.field)
-                .len();
+            self.nats_server_edit.cursor = InputCursor::end_of(
+                &self.nats_server_edit_field_value(self.nats_server_edit.field),
+            );
         } else {
             self.nats_server_edit.index = self.config.nats.servers.len();
 
@@ -2394,13 +5877,13 @@ impl App {
             self.nats_server_edit.subscribe_subject =
                 BrokerKind::Nats.default_subscribe_pattern().to_string();
 
-            self.nats_server_edit.cursor = self
-                .nats_server_edit_field_value(self.nats_server_edit.field)
-                .len();
+            self.nats_server_edit.cursor = InputCursor::end_of(
+                &self.nats_server_edit_field_value(self.nats_server_edit.field),
+            );
         }
     }
 
-    fn handle_nats_server_edit_input(&mut self, code: KeyCode) {
+    fn handle_nats_server_edit_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
         match code {
             KeyCode::Esc => {
                 self.nats_server_edit.active = false;
@@ -2415,36 +5898,15 @@ impl App {
             },
             KeyCode::Tab => {
                 self.nats_server_edit.field = self.next_nats_server_field(self.nats_server_edit.field);
-                self.nats_server_edit.cursor = self
-                    .nats_server_edit_field_value(self.nats_server_edit.field)
-                    .len();
+                self.nats_server_edit.cursor = InputCursor::end_of(
+                    &self.nats_server_edit_field_value(self.nats_server_edit.field),
+                );
             }
             KeyCode::BackTab => {
                 self.nats_server_edit.field = self.prev_nats_server_field(self.nats_server_edit.field);
-                self.nats_server_edit.cursor = self
-                    .nats_server_edit_field_value(self.nats_server_edit.field)
-                    .len();
-            }
-            KeyCode::Left => {
-                if self.nats_server_edit.cursor > 0 {
-                    self.nats_server_edit.cursor -= 1;
-                }
-            }
-            KeyCode::Right => {
-                let max = self
-                    .nats_server_edit_field_value(self.nats_server_edit.field)
-                    .len();
-                if self.nats_server_edit.cursor < max {
-                    self.nats_server_edit.cursor += 1;
-                }
-            }
-            KeyCode::Home => {
-                self.nats_server_edit.cursor = 0;
-            }
-            KeyCode::End => {
-                self.nats_server_edit.cursor = self
-                    .nats_server_edit_field_value(self.nats_server_edit.field)
-                    .len();
+                self.nats_server_edit.cursor = InputCursor::end_of(
+                    &self.nats_server_edit_field_value(self.nats_server_edit.field),
+                );
             }
             KeyCode::Char(' ') if self.nats_server_edit.field == NatsServerField::UseTls => {
                 self.nats_server_edit.use_tls = !self.nats_server_edit.use_tls;
@@ -2452,22 +5914,13 @@ impl App {
             KeyCode::Char(' ') if self.nats_server_edit.field == NatsServerField::TlsInsecure => {
                 self.nats_server_edit.tls_insecure = !self.nats_server_edit.tls_insecure;
             }
-            KeyCode::Backspace => {
+            _ => {
                 if !self.nats_server_edit.field.is_checkbox() {
-                    self.nats_server_edit_backspace();
+                    let mut cursor = self.nats_server_edit.cursor;
+                    cursor.handle_key(self.nats_server_edit_mut_field(), code, modifiers);
+                    self.nats_server_edit.cursor = cursor;
                 }
             }
-            KeyCode::Delete => {
-                if !self.nats_server_edit.field.is_checkbox() {
-                    self.nats_server_edit_delete();
-                }
-            }
-            KeyCode::Char(c) => {
-                if !self.nats_server_edit.field.is_checkbox() {
-                    self.nats_server_edit_insert(c);
-                }
-            }
-            _ => {}
         }
     }
 
@@ -2486,40 +5939,6 @@ impl App {
         }
     }
 
-    fn nats_server_edit_insert(&mut self, ch: char) {
-        let mut cursor = self.nats_server_edit.cursor;
-        let value = self.nats_server_edit_mut_field();
-        if cursor > value.len() {
-            cursor = value.len();
-        }
-        value.insert(cursor, ch);
-        self.nats_server_edit.cursor = cursor.saturating_add(1);
-    }
-
-    fn nats_server_edit_backspace(&mut self) {
-        let mut cursor = self.nats_server_edit.cursor;
-        let value = self.nats_server_edit_mut_field();
-        if cursor == 0 || value.is_empty() {
-            return;
-        }
-        if cursor > value.len() {
-            cursor = value.len();
-        }
-        let remove_at = cursor.saturating_sub(1);
-        value.remove(remove_at);
-        self.nats_server_edit.cursor = cursor.saturating_sub(1);
-    }
-
-    fn nats_server_edit_delete(&mut self) {
-        let cursor = self.nats_server_edit.cursor;
-        let value = self.nats_server_edit_mut_field();
-        if value.is_empty() || cursor >= value.len() {
-            return;
-        }
-        value.remove(cursor);
-        self.nats_server_edit.cursor = cursor;
-    }
-
     pub fn nats_server_edit_field_value(&self, field: NatsServerField) -> String {
         match field {
             NatsServerField::Name => self.nats_server_edit.name.clone(),
@@ -2644,162 +6063,742 @@ impl App {
             }
         }
 
-        self.save_config()?;
-        if prev_active != self.config.nats.active_server {
-            if let Some(index) = self.config.nats.active_index() {
-                self.pending_server_switch = Some(PendingServerSwitch {
-                    kind: BrokerKind::Nats,
-                    index,
-                });
-            }
+        self.save_config()?;
+        if prev_active != self.config.nats.active_server {
+            if let Some(index) = self.config.nats.active_index() {
+                self.pending_server_switch = Some(PendingServerSwitch {
+                    kind: BrokerKind::Nats,
+                    index,
+                });
+            }
+        }
+        self.set_status("Server saved");
+        Ok(())
+    }
+
+    pub fn save_config(&self) -> Result<()> {
+        self.config
+            .save_with_backup(&self.config_path, CONFIG_BACKUP_LIMIT)
+            .context("Failed to save config")?;
+        Ok(())
+    }
+
+    /// Get messages for currently selected topic
+    pub fn get_current_messages(&self) -> Vec<&MqttMessage> {
+        if self.selected_instances().is_empty() {
+            return self
+                .selected_topic
+                .as_ref()
+                .map(|t| self.message_buffer.get_messages(t))
+                .unwrap_or_default();
+        }
+        self.get_current_entries()
+            .into_iter()
+            .map(|entry| &entry.message)
+            .collect()
+    }
+
+    /// Topics behind the selected template in the grouped tree
+    fn selected_instances(&self) -> &[String] {
+        match &self.selected_topic {
+            Some(topic) if self.group_templates => self.topic_templates.instances(topic),
+            _ => &[],
+        }
+    }
+
+    /// Messages listed for the selected topic: only the newest under high load
+    pub fn listed_message_count(&self) -> usize {
+        let count = self.get_current_messages().len();
+        if self.high_load {
+            count.min(HIGH_LOAD_MESSAGES)
+        } else {
+            count
+        }
+    }
+
+    /// Get buffered entries with repeat counts for currently selected topic
+    pub fn get_current_entries(&self) -> Vec<&BufferedMessage> {
+        let instances = self.selected_instances();
+        if instances.is_empty() {
+            return self
+                .selected_topic
+                .as_ref()
+                .map(|t| self.message_buffer.get_entries(t))
+                .unwrap_or_default();
+        }
+        // Every instance's messages, newest first
+        let mut entries: Vec<&BufferedMessage> = instances
+            .iter()
+            .flat_map(|topic| self.message_buffer.get_entries(topic))
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.message.timestamp));
+        entries
+    }
+
+    /// Get formatted payload for a message
+    pub fn format_payload(&self, msg: &MqttMessage) -> String {
+        // Formatting megabytes of JSON or hex every frame would stall the UI
+        if msg.is_large() {
+            return match self.payload_mode {
+                PayloadMode::Hex => msg.payload_hex_prefix(LARGE_PAYLOAD_BYTES),
+                _ => msg
+                    .payload_str_prefix(LARGE_PAYLOAD_BYTES)
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| msg.payload_hex_prefix(LARGE_PAYLOAD_BYTES)),
+            };
+        }
+        match self.payload_mode {
+            PayloadMode::Auto => {
+                content_type::decode(&msg.payload, self.config.ui.encoding_for(&msg.topic)).text
+            }
+            PayloadMode::Raw => msg
+                .payload_str()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| msg.payload_hex()),
+            PayloadMode::Hex => msg.payload_hex(),
+            PayloadMode::Json => msg
+                .payload_json_pretty()
+                .unwrap_or_else(|| "<not valid JSON>".to_string()),
+        }
+    }
+
+    fn is_away(&self) -> bool {
+        self.away
+            .as_ref()
+            .is_some_and(|away| away.phase == AwayPhase::Away)
+    }
+
+    /// Get connection status string
+    pub fn connection_status(&self) -> &'static str {
+        if self.is_away() {
+            return "Away (session kept)";
+        }
+        match self.connection_state {
+            ConnectionState::Disconnected => "Disconnected",
+            ConnectionState::Connecting => "Connecting...",
+            ConnectionState::Connected => "Connected",
+            ConnectionState::Reconnecting => "Reconnecting...",
+        }
+    }
+
+    /// Get connection status color
+    pub fn connection_color(&self) -> ratatui::style::Color {
+        use ratatui::style::Color;
+        if self.is_away() {
+            return Color::Yellow;
+        }
+        match self.connection_state {
+            ConnectionState::Disconnected => Color::Red,
+            ConnectionState::Connecting => Color::Yellow,
+            ConnectionState::Connected => Color::Green,
+            ConnectionState::Reconnecting => Color::Yellow,
+        }
+    }
+
+    /// Open bookmark manager
+    pub fn open_bookmark_manager(&mut self) {
+        if !self.allow(Feature::Bookmarks) {
+            return;
+        }
+        self.input_mode = InputMode::BookmarkManager;
+        self.bookmark_manager.selected_index = 0;
+        self.bookmark_manager.editing = None;
+        self.bookmark_manager.prompt = None;
+        self.set_status("Bookmarks");
+    }
+
+    /// Handle bookmark manager input
+    pub fn handle_bookmark_manager_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        // If editing a bookmark, handle edit input
+        if self.bookmark_manager.editing.is_some() {
+            self.handle_bookmark_edit_input(code, modifiers);
+            return;
+        }
+
+        if self.bookmark_manager.prompt.is_some() {
+            self.handle_bookmark_prompt_input(code, modifiers);
+            return;
+        }
+
+        let rows = self.bookmark_rows();
+        let selected = rows.get(self.bookmark_manager.selected_index).cloned();
+        match code {
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Down | KeyCode::Char('j')
+                if self.bookmark_manager.selected_index + 1 < rows.len() =>
+            {
+                self.bookmark_manager.selected_index += 1;
+            }
+            KeyCode::Up | KeyCode::Char('k') if self.bookmark_manager.selected_index > 0 => {
+                self.bookmark_manager.selected_index -= 1;
+            }
+            KeyCode::Enter => match selected {
+                Some(BookmarkRow::Folder { path, .. }) => self.toggle_bookmark_folder(&path),
+                Some(BookmarkRow::Bookmark { index, .. }) if self.allow(Feature::Publish) => {
+                    // Quick publish selected bookmark
+                    if let Some(bookmark) = self.user_data.bookmarks.get(index) {
+                        self.pending_publish = Some(PendingPublish {
+                            topic: bookmark.topic.clone(),
+                            payload: bookmark.payload.as_bytes().to_vec(),
+                            qos: bookmark.qos,
+                            retain: bookmark.retain,
+                            response: None,
+                        });
+                        self.set_status(&format!("Publishing to {}", bookmark.topic));
+                    }
+                }
+                Some(BookmarkRow::Bookmark { .. }) | None => {}
+            },
+            KeyCode::Left | KeyCode::Char('h') => {
+                if let Some(folder) = self.selected_bookmark_folder() {
+                    self.bookmark_manager.collapsed.insert(folder.clone());
+                    self.select_bookmark_folder(&folder);
+                }
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                if let Some(BookmarkRow::Folder { path, .. }) = selected {
+                    self.bookmark_manager.collapsed.remove(&path);
+                }
+            }
+            KeyCode::Char('a') => {
+                // Add new bookmark, in the selected folder
+                let folder = self.selected_bookmark_folder();
+                self.start_bookmark_edit(None);
+                if let (Some(editing), Some(folder)) = (&mut self.bookmark_manager.editing, folder)
+                {
+                    editing.category = folder;
+                }
+            }
+            KeyCode::Char('e') => match selected {
+                Some(BookmarkRow::Bookmark { index, .. }) => self.start_bookmark_edit(Some(index)),
+                Some(BookmarkRow::Folder { .. }) => {
+                    self.set_status("Folders follow their bookmarks; edit a bookmark's folder")
+                }
+                None => {}
+            },
+            KeyCode::Char('d') => match selected {
+                Some(BookmarkRow::Bookmark { index, .. }) => {
+                    self.user_data.remove_bookmark(index);
+                    self.save_user_data();
+                    // Adjust selection if needed
+                    let count = self.bookmark_rows().len();
+                    if self.bookmark_manager.selected_index >= count {
+                        self.bookmark_manager.selected_index = count.saturating_sub(1);
+                    }
+                    self.set_status("Bookmark deleted");
+                }
+                Some(BookmarkRow::Folder { .. }) => {
+                    self.set_status("Select a bookmark to delete");
+                }
+                None => {}
+            },
+            KeyCode::Char('x') => self.export_bookmarks(),
+            KeyCode::Char('i') => self.open_bookmark_prompt(BookmarkPrompt::Import),
+            KeyCode::Char('t') => match selected {
+                Some(BookmarkRow::Bookmark { index, .. }) => self.toggle_bookmark_schedule(index),
+                Some(BookmarkRow::Folder { .. }) => {
+                    self.set_status("Select a bookmark to schedule")
+                }
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Rows of the bookmark folder tree as shown in the manager
+    pub fn bookmark_rows(&self) -> Vec<BookmarkRow> {
+        bookmark_rows(&self.user_data.bookmarks, &self.bookmark_manager.collapsed)
+    }
+
+    /// The selected folder, or the folder of the selected bookmark
+    fn selected_bookmark_folder(&self) -> Option<String> {
+        match self
+            .bookmark_rows()
+            .get(self.bookmark_manager.selected_index)?
+        {
+            BookmarkRow::Folder { path, .. } => Some(path.clone()),
+            BookmarkRow::Bookmark { index, .. } => {
+                self.user_data.bookmarks.get(*index)?.category.clone()
+            }
+        }
+    }
+
+    fn toggle_bookmark_folder(&mut self, path: &str) {
+        if !self.bookmark_manager.collapsed.remove(path) {
+            self.bookmark_manager.collapsed.insert(path.to_string());
+        }
+    }
+
+    fn select_bookmark_folder(&mut self, folder: &str) {
+        if let Some(row) = self
+            .bookmark_rows()
+            .iter()
+            .position(|row| matches!(row, BookmarkRow::Folder { path, .. } if path == folder))
+        {
+            self.bookmark_manager.selected_index = row;
+        }
+    }
+
+    /// Select a bookmark's row, unfolding the folders it lives in
+    fn select_bookmark(&mut self, index: usize) {
+        if let Some(bookmark) = self.user_data.bookmarks.get(index) {
+            let collapsed = &mut self.bookmark_manager.collapsed;
+            collapsed.retain(|folder| !bookmark.in_folder(folder));
+        }
+        if let Some(row) = self
+            .bookmark_rows()
+            .iter()
+            .position(|row| matches!(row, BookmarkRow::Bookmark { index: i, .. } if *i == index))
+        {
+            self.bookmark_manager.selected_index = row;
+        }
+    }
+
+    /// Export the selected folder's bookmarks, or all of them, for sharing
+    fn export_bookmarks(&mut self) {
+        let selected = self
+            .bookmark_rows()
+            .get(self.bookmark_manager.selected_index)
+            .cloned();
+        let (bookmarks, scope): (Vec<Bookmark>, String) = match selected {
+            Some(BookmarkRow::Folder { path, .. }) => (
+                self.user_data
+                    .bookmarks
+                    .iter()
+                    .filter(|b| b.in_folder(&path))
+                    .cloned()
+                    .collect(),
+                format!(" from {}", path),
+            ),
+            _ => (self.user_data.bookmarks.clone(), String::new()),
+        };
+        if bookmarks.is_empty() {
+            self.set_status("No bookmarks to export");
+            return;
         }
-        self.set_status("Server saved");
-        Ok(())
-    }
 
-    pub fn save_config(&self) -> Result<()> {
-        self.config
-            .save_with_backup(&self.config_path, CONFIG_BACKUP_LIMIT)
-            .context("Failed to save config")?;
-        Ok(())
+        let dir = &self.paths.cache_dir;
+        let filename = dir.join(BookmarkSet::default_filename());
+        let count = bookmarks.len();
+        let result = std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {:?}", dir))
+            .and_then(|_| BookmarkSet::new(bookmarks).save_to(&filename));
+        match result {
+            Ok(()) => self.set_status(&format!(
+                "Exported {} bookmarks{} to {}",
+                count,
+                scope,
+                filename.display()
+            )),
+            Err(e) => self.set_status(&format!("Export failed: {:#}", e)),
+        }
     }
 
-    /// Get messages for currently selected topic
-    pub fn get_current_messages(&self) -> Vec<&MqttMessage> {
-        self.selected_topic
-            .as_ref()
-            .map(|t| self.message_buffer.get_messages(t))
-            .unwrap_or_default()
+    fn open_bookmark_prompt(&mut self, prompt: BookmarkPrompt) {
+        self.bookmark_manager.prompt = Some(prompt);
+        self.bookmark_manager.prompt_input.clear();
+        self.bookmark_manager.prompt_cursor = InputCursor::default();
     }
 
-    /// Get formatted payload for a message
-    pub fn format_payload(&self, msg: &MqttMessage) -> String {
-        match self.payload_mode {
-            PayloadMode::Auto => {
-                if let Some(json) = msg.payload_json_pretty() {
-                    json
-                } else if let Some(s) = msg.payload_str() {
-                    s.to_string()
-                } else {
-                    msg.payload_hex()
+    fn handle_bookmark_prompt_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc => {
+                self.bookmark_manager.prompt = None;
+            }
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut self.bookmark_manager.prompt_input);
+                match self.bookmark_manager.prompt.take() {
+                    Some(BookmarkPrompt::Import) => {
+                        if let Err(e) = self.import_bookmarks(Path::new(input.trim())) {
+                            self.set_status(&format!("Import failed: {:#}", e));
+                        }
+                    }
+                    Some(BookmarkPrompt::Schedule(index)) => self.schedule_bookmark(index, &input),
+                    None => {}
                 }
             }
-            PayloadMode::Raw => msg
-                .payload_str()
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| msg.payload_hex()),
-            PayloadMode::Hex => msg.payload_hex(),
-            PayloadMode::Json => msg
-                .payload_json_pretty()
-                .unwrap_or_else(|| "<not valid JSON>".to_string()),
+            _ => {
+                let manager = &mut self.bookmark_manager;
+                manager
+                    .prompt_cursor
+                    .handle_key(&mut manager.prompt_input, code, modifiers);
+            }
         }
     }
 
-    /// Get connection status string
-    pub fn connection_status(&self) -> &'static str {
-        match self.connection_state {
-            ConnectionState::Disconnected => "Disconnected",
-            ConnectionState::Connecting => "Connecting...",
-            ConnectionState::Connected => "Connected",
-            ConnectionState::Reconnecting => "Reconnecting...",
+    /// Cancel a bookmark's schedule, or ask for one
+    fn toggle_bookmark_schedule(&mut self, index: usize) {
+        if !self.allow(Feature::Publish) {
+            return;
+        }
+        let Some(bookmark) = self.user_data.bookmarks.get(index) else {
+            return;
+        };
+        if let Some(pos) = self
+            .bookmark_schedules
+            .iter()
+            .position(|schedule| schedule.matches(bookmark))
+        {
+            let schedule = self.bookmark_schedules.remove(pos);
+            self.set_status(&format!(
+                "Stopped scheduled publish of {} after {} runs",
+                schedule.name, schedule.runs
+            ));
+        } else {
+            self.open_bookmark_prompt(BookmarkPrompt::Schedule(index));
         }
     }
 
-    /// Get connection status color
-    pub fn connection_color(&self) -> ratatui::style::Color {
-        use ratatui::style::Color;
-        match self.connection_state {
-            ConnectionState::Disconnected => Color::Red,
-            ConnectionState::Connecting => Color::Yellow,
-            ConnectionState::Connected => Color::Green,
-            ConnectionState::Reconnecting => Color::Yellow,
+    fn schedule_bookmark(&mut self, index: usize, spec: &str) {
+        let Some(bookmark) = self.user_data.bookmarks.get(index) else {
+            return;
+        };
+        match ActiveSchedule::start(bookmark, spec) {
+            Ok(schedule) => {
+                self.set_status(&format!(
+                    "Publishing {} {} (next {})",
+                    schedule.name,
+                    schedule.spec,
+                    schedule.next_run.format("%H:%M:%S")
+                ));
+                self.bookmark_schedules.push(schedule);
+            }
+            Err(e) => self.set_status(&format!("Invalid schedule: {:#}", e)),
         }
     }
 
-    /// Open bookmark manager
-    pub fn open_bookmark_manager(&mut self) {
-        self.input_mode = InputMode::BookmarkManager;
-        self.bookmark_manager.selected_index = 0;
-        self.bookmark_manager.editing = None;
-        self.set_status("Bookmarks");
+    /// The active schedule of a bookmark, if any
+    pub fn bookmark_schedule(&self, bookmark: &Bookmark) -> Option<&ActiveSchedule> {
+        self.bookmark_schedules
+            .iter()
+            .find(|schedule| schedule.matches(bookmark))
     }
 
-    /// Handle bookmark manager input
-    pub fn handle_bookmark_manager_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
-        // If editing a bookmark, handle edit input
-        if self.bookmark_manager.editing.is_some() {
-            self.handle_bookmark_edit_input(code, modifiers);
+    /// Publish the next due scheduled bookmark. One per loop iteration since
+    /// there is a single pending publish slot; others follow on the next tick.
+    pub fn run_schedules(&mut self) {
+        if self.pending_publish.is_some() {
             return;
         }
+        let now = chrono::Local::now();
+        let Some(pos) = self
+            .bookmark_schedules
+            .iter()
+            .position(|schedule| schedule.next_run <= now)
+        else {
+            return;
+        };
 
-        match code {
-            KeyCode::Esc => {
-                self.input_mode = InputMode::Normal;
+        let schedule = &mut self.bookmark_schedules[pos];
+        let Some(bookmark) = self
+            .user_data
+            .bookmarks
+            .iter()
+            .find(|bookmark| schedule.matches(bookmark))
+        else {
+            let schedule = self.bookmark_schedules.remove(pos);
+            self.set_status(&format!(
+                "Stopped schedule of {}: bookmark is gone",
+                schedule.name
+            ));
+            return;
+        };
+
+        self.pending_publish = Some(PendingPublish {
+            topic: bookmark.topic.clone(),
+            payload: bookmark.payload.as_bytes().to_vec(),
+            qos: bookmark.qos,
+            retain: bookmark.retain,
+            response: None,
+        });
+        schedule.runs += 1;
+        match schedule.schedule.next_after(now) {
+            Some(next) => schedule.next_run = next,
+            None => {
+                self.bookmark_schedules.remove(pos);
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let count = self.user_data.bookmarks.len();
-                if count > 0 && self.bookmark_manager.selected_index < count - 1 {
-                    self.bookmark_manager.selected_index += 1;
-                }
+        }
+    }
+
+    /// Open the scenario manager
+    pub fn open_scenarios(&mut self) {
+        if !self.allow(Feature::Publish) {
+            return;
+        }
+        self.input_mode = InputMode::Scenarios;
+        self.scenario_manager.editing = None;
+        let last = self.user_data.scenarios.len().saturating_sub(1);
+        self.scenario_manager.selected_index = self.scenario_manager.selected_index.min(last);
+    }
+
+    fn handle_scenario_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if self.scenario_manager.editing.is_some() {
+            self.handle_scenario_edit_input(code, modifiers);
+            return;
+        }
+
+        let count = self.user_data.scenarios.len();
+        let selected = self.scenario_manager.selected_index;
+        match code {
+            KeyCode::Esc => self.input_mode = InputMode::Normal,
+            KeyCode::Down | KeyCode::Char('j') if selected + 1 < count => {
+                self.scenario_manager.selected_index += 1;
             }
             KeyCode::Up | KeyCode::Char('k') => {
-                if self.bookmark_manager.selected_index > 0 {
-                    self.bookmark_manager.selected_index -= 1;
-                }
-            }
-            KeyCode::Enter => {
-                // Quick publish selected bookmark
-                if let Some(bookmark) = self
-                    .user_data
-                    .bookmarks
-                    .get(self.bookmark_manager.selected_index)
-                {
-                    self.pending_publish = Some(PendingPublish {
-                        topic: bookmark.topic.clone(),
-                        payload: bookmark.payload.as_bytes().to_vec(),
-                        qos: bookmark.qos,
-                        retain: bookmark.retain,
-                    });
-                    self.set_status(&format!("Publishing to {}", bookmark.topic));
-                }
+                self.scenario_manager.selected_index = selected.saturating_sub(1);
             }
+            KeyCode::Enter => self.start_scenario(selected),
+            KeyCode::Char('s') => self.stop_scenario(),
             KeyCode::Char('a') => {
-                // Add new bookmark
-                self.start_bookmark_edit(None);
+                self.scenario_manager.editing = Some(ScenarioEditState {
+                    is_new: true,
+                    index: count,
+                    on_steps: false,
+                    cursor: InputCursor::default(),
+                    name: String::new(),
+                    steps: String::new(),
+                });
             }
             KeyCode::Char('e') => {
-                // Edit selected bookmark
-                if !self.user_data.bookmarks.is_empty() {
-                    let index = self
-                        .bookmark_manager
-                        .selected_index
-                        .min(self.user_data.bookmarks.len() - 1);
-                    self.start_bookmark_edit(Some(index));
+                if let Some(scenario) = self.user_data.scenarios.get(selected) {
+                    self.scenario_manager.editing = Some(ScenarioEditState {
+                        is_new: false,
+                        index: selected,
+                        on_steps: false,
+                        cursor: InputCursor::end_of(&scenario.name),
+                        name: scenario.name.clone(),
+                        steps: scenario.steps_text(),
+                    });
                 }
             }
-            KeyCode::Char('d') => {
-                // Delete selected bookmark
-                if !self.user_data.bookmarks.is_empty() {
-                    let index = self.bookmark_manager.selected_index;
-                    if index < self.user_data.bookmarks.len() {
-                        self.user_data.remove_bookmark(index);
-                        self.save_user_data();
-                        // Adjust selection if needed
-                        if self.bookmark_manager.selected_index > 0
-                            && self.bookmark_manager.selected_index
-                                >= self.user_data.bookmarks.len()
-                        {
-                            self.bookmark_manager.selected_index =
-                                self.user_data.bookmarks.len().saturating_sub(1);
-                        }
-                        self.set_status("Bookmark deleted");
-                    }
-                }
+            KeyCode::Char('d') if selected < count => {
+                let removed = self.user_data.scenarios.remove(selected);
+                self.scenario_manager.selected_index = selected.min(count.saturating_sub(2));
+                self.save_user_data();
+                self.set_status(&format!("Deleted scenario {}", removed.name));
             }
             _ => {}
         }
     }
 
+    fn handle_scenario_edit_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(editing) = &mut self.scenario_manager.editing else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.scenario_manager.editing = None,
+            KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.save_scenario_edit()
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                editing.on_steps = !editing.on_steps;
+                let value = if editing.on_steps {
+                    &editing.steps
+                } else {
+                    &editing.name
+                };
+                editing.cursor = InputCursor::end_of(value);
+            }
+            // Steps take one line each
+            KeyCode::Enter if editing.on_steps => {
+                editing.cursor.paste(&mut editing.steps, "\n", true)
+            }
+            KeyCode::Enter => self.save_scenario_edit(),
+            _ => {
+                let value = if editing.on_steps {
+                    &mut editing.steps
+                } else {
+                    &mut editing.name
+                };
+                editing.cursor.handle_key(value, code, modifiers);
+            }
+        }
+    }
+
+    fn save_scenario_edit(&mut self) {
+        let Some(editing) = self.scenario_manager.editing.clone() else {
+            return;
+        };
+        let name = editing.name.trim();
+        if name.is_empty() {
+            self.set_status("Name cannot be empty");
+            return;
+        }
+        let steps = match Scenario::parse_steps(&editing.steps) {
+            Ok(steps) => steps,
+            Err(err) => {
+                self.set_status(&format!("{:#}", err));
+                return;
+            }
+        };
+
+        let scenario = Scenario {
+            name: name.to_string(),
+            steps,
+        };
+        let status = format!("Saved scenario {} ({} steps)", name, scenario.steps.len());
+        if editing.is_new {
+            self.user_data.scenarios.push(scenario);
+            self.scenario_manager.selected_index = self.user_data.scenarios.len() - 1;
+        } else if let Some(existing) = self.user_data.scenarios.get_mut(editing.index) {
+            *existing = scenario;
+        }
+        self.scenario_manager.editing = None;
+        self.save_user_data();
+        self.set_status(&status);
+    }
+
+    fn start_scenario(&mut self, index: usize) {
+        let Some(scenario) = self.user_data.scenarios.get(index) else {
+            return;
+        };
+        if let Some(run) = &self.scenario_run {
+            let status = format!("Scenario {} is still running (s to stop)", run.name);
+            self.set_status(&status);
+            return;
+        }
+        if self.connection_state != ConnectionState::Connected {
+            self.set_status("Cannot run scenario: not connected");
+            return;
+        }
+        let status = format!(
+            "Running scenario {}: {} steps over {:.1}s",
+            scenario.name,
+            scenario.steps.len(),
+            scenario.duration().as_secs_f64()
+        );
+        tracing::info!("{}", status);
+        self.scenario_run = Some(ScenarioRun::start(scenario, Instant::now()));
+        self.set_status(&status);
+    }
+
+    fn stop_scenario(&mut self) {
+        if let Some(run) = self.scenario_run.take() {
+            self.set_status(&format!(
+                "Stopped scenario {} after {}/{} steps",
+                run.name,
+                run.sent(),
+                run.total()
+            ));
+        }
+    }
+
+    /// Publish the running scenario's next step once its delay has passed
+    pub fn run_scenario(&mut self) {
+        if self.pending_publish.is_some() {
+            return;
+        }
+        let Some(run) = &mut self.scenario_run else {
+            return;
+        };
+        // Reported a loop after the last publish so it isn't overwritten
+        if run.is_finished() {
+            let status = format!("Scenario {} finished ({} steps)", run.name, run.total());
+            tracing::info!("{}", status);
+            self.scenario_run = None;
+            self.set_status(&status);
+            return;
+        }
+        let Some(step) = run.due(Instant::now()) else {
+            return;
+        };
+        tracing::info!(
+            "Scenario {} step {}/{}: {}",
+            run.name,
+            run.sent(),
+            run.total(),
+            step.topic
+        );
+        self.pending_publish = Some(PendingPublish {
+            topic: step.topic,
+            payload: step.payload.into_bytes(),
+            qos: step.qos,
+            retain: step.retain,
+            response: None,
+        });
+    }
+
+    /// Start waiting for the response to a publish that just went out
+    pub fn watch_response(&mut self, topic: &str, spec: ResponseSpec) {
+        let status = format!(
+            "Published to {}, waiting {}s for a response on {}",
+            topic,
+            spec.timeout.as_secs_f64(),
+            spec.pattern
+        );
+        let pattern = spec.pattern.clone();
+        let timeout = spec.timeout;
+        let id = self.response_watcher.watch(topic, spec, Instant::now());
+        tracing::info!(
+            "Publish #{} to {} expects a response on {} within {:?}",
+            id,
+            topic,
+            pattern,
+            timeout
+        );
+        self.set_status(&status);
+    }
+
+    /// Warn about publishes whose response didn't arrive in time
+    pub fn check_response_watchers(&mut self) {
+        for event in self.response_watcher.expire(Instant::now()) {
+            self.report_response(event, None);
+        }
+    }
+
+    fn report_response(&mut self, event: ResponseEvent, message: Option<&MqttMessage>) {
+        let elapsed = event.elapsed.as_millis();
+        match event.outcome {
+            ResponseOutcome::Received { topic } => {
+                let payload = message
+                    .and_then(|msg| msg.payload_str_prefix(200))
+                    .map(|payload| text::ellipsize(payload.trim(), 60))
+                    .unwrap_or_default();
+                tracing::info!(
+                    "Response #{} to {} on {} after {} ms: {}",
+                    event.id,
+                    event.request_topic,
+                    topic,
+                    elapsed,
+                    payload
+                );
+                self.set_status(&format!(
+                    "Response to {} on {} after {} ms",
+                    event.request_topic, topic, elapsed
+                ));
+            }
+            ResponseOutcome::TimedOut => {
+                tracing::warn!(
+                    "No response #{} to {} on {} within {} ms",
+                    event.id,
+                    event.request_topic,
+                    event.pattern,
+                    elapsed
+                );
+                self.set_status(&format!(
+                    "No response to {} on {} after {}s",
+                    event.request_topic,
+                    event.pattern,
+                    event.elapsed.as_secs()
+                ));
+            }
+        }
+    }
+
+    /// Merge a shared bookmark file into the user's bookmarks
+    pub fn import_bookmarks(&mut self, path: &Path) -> Result<()> {
+        let set = BookmarkSet::load_from(path)?;
+        let (added, updated) = self.user_data.import_bookmarks(set.bookmarks);
+        self.save_user_data();
+        self.set_status(&format!(
+            "Imported bookmarks: {} added, {} updated",
+            added, updated
+        ));
+        Ok(())
+    }
+
     fn start_bookmark_edit(&mut self, index: Option<usize>) {
         let edit_state = if let Some(idx) = index {
             if let Some(bookmark) = self.user_data.bookmarks.get(idx) {
@@ -2807,7 +6806,7 @@ impl App {
                     is_new: false,
                     index: idx,
                     field: BookmarkField::Name,
-                    cursor: bookmark.name.len(),
+                    cursor: InputCursor::end_of(&bookmark.name),
                     name: bookmark.name.clone(),
                     topic: bookmark.topic.clone(),
                     payload: bookmark.payload.clone(),
@@ -2824,7 +6823,7 @@ impl App {
                 is_new: true,
                 index: self.user_data.bookmarks.len(),
                 field: BookmarkField::Name,
-                cursor: 0,
+                cursor: InputCursor::default(),
                 name: String::new(),
                 topic: self.selected_topic.clone().unwrap_or_default(),
                 payload: String::new(),
@@ -2836,7 +6835,7 @@ impl App {
         self.bookmark_manager.editing = Some(edit_state);
     }
 
-    fn handle_bookmark_edit_input(&mut self, code: KeyCode, _modifiers: KeyModifiers) {
+    fn handle_bookmark_edit_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
         // Get current field to check conditions
         let current_field = match &self.bookmark_manager.editing {
             Some(e) => e.field,
@@ -2867,20 +6866,16 @@ impl App {
                     payload: editing.payload.clone(),
                     qos: editing.qos,
                     retain: editing.retain,
-                    category: if editing.category.trim().is_empty() {
-                        None
-                    } else {
-                        Some(editing.category.trim().to_string())
-                    },
+                    category: normalize_folder(&editing.category),
                 };
 
                 if editing.is_new {
                     self.user_data.add_bookmark(bookmark);
-                    self.bookmark_manager.selected_index =
-                        self.user_data.bookmarks.len().saturating_sub(1);
+                    self.select_bookmark(self.user_data.bookmarks.len().saturating_sub(1));
                     self.set_status("Bookmark added");
                 } else {
                     self.user_data.update_bookmark(editing.index, bookmark);
+                    self.select_bookmark(editing.index);
                     self.set_status("Bookmark updated");
                 }
                 self.save_user_data();
@@ -2889,39 +6884,14 @@ impl App {
                 let next_field = next_bookmark_field(current_field);
                 if let Some(editing) = &mut self.bookmark_manager.editing {
                     editing.field = next_field;
-                    editing.cursor = bookmark_field_len(editing);
+                    editing.cursor = InputCursor::at(bookmark_field_len(editing));
                 }
             }
             KeyCode::BackTab => {
                 let prev_field = prev_bookmark_field(current_field);
                 if let Some(editing) = &mut self.bookmark_manager.editing {
                     editing.field = prev_field;
-                    editing.cursor = bookmark_field_len(editing);
-                }
-            }
-            KeyCode::Left => {
-                if let Some(editing) = &mut self.bookmark_manager.editing {
-                    if editing.cursor > 0 {
-                        editing.cursor -= 1;
-                    }
-                }
-            }
-            KeyCode::Right => {
-                if let Some(editing) = &mut self.bookmark_manager.editing {
-                    let max = bookmark_field_len(editing);
-                    if editing.cursor < max {
-                        editing.cursor += 1;
-                    }
-                }
-            }
-            KeyCode::Home => {
-                if let Some(editing) = &mut self.bookmark_manager.editing {
-                    editing.cursor = 0;
-                }
-            }
-            KeyCode::End => {
-                if let Some(editing) = &mut self.bookmark_manager.editing {
-                    editing.cursor = bookmark_field_len(editing);
+                    editing.cursor = InputCursor::at(bookmark_field_len(editing));
                 }
             }
             // QoS field: 0, 1, 2 to set directly, space to cycle
@@ -2951,40 +6921,18 @@ impl App {
                     editing.retain = !editing.retain;
                 }
             }
-            KeyCode::Backspace => {
-                if matches!(
-                    current_field,
-                    BookmarkField::Name
-                        | BookmarkField::Category
-                        | BookmarkField::Topic
-                        | BookmarkField::Payload
-                ) {
-                    self.bookmark_edit_backspace();
-                }
-            }
-            KeyCode::Delete => {
-                if matches!(
-                    current_field,
-                    BookmarkField::Name
-                        | BookmarkField::Category
-                        | BookmarkField::Topic
-                        | BookmarkField::Payload
-                ) {
-                    self.bookmark_edit_delete();
-                }
-            }
-            KeyCode::Char(c) => {
-                if matches!(
-                    current_field,
-                    BookmarkField::Name
-                        | BookmarkField::Category
-                        | BookmarkField::Topic
-                        | BookmarkField::Payload
-                ) {
-                    self.bookmark_edit_insert(c);
+            _ => {
+                if let Some(editing) = &mut self.bookmark_manager.editing {
+                    let value = match editing.field {
+                        BookmarkField::Name => &mut editing.name,
+                        BookmarkField::Category => &mut editing.category,
+                        BookmarkField::Topic => &mut editing.topic,
+                        BookmarkField::Payload => &mut editing.payload,
+                        BookmarkField::Qos | BookmarkField::Retain => return,
+                    };
+                    editing.cursor.handle_key(value, code, modifiers);
                 }
             }
-            _ => {}
         }
     }
 
@@ -2998,67 +6946,6 @@ impl App {
         }
     }
 
-    fn bookmark_edit_mut_field(&mut self) -> Option<&mut String> {
-        let editing = self.bookmark_manager.editing.as_mut()?;
-        Some(match editing.field {
-            BookmarkField::Name => &mut editing.name,
-            BookmarkField::Category => &mut editing.category,
-            BookmarkField::Topic => &mut editing.topic,
-            BookmarkField::Payload => &mut editing.payload,
-            BookmarkField::Qos | BookmarkField::Retain => return None,
-        })
-    }
-
-    fn bookmark_edit_insert(&mut self, ch: char) {
-        let cursor = self
-            .bookmark_manager
-            .editing
-            .as_ref()
-            .map(|e| e.cursor)
-            .unwrap_or(0);
-        if let Some(value) = self.bookmark_edit_mut_field() {
-            let cursor = cursor.min(value.len());
-            value.insert(cursor, ch);
-        }
-        if let Some(editing) = &mut self.bookmark_manager.editing {
-            editing.cursor = editing.cursor.saturating_add(1);
-        }
-    }
-
-    fn bookmark_edit_backspace(&mut self) {
-        let cursor = self
-            .bookmark_manager
-            .editing
-            .as_ref()
-            .map(|e| e.cursor)
-            .unwrap_or(0);
-        if cursor == 0 {
-            return;
-        }
-        if let Some(value) = self.bookmark_edit_mut_field() {
-            if !value.is_empty() && cursor <= value.len() {
-                value.remove(cursor.saturating_sub(1));
-            }
-        }
-        if let Some(editing) = &mut self.bookmark_manager.editing {
-            editing.cursor = editing.cursor.saturating_sub(1);
-        }
-    }
-
-    fn bookmark_edit_delete(&mut self) {
-        let cursor = self
-            .bookmark_manager
-            .editing
-            .as_ref()
-            .map(|e| e.cursor)
-            .unwrap_or(0);
-        if let Some(value) = self.bookmark_edit_mut_field() {
-            if cursor < value.len() {
-                value.remove(cursor);
-            }
-        }
-    }
-
     pub fn bookmark_edit_field_value(&self, field: BookmarkField) -> String {
         if let Some(editing) = &self.bookmark_manager.editing {
             match field {
@@ -3076,24 +6963,23 @@ impl App {
 
     /// Save current publish dialog as a bookmark
     pub fn save_publish_as_bookmark(&mut self) {
+        if !self.allow(Feature::Bookmarks) {
+            return;
+        }
         if self.publish_edit.topic.trim().is_empty() {
             self.set_status("Cannot save: topic is empty");
             return;
         }
 
         // Create a name from the topic
-        let name = if self.publish_edit.topic.len() > 20 {
-            format!("{}...", &self.publish_edit.topic[..20])
-        } else {
-            self.publish_edit.topic.clone()
-        };
+        let name = text::ellipsize(&self.publish_edit.topic, 23);
 
         // Start bookmark edit with pre-filled values
         let edit_state = BookmarkEditState {
             is_new: true,
             index: self.user_data.bookmarks.len(),
             field: BookmarkField::Name,
-            cursor: name.len(),
+            cursor: InputCursor::end_of(&name),
             name,
             topic: self.publish_edit.topic.clone(),
             payload: self.publish_edit.payload.clone(),
@@ -3110,6 +6996,14 @@ impl App {
 }
 
 /// Get the length of the current field value in a bookmark edit state
+/// Write an export, creating its directory first
+fn write_new_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, contents)
+}
+
 fn bookmark_field_len(editing: &BookmarkEditState) -> usize {
     match editing.field {
         BookmarkField::Name => editing.name.len(),
@@ -3168,6 +7062,32 @@ fn create_wildcard_pattern(topic: &str, separator: char, wildcard_single: char)
         .join(&sep)
 }
 
+/// Key for a server's parked data
+fn server_key(kind: BrokerKind, name: &str) -> String {
+    format!("{}:{}", kind.label(), name)
+}
+
+fn new_metric_tracker(ui: &UiConfig) -> MetricTracker {
+    MetricTracker::new(Duration::from_secs(ui.metric_window_secs), ui.metric_points)
+}
+
+fn new_device_tracker(ui: &UiConfig) -> DeviceTracker {
+    let mut tracker = DeviceTracker::new();
+    tracker.set_availability_topics(ui.availability_topics.clone());
+    tracker
+}
+
+fn new_cardinality_watch(ui: &UiConfig) -> CardinalityWatch {
+    CardinalityWatch::new(
+        ui.cardinality_limit,
+        Duration::from_secs(ui.cardinality_window_secs),
+    )
+}
+
+fn new_state_tracker(ui: &UiConfig) -> StateTracker {
+    StateTracker::new(Duration::from_secs(ui.metric_window_secs))
+}
+
 /// Get a short version of a topic for display
 fn short_topic(topic: &str, separator: char) -> String {
     let parts: Vec<&str> = topic.split(separator).collect();
@@ -0,0 +1,599 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::state::schema_tracker::{FieldType, Schema};
+use crate::state::{looks_like_id, parameter_name};
+
+/// `$ref` hops followed before giving up on a reference cycle
+const MAX_REF_DEPTH: usize = 16;
+
+/// Larger payloads are left out of generated examples
+const MAX_EXAMPLE_BYTES: usize = 4096;
+
+/// Topic documentation loaded from an AsyncAPI 2.x or 3.x document
+#[derive(Debug, Clone, Default)]
+pub struct AsyncApiSpec {
+    pub title: String,
+    pub version: String,
+    pub channels: Vec<ChannelDoc>,
+}
+
+/// One channel: a topic (with `{parameter}` levels) and its messages
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelDoc {
+    pub address: String,
+    pub description: Option<String>,
+    pub messages: Vec<MessageDoc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageDoc {
+    pub name: Option<String>,
+    pub summary: Option<String>,
+    /// JSON Schema of the payload, with a top-level `$ref` resolved
+    pub payload: Option<Value>,
+    pub examples: Vec<Value>,
+}
+
+impl AsyncApiSpec {
+    /// Load a YAML or JSON AsyncAPI document
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read AsyncAPI spec from {:?}", path))?;
+        Self::parse(&content).with_context(|| format!("Invalid AsyncAPI spec {:?}", path))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        // YAML is a superset of JSON, so this reads both
+        let root: Value = serde_yaml::from_str(content)?;
+        let Some(version) = root.get("asyncapi").and_then(Value::as_str) else {
+            bail!("Missing 'asyncapi' version field");
+        };
+
+        let mut channels = Vec::new();
+        if let Some(map) = root.get("channels").and_then(Value::as_object) {
+            for (key, channel) in map {
+                let channel = resolve(&root, channel);
+                // 3.x keeps the topic in `address`, 2.x uses the key
+                let address = channel
+                    .get("address")
+                    .and_then(Value::as_str)
+                    .unwrap_or(key);
+                let description = text_field(channel, &["description", "summary"]);
+
+                let mut messages = Vec::new();
+                if let Some(map) = channel.get("messages").and_then(Value::as_object) {
+                    for (name, message) in map {
+                        collect_messages(&root, message, Some(name), &mut messages);
+                    }
+                }
+                for operation in ["subscribe", "publish"] {
+                    if let Some(message) = channel.get(operation).and_then(|op| op.get("message")) {
+                        collect_messages(&root, message, None, &mut messages);
+                    }
+                }
+
+                channels.push(ChannelDoc {
+                    address: address.to_string(),
+                    description,
+                    messages,
+                });
+            }
+        }
+
+        Ok(Self {
+            title: root
+                .pointer("/info/title")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            version: version.to_string(),
+            channels,
+        })
+    }
+
+    /// The channel documenting `topic`; the most specific one when several
+    /// match (fewest parameter levels)
+    pub fn channel_for(&self, topic: &str) -> Option<&ChannelDoc> {
+        self.channels
+            .iter()
+            .filter(|channel| channel.matches(topic))
+            .min_by_key(|channel| channel.parameter_count())
+    }
+}
+
+impl ChannelDoc {
+    /// Whether `topic` fits the address, each `{parameter}` level matching
+    /// any single level
+    pub fn matches(&self, topic: &str) -> bool {
+        let separator = if self.address.contains('/') || !self.address.contains('.') {
+            '/'
+        } else {
+            '.'
+        };
+        let mut levels = topic.split(separator);
+        for part in self.address.split(separator) {
+            match levels.next() {
+                Some(level) if is_parameter(part) && !level.is_empty() => {}
+                Some(level) if level == part => {}
+                _ => return false,
+            }
+        }
+        levels.next().is_none()
+    }
+
+    fn parameter_count(&self) -> usize {
+        self.address
+            .split(['/', '.'])
+            .filter(|part| is_parameter(part))
+            .count()
+    }
+}
+
+fn is_parameter(part: &str) -> bool {
+    part.len() > 2 && part.starts_with('{') && part.ends_with('}')
+}
+
+/// Follow local `$ref`s such as `#/components/messages/state`
+fn resolve<'a>(root: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..MAX_REF_DEPTH {
+        let Some(target) = value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+            .and_then(|pointer| root.pointer(pointer))
+        else {
+            break;
+        };
+        value = target;
+    }
+    value
+}
+
+fn text_field(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(Value::as_str))
+        .map(|text| text.trim().to_string())
+}
+
+fn collect_messages(root: &Value, message: &Value, key: Option<&str>, out: &mut Vec<MessageDoc>) {
+    let message = resolve(root, message);
+    if let Some(options) = message.get("oneOf").and_then(Value::as_array) {
+        for option in options {
+            collect_messages(root, option, None, out);
+        }
+        return;
+    }
+    let examples = message
+        .get("examples")
+        .and_then(Value::as_array)
+        .map(|examples| {
+            examples
+                .iter()
+                .map(|example| example.get("payload").unwrap_or(example).clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    out.push(MessageDoc {
+        name: text_field(message, &["name", "title"]).or_else(|| key.map(str::to_string)),
+        summary: text_field(message, &["summary", "description"]),
+        payload: message
+            .get("payload")
+            .map(|payload| resolve(root, payload).clone()),
+        examples,
+    });
+}
+
+/// Observed topics that no channel of the spec documents
+#[derive(Debug, Default)]
+pub struct SpecCoverage {
+    checked: HashSet<String>,
+    uncovered: BTreeSet<String>,
+}
+
+impl SpecCoverage {
+    /// Check a topic the first time it carries a message
+    pub fn observe(&mut self, spec: &AsyncApiSpec, topic: &str) {
+        if self.checked.contains(topic) {
+            return;
+        }
+        self.checked.insert(topic.to_string());
+        if spec.channel_for(topic).is_none() {
+            self.uncovered.insert(topic.to_string());
+        }
+    }
+
+    pub fn is_uncovered(&self, topic: &str) -> bool {
+        self.uncovered.contains(topic)
+    }
+
+    /// Undocumented topics, sorted
+    pub fn uncovered(&self) -> &BTreeSet<String> {
+        &self.uncovered
+    }
+
+    pub fn observed(&self) -> usize {
+        self.checked.len()
+    }
+}
+
+/// A topic seen on the broker, input to [`generate`]
+pub struct ObservedTopic<'a> {
+    pub topic: &'a str,
+    /// Learned JSON schema, `None` for non-JSON topics
+    pub schema: Option<&'a Schema>,
+    /// Latest payload, used as the example
+    pub example: Option<&'a [u8]>,
+}
+
+/// Channel being assembled from one or more observed topics
+struct ChannelDraft {
+    parameters: Vec<String>,
+    fields: HashMap<String, FieldType>,
+    is_json: bool,
+    example: Option<Value>,
+    topics: usize,
+}
+
+/// Default file name for a generated spec, e.g. `mqtop-asyncapi-20240101-120000.yaml`
+pub fn default_export_filename() -> String {
+    format!(
+        "mqtop-asyncapi-{}.yaml",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// Build a skeleton AsyncAPI 3.0 document from observed traffic. Levels that
+/// look like IDs become `{parameter}`s, so `devices/a1b2/state` and
+/// `devices/c3d4/state` share one channel with their schemas merged.
+pub fn generate(title: &str, separator: char, topics: &[ObservedTopic]) -> Result<String> {
+    let mut drafts: BTreeMap<String, ChannelDraft> = BTreeMap::new();
+    for observed in topics {
+        let (address, parameters) = templated_address(observed.topic, separator);
+        let draft = drafts.entry(address).or_insert_with(|| ChannelDraft {
+            parameters,
+            fields: HashMap::new(),
+            is_json: false,
+            example: None,
+            topics: 0,
+        });
+        draft.topics += 1;
+        if let Some(schema) = observed.schema {
+            draft.is_json = true;
+            for (path, field_type) in &schema.fields {
+                draft.fields.entry(path.clone()).or_insert(*field_type);
+            }
+        }
+        if draft.example.is_none() {
+            draft.example = observed
+                .example
+                .filter(|payload| payload.len() <= MAX_EXAMPLE_BYTES)
+                .map(|payload| {
+                    serde_json::from_slice(payload).unwrap_or_else(|_| {
+                        Value::String(String::from_utf8_lossy(payload).into_owned())
+                    })
+                });
+        }
+    }
+
+    let mut channels = serde_yaml::Mapping::new();
+    let mut ids = HashSet::new();
+    for (address, draft) in drafts {
+        let mut id = channel_id(&address);
+        while !ids.insert(id.clone()) {
+            id.push('_');
+        }
+
+        let payload = if draft.is_json {
+            json_schema(&draft.fields, draft.example.as_ref())
+        } else {
+            json!({"type": "string"})
+        };
+        let mut message = json!({ "name": id, "payload": payload });
+        if let Some(example) = draft.example {
+            message["examples"] = json!([{ "payload": example }]);
+        }
+
+        let mut channel = json!({ "address": address });
+        if draft.topics > 1 {
+            channel["description"] = json!(format!("Seen on {} topics", draft.topics));
+        }
+        if !draft.parameters.is_empty() {
+            let parameters: serde_json::Map<String, Value> = draft
+                .parameters
+                .iter()
+                .map(|name| (name.clone(), json!({ "description": "TODO" })))
+                .collect();
+            channel["parameters"] = Value::Object(parameters);
+        }
+        channel["messages"] = json!({ id.clone(): message });
+        channels.insert(id.into(), serde_yaml::to_value(channel)?);
+    }
+
+    // Built as a YAML mapping so the sections keep their conventional order
+    let mut document = serde_yaml::Mapping::new();
+    document.insert("asyncapi".into(), "3.0.0".into());
+    document.insert(
+        "info".into(),
+        serde_yaml::to_value(json!({
+            "title": title,
+            "version": "0.1.0",
+            "description": format!(
+                "Skeleton generated by mqtop from {} observed topics on {}. \
+                 Review channel names, parameters and schemas before publishing.",
+                topics.len(),
+                chrono::Local::now().format("%Y-%m-%d")
+            ),
+        }))?,
+    );
+    document.insert("channels".into(), serde_yaml::Value::Mapping(channels));
+    Ok(serde_yaml::to_string(&document)?)
+}
+
+/// Topic with ID-like levels replaced by parameters, and the parameter names
+fn templated_address(topic: &str, separator: char) -> (String, Vec<String>) {
+    let mut parameters: Vec<String> = Vec::new();
+    let mut previous = "";
+    let levels: Vec<String> = topic
+        .split(separator)
+        .map(|level| {
+            let templated = if looks_like_id(level) {
+                let name = parameter_name(previous, &parameters);
+                parameters.push(name.clone());
+                format!("{{{}}}", name)
+            } else {
+                level.to_string()
+            };
+            previous = level;
+            templated
+        })
+        .collect();
+    (levels.join(&separator.to_string()), parameters)
+}
+
+fn channel_id(address: &str) -> String {
+    let id: String = address
+        .chars()
+        .filter(|c| !matches!(c, '{' | '}'))
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let id = id.trim_matches('_');
+    if id.is_empty() {
+        "root".to_string()
+    } else {
+        id.to_string()
+    }
+}
+
+#[derive(Default)]
+struct SchemaNode {
+    field_type: Option<FieldType>,
+    properties: BTreeMap<String, SchemaNode>,
+    items: Option<Box<SchemaNode>>,
+}
+
+impl SchemaNode {
+    fn to_json(&self) -> Value {
+        let field_type = self.field_type.unwrap_or(if self.items.is_some() {
+            FieldType::Array
+        } else {
+            FieldType::Object
+        });
+        let mut schema = json!({ "type": json_type(field_type) });
+        if field_type == FieldType::Object && !self.properties.is_empty() {
+            let properties: serde_json::Map<String, Value> = self
+                .properties
+                .iter()
+                .map(|(name, node)| (name.clone(), node.to_json()))
+                .collect();
+            schema["properties"] = Value::Object(properties);
+        }
+        if let Some(items) = self
+            .items
+            .as_ref()
+            .filter(|_| field_type == FieldType::Array)
+        {
+            schema["items"] = items.to_json();
+        }
+        schema
+    }
+}
+
+/// Nest the tracker's flattened field paths (`data.power`, `list[0].id`) into
+/// a JSON Schema
+fn json_schema(fields: &HashMap<String, FieldType>, example: Option<&Value>) -> Value {
+    // Scalar JSON payloads have no fields; take the type from the example
+    if fields.is_empty() {
+        if let Some(value) = example.filter(|value| !value.is_object() && !value.is_array()) {
+            return json!({ "type": json_type(value_type(value)) });
+        }
+    }
+    let mut root = SchemaNode::default();
+    for (path, field_type) in fields {
+        let mut node = &mut root;
+        for part in path.split('.') {
+            let name = part.trim_end_matches("[0]");
+            if !name.is_empty() {
+                node = node.properties.entry(name.to_string()).or_default();
+            }
+            for _ in 0..(part.len() - name.len()) / 3 {
+                node = node.items.get_or_insert_with(Default::default);
+            }
+        }
+        node.field_type = Some(*field_type);
+    }
+    root.to_json()
+}
+
+fn value_type(value: &Value) -> FieldType {
+    match value {
+        Value::Null => FieldType::Null,
+        Value::Bool(_) => FieldType::Boolean,
+        Value::Number(_) => FieldType::Number,
+        Value::String(_) => FieldType::String,
+        Value::Array(_) => FieldType::Array,
+        Value::Object(_) => FieldType::Object,
+    }
+}
+
+fn json_type(field_type: FieldType) -> &'static str {
+    match field_type {
+        FieldType::Null => "null",
+        FieldType::Boolean => "boolean",
+        FieldType::Number => "number",
+        FieldType::String => "string",
+        FieldType::Array => "array",
+        FieldType::Object => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC_V2: &str = r#"
+asyncapi: 2.6.0
+info:
+  title: Fleet
+  version: 1.0.0
+channels:
+  devices/{id}/state:
+    description: Current device state
+    subscribe:
+      message:
+        $ref: '#/components/messages/State'
+  devices/gateway/state:
+    description: Gateway state
+components:
+  messages:
+    State:
+      name: state
+      payload:
+        $ref: '#/components/schemas/State'
+      examples:
+        - payload: {on: true}
+  schemas:
+    State:
+      type: object
+      properties:
+        on: {type: boolean}
+"#;
+
+    #[test]
+    fn test_parse_v2_with_refs() {
+        let spec = AsyncApiSpec::parse(SPEC_V2).unwrap();
+        assert_eq!(spec.title, "Fleet");
+        assert_eq!(spec.version, "2.6.0");
+
+        let channel = spec.channel_for("devices/42/state").unwrap();
+        assert_eq!(channel.description.as_deref(), Some("Current device state"));
+        let message = &channel.messages[0];
+        assert_eq!(message.name.as_deref(), Some("state"));
+        assert_eq!(message.payload.as_ref().unwrap()["type"], "object");
+        assert_eq!(message.examples, vec![serde_json::json!({"on": true})]);
+
+        // The literal channel wins over the parameterised one
+        let gateway = spec.channel_for("devices/gateway/state").unwrap();
+        assert_eq!(gateway.address, "devices/gateway/state");
+
+        assert!(spec.channel_for("devices/42/state/extra").is_none());
+        assert!(spec.channel_for("devices//state").is_none());
+    }
+
+    #[test]
+    fn test_parse_v3_json() {
+        let spec = AsyncApiSpec::parse(
+            r#"{
+                "asyncapi": "3.0.0",
+                "info": {"title": "Meters", "version": "1"},
+                "channels": {
+                    "reading": {
+                        "address": "meters.{id}.reading",
+                        "messages": {"reading": {"payload": {"type": "number"}}}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        let channel = spec.channel_for("meters.m7.reading").unwrap();
+        assert_eq!(channel.messages[0].name.as_deref(), Some("reading"));
+        assert!(AsyncApiSpec::parse("openapi: 3.0.0").is_err());
+    }
+
+    #[test]
+    fn test_coverage() {
+        let spec = AsyncApiSpec::parse(SPEC_V2).unwrap();
+        let mut coverage = SpecCoverage::default();
+        coverage.observe(&spec, "devices/42/state");
+        coverage.observe(&spec, "devices/42/debug");
+        coverage.observe(&spec, "devices/42/debug");
+        assert_eq!(coverage.observed(), 2);
+        assert!(coverage.is_uncovered("devices/42/debug"));
+        assert!(!coverage.is_uncovered("devices/42/state"));
+        assert_eq!(coverage.uncovered().len(), 1);
+    }
+
+    #[test]
+    fn test_generate_round_trip() {
+        let state = serde_json::json!({"on": true, "power": {"w": 12.5}, "tags": [{"k": "a"}]});
+        let schema = Schema::from_json(&state);
+        let payload = state.to_string().into_bytes();
+        let topics = [
+            ObservedTopic {
+                topic: "devices/a1b2c3d4/state",
+                schema: Some(&schema),
+                example: Some(&payload),
+            },
+            ObservedTopic {
+                topic: "devices/sensor-17/state",
+                schema: Some(&schema),
+                example: None,
+            },
+            ObservedTopic {
+                topic: "devices/sensor-17/log",
+                schema: None,
+                example: Some(b"booted"),
+            },
+        ];
+        let yaml = generate("Fleet", '/', &topics).unwrap();
+        assert!(yaml.starts_with("asyncapi: 3.0.0\ninfo:"));
+
+        let spec = AsyncApiSpec::parse(&yaml).unwrap();
+        assert_eq!(spec.channels.len(), 2);
+        let state = spec.channel_for("devices/ffff0000/state").unwrap();
+        assert_eq!(state.address, "devices/{deviceId}/state");
+        let payload = state.messages[0].payload.as_ref().unwrap();
+        assert_eq!(
+            payload["properties"]["power"]["properties"]["w"]["type"],
+            "number"
+        );
+        assert_eq!(
+            payload["properties"]["tags"]["items"]["properties"]["k"]["type"],
+            "string"
+        );
+        assert_eq!(state.messages[0].examples[0]["on"], true);
+
+        let log = spec.channel_for("devices/sensor-17/log").unwrap();
+        assert_eq!(log.messages[0].payload.as_ref().unwrap()["type"], "string");
+    }
+
+    #[test]
+    fn test_templated_address() {
+        assert_eq!(
+            templated_address("site/12/meters/00ff00ff/power", '/'),
+            (
+                "site/{siteId}/meters/{meterId}/power".to_string(),
+                vec!["siteId".to_string(), "meterId".to_string()]
+            )
+        );
+        // A single digit is a version, not an ID
+        assert_eq!(templated_address("v2/status", '/').0, "v2/status");
+        assert_eq!(
+            templated_address("fleet.truck42.gps", '.').0,
+            "fleet.{fleetId}.gps"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! "While you were away": leave a persistent MQTT session on the broker,
+//! disconnect, and on return collect the QoS 1+ messages it queued meanwhile,
+//! then have the broker drop the session.
+
+use chrono::{DateTime, Utc};
+
+use crate::config::MqttServerConfig;
+use crate::mqtt::MqttMessage;
+
+/// Where a trip away from the broker stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AwayPhase {
+    /// Connecting with a persistent session, to disconnect once subscribed
+    Leaving,
+    /// Disconnected; the broker queues messages for the session
+    Away,
+    /// Reconnecting to the session
+    Returning,
+    /// Session resumed; queued messages arrive until the subscription is confirmed
+    Replaying,
+    /// Reconnecting with a clean session under the same client ID, so the
+    /// broker drops the kept one instead of queueing for it indefinitely
+    Discarding,
+}
+
+#[derive(Debug)]
+pub struct AwaySession {
+    pub phase: AwayPhase,
+    /// Fixed client ID the broker keeps the session under
+    pub client_id: String,
+    /// When the session was left
+    pub since: DateTime<Utc>,
+    /// Queued messages received so far
+    pub messages: Vec<MqttMessage>,
+}
+
+impl AwaySession {
+    /// Leave with the server's own client ID when it is used exactly,
+    /// otherwise with one made up for this trip
+    pub fn leave(server: &MqttServerConfig, now: DateTime<Utc>) -> Self {
+        let client_id = if server.use_exact_client_id && !server.client_id.trim().is_empty() {
+            server.client_id.clone()
+        } else {
+            let base = match server.client_id.trim() {
+                "" => "mqtop",
+                id => id,
+            };
+            format!("{}-away-{}", base, now.timestamp_millis() % 100_000)
+        };
+        Self {
+            phase: AwayPhase::Leaving,
+            client_id,
+            since: now,
+            messages: Vec::new(),
+        }
+    }
+
+    /// The server's settings with a persistent session under the fixed client
+    /// ID. Brokers only queue QoS 1 and 2, so QoS 0 subscriptions are raised to 1.
+    /// While discarding, the same ID connects with a clean session instead.
+    pub fn server_config(&self, server: &MqttServerConfig) -> MqttServerConfig {
+        let mut server = server.clone();
+        server.client_id = self.client_id.clone();
+        server.use_exact_client_id = true;
+        if self.phase == AwayPhase::Discarding {
+            server.clean_session = true;
+        } else {
+            server.clean_session = false;
+            server.subscribe_qos = server.subscribe_qos.max(1);
+        }
+        server
+    }
+
+    /// The messages collected, oldest first; the session is discarded next
+    pub fn finish(&mut self, now: DateTime<Utc>) -> AwayBatch {
+        self.phase = AwayPhase::Discarding;
+        AwayBatch {
+            from: self.since,
+            to: now,
+            messages: std::mem::take(&mut self.messages),
+        }
+    }
+}
+
+/// Messages the broker held for the session while mqtop was disconnected
+#[derive(Debug)]
+pub struct AwayBatch {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub messages: Vec<MqttMessage>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn server(client_id: &str, exact: bool) -> MqttServerConfig {
+        toml::from_str(&format!(
+            "name = \"lab\"\nhost = \"broker.lab\"\nclient_id = \"{}\"\nuse_exact_client_id = {}\nsubscribe_qos = 0",
+            client_id, exact
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_persistent_server_config() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).single().unwrap();
+
+        let session = AwaySession::leave(&server("", false), now);
+        assert!(session.client_id.starts_with("mqtop-away-"));
+        let config = session.server_config(&server("", false));
+        assert_eq!(config.client_id, session.client_id);
+        assert!(config.use_exact_client_id);
+        assert!(!config.clean_session);
+        assert_eq!(config.subscribe_qos, 1);
+
+        let session = AwaySession::leave(&server("dashboard-7", true), now);
+        assert_eq!(session.client_id, "dashboard-7");
+    }
+
+    #[test]
+    fn test_finish_discards_session() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).single().unwrap();
+        let mut session = AwaySession::leave(&server("", false), now);
+        session.phase = AwayPhase::Replaying;
+        session.messages.push(MqttMessage::new(
+            "alarms/press-1".to_string(),
+            b"E42".to_vec(),
+            1,
+            false,
+        ));
+
+        let batch = session.finish(now);
+        assert_eq!(batch.messages.len(), 1);
+        assert_eq!(session.phase, AwayPhase::Discarding);
+        assert!(session.messages.is_empty());
+
+        // Same client ID, clean session, the server's own QoS
+        let config = session.server_config(&server("", false));
+        assert_eq!(config.client_id, session.client_id);
+        assert!(config.use_exact_client_id);
+        assert!(config.clean_session);
+        assert_eq!(config.subscribe_qos, 0);
+    }
+}
@@ -1,7 +1,19 @@
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use crate::config_crypto::{self, ConfigKey};
+use crate::numbers::NumberFormat;
+use crate::paths::Paths;
+use crate::state::metric_tracker::topic_matches;
+use crate::state::AvailabilityTopic;
+
+pub use mqtop_core::config::{
+    ClientPreset, ClientTuning, MqttConfig, MqttServerConfig, NatsConfig, NatsServerConfig,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub mqtt: MqttConfig,
@@ -9,6 +21,317 @@ pub struct Config {
     pub nats: NatsConfig,
     #[serde(default)]
     pub ui: UiConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    /// Capability groups available in this install
+    #[serde(default)]
+    pub features: FeaturesConfig,
+    /// Where to push tracked metric samples (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric_sink: Option<MetricSinkConfig>,
+    /// Key the file was decrypted with; saves are encrypted with it too
+    #[serde(skip)]
+    pub encryption: Option<ConfigKey>,
+    /// Connect to the active server at startup instead of opening the server manager
+    #[serde(skip)]
+    pub connect_on_start: bool,
+}
+
+/// Live push of tracked metrics to a dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSinkConfig {
+    #[serde(default)]
+    pub kind: MetricSinkKind,
+    /// e.g. http://localhost:3000/api/live/push/mqtop or ws://localhost:8080/metrics
+    pub url: String,
+    /// Bearer token (Grafana service account token)
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricSinkKind {
+    /// Grafana Live HTTP push API, Influx line protocol
+    #[default]
+    Grafana,
+    /// Generic WebSocket endpoint, JSON arrays of samples
+    Websocket,
+}
+
+/// Color set for the UI. The color-blind palettes move health and error
+/// colors off the red/green axis; high contrast brightens everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    HighContrast,
+}
+
+impl Palette {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Palette::Default => "default",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::HighContrast => "high contrast",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Palette::Default => Palette::Deuteranopia,
+            Palette::Deuteranopia => Palette::Protanopia,
+            Palette::Protanopia => Palette::HighContrast,
+            Palette::HighContrast => Palette::Default,
+        }
+    }
+}
+
+/// Where sparkline bars start. `zero` keeps bar heights proportional to the
+/// values; `min` stretches the window's range to show small changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SparklineBaseline {
+    #[default]
+    Zero,
+    Min,
+}
+
+/// Timestamp column of the message list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    /// 12:00:05
+    #[default]
+    Time,
+    /// 12:00:05.123
+    Millis,
+    /// 2024-05-01 12:00:05
+    DateTime,
+    /// No timestamp column
+    Off,
+}
+
+impl TimestampFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimestampFormat::Time => "time",
+            TimestampFormat::Millis => "time with millis",
+            TimestampFormat::DateTime => "date and time",
+            TimestampFormat::Off => "off",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TimestampFormat::Time => TimestampFormat::Millis,
+            TimestampFormat::Millis => TimestampFormat::DateTime,
+            TimestampFormat::DateTime => TimestampFormat::Off,
+            TimestampFormat::Off => TimestampFormat::Time,
+        }
+    }
+
+    /// chrono format string, None when the column is off
+    pub fn pattern(&self) -> Option<&'static str> {
+        match self {
+            TimestampFormat::Time => Some("%H:%M:%S"),
+            TimestampFormat::Millis => Some("%H:%M:%S%.3f"),
+            TimestampFormat::DateTime => Some("%Y-%m-%d %H:%M:%S"),
+            TimestampFormat::Off => None,
+        }
+    }
+}
+
+/// Columns of the message list, `[ui.message_columns]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageColumns {
+    #[serde(default)]
+    pub timestamp: TimestampFormat,
+    #[serde(default = "default_true")]
+    pub qos: bool,
+    #[serde(default = "default_true")]
+    pub retain: bool,
+    /// Payload size
+    #[serde(default)]
+    pub size: bool,
+    /// JSON field shown as its own column, as a dotted path such as `meter.power_w`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl Default for MessageColumns {
+    fn default() -> Self {
+        Self {
+            timestamp: TimestampFormat::default(),
+            qos: true,
+            retain: true,
+            size: false,
+            field: None,
+        }
+    }
+}
+
+/// Whether the message list and topic tree show times of day or ages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeDisplay {
+    /// Time of day in the message list, age in the tree
+    #[default]
+    Auto,
+    Absolute,
+    Relative,
+}
+
+impl TimeDisplay {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeDisplay::Auto => "auto",
+            TimeDisplay::Absolute => "absolute",
+            TimeDisplay::Relative => "relative",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TimeDisplay::Auto => TimeDisplay::Absolute,
+            TimeDisplay::Absolute => TimeDisplay::Relative,
+            TimeDisplay::Relative => TimeDisplay::Auto,
+        }
+    }
+
+    pub fn relative_in_list(&self) -> bool {
+        *self == TimeDisplay::Relative
+    }
+
+    pub fn relative_in_tree(&self) -> bool {
+        *self != TimeDisplay::Absolute
+    }
+}
+
+/// Timezone for absolute timestamps: `utc`, `local` or a fixed offset such as `+02:00`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum DisplayTimeZone {
+    #[default]
+    Utc,
+    Local,
+    Offset(FixedOffset),
+}
+
+impl DisplayTimeZone {
+    /// UTC and local time; a fixed offset goes back to UTC
+    pub fn next(&self) -> Self {
+        match self {
+            DisplayTimeZone::Utc => DisplayTimeZone::Local,
+            DisplayTimeZone::Local | DisplayTimeZone::Offset(_) => DisplayTimeZone::Utc,
+        }
+    }
+
+    pub fn format(&self, timestamp: DateTime<Utc>, pattern: &str) -> String {
+        match self {
+            DisplayTimeZone::Utc => timestamp.format(pattern).to_string(),
+            DisplayTimeZone::Local => timestamp.with_timezone(&Local).format(pattern).to_string(),
+            DisplayTimeZone::Offset(offset) => {
+                timestamp.with_timezone(offset).format(pattern).to_string()
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for DisplayTimeZone {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        match value.to_ascii_lowercase().as_str() {
+            "utc" | "z" => Ok(DisplayTimeZone::Utc),
+            "local" => Ok(DisplayTimeZone::Local),
+            _ => value.parse().map(DisplayTimeZone::Offset).map_err(|_| {
+                format!(
+                    "Unknown timezone '{}', expected utc, local or an offset like +02:00",
+                    value
+                )
+            }),
+        }
+    }
+}
+
+impl From<DisplayTimeZone> for String {
+    fn from(zone: DisplayTimeZone) -> Self {
+        match zone {
+            DisplayTimeZone::Utc => "utc".to_string(),
+            DisplayTimeZone::Local => "local".to_string(),
+            DisplayTimeZone::Offset(offset) => offset.to_string(),
+        }
+    }
+}
+
+/// Who the install is for. Operators get a read-only monitor; developers get everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    Developer,
+    Operator,
+}
+
+/// A capability group that can be switched off in `[features]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Publish,
+    Bookmarks,
+    ServerEditing,
+    Recording,
+}
+
+impl Feature {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Feature::Publish => "Publishing",
+            Feature::Bookmarks => "Bookmarks",
+            Feature::ServerEditing => "Server editing",
+            Feature::Recording => "Recording and export",
+        }
+    }
+}
+
+/// Enabled capability groups. The role sets the defaults and any group set
+/// explicitly overrides it, e.g. an operator install that may still publish.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeaturesConfig {
+    #[serde(default)]
+    pub role: Role,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmarks: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_editing: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recording: Option<bool>,
+}
+
+impl FeaturesConfig {
+    pub fn allows(&self, feature: Feature) -> bool {
+        let explicit = match feature {
+            Feature::Publish => self.publish,
+            Feature::Bookmarks => self.bookmarks,
+            Feature::ServerEditing => self.server_editing,
+            Feature::Recording => self.recording,
+        };
+        explicit.unwrap_or(self.role == Role::Developer)
+    }
+}
+
+/// Part of a backup that can be restored on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConfigSection {
+    /// The whole `[mqtt]` section: all MQTT servers and the active one
+    Mqtt,
+    /// The whole `[nats]` section
+    Nats,
 }
 
 pub const CONFIG_BACKUP_LIMIT: usize = 5;
@@ -91,70 +414,64 @@ impl TopicCategory {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MqttConfig {
-    pub active_server: String,
-    #[serde(default)]
-    pub servers: Vec<MqttServerConfig>,
+/// The header line, `[ui.header]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderConfig {
+    /// Title badge, with `{host}`, `{port}`, `{server}` and `{profile}` filled
+    /// in. Unset shows `mqtop @ host:port`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Name of the active server
+    #[serde(default = "default_true")]
+    pub show_server: bool,
+    #[serde(default = "default_true")]
+    pub show_profile: bool,
+    /// Badge of read-only operator installs
+    #[serde(default = "default_true")]
+    pub show_read_only: bool,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct NatsConfig {
-    pub active_server: String,
-    #[serde(default)]
-    pub servers: Vec<NatsServerConfig>,
+impl Default for HeaderConfig {
+    fn default() -> Self {
+        Self {
+            title: None,
+            show_server: true,
+            show_profile: true,
+            show_read_only: true,
+        }
+    }
+}
+
+/// Character set of text payloads that are not UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[serde(rename = "latin1", alias = "iso-8859-1")]
+    Latin1,
+    #[serde(rename = "utf16le", alias = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf16be", alias = "utf-16be")]
+    Utf16Be,
+    #[serde(rename = "shift_jis", alias = "sjis")]
+    ShiftJis,
+}
+
+impl TextEncoding {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextEncoding::Latin1 => "Latin-1",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::ShiftJis => "Shift-JIS",
+        }
+    }
 }
 
+/// Text encoding of the payloads on matching topics, for devices that don't send UTF-8
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MqttServerConfig {
-    pub name: String,
-    pub host: String,
-    #[serde(default = "default_port")]
-    pub port: u16,
-    #[serde(default)]
-    pub use_tls: bool,
-    /// Path to custom CA certificate (PEM format)
-    pub ca_cert: Option<String>,
-    /// Path to client certificate for mTLS (PEM format)
-    pub client_cert: Option<String>,
-    /// Path to client private key for mTLS (PEM format)
-    pub client_key: Option<String>,
-    /// Skip TLS certificate verification (insecure, for testing only)
-    #[serde(default)]
-    pub tls_insecure: bool,
-    pub client_id: String,
-    /// If true, use client_id exactly as specified (no auto-generated suffix)
-    /// If false and client_id is empty, generates "mqtop-{timestamp}"
-    /// If false and client_id is set, appends "-{timestamp}" for reconnect safety
-    #[serde(default)]
-    pub use_exact_client_id: bool,
-    /// Username for MQTT auth (defaults to client_id if not set)
-    pub username: Option<String>,
-    /// Token for authentication (goes in password field)
-    pub token: Option<String>,
-    #[serde(default = "default_subscribe_topic")]
-    pub subscribe_topic: String,
-    /// QoS level for subscriptions (0, 1, or 2)
-    #[serde(default = "default_qos")]
-    pub subscribe_qos: u8,
-    #[serde(default = "default_keep_alive")]
-    pub keep_alive_secs: u64,
-    /// MQTT protocol version (3 = 3.1.1, 5 = 5.0)
-    #[serde(default = "default_mqtt_version")]
-    pub mqtt_version: u8,
-    /// Clean session - if true, broker discards previous session state
-    #[serde(default = "default_clean_session")]
-    pub clean_session: bool,
-    /// Last Will and Testament topic (optional)
-    pub lwt_topic: Option<String>,
-    /// Last Will and Testament payload (optional)
-    pub lwt_payload: Option<String>,
-    /// Last Will QoS (0, 1, or 2)
-    #[serde(default)]
-    pub lwt_qos: u8,
-    /// Last Will retain flag
-    #[serde(default)]
-    pub lwt_retain: bool,
+pub struct PayloadEncoding {
+    /// MQTT topic pattern (`+` and `#` wildcards)
+    pub pattern: String,
+    pub encoding: TextEncoding,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,14 +480,135 @@ pub struct UiConfig {
     pub message_buffer_size: usize,
     #[serde(default = "default_stats_window")]
     pub stats_window_secs: u64,
-    #[serde(default = "default_tick_rate")]
-    pub tick_rate_ms: u64,
+    /// Longest wait for a key before broker events are handled again
+    #[serde(default = "default_input_poll")]
+    pub input_poll_ms: u64,
+    /// Redraw at most this often; keystrokes still redraw right away
+    #[serde(default = "default_render_interval", alias = "tick_rate_ms")]
+    pub render_interval_ms: u64,
+    /// How often background maintenance runs: dead topic pruning, buffer
+    /// housekeeping and device status refresh
+    #[serde(default = "default_maintenance_interval")]
+    pub maintenance_interval_ms: u64,
+    /// History kept per tracked metric
+    #[serde(default = "default_metric_window")]
+    pub metric_window_secs: u64,
+    /// Points per tracked metric over that window. Faster samples are merged
+    /// into min/max/avg buckets (0 = keep every sample).
+    #[serde(default = "default_metric_points")]
+    pub metric_points: usize,
+    /// Messages per topic written to session snapshots (0 = whole buffer)
+    #[serde(default)]
+    pub session_messages_per_topic: usize,
+    /// Warn when a catch-all subscription starts above this many messages/sec (0 = never)
+    #[serde(default = "default_subscribe_warn_rate")]
+    pub subscribe_warn_rate: u64,
+    /// Above this many messages/sec, render less: plain payloads, shorter
+    /// message lists, fewer frames (0 = never)
+    #[serde(default = "default_high_load_rate")]
+    pub high_load_rate: u64,
+    /// Warn when a branch gains more than this many child topics within
+    /// `cardinality_window_secs` (0 = never)
+    #[serde(default = "default_cardinality_limit")]
+    pub cardinality_limit: usize,
+    #[serde(default = "default_cardinality_window")]
+    pub cardinality_window_secs: u64,
+    /// Minutes without messages before a topic is shown as dead (0 = never)
+    #[serde(default)]
+    pub topic_ttl_mins: u64,
+    /// Remove dead topics from the tree and free their buffers
+    #[serde(default)]
+    pub prune_dead_topics: bool,
+    /// List broker `$` topics such as `$SYS` in the tree (toggle with `$`)
+    #[serde(default)]
+    pub show_system_topics: bool,
+    /// Fold topics into learned templates such as `sites/{siteId}/power`
+    /// (toggle with `z`)
+    #[serde(default)]
+    pub group_topic_templates: bool,
+    /// Start with the Messages panel following the busiest topic, or the
+    /// latest watchdog alert, for unattended displays (toggle with `a`)
+    #[serde(default)]
+    pub follow_busiest: bool,
+    /// Wall display mode: rotating full-screen pages without selection
+    /// highlights, connecting on start and retrying forever (or --kiosk)
+    #[serde(default)]
+    pub kiosk: bool,
+    /// Seconds each kiosk page stays on screen
+    #[serde(default = "default_kiosk_page_secs")]
+    pub kiosk_page_secs: u64,
+    /// Color topic names by freshness (green < 1 min, yellow < 10 min, grey older)
+    /// instead of the topic color rules
+    #[serde(default)]
+    pub freshness_colors: bool,
+    /// Show the dry-run preview (payload lint and diff) before every publish
+    #[serde(default)]
+    pub preview_publish: bool,
+    /// Keep publish, filter and search history between runs
+    #[serde(default)]
+    pub persist_history: bool,
+    /// Keep per-topic message and byte totals between runs, per server
+    #[serde(default)]
+    pub persist_topic_stats: bool,
+    /// Forget saved topic totals not seen for this many days
+    #[serde(default = "default_topic_stats_days")]
+    pub topic_stats_days: u64,
+    /// Draw borders, sparklines and status glyphs with ASCII only, for fonts
+    /// that show box and symbol characters as tofu
+    #[serde(default)]
+    pub ascii: bool,
+    /// Screen reader friendly output: status words next to colors, no
+    /// borders or activity dots, and a readout line for the selection
+    #[serde(default)]
+    pub screen_reader: bool,
+    /// Color palette: default, deuteranopia, protanopia or high_contrast
+    #[serde(default)]
+    pub palette: Palette,
+    /// Baseline for sparklines in the Stats panel: zero or min
+    #[serde(default)]
+    pub sparkline_baseline: SparklineBaseline,
+    /// Schema baseline to check live traffic against (exported with `T` then `s`)
+    #[serde(default)]
+    pub schema_baseline: Option<PathBuf>,
+    /// AsyncAPI document describing the broker's topics (shown with `A`)
+    #[serde(default)]
+    pub asyncapi: Option<PathBuf>,
+    /// CSV or JSON mapping of device IDs to friendly names
+    #[serde(default)]
+    pub friendly_names: Option<PathBuf>,
+    /// http:// endpoint serving a JSON mapping of IDs to names, fetched at startup
+    #[serde(default)]
+    pub friendly_names_url: Option<String>,
+    /// Show friendly names in place of IDs instead of next to them
+    #[serde(default)]
+    pub replace_ids: bool,
+    /// What the message list shows besides the payload (change with `I`)
+    #[serde(default)]
+    pub message_columns: MessageColumns,
+    /// Message and topic times as the time of day or as "2.3s ago"
+    #[serde(default)]
+    pub times: TimeDisplay,
+    /// Timezone of absolute times: utc, local or an offset such as +02:00
+    #[serde(default)]
+    pub timezone: DisplayTimeZone,
+    /// How counts, sizes, rates and metric values are written
+    #[serde(default)]
+    pub numbers: NumberFormat,
+    /// Title and badges of the header line
+    #[serde(default)]
+    pub header: HeaderConfig,
     /// Custom topic color rules for highlighting in tree view
     #[serde(default)]
     pub topic_colors: Vec<TopicColorRule>,
     /// Topic categories for counting in stats panel
     #[serde(default)]
     pub topic_categories: Vec<TopicCategory>,
+    /// Topics where devices announce online/offline, e.g. their last will
+    #[serde(default)]
+    pub availability_topics: Vec<AvailabilityTopic>,
+    /// Text encodings of topics whose payloads are not UTF-8; others are detected
+    #[serde(default)]
+    pub payload_encodings: Vec<PayloadEncoding>,
 }
 
 impl Default for UiConfig {
@@ -178,72 +616,160 @@ impl Default for UiConfig {
         Self {
             message_buffer_size: default_message_buffer_size(),
             stats_window_secs: default_stats_window(),
-            tick_rate_ms: default_tick_rate(),
+            input_poll_ms: default_input_poll(),
+            render_interval_ms: default_render_interval(),
+            maintenance_interval_ms: default_maintenance_interval(),
+            metric_window_secs: default_metric_window(),
+            metric_points: default_metric_points(),
+            session_messages_per_topic: 0,
+            subscribe_warn_rate: default_subscribe_warn_rate(),
+            high_load_rate: default_high_load_rate(),
+            cardinality_limit: default_cardinality_limit(),
+            cardinality_window_secs: default_cardinality_window(),
+            topic_ttl_mins: 0,
+            prune_dead_topics: false,
+            show_system_topics: false,
+            group_topic_templates: false,
+            follow_busiest: false,
+            kiosk: false,
+            kiosk_page_secs: default_kiosk_page_secs(),
+            freshness_colors: false,
+            preview_publish: false,
+            persist_history: false,
+            persist_topic_stats: false,
+            topic_stats_days: default_topic_stats_days(),
+            ascii: false,
+            screen_reader: false,
+            palette: Palette::default(),
+            sparkline_baseline: SparklineBaseline::default(),
+            schema_baseline: None,
+            asyncapi: None,
+            friendly_names: None,
+            friendly_names_url: None,
+            replace_ids: false,
+            message_columns: MessageColumns::default(),
+            times: TimeDisplay::default(),
+            timezone: DisplayTimeZone::default(),
+            numbers: NumberFormat::default(),
+            header: HeaderConfig::default(),
             topic_colors: Vec::new(),
             topic_categories: Vec::new(),
+            availability_topics: Vec::new(),
+            payload_encodings: Vec::new(),
         }
     }
 }
 
-fn default_port() -> u16 {
-    1883
+impl UiConfig {
+    /// Encoding configured for a topic's payloads, first matching rule wins
+    pub fn encoding_for(&self, topic: &str) -> Option<TextEncoding> {
+        self.payload_encodings
+            .iter()
+            .find(|rule| topic_matches(&rule.pattern, topic))
+            .map(|rule| rule.encoding)
+    }
 }
 
-fn default_nats_port() -> u16 {
-    4222
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Default level for all modules (error, warn, info, debug, trace)
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// Per-module level overrides, e.g. `rumqttc = "debug"`
+    #[serde(default)]
+    pub modules: BTreeMap<String, String>,
+    /// Log file path (logs are only kept in memory when unset)
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    /// Rotate the log file when it grows beyond this size
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Number of rotated files to keep
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
 }
 
-fn default_subscribe_topic() -> String {
-    "#".to_string()
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            modules: BTreeMap::new(),
+            file: None,
+            max_size_mb: default_log_max_size_mb(),
+            max_files: default_log_max_files(),
+        }
+    }
 }
 
-fn default_nats_subscribe_subject() -> String {
-    ">".to_string()
+fn default_log_level() -> String {
+    "info".to_string()
 }
 
-fn default_keep_alive() -> u64 {
-    30
+fn default_log_max_size_mb() -> u64 {
+    10
 }
 
-fn default_qos() -> u8 {
-    1
+fn default_log_max_files() -> usize {
+    3
 }
 
-fn default_mqtt_version() -> u8 {
-    3 // 3 = MQTT 3.1.1, 5 = MQTT 5.0
+fn default_message_buffer_size() -> usize {
+    100
 }
 
-fn default_clean_session() -> bool {
-    true
+fn default_stats_window() -> u64 {
+    10
 }
 
-fn default_message_buffer_size() -> usize {
+fn default_input_poll() -> u64 {
+    20
+}
+
+fn default_render_interval() -> u64 {
     100
 }
 
-fn default_stats_window() -> u64 {
-    10
+fn default_maintenance_interval() -> u64 {
+    1000
+}
+
+fn default_metric_window() -> u64 {
+    3600
 }
 
-fn default_tick_rate() -> u64 {
+fn default_metric_points() -> usize {
+    360
+}
+
+fn default_subscribe_warn_rate() -> u64 {
+    1000
+}
+
+fn default_high_load_rate() -> u64 {
+    5000
+}
+
+fn default_cardinality_limit() -> usize {
     100
 }
 
-impl Config {
-    /// Get the default config directory path (~/.config/mqtop/)
-    /// Uses ~/.config explicitly for cross-platform consistency
-    pub fn default_dir() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".config")
-            .join("mqtop")
-    }
+fn default_cardinality_window() -> u64 {
+    60
+}
 
-    /// Get the default config file path (~/.config/mqtop/config.toml)
-    pub fn default_path() -> PathBuf {
-        Self::default_dir().join("config.toml")
-    }
+fn default_topic_stats_days() -> u64 {
+    30
+}
+
+fn default_kiosk_page_secs() -> u64 {
+    15
+}
 
+fn default_true() -> bool {
+    true
+}
+
+impl Config {
     /// Get the config backup directory path (<config-dir>/backups/)
     pub fn backup_dir_for(path: &Path) -> PathBuf {
         path.parent()
@@ -253,34 +779,61 @@ impl Config {
 
     /// Find config file using fallback chain:
     /// 1. If explicit path provided, use it
-    /// 2. If ./config.toml exists in current directory, use it
-    /// 3. Otherwise use ~/.config/mqtop/config.toml
-    pub fn find_config_path(explicit_path: Option<&Path>) -> PathBuf {
+    /// 2. If ./config.toml exists in current directory, use it (not with --profile)
+    /// 3. Otherwise use config.toml in the (profile's) config dir,
+    ///    ~/.config/mqtop/config.toml by default
+    pub fn find_config_path(explicit_path: Option<&Path>, paths: &Paths) -> PathBuf {
         if let Some(path) = explicit_path {
             return path.to_path_buf();
         }
 
         // 2. Local config.toml in current directory
         let local_config = PathBuf::from("config.toml");
-        if local_config.exists() {
+        if paths.profile.is_none() && local_config.exists() {
             return local_config;
         }
 
-        // 3. Default to ~/.config/mqtop/config.toml
-        Self::default_path()
+        // 3. Default to the config dir
+        paths.config_file()
     }
 
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let contents = std::fs::read_to_string(path.as_ref())
             .with_context(|| format!("Failed to read config file: {:?}", path.as_ref()))?;
+        if config_crypto::is_encrypted(contents.as_bytes()) {
+            bail!("Config file is encrypted");
+        }
+        Self::parse(&contents)
+    }
 
+    pub fn parse(contents: &str) -> Result<Self> {
         let config: Config =
-            toml::from_str(&contents).with_context(|| "Failed to parse config file")?;
+            toml::from_str(contents).with_context(|| "Failed to parse config file")?;
 
         config.validate()?;
         Ok(config)
     }
 
+    /// Whether the config file is age encrypted
+    pub fn is_encrypted_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+        let contents = std::fs::read(path.as_ref())
+            .with_context(|| format!("Failed to read config file: {:?}", path.as_ref()))?;
+        Ok(config_crypto::is_encrypted(&contents))
+    }
+
+    /// Decrypt and load an encrypted config, keeping the key for later saves
+    pub fn load_encrypted<P: AsRef<Path>>(path: P, key: ConfigKey) -> Result<Self> {
+        let contents = std::fs::read(path.as_ref())
+            .with_context(|| format!("Failed to read config file: {:?}", path.as_ref()))?;
+        let plaintext = key.decrypt(&contents)?;
+        let contents =
+            String::from_utf8(plaintext).context("Decrypted config is not valid UTF-8")?;
+
+        let mut config = Self::parse(&contents)?;
+        config.encryption = Some(key);
+        Ok(config)
+    }
+
     pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         self.validate()?;
 
@@ -292,6 +845,10 @@ impl Config {
 
         let contents =
             toml::to_string_pretty(self).with_context(|| "Failed to serialize config")?;
+        let contents = match &self.encryption {
+            Some(key) => key.encrypt(contents.as_bytes())?,
+            None => contents.into_bytes(),
+        };
         std::fs::write(path, contents)
             .with_context(|| format!("Failed to write config file: {:?}", path))?;
         Ok(())
@@ -333,16 +890,58 @@ impl Config {
         Ok(entries.into_iter().map(|entry| entry.path()).collect())
     }
 
-    pub fn rollback_backup<P: AsRef<Path>>(path: P, index: usize, retention: usize) -> Result<()> {
+    /// Path of backup `index` (1 = newest)
+    pub fn backup_path<P: AsRef<Path>>(path: P, index: usize) -> Result<PathBuf> {
         if index == 0 {
             bail!("Backup index must start at 1");
         }
+        Self::list_backups(path)?
+            .into_iter()
+            .nth(index - 1)
+            .with_context(|| "Backup index out of range")
+    }
 
+    /// TOML text of a config or backup file, decrypted with `key` when encrypted
+    pub fn read_text<P: AsRef<Path>>(path: P, key: Option<&ConfigKey>) -> Result<String> {
         let path = path.as_ref();
-        let backups = Self::list_backups(path)?;
-        let backup = backups
-            .get(index - 1)
-            .with_context(|| "Backup index out of range")?;
+        let contents = std::fs::read(path)
+            .with_context(|| format!("Failed to read config file: {:?}", path))?;
+        if !config_crypto::is_encrypted(&contents) {
+            return String::from_utf8(contents).context("Config is not valid UTF-8");
+        }
+        let key = key.with_context(|| format!("{:?} is encrypted", path))?;
+        String::from_utf8(key.decrypt(&contents)?).context("Decrypted config is not valid UTF-8")
+    }
+
+    /// Replace one section of this config with the same section from `backup`
+    pub fn restore_section(&mut self, backup: &Config, section: ConfigSection) {
+        match section {
+            ConfigSection::Mqtt => self.mqtt = backup.mqtt.clone(),
+            ConfigSection::Nats => self.nats = backup.nats.clone(),
+        }
+    }
+
+    /// Replace (or re-add) the server called `name` with its settings in `backup`
+    pub fn restore_server(&mut self, backup: &Config, name: &str) -> Result<()> {
+        if let Some(server) = backup.mqtt.servers.iter().find(|s| s.name == name) {
+            match self.mqtt.servers.iter_mut().find(|s| s.name == name) {
+                Some(current) => *current = server.clone(),
+                None => self.mqtt.servers.push(server.clone()),
+            }
+        } else if let Some(server) = backup.nats.servers.iter().find(|s| s.name == name) {
+            match self.nats.servers.iter_mut().find(|s| s.name == name) {
+                Some(current) => *current = server.clone(),
+                None => self.nats.servers.push(server.clone()),
+            }
+        } else {
+            bail!("No server named '{}' in the backup", name);
+        }
+        Ok(())
+    }
+
+    pub fn rollback_backup<P: AsRef<Path>>(path: P, index: usize, retention: usize) -> Result<()> {
+        let path = path.as_ref();
+        let backup = Self::backup_path(path, index)?;
 
         if path.exists() {
             Self::create_backup(path)?;
@@ -353,7 +952,7 @@ impl Config {
                 .with_context(|| format!("Failed to create config directory: {:?}", parent))?;
         }
 
-        std::fs::copy(backup, path)
+        std::fs::copy(&backup, path)
             .with_context(|| format!("Failed to restore backup: {:?}", backup))?;
 
         Self::prune_backups(path, retention)?;
@@ -396,6 +995,21 @@ impl Config {
                     server.name
                 );
             }
+            crate::mqtt::subscription::validate_subscription(&server.subscribe_topic)
+                .with_context(|| format!("Invalid subscribe_topic (server: {})", server.name))?;
+            let tuning = server.tuning();
+            if tuning.inflight == 0 || tuning.request_channel_capacity == 0 {
+                bail!(
+                    "MQTT inflight and request_channel_capacity must be > 0 (server: {})",
+                    server.name
+                );
+            }
+            if tuning.max_packet_size < 1024 {
+                bail!(
+                    "MQTT max_packet_size must be at least 1024 bytes (server: {})",
+                    server.name
+                );
+            }
         }
         Ok(())
     }
@@ -463,76 +1077,3 @@ impl Config {
     }
 }
 
-impl MqttConfig {
-    pub fn active_index(&self) -> Option<usize> {
-        self.servers
-            .iter()
-            .position(|server| server.name == self.active_server)
-    }
-
-    pub fn active_server(&self) -> Option<&MqttServerConfig> {
-        self.active_index().and_then(|idx| self.servers.get(idx))
-    }
-
-    pub fn active_server_mut(&mut self) -> Option<&mut MqttServerConfig> {
-        let idx = self.active_index()?;
-        self.servers.get_mut(idx)
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NatsServerConfig {
-    pub name: String,
-    pub host: String,
-    #[serde(default = "default_nats_port")]
-    pub port: u16,
-    #[serde(default)]
-    pub use_tls: bool,
-    /// Path to custom CA certificate (PEM format)
-    pub ca_cert: Option<String>,
-    /// Skip TLS certificate verification (insecure, for testing only)
-    #[serde(default)]
-    pub tls_insecure: bool,
-    /// Username for NATS auth (optional)
-    pub username: Option<String>,
-    /// Token for NATS auth (optional; also used as password for user/pass setups)
-    pub token: Option<String>,
-    /// Optional NATS creds file (JWT/NKey)
-    pub creds_file: Option<String>,
-    #[serde(default = "default_nats_subscribe_subject")]
-    pub subscribe_subject: String,
-}
-
-impl NatsConfig {
-    pub fn active_index(&self) -> Option<usize> {
-        self.servers
-            .iter()
-            .position(|server| server.name == self.active_server)
-    }
-
-    pub fn active_server(&self) -> Option<&NatsServerConfig> {
-        self.active_index().and_then(|idx| self.servers.get(idx))
-    }
-}
-
-impl MqttServerConfig {
-    /// Get the username, defaulting to client_id if not set
-    pub fn get_username(&self) -> &str {
-        self.username.as_deref().unwrap_or(&self.client_id)
-    }
-
-    /// Get the token, returning empty string if none set
-    pub fn get_token(&self) -> &str {
-        self.token.as_deref().unwrap_or("")
-    }
-}
-
-impl NatsServerConfig {
-    pub fn get_username(&self) -> &str {
-        self.username.as_deref().unwrap_or("")
-    }
-
-    pub fn get_token(&self) -> &str {
-        self.token.as_deref().unwrap_or("")
-    }
-}
@@ -0,0 +1,178 @@
+//! age encryption for config files holding server credentials, for machines
+//! without an OS keyring. A config is either locked with a passphrase or to
+//! the public key of an age identity file (as written by `age-keygen`).
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::secrecy::SecretString;
+use anyhow::{bail, Context, Result};
+
+const ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const BINARY_HEADER: &str = "age-encryption.org/v1";
+
+/// Key a config was unlocked with, kept so saves are encrypted the same way
+#[derive(Clone)]
+pub enum ConfigKey {
+    Passphrase(SecretString),
+    Identity(Box<age::x25519::Identity>),
+}
+
+impl std::fmt::Debug for ConfigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigKey::Passphrase(_) => f.write_str("ConfigKey::Passphrase(..)"),
+            ConfigKey::Identity(_) => f.write_str("ConfigKey::Identity(..)"),
+        }
+    }
+}
+
+impl ConfigKey {
+    /// Read the first `AGE-SECRET-KEY-` line of an age identity file
+    pub fn from_key_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read key file: {:?}", path))?;
+        let line = contents
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("AGE-SECRET-KEY-"))
+            .with_context(|| format!("No AGE-SECRET-KEY found in {:?}", path))?;
+        let identity = line
+            .parse::<age::x25519::Identity>()
+            .map_err(|err| anyhow::anyhow!("Invalid age identity in {:?}: {}", path, err))?;
+        Ok(ConfigKey::Identity(Box::new(identity)))
+    }
+
+    /// Ask for the passphrase on the terminal without echoing it
+    pub fn prompt(path: &Path) -> Result<Self> {
+        let passphrase = rpassword::prompt_password(format!("Passphrase for {}: ", path.display()))
+            .context("Failed to read passphrase")?;
+        Ok(ConfigKey::Passphrase(SecretString::from(passphrase)))
+    }
+
+    /// Ask for a new passphrase twice, for encrypting a config
+    pub fn prompt_new() -> Result<Self> {
+        let passphrase = rpassword::prompt_password("New config passphrase: ")
+            .context("Failed to read passphrase")?;
+        if passphrase.is_empty() {
+            bail!("Passphrase cannot be empty");
+        }
+        let confirm = rpassword::prompt_password("Repeat passphrase: ")
+            .context("Failed to read passphrase")?;
+        if passphrase != confirm {
+            bail!("Passphrases don't match");
+        }
+        Ok(ConfigKey::Passphrase(SecretString::from(passphrase)))
+    }
+
+    /// Encrypt to ASCII-armored age, so the file stays readable as text
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let encryptor = match self {
+            ConfigKey::Passphrase(passphrase) => {
+                age::Encryptor::with_user_passphrase(passphrase.clone())
+            }
+            ConfigKey::Identity(identity) => {
+                let recipient = identity.to_public();
+                age::Encryptor::with_recipients(std::iter::once(&recipient as _))
+                    .context("Failed to set up encryption")?
+            }
+        };
+
+        let mut output = Vec::new();
+        let armor = ArmoredWriter::wrap_output(&mut output, Format::AsciiArmor)?;
+        let mut writer = encryptor.wrap_output(armor)?;
+        writer.write_all(plaintext)?;
+        writer.finish().and_then(|armor| armor.finish())?;
+        Ok(output)
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let decryptor = age::Decryptor::new(ArmoredReader::new(ciphertext))
+            .context("Not an age encrypted file")?;
+        let mut reader = match self {
+            ConfigKey::Passphrase(passphrase) => {
+                let identity = age::scrypt::Identity::new(passphrase.clone());
+                decryptor.decrypt(std::iter::once(&identity as _))
+            }
+            ConfigKey::Identity(identity) => {
+                decryptor.decrypt(std::iter::once(identity.as_ref() as _))
+            }
+        }
+        .map_err(|err| match err {
+            age::DecryptError::NoMatchingKeys => {
+                anyhow::anyhow!("Wrong key file: the config is encrypted to another key")
+            }
+            age::DecryptError::DecryptionFailed => anyhow::anyhow!("Wrong passphrase"),
+            err => anyhow::anyhow!("Failed to decrypt config: {}", err),
+        })?;
+
+        let mut plaintext = Vec::new();
+        reader
+            .read_to_end(&mut plaintext)
+            .context("Failed to decrypt config")?;
+        Ok(plaintext)
+    }
+}
+
+/// Whether file contents start with an age header (armored or binary)
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    let start = contents
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(contents.len());
+    let contents = &contents[start..];
+    contents.starts_with(ARMOR_HEADER.as_bytes()) || contents.starts_with(BINARY_HEADER.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_round_trip() {
+        let key = ConfigKey::Identity(Box::new(age::x25519::Identity::generate()));
+        let ciphertext = key.encrypt(b"[mqtt]\nactive_server = \"lab\"\n").unwrap();
+        assert!(is_encrypted(&ciphertext));
+        assert!(!is_encrypted(b"[mqtt]\nactive_server = \"lab\"\n"));
+        assert_eq!(
+            key.decrypt(&ciphertext).unwrap(),
+            b"[mqtt]\nactive_server = \"lab\"\n"
+        );
+
+        let other = ConfigKey::Identity(Box::new(age::x25519::Identity::generate()));
+        let err = other.decrypt(&ciphertext).unwrap_err();
+        assert!(err.to_string().contains("Wrong key file"));
+    }
+
+    #[test]
+    fn test_key_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mqtop.key");
+        let identity = age::x25519::Identity::generate();
+        let secret = {
+            use age::secrecy::ExposeSecret;
+            identity.to_string().expose_secret().to_string()
+        };
+        std::fs::write(
+            &path,
+            format!(
+                "# created: 2024-01-01\n# public key: {}\n{}\n",
+                identity.to_public(),
+                secret
+            ),
+        )
+        .unwrap();
+
+        let key = ConfigKey::from_key_file(&path).unwrap();
+        let ciphertext = key.encrypt(b"token = \"secret\"").unwrap();
+        let identity_key = ConfigKey::Identity(Box::new(identity));
+        assert_eq!(
+            identity_key.decrypt(&ciphertext).unwrap(),
+            b"token = \"secret\""
+        );
+
+        std::fs::write(&path, "# no key here\n").unwrap();
+        assert!(ConfigKey::from_key_file(&path).is_err());
+    }
+}
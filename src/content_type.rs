@@ -0,0 +1,722 @@
+//! Content sniffing for `PayloadMode::Auto`: recognizes gzip, images,
+//! non-UTF-8 text, MessagePack, CBOR and protobuf by their leading bytes and
+//! structure, and decodes them for display instead of falling back to hex.
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use serde_json::{Map, Number, Value};
+
+use crate::config::TextEncoding;
+
+/// Largest decompressed gzip payload shown
+const MAX_INFLATED_BYTES: u64 = 1024 * 1024;
+/// Nesting deeper than this is treated as not a structured payload
+const MAX_DEPTH: usize = 32;
+
+/// What a payload turned out to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    Json,
+    Text,
+    Gzip,
+    Image(&'static str),
+    /// Text in a legacy or UTF-16 encoding
+    Encoded(TextEncoding),
+    MessagePack,
+    Cbor,
+    Protobuf,
+    /// Protobuf message behind a varint length prefix
+    ProtobufDelimited,
+    Binary,
+}
+
+impl ContentType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContentType::Json => "JSON",
+            ContentType::Text => "text",
+            ContentType::Gzip => "gzip",
+            ContentType::Image(kind) => kind,
+            ContentType::Encoded(encoding) => encoding.label(),
+            ContentType::MessagePack => "MessagePack",
+            ContentType::Cbor => "CBOR",
+            ContentType::Protobuf => "protobuf",
+            ContentType::ProtobufDelimited => "protobuf, length-delimited",
+            ContentType::Binary => "binary",
+        }
+    }
+}
+
+/// A payload decoded for display
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    /// Detected type, e.g. `gzip → JSON`
+    pub label: String,
+    pub text: String,
+    /// `text` is pretty-printed JSON, fit for syntax highlighting
+    pub is_json: bool,
+}
+
+/// Decode a payload as text in the topic's configured encoding, otherwise by
+/// what it looks like, hex when nothing matches
+pub fn decode(payload: &[u8], encoding: Option<TextEncoding>) -> Decoded {
+    if let Some(encoding) = encoding {
+        if let Some(text) = decode_text(payload, encoding) {
+            return text_or_json(encoding.label(), text);
+        }
+    }
+    decode_at(payload, 0)
+}
+
+/// Text decoded from another encoding, pretty-printed when it holds JSON
+fn text_or_json(label: &str, text: String) -> Decoded {
+    match serde_json::from_str::<Value>(&text) {
+        Ok(value) => Decoded {
+            label: format!("{} → JSON", label),
+            text: serde_json::to_string_pretty(&value).unwrap_or_default(),
+            is_json: true,
+        },
+        Err(_) => Decoded {
+            label: label.to_string(),
+            text,
+            is_json: false,
+        },
+    }
+}
+
+fn decode_at(payload: &[u8], depth: usize) -> Decoded {
+    let kind = sniff(payload);
+    let json = |value: &Value| Decoded {
+        label: kind.label().to_string(),
+        text: serde_json::to_string_pretty(value).unwrap_or_default(),
+        is_json: true,
+    };
+    match kind {
+        ContentType::Gzip if depth == 0 => match inflate(payload) {
+            Some(inner) => {
+                let inner = decode_at(&inner, depth + 1);
+                Decoded {
+                    label: format!("gzip → {}", inner.label),
+                    ..inner
+                }
+            }
+            None => hex(kind, payload),
+        },
+        ContentType::Image(_) => Decoded {
+            label: kind.label().to_string(),
+            text: match image_size(payload) {
+                Some((width, height)) => format!("{} image, {}×{}", kind.label(), width, height),
+                None => format!("{} image", kind.label()),
+            },
+            is_json: false,
+        },
+        ContentType::Encoded(encoding) => match decode_text(payload, encoding) {
+            Some(text) => text_or_json(encoding.label(), text),
+            None => hex(kind, payload),
+        },
+        ContentType::Json => match serde_json::from_slice::<Value>(payload) {
+            Ok(value) => json(&value),
+            Err(_) => hex(kind, payload),
+        },
+        ContentType::Text => Decoded {
+            label: kind.label().to_string(),
+            text: String::from_utf8_lossy(payload).into_owned(),
+            is_json: false,
+        },
+        ContentType::MessagePack => match msgpack(payload) {
+            Some(value) => json(&value),
+            None => hex(kind, payload),
+        },
+        ContentType::Cbor => match cbor(payload) {
+            Some(value) => json(&value),
+            None => hex(kind, payload),
+        },
+        ContentType::Protobuf | ContentType::ProtobufDelimited => {
+            let body = match kind {
+                ContentType::ProtobufDelimited => delimited_body(payload).unwrap_or(payload),
+                _ => payload,
+            };
+            let mut text = String::new();
+            if let Some(fields) = protobuf_fields(body) {
+                write_protobuf(&fields, 0, &mut text);
+            }
+            Decoded {
+                label: kind.label().to_string(),
+                text,
+                is_json: false,
+            }
+        }
+        ContentType::Gzip | ContentType::Binary => hex(kind, payload),
+    }
+}
+
+fn hex(kind: ContentType, payload: &[u8]) -> Decoded {
+    Decoded {
+        label: kind.label().to_string(),
+        text: payload
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" "),
+        is_json: false,
+    }
+}
+
+/// Recognize a payload without decoding it in full; structured binary
+/// formats count only when the whole payload parses
+pub fn sniff(payload: &[u8]) -> ContentType {
+    if payload.starts_with(&[0x1f, 0x8b]) {
+        return ContentType::Gzip;
+    }
+    if let Some(kind) = image_kind(payload) {
+        return ContentType::Image(kind);
+    }
+    if let Some(encoding) = utf16_encoding(payload) {
+        return ContentType::Encoded(encoding);
+    }
+    if let Ok(text) = std::str::from_utf8(payload) {
+        if serde_json::from_str::<serde::de::IgnoredAny>(text).is_ok() {
+            return ContentType::Json;
+        }
+        // Control characters suggest a binary format that happens to be valid UTF-8
+        if text
+            .chars()
+            .all(|c| !c.is_control() || c.is_ascii_whitespace())
+        {
+            return ContentType::Text;
+        }
+    }
+    if msgpack(payload).is_some() {
+        ContentType::MessagePack
+    } else if cbor(payload).is_some() {
+        ContentType::Cbor
+    } else if protobuf_fields(payload).is_some() {
+        ContentType::Protobuf
+    } else if delimited_body(payload).is_some_and(|body| protobuf_fields(body).is_some()) {
+        ContentType::ProtobufDelimited
+    } else if std::str::from_utf8(payload).is_ok() {
+        ContentType::Text
+    } else if let Some(encoding) = legacy_encoding(payload) {
+        ContentType::Encoded(encoding)
+    } else {
+        ContentType::Binary
+    }
+}
+
+/// Text of a payload in the given encoding, None when the bytes don't fit it
+pub fn decode_text(payload: &[u8], encoding: TextEncoding) -> Option<String> {
+    match encoding {
+        TextEncoding::Latin1 => Some(payload.iter().map(|&b| b as char).collect()),
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let little_endian = encoding == TextEncoding::Utf16Le;
+            let body = match payload {
+                [0xff, 0xfe, rest @ ..] if little_endian => rest,
+                [0xfe, 0xff, rest @ ..] if !little_endian => rest,
+                _ => payload,
+            };
+            if !body.len().is_multiple_of(2) {
+                return None;
+            }
+            let units: Vec<u16> = body
+                .chunks(2)
+                .map(|pair| {
+                    if little_endian {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16(&units).ok()
+        }
+        TextEncoding::ShiftJis => encoding_rs::SHIFT_JIS
+            .decode_without_bom_handling_and_without_replacement(payload)
+            .map(|text| text.into_owned()),
+    }
+}
+
+/// UTF-16 with a byte order mark, or ASCII-range UTF-16 without one
+fn utf16_encoding(payload: &[u8]) -> Option<TextEncoding> {
+    if payload.len() < 4 || !payload.len().is_multiple_of(2) {
+        return None;
+    }
+    let encoding = match payload {
+        [0xff, 0xfe, ..] => TextEncoding::Utf16Le,
+        [0xfe, 0xff, ..] => TextEncoding::Utf16Be,
+        _ if payload.chunks(2).all(|pair| pair[0] != 0 && pair[1] == 0) => TextEncoding::Utf16Le,
+        _ if payload.chunks(2).all(|pair| pair[0] == 0 && pair[1] != 0) => TextEncoding::Utf16Be,
+        _ => return None,
+    };
+    decode_text(payload, encoding).map(|_| encoding)
+}
+
+/// Shift-JIS when the payload decodes to kana or kanji, Latin-1 when every
+/// byte is a printable Latin-1 character. Half-width katakana share their
+/// bytes with accented Latin-1 letters, so they don't count as Japanese.
+fn legacy_encoding(payload: &[u8]) -> Option<TextEncoding> {
+    let printable = |c: char| !c.is_control() || c.is_ascii_whitespace();
+    let japanese = |c: char| matches!(c, '\u{3040}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}');
+    if let Some(text) = decode_text(payload, TextEncoding::ShiftJis) {
+        if text.chars().all(printable) && text.chars().any(japanese) {
+            return Some(TextEncoding::ShiftJis);
+        }
+    }
+    payload
+        .iter()
+        .all(|&b| printable(b as char))
+        .then_some(TextEncoding::Latin1)
+}
+
+fn inflate(payload: &[u8]) -> Option<Vec<u8>> {
+    let mut inflated = Vec::new();
+    GzDecoder::new(payload)
+        .take(MAX_INFLATED_BYTES)
+        .read_to_end(&mut inflated)
+        .ok()?;
+    Some(inflated)
+}
+
+fn image_kind(payload: &[u8]) -> Option<&'static str> {
+    if payload.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("PNG")
+    } else if payload.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("JPEG")
+    } else if payload.starts_with(b"GIF87a") || payload.starts_with(b"GIF89a") {
+        Some("GIF")
+    } else if payload.len() >= 12 && payload.starts_with(b"RIFF") && &payload[8..12] == b"WEBP" {
+        Some("WebP")
+    } else if payload.starts_with(b"BM") && payload.len() >= 26 {
+        Some("BMP")
+    } else {
+        None
+    }
+}
+
+/// Width and height from the image header, where it sits at a fixed place;
+/// BMP stores top-down images with a negative height
+fn image_size(payload: &[u8]) -> Option<(u32, u32)> {
+    match image_kind(payload)? {
+        "PNG" => Some((
+            u32::from_be_bytes(array_at(payload, 16)?),
+            u32::from_be_bytes(array_at(payload, 20)?),
+        )),
+        "GIF" => Some((
+            u16::from_le_bytes(array_at(payload, 6)?) as u32,
+            u16::from_le_bytes(array_at(payload, 8)?) as u32,
+        )),
+        "BMP" => Some((
+            u32::from_le_bytes(array_at(payload, 18)?),
+            i32::from_le_bytes(array_at(payload, 22)?).unsigned_abs(),
+        )),
+        _ => None,
+    }
+}
+
+fn array_at<const N: usize>(bytes: &[u8], at: usize) -> Option<[u8; N]> {
+    bytes.get(at..at.checked_add(N)?)?.try_into().ok()
+}
+
+/// Reads the bytes of a binary document front to back
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn done(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let slice = self.bytes.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn uint(&mut self, n: usize) -> Option<u64> {
+        Some(
+            self.take(n)?
+                .iter()
+                .fold(0u64, |acc, b| (acc << 8) | *b as u64),
+        )
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+fn float(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn bytes_value(bytes: &[u8]) -> Value {
+    Value::String(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Map keys as JSON object keys: strings as they are, anything else as JSON
+fn key_string(key: Value) -> String {
+    match key {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+/// A whole MessagePack document; None unless it is one value using every byte
+fn msgpack(payload: &[u8]) -> Option<Value> {
+    let mut reader = Reader::new(payload);
+    // A lone scalar byte is far more likely something else
+    if payload.len() < 2 {
+        return None;
+    }
+    let value = msgpack_value(&mut reader, 0)?;
+    reader.done().then_some(value)
+}
+
+fn msgpack_value(reader: &mut Reader, depth: usize) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let marker = reader.byte()?;
+    let value = match marker {
+        0x00..=0x7f => Value::from(marker),
+        0xe0..=0xff => Value::from(marker as i8),
+        0x80..=0x8f => msgpack_map(reader, (marker & 0x0f) as usize, depth)?,
+        0x90..=0x9f => msgpack_array(reader, (marker & 0x0f) as usize, depth)?,
+        0xa0..=0xbf => msgpack_str(reader, (marker & 0x1f) as usize)?,
+        0xc0 => Value::Null,
+        0xc2 => Value::Bool(false),
+        0xc3 => Value::Bool(true),
+        0xc4..=0xc6 => {
+            let len = reader.uint(1 << (marker - 0xc4))? as usize;
+            bytes_value(reader.take(len)?)
+        }
+        0xca => float(f32::from_bits(reader.uint(4)? as u32) as f64),
+        0xcb => float(f64::from_bits(reader.uint(8)?)),
+        0xcc..=0xcf => Value::from(reader.uint(1 << (marker - 0xcc))?),
+        0xd0 => Value::from(reader.uint(1)? as u8 as i8),
+        0xd1 => Value::from(reader.uint(2)? as u16 as i16),
+        0xd2 => Value::from(reader.uint(4)? as u32 as i32),
+        0xd3 => Value::from(reader.uint(8)? as i64),
+        0xd9..=0xdb => {
+            let len = reader.uint(1 << (marker - 0xd9))? as usize;
+            msgpack_str(reader, len)?
+        }
+        0xdc | 0xdd => {
+            let len = reader.uint(if marker == 0xdc { 2 } else { 4 })? as usize;
+            msgpack_array(reader, len, depth)?
+        }
+        0xde | 0xdf => {
+            let len = reader.uint(if marker == 0xde { 2 } else { 4 })? as usize;
+            msgpack_map(reader, len, depth)?
+        }
+        // Extension types and the unused 0xc1
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn msgpack_str(reader: &mut Reader, len: usize) -> Option<Value> {
+    let bytes = reader.take(len)?;
+    Some(Value::String(std::str::from_utf8(bytes).ok()?.to_string()))
+}
+
+fn msgpack_array(reader: &mut Reader, len: usize, depth: usize) -> Option<Value> {
+    // Every element takes at least a byte
+    if len > reader.bytes.len() - reader.pos {
+        return None;
+    }
+    let items = (0..len)
+        .map(|_| msgpack_value(reader, depth + 1))
+        .collect::<Option<Vec<_>>>()?;
+    Some(Value::Array(items))
+}
+
+fn msgpack_map(reader: &mut Reader, len: usize, depth: usize) -> Option<Value> {
+    if len > reader.bytes.len() - reader.pos {
+        return None;
+    }
+    let mut map = Map::new();
+    for _ in 0..len {
+        let key = key_string(msgpack_value(reader, depth + 1)?);
+        map.insert(key, msgpack_value(reader, depth + 1)?);
+    }
+    Some(Value::Object(map))
+}
+
+/// A whole CBOR document; None unless it is one value using every byte
+fn cbor(payload: &[u8]) -> Option<Value> {
+    if payload.len() < 2 {
+        return None;
+    }
+    let mut reader = Reader::new(payload);
+    let value = cbor_value(&mut reader, 0)?;
+    reader.done().then_some(value)
+}
+
+/// Marker closing an indefinite-length array or map
+const CBOR_BREAK: u8 = 0xff;
+
+fn cbor_value(reader: &mut Reader, depth: usize) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let initial = reader.byte()?;
+    let major = initial >> 5;
+    let info = initial & 0x1f;
+    let indefinite = info == 31 && matches!(major, 4 | 5);
+    let argument = match info {
+        0..=23 => info as u64,
+        24..=27 => reader.uint(1 << (info - 24))?,
+        31 if indefinite => 0,
+        _ => return None,
+    };
+    let value = match major {
+        0 => Value::from(argument),
+        1 => Value::from(-1 - i64::try_from(argument).ok()?),
+        2 => bytes_value(reader.take(argument as usize)?),
+        3 => Value::String(
+            std::str::from_utf8(reader.take(argument as usize)?)
+                .ok()?
+                .to_string(),
+        ),
+        4 => {
+            let mut items = Vec::new();
+            if indefinite {
+                while reader.bytes.get(reader.pos) != Some(&CBOR_BREAK) {
+                    items.push(cbor_value(reader, depth + 1)?);
+                }
+                reader.byte()?;
+            } else {
+                if argument as usize > reader.bytes.len() - reader.pos {
+                    return None;
+                }
+                for _ in 0..argument {
+                    items.push(cbor_value(reader, depth + 1)?);
+                }
+            }
+            Value::Array(items)
+        }
+        5 => {
+            let mut map = Map::new();
+            if indefinite {
+                while reader.bytes.get(reader.pos) != Some(&CBOR_BREAK) {
+                    let key = key_string(cbor_value(reader, depth + 1)?);
+                    map.insert(key, cbor_value(reader, depth + 1)?);
+                }
+                reader.byte()?;
+            } else {
+                if argument as usize > reader.bytes.len() - reader.pos {
+                    return None;
+                }
+                for _ in 0..argument {
+                    let key = key_string(cbor_value(reader, depth + 1)?);
+                    map.insert(key, cbor_value(reader, depth + 1)?);
+                }
+            }
+            Value::Object(map)
+        }
+        // Tags (such as the self-describe tag) wrap the value they describe
+        6 => cbor_value(reader, depth + 1)?,
+        _ => match info {
+            20 => Value::Bool(false),
+            21 => Value::Bool(true),
+            22 | 23 => Value::Null,
+            25 => float(half_to_f64(argument as u16)),
+            26 => float(f32::from_bits(argument as u32) as f64),
+            27 => float(f64::from_bits(argument)),
+            _ => return None,
+        },
+    };
+    Some(value)
+}
+
+fn half_to_f64(half: u16) -> f64 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let fraction = (half & 0x3ff) as f64;
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        31 if fraction == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+/// A field of a protobuf message decoded without its schema
+#[derive(Debug)]
+enum ProtoValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Fixed32(u32),
+    Bytes(&'a [u8]),
+}
+
+/// Fields of a protobuf message, the way `protoc --decode_raw` reads them;
+/// None unless every byte belongs to a well-formed field
+fn protobuf_fields(payload: &[u8]) -> Option<Vec<(u64, ProtoValue<'_>)>> {
+    let mut reader = Reader::new(payload);
+    let mut fields = Vec::new();
+    while !reader.done() {
+        let key = reader.varint()?;
+        let field = key >> 3;
+        if field == 0 || field > 536_870_911 {
+            return None;
+        }
+        let value = match key & 0x07 {
+            0 => ProtoValue::Varint(reader.varint()?),
+            1 => ProtoValue::Fixed64(u64::from_le_bytes(reader.take(8)?.try_into().ok()?)),
+            2 => {
+                let len = reader.varint()? as usize;
+                ProtoValue::Bytes(reader.take(len)?)
+            }
+            5 => ProtoValue::Fixed32(u32::from_le_bytes(reader.take(4)?.try_into().ok()?)),
+            _ => return None,
+        };
+        fields.push((field, value));
+    }
+    (!fields.is_empty()).then_some(fields)
+}
+
+/// The message after a varint length prefix covering the rest of the payload
+fn delimited_body(payload: &[u8]) -> Option<&[u8]> {
+    let mut reader = Reader::new(payload);
+    let len = reader.varint()? as usize;
+    let body = &payload[reader.pos..];
+    (len == body.len() && len > 0).then_some(body)
+}
+
+fn write_protobuf(fields: &[(u64, ProtoValue)], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    for (field, value) in fields {
+        match value {
+            ProtoValue::Varint(v) => out.push_str(&format!("{}{}: {}\n", indent, field, v)),
+            ProtoValue::Fixed64(v) => out.push_str(&format!("{}{}: 0x{:016x}\n", indent, field, v)),
+            ProtoValue::Fixed32(v) => out.push_str(&format!("{}{}: 0x{:08x}\n", indent, field, v)),
+            ProtoValue::Bytes(bytes) => {
+                let text = std::str::from_utf8(bytes)
+                    .ok()
+                    .filter(|s| !s.chars().any(|c| c.is_control() && c != '\n'));
+                let nested = (text.is_none() && depth < MAX_DEPTH)
+                    .then(|| protobuf_fields(bytes))
+                    .flatten();
+                if let Some(text) = text {
+                    out.push_str(&format!("{}{}: {:?}\n", indent, field, text));
+                } else if let Some(nested) = nested {
+                    out.push_str(&format!("{}{} {{\n", indent, field));
+                    write_protobuf(&nested, depth + 1, out);
+                    out.push_str(&format!("{}}}\n", indent));
+                } else {
+                    let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                    out.push_str(&format!("{}{}: <{}>\n", indent, field, hex.join(" ")));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_gzip_json() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(br#"{"rpm":1200}"#).unwrap();
+        let payload = encoder.finish().unwrap();
+
+        let decoded = decode(&payload, None);
+        assert_eq!(decoded.label, "gzip → JSON");
+        assert!(decoded.is_json);
+        assert_eq!(decoded.text, "{\n  \"rpm\": 1200\n}");
+    }
+
+    #[test]
+    fn test_msgpack_and_cbor() {
+        // {"temp": 21.5, "ok": true}
+        let msgpack = b"\x82\xa4temp\xcb\x40\x35\x80\x00\x00\x00\x00\x00\xa2ok\xc3";
+        assert_eq!(sniff(msgpack), ContentType::MessagePack);
+        assert_eq!(
+            decode(msgpack, None).text,
+            "{\n  \"ok\": true,\n  \"temp\": 21.5\n}"
+        );
+
+        // {"temp": 21.5} with a half-precision float, then [1, -2] of indefinite length
+        let cbor_map = b"\xa1\x64temp\xf9\x4d\x60";
+        assert_eq!(sniff(cbor_map), ContentType::Cbor);
+        assert_eq!(decode(cbor_map, None).text, "{\n  \"temp\": 21.5\n}");
+        assert_eq!(cbor(b"\x9f\x01\x21\xff"), Some(serde_json::json!([1, -2])));
+    }
+
+    #[test]
+    fn test_protobuf() {
+        // 1: 150, 2: "hi", 3 { 1: 1 }
+        let message = b"\x08\x96\x01\x12\x02hi\x1a\x02\x08\x01";
+        assert_eq!(sniff(message), ContentType::Protobuf);
+        assert_eq!(
+            decode(message, None).text,
+            "1: 150\n2: \"hi\"\n3 {\n  1: 1\n}\n"
+        );
+
+        let mut delimited = vec![message.len() as u8];
+        delimited.extend_from_slice(message);
+        assert_eq!(sniff(&delimited), ContentType::ProtobufDelimited);
+    }
+
+    #[test]
+    fn test_text_utf16_and_images() {
+        assert_eq!(sniff(b"online"), ContentType::Text);
+        assert_eq!(sniff(br#"{"a":1}"#), ContentType::Json);
+
+        let utf16: Vec<u8> = "\u{feff}{\"a\":1}"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert_eq!(decode(&utf16, None).label, "UTF-16LE → JSON");
+
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(decode(&png, None).text, "PNG image, 640×480");
+
+        assert_eq!(sniff(&[0xc1, 0xff, 0x00]), ContentType::Binary);
+    }
+
+    #[test]
+    fn test_legacy_encodings() {
+        // "Temp 21°C" in Latin-1, "温度" in Shift-JIS
+        let latin1 = b"Temp 21\xb0C";
+        assert_eq!(sniff(latin1), ContentType::Encoded(TextEncoding::Latin1));
+        assert_eq!(decode(latin1, None).text, "Temp 21°C");
+        let shift_jis = b"\x89\xb7\x93\x78";
+        assert_eq!(
+            sniff(shift_jis),
+            ContentType::Encoded(TextEncoding::ShiftJis)
+        );
+        assert_eq!(decode(shift_jis, None).text, "温度");
+
+        // A configured encoding wins over detection, JSON still pretty-printed
+        let decoded = decode(b"{\"t\":\"\xb0\"}", Some(TextEncoding::Latin1));
+        assert_eq!(decoded.label, "Latin-1 → JSON");
+        assert_eq!(decoded.text, "{\n  \"t\": \"°\"\n}");
+    }
+}
@@ -0,0 +1,415 @@
+//! A small in-process MQTT 3.1.1 broker: enough of the protocol for mqtop's
+//! own client (connect, subscribe, publish at any QoS, retained messages,
+//! last will, keep-alive), with no persistence or authentication.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+/// Larger packets are refused and the connection is dropped
+const MAX_PACKET_BYTES: usize = 4 * 1024 * 1024;
+
+const CONNECT: u8 = 1;
+const PUBLISH: u8 = 3;
+const PUBREL: u8 = 6;
+const SUBSCRIBE: u8 = 8;
+const UNSUBSCRIBE: u8 = 10;
+const PINGREQ: u8 = 12;
+const DISCONNECT: u8 = 14;
+
+#[derive(Clone)]
+pub struct MockBroker {
+    shared: Arc<Shared>,
+    local_addr: SocketAddr,
+}
+
+#[derive(Default)]
+struct Shared {
+    sessions: Mutex<Vec<Session>>,
+    retained: Mutex<BTreeMap<String, Vec<u8>>>,
+    next_id: AtomicU64,
+}
+
+struct Session {
+    id: u64,
+    filters: Vec<String>,
+    outgoing: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+struct Will {
+    topic: String,
+    payload: Vec<u8>,
+    retain: bool,
+}
+
+impl MockBroker {
+    /// Bind the listener (use port 0 for any free port) and accept clients in the background
+    pub async fn bind(addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind demo broker on {}", addr))?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+
+        let accept_shared = shared.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let shared = accept_shared.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_client(stream, shared).await {
+                                debug!("Demo broker client {} dropped: {:?}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        debug!("Demo broker accept failed: {:?}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Demo broker listening on {}", local_addr);
+        Ok(Self { shared, local_addr })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Number of connected clients
+    pub fn client_count(&self) -> usize {
+        self.shared.sessions.lock().map(|s| s.len()).unwrap_or(0)
+    }
+
+    /// Deliver a message to every subscribed client, as if a device published it
+    pub fn publish(&self, topic: &str, payload: impl Into<Vec<u8>>, retain: bool) {
+        self.shared.publish(topic, payload.into(), retain);
+    }
+}
+
+impl Shared {
+    fn publish(&self, topic: &str, payload: Vec<u8>, retain: bool) {
+        if retain {
+            if let Ok(mut retained) = self.retained.lock() {
+                // An empty retained message clears the topic
+                if payload.is_empty() {
+                    retained.remove(topic);
+                } else {
+                    retained.insert(topic.to_string(), payload.clone());
+                }
+            }
+        }
+
+        // Retain is only set on delivery to new subscriptions
+        let packet = encode_publish(topic, &payload, false);
+        if let Ok(sessions) = self.sessions.lock() {
+            for session in sessions.iter() {
+                if session.filters.iter().any(|f| filter_matches(f, topic)) {
+                    let _ = session.outgoing.send(packet.clone());
+                }
+            }
+        }
+    }
+
+    fn subscribe(&self, id: u64, filters: &[String]) {
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return;
+        };
+        let Some(session) = sessions.iter_mut().find(|s| s.id == id) else {
+            return;
+        };
+        for filter in filters {
+            if !session.filters.contains(filter) {
+                session.filters.push(filter.clone());
+            }
+        }
+        if let Ok(retained) = self.retained.lock() {
+            for (topic, payload) in retained.iter() {
+                if filters.iter().any(|f| filter_matches(f, topic)) {
+                    let _ = session.outgoing.send(encode_publish(topic, payload, true));
+                }
+            }
+        }
+    }
+
+    fn unsubscribe(&self, id: u64, filters: &[String]) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            if let Some(session) = sessions.iter_mut().find(|s| s.id == id) {
+                session.filters.retain(|f| !filters.contains(f));
+            }
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|s| s.id != id);
+        }
+    }
+}
+
+async fn serve_client(stream: TcpStream, shared: Arc<Shared>) -> Result<()> {
+    let (mut reader, mut writer) = stream.into_split();
+
+    let Some((header, body)) = read_packet(&mut reader).await? else {
+        return Ok(());
+    };
+    if header >> 4 != CONNECT {
+        bail!("Expected CONNECT, got packet type {}", header >> 4);
+    }
+    let will = parse_connect(&body)?;
+    // Session present = 0, return code 0 (accepted)
+    writer.write_all(&[0x20, 2, 0, 0]).await?;
+
+    let id = shared.next_id.fetch_add(1, Ordering::Relaxed);
+    let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<Vec<u8>>();
+    if let Ok(mut sessions) = shared.sessions.lock() {
+        sessions.push(Session {
+            id,
+            filters: Vec::new(),
+            outgoing: outgoing.clone(),
+        });
+    }
+
+    let writer_task = tokio::spawn(async move {
+        while let Some(packet) = outgoing_rx.recv().await {
+            if writer.write_all(&packet).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let result = client_loop(&mut reader, &shared, id, &outgoing).await;
+    shared.remove(id);
+    writer_task.abort();
+
+    match result {
+        // Clean DISCONNECT: the will is discarded
+        Ok(true) => Ok(()),
+        Ok(false) | Err(_) => {
+            if let Some(will) = will {
+                shared.publish(&will.topic, will.payload, will.retain);
+            }
+            result.map(|_| ())
+        }
+    }
+}
+
+/// Handle packets until the client goes away. Returns whether it sent DISCONNECT.
+async fn client_loop<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    shared: &Shared,
+    id: u64,
+    outgoing: &mpsc::UnboundedSender<Vec<u8>>,
+) -> Result<bool> {
+    while let Some((header, body)) = read_packet(reader).await? {
+        match header >> 4 {
+            PUBLISH => {
+                let qos = (header >> 1) & 0x03;
+                let retain = header & 0x01 == 1;
+                let (topic, mut pos) = read_string(&body, 0)?;
+                if qos > 0 {
+                    let packet_id = read_u16(&body, pos)?;
+                    pos += 2;
+                    // PUBACK for QoS 1, PUBREC for QoS 2
+                    let ack = if qos == 1 { 0x40 } else { 0x50 };
+                    let _ = outgoing.send(vec![ack, 2, (packet_id >> 8) as u8, packet_id as u8]);
+                }
+                shared.publish(&topic, body[pos..].to_vec(), retain);
+            }
+            PUBREL => {
+                let packet_id = read_u16(&body, 0)?;
+                let _ = outgoing.send(vec![0x70, 2, (packet_id >> 8) as u8, packet_id as u8]);
+            }
+            SUBSCRIBE => {
+                let packet_id = read_u16(&body, 0)?;
+                let mut pos = 2;
+                let mut filters = Vec::new();
+                while pos < body.len() {
+                    let (filter, next) = read_string(&body, pos)?;
+                    // Skip the requested QoS; everything is delivered at QoS 0
+                    pos = next + 1;
+                    filters.push(filter);
+                }
+                let mut suback = vec![0x90, 2 + filters.len() as u8];
+                suback.extend_from_slice(&packet_id.to_be_bytes());
+                suback.extend(std::iter::repeat_n(0, filters.len()));
+                let _ = outgoing.send(suback);
+                shared.subscribe(id, &filters);
+            }
+            UNSUBSCRIBE => {
+                let packet_id = read_u16(&body, 0)?;
+                let mut pos = 2;
+                let mut filters = Vec::new();
+                while pos < body.len() {
+                    let (filter, next) = read_string(&body, pos)?;
+                    pos = next;
+                    filters.push(filter);
+                }
+                shared.unsubscribe(id, &filters);
+                let _ = outgoing.send(vec![0xB0, 2, (packet_id >> 8) as u8, packet_id as u8]);
+            }
+            PINGREQ => {
+                let _ = outgoing.send(vec![0xD0, 0]);
+            }
+            DISCONNECT => return Ok(true),
+            // Acks for our QoS 0 deliveries never come; ignore anything else
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+/// Read one packet: the fixed header byte and the body. `None` on a clean EOF.
+async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<(u8, Vec<u8>)>> {
+    let mut header = [0u8; 1];
+    if reader.read(&mut header).await? == 0 {
+        return Ok(None);
+    }
+
+    let mut length = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte = reader.read_u8().await?;
+        length |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+        if shift > 21 {
+            bail!("Malformed remaining length");
+        }
+    }
+    if length > MAX_PACKET_BYTES {
+        bail!("Packet of {} bytes is over the demo broker limit", length);
+    }
+
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some((header[0], body)))
+}
+
+/// Pull the last will out of a CONNECT body
+fn parse_connect(body: &[u8]) -> Result<Option<Will>> {
+    let (protocol, pos) = read_string(body, 0)?;
+    if protocol != "MQTT" && protocol != "MQIsdp" {
+        bail!("Unknown protocol name {:?}", protocol);
+    }
+    // Protocol level, then connect flags, then keep-alive
+    let flags = *body.get(pos + 1).context("Truncated CONNECT")?;
+    let (_client_id, pos) = read_string(body, pos + 4)?;
+
+    if flags & 0x04 == 0 {
+        return Ok(None);
+    }
+    let (topic, pos) = read_string(body, pos)?;
+    let (payload, _) = read_bytes(body, pos)?;
+    Ok(Some(Will {
+        topic,
+        payload: payload.to_vec(),
+        retain: flags & 0x20 != 0,
+    }))
+}
+
+fn read_u16(body: &[u8], pos: usize) -> Result<u16> {
+    match body.get(pos..pos + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => bail!("Truncated packet"),
+    }
+}
+
+fn read_bytes(body: &[u8], pos: usize) -> Result<(&[u8], usize)> {
+    let len = read_u16(body, pos)? as usize;
+    let start = pos + 2;
+    let bytes = body.get(start..start + len).context("Truncated packet")?;
+    Ok((bytes, start + len))
+}
+
+fn read_string(body: &[u8], pos: usize) -> Result<(String, usize)> {
+    let (bytes, next) = read_bytes(body, pos)?;
+    let text = std::str::from_utf8(bytes).context("Invalid UTF-8 in packet")?;
+    Ok((text.to_string(), next))
+}
+
+fn encode_publish(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let remaining = 2 + topic.len() + payload.len();
+    let mut packet = Vec::with_capacity(remaining + 5);
+    packet.push(0x30 | retain as u8);
+    let mut length = remaining;
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(&(topic.len() as u16).to_be_bytes());
+    packet.extend_from_slice(topic.as_bytes());
+    packet.extend_from_slice(payload);
+    packet
+}
+
+/// MQTT topic filter matching. Wildcards don't match topics starting with `$`.
+fn filter_matches(filter: &str, topic: &str) -> bool {
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        match level {
+            "#" => return true,
+            "+" => {
+                if topic_levels.next().is_none() {
+                    return false;
+                }
+            }
+            _ => {
+                if topic_levels.next() != Some(level) {
+                    return false;
+                }
+            }
+        }
+    }
+    topic_levels.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_matches() {
+        assert!(filter_matches("#", "a/b/c"));
+        assert!(filter_matches("a/#", "a"));
+        assert!(filter_matches("a/#", "a/b/c"));
+        assert!(filter_matches("a/+/c", "a/b/c"));
+        assert!(!filter_matches("a/+/c", "a/b/d"));
+        assert!(!filter_matches("a/+", "a/b/c"));
+        assert!(filter_matches("a/b", "a/b"));
+        assert!(!filter_matches("a/b", "a/b/c"));
+        assert!(!filter_matches("#", "$SYS/uptime"));
+        assert!(!filter_matches("+/uptime", "$SYS/uptime"));
+        assert!(filter_matches("$SYS/#", "$SYS/uptime"));
+    }
+
+    #[test]
+    fn test_encode_publish_long_payload() {
+        let payload = vec![b'x'; 200];
+        let packet = encode_publish("t", &payload, true);
+        // 0x31, two length bytes for 203, then topic length, topic, payload
+        assert_eq!(&packet[..3], &[0x31, 0xCB, 0x01]);
+        assert_eq!(packet.len(), 3 + 2 + 1 + 200);
+    }
+}
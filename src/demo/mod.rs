@@ -0,0 +1,45 @@
+//! Demo mode: an in-process broker fed by simulated telemetry, so mqtop can be
+//! tried without a broker and the integration tests can run the real client.
+
+mod broker;
+mod simulator;
+
+pub use broker::MockBroker;
+pub use simulator::{SimMessage, Simulator, TICK};
+
+use std::net::SocketAddr;
+
+use anyhow::Result;
+
+use crate::config::Config;
+
+/// Profile demo mode runs in, so it never touches the user's own servers or stars
+pub const DEMO_PROFILE: &str = "demo";
+
+/// Start a broker on a free local port with the simulator publishing to it
+pub async fn start() -> Result<MockBroker> {
+    let broker = MockBroker::bind("127.0.0.1:0").await?;
+    Simulator::new().spawn(broker.clone());
+    Ok(broker)
+}
+
+/// A config with a single server pointing at the demo broker, connected at startup
+pub fn config(addr: SocketAddr) -> Result<Config> {
+    let mut config = Config::parse(&format!(
+        r##"
+[mqtt]
+active_server = "demo"
+
+[[mqtt.servers]]
+name = "demo"
+host = "{}"
+port = {}
+client_id = "mqtop-demo"
+subscribe_topic = "#"
+"##,
+        addr.ip(),
+        addr.port()
+    ))?;
+    config.connect_on_start = true;
+    Ok(config)
+}
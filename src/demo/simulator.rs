@@ -0,0 +1,283 @@
+//! Made-up but plausible telemetry for demo mode: a factory site, energy
+//! meters, home devices and vehicles, on the topic layouts mqtop recognizes
+//! as devices. Values drift over time; one machine reports slowly and one
+//! meter stops after a while, so the health views have something to show.
+
+use std::time::Duration;
+
+use super::MockBroker;
+
+/// Time between simulator steps
+pub const TICK: Duration = Duration::from_millis(250);
+
+const LINES: [&str; 2] = ["line-1", "line-2"];
+const MACHINES: [&str; 4] = ["press", "lathe", "mill", "packer"];
+const METERS: [&str; 3] = ["meter-0a17", "meter-3f42", "meter-9c05"];
+const ROOMS: [&str; 3] = ["living-room", "kitchen", "bedroom"];
+const VEHICLES: [&str; 3] = ["truck-1", "truck-2", "van-7"];
+
+/// Ticks after which `METERS[2]` goes silent
+const METER_DROPOUT_TICK: u64 = 240;
+
+/// One message produced by a simulator step
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimMessage {
+    pub topic: String,
+    pub payload: Vec<u8>,
+    pub retain: bool,
+}
+
+pub struct Simulator {
+    tick: u64,
+    rng: u64,
+    lights_on: [bool; ROOMS.len()],
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulator {
+    pub fn new() -> Self {
+        Self {
+            tick: 0,
+            rng: 0x9E37_79B9_7F4A_7C15,
+            lights_on: [false; ROOMS.len()],
+        }
+    }
+
+    /// Publish a step's messages to `broker` every `TICK`, forever
+    pub fn spawn(mut self, broker: MockBroker) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK);
+            loop {
+                interval.tick().await;
+                for message in self.step() {
+                    broker.publish(&message.topic, message.payload, message.retain);
+                }
+            }
+        })
+    }
+
+    /// Advance one tick and return what was published during it
+    pub fn step(&mut self) -> Vec<SimMessage> {
+        let tick = self.tick;
+        self.tick += 1;
+        let mut out = Vec::new();
+
+        if tick == 0 {
+            self.announce(&mut out);
+        }
+
+        // Factory machines every second; the mill on line 2 only every 20 seconds
+        for (line_index, line) in LINES.iter().enumerate() {
+            for (machine_index, machine) in MACHINES.iter().enumerate() {
+                let period = if line_index == 1 && machine_index == 2 {
+                    80
+                } else {
+                    4
+                };
+                if !(tick + machine_index as u64).is_multiple_of(period) {
+                    continue;
+                }
+                let phase = (tick as f64 / 40.0) + machine_index as f64;
+                let state = if (tick / 400 + machine_index as u64).is_multiple_of(7) {
+                    "idle"
+                } else {
+                    "running"
+                };
+                let rpm = if state == "idle" {
+                    0.0
+                } else {
+                    1200.0 + 300.0 * phase.sin() + self.noise(40.0)
+                };
+                out.push(json(
+                    format!("sites/plant-north/devices/{}-{}/telemetry", line, machine),
+                    format!(
+                        r#"{{"state":"{}","rpm":{:.0},"temp_c":{:.1},"vibration_mm_s":{:.2},"parts":{}}}"#,
+                        state,
+                        rpm,
+                        55.0 + 8.0 * phase.cos() + self.noise(1.5),
+                        2.0 + phase.sin().abs() * 3.0 + self.noise(0.4),
+                        tick / 4 * (machine_index as u64 + 1)
+                    ),
+                ));
+            }
+        }
+
+        // Energy meters every two seconds
+        if tick.is_multiple_of(8) {
+            for (index, meter) in METERS.iter().enumerate() {
+                if index == 2 && tick >= METER_DROPOUT_TICK {
+                    continue;
+                }
+                let watts = 1800.0
+                    + 900.0 * ((tick as f64 / 120.0) + index as f64).sin()
+                    + self.noise(120.0);
+                let voltage = 230.0 + self.noise(2.5);
+                out.push(json(
+                    format!("telemetry/{}/meter/power/json", meter),
+                    format!(
+                        r#"{{"power_w":{:.0},"voltage_v":{:.1},"current_a":{:.2},"energy_kwh":{:.3}}}"#,
+                        watts,
+                        voltage,
+                        watts / voltage,
+                        12_000.0 + tick as f64 * 0.0005 * (index as f64 + 1.0)
+                    ),
+                ));
+            }
+        }
+
+        // Room climate every five seconds, lights toggle now and then
+        if tick.is_multiple_of(20) {
+            for (index, room) in ROOMS.iter().enumerate() {
+                out.push(json(
+                    format!("devices/thermostat-{}/climate", room),
+                    format!(
+                        r#"{{"temperature":{:.1},"humidity":{:.0},"setpoint":21.0,"battery":{}}}"#,
+                        20.0 + index as f64 + (tick as f64 / 300.0).sin() + self.noise(0.2),
+                        45.0 + 5.0 * (tick as f64 / 500.0).cos() + self.noise(1.0),
+                        100 - (tick / 2000).min(60)
+                    ),
+                ));
+            }
+        }
+        if tick % 60 == 30 {
+            let room = (self.next() % ROOMS.len() as u64) as usize;
+            self.lights_on[room] = !self.lights_on[room];
+            out.push(SimMessage {
+                topic: format!("devices/light-{}/state", ROOMS[room]),
+                payload: if self.lights_on[room] { "ON" } else { "OFF" }.into(),
+                retain: true,
+            });
+        }
+        if tick % 90 == 45 {
+            out.push(SimMessage {
+                topic: "devices/door-front/event".to_string(),
+                payload: if tick % 180 == 45 { "opened" } else { "closed" }.into(),
+                retain: false,
+            });
+        }
+
+        // Vehicles every three seconds
+        if tick % 12 == 6 {
+            for (index, vehicle) in VEHICLES.iter().enumerate() {
+                let t = tick as f64 / 200.0 + index as f64 * 2.0;
+                out.push(json(
+                    format!("fleet/{}/gps", vehicle),
+                    format!(
+                        r#"{{"lat":{:.5},"lon":{:.5},"speed_kmh":{:.0},"heading":{:.0}}}"#,
+                        59.33 + 0.05 * t.sin(),
+                        18.06 + 0.08 * t.cos(),
+                        (60.0 + 30.0 * (t * 3.0).sin()).max(0.0),
+                        (t * 57.3) % 360.0
+                    ),
+                ));
+            }
+        }
+
+        // Raw sensor frames, for hex mode: id, reading (big endian), checksum
+        if tick % 16 == 3 {
+            let reading = (self.next() % 4096) as u16;
+            let [high, low] = reading.to_be_bytes();
+            out.push(SimMessage {
+                topic: "sensors/raw/adc-4".to_string(),
+                payload: vec![0x04, high, low, 0x04 ^ high ^ low],
+                retain: false,
+            });
+        }
+
+        // Broker stats every ten seconds
+        if tick.is_multiple_of(40) {
+            out.push(SimMessage {
+                topic: "$SYS/broker/uptime".to_string(),
+                payload: format!("{} seconds", tick / 4).into_bytes(),
+                retain: true,
+            });
+        }
+
+        out
+    }
+
+    /// Retained state that a real fleet would already have on the broker
+    fn announce(&self, out: &mut Vec<SimMessage>) {
+        for line in LINES {
+            for machine in MACHINES {
+                out.push(SimMessage {
+                    topic: format!("sites/plant-north/devices/{}-{}/status", line, machine),
+                    payload: b"online".to_vec(),
+                    retain: true,
+                });
+            }
+        }
+        for room in ROOMS {
+            out.push(SimMessage {
+                topic: format!("devices/light-{}/state", room),
+                payload: b"OFF".to_vec(),
+                retain: true,
+            });
+        }
+        out.push(SimMessage {
+            topic: "$SYS/broker/version".to_string(),
+            payload: format!("mqtop demo broker {}", env!("CARGO_PKG_VERSION")).into_bytes(),
+            retain: true,
+        });
+    }
+
+    /// xorshift64, so runs are repeatable without a rand dependency
+    fn next(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    /// Uniform noise in `-amplitude..amplitude`
+    fn noise(&mut self, amplitude: f64) -> f64 {
+        let unit = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        (unit * 2.0 - 1.0) * amplitude
+    }
+}
+
+fn json(topic: String, payload: String) -> SimMessage {
+    SimMessage {
+        topic,
+        payload: payload.into_bytes(),
+        retain: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_produces_valid_telemetry() {
+        let mut simulator = Simulator::new();
+        let first = simulator.step();
+        assert!(first
+            .iter()
+            .any(|m| m.retain && m.topic.ends_with("/status")));
+
+        let mut meter_topics = std::collections::HashSet::new();
+        for _ in 0..METER_DROPOUT_TICK {
+            for message in simulator.step() {
+                if message.topic.starts_with("telemetry/") {
+                    meter_topics.insert(message.topic.clone());
+                }
+                if message.topic.ends_with("/telemetry") || message.topic.ends_with("/json") {
+                    serde_json::from_slice::<serde_json::Value>(&message.payload)
+                        .expect("telemetry payloads are JSON");
+                }
+            }
+        }
+        assert_eq!(meter_topics.len(), METERS.len());
+
+        // The last meter has dropped out
+        let later: Vec<_> = (0..40).flat_map(|_| simulator.step()).collect();
+        assert!(!later.iter().any(|m| m.topic.contains(METERS[2])));
+        assert!(later.iter().any(|m| m.topic.contains(METERS[0])));
+    }
+}
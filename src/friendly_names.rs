@@ -0,0 +1,168 @@
+//! Human-friendly names for device IDs and other opaque topic levels,
+//! loaded from a CSV or JSON file or fetched from an HTTP endpoint.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+use crate::http;
+
+/// Mapping of IDs to friendly names
+#[derive(Debug, Clone, Default)]
+pub struct FriendlyNames {
+    names: HashMap<String, String>,
+}
+
+impl FriendlyNames {
+    /// Load a `.json` file, or CSV for any other extension
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read friendly names from {:?}", path))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::parse_json(&content).with_context(|| format!("Invalid names file {:?}", path))
+        } else {
+            Ok(Self::parse_csv(&content))
+        }
+    }
+
+    /// Fetch a JSON mapping from an http:// endpoint
+    pub async fn fetch(url: &str) -> Result<Self> {
+        let body = http::get(url).await?;
+        Self::parse_json(&body).with_context(|| format!("Invalid names from {}", url))
+    }
+
+    /// `id,name` lines. A name may contain commas; blank lines, `#` comments
+    /// and an `id,name` header are skipped.
+    pub fn parse_csv(content: &str) -> Self {
+        let names = content
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .filter_map(|line| line.split_once(','))
+            .map(|(id, name)| (unquote(id), unquote(name)))
+            .filter(|(id, name)| {
+                !id.is_empty() && !name.is_empty() && !id.eq_ignore_ascii_case("id")
+            })
+            .collect();
+        Self { names }
+    }
+
+    /// Either an `{"id": "name"}` object or an array of `{"id", "name"}` objects
+    pub fn parse_json(content: &str) -> Result<Self> {
+        let root: Value = serde_json::from_str(content)?;
+        let names = match root {
+            Value::Object(map) => map
+                .into_iter()
+                .filter_map(|(id, name)| Some((id, name.as_str()?.to_string())))
+                .collect(),
+            Value::Array(entries) => entries
+                .iter()
+                .filter_map(|entry| {
+                    let id = entry.get("id")?;
+                    let id = id.as_str().map_or_else(|| id.to_string(), str::to_string);
+                    Some((id, entry.get("name")?.as_str()?.to_string()))
+                })
+                .collect(),
+            _ => bail!("Expected an object of id to name, or an array of {{id, name}}"),
+        };
+        Ok(Self { names })
+    }
+
+    /// Add names from `other`, replacing existing ones for the same ID
+    pub fn extend(&mut self, other: FriendlyNames) {
+        self.names.extend(other.names);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.names.get(id).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// `id (name)`, or just the name with `replace`; the ID itself when unnamed
+    pub fn label<'a>(&self, id: &'a str, replace: bool) -> Cow<'a, str> {
+        match self.get(id) {
+            Some(name) if replace => Cow::Owned(name.to_string()),
+            Some(name) => Cow::Owned(format!("{} ({})", id, name)),
+            None => Cow::Borrowed(id),
+        }
+    }
+
+    /// A topic with each named level labelled
+    pub fn label_topic<'a>(&self, topic: &'a str, separator: char, replace: bool) -> Cow<'a, str> {
+        if self.is_empty()
+            || !topic
+                .split(separator)
+                .any(|level| self.get(level).is_some())
+        {
+            return Cow::Borrowed(topic);
+        }
+        let levels: Vec<Cow<str>> = topic
+            .split(separator)
+            .map(|level| self.label(level, replace))
+            .collect();
+        Cow::Owned(levels.join(&separator.to_string()))
+    }
+}
+
+fn unquote(field: &str) -> String {
+    let field = field.trim();
+    field
+        .strip_prefix('"')
+        .and_then(|f| f.strip_suffix('"'))
+        .unwrap_or(field)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let names = FriendlyNames::parse_csv(
+            "id,name\n# meters\ndev-0042,Boiler room\n\"dev-0043\", \"Roof, east\"\nbroken\n",
+        );
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.get("dev-0042"), Some("Boiler room"));
+        assert_eq!(names.get("dev-0043"), Some("Roof, east"));
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let names = FriendlyNames::parse_json(r#"{"dev-0042": "Boiler room"}"#).unwrap();
+        assert_eq!(names.get("dev-0042"), Some("Boiler room"));
+
+        let names =
+            FriendlyNames::parse_json(r#"[{"id": 7, "name": "Pump"}, {"id": "x"}]"#).unwrap();
+        assert_eq!(names.len(), 1);
+        assert_eq!(names.get("7"), Some("Pump"));
+
+        assert!(FriendlyNames::parse_json("42").is_err());
+    }
+
+    #[test]
+    fn test_label_topic() {
+        let names = FriendlyNames::parse_csv("dev-0042,Boiler room");
+        assert_eq!(
+            names.label_topic("sites/dev-0042/power", '/', false),
+            "sites/dev-0042 (Boiler room)/power"
+        );
+        assert_eq!(
+            names.label_topic("sites/dev-0042/power", '/', true),
+            "sites/Boiler room/power"
+        );
+        assert_eq!(names.label_topic("sites/other", '/', true), "sites/other");
+    }
+}
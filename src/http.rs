@@ -0,0 +1,202 @@
+//! Just enough plain-text HTTP/1.1 for mqtop's outgoing requests: pushing
+//! metrics, opening the metric WebSocket and fetching friendly names.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Timeout for connecting and for each HTTP exchange
+pub(crate) const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Scheme {
+    Http,
+    Ws,
+}
+
+/// A plain-text http:// or ws:// endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Endpoint {
+    pub(crate) scheme: Scheme,
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) path: String,
+}
+
+impl Endpoint {
+    pub(crate) fn parse(url: &str) -> Result<Self> {
+        let (scheme, rest) = if let Some(rest) = url.strip_prefix("http://") {
+            (Scheme::Http, rest)
+        } else if let Some(rest) = url.strip_prefix("ws://") {
+            (Scheme::Ws, rest)
+        } else if url.starts_with("https://") || url.starts_with("wss://") {
+            bail!("TLS endpoints are not supported yet, use http:// or ws://");
+        } else {
+            bail!("URL must start with http:// or ws://");
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], &rest[idx..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().context("Invalid port in URL")?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            bail!("URL has no host");
+        }
+
+        Ok(Self {
+            scheme,
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    pub(crate) fn host_header(&self) -> String {
+        if self.port == 80 {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    pub(crate) async fn connect(&self) -> Result<TcpStream> {
+        let addr = (self.host.as_str(), self.port);
+        tokio::time::timeout(IO_TIMEOUT, TcpStream::connect(addr))
+            .await
+            .context("Connect timed out")?
+            .with_context(|| format!("Failed to connect to {}", self.host_header()))
+    }
+}
+
+/// GET an http:// URL and return the response body. Asks for HTTP/1.0 so the
+/// body arrives whole, without chunked encoding.
+pub(crate) async fn get(url: &str) -> Result<String> {
+    let endpoint = Endpoint::parse(url)?;
+    if endpoint.scheme != Scheme::Http {
+        bail!("Expected an http:// URL");
+    }
+    let mut stream = endpoint.connect().await?;
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\n\r\n",
+        endpoint.path,
+        endpoint.host_header()
+    );
+
+    let mut response = Vec::new();
+    tokio::time::timeout(IO_TIMEOUT, async {
+        stream.write_all(request.as_bytes()).await?;
+        stream.read_to_end(&mut response).await
+    })
+    .await
+    .context("Request timed out")??;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .context("Malformed HTTP response")?;
+    let status: u16 = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .context("Malformed HTTP response")?;
+    if !(200..=299).contains(&status) {
+        bail!("HTTP {}", status);
+    }
+    Ok(body.to_string())
+}
+
+/// Status and headers of an HTTP response, plus whatever arrived after them
+pub(crate) struct ResponseHead {
+    pub(crate) status: u16,
+    headers: Vec<(String, String)>,
+    pub(crate) rest: Vec<u8>,
+}
+
+impl ResponseHead {
+    fn parse(head: &str, rest: Vec<u8>) -> Result<Self> {
+        let mut lines = head.split("\r\n");
+        let status = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .context("Malformed HTTP response")?;
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        Ok(Self {
+            status,
+            headers,
+            rest,
+        })
+    }
+
+    /// Header names are case-insensitive
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read up to the end of the response head, keeping any bytes after it
+pub(crate) async fn read_response_head(stream: &mut TcpStream) -> Result<ResponseHead> {
+    let mut received = Vec::new();
+    let mut buf = [0u8; 512];
+    let end = loop {
+        if let Some(pos) = received.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            bail!("Connection closed before the response head");
+        }
+        received.extend_from_slice(&buf[..n]);
+    };
+    let rest = received.split_off(end + 4);
+    ResponseHead::parse(&String::from_utf8_lossy(&received[..end]), rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_parse() {
+        let endpoint = Endpoint::parse("http://grafana:3000/api/live/push/mqtop").unwrap();
+        assert_eq!(endpoint.scheme, Scheme::Http);
+        assert_eq!(endpoint.host, "grafana");
+        assert_eq!(endpoint.port, 3000);
+        assert_eq!(endpoint.path, "/api/live/push/mqtop");
+
+        let endpoint = Endpoint::parse("ws://localhost").unwrap();
+        assert_eq!(endpoint.port, 80);
+        assert_eq!(endpoint.path, "/");
+
+        assert!(Endpoint::parse("https://grafana/api").is_err());
+        assert!(Endpoint::parse("grafana:3000").is_err());
+    }
+
+    #[test]
+    fn test_response_head() {
+        let head = ResponseHead::parse(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+             sec-websocket-accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=",
+            vec![0x89, 0x00],
+        )
+        .unwrap();
+        assert_eq!(head.status, 101);
+        assert_eq!(
+            head.header("Sec-WebSocket-Accept"),
+            Some("s3pPLMBiTxaQ9kYGzzhZRbK+xOo=")
+        );
+        assert_eq!(head.rest, vec![0x89, 0x00]);
+    }
+}
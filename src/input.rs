@@ -0,0 +1,415 @@
+use std::collections::VecDeque;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::text;
+
+/// Entries kept per history list
+const HISTORY_LEN: usize = 50;
+
+/// Cursor and selection for an editable text field.
+///
+/// The text itself stays with the form that owns it; every field shares the
+/// same editing keys:
+/// - `←` `→` `Home` `End` move, with `Shift` to select and `Ctrl`/`Alt` to jump words
+/// - `Ctrl+W` / `Alt+Backspace` delete the word before the cursor, `Ctrl+Delete` the next one
+/// - `Ctrl+U` deletes to the start, `Ctrl+A` selects everything
+/// - `Ctrl+C` / `Ctrl+X` copy / cut the selection
+///
+/// Pasting (`Ctrl+V` or the terminal's bracketed paste) goes through [`InputCursor::paste`]
+/// since only the owning form knows whether a field takes multiple lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputCursor {
+    /// Byte offset, on a grapheme boundary
+    pos: usize,
+    /// Other end of the selection, when one is active
+    anchor: Option<usize>,
+}
+
+impl InputCursor {
+    pub fn at(pos: usize) -> Self {
+        Self { pos, anchor: None }
+    }
+
+    /// Cursor after the last character of `value`
+    pub fn end_of(value: &str) -> Self {
+        Self::at(value.len())
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Selected byte range within `value`, if any
+    pub fn selection(&self, value: &str) -> Option<(usize, usize)> {
+        let anchor = text::clamp_cursor(value, self.anchor?);
+        let pos = text::clamp_cursor(value, self.pos);
+        (anchor != pos).then(|| (anchor.min(pos), anchor.max(pos)))
+    }
+
+    /// Apply an editing key to `value`. Returns false for keys the field
+    /// doesn't use, so the caller can handle them.
+    pub fn handle_key(
+        &mut self,
+        value: &mut String,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> bool {
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        let alt = modifiers.contains(KeyModifiers::ALT);
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+        self.pos = text::clamp_cursor(value, self.pos);
+
+        match code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Home | KeyCode::End => {
+                let target = match code {
+                    KeyCode::Left if ctrl || alt => word_start_before(value, self.pos),
+                    KeyCode::Left => match self.selection(value) {
+                        Some((start, _)) if !shift => start,
+                        _ => text::prev_boundary(value, self.pos),
+                    },
+                    KeyCode::Right if ctrl || alt => word_end_after(value, self.pos),
+                    KeyCode::Right => match self.selection(value) {
+                        Some((_, end)) if !shift => end,
+                        _ => text::next_boundary(value, self.pos),
+                    },
+                    KeyCode::Home => 0,
+                    _ => value.len(),
+                };
+                self.move_to(target, shift);
+            }
+            KeyCode::Backspace if ctrl || alt => {
+                self.delete_to(value, word_start_before(value, self.pos))
+            }
+            KeyCode::Delete if ctrl || alt => {
+                self.delete_to(value, word_end_after(value, self.pos))
+            }
+            KeyCode::Backspace => {
+                if !self.delete_selection(value) {
+                    self.pos = text::remove_before(value, self.pos);
+                }
+            }
+            KeyCode::Delete => {
+                if !self.delete_selection(value) {
+                    text::remove_at(value, self.pos);
+                }
+            }
+            KeyCode::Char('w') if ctrl => self.delete_to(value, word_start_before(value, self.pos)),
+            KeyCode::Char('u') if ctrl => self.delete_to(value, 0),
+            KeyCode::Char('a') if ctrl => {
+                self.anchor = Some(0);
+                self.pos = value.len();
+            }
+            KeyCode::Char('c') if ctrl => {
+                if let Some((start, end)) = self.selection(value) {
+                    copy_to_clipboard(&value[start..end]);
+                }
+            }
+            KeyCode::Char('x') if ctrl => {
+                if let Some((start, end)) = self.selection(value) {
+                    copy_to_clipboard(&value[start..end]);
+                    self.delete_selection(value);
+                }
+            }
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => {
+                self.delete_selection(value);
+                self.pos = text::insert_char(value, self.pos, c);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Replace the selection (if any) with pasted text, leaving the cursor
+    /// after it. Line endings become `\n`, or spaces unless `multiline`.
+    pub fn paste(&mut self, value: &mut String, pasted: &str, multiline: bool) {
+        self.delete_selection(value);
+        let pasted = pasted.replace("\r\n", "\n").replace('\r', "\n");
+        let s = if multiline {
+            pasted
+        } else {
+            pasted.trim_end_matches('\n').replace('\n', " ")
+        };
+        self.pos = text::clamp_cursor(value, self.pos);
+        value.insert_str(self.pos, &s);
+        self.pos += s.len();
+    }
+
+    fn move_to(&mut self, target: usize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.pos);
+        } else {
+            self.anchor = None;
+        }
+        self.pos = target;
+    }
+
+    /// Delete the selection; false when nothing was selected
+    fn delete_selection(&mut self, value: &mut String) -> bool {
+        let selection = self.selection(value);
+        self.anchor = None;
+        match selection {
+            Some((start, end)) => {
+                value.replace_range(start..end, "");
+                self.pos = start;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Delete from the cursor to `target`, or the selection if there is one
+    fn delete_to(&mut self, value: &mut String, target: usize) {
+        if self.delete_selection(value) {
+            return;
+        }
+        let (start, end) = (self.pos.min(target), self.pos.max(target));
+        value.replace_range(start..end, "");
+        self.pos = start;
+    }
+}
+
+/// Recently entered values for an input, browsed with Up/Down like shell history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct InputHistory<T> {
+    /// Oldest first
+    entries: VecDeque<T>,
+    /// Entry currently shown while browsing
+    #[serde(skip)]
+    browsing: Option<usize>,
+    /// What was typed before browsing started, restored past the newest entry
+    #[serde(skip)]
+    draft: Option<T>,
+}
+
+impl<T> Default for InputHistory<T> {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            browsing: None,
+            draft: None,
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> InputHistory<T> {
+    /// Remember a submitted value; repeats move to the front
+    pub fn push(&mut self, entry: T) {
+        self.entries.retain(|existing| *existing != entry);
+        if self.entries.len() >= HISTORY_LEN {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+        self.reset();
+    }
+
+    /// Previous entry (Up); `current` is kept as the draft on the first step
+    pub fn older(&mut self, current: &T) -> Option<T> {
+        let index = match self.browsing {
+            Some(0) => return None,
+            Some(index) => index - 1,
+            None => {
+                self.draft = Some(current.clone());
+                self.entries.len().checked_sub(1)?
+            }
+        };
+        self.browsing = Some(index);
+        self.entries.get(index).cloned()
+    }
+
+    /// Next entry (Down), or the draft once past the newest
+    pub fn newer(&mut self) -> Option<T> {
+        let index = self.browsing? + 1;
+        if index < self.entries.len() {
+            self.browsing = Some(index);
+            self.entries.get(index).cloned()
+        } else {
+            self.browsing = None;
+            self.draft.take()
+        }
+    }
+
+    /// Stop browsing, e.g. when the input is opened again
+    pub fn reset(&mut self) {
+        self.browsing = None;
+        self.draft = None;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Start of the word before `pos`, skipping separators such as `/` and spaces
+fn word_start_before(value: &str, pos: usize) -> usize {
+    let mut start = pos;
+    let mut in_word = false;
+    for (idx, grapheme) in value[..pos].grapheme_indices(true).rev() {
+        if is_word(grapheme) {
+            in_word = true;
+        } else if in_word {
+            break;
+        }
+        start = idx;
+    }
+    start
+}
+
+/// End of the word after `pos`, skipping separators such as `/` and spaces
+fn word_end_after(value: &str, pos: usize) -> usize {
+    let mut end = pos;
+    let mut in_word = false;
+    for (idx, grapheme) in value[pos..].grapheme_indices(true) {
+        if is_word(grapheme) {
+            in_word = true;
+        } else if in_word {
+            break;
+        }
+        end = pos + idx + grapheme.len();
+    }
+    end
+}
+
+fn copy_to_clipboard(s: &str) {
+    if let Ok(mut clipboard) = arboard::Clipboard::new() {
+        let _ = clipboard.set_text(s.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(cursor: &mut InputCursor, value: &mut String, code: KeyCode, modifiers: KeyModifiers) {
+        assert!(cursor.handle_key(value, code, modifiers));
+    }
+
+    #[test]
+    fn test_word_motion_and_delete() {
+        let mut value = String::from("sensors/kök 1/temp");
+        let mut cursor = InputCursor::end_of(&value);
+
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Left,
+            KeyModifiers::CONTROL,
+        );
+        assert_eq!(&value[cursor.pos()..], "temp");
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Left,
+            KeyModifiers::CONTROL,
+        );
+        assert_eq!(&value[cursor.pos()..], "1/temp");
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Char('w'),
+            KeyModifiers::CONTROL,
+        );
+        assert_eq!(value, "sensors/1/temp");
+
+        press(&mut cursor, &mut value, KeyCode::Right, KeyModifiers::ALT);
+        assert_eq!(&value[cursor.pos()..], "/temp");
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Delete,
+            KeyModifiers::CONTROL,
+        );
+        assert_eq!(value, "sensors/1");
+    }
+
+    #[test]
+    fn test_selection_replaced_by_typing() {
+        let mut value = String::from("home/ute");
+        let mut cursor = InputCursor::end_of(&value);
+
+        press(&mut cursor, &mut value, KeyCode::Left, KeyModifiers::SHIFT);
+        press(&mut cursor, &mut value, KeyCode::Left, KeyModifiers::SHIFT);
+        press(&mut cursor, &mut value, KeyCode::Left, KeyModifiers::SHIFT);
+        assert_eq!(cursor.selection(&value), Some((5, 8)));
+
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Char('i'),
+            KeyModifiers::NONE,
+        );
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Char('n'),
+            KeyModifiers::NONE,
+        );
+        assert_eq!(value, "home/in");
+        assert_eq!(cursor.selection(&value), None);
+
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Char('a'),
+            KeyModifiers::CONTROL,
+        );
+        press(
+            &mut cursor,
+            &mut value,
+            KeyCode::Backspace,
+            KeyModifiers::NONE,
+        );
+        assert!(value.is_empty());
+
+        // Keys the field doesn't use are left to the caller
+        assert!(!cursor.handle_key(&mut value, KeyCode::Enter, KeyModifiers::NONE));
+        assert!(!cursor.handle_key(&mut value, KeyCode::Char('s'), KeyModifiers::CONTROL));
+    }
+
+    #[test]
+    fn test_paste() {
+        let mut value = String::from("ab");
+        let mut cursor = InputCursor::at(1);
+        cursor.paste(&mut value, "x\r\ny\n", false);
+        assert_eq!(value, "ax yb");
+        assert_eq!(cursor.pos(), 4);
+
+        // Payload fields keep the lines, with normalised line endings
+        let mut payload = String::new();
+        let mut cursor = InputCursor::default();
+        cursor.paste(&mut payload, "{\r\n  \"on\": true\r\n}", true);
+        assert_eq!(payload, "{\n  \"on\": true\n}");
+        assert_eq!(cursor, InputCursor::end_of(&payload));
+    }
+
+    #[test]
+    fn test_history_browsing() {
+        let mut history = InputHistory::default();
+        history.push("a/#".to_string());
+        history.push("b/#".to_string());
+        history.push("a/#".to_string());
+
+        let draft = "c/".to_string();
+        assert_eq!(history.older(&draft).as_deref(), Some("a/#"));
+        assert_eq!(history.older(&draft).as_deref(), Some("b/#"));
+        assert_eq!(history.older(&draft), None);
+        assert_eq!(history.newer().as_deref(), Some("a/#"));
+        // Past the newest entry the draft comes back
+        assert_eq!(history.newer().as_deref(), Some("c/"));
+        assert_eq!(history.newer(), None);
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert_eq!(json, r#"{"entries":["b/#","a/#"]}"#);
+    }
+}
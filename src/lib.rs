@@ -0,0 +1,31 @@
+//! mqtop's UI and frontend modules, shared by the `mqtop` binary, the benchmarks
+//! and the integration tests. The engine (clients, state) lives in `mqtop-core`.
+
+pub mod app;
+pub mod asyncapi;
+pub mod away;
+pub mod broker;
+pub mod config;
+pub mod config_crypto;
+pub mod content_type;
+pub mod demo;
+pub mod friendly_names;
+pub mod http;
+pub mod input;
+pub mod lint;
+pub mod logging;
+pub mod metric_sink;
+pub mod paths;
+pub mod persistence;
+pub mod pipe;
+pub mod process_stats;
+pub mod report;
+pub mod scenario;
+pub mod schedule;
+pub mod session;
+pub mod share;
+pub mod text;
+pub mod ui;
+pub mod web;
+
+pub use mqtop_core::{mqtt, nats, numbers, state};
@@ -0,0 +1,288 @@
+use chrono::{DateTime, Utc};
+
+use crate::mqtt::{MqttMessage, LARGE_PAYLOAD_BYTES};
+use crate::state::schema_tracker::{FieldType, Schema};
+
+/// Payloads longer than this (in lines) are not diffed
+const MAX_DIFF_LINES: usize = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Dry-run result for the publish dialog: lint findings plus a diff against
+/// the topic's last message
+#[derive(Debug, Clone)]
+pub struct PublishPreview {
+    pub issues: Vec<LintIssue>,
+    /// Line diff against the last message, `None` when the topic has none
+    pub diff: Option<Vec<DiffLine>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    pub scroll: usize,
+}
+
+impl PublishPreview {
+    pub fn build(
+        payload: &str,
+        retain: bool,
+        last: Option<&MqttMessage>,
+        schema: Option<&Schema>,
+        max_bytes: Option<usize>,
+    ) -> Self {
+        let mut issues = lint_payload(payload, schema, max_bytes);
+        if retain && payload.is_empty() {
+            issues.push(LintIssue::new(
+                Severity::Info,
+                "Empty retained payload clears the topic's retained message",
+            ));
+        }
+        issues.sort_by_key(|issue| issue.severity);
+
+        let diff = last.map(|message| {
+            let previous = String::from_utf8_lossy(&message.payload);
+            diff_lines(&normalize(&previous), &normalize(payload))
+        });
+        Self {
+            issues,
+            diff,
+            last_timestamp: last.map(|message| message.timestamp),
+            scroll: 0,
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error)
+    }
+}
+
+/// Check a payload before it is sent: JSON syntax, fields and types against
+/// the topic's learned schema, and size
+pub fn lint_payload(
+    payload: &str,
+    schema: Option<&Schema>,
+    max_bytes: Option<usize>,
+) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    let size = payload.len();
+    match max_bytes {
+        Some(max) if size > max => issues.push(LintIssue::new(
+            Severity::Error,
+            format!(
+                "{} bytes exceeds the broker's max packet size of {}",
+                size, max
+            ),
+        )),
+        _ if size > LARGE_PAYLOAD_BYTES => issues.push(LintIssue::new(
+            Severity::Warning,
+            format!("Large payload: {} KB", size / 1024),
+        )),
+        _ => {}
+    }
+
+    let trimmed = payload.trim_start();
+    let expects_json = trimmed.starts_with(['{', '[']) || schema.is_some();
+    if !expects_json {
+        return issues;
+    }
+
+    let value = match serde_json::from_str::<serde_json::Value>(payload) {
+        Ok(value) => value,
+        Err(err) => {
+            let message = if trimmed.starts_with(['{', '[']) {
+                format!(
+                    "Invalid JSON at line {} column {}: {}",
+                    err.line(),
+                    err.column(),
+                    err
+                )
+            } else {
+                "Topic carries JSON but the payload is not JSON".to_string()
+            };
+            issues.push(LintIssue::new(Severity::Error, message));
+            return issues;
+        }
+    };
+
+    if let Some(learned) = schema {
+        let sent = Schema::from_json(&value);
+        let mut fields: Vec<_> = sent.fields.iter().collect();
+        fields.sort_by_key(|(path, _)| *path);
+        for (path, field_type) in fields {
+            match learned.fields.get(path) {
+                None => issues.push(LintIssue::new(
+                    Severity::Warning,
+                    format!("Field '{}' has not been seen on this topic", path),
+                )),
+                Some(expected)
+                    if expected != field_type
+                        && *expected != FieldType::Null
+                        && *field_type != FieldType::Null =>
+                {
+                    issues.push(LintIssue::new(
+                        Severity::Warning,
+                        format!(
+                            "Field '{}' is {}, topic sends {}",
+                            path, field_type, expected
+                        ),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    issues
+}
+
+/// Pretty-print JSON so formatting alone doesn't show up in the diff
+fn normalize(payload: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(payload)
+        .ok()
+        .filter(|value| value.is_object() || value.is_array())
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| payload.to_string())
+}
+
+/// Line diff (longest common subsequence) from `old` to `new`
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len() > MAX_DIFF_LINES || new.len() > MAX_DIFF_LINES {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string()))
+            .chain(new.iter().map(|line| DiffLine::Added(line.to_string())))
+            .collect();
+    }
+
+    // lcs[i][j]: common lines between old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(json: &str) -> Schema {
+        Schema::from_json(&serde_json::from_str(json).unwrap())
+    }
+
+    fn messages(issues: &[LintIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_json_and_schema() {
+        assert!(lint_payload("on", None, None).is_empty());
+
+        let issues = lint_payload("{\"on\": tru}", None, None);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert!(issues[0].message.starts_with("Invalid JSON at line 1"));
+
+        let learned = schema(r#"{"state": "on", "brightness": 80}"#);
+        let issues = lint_payload(r#"{"state": 1, "colour": "red"}"#, Some(&learned), None);
+        assert_eq!(
+            messages(&issues),
+            vec![
+                "Field 'colour' has not been seen on this topic",
+                "Field 'state' is number, topic sends string",
+            ]
+        );
+        // A partial command is fine
+        assert!(lint_payload(r#"{"state": "off"}"#, Some(&learned), None).is_empty());
+
+        let issues = lint_payload("off", Some(&learned), None);
+        assert_eq!(
+            messages(&issues),
+            vec!["Topic carries JSON but the payload is not JSON"]
+        );
+    }
+
+    #[test]
+    fn test_lint_size() {
+        let issues = lint_payload("0123456789", None, Some(8));
+        assert_eq!(issues[0].severity, Severity::Error);
+
+        let large = "x".repeat(LARGE_PAYLOAD_BYTES + 1);
+        assert_eq!(
+            lint_payload(&large, None, None)[0].severity,
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_preview_diff_ignores_formatting() {
+        let last = MqttMessage::new(
+            "lamp/set".to_string(),
+            br#"{"state":"on","brightness":80}"#.to_vec(),
+            0,
+            false,
+        );
+        let payload = "{\n  \"brightness\": 40,\n  \"state\": \"on\"\n}";
+        let preview = PublishPreview::build(payload, false, Some(&last), None, None);
+        assert_eq!(
+            preview.diff.clone().unwrap(),
+            vec![
+                DiffLine::Same("{".to_string()),
+                DiffLine::Removed("  \"brightness\": 80,".to_string()),
+                DiffLine::Added("  \"brightness\": 40,".to_string()),
+                DiffLine::Same("  \"state\": \"on\"".to_string()),
+                DiffLine::Same("}".to_string()),
+            ]
+        );
+        assert!(!preview.has_errors());
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+use crate::config::LoggingConfig;
+
+/// Log lines kept in memory for the in-app log view
+const LOG_BUFFER_LINES: usize = 1000;
+
+/// Shared ring buffer of formatted log lines, read by the log view
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(LOG_BUFFER_LINES)
+    }
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn push(&self, line: String) {
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() >= self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    /// Snapshot of all buffered lines (oldest first)
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.lock().map(|lines| lines.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogBuffer {
+    type Writer = LogBufferWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogBufferWriter {
+            buffer: self.clone(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+/// Collects one formatted event and pushes its lines into the buffer on drop
+pub struct LogBufferWriter {
+    buffer: LogBuffer,
+    pending: Vec<u8>,
+}
+
+impl Write for LogBufferWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        for line in text.lines().filter(|l| !l.is_empty()) {
+            self.buffer.push(line.to_string());
+        }
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl Drop for LogBufferWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Log file that rotates to `<name>.1`, `<name>.2`, ... once it exceeds `max_bytes`
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_bytes: u64, max_files: usize) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create log directory {:?}", parent))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {:?}", path))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(self.rotated_path(self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.max_bytes > 0 && self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Build `EnvFilter` directives from config, e.g. `info,rumqttc=debug`
+pub fn filter_directives(config: &LoggingConfig, debug: bool) -> String {
+    let level = if debug {
+        "debug"
+    } else {
+        config.level.as_str()
+    };
+    let mut directives = vec![level.to_string()];
+    directives.extend(
+        config
+            .modules
+            .iter()
+            .map(|(module, level)| format!("{}={}", module, level)),
+    );
+    directives.join(",")
+}
+
+/// Install the global subscriber. Logs always go to the in-memory buffer and,
+/// when configured (or with `--debug`), to a rotating file. `RUST_LOG` overrides
+/// the configured levels.
+pub fn init(config: &LoggingConfig, debug: bool, debug_file: &Path) -> Result<LogBuffer> {
+    let filter = match std::env::var("RUST_LOG") {
+        Ok(directives) if !directives.is_empty() => EnvFilter::try_new(directives),
+        _ => EnvFilter::try_new(filter_directives(config, debug)),
+    }
+    .context("Invalid log level in [logging] config")?;
+
+    let buffer = LogBuffer::default();
+    let buffer_layer = fmt::layer()
+        .with_ansi(false)
+        .without_time()
+        .with_writer(buffer.clone());
+
+    let file_path = config
+        .file
+        .clone()
+        .or_else(|| debug.then(|| debug_file.to_path_buf()));
+    let file_layer = match file_path {
+        Some(path) => {
+            let file = RotatingFile::open(
+                &path,
+                config.max_size_mb.saturating_mul(1024 * 1024),
+                config.max_files,
+            )?;
+            Some(fmt::layer().with_ansi(false).with_writer(Mutex::new(file)))
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(buffer_layer)
+        .with(file_layer)
+        .try_init()
+        .context("Failed to set tracing subscriber")?;
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_log_buffer_capacity() {
+        let buffer = LogBuffer::new(2);
+        buffer.push("one".to_string());
+        buffer.push("two".to_string());
+        buffer.push("three".to_string());
+
+        assert_eq!(buffer.lines(), vec!["two", "three"]);
+    }
+
+    #[test]
+    fn test_log_buffer_writer_splits_lines() {
+        let buffer = LogBuffer::new(10);
+        {
+            let mut writer = buffer.make_writer();
+            writer.write_all(b" INFO first\n").unwrap();
+            writer.write_all(b" WARN second\n").unwrap();
+        }
+
+        assert_eq!(buffer.lines(), vec![" INFO first", " WARN second"]);
+    }
+
+    #[test]
+    fn test_filter_directives() {
+        let mut config = LoggingConfig::default();
+        assert_eq!(filter_directives(&config, false), "info");
+        assert_eq!(filter_directives(&config, true), "debug");
+
+        config
+            .modules
+            .insert("rumqttc".to_string(), "trace".to_string());
+        config
+            .modules
+            .insert("mqtop::mqtt".to_string(), "debug".to_string());
+        assert_eq!(
+            filter_directives(&config, false),
+            "info,mqtop::mqtt=debug,rumqttc=trace"
+        );
+        assert!(EnvFilter::try_new(filter_directives(&config, false)).is_ok());
+    }
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mqtop.log");
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+
+        file.write_all(b"first-123\n").unwrap();
+        file.write_all(b"second-12\n").unwrap();
+        file.write_all(b"third-123\n").unwrap();
+        file.write_all(b"fourth-12\n").unwrap();
+        file.flush().unwrap();
+
+        let read = |p: PathBuf| std::fs::read_to_string(p).unwrap();
+        assert_eq!(read(path.clone()), "fourth-12\n");
+        assert_eq!(read(dir.path().join("mqtop.log.1")), "third-123\n");
+        assert_eq!(read(dir.path().join("mqtop.log.2")), "second-12\n");
+        assert!(!dir.path().join("mqtop.log.3").exists());
+    }
+}
@@ -1,36 +1,50 @@
-mod app;
-mod broker;
-mod config;
-mod mqtt;
-mod nats;
-mod persistence;
-mod state;
-mod ui;
-
-use std::io::{self, stdin, Write};
-use std::path::PathBuf;
-use std::time::Duration;
+use std::io::{self, stdin, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
 use tokio::sync::mpsc;
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::info;
 
-use app::App;
+use mqtop::{
+    app, broker, config, config_crypto, demo, friendly_names, lint, logging, metric_sink, mqtt,
+    nats, paths, pipe, report, session, share, ui, web,
+};
+
+use app::{App, InputMode};
 use broker::BrokerKind;
-use config::{Config, MqttConfig, MqttServerConfig, NatsConfig, CONFIG_BACKUP_LIMIT};
+use config::{
+    ClientPreset, Config, ConfigSection, MqttConfig, MqttServerConfig, NatsConfig,
+    CONFIG_BACKUP_LIMIT,
+};
+use config_crypto::ConfigKey;
+use friendly_names::FriendlyNames;
+use metric_sink::MetricSink;
 use mqtt::{MqttClient, MqttEvent};
 use nats::NatsClient;
+use paths::Paths;
+use pipe::TopicPipe;
+use report::{Report, ReportBuilder, ReportFormat};
+use session::SessionSnapshot;
+use share::ShareServer;
+use web::WebServer;
 
 const DEFAULT_WIZARD_PORT: u16 = 1883;
 const DEFAULT_WIZARD_KEEP_ALIVE: u64 = 30;
+/// Unchanged lines shown around each change in `mqtop config diff`
+const CONFIG_DIFF_CONTEXT: usize = 3;
+/// Slowest redraw in high load mode
+const HIGH_LOAD_RENDER_INTERVAL: Duration = Duration::from_millis(500);
 
 fn list_backups(config_path: &PathBuf) -> Result<()> {
     let backups = Config::list_backups(config_path)?;
@@ -46,6 +60,166 @@ fn list_backups(config_path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Key for an encrypted config: the identity file when given, else a passphrase prompt
+fn config_key(config_path: &Path, key_file: Option<&Path>) -> Result<ConfigKey> {
+    match key_file {
+        Some(path) => ConfigKey::from_key_file(path),
+        None => ConfigKey::prompt(config_path),
+    }
+}
+
+fn encrypt_config(config_path: &PathBuf, key_file: Option<&Path>) -> Result<()> {
+    if Config::is_encrypted_file(config_path)? {
+        anyhow::bail!("{} is already encrypted", config_path.display());
+    }
+    let mut config = Config::load(config_path)?;
+    config.encryption = Some(match key_file {
+        Some(path) => ConfigKey::from_key_file(path)?,
+        None => ConfigKey::prompt_new()?,
+    });
+    // No backup here: it would be another plaintext copy of the credentials
+    config.save_to(config_path)?;
+    eprintln!("Encrypted {}", config_path.display());
+
+    if !Config::list_backups(config_path)?.is_empty() {
+        eprintln!(
+            "Note: existing backups in {} are not encrypted",
+            Config::backup_dir_for(config_path).display()
+        );
+    }
+    Ok(())
+}
+
+fn decrypt_config(config_path: &PathBuf, key_file: Option<&Path>) -> Result<()> {
+    if !Config::is_encrypted_file(config_path)? {
+        anyhow::bail!("{} is not encrypted", config_path.display());
+    }
+    let mut config = Config::load_encrypted(config_path, config_key(config_path, key_file)?)?;
+    config.encryption = None;
+    config.save_to(config_path)?;
+    eprintln!("Decrypted {}", config_path.display());
+    Ok(())
+}
+
+/// Key for the config and a backup, asked for only when either is encrypted
+fn backup_key(
+    config_path: &Path,
+    backup: &Path,
+    key_file: Option<&Path>,
+) -> Result<Option<ConfigKey>> {
+    let encrypted = Config::is_encrypted_file(config_path).unwrap_or(false)
+        || Config::is_encrypted_file(backup)?;
+    if encrypted {
+        Ok(Some(config_key(config_path, key_file)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn diff_config(config_path: &Path, index: usize, key_file: Option<&Path>) -> Result<()> {
+    let backup = Config::backup_path(config_path, index)?;
+    let key = backup_key(config_path, &backup, key_file)?;
+    let old = Config::read_text(&backup, key.as_ref())?;
+    let new = Config::read_text(config_path, key.as_ref())?;
+
+    let diff = lint::diff_lines(&old, &new);
+    if diff
+        .iter()
+        .all(|line| matches!(line, lint::DiffLine::Same(_)))
+    {
+        println!("No changes since backup #{}", index);
+        return Ok(());
+    }
+
+    let color = io::stdout().is_terminal();
+    let paint = |text: String, ansi: &str| {
+        if color {
+            format!("\x1b[{}m{}\x1b[0m", ansi, text)
+        } else {
+            text
+        }
+    };
+    println!(
+        "{}",
+        paint(format!("--- backup #{} ({})", index, backup.display()), "1")
+    );
+    println!("{}", paint(format!("+++ {}", config_path.display()), "1"));
+
+    // Only print unchanged lines near a change
+    let changed: Vec<usize> = diff
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, lint::DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    let near_change = |i: usize| {
+        changed
+            .iter()
+            .any(|&c| c.abs_diff(i) <= CONFIG_DIFF_CONTEXT)
+    };
+    let mut skipped = false;
+    for (i, line) in diff.into_iter().enumerate() {
+        match line {
+            lint::DiffLine::Same(_) if !near_change(i) => {
+                if !skipped {
+                    println!("{}", paint("  ...".to_string(), "90"));
+                    skipped = true;
+                }
+                continue;
+            }
+            lint::DiffLine::Same(text) => println!("  {}", text),
+            lint::DiffLine::Removed(text) => println!("{}", paint(format!("- {}", text), "31")),
+            lint::DiffLine::Added(text) => println!("{}", paint(format!("+ {}", text), "32")),
+        }
+        skipped = false;
+    }
+    Ok(())
+}
+
+fn restore_config(
+    config_path: &Path,
+    index: usize,
+    section: Option<ConfigSection>,
+    server: Option<&str>,
+    key_file: Option<&Path>,
+) -> Result<()> {
+    let backup_path = Config::backup_path(config_path, index)?;
+    if section.is_none() && server.is_none() {
+        Config::rollback_backup(config_path, index, CONFIG_BACKUP_LIMIT)?;
+        eprintln!("Restored config from backup #{}", index);
+        return Ok(());
+    }
+
+    let key = backup_key(config_path, &backup_path, key_file)?;
+    let mut config = match key.clone() {
+        Some(key) if Config::is_encrypted_file(config_path)? => {
+            Config::load_encrypted(config_path, key)?
+        }
+        _ => Config::load(config_path)?,
+    };
+    let backup = Config::parse(&Config::read_text(&backup_path, key.as_ref())?)
+        .with_context(|| format!("Failed to load backup #{}", index))?;
+
+    let restored = match (section, server) {
+        (Some(section), _) => {
+            config.restore_section(&backup, section);
+            match section {
+                ConfigSection::Mqtt => "[mqtt]".to_string(),
+                ConfigSection::Nats => "[nats]".to_string(),
+            }
+        }
+        (None, Some(name)) => {
+            config.restore_server(&backup, name)?;
+            format!("server '{}'", name)
+        }
+        (None, None) => unreachable!("whole restores return above"),
+    };
+    config.validate()?;
+    config.save_with_backup(config_path, CONFIG_BACKUP_LIMIT)?;
+    eprintln!("Restored {} from backup #{}", restored, index);
+    Ok(())
+}
+
 fn prompt_input(label: &str, default: Option<&str>) -> Result<String> {
     let mut input = String::new();
     match default {
@@ -86,6 +260,11 @@ fn create_default_config(config_path: &std::path::Path) -> Result<Config> {
         },
         nats: NatsConfig::default(),
         ui: config::UiConfig::default(),
+        logging: config::LoggingConfig::default(),
+        features: config::FeaturesConfig::default(),
+        metric_sink: None,
+        encryption: None,
+        connect_on_start: false,
     };
 
     // Create config directory if needed
@@ -156,6 +335,10 @@ fn run_config_wizard(config_path: &PathBuf) -> Result<Config> {
         lwt_payload: None,
         lwt_qos: 0,
         lwt_retain: false,
+        preset: ClientPreset::default(),
+        inflight: None,
+        max_packet_size: None,
+        request_channel_capacity: None,
     };
 
     let config = Config {
@@ -165,6 +348,11 @@ fn run_config_wizard(config_path: &PathBuf) -> Result<Config> {
         },
         nats: NatsConfig::default(),
         ui: config::UiConfig::default(),
+        logging: config::LoggingConfig::default(),
+        features: config::FeaturesConfig::default(),
+        metric_sink: None,
+        encryption: None,
+        connect_on_start: false,
     };
 
     config.save_with_backup(config_path, CONFIG_BACKUP_LIMIT)?;
@@ -174,9 +362,8 @@ fn run_config_wizard(config_path: &PathBuf) -> Result<Config> {
 
 async fn connect_mqtt(app: &App, mqtt_tx: mpsc::UnboundedSender<MqttEvent>) -> Result<MqttClient> {
     let server = app
-        .active_mqtt_server()
-        .context("Active MQTT server missing")?
-        .clone();
+        .session_mqtt_server()
+        .context("Active MQTT server missing")?;
     MqttClient::connect(server, mqtt_tx)
         .await
         .context("Failed to connect to MQTT broker")
@@ -219,9 +406,8 @@ async fn connect_client(
         BrokerKind::Mqtt => Ok(Client::Mqtt(connect_mqtt(app, tx).await?)),
         BrokerKind::Nats => {
             let server = app
-                .active_nats_server()
-                .context("Active NATS server missing")?
-                .clone();
+                .session_nats_server()
+                .context("Active NATS server missing")?;
             Ok(Client::Nats(
                 NatsClient::connect(server, tx)
                     .await
@@ -231,6 +417,61 @@ async fn connect_client(
     }
 }
 
+/// Connect to the active server and collect traffic for a report
+async fn observe_for_report(
+    config: Config,
+    config_path: PathBuf,
+    paths: &Paths,
+    duration: Duration,
+) -> Result<Report> {
+    let mut app = App::new(config, config_path, paths);
+    let kind = if app.active_mqtt_server().is_some() {
+        BrokerKind::Mqtt
+    } else if app.active_nats_server().is_some() {
+        BrokerKind::Nats
+    } else {
+        anyhow::bail!("No server configured - run mqtop --setup or add one in the Server Manager");
+    };
+    app.connected_broker_kind = kind;
+    let server = app.active_server_info().context("Active server missing")?;
+    let source = format!(
+        "{}:{} ({}:{})",
+        kind.label(),
+        server.name,
+        server.host,
+        server.port
+    );
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<MqttEvent>();
+    let client = connect_client(&app, kind, tx).await?;
+    eprintln!("Observing {} for {}s...", source, duration.as_secs());
+
+    let mut builder = ReportBuilder::new(kind.topic_separator());
+    let started = Instant::now();
+    let deadline = tokio::time::sleep(duration);
+    tokio::pin!(deadline);
+    let mut last_error = None;
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            event = rx.recv() => match event {
+                Some(MqttEvent::Message(msg)) => builder.observe(&msg),
+                Some(MqttEvent::Error(err)) if last_error.as_ref() != Some(&err) => {
+                    eprintln!("{}", err);
+                    last_error = Some(err);
+                }
+                Some(_) => {}
+                None => break,
+            },
+        }
+    }
+    if let Err(err) = client.disconnect().await {
+        tracing::warn!("Failed to disconnect client: {:?}", err);
+    }
+
+    Ok(builder.finish(source, started.elapsed().as_secs_f64()))
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "mqtop")]
 #[command(author = "Sourceful Energy")]
@@ -241,6 +482,14 @@ struct Args {
     #[arg(short, long)]
     config: Option<PathBuf>,
 
+    /// Use a separate set of servers, stars, metrics and bookmarks (e.g. work, home, lab)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Try mqtop against a built-in broker with simulated devices (no broker needed)
+    #[arg(long, conflicts_with_all = ["config", "profile", "setup"])]
+    demo: bool,
+
     /// MQTT broker host (overrides config)
     #[arg(long)]
     host: Option<String>,
@@ -273,56 +522,176 @@ struct Args {
     #[arg(long)]
     list_backups: bool,
 
+    /// age identity file (from age-keygen) for an encrypted config, instead of a passphrase
+    #[arg(long, value_name = "FILE")]
+    key_file: Option<PathBuf>,
+
+    /// Encrypt the config file with a passphrase, or to --key-file
+    #[arg(long, conflicts_with = "decrypt_config")]
+    encrypt_config: bool,
+
+    /// Decrypt the config file back to plain TOML
+    #[arg(long)]
+    decrypt_config: bool,
+
     /// Run interactive config wizard
     #[arg(long)]
     setup: bool,
 
-    /// Enable debug logging to file
+    /// Enable debug logging (also writes mqtop.log in the state dir unless [logging] file is set)
     #[arg(short, long)]
     debug: bool,
 
     /// Use TLS
     #[arg(long)]
     tls: bool,
+
+    /// Open a saved session snapshot instead of connecting
+    #[arg(long)]
+    session: Option<PathBuf>,
+
+    /// Share a read-only live view over TCP (e.g. 0.0.0.0:7070), watch with telnet or nc
+    #[arg(long, value_name = "ADDR")]
+    share: Option<String>,
+
+    /// Serve a read-only web dashboard on this local port (or ADDR:PORT, e.g. 0.0.0.0:8080 to expose it)
+    #[arg(long, value_name = "PORT")]
+    web: Option<String>,
+
+    /// Serve only the read-only JSON API on this local port (or ADDR:PORT), for scripts and CI
+    #[arg(long, value_name = "PORT", conflicts_with = "web")]
+    api: Option<String>,
+
+    /// Flag topics whose JSON schema differs from this baseline (overrides config)
+    #[arg(long, value_name = "FILE")]
+    schema_baseline: Option<PathBuf>,
+
+    /// Document topics from this AsyncAPI spec (YAML or JSON, overrides config)
+    #[arg(long, value_name = "FILE")]
+    asyncapi: Option<PathBuf>,
+
+    /// Show friendly names for device IDs from this CSV or JSON file (overrides config)
+    #[arg(long, value_name = "FILE")]
+    names: Option<PathBuf>,
+
+    /// Draw with ASCII characters only, for fonts without box and symbol glyphs
+    #[arg(long)]
+    ascii: bool,
+
+    /// Screen reader friendly output: text labels, fewer glyphs, a selection readout line
+    #[arg(long)]
+    screen_reader: bool,
+
+    /// Wall display mode: rotating pages, no selection highlights, reconnecting forever
+    #[arg(long)]
+    kiosk: bool,
+
+    /// Color palette, also switchable at runtime with C
+    #[arg(long, value_enum)]
+    palette: Option<config::Palette>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Observe traffic and print a report (topics, rates, payload sizes, schemas, devices)
+    Report {
+        /// Seconds to observe the active server
+        #[arg(long, default_value_t = 30)]
+        duration: u64,
+
+        /// Build the report from a saved session snapshot instead of connecting
+        #[arg(long)]
+        session: Option<PathBuf>,
+
+        /// Report format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+
+        /// Write the report to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Compare the config with a backup, or restore parts of one
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Show what changed between a backup (1 = newest) and the current config
+    Diff { index: usize },
+
+    /// Restore a backup (1 = newest), or only a section or server from it
+    Restore {
+        index: usize,
+
+        /// Only restore this section
+        #[arg(long, conflicts_with = "server")]
+        section: Option<ConfigSection>,
+
+        /// Only restore the MQTT or NATS server with this name
+        #[arg(long)]
+        server: Option<String>,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Set up logging
-    if args.debug {
-        let subscriber = FmtSubscriber::builder()
-            .with_max_level(Level::DEBUG)
-            .with_writer(|| {
-                std::fs::OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open("mqtop.log")
-                    .expect("Failed to open log file")
-            })
-            .finish();
-        tracing::subscriber::set_global_default(subscriber)
-            .context("Failed to set tracing subscriber")?;
-    }
-
     // Find and load config
-    let config_path = Config::find_config_path(args.config.as_deref());
+    let profile = if args.demo {
+        Some(demo::DEMO_PROFILE)
+    } else {
+        args.profile.as_deref()
+    };
+    let paths = Paths::new(profile)?;
+    let config_path = Config::find_config_path(args.config.as_deref(), &paths);
 
     if args.list_backups {
         list_backups(&config_path)?;
         return Ok(());
     }
 
+    if args.encrypt_config {
+        return encrypt_config(&config_path, args.key_file.as_deref());
+    }
+    if args.decrypt_config {
+        return decrypt_config(&config_path, args.key_file.as_deref());
+    }
+
+    if let Some(Command::Config(command)) = &args.command {
+        let key_file = args.key_file.as_deref();
+        return match command {
+            ConfigCommand::Diff { index } => diff_config(&config_path, *index, key_file),
+            ConfigCommand::Restore {
+                index,
+                section,
+                server,
+            } => restore_config(&config_path, *index, *section, server.as_deref(), key_file),
+        };
+    }
+
     if let Some(index) = args.rollback {
         Config::rollback_backup(&config_path, index, CONFIG_BACKUP_LIMIT)?;
         eprintln!("Rolled back config using backup #{}", index);
         return Ok(());
     }
 
-    let mut config = if args.setup {
+    let mut config = if args.demo {
+        let broker = demo::start().await?;
+        demo::config(broker.local_addr())?
+    } else if args.setup {
         // Explicit setup requested via --setup flag
         run_config_wizard(&config_path)?
+    } else if Config::is_encrypted_file(&config_path).unwrap_or(false) {
+        // A wrong key stops here instead of falling back to an empty config
+        let key = config_key(&config_path, args.key_file.as_deref())?;
+        Config::load_encrypted(&config_path, key)?
     } else if config_path.exists() {
         match Config::load(&config_path) {
             Ok(config) => config,
@@ -339,6 +708,12 @@ async fn main() -> Result<()> {
         create_default_config(&config_path)?
     };
 
+    // Set up logging (in-memory for the log view, plus file when configured or --debug)
+    let log_buffer = logging::init(&config.logging, args.debug, &paths.debug_log_file())?;
+    if let Err(err) = paths.migrate_user_data() {
+        tracing::warn!("User data not moved to {:?}: {:#}", paths.state_dir, err);
+    }
+
     // Check if we have servers configured
     let needs_server_setup = config.mqtt.servers.is_empty() && config.nats.servers.is_empty();
 
@@ -367,6 +742,36 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some(Command::Report {
+        duration,
+        session,
+        format,
+        output,
+    }) = args.command
+    {
+        let report = match session {
+            Some(path) => {
+                let path = paths.session_file(&path);
+                let snapshot = SessionSnapshot::load_from(&path)?;
+                Report::from_session(&snapshot, path.display().to_string())?
+            }
+            None => {
+                let duration = Duration::from_secs(duration);
+                observe_for_report(config, config_path, &paths, duration).await?
+            }
+        };
+        let text = report.render(format)?;
+        match output {
+            Some(path) => {
+                std::fs::write(&path, text)
+                    .with_context(|| format!("Failed to write report to {:?}", path))?;
+                eprintln!("Report written to {}", path.display());
+            }
+            None => print!("{}", text),
+        }
+        return Ok(());
+    }
+
     // Only save config if we have servers (avoid saving empty config)
     if !needs_server_setup {
         config
@@ -384,54 +789,262 @@ async fn main() -> Result<()> {
         info!("Starting mqtop - no servers configured");
     }
 
+    // Applied after saving so a one-off baseline isn't persisted
+    if let Some(path) = args.schema_baseline {
+        config.ui.schema_baseline = Some(path);
+    }
+    if let Some(path) = args.asyncapi {
+        config.ui.asyncapi = Some(path);
+    }
+    if let Some(path) = args.names {
+        config.ui.friendly_names = Some(path);
+    }
+    if args.ascii {
+        config.ui.ascii = true;
+    }
+    if args.screen_reader {
+        config.ui.screen_reader = true;
+    }
+    if args.kiosk {
+        config.ui.kiosk = true;
+    }
+    if let Some(palette) = args.palette {
+        config.ui.palette = palette;
+    }
+
+    let share = match args.share {
+        Some(ref addr) => {
+            let server = ShareServer::bind(addr).await?;
+            info!("Sharing read-only view on {}", server.local_addr());
+            Some(server)
+        }
+        None => None,
+    };
+    let web = match (&args.web, &args.api) {
+        (Some(value), _) => Some(WebServer::bind(&web::listen_addr(value), true).await?),
+        (None, Some(value)) => Some(WebServer::bind(&web::listen_addr(value), false).await?),
+        (None, None) => None,
+    };
+
     // Run the TUI application
-    run_app(config, config_path, needs_server_setup).await
+    run_app(
+        config,
+        config_path,
+        &paths,
+        args.session,
+        Viewers { share, web },
+        log_buffer,
+        args.debug,
+    )
+    .await
+}
+
+/// Read-only views of the session for people without the terminal
+struct Viewers {
+    share: Option<ShareServer>,
+    web: Option<WebServer>,
 }
 
-async fn run_app(config: Config, config_path: PathBuf, needs_server_setup: bool) -> Result<()> {
+async fn run_app(
+    config: Config,
+    config_path: PathBuf,
+    paths: &Paths,
+    session: Option<PathBuf>,
+    mut viewers: Viewers,
+    log_buffer: logging::LogBuffer,
+    debug: bool,
+) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create app state
-    let tick_rate = Duration::from_millis(config.ui.tick_rate_ms);
-    let mut app = App::new(config.clone(), config_path);
+    let input_poll = Duration::from_millis(config.ui.input_poll_ms);
+    let render_interval = Duration::from_millis(config.ui.render_interval_ms);
+    let maintenance_interval = Duration::from_millis(config.ui.maintenance_interval_ms);
+    let needs_server_setup = config.mqtt.servers.is_empty() && config.nats.servers.is_empty();
+    let mut app = App::new(config.clone(), config_path, paths);
+    app.log_buffer = log_buffer;
+    app.debug = debug;
+    if let Some(path) = config.ui.schema_baseline.clone() {
+        if let Err(e) = app.load_schema_baseline(&path) {
+            tracing::warn!("Schema baseline not loaded: {:#}", e);
+            app.set_status(&format!("Schema baseline not loaded: {:#}", e));
+        }
+    }
+    if let Some(path) = config.ui.asyncapi.clone() {
+        if let Err(e) = app.load_asyncapi(&path) {
+            tracing::warn!("AsyncAPI spec not loaded: {:#}", e);
+            app.set_status(&format!("AsyncAPI spec not loaded: {:#}", e));
+        }
+    }
+    if let Some(path) = config.ui.friendly_names.clone() {
+        if let Err(e) = app.load_friendly_names(&path) {
+            tracing::warn!("Friendly names not loaded: {:#}", e);
+            app.set_status(&format!("Friendly names not loaded: {:#}", e));
+        }
+    }
+    if let Some(url) = config.ui.friendly_names_url.clone() {
+        match FriendlyNames::fetch(&url).await {
+            Ok(names) => {
+                info!("Fetched {} friendly names from {}", names.len(), url);
+                app.friendly_names.extend(names);
+            }
+            Err(e) => {
+                tracing::warn!("Friendly names not fetched: {:#}", e);
+                app.set_status(&format!("Friendly names not fetched: {:#}", e));
+            }
+        }
+    }
+    if let Some(sink_config) = config.metric_sink.clone() {
+        match MetricSink::start(sink_config) {
+            Ok(sink) => app.metric_sink = Some(sink),
+            Err(e) => app.set_status(&format!("Metric sink disabled: {}", e)),
+        }
+    }
 
     // Create channel for broker events (MQTT/NATS)
     let (mqtt_tx, mut mqtt_rx) = mpsc::unbounded_channel::<MqttEvent>();
 
     // Never auto-connect - always start with Server Manager open
-    // User must explicitly select a server (Enter) to connect
+    // User must explicitly select a server (Enter) to connect (demo and kiosk
+    // modes excepted)
     let mut client: Option<Client> = None;
-    app.open_server_manager();
-    if needs_server_setup {
-        app.set_status("No servers configured - press 'a' to add one");
+    if config.connect_on_start {
+        app.pending_server_switch = Some(app::PendingServerSwitch {
+            kind: BrokerKind::Mqtt,
+            index: config.mqtt.active_index().unwrap_or(0),
+        });
+    } else if config.ui.kiosk && !needs_server_setup {
+        app.pending_server_switch = Some(if config.mqtt.servers.is_empty() {
+            app::PendingServerSwitch {
+                kind: BrokerKind::Nats,
+                index: config.nats.active_index().unwrap_or(0),
+            }
+        } else {
+            app::PendingServerSwitch {
+                kind: BrokerKind::Mqtt,
+                index: config.mqtt.active_index().unwrap_or(0),
+            }
+        });
+    } else if let Some(path) = session {
+        if let Err(err) = app.load_session(&path) {
+            app.open_server_manager();
+            app.set_status(&format!("Session load failed: {}", err));
+        }
     } else {
-        app.set_status("Select a server and press Enter to connect");
+        app.open_server_manager();
+        if needs_server_setup {
+            app.set_status("No servers configured - press 'a' to add one");
+        } else {
+            app.set_status("Select a server and press Enter to connect");
+        }
     }
 
-    // Main loop
+    app.web_label = viewers.web.as_ref().map(WebServer::label);
+
+    // Main loop: input, rendering and maintenance each run on their own clock,
+    // so busy sessions can redraw less often without delaying keystrokes
+    let mut next_render = Instant::now();
+    let mut next_maintenance = Instant::now();
+    let mut redraw_now = false;
     loop {
         // Draw UI
-        terminal.draw(|f| ui::render(f, &mut app))?;
+        if redraw_now || Instant::now() >= next_render {
+            let draw_started = Instant::now();
+            let frame = terminal.draw(|f| ui::render(f, &mut app))?;
+            app.frame_time = draw_started.elapsed();
+            next_render = draw_started
+                + if app.high_load {
+                    render_interval.max(HIGH_LOAD_RENDER_INTERVAL)
+                } else {
+                    render_interval
+                };
+            redraw_now = false;
+
+            // Mirror the frame to read-only viewers
+            if let Some(ref share) = viewers.share {
+                let viewers = share.viewer_count();
+                if viewers > 0 {
+                    share.publish(ui::buffer_to_ansi(frame.buffer, frame.area));
+                }
+                app.share_viewers = Some(viewers);
+            }
 
-        // Handle events with timeout
-        let timeout = tick_rate;
+            if let Some(scope) = app.pending_screenshot.take() {
+                let area = match scope {
+                    app::ScreenshotScope::Screen => frame.area,
+                    app::ScreenshotScope::FocusedPanel => {
+                        app.focused_panel_area.unwrap_or(frame.area)
+                    }
+                };
+                let text = ui::buffer_to_text(frame.buffer, area);
+                let ansi = ui::buffer_to_ansi(frame.buffer, area);
+                app.save_screenshot(&text, &ansi);
+            }
+        }
 
         // Check for broker events (non-blocking)
+        app.event_backlog = mqtt_rx.len();
+        if app.debug {
+            app.process.sample(Instant::now());
+        }
         while let Ok(event) = mqtt_rx.try_recv() {
             app.handle_mqtt_event(event);
         }
+        if Instant::now() >= next_maintenance {
+            app.check_subscribe_rate();
+            app.prune_dead_topics();
+            app.run_housekeeping();
+            app.refresh_topic_templates();
+            app.refresh_device_statuses();
+            app.update_load_mode();
+            next_maintenance = Instant::now() + maintenance_interval;
+        }
+        app.check_watchdogs();
+        app.follow_busiest_topic();
+        app.advance_kiosk();
+        app.preview_filter();
+        app.run_schedules();
+        app.run_scenario();
+        app.check_response_watchers();
+        if let Some(ref mut web) = viewers.web {
+            web.answer_pending(&app);
+        }
 
-        // Check for terminal events
+        // Check for terminal events, waking up in time for the next frame
+        let timeout = input_poll.min(next_render.saturating_duration_since(Instant::now()));
         if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
                     app.handle_key(key.code, key.modifiers);
+                    redraw_now = true;
+                }
+                Event::Paste(pasted) => {
+                    app.handle_paste(&pasted);
+                    redraw_now = true;
                 }
+                Event::Resize(..) => redraw_now = true,
+                _ => {}
+            }
+        }
+
+        if let Some(pending) = app.pending_pipe.take() {
+            let separator = app.connected_broker_kind.topic_separator();
+            match TopicPipe::start(&pending.topic, separator, &pending.target).await {
+                Ok(pipe) => {
+                    app.set_status(&format!("Piping {} to {}", pipe.topic, pipe.target));
+                    app.topic_pipe = Some(pipe);
+                }
+                Err(e) => app.set_status(&format!("Pipe failed: {:#}", e)),
             }
         }
 
@@ -443,7 +1056,50 @@ async fn run_app(config: Config, config_path: PathBuf, needs_server_setup: bool)
                 }
             }
             app.reset_for_server_switch(switch.kind, switch.index)?;
-            client = Some(connect_client(&app, switch.kind, mqtt_tx.clone()).await?);
+            match connect_client(&app, switch.kind, mqtt_tx.clone()).await {
+                Ok(connected) => client = Some(connected),
+                Err(e) if app.input_mode == InputMode::Kiosk => app.kiosk_connect_failed(&e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        if app.pending_away_connect {
+            app.pending_away_connect = false;
+            if let Some(client) = client.take() {
+                if let Err(err) = client.disconnect().await {
+                    tracing::warn!("Failed to disconnect client: {:?}", err);
+                }
+            }
+            if let Some(server) = app.away_server() {
+                match MqttClient::connect(server, mqtt_tx.clone()).await {
+                    Ok(mqtt) => client = Some(Client::Mqtt(mqtt)),
+                    Err(e) => app.away_connect_failed(&e),
+                }
+            }
+        }
+
+        if app.pending_reconnect {
+            app.pending_reconnect = false;
+            if let Some(client) = client.take() {
+                if let Err(err) = client.disconnect().await {
+                    tracing::warn!("Failed to disconnect client: {:?}", err);
+                }
+            }
+            match connect_client(&app, app.connected_broker_kind, mqtt_tx.clone()).await {
+                Ok(connected) => client = Some(connected),
+                Err(e) if app.input_mode == InputMode::Kiosk => app.kiosk_connect_failed(&e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        if app.pending_disconnect {
+            app.pending_disconnect = false;
+            if let Some(client) = client.take() {
+                if let Err(err) = client.disconnect().await {
+                    tracing::warn!("Failed to disconnect client: {:?}", err);
+                }
+            }
+            app.connection_state = mqtt::ConnectionState::Disconnected;
         }
 
         // Handle pending publish
@@ -454,7 +1110,24 @@ async fn run_app(config: Config, config_path: PathBuf, needs_server_setup: bool)
                     .await
                 {
                     Ok(()) => {
-                        app.set_status(&format!("Published to {}", publish.topic));
+                        app.stats.record_outbound(publish.payload.len());
+                        // MQTT QoS 1/2 publishes are confirmed once the broker acks them
+                        if matches!(client, Client::Mqtt(_)) && publish.qos > 0 {
+                            let ack = if publish.qos == 1 {
+                                "PUBACK"
+                            } else {
+                                "PUBCOMP"
+                            };
+                            app.set_status(&format!(
+                                "Published to {}, awaiting {}",
+                                publish.topic, ack
+                            ));
+                        } else {
+                            app.set_status(&format!("Published to {}", publish.topic));
+                        }
+                        if let Some(response) = publish.response {
+                            app.watch_response(&publish.topic, response);
+                        }
                     }
                     Err(err) => {
                         app.set_status(&format!("Publish failed: {}", err));
@@ -470,13 +1143,15 @@ async fn run_app(config: Config, config_path: PathBuf, needs_server_setup: bool)
             break;
         }
     }
+    app.save_topic_totals();
 
     // Restore terminal
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
@@ -0,0 +1,460 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::rand::{SecureRandom, SystemRandom};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::config::{MetricSinkConfig, MetricSinkKind};
+use crate::http::{read_response_head, Endpoint, Scheme, IO_TIMEOUT};
+
+/// How often queued samples are flushed to the endpoint
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+const WS_TEXT: u8 = 0x1;
+const WS_CLOSE: u8 = 0x8;
+const WS_PING: u8 = 0x9;
+const WS_PONG: u8 = 0xA;
+/// Appended to the handshake key before hashing, per RFC 6455
+const WS_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// One tracked metric value
+#[derive(Debug, Clone)]
+pub struct MetricSample {
+    pub label: String,
+    pub value: f64,
+    /// Unix millis
+    pub timestamp_ms: i64,
+}
+
+/// Pushes tracked metric samples to Grafana Live or a WebSocket endpoint.
+///
+/// Samples are queued without blocking the UI and flushed in batches by a
+/// background task. Failed batches are dropped; the sink keeps retrying.
+pub struct MetricSink {
+    samples: mpsc::UnboundedSender<MetricSample>,
+}
+
+impl MetricSink {
+    /// Validate the endpoint and start the background sender
+    pub fn start(config: MetricSinkConfig) -> Result<Self> {
+        let endpoint = Endpoint::parse(&config.url)?;
+        match (config.kind, endpoint.scheme) {
+            (MetricSinkKind::Grafana, Scheme::Http) | (MetricSinkKind::Websocket, Scheme::Ws) => {}
+            (MetricSinkKind::Grafana, _) => bail!("Grafana sink needs an http:// URL"),
+            (MetricSinkKind::Websocket, _) => bail!("WebSocket sink needs a ws:// URL"),
+        }
+
+        let (samples, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_sink(config, endpoint, rx));
+        Ok(Self { samples })
+    }
+
+    /// Queue a sample for the next flush
+    pub fn send(&self, sample: MetricSample) {
+        let _ = self.samples.send(sample);
+    }
+}
+
+async fn run_sink(
+    config: MetricSinkConfig,
+    endpoint: Endpoint,
+    mut rx: mpsc::UnboundedReceiver<MetricSample>,
+) {
+    info!("Pushing tracked metrics to {}", config.url);
+    let mut socket: Option<WsConnection> = None;
+    let mut healthy = true;
+    let mut batch = Vec::new();
+
+    loop {
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+        loop {
+            match rx.try_recv() {
+                Ok(sample) => batch.push(sample),
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => return,
+            }
+        }
+        // Answer pings and notice a close even while there is nothing to send
+        if let Some(connection) = socket.as_mut() {
+            if let Err(e) = connection.answer().await {
+                warn!("Metric sink WebSocket dropped: {:#}", e);
+                socket = None;
+            }
+        }
+        if batch.is_empty() {
+            continue;
+        }
+
+        let result = match config.kind {
+            MetricSinkKind::Grafana => {
+                let body = line_protocol(&batch);
+                if body.is_empty() {
+                    Ok(())
+                } else {
+                    http_post(&endpoint, config.token.as_deref(), &body).await
+                }
+            }
+            MetricSinkKind::Websocket => {
+                let body = samples_json(&batch);
+                ws_send(&mut socket, &endpoint, config.token.as_deref(), &body).await
+            }
+        };
+        batch.clear();
+
+        match result {
+            Ok(()) if !healthy => {
+                info!("Metric sink recovered");
+                healthy = true;
+            }
+            Ok(()) => {}
+            Err(e) => {
+                socket = None;
+                if healthy {
+                    warn!("Metric sink push failed: {:#}", e);
+                    healthy = false;
+                }
+            }
+        }
+    }
+}
+
+/// Influx line protocol, as accepted by Grafana Live's push API. NaN and
+/// infinite values are left out, since one of them fails the whole batch.
+fn line_protocol(samples: &[MetricSample]) -> String {
+    samples
+        .iter()
+        .filter(|s| s.value.is_finite())
+        .map(|s| {
+            let label = s
+                .label
+                .replace('\\', "\\\\")
+                .replace(',', "\\,")
+                .replace('=', "\\=")
+                .replace(' ', "\\ ");
+            format!(
+                "mqtop,metric={} value={} {}\n",
+                label,
+                s.value,
+                s.timestamp_ms * 1_000_000
+            )
+        })
+        .collect()
+}
+
+/// JSON array of `{metric, value, ts}` objects for generic WebSocket consumers
+fn samples_json(samples: &[MetricSample]) -> String {
+    let values: Vec<serde_json::Value> = samples
+        .iter()
+        .map(|s| serde_json::json!({ "metric": s.label, "value": s.value, "ts": s.timestamp_ms }))
+        .collect();
+    serde_json::Value::Array(values).to_string()
+}
+
+async fn http_post(endpoint: &Endpoint, token: Option<&str>, body: &str) -> Result<()> {
+    let mut stream = endpoint.connect().await?;
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\
+         Connection: close\r\n",
+        endpoint.path,
+        endpoint.host_header(),
+        body.len()
+    );
+    if let Some(token) = token {
+        request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+
+    let head = tokio::time::timeout(IO_TIMEOUT, async {
+        stream.write_all(request.as_bytes()).await?;
+        read_response_head(&mut stream).await
+    })
+    .await
+    .context("Request timed out")??;
+
+    match head.status {
+        200..=299 => Ok(()),
+        401 | 403 => bail!("HTTP {}: check the sink token", head.status),
+        status => bail!("HTTP {}", status),
+    }
+}
+
+/// An open WebSocket, with received bytes that don't make a whole frame yet
+struct WsConnection {
+    stream: TcpStream,
+    inbound: Vec<u8>,
+}
+
+impl WsConnection {
+    async fn write(&mut self, frame: &[u8]) -> Result<()> {
+        tokio::time::timeout(IO_TIMEOUT, self.stream.write_all(frame))
+            .await
+            .context("WebSocket write timed out")??;
+        Ok(())
+    }
+
+    /// Read whatever the server sent without waiting, answer pings, and fail
+    /// once the server closes the connection
+    async fn answer(&mut self) -> Result<()> {
+        let mut buf = [0u8; 512];
+        loop {
+            match self.stream.try_read(&mut buf) {
+                Ok(0) => bail!("WebSocket closed by the server"),
+                Ok(n) => self.inbound.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e).context("WebSocket read failed"),
+            }
+        }
+        while let Some((opcode, payload, len)) = parse_ws_frame(&self.inbound) {
+            self.inbound.drain(..len);
+            match opcode {
+                WS_PING => {
+                    self.write(&ws_frame(WS_PONG, &payload, mask_key()?))
+                        .await?
+                }
+                WS_CLOSE => {
+                    // Echo the close before dropping the socket, as the protocol asks
+                    let _ = self.write(&ws_frame(WS_CLOSE, &payload, mask_key()?)).await;
+                    bail!("WebSocket closed by the server");
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn ws_send(
+    socket: &mut Option<WsConnection>,
+    endpoint: &Endpoint,
+    token: Option<&str>,
+    text: &str,
+) -> Result<()> {
+    if socket.is_none() {
+        *socket = Some(ws_connect(endpoint, token).await?);
+    }
+    let Some(connection) = socket.as_mut() else {
+        bail!("WebSocket not connected");
+    };
+    connection.answer().await?;
+    connection
+        .write(&ws_frame(WS_TEXT, text.as_bytes(), mask_key()?))
+        .await
+}
+
+async fn ws_connect(endpoint: &Endpoint, token: Option<&str>) -> Result<WsConnection> {
+    let mut stream = endpoint.connect().await?;
+    let key = BASE64.encode(random_bytes::<16>()?);
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n",
+        endpoint.path,
+        endpoint.host_header(),
+        key
+    );
+    if let Some(token) = token {
+        request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    request.push_str("\r\n");
+
+    let head = tokio::time::timeout(IO_TIMEOUT, async {
+        stream.write_all(request.as_bytes()).await?;
+        read_response_head(&mut stream).await
+    })
+    .await
+    .context("WebSocket handshake timed out")??;
+    if head.status != 101 {
+        bail!("WebSocket handshake failed with HTTP {}", head.status);
+    }
+    if head.header("Sec-WebSocket-Accept") != Some(accept_key(&key).as_str()) {
+        bail!("WebSocket handshake failed: server did not accept the key");
+    }
+    info!(
+        "Metric sink WebSocket connected to {}",
+        endpoint.host_header()
+    );
+    // The server may send its first frame right behind the handshake
+    Ok(WsConnection {
+        stream,
+        inbound: head.rest,
+    })
+}
+
+/// The `Sec-WebSocket-Accept` a server must answer `key` with
+fn accept_key(key: &str) -> String {
+    let hash = ring::digest::digest(
+        &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{}{}", key, WS_GUID).as_bytes(),
+    );
+    BASE64.encode(hash.as_ref())
+}
+
+/// A single masked client-to-server frame
+fn ws_frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    let len = payload.len();
+    if len < 126 {
+        frame.push(0x80 | len as u8);
+    } else if len <= u16::MAX as usize {
+        frame.push(0x80 | 126);
+        frame.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        frame.push(0x80 | 127);
+        frame.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    frame
+}
+
+/// The first whole frame in `buf` as its opcode, unmasked payload and length
+/// in bytes, or `None` until all of it has arrived
+fn parse_ws_frame(buf: &[u8]) -> Option<(u8, Vec<u8>, usize)> {
+    let opcode = buf.first()? & 0x0f;
+    let masked = buf.get(1)? & 0x80 != 0;
+    let (len, mut pos) = match buf[1] & 0x7f {
+        126 => (
+            u16::from_be_bytes(buf.get(2..4)?.try_into().ok()?) as usize,
+            4,
+        ),
+        127 => (
+            u64::from_be_bytes(buf.get(2..10)?.try_into().ok()?) as usize,
+            10,
+        ),
+        len => (len as usize, 2),
+    };
+    let mask = if masked {
+        let mask: [u8; 4] = buf.get(pos..pos + 4)?.try_into().ok()?;
+        pos += 4;
+        mask
+    } else {
+        [0; 4]
+    };
+    let payload = buf.get(pos..pos.checked_add(len)?)?;
+    let payload = payload
+        .iter()
+        .enumerate()
+        .map(|(i, b)| b ^ mask[i % 4])
+        .collect();
+    Some((opcode, payload, pos + len))
+}
+
+/// Each frame gets a fresh mask from the system RNG, as RFC 6455 requires
+fn mask_key() -> Result<[u8; 4]> {
+    random_bytes()
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| anyhow!("System random number generator failed"))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    #[test]
+    fn test_line_protocol() {
+        let samples = [MetricSample {
+            label: "power w".to_string(),
+            value: 12.5,
+            timestamp_ms: 1_700_000_000_000,
+        }];
+        assert_eq!(
+            line_protocol(&samples),
+            "mqtop,metric=power\\ w value=12.5 1700000000000000000\n"
+        );
+
+        let invalid = [f64::NAN, f64::INFINITY].map(|value| MetricSample {
+            label: "power".to_string(),
+            value,
+            timestamp_ms: 1_700_000_000_000,
+        });
+        assert_eq!(line_protocol(&invalid), "");
+    }
+
+    #[test]
+    fn test_ws_text_frame() {
+        let frame = ws_frame(WS_TEXT, b"hi", [0, 0, 0, 0]);
+        assert_eq!(frame, vec![0x81, 0x82, 0, 0, 0, 0, b'h', b'i']);
+
+        let frame = ws_frame(WS_TEXT, &[b'x'; 200], [1, 2, 3, 4]);
+        assert_eq!(&frame[..4], &[0x81, 0x80 | 126, 0, 200]);
+        assert_eq!(frame.len(), 4 + 4 + 200);
+    }
+
+    #[test]
+    fn test_parse_ws_frame() {
+        // Unmasked ping from the server, followed by part of the next frame
+        let (opcode, payload, len) = parse_ws_frame(&[0x89, 0x02, b'h', b'i', 0x88]).unwrap();
+        assert_eq!((opcode, payload.as_slice(), len), (WS_PING, &b"hi"[..], 4));
+        assert!(parse_ws_frame(&[0x88]).is_none());
+        assert!(parse_ws_frame(&[0x81, 0x05, b'h']).is_none());
+
+        // Our own masked frames read back
+        let frame = ws_frame(WS_PONG, &[b'x'; 200], [1, 2, 3, 4]);
+        let (opcode, payload, len) = parse_ws_frame(&frame).unwrap();
+        assert_eq!(
+            (opcode, payload, len),
+            (WS_PONG, vec![b'x'; 200], frame.len())
+        );
+    }
+
+    #[test]
+    fn test_handshake_accept_key() {
+        // The example from RFC 6455, section 1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    /// Accept one WebSocket handshake, answering with `accept(key)` and a
+    /// ping in the same write
+    async fn ws_server(accept: fn(&str) -> String) -> Endpoint {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 512];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let request = String::from_utf8(request).unwrap();
+            let key = request
+                .lines()
+                .find_map(|line| line.strip_prefix("Sec-WebSocket-Key: "))
+                .unwrap();
+            let mut response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                 Connection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                accept(key)
+            )
+            .into_bytes();
+            response.extend_from_slice(&[0x89, 0x00]);
+            stream.write_all(&response).await.unwrap();
+        });
+        Endpoint::parse(&format!("ws://127.0.0.1:{}/", port)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ws_connect() {
+        let endpoint = ws_server(accept_key).await;
+        let connection = ws_connect(&endpoint, None).await.unwrap();
+        assert_eq!(connection.inbound, vec![0x89, 0x00]);
+
+        let endpoint = ws_server(|_| accept_key("someone else's key")).await;
+        assert!(ws_connect(&endpoint, None).await.is_err());
+    }
+}
@@ -1,6 +0,0 @@
-pub mod client;
-pub mod message;
-pub mod resilience;
-
-pub use client::{ConnectionState, MqttClient, MqttEvent};
-pub use message::MqttMessage;
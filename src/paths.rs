@@ -0,0 +1,231 @@
+//! Where mqtop keeps its files. Following the XDG base directories, servers
+//! live in the config dir, user data (stars, metrics, bookmarks, history),
+//! sessions and the debug log in the state dir, and screenshots and exports
+//! in the cache dir. A profile gets its own subdirectory in each, so work,
+//! home and lab setups never share servers or stars.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+const APP_DIR: &str = "mqtop";
+const PROFILES_DIR: &str = "profiles";
+const SESSIONS_DIR: &str = "sessions";
+const DEBUG_LOG_FILE: &str = "mqtop.log";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    pub config_dir: PathBuf,
+    pub state_dir: PathBuf,
+    pub cache_dir: PathBuf,
+    pub profile: Option<String>,
+}
+
+impl Paths {
+    /// Directories for `profile` (or the default one) under the user's XDG dirs
+    pub fn new(profile: Option<&str>) -> Result<Self> {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        Self::from_bases(
+            &base_dir("XDG_CONFIG_HOME", &home, ".config"),
+            &base_dir("XDG_STATE_HOME", &home, ".local/state"),
+            &base_dir("XDG_CACHE_HOME", &home, ".cache"),
+            profile,
+        )
+    }
+
+    fn from_bases(
+        config_home: &Path,
+        state_home: &Path,
+        cache_home: &Path,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        let mut config_dir = config_home.join(APP_DIR);
+        let mut state_dir = state_home.join(APP_DIR);
+        let mut cache_dir = cache_home.join(APP_DIR);
+        if let Some(name) = profile {
+            validate_profile(name)?;
+            config_dir = config_dir.join(PROFILES_DIR).join(name);
+            state_dir = state_dir.join(PROFILES_DIR).join(name);
+            cache_dir = cache_dir.join(PROFILES_DIR).join(name);
+        }
+        Ok(Self {
+            config_dir,
+            state_dir,
+            cache_dir,
+            profile: profile.map(str::to_string),
+        })
+    }
+
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir.join("config.toml")
+    }
+
+    pub fn user_data_file(&self) -> PathBuf {
+        self.state_dir.join("userdata.json")
+    }
+
+    /// Where `W` saves session snapshots
+    pub fn sessions_dir(&self) -> PathBuf {
+        self.state_dir.join(SESSIONS_DIR)
+    }
+
+    /// A session file as given, or a saved one in the sessions dir when only
+    /// its name is given
+    pub fn session_file(&self, path: &Path) -> PathBuf {
+        if path.is_relative() && !path.exists() {
+            let saved = self.sessions_dir().join(path);
+            if saved.exists() {
+                return saved;
+            }
+        }
+        path.to_path_buf()
+    }
+
+    /// Log file used by `--debug` when no file is configured
+    pub fn debug_log_file(&self) -> PathBuf {
+        self.state_dir.join(DEBUG_LOG_FILE)
+    }
+
+    /// Saved per-topic totals for a server, one file per server. A hash of
+    /// the raw name keeps names that sanitise alike (`Home Lab/1`,
+    /// `Home_Lab_1`) apart.
+    pub fn topic_totals_file(&self, kind: &str, server: &str) -> PathBuf {
+        let name: String = server
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.state_dir.join("topic-totals").join(format!(
+            "{}-{}-{:08x}.json",
+            kind.to_lowercase(),
+            name,
+            fnv1a(server)
+        ))
+    }
+
+    /// Move userdata.json out of the config dir, where it lived before state
+    /// was split out. Only the default profile has an old file to move.
+    pub fn migrate_user_data(&self) -> Result<()> {
+        let target = self.user_data_file();
+        if self.profile.is_some() || target.exists() {
+            return Ok(());
+        }
+        let Some(legacy) = dirs::config_dir().map(|dir| dir.join(APP_DIR).join("userdata.json"))
+        else {
+            return Ok(());
+        };
+        if !legacy.exists() || legacy == target {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.state_dir)
+            .with_context(|| format!("Failed to create directory {:?}", self.state_dir))?;
+        if std::fs::rename(&legacy, &target).is_err() {
+            // Different filesystems: copy and leave the old file in place
+            std::fs::copy(&legacy, &target)
+                .with_context(|| format!("Failed to copy {:?} to {:?}", legacy, target))?;
+        }
+        tracing::info!("Moved user data from {:?} to {:?}", legacy, target);
+        Ok(())
+    }
+}
+
+/// `$VAR` when set to an absolute path (as the XDG spec requires), else `~/fallback`
+fn base_dir(var: &str, home: &Path, fallback: &str) -> PathBuf {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| home.join(fallback))
+}
+
+/// Profile names become directory names, so keep them to a safe set
+pub fn validate_profile(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Profile name cannot be empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Profile name '{}' may only use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// 32-bit FNV-1a, stable across builds unlike std's hasher
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_layout() {
+        let config = Path::new("/home/u/.config");
+        let state = Path::new("/home/u/.local/state");
+        let cache = Path::new("/home/u/.cache");
+
+        let default = Paths::from_bases(config, state, cache, None).unwrap();
+        assert_eq!(
+            default.config_file(),
+            PathBuf::from("/home/u/.config/mqtop/config.toml")
+        );
+        assert_eq!(
+            default.user_data_file(),
+            PathBuf::from("/home/u/.local/state/mqtop/userdata.json")
+        );
+        assert_eq!(
+            default.sessions_dir(),
+            PathBuf::from("/home/u/.local/state/mqtop/sessions")
+        );
+        assert_eq!(default.cache_dir, PathBuf::from("/home/u/.cache/mqtop"));
+
+        let totals = default.topic_totals_file("MQTT", "Home Lab/1");
+        assert_eq!(
+            totals.parent().unwrap(),
+            PathBuf::from("/home/u/.local/state/mqtop/topic-totals")
+        );
+        let name = totals.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("mqtt-Home_Lab_1-") && name.ends_with(".json"));
+        assert_ne!(totals, default.topic_totals_file("MQTT", "Home_Lab_1"));
+
+        let lab = Paths::from_bases(config, state, cache, Some("lab")).unwrap();
+        assert_eq!(
+            lab.config_file(),
+            PathBuf::from("/home/u/.config/mqtop/profiles/lab/config.toml")
+        );
+        assert_eq!(
+            lab.user_data_file(),
+            PathBuf::from("/home/u/.local/state/mqtop/profiles/lab/userdata.json")
+        );
+        assert_eq!(
+            lab.debug_log_file(),
+            PathBuf::from("/home/u/.local/state/mqtop/profiles/lab/mqtop.log")
+        );
+        assert_eq!(
+            lab.cache_dir,
+            PathBuf::from("/home/u/.cache/mqtop/profiles/lab")
+        );
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        assert!(validate_profile("work").is_ok());
+        assert!(validate_profile("home-lab_2").is_ok());
+        assert!(validate_profile("").is_err());
+        assert!(validate_profile("../etc").is_err());
+        assert!(validate_profile("a/b").is_err());
+    }
+}
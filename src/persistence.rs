@@ -1,9 +1,16 @@
 #![allow(dead_code)]
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::input::InputHistory;
+use crate::scenario::Scenario;
+
+/// Current format version of exported bookmark sets
+pub const BOOKMARK_SET_VERSION: u32 = 1;
 
 /// User data that persists across sessions
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -16,6 +23,18 @@ pub struct UserData {
     #[serde(default)]
     pub starred_devices: HashSet<String>,
 
+    /// Topics (and their subtrees) hidden from the tree
+    #[serde(default)]
+    pub hidden_topics: HashSet<String>,
+
+    /// Silence watchdogs on starred topics (topic -> window in seconds)
+    #[serde(default)]
+    pub watchdogs: BTreeMap<String, u64>,
+
+    /// Free-text notes attached to topics
+    #[serde(default)]
+    pub notes: BTreeMap<String, String>,
+
     /// Last selected topic (for restoring state)
     #[serde(default)]
     pub last_topic: Option<String>,
@@ -27,6 +46,40 @@ pub struct UserData {
     /// Saved publish presets / bookmarks
     #[serde(default)]
     pub bookmarks: Vec<Bookmark>,
+
+    /// Publish sequences run from the scenario manager
+    #[serde(default)]
+    pub scenarios: Vec<Scenario>,
+
+    /// Recent publishes, filters and searches (only with `ui.persist_history`)
+    #[serde(default, skip_serializing_if = "InputHistories::is_empty")]
+    pub history: InputHistories,
+}
+
+/// Recently entered values per input, browsed with Up/Down
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputHistories {
+    #[serde(default)]
+    pub publishes: InputHistory<RecentPublish>,
+    #[serde(default)]
+    pub filters: InputHistory<String>,
+    #[serde(default)]
+    pub searches: InputHistory<String>,
+}
+
+impl InputHistories {
+    pub fn is_empty(&self) -> bool {
+        self.publishes.is_empty() && self.filters.is_empty() && self.searches.is_empty()
+    }
+}
+
+/// A publish from the publish dialog, as kept in history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentPublish {
+    pub topic: String,
+    pub payload: String,
+    pub qos: u8,
+    pub retain: bool,
 }
 
 /// A metric being tracked for stats
@@ -38,30 +91,171 @@ pub struct TrackedMetric {
 }
 
 /// A saved publish preset / bookmark
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Bookmark {
     pub name: String,    // Display name (e.g., "Temp sensor alert")
     pub topic: String,   // MQTT topic
     pub payload: String, // Default payload
     pub qos: u8,         // 0, 1, 2
     pub retain: bool,
-    pub category: Option<String>, // Optional: "testing", "alerts", etc.
+    pub category: Option<String>, // Folder path, e.g. "fleet/chargers" ("testing" is top-level)
 }
 
-impl UserData {
-    /// Get the default data file path
-    pub fn default_path() -> PathBuf {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("mqtop")
-            .join("userdata.json")
+impl Bookmark {
+    /// Whether the bookmark lives in `folder` or one of its subfolders
+    pub fn in_folder(&self, folder: &str) -> bool {
+        self.category.as_deref().is_some_and(|category| {
+            category
+                .strip_prefix(folder)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+/// Tidy a folder path typed by the user: `" fleet / chargers/ "` -> `fleet/chargers`
+pub fn normalize_folder(folder: &str) -> Option<String> {
+    let path = folder
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!path.is_empty()).then_some(path)
+}
+
+/// One row of the bookmark manager's folder tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookmarkRow {
+    Folder {
+        path: String,
+        depth: usize,
+        /// Bookmarks in the folder and its subfolders
+        count: usize,
+        collapsed: bool,
+    },
+    Bookmark {
+        index: usize,
+        depth: usize,
+    },
+}
+
+#[derive(Default)]
+struct FolderNode<'a> {
+    folders: BTreeMap<&'a str, FolderNode<'a>>,
+    bookmarks: Vec<usize>,
+}
+
+impl FolderNode<'_> {
+    fn count(&self) -> usize {
+        self.bookmarks.len() + self.folders.values().map(FolderNode::count).sum::<usize>()
     }
 
-    /// Load user data from file, or return default if not found
-    pub fn load() -> Self {
-        Self::load_from(Self::default_path()).unwrap_or_default()
+    fn push_rows(
+        &self,
+        prefix: &str,
+        depth: usize,
+        collapsed: &HashSet<String>,
+        rows: &mut Vec<BookmarkRow>,
+    ) {
+        for (name, folder) in &self.folders {
+            let path = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let is_collapsed = collapsed.contains(&path);
+            rows.push(BookmarkRow::Folder {
+                path: path.clone(),
+                depth,
+                count: folder.count(),
+                collapsed: is_collapsed,
+            });
+            if !is_collapsed {
+                folder.push_rows(&path, depth + 1, collapsed, rows);
+            }
+        }
+        rows.extend(
+            self.bookmarks
+                .iter()
+                .map(|&index| BookmarkRow::Bookmark { index, depth }),
+        );
+    }
+}
+
+/// Lay bookmarks out as a folder tree: subfolders first (sorted), then the
+/// folder's own bookmarks in saved order. Collapsed folders hide their contents.
+pub fn bookmark_rows(bookmarks: &[Bookmark], collapsed: &HashSet<String>) -> Vec<BookmarkRow> {
+    let mut root = FolderNode::default();
+    for (index, bookmark) in bookmarks.iter().enumerate() {
+        let mut node = &mut root;
+        for segment in bookmark
+            .category
+            .as_deref()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+        {
+            node = node.folders.entry(segment).or_default();
+        }
+        node.bookmarks.push(index);
     }
 
+    let mut rows = Vec::new();
+    root.push_rows("", 0, collapsed, &mut rows);
+    rows
+}
+
+/// Bookmarks exported to a file for sharing, e.g. a team's canonical command
+/// payloads for a device fleet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkSet {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarkSet {
+    pub fn new(bookmarks: Vec<Bookmark>) -> Self {
+        Self {
+            version: BOOKMARK_SET_VERSION,
+            exported_at: Utc::now(),
+            bookmarks,
+        }
+    }
+
+    /// Default file name for an export, e.g. `mqtop-bookmarks-20240101-120000.json`
+    pub fn default_filename() -> String {
+        format!(
+            "mqtop-bookmarks-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        )
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read bookmarks from {:?}", path))?;
+        let set: Self = serde_json::from_str(&contents).context("Failed to parse bookmarks")?;
+
+        if set.version > BOOKMARK_SET_VERSION {
+            bail!(
+                "Bookmark file version {} is newer than supported version {}",
+                set.version,
+                BOOKMARK_SET_VERSION
+            );
+        }
+
+        Ok(set)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(self).context("Failed to serialize bookmarks")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write bookmarks to {:?}", path))
+    }
+}
+
+impl UserData {
     /// Load from a specific path
     pub fn load_from(path: PathBuf) -> Result<Self> {
         if !path.exists() {
@@ -74,11 +268,6 @@ impl UserData {
         serde_json::from_str(&contents).with_context(|| "Failed to parse user data")
     }
 
-    /// Save user data to file
-    pub fn save(&self) -> Result<()> {
-        self.save_to(Self::default_path())
-    }
-
     /// Save to a specific path
     pub fn save_to(&self, path: PathBuf) -> Result<()> {
         // Create parent directories if needed
@@ -128,6 +317,30 @@ impl UserData {
         self.starred_devices.contains(device_id)
     }
 
+    /// Attach a note to a topic; an empty note removes it
+    pub fn set_note(&mut self, topic: &str, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.notes.remove(topic);
+        } else {
+            self.notes.insert(topic.to_string(), note.to_string());
+        }
+    }
+
+    pub fn note(&self, topic: &str) -> Option<&str> {
+        self.notes.get(topic).map(String::as_str)
+    }
+
+    /// Topics whose note contains `query` (case-insensitive)
+    pub fn search_notes(&self, query: &str) -> Vec<String> {
+        let query = query.to_lowercase();
+        self.notes
+            .iter()
+            .filter(|(_, note)| note.to_lowercase().contains(&query))
+            .map(|(topic, _)| topic.clone())
+            .collect()
+    }
+
     /// Add a tracked metric
     pub fn add_tracked_metric(&mut self, topic_pattern: String, field_path: String, label: String) {
         // Remove existing with same label
@@ -163,6 +376,30 @@ impl UserData {
         }
     }
 
+    /// Merge imported bookmarks: one with the same folder and name replaces
+    /// the existing entry, anything else is appended. Returns (added, updated).
+    pub fn import_bookmarks(&mut self, bookmarks: Vec<Bookmark>) -> (usize, usize) {
+        let (mut added, mut updated) = (0, 0);
+        for mut bookmark in bookmarks {
+            bookmark.category = bookmark.category.as_deref().and_then(normalize_folder);
+            match self
+                .bookmarks
+                .iter_mut()
+                .find(|b| b.category == bookmark.category && b.name == bookmark.name)
+            {
+                Some(existing) => {
+                    *existing = bookmark;
+                    updated += 1;
+                }
+                None => {
+                    self.bookmarks.push(bookmark);
+                    added += 1;
+                }
+            }
+        }
+        (added, updated)
+    }
+
     /// Get unique categories from existing bookmarks
     pub fn bookmark_categories(&self) -> Vec<String> {
         let mut categories: Vec<String> = self
@@ -196,6 +433,91 @@ mod tests {
         assert!(!data.is_starred("test/topic"));
     }
 
+    #[test]
+    fn test_topic_notes() {
+        let mut data = UserData::default();
+        data.set_note("plant/gw1/power", "  Legacy gateway, ignore spikes ");
+        data.set_note("plant/gw2/power", "New gateway");
+        assert_eq!(
+            data.note("plant/gw1/power"),
+            Some("Legacy gateway, ignore spikes")
+        );
+        assert_eq!(data.search_notes("SPIKES"), vec!["plant/gw1/power"]);
+        assert_eq!(data.search_notes("gateway").len(), 2);
+
+        data.set_note("plant/gw1/power", "");
+        assert_eq!(data.note("plant/gw1/power"), None);
+    }
+
+    fn bookmark(name: &str, folder: Option<&str>) -> Bookmark {
+        Bookmark {
+            name: name.to_string(),
+            topic: format!("cmd/{}", name),
+            payload: String::new(),
+            qos: 0,
+            retain: false,
+            category: folder.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_bookmark_folders() {
+        let bookmarks = vec![
+            bookmark("ping", None),
+            bookmark("reset", Some("fleet/chargers")),
+            bookmark("reboot", Some("fleet")),
+            bookmark("alert", Some("testing")),
+        ];
+        assert!(bookmarks[1].in_folder("fleet"));
+        assert!(!bookmarks[1].in_folder("fle"));
+
+        let rows = bookmark_rows(&bookmarks, &HashSet::new());
+        let folder = |path: &str, depth, count| BookmarkRow::Folder {
+            path: path.to_string(),
+            depth,
+            count,
+            collapsed: false,
+        };
+        assert_eq!(
+            rows,
+            vec![
+                folder("fleet", 0, 2),
+                folder("fleet/chargers", 1, 1),
+                BookmarkRow::Bookmark { index: 1, depth: 2 },
+                BookmarkRow::Bookmark { index: 2, depth: 1 },
+                folder("testing", 0, 1),
+                BookmarkRow::Bookmark { index: 3, depth: 1 },
+                BookmarkRow::Bookmark { index: 0, depth: 0 },
+            ]
+        );
+
+        let collapsed = HashSet::from(["fleet".to_string()]);
+        assert_eq!(bookmark_rows(&bookmarks, &collapsed).len(), 4);
+        assert_eq!(
+            normalize_folder(" fleet / chargers/ ").as_deref(),
+            Some("fleet/chargers")
+        );
+        assert_eq!(normalize_folder(" / "), None);
+    }
+
+    #[test]
+    fn test_bookmark_set_import() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+        let mut shared = bookmark("reset", Some("fleet/ chargers"));
+        shared.payload = "{\"cmd\":\"reset\"}".to_string();
+        BookmarkSet::new(vec![shared, bookmark("ping", None)])
+            .save_to(&path)
+            .unwrap();
+
+        let mut data = UserData::default();
+        data.add_bookmark(bookmark("reset", Some("fleet/chargers")));
+        let set = BookmarkSet::load_from(&path).unwrap();
+        assert_eq!(data.import_bookmarks(set.bookmarks), (1, 1));
+        assert_eq!(data.bookmarks.len(), 2);
+        assert_eq!(data.bookmarks[0].payload, "{\"cmd\":\"reset\"}");
+    }
+
     #[test]
     fn test_save_and_load() {
         let dir = tempdir().unwrap();
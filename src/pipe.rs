@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Where piped payloads go
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipeTarget {
+    /// `> path` truncates, `>> path` appends
+    File { path: PathBuf, append: bool },
+    /// Anything else runs through the shell with payloads on stdin
+    Command(String),
+}
+
+impl PipeTarget {
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        let (rest, append) = if let Some(rest) = input.strip_prefix(">>") {
+            (Some(rest), true)
+        } else {
+            (input.strip_prefix('>'), false)
+        };
+
+        match rest.map(str::trim) {
+            Some("") => bail!("Missing file name after '>'"),
+            Some(path) => Ok(PipeTarget::File {
+                path: PathBuf::from(path),
+                append,
+            }),
+            None if input.is_empty() => bail!("Enter a command or '>> file'"),
+            None => Ok(PipeTarget::Command(input.to_string())),
+        }
+    }
+}
+
+/// Streams every new payload of a topic (and its subtopics) to a file or a
+/// command's stdin, one payload per line.
+///
+/// Writes happen on a background task so a slow consumer never stalls the UI.
+/// A command's own output goes to the log view (`D`).
+pub struct TopicPipe {
+    pub topic: String,
+    /// What the user typed, for display
+    pub target: String,
+    separator: char,
+    payloads: mpsc::UnboundedSender<Bytes>,
+}
+
+impl TopicPipe {
+    pub async fn start(topic: &str, separator: char, input: &str) -> Result<Self> {
+        let target = PipeTarget::parse(input)?;
+        let (payloads, rx) = mpsc::unbounded_channel();
+
+        match target {
+            PipeTarget::File { path, append } => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(append)
+                    .truncate(!append)
+                    .open(&path)
+                    .await
+                    .with_context(|| format!("Cannot open {}", path.display()))?;
+                tokio::spawn(run_pipe(file, None, rx));
+            }
+            PipeTarget::Command(command) => {
+                let mut child = shell(&command)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .with_context(|| format!("Cannot run '{}'", command))?;
+                let stdin = child.stdin.take().context("Command has no stdin")?;
+                if let Some(stdout) = child.stdout.take() {
+                    tokio::spawn(log_lines(stdout));
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    tokio::spawn(log_lines(stderr));
+                }
+                tokio::spawn(run_pipe(stdin, Some(child), rx));
+            }
+        }
+
+        info!("Piping {} to {}", topic, input.trim());
+        Ok(Self {
+            topic: topic.to_string(),
+            target: input.trim().to_string(),
+            separator,
+            payloads,
+        })
+    }
+
+    /// Whether messages on `topic` belong to the piped topic or its subtree
+    pub fn matches(&self, topic: &str) -> bool {
+        topic
+            .strip_prefix(self.topic.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(self.separator))
+    }
+
+    /// Queue a payload; returns false once the target has gone away
+    pub fn send(&self, payload: &Bytes) -> bool {
+        self.payloads.send(payload.clone()).is_ok()
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+async fn run_pipe<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    child: Option<Child>,
+    mut rx: mpsc::UnboundedReceiver<Bytes>,
+) {
+    while let Some(payload) = rx.recv().await {
+        let result = match writer.write_all(&payload).await {
+            Ok(()) => match writer.write_all(b"\n").await {
+                Ok(()) => writer.flush().await,
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!("Pipe closed: {}", e);
+            break;
+        }
+    }
+    rx.close();
+
+    // Closing stdin lets the command finish its output
+    drop(writer);
+    if let Some(mut child) = child {
+        match child.wait().await {
+            Ok(status) if !status.success() => warn!("Pipe command exited with {}", status),
+            Ok(_) => {}
+            Err(e) => warn!("Pipe command failed: {}", e),
+        }
+    }
+}
+
+async fn log_lines<R: tokio::io::AsyncRead + Unpin>(output: R) {
+    let mut lines = BufReader::new(output).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        info!("pipe: {}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            PipeTarget::parse(">> out.jsonl").unwrap(),
+            PipeTarget::File {
+                path: PathBuf::from("out.jsonl"),
+                append: true
+            }
+        );
+        assert_eq!(
+            PipeTarget::parse(">out.jsonl").unwrap(),
+            PipeTarget::File {
+                path: PathBuf::from("out.jsonl"),
+                append: false
+            }
+        );
+        assert_eq!(
+            PipeTarget::parse(" jq -c .temp ").unwrap(),
+            PipeTarget::Command("jq -c .temp".to_string())
+        );
+        assert!(PipeTarget::parse(">> ").is_err());
+        assert!(PipeTarget::parse("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pipe_to_file() {
+        let path = std::env::temp_dir().join(format!("mqtop-pipe-{}.log", std::process::id()));
+        let arg = format!("> {}", path.display());
+        let pipe = TopicPipe::start("sensors/a", '/', &arg).await.unwrap();
+
+        assert!(pipe.matches("sensors/a"));
+        assert!(pipe.matches("sensors/a/temp"));
+        assert!(!pipe.matches("sensors/ab"));
+
+        assert!(pipe.send(&Bytes::from_static(b"{\"t\":1}")));
+        assert!(pipe.send(&Bytes::from_static(b"{\"t\":2}")));
+        drop(pipe);
+
+        let mut contents = String::new();
+        for _ in 0..50 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            contents = std::fs::read_to_string(&path).unwrap_or_default();
+            if contents.lines().count() == 2 {
+                break;
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(contents, "{\"t\":1}\n{\"t\":2}\n");
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+/// How often CPU use and memory are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// Clock ticks per second in `/proc/<pid>/stat`, fixed by the Linux ABI
+const USER_HZ: u64 = 100;
+
+/// mqtop's own CPU and memory use, to tell a slow mqtop from a slow broker.
+/// Read from `/proc`, so other platforms show nothing.
+#[derive(Debug, Default)]
+pub struct ProcessMonitor {
+    /// Previous sample: when, and CPU time used up to then
+    last: Option<(Instant, Duration)>,
+    /// CPU use over the last interval; 100 is one full core
+    pub cpu_percent: Option<f64>,
+    /// Resident memory
+    pub rss_bytes: Option<u64>,
+}
+
+impl ProcessMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a sample, at most once per interval
+    pub fn sample(&mut self, now: Instant) {
+        if self
+            .last
+            .is_some_and(|(at, _)| now.saturating_duration_since(at) < SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        let cpu = read_cpu_time();
+        if let (Some((at, before)), Some(cpu)) = (self.last, cpu) {
+            let wall = now.saturating_duration_since(at).as_secs_f64();
+            if wall > 0.0 {
+                self.cpu_percent = Some(cpu.saturating_sub(before).as_secs_f64() / wall * 100.0);
+            }
+        }
+        self.last = cpu.map(|cpu| (now, cpu));
+        self.rss_bytes = read_rss();
+    }
+}
+
+fn read_cpu_time() -> Option<Duration> {
+    parse_cpu_time(&std::fs::read_to_string("/proc/self/stat").ok()?)
+}
+
+fn read_rss() -> Option<u64> {
+    parse_rss(&std::fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// User plus system time from `/proc/<pid>/stat`
+fn parse_cpu_time(stat: &str) -> Option<Duration> {
+    // The command name may contain spaces; fields are counted after it
+    let (_, rest) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(Duration::from_millis((utime + stime) * 1000 / USER_HZ))
+}
+
+/// `VmRSS` from `/proc/<pid>/status`
+fn parse_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "4242 (mqtop (dev)) S 1 4242 4242 0 -1 4194560 9000 0 0 0 250 50 0 0 20 0 9";
+        assert_eq!(parse_cpu_time(stat), Some(Duration::from_secs(3)));
+        assert_eq!(parse_cpu_time("garbage"), None);
+
+        let status = "Name:\tmqtop\nVmPeak:\t  90000 kB\nVmRSS:\t   12345 kB\n";
+        assert_eq!(parse_rss(status), Some(12345 * 1024));
+        assert_eq!(parse_rss("Name:\tmqtop\n"), None);
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::mqtt::MqttMessage;
+use crate::session::SessionSnapshot;
+use crate::state::{DeviceTracker, SchemaTracker};
+
+/// Output format for `mqtop report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+}
+
+/// Summary of observed traffic for architecture reviews
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub generated_at: DateTime<Utc>,
+    /// Server or session file the traffic came from
+    pub source: String,
+    pub duration_secs: f64,
+    pub total_messages: u64,
+    pub total_bytes: u64,
+    pub prefixes: Vec<PrefixReport>,
+    pub topics: Vec<TopicReport>,
+    pub devices: Vec<DeviceReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrefixReport {
+    pub prefix: String,
+    pub topics: usize,
+    pub messages: u64,
+    pub bytes: u64,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicReport {
+    pub topic: String,
+    pub messages: u64,
+    pub bytes: u64,
+    pub rate: f64,
+    /// Payload size stats; from buffered messages only when read from a session
+    pub min_payload: Option<usize>,
+    pub avg_payload: Option<f64>,
+    pub max_payload: Option<usize>,
+    /// JSON field paths and types of the latest payload
+    pub schema: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceReport {
+    pub device_id: String,
+    pub device_type: Option<String>,
+    pub messages: u64,
+    pub topics: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct TopicAccumulator {
+    messages: u64,
+    bytes: u64,
+    sizes: u64,
+    sampled: u64,
+    min_payload: Option<usize>,
+    max_payload: Option<usize>,
+}
+
+impl TopicAccumulator {
+    fn record_size(&mut self, size: usize) {
+        self.sizes += size as u64;
+        self.sampled += 1;
+        self.min_payload = Some(self.min_payload.map_or(size, |min| min.min(size)));
+        self.max_payload = Some(self.max_payload.map_or(size, |max| max.max(size)));
+    }
+}
+
+/// Collects traffic for a [`Report`]
+pub struct ReportBuilder {
+    separator: char,
+    topics: BTreeMap<String, TopicAccumulator>,
+    schemas: SchemaTracker,
+    devices: DeviceTracker,
+}
+
+impl ReportBuilder {
+    pub fn new(separator: char) -> Self {
+        Self {
+            separator,
+            topics: BTreeMap::new(),
+            schemas: SchemaTracker::new(),
+            devices: DeviceTracker::new(),
+        }
+    }
+
+    pub fn observe(&mut self, msg: &MqttMessage) {
+        let topic = self.topics.entry(msg.topic.clone()).or_default();
+        topic.messages += 1;
+        topic.bytes += msg.payload_size() as u64;
+        topic.record_size(msg.payload_size());
+        self.schemas.process_message(&msg.topic, &msg.payload);
+        self.devices.process_message(&msg.topic, &msg.payload);
+    }
+
+    pub fn finish(self, source: String, duration_secs: f64) -> Report {
+        let devices = self
+            .devices
+            .get_devices()
+            .into_iter()
+            .map(|device| DeviceReport {
+                device_id: device.device_id.clone(),
+                device_type: device.device_type.clone(),
+                messages: device.message_count,
+                topics: device.topics.clone(),
+            })
+            .collect();
+        self.build(source, duration_secs, devices)
+    }
+
+    fn build(self, source: String, duration_secs: f64, mut devices: Vec<DeviceReport>) -> Report {
+        let rate = |messages: u64| {
+            if duration_secs > 0.0 {
+                messages as f64 / duration_secs
+            } else {
+                0.0
+            }
+        };
+
+        let mut prefixes: BTreeMap<String, PrefixReport> = BTreeMap::new();
+        let mut topics = Vec::with_capacity(self.topics.len());
+        for (name, acc) in self.topics {
+            let prefix = name.split(self.separator).next().unwrap_or_default();
+            let entry = prefixes
+                .entry(prefix.to_string())
+                .or_insert_with(|| PrefixReport {
+                    prefix: prefix.to_string(),
+                    topics: 0,
+                    messages: 0,
+                    bytes: 0,
+                    rate: 0.0,
+                });
+            entry.topics += 1;
+            entry.messages += acc.messages;
+            entry.bytes += acc.bytes;
+
+            let schema = self
+                .schemas
+                .get_schema(&name)
+                .map(|schema| {
+                    schema
+                        .fields
+                        .iter()
+                        .map(|(path, kind)| (path.clone(), kind.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            topics.push(TopicReport {
+                rate: rate(acc.messages),
+                messages: acc.messages,
+                bytes: acc.bytes,
+                min_payload: acc.min_payload,
+                avg_payload: (acc.sampled > 0).then(|| acc.sizes as f64 / acc.sampled as f64),
+                max_payload: acc.max_payload,
+                schema,
+                topic: name,
+            });
+        }
+
+        let mut prefixes: Vec<_> = prefixes.into_values().collect();
+        for prefix in &mut prefixes {
+            prefix.rate = rate(prefix.messages);
+        }
+        prefixes.sort_by_key(|p| std::cmp::Reverse(p.messages));
+        devices.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+
+        Report {
+            generated_at: Utc::now(),
+            source,
+            duration_secs,
+            total_messages: topics.iter().map(|t| t.messages).sum(),
+            total_bytes: topics.iter().map(|t| t.bytes).sum(),
+            prefixes,
+            topics,
+            devices,
+        }
+    }
+}
+
+impl Report {
+    /// Build a report from a saved session. Counts come from the topic tree;
+    /// payload sizes and schemas from the buffered messages.
+    pub fn from_session(snapshot: &SessionSnapshot, source: String) -> Result<Self> {
+        let mut builder = ReportBuilder::new(snapshot.broker.topic_separator());
+        for message in &snapshot.messages {
+            let msg = message.clone().into_message()?;
+            let topic = builder.topics.entry(msg.topic.clone()).or_default();
+            topic.record_size(msg.payload_size());
+            builder.schemas.process_message(&msg.topic, &msg.payload);
+        }
+        for topic in &snapshot.topics {
+            let acc = builder.topics.entry(topic.topic.clone()).or_default();
+            acc.messages = topic.message_count;
+            acc.bytes = topic.bytes_received;
+        }
+
+        let devices = snapshot
+            .devices
+            .iter()
+            .map(|device| DeviceReport {
+                device_id: device.device_id.clone(),
+                device_type: device.device_type.clone(),
+                messages: device.message_count,
+                topics: device.topics.clone(),
+            })
+            .collect();
+        Ok(builder.build(source, snapshot.stats.uptime_secs as f64, devices))
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Markdown => Ok(self.to_markdown()),
+            ReportFormat::Json => {
+                serde_json::to_string_pretty(self).context("Failed to serialize report")
+            }
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# mqtop report\n");
+        let _ = writeln!(out, "- Source: {}", self.source);
+        let _ = writeln!(
+            out,
+            "- Generated: {}",
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        let _ = writeln!(out, "- Observed: {:.0}s", self.duration_secs);
+        let _ = writeln!(
+            out,
+            "- Traffic: {} messages, {} bytes, {} topics\n",
+            self.total_messages,
+            self.total_bytes,
+            self.topics.len()
+        );
+
+        let _ = writeln!(out, "## Prefixes\n");
+        let _ = writeln!(out, "| Prefix | Topics | Messages | Bytes | msg/s |");
+        let _ = writeln!(out, "|--------|-------:|---------:|------:|------:|");
+        for p in &self.prefixes {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} | {:.2} |",
+                p.prefix, p.topics, p.messages, p.bytes, p.rate
+            );
+        }
+
+        let _ = writeln!(out, "\n## Topics\n");
+        let _ = writeln!(
+            out,
+            "| Topic | Messages | msg/s | Min B | Avg B | Max B | Schema |"
+        );
+        let _ = writeln!(
+            out,
+            "|-------|---------:|------:|------:|------:|------:|--------|"
+        );
+        for t in &self.topics {
+            let schema = t
+                .schema
+                .iter()
+                .map(|(path, kind)| format!("`{}`: {}", path, kind))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {:.2} | {} | {} | {} | {} |",
+                t.topic,
+                t.messages,
+                t.rate,
+                optional(t.min_payload),
+                t.avg_payload
+                    .map_or_else(|| "-".to_string(), |avg| format!("{:.0}", avg)),
+                optional(t.max_payload),
+                if schema.is_empty() { "-" } else { &schema }
+            );
+        }
+
+        let _ = writeln!(out, "\n## Devices\n");
+        if self.devices.is_empty() {
+            let _ = writeln!(out, "No devices detected.");
+        } else {
+            let _ = writeln!(out, "| Device | Type | Messages | Topics |");
+            let _ = writeln!(out, "|--------|------|---------:|-------:|");
+            for d in &self.devices {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} |",
+                    d.device_id,
+                    d.device_type.as_deref().unwrap_or("-"),
+                    d.messages,
+                    d.topics.len()
+                );
+            }
+        }
+        out
+    }
+}
+
+fn optional(value: Option<usize>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_from_messages() {
+        let mut builder = ReportBuilder::new('/');
+        for (topic, payload) in [
+            ("telemetry/dev1/meter", r#"{"power":10}"#),
+            ("telemetry/dev1/meter", r#"{"power":12.5,"ok":true}"#),
+            ("status/dev1", "online"),
+        ] {
+            builder.observe(&MqttMessage::new(
+                topic.to_string(),
+                payload.as_bytes().to_vec(),
+                0,
+                false,
+            ));
+        }
+        let report = builder.finish("test".to_string(), 10.0);
+
+        assert_eq!(report.total_messages, 3);
+        assert_eq!(report.prefixes[0].prefix, "telemetry");
+        assert!((report.prefixes[0].rate - 0.2).abs() < f64::EPSILON);
+
+        let meter = report
+            .topics
+            .iter()
+            .find(|t| t.topic == "telemetry/dev1/meter")
+            .unwrap();
+        assert_eq!(meter.min_payload, Some(12));
+        assert_eq!(meter.max_payload, Some(24));
+        assert_eq!(meter.schema.get("ok").map(String::as_str), Some("bool"));
+        assert_eq!(report.devices.len(), 1);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| `telemetry` | 1 | 2 |"));
+        assert!(report
+            .render(ReportFormat::Json)
+            .unwrap()
+            .contains("\"devices\""));
+    }
+}
@@ -0,0 +1,237 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::state::response_watcher::parse_delay;
+
+/// An ordered list of publishes with delays between them, e.g. the
+/// messages of a device provisioning flow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    /// Wait before this publish, in milliseconds
+    #[serde(default)]
+    pub delay_ms: u64,
+    pub topic: String,
+    #[serde(default)]
+    pub payload: String,
+    #[serde(default)]
+    pub qos: u8,
+    #[serde(default)]
+    pub retain: bool,
+}
+
+impl Scenario {
+    /// Parse the step editor's text: one step per line as
+    /// `[+DELAY] [qos=N] [retain] TOPIC [PAYLOAD]`, `#` starts a comment line
+    pub fn parse_steps(text: &str) -> Result<Vec<ScenarioStep>> {
+        let mut steps = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let step = parse_step(line).with_context(|| format!("Line {}", number + 1))?;
+            steps.push(step);
+        }
+        if steps.is_empty() {
+            bail!("A scenario needs at least one step");
+        }
+        Ok(steps)
+    }
+
+    /// Steps in the editor's text form, the inverse of [`Scenario::parse_steps`]
+    pub fn steps_text(&self) -> String {
+        self.steps
+            .iter()
+            .map(|step| {
+                let mut line = String::new();
+                if step.delay_ms > 0 {
+                    line.push_str(&format!("+{} ", format_delay(step.delay_ms)));
+                }
+                if step.qos > 0 {
+                    line.push_str(&format!("qos={} ", step.qos));
+                }
+                if step.retain {
+                    line.push_str("retain ");
+                }
+                line.push_str(&step.topic);
+                if !step.payload.is_empty() {
+                    line.push(' ');
+                    line.push_str(&step.payload);
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Time from start to the last publish
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.steps.iter().map(|step| step.delay_ms).sum())
+    }
+}
+
+fn parse_step(line: &str) -> Result<ScenarioStep> {
+    let mut rest = line;
+    let mut step = ScenarioStep {
+        delay_ms: 0,
+        topic: String::new(),
+        payload: String::new(),
+        qos: 0,
+        retain: false,
+    };
+    loop {
+        let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if let Some(delay) = word.strip_prefix('+') {
+            step.delay_ms = parse_delay(delay).context("Step delay")?;
+        } else if let Some(qos) = word.strip_prefix("qos=") {
+            step.qos = match qos {
+                "0" => 0,
+                "1" => 1,
+                "2" => 2,
+                _ => bail!("QoS must be 0, 1 or 2, got '{}'", qos),
+            };
+        } else if word == "retain" {
+            step.retain = true;
+        } else {
+            if word.is_empty() {
+                bail!("Missing topic");
+            }
+            step.topic = word.to_string();
+            step.payload = tail.trim().to_string();
+            return Ok(step);
+        }
+        rest = tail.trim_start();
+    }
+}
+
+fn format_delay(ms: u64) -> String {
+    if ms.is_multiple_of(60_000) {
+        format!("{}m", ms / 60_000)
+    } else if ms.is_multiple_of(1000) {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+/// A scenario being played back, one step at a time
+#[derive(Debug, Clone)]
+pub struct ScenarioRun {
+    pub name: String,
+    steps: Vec<ScenarioStep>,
+    /// Index of the next step to publish
+    next: usize,
+    next_at: Instant,
+}
+
+impl ScenarioRun {
+    pub fn start(scenario: &Scenario, now: Instant) -> Self {
+        let first_delay = scenario.steps.first().map_or(0, |step| step.delay_ms);
+        Self {
+            name: scenario.name.clone(),
+            steps: scenario.steps.clone(),
+            next: 0,
+            next_at: now + Duration::from_millis(first_delay),
+        }
+    }
+
+    /// The next step if its delay has passed; the following step's delay
+    /// counts from `now`
+    pub fn due(&mut self, now: Instant) -> Option<ScenarioStep> {
+        if now < self.next_at {
+            return None;
+        }
+        let step = self.steps.get(self.next)?.clone();
+        self.next += 1;
+        if let Some(following) = self.steps.get(self.next) {
+            self.next_at = now + Duration::from_millis(following.delay_ms);
+        }
+        Some(step)
+    }
+
+    /// Steps published so far
+    pub fn sent(&self) -> usize {
+        self.next
+    }
+
+    pub fn total(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.steps.len()
+    }
+
+    /// Step waiting to go out, with the time left before it does
+    pub fn upcoming(&self, now: Instant) -> Option<(&ScenarioStep, Duration)> {
+        let step = self.steps.get(self.next)?;
+        Some((step, self.next_at.saturating_duration_since(now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_steps() {
+        let text = "# provision\n\
+                    dev/42/hello\n\
+                    +500ms qos=1 dev/42/config {\"wifi\": \"lab\"}\n\
+                    +2s qos=2 retain dev/42/state active\n";
+        let steps = Scenario::parse_steps(text).unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].topic, "dev/42/hello");
+        assert_eq!(steps[0].payload, "");
+        assert_eq!(steps[1].delay_ms, 500);
+        assert_eq!(steps[1].qos, 1);
+        assert_eq!(steps[1].payload, "{\"wifi\": \"lab\"}");
+        assert_eq!(steps[2].delay_ms, 2000);
+        assert!(steps[2].retain);
+
+        let scenario = Scenario {
+            name: "provision".to_string(),
+            steps,
+        };
+        assert_eq!(scenario.duration(), Duration::from_millis(2500));
+        assert_eq!(
+            Scenario::parse_steps(&scenario.steps_text()).unwrap(),
+            scenario.steps
+        );
+
+        let err = Scenario::parse_steps("dev/a\n+soon dev/b").unwrap_err();
+        assert_eq!(err.to_string(), "Line 2");
+        assert!(format!("{:#}", err)
+            .starts_with("Line 2: Step delay: Bad duration 'soon', use e.g. 500ms or 2s"));
+        assert!(Scenario::parse_steps("qos=3 dev/a").is_err());
+        assert!(Scenario::parse_steps("# only a comment").is_err());
+    }
+
+    #[test]
+    fn test_run_waits_between_steps() {
+        let scenario = Scenario {
+            name: "flow".to_string(),
+            steps: Scenario::parse_steps("a\n+1s b\n+1s c").unwrap(),
+        };
+        let start = Instant::now();
+        let mut run = ScenarioRun::start(&scenario, start);
+
+        assert_eq!(run.due(start).unwrap().topic, "a");
+        assert!(run.due(start + Duration::from_millis(900)).is_none());
+        let later = start + Duration::from_millis(1200);
+        assert_eq!(run.due(later).unwrap().topic, "b");
+        // The next delay counts from when the previous step went out
+        assert!(run.due(later + Duration::from_millis(900)).is_none());
+        assert_eq!(run.due(later + Duration::from_secs(1)).unwrap().topic, "c");
+        assert!(run.is_finished());
+        assert_eq!(run.sent(), 3);
+    }
+}
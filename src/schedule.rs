@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Local, TimeDelta, TimeZone, Timelike};
+
+use crate::persistence::Bookmark;
+
+/// When a scheduled bookmark publishes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// Fixed interval, e.g. `30s`, `every 5m`, `every 2 minutes`
+    Every(Duration),
+    /// Five-field cron expression (`minute hour day month weekday`)
+    Cron(CronSpec),
+}
+
+impl Schedule {
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.split_whitespace().count() == 5 {
+            return CronSpec::parse(input).map(Schedule::Cron);
+        }
+
+        let spec = input.strip_prefix("every").unwrap_or(input).trim();
+        let split = spec
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(spec.len());
+        let (count, unit) = spec.split_at(split);
+        let count: u64 = count.parse().with_context(|| {
+            format!("Expected e.g. '30s', '5m' or a cron line, got '{}'", input)
+        })?;
+        let unit_secs = match unit.trim() {
+            "" | "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hour" | "hours" => 3600,
+            other => bail!("Unknown unit '{}' (use s, m or h)", other),
+        };
+        let Some(secs) = count.checked_mul(unit_secs) else {
+            bail!("Interval '{}' is too long", input);
+        };
+        if secs == 0 {
+            bail!("Interval must be at least one second");
+        }
+        Ok(Schedule::Every(Duration::from_secs(secs)))
+    }
+
+    /// Next run strictly after `after`
+    pub fn next_after<Tz: TimeZone>(&self, after: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        match self {
+            Schedule::Every(interval) => Some(after + TimeDelta::from_std(*interval).ok()?),
+            Schedule::Cron(spec) => spec.next_after(after),
+        }
+    }
+}
+
+/// Parsed cron fields as bitmasks; supports `*`, lists, ranges and steps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSpec {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month and weekday both restricted: either may match, like cron
+    day_or_weekday: bool,
+}
+
+impl CronSpec {
+    pub fn parse(input: &str) -> Result<Self> {
+        let fields: Vec<&str> = input.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            bail!("Cron needs 5 fields: minute hour day month weekday");
+        };
+        let mut weekdays = parse_field(weekday, 0, 7).context("weekday")?;
+        // Both 0 and 7 mean Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59).context("minute")?,
+            hours: parse_field(hour, 0, 23).context("hour")?,
+            days: parse_field(day, 1, 31).context("day")?,
+            months: parse_field(month, 1, 12).context("month")?,
+            weekdays,
+            day_or_weekday: day != "*" && weekday != "*",
+        })
+    }
+
+    fn matches<Tz: TimeZone>(&self, t: &DateTime<Tz>) -> bool {
+        let bit = |mask: u64, value: u32| mask & (1 << value) != 0;
+        let day = bit(self.days, t.day());
+        let weekday = bit(self.weekdays, t.weekday().num_days_from_sunday());
+        let day_matches = if self.day_or_weekday {
+            day || weekday
+        } else {
+            day && weekday
+        };
+        bit(self.minutes, t.minute())
+            && bit(self.hours, t.hour())
+            && bit(self.months, t.month())
+            && day_matches
+    }
+
+    fn next_after<Tz: TimeZone>(&self, after: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)?;
+        // Give up after a year without a match (e.g. February 30th)
+        for _ in 0..366 * 24 * 60 {
+            t += TimeDelta::minutes(1);
+            if self.matches(&t) {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().context("bad step")?),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (a.parse()?, b.parse()?),
+                None => {
+                    let value: u32 = range.parse()?;
+                    // `5/15` means from 5 to the end in steps of 15
+                    (value, if item.contains('/') { max } else { value })
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            bail!("'{}' is outside {}-{}", item, min, max);
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+/// A bookmark publishing on a schedule. Bookmarks are looked up by folder and
+/// name on each run so edits apply to the next publish.
+#[derive(Debug, Clone)]
+pub struct ActiveSchedule {
+    pub folder: Option<String>,
+    pub name: String,
+    /// What the user typed, for display
+    pub spec: String,
+    pub schedule: Schedule,
+    pub next_run: DateTime<Local>,
+    pub runs: u64,
+}
+
+impl ActiveSchedule {
+    pub fn start(bookmark: &Bookmark, spec: &str) -> Result<Self> {
+        let schedule = Schedule::parse(spec)?;
+        let next_run = schedule
+            .next_after(Local::now())
+            .context("Schedule never fires")?;
+        Ok(Self {
+            folder: bookmark.category.clone(),
+            name: bookmark.name.clone(),
+            spec: spec.trim().to_string(),
+            schedule,
+            next_run,
+            runs: 0,
+        })
+    }
+
+    pub fn matches(&self, bookmark: &Bookmark) -> bool {
+        bookmark.name == self.name && bookmark.category == self.folder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        let every = |secs| Schedule::Every(Duration::from_secs(secs));
+        assert_eq!(Schedule::parse("30s").unwrap(), every(30));
+        assert_eq!(Schedule::parse("every 5m").unwrap(), every(300));
+        assert_eq!(Schedule::parse("every 2 minutes").unwrap(), every(120));
+        assert_eq!(Schedule::parse("1h").unwrap(), every(3600));
+        assert!(Schedule::parse("0s").is_err());
+        assert!(Schedule::parse("5 days").is_err());
+        assert!(Schedule::parse("soon").is_err());
+        assert!(Schedule::parse("9999999999999999h").is_err());
+
+        let next = every(30).next_after(at("2024-01-01T12:00:00Z")).unwrap();
+        assert_eq!(next, at("2024-01-01T12:00:30Z"));
+    }
+
+    #[test]
+    fn test_cron_next_run() {
+        let next = |spec: &str, after: &str| {
+            Schedule::parse(spec)
+                .unwrap()
+                .next_after(at(after))
+                .unwrap()
+        };
+        assert_eq!(
+            next("*/15 * * * *", "2024-01-01T12:07:30Z"),
+            at("2024-01-01T12:15:00Z")
+        );
+        assert_eq!(
+            next("0 9 * * 1-5", "2024-01-05T09:00:00Z"),
+            at("2024-01-08T09:00:00Z")
+        );
+        // Sunday as 7
+        assert_eq!(
+            next("30 6 * * 7", "2024-01-01T00:00:00Z"),
+            at("2024-01-07T06:30:00Z")
+        );
+        assert!(Schedule::parse("61 * * * *").is_err());
+        assert_eq!(
+            Schedule::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(at("2024-01-01T00:00:00Z")),
+            None
+        );
+    }
+}
@@ -0,0 +1,557 @@
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::broker::BrokerKind;
+use crate::mqtt::MqttMessage;
+use crate::state::device_tracker::DeviceHealth;
+use crate::state::metric_tracker::{
+    compile_metric_regex, MetricChart, MetricDisplay, MetricPoint, TrackedMetric,
+};
+use crate::state::{BufferedMessage, StateTimeline};
+
+/// Snapshot format version, bumped on incompatible changes
+pub const SESSION_VERSION: u32 = 1;
+
+/// A frozen copy of everything shown on screen: topic tree, buffered messages,
+/// tracked metrics and states, device health and stats. Saved as JSON so it can be shared
+/// and reopened later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub version: u32,
+    pub saved_at: DateTime<Utc>,
+    pub broker: BrokerKind,
+    /// Name of the server the session was captured from
+    #[serde(default)]
+    pub server: Option<String>,
+    #[serde(default)]
+    pub selected_topic: Option<String>,
+    #[serde(default)]
+    pub expanded_topics: Vec<String>,
+    #[serde(default)]
+    pub topic_filter: Option<String>,
+    pub stats: StatsSnapshot,
+    #[serde(default)]
+    pub topics: Vec<TopicSnapshot>,
+    #[serde(default)]
+    pub messages: Vec<MessageSnapshot>,
+    #[serde(default)]
+    pub metrics: Vec<MetricSnapshot>,
+    #[serde(default)]
+    pub charts: Vec<MetricChart>,
+    #[serde(default)]
+    pub states: Vec<StateSnapshot>,
+    #[serde(default)]
+    pub devices: Vec<DeviceSnapshot>,
+}
+
+/// All-time counters from the stats panel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    pub total_messages: u64,
+    pub total_bytes: u64,
+    pub uptime_secs: u64,
+}
+
+/// Per-topic counters from the topic tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicSnapshot {
+    pub topic: String,
+    pub message_count: u64,
+    pub bytes_received: u64,
+    pub last_message_time: Option<i64>,
+}
+
+/// A buffered message. Binary payloads are stored hex-encoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageSnapshot {
+    pub topic: String,
+    pub payload: String,
+    #[serde(default)]
+    pub binary: bool,
+    pub qos: u8,
+    pub retain: bool,
+    pub timestamp: DateTime<Utc>,
+    /// Identical payloads folded into this message
+    #[serde(default)]
+    pub repeats: u32,
+    /// When the last repeat arrived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+/// A tracked metric with its history. Sample times are stored as age
+/// relative to `saved_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSnapshot {
+    pub label: String,
+    pub topic_pattern: String,
+    /// JSON field path, or the regex when `regex` is set
+    pub field_path: String,
+    #[serde(default)]
+    pub regex: bool,
+    pub points: Vec<PointSnapshot>,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub count: u64,
+    #[serde(default)]
+    pub display: MetricDisplay,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<usize>,
+}
+
+/// A point of metric history, with ages relative to `saved_at`. Sessions
+/// from before bucketed history hold plain `[age_ms, value]` samples.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PointSnapshot {
+    Bucket {
+        first_ms: u64,
+        last_ms: u64,
+        min: f64,
+        max: f64,
+        sum: f64,
+        count: u64,
+        last: f64,
+    },
+    Sample(u64, f64),
+}
+
+/// A tracked state with its changes as `[age_ms, state]`, relative to `saved_at`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub label: String,
+    pub topic_pattern: String,
+    /// JSON field path, empty for the whole payload
+    pub field_path: String,
+    pub changes: Vec<(u64, String)>,
+    pub count: u64,
+}
+
+/// Device health entry. Times are stored as age relative to `saved_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceSnapshot {
+    pub device_id: String,
+    pub device_type: Option<String>,
+    pub message_count: u64,
+    pub last_seen_ms: u64,
+    pub recent_messages_ms: Vec<u64>,
+    pub last_payload_size: usize,
+    pub topics: Vec<String>,
+    /// Last online/offline announcement on an availability topic
+    #[serde(default)]
+    pub online: Option<bool>,
+}
+
+impl SessionSnapshot {
+    /// Default file name for a new snapshot, e.g. `mqtop-session-20240101-120000.json`
+    pub fn default_filename() -> String {
+        format!(
+            "mqtop-session-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        )
+    }
+
+    /// Load a snapshot from disk
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session from {:?}", path))?;
+        let snapshot: Self =
+            serde_json::from_str(&contents).with_context(|| "Failed to parse session file")?;
+
+        if snapshot.version > SESSION_VERSION {
+            bail!(
+                "Session file version {} is newer than supported version {}",
+                snapshot.version,
+                SESSION_VERSION
+            );
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Save the snapshot to disk
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string(self).context("Failed to serialize session")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write session to {:?}", path))
+    }
+}
+
+impl MessageSnapshot {
+    /// Snapshot a buffered message together with its folded repeats
+    pub fn from_entry(entry: &BufferedMessage) -> Self {
+        Self {
+            repeats: entry.repeats,
+            last_seen: (entry.repeats > 0).then_some(entry.last_seen),
+            ..Self::from_message(&entry.message)
+        }
+    }
+
+    pub fn from_message(msg: &MqttMessage) -> Self {
+        let (payload, binary) = match msg.payload_str() {
+            Some(text) => (text.to_string(), false),
+            None => (encode_hex(&msg.payload), true),
+        };
+        Self {
+            topic: msg.topic.clone(),
+            payload,
+            binary,
+            qos: msg.qos,
+            retain: msg.retain,
+            timestamp: msg.timestamp,
+            repeats: 0,
+            last_seen: None,
+        }
+    }
+
+    pub fn into_message(self) -> Result<MqttMessage> {
+        let payload = if self.binary {
+            decode_hex(&self.payload)
+                .with_context(|| format!("Invalid binary payload for {}", self.topic))?
+        } else {
+            self.payload.into_bytes()
+        };
+        Ok(MqttMessage {
+            topic: self.topic,
+            payload: payload.into(),
+            qos: self.qos,
+            retain: self.retain,
+            dup: false,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
+impl MetricSnapshot {
+    pub fn from_metric(metric: &TrackedMetric, now: Instant) -> Self {
+        Self {
+            label: metric.label.clone(),
+            topic_pattern: metric.topic_pattern.clone(),
+            field_path: metric.field_path.clone(),
+            regex: metric.regex.is_some(),
+            points: metric
+                .data
+                .iter()
+                .map(|point| PointSnapshot::Bucket {
+                    first_ms: age_ms(now, point.first_at),
+                    last_ms: age_ms(now, point.last_at),
+                    min: point.min,
+                    max: point.max,
+                    sum: point.sum,
+                    count: point.count,
+                    last: point.last,
+                })
+                .collect(),
+            min: metric.min,
+            max: metric.max,
+            sum: metric.sum,
+            count: metric.count,
+            display: metric.display,
+            precision: metric.precision,
+        }
+    }
+
+    pub fn into_metric(self, now: Instant) -> TrackedMetric {
+        let mut metric = TrackedMetric::new(self.label, self.topic_pattern, self.field_path);
+        if self.regex {
+            metric.regex = compile_metric_regex(&metric.field_path).ok();
+        }
+        metric.data = self
+            .points
+            .into_iter()
+            .map(|point| match point {
+                PointSnapshot::Bucket {
+                    first_ms,
+                    last_ms,
+                    min,
+                    max,
+                    sum,
+                    count,
+                    last,
+                } => MetricPoint {
+                    first_at: instant_from_age(now, first_ms),
+                    last_at: instant_from_age(now, last_ms),
+                    min,
+                    max,
+                    sum,
+                    count,
+                    last,
+                },
+                PointSnapshot::Sample(age, value) => {
+                    MetricPoint::new(value, instant_from_age(now, age))
+                }
+            })
+            .collect();
+        metric.min = self.min;
+        metric.max = self.max;
+        metric.sum = self.sum;
+        metric.count = self.count;
+        metric.display = self.display;
+        metric.precision = self.precision;
+        metric
+    }
+}
+
+impl StateSnapshot {
+    pub fn from_timeline(timeline: &StateTimeline, now: Instant) -> Self {
+        Self {
+            label: timeline.label.clone(),
+            topic_pattern: timeline.topic_pattern.clone(),
+            field_path: timeline.field_path.clone(),
+            changes: timeline
+                .changes
+                .iter()
+                .map(|(at, state)| (age_ms(now, *at), state.clone()))
+                .collect(),
+            count: timeline.count,
+        }
+    }
+
+    pub fn into_timeline(self, now: Instant) -> StateTimeline {
+        let mut timeline = StateTimeline::new(self.label, self.topic_pattern, self.field_path);
+        timeline.changes = self
+            .changes
+            .into_iter()
+            .map(|(age, state)| (instant_from_age(now, age), state))
+            .collect();
+        timeline.count = self.count;
+        timeline
+    }
+}
+
+impl DeviceSnapshot {
+    pub fn from_device(device: &DeviceHealth, now: Instant) -> Self {
+        Self {
+            device_id: device.device_id.clone(),
+            device_type: device.device_type.clone(),
+            message_count: device.message_count,
+            last_seen_ms: age_ms(now, device.last_seen),
+            recent_messages_ms: device
+                .recent_messages
+                .iter()
+                .map(|time| age_ms(now, *time))
+                .collect(),
+            last_payload_size: device.last_payload_size,
+            topics: device.topics.clone(),
+            online: device.online,
+        }
+    }
+
+    /// Rebuild the device entry. Status is left as captured by the caller's tracker.
+    pub fn into_device(self, now: Instant) -> DeviceHealth {
+        let mut device = DeviceHealth::new(self.device_id);
+        device.device_type = self.device_type;
+        device.message_count = self.message_count;
+        device.last_seen = instant_from_age(now, self.last_seen_ms);
+        device.recent_messages = self
+            .recent_messages_ms
+            .into_iter()
+            .map(|age| instant_from_age(now, age))
+            .collect::<VecDeque<_>>();
+        device.last_payload_size = self.last_payload_size;
+        device.topics = self.topics;
+        device.online = self.online;
+        device
+    }
+}
+
+fn age_ms(now: Instant, time: Instant) -> u64 {
+    now.saturating_duration_since(time).as_millis() as u64
+}
+
+fn instant_from_age(now: Instant, age_ms: u64) -> Instant {
+    now.checked_sub(Duration::from_millis(age_ms))
+        .unwrap_or(now)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        bail!("Invalid hex string");
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let digits = std::str::from_utf8(pair).context("Invalid hex digit")?;
+            u8::from_str_radix(digits, 16).context("Invalid hex digit")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn empty_snapshot() -> SessionSnapshot {
+        SessionSnapshot {
+            version: SESSION_VERSION,
+            saved_at: Utc::now(),
+            broker: BrokerKind::Mqtt,
+            server: Some("local".to_string()),
+            selected_topic: None,
+            expanded_topics: Vec::new(),
+            topic_filter: None,
+            stats: StatsSnapshot::default(),
+            topics: Vec::new(),
+            messages: Vec::new(),
+            metrics: Vec::new(),
+            charts: Vec::new(),
+            states: Vec::new(),
+            devices: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let bytes = vec![0x00, 0xff, 0x10, 0xab];
+        assert_eq!(encode_hex(&bytes), "00ff10ab");
+        assert_eq!(decode_hex("00ff10ab").unwrap(), bytes);
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("zz").is_err());
+        assert!(decode_hex("ééé").is_err());
+    }
+
+    #[test]
+    fn test_message_snapshot_text_and_binary() {
+        let text = MqttMessage::new("a/b".to_string(), b"hello".to_vec(), 1, true);
+        let snap = MessageSnapshot::from_message(&text);
+        assert!(!snap.binary);
+        assert_eq!(snap.payload, "hello");
+        let restored = snap.into_message().unwrap();
+        assert_eq!(&restored.payload[..], b"hello");
+        assert_eq!(restored.qos, 1);
+        assert!(restored.retain);
+        assert_eq!(restored.timestamp, text.timestamp);
+
+        let binary = MqttMessage::new("a/c".to_string(), vec![0xff, 0xfe, 0x00], 0, false);
+        let snap = MessageSnapshot::from_message(&binary);
+        assert!(snap.binary);
+        assert_eq!(snap.into_message().unwrap().payload, vec![0xff, 0xfe, 0x00]);
+    }
+
+    #[test]
+    fn test_metric_snapshot_roundtrip() {
+        let mut metric = TrackedMetric::new("Power".into(), "meter/+".into(), "W".into());
+        metric.record(10.0, Duration::from_secs(3600), Duration::ZERO);
+        metric.record(30.0, Duration::from_secs(3600), Duration::ZERO);
+
+        let now = Instant::now();
+        let restored = MetricSnapshot::from_metric(&metric, now).into_metric(now);
+
+        assert_eq!(restored.label, "Power");
+        assert_eq!(restored.data.len(), 2);
+        assert_eq!(restored.latest(), Some(30.0));
+        assert_eq!(restored.min, 10.0);
+        assert_eq!(restored.max, 30.0);
+        assert_eq!(restored.count, 2);
+    }
+
+    #[test]
+    fn test_regex_metric_snapshot_roundtrip() {
+        let mut metric =
+            TrackedMetric::with_regex("temp".into(), "legacy/+".into(), r"temp=([\d.]+)").unwrap();
+        metric.record(21.5, Duration::from_secs(3600), Duration::ZERO);
+
+        let now = Instant::now();
+        let json = serde_json::to_string(&MetricSnapshot::from_metric(&metric, now)).unwrap();
+        let snapshot: MetricSnapshot = serde_json::from_str(&json).unwrap();
+        let restored = snapshot.into_metric(now);
+
+        assert_eq!(restored.field_path, r"temp=([\d.]+)");
+        assert!(restored.regex.is_some());
+        assert_eq!(restored.latest(), Some(21.5));
+    }
+
+    #[test]
+    fn test_state_snapshot_roundtrip() {
+        let start = Instant::now();
+        let mut timeline = StateTimeline::new("lwt".into(), "pumps/1/lwt".into(), String::new());
+        let hour = Duration::from_secs(3600);
+        timeline.record_at("online".into(), start, hour);
+        timeline.record_at("offline".into(), start + Duration::from_secs(30), hour);
+
+        let now = start + Duration::from_secs(40);
+        let snapshot = StateSnapshot::from_timeline(&timeline, now);
+        assert_eq!(snapshot.changes[0], (40_000, "online".to_string()));
+        let restored = snapshot.into_timeline(now);
+
+        assert_eq!(restored.changes, timeline.changes);
+        assert_eq!(restored.count, 2);
+        assert_eq!(restored.uptime(now), Some(75.0));
+    }
+
+    #[test]
+    fn test_metric_snapshot_reads_plain_samples() {
+        let json = r#"{"label":"Power","topic_pattern":"meter/+","field_path":"W",
+            "points":[[2000,10.0],[1000,30.0]],"min":10.0,"max":30.0,"sum":40.0,"count":2}"#;
+        let snapshot: MetricSnapshot = serde_json::from_str(json).unwrap();
+        let metric = snapshot.into_metric(Instant::now());
+
+        assert_eq!(metric.data.len(), 2);
+        assert_eq!(metric.latest(), Some(30.0));
+        assert_eq!(metric.data[0].count, 1);
+    }
+
+    #[test]
+    fn test_device_snapshot_preserves_age() {
+        let now = Instant::now();
+        let mut device = DeviceHealth::new("dev-1".to_string());
+        device.message_count = 7;
+        device.last_seen = now - Duration::from_secs(30);
+        device.topics.push("telemetry/dev-1/meter".to_string());
+        device.online = Some(false);
+
+        let restored = DeviceSnapshot::from_device(&device, now).into_device(now);
+
+        assert_eq!(restored.device_id, "dev-1");
+        assert_eq!(restored.message_count, 7);
+        assert_eq!(restored.topics, device.topics);
+        assert_eq!(restored.online, Some(false));
+        assert!(restored.time_since_last() >= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.json");
+
+        let mut snapshot = empty_snapshot();
+        snapshot.topics.push(TopicSnapshot {
+            topic: "sensors/temp".to_string(),
+            message_count: 3,
+            bytes_received: 12,
+            last_message_time: Some(1),
+        });
+        snapshot.save_to(&path).unwrap();
+
+        let loaded = SessionSnapshot::load_from(&path).unwrap();
+        assert_eq!(loaded.broker, BrokerKind::Mqtt);
+        assert_eq!(loaded.server.as_deref(), Some("local"));
+        assert_eq!(loaded.topics.len(), 1);
+        assert_eq!(loaded.topics[0].message_count, 3);
+    }
+
+    #[test]
+    fn test_load_rejects_newer_version() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.json");
+
+        let mut snapshot = empty_snapshot();
+        snapshot.version = SESSION_VERSION + 1;
+        snapshot.save_to(&path).unwrap();
+
+        assert!(SessionSnapshot::load_from(&path).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tracing::{debug, info};
+
+/// Streams rendered frames to read-only viewers over plain TCP.
+///
+/// Viewers connect with `telnet` or `nc` and see the same screen as the host,
+/// without needing broker credentials. Anything a viewer types is ignored.
+pub struct ShareServer {
+    frames: watch::Sender<Arc<String>>,
+    local_addr: SocketAddr,
+}
+
+impl ShareServer {
+    /// Bind the listener and start accepting viewers in the background
+    pub async fn bind(addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind share server on {}", addr))?;
+        let local_addr = listener.local_addr()?;
+        let (frames, template) = watch::channel(Arc::new(String::new()));
+
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        info!("Share viewer connected from {}", peer);
+                        let frames = template.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_viewer(stream, frames).await {
+                                debug!("Share viewer {} disconnected: {:?}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        debug!("Share accept failed: {:?}", e);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { frames, local_addr })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Number of connected viewers
+    pub fn viewer_count(&self) -> usize {
+        // The accept loop holds one receiver as a template for new viewers
+        self.frames.receiver_count().saturating_sub(1)
+    }
+
+    /// Send an ANSI-rendered frame to all viewers
+    pub fn publish(&self, frame: String) {
+        self.frames.send_replace(Arc::new(frame));
+    }
+}
+
+async fn serve_viewer(
+    mut stream: TcpStream,
+    mut frames: watch::Receiver<Arc<String>>,
+) -> Result<()> {
+    // Hide cursor and clear the screen once, then redraw from the top-left on each frame
+    stream.write_all(b"\x1b[?25l\x1b[2J").await?;
+
+    loop {
+        let frame = frames.borrow_and_update().clone();
+        stream.write_all(b"\x1b[H").await?;
+        stream
+            .write_all(frame.replace('\n', "\r\n").as_bytes())
+            .await?;
+
+        if frames.changed().await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_viewer_receives_frames() {
+        let server = ShareServer::bind("127.0.0.1:0").await.unwrap();
+        let mut viewer = TcpStream::connect(server.local_addr()).await.unwrap();
+
+        // Wait for the accept loop to register the viewer
+        for _ in 0..50 {
+            if server.viewer_count() == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(server.viewer_count(), 1);
+
+        server.publish("hello\nworld\n".to_string());
+
+        let mut received = Vec::new();
+        let mut buf = [0u8; 256];
+        while !String::from_utf8_lossy(&received).contains("world") {
+            let n = tokio::time::timeout(std::time::Duration::from_secs(2), viewer.read(&mut buf))
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(n > 0, "connection closed early");
+            received.extend_from_slice(&buf[..n]);
+        }
+
+        assert!(String::from_utf8_lossy(&received).contains("hello\r\nworld\r\n"));
+    }
+}
@@ -1,15 +0,0 @@
-pub mod device_tracker;
-pub mod latency_tracker;
-pub mod message_buffer;
-pub mod metric_tracker;
-pub mod schema_tracker;
-pub mod stats;
-pub mod topic_tree;
-
-pub use device_tracker::{DeviceTracker, HealthStatus};
-pub use latency_tracker::LatencyTracker;
-pub use message_buffer::MessageBuffer;
-pub use metric_tracker::{get_numeric_fields, render_sparkline, MetricTracker};
-pub use schema_tracker::SchemaTracker;
-pub use stats::Stats;
-pub use topic_tree::{TopicInfo, TopicTree};
@@ -0,0 +1,207 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: &str = "...";
+
+/// Display width in terminal cells
+pub fn width(s: &str) -> usize {
+    s.width()
+}
+
+/// Longest prefix of `s` that fits in `max_width` cells
+pub fn truncate_width(s: &str, max_width: usize) -> &str {
+    let mut used = 0;
+    for (idx, grapheme) in s.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > max_width {
+            return &s[..idx];
+        }
+    }
+    s
+}
+
+/// Longest suffix of `s` that fits in `max_width` cells
+pub fn truncate_width_start(s: &str, max_width: usize) -> &str {
+    let mut used = 0;
+    for (idx, grapheme) in s.grapheme_indices(true).rev() {
+        used += grapheme.width();
+        if used > max_width {
+            return &s[idx + grapheme.len()..];
+        }
+    }
+    s
+}
+
+/// Fit `s` in `max_width` cells, ending with `...` when cut
+pub fn ellipsize(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let keep = max_width.saturating_sub(ELLIPSIS.len());
+    format!("{}{}", truncate_width(s, keep), ELLIPSIS)
+}
+
+/// Fit `s` in `max_width` cells, starting with `...` when cut (keeps the tail of a topic)
+pub fn ellipsize_start(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let keep = max_width.saturating_sub(ELLIPSIS.len());
+    format!("{}{}", ELLIPSIS, truncate_width_start(s, keep))
+}
+
+/// Truncate or right-pad `s` to exactly `cells` wide
+pub fn fit_width(s: &str, cells: usize) -> String {
+    let cut = truncate_width(s, cells);
+    format!("{}{}", cut, " ".repeat(cells - cut.width()))
+}
+
+// Cursors are byte offsets into the edited string, kept on grapheme boundaries
+
+/// Clamp a cursor into `s` and back onto a char boundary
+pub fn clamp_cursor(s: &str, cursor: usize) -> usize {
+    let mut cursor = cursor.min(s.len());
+    while !s.is_char_boundary(cursor) {
+        cursor -= 1;
+    }
+    cursor
+}
+
+/// Byte offset of the grapheme boundary before `cursor`
+pub fn prev_boundary(s: &str, cursor: usize) -> usize {
+    let cursor = clamp_cursor(s, cursor);
+    s[..cursor]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(idx, _)| idx)
+}
+
+/// Byte offset of the grapheme boundary after `cursor`
+pub fn next_boundary(s: &str, cursor: usize) -> usize {
+    let cursor = clamp_cursor(s, cursor);
+    s[cursor..]
+        .graphemes(true)
+        .next()
+        .map_or(cursor, |g| cursor + g.len())
+}
+
+/// Insert `ch` at `cursor`, returning the cursor after it
+pub fn insert_char(s: &mut String, cursor: usize, ch: char) -> usize {
+    let cursor = clamp_cursor(s, cursor);
+    s.insert(cursor, ch);
+    cursor + ch.len_utf8()
+}
+
+/// Remove the grapheme before `cursor`, returning the new cursor
+pub fn remove_before(s: &mut String, cursor: usize) -> usize {
+    let end = clamp_cursor(s, cursor);
+    let start = prev_boundary(s, end);
+    s.replace_range(start..end, "");
+    start
+}
+
+/// Remove the grapheme at `cursor`
+pub fn remove_at(s: &mut String, cursor: usize) {
+    let start = clamp_cursor(s, cursor);
+    let end = next_boundary(s, start);
+    s.replace_range(start..end, "");
+}
+
+/// ASCII stand-in for a decorative glyph (box drawing, blocks, arrows, shapes,
+/// symbols, braille) so terminals without those glyphs still line up. Other
+/// text, such as accented letters in payloads, has no stand-in.
+pub fn ascii_glyph(ch: char) -> Option<char> {
+    const SPARK: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+    let replacement = match ch {
+        '─' | '━' | '═' | '╌' | '┄' => '-',
+        '│' | '┃' | '║' | '╎' | '┆' | '▌' | '▐' => '|',
+        '\u{2500}'..='\u{257F}' => '+',
+        '▁'..='█' => SPARK[ch as usize - '▁' as usize],
+        '░' => '.',
+        '▒' => ':',
+        '▓' => '#',
+        '▶' | '▸' | '►' | '→' | '⇒' | '›' => '>',
+        '◀' | '◂' | '◄' | '←' | '⇐' => '<',
+        '▲' | '▴' | '↑' => '^',
+        '▼' | '▾' | '↓' => 'v',
+        '⇄' | '↔' => '~',
+        '★' | '•' => '*',
+        '☆' => '-',
+        '●' => 'O',
+        '○' => 'o',
+        '◌' | '·' | '…' => '.',
+        '⏱' => '@',
+        '⚠' => '!',
+        '✎' => '~',
+        '✓' | '✔' => '+',
+        '✗' | '✘' | '×' => 'x',
+        '≠' => '#',
+        '®' => 'R',
+        '⧉' => '=',
+        '—' | '–' => '-',
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28FF}' => ':',
+        '\u{2190}'..='\u{21FF}'
+        | '\u{2300}'..='\u{23FF}'
+        | '\u{2580}'..='\u{25FF}'
+        | '\u{2600}'..='\u{27BF}' => '?',
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_by_width() {
+        assert_eq!(truncate_width("sensors/temp", 7), "sensors");
+        // Wide CJK characters take two cells each
+        assert_eq!(truncate_width("温度/室内", 5), "温度/");
+        // Never splits a combining sequence
+        assert_eq!(truncate_width("cafe\u{301}!", 4), "cafe\u{301}");
+        assert_eq!(truncate_width_start("a/b/温度", 5), "/温度");
+
+        assert_eq!(ellipsize("välkommen", 7), "välk...");
+        assert_eq!(ellipsize("kort", 7), "kort");
+        assert_eq!(ellipsize_start("home/kök/temp", 9), "...k/temp");
+        assert_eq!(fit_width("温度", 5), "温度 ");
+    }
+
+    #[test]
+    fn test_ascii_glyph() {
+        let ascii = |s: &str| {
+            s.chars()
+                .map(|ch| ascii_glyph(ch).unwrap_or(ch))
+                .collect::<String>()
+        };
+        assert_eq!(ascii("┌─┐│└┘"), "+-+|++");
+        assert_eq!(ascii("▁▂▃▄▅▆▇█"), "_.-~=+*#");
+        assert_eq!(ascii("▶ ★ ● ○ ◌"), "> * O o .");
+        // Text is left alone, only decoration changes
+        assert_eq!(ascii("välkommen 温度"), "välkommen 温度");
+        assert_eq!(ascii("⣿⠀"), ": ");
+    }
+
+    #[test]
+    fn test_cursor_editing() {
+        let mut s = String::from("aé");
+        let mut cursor = s.len();
+        cursor = prev_boundary(&s, cursor);
+        assert_eq!(cursor, 1);
+        cursor = insert_char(&mut s, cursor, 'ö');
+        assert_eq!(s, "aöé");
+        assert_eq!(cursor, 3);
+
+        cursor = remove_before(&mut s, cursor);
+        assert_eq!((s.as_str(), cursor), ("aé", 1));
+        remove_at(&mut s, cursor);
+        assert_eq!(s, "a");
+
+        // Stale cursors are clamped instead of panicking
+        assert_eq!(remove_before(&mut s, 10), 0);
+        assert_eq!(next_boundary("é", 1), 2);
+        assert_eq!(clamp_cursor("é", 1), 0);
+    }
+}
@@ -0,0 +1,139 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::text::ascii_glyph;
+
+/// Render a region of a frame buffer as text with ANSI SGR color escapes.
+/// Lines are separated by `\n` and every line ends with a style reset.
+pub fn buffer_to_ansi(buffer: &Buffer, area: Rect) -> String {
+    let area = area.intersection(buffer.area);
+    let mut output = String::new();
+
+    for y in area.top()..area.bottom() {
+        let mut current: Option<(Color, Color, Modifier)> = None;
+        let mut skip = 0;
+
+        for x in area.left()..area.right() {
+            let cell = &buffer[(x, y)];
+            // Cells hidden behind a preceding wide character
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                output.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                current = Some(style);
+            }
+            output.push_str(cell.symbol());
+            skip = cell.symbol().width().saturating_sub(1);
+        }
+
+        output.push_str("\x1b[0m\n");
+    }
+
+    output
+}
+
+/// Render a region of a frame buffer as plain text with trailing spaces trimmed
+pub fn buffer_to_text(buffer: &Buffer, area: Rect) -> String {
+    let area = area.intersection(buffer.area);
+    let mut output = String::new();
+
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        let mut skip = 0;
+
+        for x in area.left()..area.right() {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let symbol = buffer[(x, y)].symbol();
+            line.push_str(symbol);
+            skip = symbol.width().saturating_sub(1);
+        }
+
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Swap decorative glyphs for ASCII, cell by cell, for fonts without them.
+/// A wide glyph leaves its hidden cell blank, so columns stay where they were.
+/// With `blank_borders`, box drawing becomes spaces so screen readers don't
+/// spell out rows of dashes.
+pub fn asciify_buffer(buffer: &mut Buffer, blank_borders: bool) {
+    for cell in buffer.content.iter_mut() {
+        let mut chars = cell.symbol().chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            if blank_borders && ('\u{2500}'..='\u{257F}').contains(&ch) {
+                cell.set_char(' ');
+            } else if let Some(replacement) = ascii_glyph(ch) {
+                cell.set_char(replacement);
+            }
+        }
+    }
+}
+
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+
+    if let Some(code) = color_code(fg, false) {
+        codes.push(code);
+    }
+    if let Some(code) = color_code(bg, true) {
+        codes.push(code);
+    }
+
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let offset = if background { 10 } else { 0 };
+    let basic = |code: u8| Some((code + offset).to_string());
+
+    match color {
+        Color::Reset => None,
+        Color::Black => basic(30),
+        Color::Red => basic(31),
+        Color::Green => basic(32),
+        Color::Yellow => basic(33),
+        Color::Blue => basic(34),
+        Color::Magenta => basic(35),
+        Color::Cyan => basic(36),
+        Color::Gray => basic(37),
+        Color::DarkGray => basic(90),
+        Color::LightRed => basic(91),
+        Color::LightGreen => basic(92),
+        Color::LightYellow => basic(93),
+        Color::LightBlue => basic(94),
+        Color::LightMagenta => basic(95),
+        Color::LightCyan => basic(96),
+        Color::White => basic(97),
+        Color::Indexed(i) => Some(format!("{};5;{}", 38 + offset, i)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", 38 + offset, r, g, b)),
+    }
+}
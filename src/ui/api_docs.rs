@@ -0,0 +1,131 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use super::widgets::{centered_rect, dialog_key_hint};
+use crate::app::App;
+use crate::asyncapi::MessageDoc;
+use crate::text::ellipsize_start;
+
+pub fn render_api_docs(frame: &mut Frame, app: &App) {
+    let (Some(spec), Some(topic)) = (&app.asyncapi, &app.selected_topic) else {
+        return;
+    };
+
+    let area = centered_rect(70, 75, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" API Docs: {} ", ellipsize_start(topic, 40)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{} (AsyncAPI {})", spec.title, spec.version),
+        Style::default().fg(Color::DarkGray),
+    ))];
+    lines.push(Line::from(""));
+
+    match spec.channel_for(topic) {
+        Some(channel) => {
+            lines.push(Line::from(vec![
+                Span::styled("Channel ", heading),
+                Span::styled(channel.address.clone(), Style::default().fg(Color::Cyan)),
+            ]));
+            if let Some(description) = &channel.description {
+                for line in description.lines() {
+                    lines.push(Line::from(line.to_string()));
+                }
+            }
+            if channel.messages.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No messages described",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            for message in &channel.messages {
+                lines.push(Line::from(""));
+                message_lines(message, &mut lines);
+            }
+        }
+        None => {
+            lines.push(Line::from(Span::styled(
+                "✗ Not covered by the spec",
+                Style::default().fg(Color::Magenta),
+            )));
+            lines.push(Line::from(Span::styled(
+                "No channel address matches this topic",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    let height = chunks[0].height as usize;
+    let scroll = app.api_docs_scroll.min(lines.len().saturating_sub(height));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll as u16, 0)),
+        chunks[0],
+    );
+
+    let mut hints = Vec::new();
+    hints.extend(dialog_key_hint("↑↓", "Scroll"));
+    hints.extend(dialog_key_hint("Esc", "Close"));
+    frame.render_widget(Paragraph::new(Line::from(hints)), chunks[1]);
+}
+
+fn message_lines(message: &MessageDoc, lines: &mut Vec<Line<'static>>) {
+    let mut title = vec![Span::styled(
+        format!("Message {}", message.name.as_deref().unwrap_or("(unnamed)")),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(summary) = &message.summary {
+        title.push(Span::styled(
+            format!("  {}", summary),
+            Style::default().fg(Color::White),
+        ));
+    }
+    lines.push(Line::from(title));
+
+    if let Some(payload) = &message.payload {
+        lines.push(Line::from(Span::styled(
+            "  Payload schema",
+            Style::default().fg(Color::DarkGray),
+        )));
+        json_lines(payload, Color::Green, lines);
+    }
+    for (i, example) in message.examples.iter().enumerate() {
+        lines.push(Line::from(Span::styled(
+            format!("  Example {}", i + 1),
+            Style::default().fg(Color::DarkGray),
+        )));
+        json_lines(example, Color::Cyan, lines);
+    }
+}
+
+fn json_lines(value: &serde_json::Value, color: Color, lines: &mut Vec<Line<'static>>) {
+    let text = serde_json::to_string_pretty(value).unwrap_or_default();
+    for line in text.lines() {
+        lines.push(Line::from(Span::styled(
+            format!("    {}", line),
+            Style::default().fg(color),
+        )));
+    }
+}
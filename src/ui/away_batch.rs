@@ -0,0 +1,87 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::centered_rect;
+use crate::app::App;
+use crate::text::ellipsize;
+
+/// Messages the broker queued while mqtop was away, apart from live traffic
+pub fn render_away_batch(frame: &mut Frame, app: &App) {
+    let Some(batch) = &app.away_batch else {
+        return;
+    };
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let zone = app.config.ui.timezone;
+    let block = Block::default()
+        .title(format!(
+            " While you were away {}–{} ({}) ",
+            zone.format(batch.from, "%H:%M:%S"),
+            zone.format(batch.to, "%H:%M:%S"),
+            batch.messages.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    // Last line explains the batch
+    let height = (inner.height as usize).saturating_sub(2);
+    let width = inner.width as usize;
+    let scroll = app
+        .away_batch_index
+        .saturating_sub(height.saturating_sub(1));
+
+    let mut lines: Vec<Line> = if batch.messages.is_empty() {
+        vec![Line::from(Span::styled(
+            "Nothing was queued",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        batch
+            .messages
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(height)
+            .map(|(i, msg)| {
+                let topic_style = if i == app.away_batch_index {
+                    Style::default()
+                        .fg(Color::White)
+                        .bg(Color::DarkGray)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Cyan)
+                };
+                let preview = msg
+                    .payload_str()
+                    .map(|s| s.lines().next().unwrap_or("").to_string())
+                    .unwrap_or_else(|| format!("<{} bytes binary>", msg.payload_size()));
+                let topic = ellipsize(&msg.topic, width / 2);
+                let room = width.saturating_sub(topic.chars().count() + 5);
+                Line::from(vec![
+                    Span::styled(format!("Q{} ", msg.qos), Style::default().fg(Color::Yellow)),
+                    Span::styled(topic, topic_style),
+                    Span::raw("  "),
+                    Span::raw(ellipsize(&preview, room)),
+                ])
+            })
+            .collect()
+    };
+
+    while lines.len() < height + 1 {
+        lines.push(Line::from(""));
+    }
+    lines.truncate(height + 1);
+    lines.push(Line::from(Span::styled(
+        "Arrival order; MQTT does not keep the time each was published",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
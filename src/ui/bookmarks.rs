@@ -1,5 +1,5 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
@@ -7,10 +7,14 @@ use ratatui::{
 };
 
 use super::widgets::{
-    centered_rect, dialog_key_hint, render_multiline_field, render_qos_field, render_retain_field,
-    render_text_field, truncate_safe,
+    centered_rect, dialog_key_hint, input_spans, render_multiline_field, render_qos_field,
+    render_retain_field, render_text_field,
 };
-use crate::app::{App, BookmarkField};
+use crate::app::{App, BookmarkField, BookmarkPrompt};
+use crate::persistence::BookmarkRow;
+use crate::text::ellipsize;
+
+const MAX_SCHEDULE_ROWS: usize = 4;
 
 pub fn render_bookmark_manager(frame: &mut Frame, app: &App) {
     // If editing, show the edit dialog instead
@@ -32,14 +36,23 @@ pub fn render_bookmark_manager(frame: &mut Frame, app: &App) {
     frame.render_widget(block.clone(), area);
     let inner = block.inner(area);
 
-    // Layout: list area + help text
+    // Layout: list area + active schedules + help text
+    let schedule_rows = app.bookmark_schedules.len().min(MAX_SCHEDULE_ROWS);
+    let schedule_height = if schedule_rows > 0 {
+        schedule_rows as u16 + 1
+    } else {
+        0
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
-        .constraints([Constraint::Min(3), Constraint::Length(2)])
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(schedule_height),
+            Constraint::Length(2),
+        ])
         .split(inner);
 
-    // Group bookmarks by category
     let bookmarks = &app.user_data.bookmarks;
 
     if bookmarks.is_empty() {
@@ -51,86 +64,158 @@ pub fn render_bookmark_manager(frame: &mut Frame, app: &App) {
             )),
             Line::from(""),
             Line::from(Span::styled(
-                "Press 'a' to add a new bookmark",
+                "Press 'a' to add a new bookmark or 'i' to import a file",
                 Style::default().fg(Color::Yellow),
             )),
         ])
         .alignment(ratatui::layout::Alignment::Center);
         frame.render_widget(empty_msg, chunks[0]);
     } else {
-        // Build list items grouped by category
-        let mut items: Vec<ListItem> = Vec::new();
-        let mut current_category: Option<Option<&String>> = None;
-
-        // Sort bookmarks by category for display
-        let mut indexed_bookmarks: Vec<(usize, &crate::persistence::Bookmark)> =
-            bookmarks.iter().enumerate().collect();
-        indexed_bookmarks.sort_by(|a, b| {
-            let cat_a = a.1.category.as_deref().unwrap_or("");
-            let cat_b = b.1.category.as_deref().unwrap_or("");
-            cat_a.cmp(cat_b)
-        });
-
-        // Map original indices to display indices
-        let mut display_to_original: Vec<usize> = Vec::new();
-
-        for (original_idx, bookmark) in &indexed_bookmarks {
-            let cat = bookmark.category.as_ref();
-
-            // Add category header if changed
-            if current_category != Some(cat) {
-                current_category = Some(cat);
-                let cat_name = cat.map(|s| s.as_str()).unwrap_or("uncategorized");
-                items.push(ListItem::new(Line::from(Span::styled(
-                    format!("[{}]", cat_name),
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
-                ))));
-            }
-
-            let is_selected = app.bookmark_manager.selected_index == *original_idx;
-            let style = if is_selected {
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            };
-
-            let prefix = if is_selected { "▶ " } else { "  " };
-
-            // Truncate topic if too long (safely handling UTF-8)
-            let max_topic_len = 30;
-            let topic_display = if bookmark.topic.len() > max_topic_len {
-                format!("{}...", truncate_safe(&bookmark.topic, max_topic_len - 3))
-            } else {
-                bookmark.topic.clone()
-            };
-
-            let line = Line::from(vec![
-                Span::styled(prefix, style),
-                Span::styled(&bookmark.name, style),
-                Span::styled("  ", Style::default()),
-                Span::styled(topic_display, Style::default().fg(Color::DarkGray)),
-            ]);
-
-            items.push(ListItem::new(line));
-            display_to_original.push(*original_idx);
-        }
-
-        let list = List::new(items);
-        frame.render_widget(list, chunks[0]);
+        let rows = app.bookmark_rows();
+        let visible = chunks[0].height as usize;
+        let selected = app.bookmark_manager.selected_index;
+        let offset = (selected + 1).saturating_sub(visible);
+
+        let items: Vec<ListItem> = rows
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(row_idx, row)| {
+                let is_selected = row_idx == selected;
+                let prefix = if is_selected { "▶ " } else { "  " };
+                let line = match row {
+                    BookmarkRow::Folder {
+                        path,
+                        depth,
+                        count,
+                        collapsed,
+                    } => {
+                        let name = path.rsplit('/').next().unwrap_or(path);
+                        let style = Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD);
+                        Line::from(vec![
+                            Span::styled(prefix, style),
+                            Span::raw("  ".repeat(*depth)),
+                            Span::styled(
+                                format!("{} {}/", if *collapsed { "▸" } else { "▾" }, name),
+                                if is_selected {
+                                    style.add_modifier(Modifier::REVERSED)
+                                } else {
+                                    style
+                                },
+                            ),
+                            Span::styled(
+                                format!(" ({})", count),
+                                Style::default().fg(Color::DarkGray),
+                            ),
+                        ])
+                    }
+                    BookmarkRow::Bookmark { index, depth } => {
+                        let bookmark = &bookmarks[*index];
+                        let style = if is_selected {
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(Color::White)
+                        };
+                        let mut spans = vec![
+                            Span::styled(prefix, style),
+                            Span::raw("  ".repeat(*depth)),
+                            Span::styled(bookmark.name.as_str(), style),
+                        ];
+                        if let Some(schedule) = app.bookmark_schedule(bookmark) {
+                            spans.push(Span::styled(
+                                format!(" ⏱ {}", schedule.spec),
+                                Style::default().fg(Color::Magenta),
+                            ));
+                        }
+                        spans.push(Span::raw("  "));
+                        spans.push(Span::styled(
+                            ellipsize(&bookmark.topic, 30),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                        Line::from(spans)
+                    }
+                };
+                ListItem::new(line)
+            })
+            .collect();
+
+        frame.render_widget(List::new(items), chunks[0]);
+    }
+
+    if schedule_rows > 0 {
+        render_schedules(frame, app, chunks[1]);
+    }
+
+    // Prompts replace the help text while typing
+    if let Some(prompt) = app.bookmark_manager.prompt {
+        let (label, action) = match prompt {
+            BookmarkPrompt::Import => ("Import file: ", "Import"),
+            BookmarkPrompt::Schedule(_) => ("Every (30s, 5m, or cron): ", "Start"),
+        };
+        let mut spans = vec![Span::styled(label, Style::default().fg(Color::Yellow))];
+        spans.extend(input_spans(
+            &app.bookmark_manager.prompt_input,
+            app.bookmark_manager.prompt_cursor,
+            Style::default().fg(Color::White),
+        ));
+        let mut hints = Vec::new();
+        hints.extend(dialog_key_hint("Enter", action));
+        hints.extend(dialog_key_hint("Esc", "Cancel"));
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(spans), Line::from(hints)]),
+            chunks[2],
+        );
+        return;
     }
 
     // Help text
     let mut hints = Vec::new();
-    hints.extend(dialog_key_hint("Enter", "Publish"));
+    hints.extend(dialog_key_hint("Enter", "Publish/Fold"));
     hints.extend(dialog_key_hint("e", "Edit"));
     hints.extend(dialog_key_hint("a", "Add"));
     hints.extend(dialog_key_hint("d", "Delete"));
+    hints.extend(dialog_key_hint("t", "Schedule"));
+    hints.extend(dialog_key_hint("x", "Export"));
+    hints.extend(dialog_key_hint("i", "Import"));
     hints.extend(dialog_key_hint("Esc", "Close"));
-    frame.render_widget(Paragraph::new(Line::from(hints)), chunks[1]);
+    frame.render_widget(
+        Paragraph::new(Line::from(hints)).wrap(ratatui::widgets::Wrap { trim: true }),
+        chunks[2],
+    );
+}
+
+/// Active schedules with their next run; `t` on a bookmark cancels its schedule
+fn render_schedules(frame: &mut Frame, app: &App, area: Rect) {
+    let now = chrono::Local::now();
+    let mut lines = vec![Line::from(Span::styled(
+        format!("Schedules ({})", app.bookmark_schedules.len()),
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    for schedule in app.bookmark_schedules.iter().take(MAX_SCHEDULE_ROWS) {
+        let next_in = (schedule.next_run - now).num_seconds().max(0);
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {}", schedule.name),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                format!("  {}", schedule.spec),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::styled(
+                format!("  next in {}s, sent {}", next_in, schedule.runs),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
 }
 
 fn render_bookmark_edit(frame: &mut Frame, app: &App) {
@@ -182,10 +267,10 @@ fn render_bookmark_edit(frame: &mut Frame, app: &App) {
         chunks[0],
     );
 
-    // Category field
+    // Folder field
     render_text_field(
         frame,
-        "Category",
+        "Folder (a/b for nested)",
         &editing.category,
         editing.cursor,
         editing.field == BookmarkField::Category,
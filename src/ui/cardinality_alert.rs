@@ -0,0 +1,63 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use super::widgets::centered_rect;
+use crate::app::App;
+
+pub fn render_cardinality_alert(frame: &mut Frame, app: &App) {
+    let Some(ref alert) = app.cardinality_alert else {
+        return;
+    };
+
+    let area = centered_rect(50, 40, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" ⚠ Topic Explosion ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red))
+        .style(Style::default().bg(Color::Black));
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(alert.branch.clone(), Style::default().fg(Color::Cyan)),
+            Span::raw(" gained "),
+            Span::styled(
+                format!("{} new topics", alert.added),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" in {}s.", app.config.ui.cardinality_window_secs)),
+        ]),
+        Line::from("A publisher may be putting timestamps or IDs into its topics."),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  c  ", Style::default().fg(Color::Yellow)),
+            Span::raw("Collapse the branch"),
+        ]),
+        Line::from(vec![
+            Span::styled("  -  ", Style::default().fg(Color::Yellow)),
+            Span::raw("Hide it for this session"),
+        ]),
+        Line::from(vec![
+            Span::styled("  _  ", Style::default().fg(Color::Yellow)),
+            Span::raw("Hide it and remember"),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Esc to keep the tree as it is",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
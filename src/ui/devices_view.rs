@@ -0,0 +1,101 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState},
+    Frame,
+};
+
+use super::bordered_block;
+use crate::app::{App, Panel};
+use crate::state::HealthStatus;
+use crate::text::fit_width;
+
+pub fn render_devices(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.focused_panel == Panel::Devices;
+    let devices = app.visible_devices();
+
+    let title = if app.device_filter_starred {
+        format!("Devices [★] ({})", devices.len())
+    } else {
+        format!("Devices ({})", devices.len())
+    };
+    let block = bordered_block(&title, focused);
+    let inner = block.inner(area);
+
+    frame.render_widget(block, area);
+
+    if devices.is_empty() {
+        let empty_msg = if app.device_filter_starred {
+            "No starred devices - press * to show all"
+        } else {
+            "No devices seen yet"
+        };
+        let text = Line::from(Span::styled(
+            empty_msg,
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        ));
+        frame.render_widget(List::new(vec![ListItem::new(text)]), inner);
+        return;
+    }
+
+    let id_width = (inner.width as usize).saturating_sub(22).max(8);
+
+    let items: Vec<ListItem> = devices
+        .iter()
+        .map(|device| {
+            let (status_char, status_color) = match device.status {
+                HealthStatus::Healthy => ("●", Color::Green),
+                HealthStatus::Warning => ("●", Color::Yellow),
+                HealthStatus::Stale => ("○", Color::Red),
+                HealthStatus::Unknown => ("◌", Color::DarkGray),
+                HealthStatus::Offline => ("✗", Color::Red),
+            };
+            let star = if app.user_data.is_device_starred(&device.device_id) {
+                "★ "
+            } else {
+                "  "
+            };
+
+            let status = if app.config.ui.screen_reader {
+                format!("{:<6}", device.status.label())
+            } else {
+                format!("{} ", status_char)
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(status, Style::default().fg(status_color)),
+                Span::styled(star, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    fit_width(&app.friendly_label(&device.device_id), id_width),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!(
+                        " {:>7} {:>6}",
+                        device.last_seen_string(),
+                        device.message_count
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let mut state = ListState::default();
+    state.select(Some(app.device_selected_index.min(devices.len() - 1)));
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(if focused {
+                Color::DarkGray
+            } else {
+                Color::Black
+            })
+            .add_modifier(Modifier::BOLD),
+    );
+
+    frame.render_stateful_widget(list, inner, &mut state);
+}
@@ -6,7 +6,7 @@ use ratatui::{
     Frame,
 };
 
-use super::widgets::centered_rect;
+use super::widgets::{centered_rect, completion_line, input_spans};
 use crate::app::App;
 
 pub fn render_filter(frame: &mut Frame, app: &App) {
@@ -20,8 +20,9 @@ pub fn render_filter(frame: &mut Frame, app: &App) {
     let sep = broker.topic_separator();
     let hint = broker.filter_title_hint();
 
+    let (matches, total) = app.filter_matches;
     let block = Block::default()
-        .title(" Topic Filter ")
+        .title(format!(" Topic Filter · {} of {} topics ", matches, total))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Yellow))
         .style(Style::default().bg(Color::Black));
@@ -34,6 +35,7 @@ pub fn render_filter(frame: &mut Frame, app: &App) {
         .constraints([
             Constraint::Length(2),
             Constraint::Length(1),
+            Constraint::Length(1),
             Constraint::Length(2),
             Constraint::Min(1),
         ])
@@ -52,18 +54,23 @@ pub fn render_filter(frame: &mut Frame, app: &App) {
     frame.render_widget(instructions, chunks[0]);
 
     // Input field with cursor
-    let input_display = format!("{}_", app.filter_input);
-    let input = Paragraph::new(Line::from(vec![
-        Span::styled("> ", Style::default().fg(Color::Yellow)),
-        Span::styled(
-            input_display,
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        ),
-    ]));
+    let mut spans = vec![Span::styled("> ", Style::default().fg(Color::Yellow))];
+    spans.extend(input_spans(
+        &app.filter_input,
+        app.filter_cursor,
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ));
+    let input = Paragraph::new(Line::from(spans));
     frame.render_widget(input, chunks[1]);
 
+    let completions = app.topic_completions(&app.filter_input, app.filter_cursor);
+    frame.render_widget(
+        Paragraph::new(completion_line(&app.filter_input, &completions, sep)),
+        chunks[2],
+    );
+
     // Examples
     let examples = Paragraph::new(vec![
         Line::from(""),
@@ -93,16 +100,18 @@ pub fn render_filter(frame: &mut Frame, app: &App) {
             Span::styled("All site devices", Style::default().fg(Color::DarkGray)),
         ]),
     ]);
-    frame.render_widget(examples, chunks[3]);
+    frame.render_widget(examples, chunks[4]);
 
     // Footer hint
     let footer = Paragraph::new(Line::from(vec![
         Span::styled("Enter", Style::default().fg(Color::Yellow)),
         Span::raw(" apply  "),
+        Span::styled("Tab", Style::default().fg(Color::Yellow)),
+        Span::raw(" complete  "),
         Span::styled("Esc", Style::default().fg(Color::Yellow)),
         Span::raw(" cancel  "),
         Span::styled("(empty)", Style::default().fg(Color::DarkGray)),
         Span::raw(" clears filter"),
     ]));
-    frame.render_widget(footer, chunks[2]);
+    frame.render_widget(footer, chunks[3]);
 }
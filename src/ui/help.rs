@@ -26,34 +26,74 @@ pub fn render_help(frame: &mut Frame) {
         keybind("↑↓ j/k", "Move up/down"),
         keybind("←→ h/l", "Collapse/Expand or move to parent/child"),
         keybind("H / L", "Collapse/Expand full branch"),
+        keybind("{ / }", "Previous/next sibling"),
+        keybind("( / )", "Parent / first child"),
         keybind("Enter", "Toggle expand/collapse"),
-        keybind("Tab", "Switch panel (Topics → Messages → Stats)"),
-        keybind("1 / 2 / 3", "Jump to panel directly"),
+        keybind("v", "Peek: preview rows without selecting them"),
+        keybind("Tab", "Switch panel (Topics → Messages → Stats → Devices)"),
+        keybind("1 / 2 / 3 / 4", "Jump to panel directly"),
         keybind("PgUp/PgDn", "Page up/down"),
         keybind("g / G", "Go to top/bottom"),
+        keybind("25j / 10G", "Count prefix: move 25 rows / go to row 10"),
         Line::from(""),
         section("Search & Filter"),
         keybind("/", "Open fuzzy search"),
         keybind("f", "Set topic filter (MQTT: + #, NATS: * >)"),
+        keybind("o / F", "Filter to selected subtree / clear filter"),
         keybind("s", "Star/unstar current topic"),
         keybind("*", "Toggle starred topics filter"),
+        keybind("'", "Jump to a starred topic (1-9)"),
+        keybind("w", "Cycle silence watchdog on starred topic"),
+        keybind("N", "Add/edit note on current topic"),
+        keybind("A", "AsyncAPI docs for current topic"),
+        keybind("s / * (Dev.)", "Star device / show starred devices"),
+        keybind("- / _", "Hide topic subtree (session / remembered)"),
+        keybind("=", "Show all hidden topics"),
+        keybind("u / U", "Clear messages and counts of topic / subtree"),
+        keybind("$", "Show/hide broker $ topics ($SYS)"),
+        keybind("z", "Group topics by learned template"),
+        keybind("a", "Follow the busiest topic or latest alert"),
+        keybind("n", "Follow new topics as they appear"),
         Line::from(""),
         section("Servers & Publishing"),
         keybind("S", "Manage servers (MQTT/NATS)"),
+        keybind("@", "Quick switch server (keeps its data)"),
+        keybind("Z", "Step away keeping a session / come back"),
         keybind("P", "Open publish dialog"),
         keybind("Ctrl+P", "Copy current message to publish"),
         keybind("B", "Open bookmark manager"),
         keybind("Ctrl+S", "Save publish as bookmark"),
+        keybind("Ctrl+D", "Preview publish (lint + diff)"),
+        keybind("R", "Scenarios (publish sequences)"),
+        Line::from(""),
+        section("Text Fields"),
+        keybind("Ctrl+←→", "Jump a word (Shift+move selects)"),
+        keybind("Ctrl+W / U", "Delete word / to start of field"),
+        keybind("Ctrl+A", "Select all"),
+        keybind("Ctrl+C/X/V", "Copy / cut / paste"),
+        keybind("↑ / ↓", "History (publish, filter, search)"),
         Line::from(""),
         section("Data & Display"),
-        keybind("m", "Track metric from current message"),
+        keybind("m", "Track metric or state from current message"),
+        keybind("M", "Tracked metrics (log, delta, rate, decimals, charts)"),
         keybind("p", "Cycle payload mode (Auto → Raw → Hex → JSON)"),
+        keybind("I", "Message list columns and times"),
+        keybind("K", "Keep more messages for this topic (10×, 100×, all)"),
         keybind("y", "Copy topic to clipboard"),
+        keybind("b", "Copy, filter or go to a prefix of the topic"),
         keybind("Y", "Copy payload to clipboard"),
+        keybind("|", "Pipe topic to file or command (again: stop)"),
         keybind("c", "Clear statistics"),
+        keybind("V", "Traffic summary against earlier days"),
+        keybind("C", "Cycle color palette (color-blind, high contrast)"),
         Line::from(""),
         section("General"),
         keybind("E", "Export all topics to file"),
+        keybind("T", "Export topic tree (text, JSON, DOT)"),
+        keybind("X / x", "Screenshot screen / focused panel"),
+        keybind("W", "Save session snapshot"),
+        keybind("O", "Open session snapshot"),
+        keybind("D", "Show application log"),
         keybind("?", "Toggle this help"),
         keybind("q / Ctrl+C", "Quit"),
         Line::from(""),
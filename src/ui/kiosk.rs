@@ -0,0 +1,398 @@
+use std::time::Instant;
+
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Sparkline},
+    Frame,
+};
+
+use super::bordered_block;
+use crate::app::{App, KioskPage};
+use crate::mqtt::resilience::ConnectionQuality;
+use crate::state::{HealthStatus, MetricDisplay};
+use crate::text::{ellipsize, fit_width};
+
+/// Height of a number tile, borders included
+const TILE_HEIGHT: u16 = 5;
+/// Prefixes ranked on the overview page
+const KIOSK_PREFIXES: usize = 8;
+/// Height of a metric chart, borders included
+const METRIC_HEIGHT: u16 = 6;
+
+/// The current kiosk page, with a line of page names and the time to the next
+pub fn render_kiosk(frame: &mut Frame, app: &App, area: Rect) {
+    let [tabs_area, page_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .areas(area);
+
+    let page = app.current_kiosk_page();
+    let mut spans = Vec::new();
+    for (index, name) in app.kiosk_pages().iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        }
+        let style = if *name == page {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::styled(name.title(), style));
+    }
+    let remaining = app
+        .kiosk_next_page
+        .saturating_duration_since(Instant::now())
+        .as_secs();
+    spans.push(Span::styled(
+        format!("   next in {}s", remaining + 1),
+        Style::default().fg(Color::DarkGray),
+    ));
+    frame.render_widget(Paragraph::new(Line::from(spans)), tabs_area);
+
+    match page {
+        KioskPage::Overview => render_overview(frame, app, page_area),
+        KioskPage::Devices => render_device_page(frame, app, page_area),
+        KioskPage::Busiest => render_busiest_page(frame, app, page_area),
+        KioskPage::Metrics => render_metrics_page(frame, app, page_area),
+    }
+}
+
+/// A bordered tile with one large value and a caption under it
+fn render_tile(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    value: &str,
+    caption: &str,
+    color: Color,
+) {
+    let block = bordered_block(title, false);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let lines = vec![
+        Line::from(Span::styled(
+            value.to_string(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            caption.to_string(),
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), inner);
+}
+
+/// Split a row into `count` tiles of equal width
+fn tile_row(area: Rect, count: usize) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, count as u32); count])
+        .split(area)
+        .to_vec()
+}
+
+fn render_overview(frame: &mut Frame, app: &App, area: Rect) {
+    let [tiles_area, prefixes_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(TILE_HEIGHT), Constraint::Min(0)])
+        .areas(area);
+    let numbers = &app.config.ui.numbers;
+    let tiles = tile_row(tiles_area, 4);
+
+    render_tile(
+        frame,
+        tiles[0],
+        "Messages",
+        &format!("{}/s", numbers.rate(app.stats.messages_per_second())),
+        &format!("{} total", numbers.count(app.stats.total_messages())),
+        Color::Green,
+    );
+    render_tile(
+        frame,
+        tiles[1],
+        "Topics",
+        &numbers.count(app.application_topic_count() as u64),
+        &format!(
+            "{}/s in",
+            numbers.bytes(app.stats.bytes_per_second() as u64)
+        ),
+        Color::Cyan,
+    );
+    let (healthy, _, _, _) = app.device_tracker.count_by_status();
+    let total = app.device_tracker.device_count();
+    render_tile(
+        frame,
+        tiles[2],
+        "Devices",
+        &format!("{}/{}", healthy, total),
+        "healthy",
+        if healthy == total {
+            Color::Green
+        } else {
+            Color::Yellow
+        },
+    );
+    let now = Instant::now();
+    let (quality, color) = match app.connection_health.quality(now) {
+        Some(ConnectionQuality::Good) => ("good", Color::Green),
+        Some(ConnectionQuality::Fair) => ("fair", Color::Yellow),
+        Some(ConnectionQuality::Poor) => ("poor", Color::Red),
+        None => ("-", Color::DarkGray),
+    };
+    let drops = app.connection_health.recent_disconnects(now);
+    render_tile(
+        frame,
+        tiles[3],
+        "Link",
+        quality,
+        &format!("{} drop{} in 10m", drops, if drops == 1 { "" } else { "s" }),
+        color,
+    );
+
+    // Share of inbound bytes per first-level prefix, as bars
+    let block = bordered_block("Top prefixes", false);
+    let inner = block.inner(prefixes_area);
+    frame.render_widget(block, prefixes_area);
+    let usage = app.stats.top_prefixes(KIOSK_PREFIXES);
+    if usage.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "No traffic yet",
+                Style::default().fg(Color::DarkGray),
+            )),
+            inner,
+        );
+        return;
+    }
+    let bar_width = (inner.width as usize).saturating_sub(34);
+    let lines: Vec<Line> = usage
+        .into_iter()
+        .map(|usage| {
+            let prefix = if usage.prefix.is_empty() {
+                "(root)".to_string()
+            } else {
+                usage.prefix
+            };
+            let filled = (usage.share * bar_width as f64).round() as usize;
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", fit_width(&prefix, 14)),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled("█".repeat(filled), Style::default().fg(Color::Green)),
+                Span::styled(
+                    "░".repeat(bar_width - filled),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!(" {:>9}/s", numbers.bytes(usage.bytes_per_second as u64)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!(" {:>4.0}%", usage.share * 100.0),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_device_page(frame: &mut Frame, app: &App, area: Rect) {
+    let [tiles_area, list_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(TILE_HEIGHT), Constraint::Min(0)])
+        .areas(area);
+    let (healthy, warning, stale, unknown) = app.device_tracker.count_by_status();
+    let offline = app.device_tracker.offline_count();
+    let tiles = tile_row(tiles_area, 4);
+    render_tile(
+        frame,
+        tiles[0],
+        "Healthy",
+        &healthy.to_string(),
+        "",
+        Color::Green,
+    );
+    render_tile(
+        frame,
+        tiles[1],
+        "Slow",
+        &warning.to_string(),
+        "",
+        Color::Yellow,
+    );
+    render_tile(frame, tiles[2], "Stale", &stale.to_string(), "", Color::Red);
+    render_tile(
+        frame,
+        tiles[3],
+        "Offline",
+        &offline.to_string(),
+        &format!("{} new", unknown),
+        Color::Red,
+    );
+
+    // Devices needing attention first, without a selection
+    let mut devices = app.device_tracker.get_devices();
+    devices.sort_by_key(|device| match device.status {
+        HealthStatus::Offline => 0,
+        HealthStatus::Stale => 1,
+        HealthStatus::Warning => 2,
+        HealthStatus::Unknown => 3,
+        HealthStatus::Healthy => 4,
+    });
+    let block = bordered_block("Devices", false);
+    let inner = block.inner(list_area);
+    frame.render_widget(block, list_area);
+    let id_width = (inner.width as usize).saturating_sub(24).max(8);
+    let lines: Vec<Line> = devices
+        .iter()
+        .take(inner.height as usize)
+        .map(|device| {
+            let color = match device.status {
+                HealthStatus::Healthy => Color::Green,
+                HealthStatus::Warning => Color::Yellow,
+                HealthStatus::Stale | HealthStatus::Offline => Color::Red,
+                HealthStatus::Unknown => Color::DarkGray,
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<8}", device.status.label()),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    fit_width(&app.friendly_label(&device.device_id), id_width),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!(
+                        " {:>7} {:>6}",
+                        device.last_seen_string(),
+                        device.message_count
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// The followed topic's latest message, filling the screen
+fn render_busiest_page(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match &app.selected_topic {
+        Some(topic) => ellipsize(
+            &format!("Busiest topic: {}", app.friendly_label(topic)),
+            (area.width as usize).saturating_sub(4),
+        ),
+        None => "Busiest topic".to_string(),
+    };
+    let block = bordered_block(&title, false);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let latest = app
+        .selected_topic
+        .as_deref()
+        .and_then(|topic| app.message_buffer.get_latest(topic));
+    let Some(msg) = latest else {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "Waiting for traffic",
+                Style::default().fg(Color::DarkGray),
+            )),
+            inner,
+        );
+        return;
+    };
+    let numbers = &app.config.ui.numbers;
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "{}  {} messages  {}",
+                app.config.ui.timezone.format(msg.timestamp, "%H:%M:%S"),
+                numbers.count(
+                    app.topic_tree
+                        .get_topic_stats(&msg.topic)
+                        .map_or(0, |(count, _, _)| count)
+                ),
+                numbers.bytes(msg.payload_size() as u64)
+            ),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+    ];
+    let payload = app.format_payload(msg);
+    lines.extend(
+        payload
+            .lines()
+            .take(inner.height.saturating_sub(2) as usize)
+            .map(|line| {
+                Line::from(Span::styled(
+                    ellipsize(line, inner.width as usize),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+            }),
+    );
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Each tracked metric as a tall sparkline under its latest value
+fn render_metrics_page(frame: &mut Frame, app: &App, area: Rect) {
+    let numbers = &app.config.ui.numbers;
+    let metrics = app.metric_tracker.get_metrics();
+    let shown = (area.height / METRIC_HEIGHT).max(1) as usize;
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(METRIC_HEIGHT); shown])
+        .split(area);
+
+    for (metric, row) in metrics.iter().zip(rows.iter()) {
+        let unit = if metric.display == MetricDisplay::Rate {
+            "/s"
+        } else {
+            ""
+        };
+        let current = metric
+            .display_latest()
+            .map(|v| format!("{}{}", numbers.value(v, metric.precision), unit))
+            .unwrap_or_else(|| "---".to_string());
+        let block = bordered_block(&metric.label, false);
+        let inner = block.inner(*row);
+        frame.render_widget(block, *row);
+        let [value_area, chart_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .areas(inner);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                current,
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            value_area,
+        );
+
+        // Sparkline heights are relative, so scale the values to 0-100
+        let values = metric.sparkline_data(chart_area.width as usize);
+        let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let span = (high - low).max(f64::EPSILON);
+        let data: Vec<u64> = values
+            .iter()
+            .map(|v| ((v - low) / span * 100.0) as u64 + 1)
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+                .data(&data)
+                .max(101)
+                .style(Style::default().fg(Color::Magenta)),
+            chart_area,
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::centered_rect;
+use crate::app::App;
+
+pub fn render_log_view(frame: &mut Frame, app: &App) {
+    let area = centered_rect(90, 80, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let lines = app.log_buffer.lines();
+    let title = if app.log_scroll > 0 {
+        format!(" Log ({} lines, ↑{}) ", lines.len(), app.log_scroll)
+    } else {
+        format!(" Log ({} lines) ", lines.len())
+    };
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    let height = inner.height as usize;
+
+    // Show the newest lines at the bottom, offset by the scroll position
+    let end = lines.len().saturating_sub(app.log_scroll);
+    let start = end.saturating_sub(height);
+
+    let visible: Vec<Line> = if lines.is_empty() {
+        vec![Line::from(Span::styled(
+            "No log entries yet",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        lines[start..end]
+            .iter()
+            .map(|line| Line::from(Span::styled(line.clone(), level_style(line))))
+            .collect()
+    };
+
+    frame.render_widget(Paragraph::new(visible).block(block), area);
+}
+
+fn level_style(line: &str) -> Style {
+    // Responses to watched publishes stand out from the rest of the log
+    if line.contains(": Response #") {
+        return Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD);
+    }
+    let level = line.split_whitespace().next().unwrap_or_default();
+    match level {
+        "ERROR" => Style::default().fg(Color::Red),
+        "WARN" => Style::default().fg(Color::Yellow),
+        "INFO" => Style::default().fg(Color::White),
+        "DEBUG" => Style::default().fg(Color::Gray),
+        _ => Style::default().fg(Color::DarkGray),
+    }
+}
@@ -0,0 +1,69 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::{centered_rect, input_spans};
+use crate::app::App;
+
+pub fn render_message_columns(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Message Columns ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    let columns = &app.config.ui.message_columns;
+    let on_off = |on: bool| if on { "on" } else { "off" };
+    let field = match &app.column_field_input {
+        Some(field) => {
+            let mut spans = vec![
+                Span::styled("  f  ", Style::default().fg(Color::Yellow)),
+                Span::raw("JSON field ✎ "),
+            ];
+            spans.extend(input_spans(
+                field,
+                app.column_field_cursor,
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            Line::from(spans)
+        }
+        None => column_line(
+            "f",
+            "JSON field",
+            columns.field.as_deref().unwrap_or("none (x clears)"),
+        ),
+    };
+
+    let lines = vec![
+        column_line("t", "Timestamp", columns.timestamp.label()),
+        column_line("q", "QoS", on_off(columns.qos)),
+        column_line("r", "Retain flag", on_off(columns.retain)),
+        column_line("s", "Payload size", on_off(columns.size)),
+        field,
+        column_line("a", "Times", app.config.ui.times.label()),
+        column_line("z", "Timezone", &String::from(app.config.ui.timezone)),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Esc to close. Keep these in the config under [ui]",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn column_line(key: &str, name: &str, value: &str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("  {}  ", key), Style::default().fg(Color::Yellow)),
+        Span::raw(format!("{:<14}", name)),
+        Span::styled(value.to_string(), Style::default().fg(Color::Cyan)),
+    ])
+}
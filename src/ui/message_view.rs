@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -7,23 +9,100 @@ use ratatui::{
 };
 
 use super::bordered_block;
-use super::widgets::truncate_safe;
-use crate::app::{App, Panel, PayloadMode};
-use crate::mqtt::MqttMessage;
+use super::widgets::format_precise_age;
+use crate::app::{App, InputMode, Panel, PayloadMode};
+use crate::config::UiConfig;
+use crate::content_type::{self, ContentType};
+use crate::mqtt::LARGE_PAYLOAD_BYTES;
+use crate::state::{extract_state, BufferedMessage};
+use crate::text::{ellipsize, ellipsize_start, fit_width};
+
+/// Bytes of the payload scanned for the one-line list preview
+const PREVIEW_SCAN_BYTES: usize = 1024;
+/// Width of the JSON field column
+const FIELD_COLUMN_WIDTH: usize = 10;
 
 pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.focused_panel == Panel::Messages;
 
-    let title = match &app.selected_topic {
-        Some(topic) => format!("Messages: {}", truncate_topic(topic, 30)),
+    let mut title = match &app.selected_topic {
+        Some(topic) => format!(
+            "Messages: {}",
+            ellipsize_start(&app.friendly_label(topic), 30)
+        ),
         None => "Messages".to_string(),
     };
+    // A buffer limit set with K, with the memory an unlimited one holds
+    if let Some(topic) = app
+        .selected_topic
+        .as_deref()
+        .filter(|topic| app.message_buffer.has_override(topic))
+    {
+        match app.message_buffer.limit_for(topic) {
+            Some(limit) => title.push_str(&format!(" [keep {}]", limit)),
+            None => title.push_str(&format!(
+                " [keep all, {}]",
+                app.config
+                    .ui
+                    .numbers
+                    .bytes(app.message_buffer.topic_payload_bytes(topic) as u64)
+            )),
+        }
+    }
+
+    if app.follow_busiest {
+        title.push_str(" [following]");
+    }
 
     let block = bordered_block(&title, focused);
-    let inner = block.inner(area);
+    let mut inner = block.inner(area);
 
     frame.render_widget(block, area);
 
+    // Breadcrumb of the topic's levels, above everything else
+    if let Some(topic) = app.selected_topic.clone() {
+        let crumb_chunks = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                ratatui::layout::Constraint::Length(1),
+                ratatui::layout::Constraint::Min(0),
+            ])
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(breadcrumb_line(app, &topic)),
+            crumb_chunks[0],
+        );
+        inner = crumb_chunks[1];
+    }
+
+    // The topic's note sits above the messages
+    let note = app
+        .selected_topic
+        .as_deref()
+        .and_then(|topic| app.user_data.note(topic))
+        .map(str::to_string);
+    if let Some(note) = note {
+        let note_chunks = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                ratatui::layout::Constraint::Length(2),
+                ratatui::layout::Constraint::Min(0),
+            ])
+            .split(inner);
+        let text = Paragraph::new(Line::from(vec![
+            Span::styled("✎ ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                note,
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::ITALIC),
+            ),
+        ]))
+        .wrap(Wrap { trim: true });
+        frame.render_widget(text, note_chunks[0]);
+        inner = note_chunks[1];
+    }
+
     // Split view: message list on top, payload detail below
     let chunks = ratatui::layout::Layout::default()
         .direction(ratatui::layout::Direction::Vertical)
@@ -34,8 +113,10 @@ pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
         .split(inner);
 
     // Update message scroll to keep selection visible (before borrowing messages)
-    let message_count = app.get_current_messages().len();
+    let message_count = app.listed_message_count();
     if message_count > 0 {
+        // Entering high load mode can leave the selection past the listed messages
+        app.selected_message_index = app.selected_message_index.min(message_count - 1);
         let visible_height = chunks[0].height as usize;
         let selected = app
             .selected_message_index
@@ -48,9 +129,9 @@ pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
         }
     }
 
-    let messages = app.get_current_messages();
+    let entries = app.get_current_entries();
 
-    if messages.is_empty() {
+    if entries.is_empty() {
         let empty_msg = if app.selected_topic.is_some() {
             "No messages for this topic"
         } else {
@@ -67,22 +148,52 @@ pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
         return;
     }
 
-    // Message list
-    render_message_list(frame, app, &messages, chunks[0]);
+    // Message list, only the newest under high load
+    let listed = &entries[..message_count.min(entries.len())];
+    render_message_list(frame, app, listed, chunks[0]);
 
     // Payload detail
-    if let Some(msg) = messages.get(app.selected_message_index) {
-        render_payload_detail(frame, app, msg, chunks[1]);
+    if let Some(entry) = entries.get(app.selected_message_index) {
+        render_payload_detail(frame, app, entry, chunks[1]);
+    }
+}
+
+/// Topic levels separated by `›`. While picking a prefix, the picked levels
+/// are highlighted and the rest dimmed.
+fn breadcrumb_line(app: &App, topic: &str) -> Line<'static> {
+    let picking = app.input_mode == InputMode::Breadcrumb;
+    let replace = app.config.ui.replace_ids;
+    let levels: Vec<&str> = topic.split(app.topic_tree.separator()).collect();
+    let mut spans = Vec::new();
+    for (depth, level) in levels.iter().enumerate() {
+        if depth > 0 {
+            spans.push(Span::styled(" › ", Style::default().fg(Color::DarkGray)));
+        }
+        let style = if picking && depth <= app.breadcrumb_depth {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else if picking || depth + 1 < levels.len() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        spans.push(Span::styled(
+            app.friendly_names.label(level, replace).into_owned(),
+            style,
+        ));
     }
+    Line::from(spans)
 }
 
-fn render_message_list(frame: &mut Frame, app: &App, messages: &[&MqttMessage], area: Rect) {
-    let items: Vec<ListItem> = messages
+fn render_message_list(frame: &mut Frame, app: &App, entries: &[&BufferedMessage], area: Rect) {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let items: Vec<ListItem> = entries
         .iter()
         .enumerate()
-        .map(|(i, msg)| {
+        .map(|(i, entry)| {
             let is_selected = i == app.selected_message_index;
-            create_message_item(msg, is_selected)
+            create_message_item(entry, is_selected, &app.config.ui, now_ms)
         })
         .collect();
 
@@ -99,8 +210,14 @@ fn render_message_list(frame: &mut Frame, app: &App, messages: &[&MqttMessage],
     frame.render_stateful_widget(list, area, &mut state);
 }
 
-fn create_message_item(msg: &MqttMessage, _is_selected: bool) -> ListItem<'static> {
-    let time = msg.timestamp.format("%H:%M:%S").to_string();
+fn create_message_item(
+    entry: &BufferedMessage,
+    _is_selected: bool,
+    ui: &UiConfig,
+    now_ms: i64,
+) -> ListItem<'static> {
+    let msg = &entry.message;
+    let columns = &ui.message_columns;
 
     // QoS indicator with color
     let (qos_label, qos_color) = match msg.qos {
@@ -110,42 +227,98 @@ fn create_message_item(msg: &MqttMessage, _is_selected: bool) -> ListItem<'stati
         _ => ("Q?", Color::White),
     };
 
-    // Preview payload (first line, truncated)
-    let preview = msg
-        .payload_str()
+    // Preview payload (first line, truncated), decoding text that isn't UTF-8
+    let kind = if msg.is_large() {
+        ContentType::Binary
+    } else {
+        match ui.encoding_for(&msg.topic) {
+            Some(encoding) => ContentType::Encoded(encoding),
+            None if msg.payload_str_prefix(PREVIEW_SCAN_BYTES).is_some() => ContentType::Text,
+            None => content_type::sniff(&msg.payload),
+        }
+    };
+    let text = match kind {
+        ContentType::Encoded(encoding) => {
+            content_type::decode_text(&msg.payload, encoding).map(Cow::Owned)
+        }
+        _ => msg
+            .payload_str_prefix(PREVIEW_SCAN_BYTES)
+            .map(Cow::Borrowed),
+    };
+    let preview = text
         .map(|s| {
             let first_line = s.lines().next().unwrap_or("");
-            if first_line.len() > 50 {
-                format!("{}...", truncate_safe(first_line, 50))
-            } else {
-                first_line.to_string()
-            }
+            ellipsize(first_line, 53)
         })
-        .unwrap_or_else(|| format!("<{} bytes binary>", msg.payload_size()));
+        .unwrap_or_else(|| format!("<{} bytes {}>", msg.payload_size(), kind.label()));
 
-    let mut spans = vec![
-        Span::styled(time, Style::default().fg(Color::DarkGray)),
-        Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
-        Span::styled(qos_label.to_string(), Style::default().fg(qos_color)),
-        Span::raw(" "),
-    ];
+    let mut spans = Vec::new();
+    if let Some(pattern) = columns.timestamp.pattern() {
+        let time = if ui.times.relative_in_list() {
+            let age_ms = now_ms - msg.timestamp.timestamp_millis();
+            format!("{:>9}", format_precise_age(age_ms))
+        } else {
+            ui.timezone.format(msg.timestamp, pattern)
+        };
+        spans.push(Span::styled(time, Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+    }
 
-    if msg.retain {
+    if columns.qos {
+        spans.push(Span::styled(
+            qos_label.to_string(),
+            Style::default().fg(qos_color),
+        ));
+        spans.push(Span::raw(" "));
+    }
+
+    if columns.retain && msg.retain {
         spans.push(Span::styled("R", Style::default().fg(Color::Yellow)));
         spans.push(Span::raw(" "));
     }
 
+    if columns.size {
+        spans.push(Span::styled(
+            format!("{:>9} ", ui.numbers.bytes(msg.payload_size() as u64)),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if let Some(field) = &columns.field {
+        // Parsing megabytes of JSON for every row would stall the UI
+        let json = (!msg.is_large())
+            .then(|| serde_json::from_slice(&msg.payload).ok())
+            .flatten();
+        let value = extract_state(json.as_ref(), &msg.payload, field).unwrap_or_default();
+        spans.push(Span::styled(
+            format!("{} ", fit_width(&value, FIELD_COLUMN_WIDTH)),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+
     spans.push(Span::raw(preview));
 
+    if entry.repeats > 0 {
+        spans.push(Span::styled(
+            format!("  unchanged ×{}", entry.repeats + 1),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
     ListItem::new(Line::from(spans))
 }
 
-fn render_payload_detail(frame: &mut Frame, app: &App, msg: &MqttMessage, area: Rect) {
-    let mode_indicator = match app.payload_mode {
-        PayloadMode::Auto => "AUTO",
-        PayloadMode::Raw => "RAW",
-        PayloadMode::Hex => "HEX",
-        PayloadMode::Json => "JSON",
+fn render_payload_detail(frame: &mut Frame, app: &App, entry: &BufferedMessage, area: Rect) {
+    let msg = &entry.message;
+    // Auto mode decodes by content; large payloads keep their plain prefix
+    let decoded = (app.payload_mode == PayloadMode::Auto && !msg.is_large())
+        .then(|| content_type::decode(&msg.payload, app.config.ui.encoding_for(&msg.topic)));
+    let mode_indicator = match (&decoded, app.payload_mode) {
+        (Some(decoded), _) => format!("AUTO: {}", decoded.label),
+        (None, PayloadMode::Auto) => "AUTO".to_string(),
+        (None, PayloadMode::Raw) => "RAW".to_string(),
+        (None, PayloadMode::Hex) => "HEX".to_string(),
+        (None, PayloadMode::Json) => "JSON".to_string(),
     };
 
     let header = Line::from(vec![
@@ -164,14 +337,43 @@ fn render_payload_detail(frame: &mut Frame, app: &App, msg: &MqttMessage, area:
         } else {
             Span::raw("")
         },
+        if entry.repeats > 0 {
+            Span::styled(
+                format!(
+                    " unchanged ×{}, last {}",
+                    entry.repeats + 1,
+                    app.config.ui.timezone.format(entry.last_seen, "%H:%M:%S")
+                ),
+                Style::default().fg(Color::DarkGray),
+            )
+        } else {
+            Span::raw("")
+        },
+        if msg.is_large() {
+            Span::styled(
+                format!(" (showing first {} KB)", LARGE_PAYLOAD_BYTES / 1024),
+                Style::default().fg(Color::Yellow),
+            )
+        } else {
+            Span::raw("")
+        },
     ]);
 
-    let payload = app.format_payload(msg);
+    let is_json = match &decoded {
+        Some(decoded) => decoded.is_json,
+        None => {
+            app.payload_mode == PayloadMode::Json
+                && !msg.is_large()
+                && msg.payload_json_pretty().is_some()
+        }
+    };
+    let payload = match decoded {
+        Some(decoded) => decoded.text,
+        None => app.format_payload(msg),
+    };
 
     // Color JSON syntax
-    let styled_payload = if matches!(app.payload_mode, PayloadMode::Auto | PayloadMode::Json)
-        && msg.payload_json_pretty().is_some()
-    {
+    let styled_payload = if is_json && !app.high_load {
         syntax_highlight_json(&payload)
     } else if matches!(app.payload_mode, PayloadMode::Hex) {
         Text::styled(payload, Style::default().fg(Color::Gray))
@@ -195,7 +397,9 @@ fn render_payload_detail(frame: &mut Frame, app: &App, msg: &MqttMessage, area:
     frame.render_widget(paragraph, area);
 }
 
-fn syntax_highlight_json(json: &str) -> Text<'static> {
+/// Color pretty-printed JSON by token. Payloads come from the broker, so this
+/// must cope with anything, not only valid JSON.
+pub fn syntax_highlight_json(json: &str) -> Text<'static> {
     let mut lines = Vec::new();
 
     for line in json.lines() {
@@ -308,10 +512,3 @@ fn syntax_highlight_json(json: &str) -> Text<'static> {
     Text::from(lines)
 }
 
-fn truncate_topic(topic: &str, max_len: usize) -> String {
-    if topic.len() <= max_len {
-        topic.to_string()
-    } else {
-        format!("...{}", &topic[topic.len() - max_len + 3..])
-    }
-}
@@ -0,0 +1,166 @@
+use std::time::Instant;
+
+use ratatui::{
+    layout::Constraint,
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph},
+    Frame,
+};
+
+use super::widgets::{centered_rect, format_age};
+use crate::app::App;
+use crate::config::SparklineBaseline;
+
+/// Series colors, in legend order
+const SERIES_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::LightRed,
+    Color::LightBlue,
+];
+
+pub fn render_metric_chart(frame: &mut Frame, app: &App) {
+    let area = centered_rect(80, 70, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let charts = app.metric_tracker.charts();
+    let Some(chart) = charts.get(app.metric_chart_index) else {
+        return;
+    };
+    let block = Block::default()
+        .title(format!(
+            " {} ({}/{}) · {} ",
+            chart.name,
+            app.metric_chart_index + 1,
+            charts.len(),
+            if chart.normalized {
+                "normalized"
+            } else {
+                "shared axis"
+            }
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta))
+        .style(Style::default().bg(Color::Black));
+
+    // Two braille dots per cell
+    let points = block.inner(area).width as usize * 2;
+    let now = Instant::now();
+    let mut series: Vec<(String, Vec<(f64, f64)>)> = chart
+        .metrics
+        .iter()
+        .filter_map(|label| app.metric_tracker.get_metric(label))
+        .map(|metric| {
+            let latest = metric
+                .display_latest()
+                .map(|v| app.config.ui.numbers.value(v, metric.precision))
+                .unwrap_or_else(|| "---".to_string());
+            (
+                format!("{} {}", metric.label, latest),
+                metric.chart_data(points, now),
+            )
+        })
+        .collect();
+
+    let oldest = series
+        .iter()
+        .flat_map(|(_, data)| data.first().map(|(x, _)| *x))
+        .fold(0.0, f64::min);
+    let (low, high) = if chart.normalized {
+        for (_, data) in &mut series {
+            normalize(data);
+        }
+        (0.0, 100.0)
+    } else {
+        let values = || {
+            series
+                .iter()
+                .flat_map(|(_, data)| data.iter().map(|(_, y)| *y))
+        };
+        let low = values().fold(f64::INFINITY, f64::min);
+        let high = values().fold(f64::NEG_INFINITY, f64::max);
+        let low = match app.config.ui.sparkline_baseline {
+            SparklineBaseline::Zero => low.min(0.0),
+            SparklineBaseline::Min => low,
+        };
+        match (low.is_finite(), high > low) {
+            (false, _) => (0.0, 1.0),
+            (true, false) => (low - 1.0, high + 1.0),
+            (true, true) => (low, high),
+        }
+    };
+
+    if series.iter().all(|(_, data)| data.is_empty()) {
+        let empty = Paragraph::new("No samples yet").block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let datasets = series
+        .iter()
+        .zip(SERIES_COLORS.iter().cycle())
+        .map(|((name, data), color)| {
+            Dataset::default()
+                .name(name.clone())
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(data)
+        })
+        .collect();
+
+    let y_label = |v: f64| {
+        if chart.normalized {
+            format!("{:.0}%", v)
+        } else {
+            app.config.ui.numbers.value(v, None)
+        }
+    };
+    let dim = Style::default().fg(Color::DarkGray);
+    let chart_widget = Chart::new(datasets)
+        .block(block)
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+        .x_axis(
+            Axis::default()
+                .style(dim)
+                .bounds([oldest.min(-1.0), 0.0])
+                .labels([
+                    Span::styled(format_age((-oldest * 1000.0) as i64), dim),
+                    Span::styled("now", dim),
+                ]),
+        )
+        .y_axis(
+            Axis::default().style(dim).bounds([low, high]).labels([
+                Span::styled(y_label(low), dim),
+                Span::styled(y_label((low + high) / 2.0), dim),
+                Span::styled(
+                    y_label(high),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+        );
+    frame.render_widget(chart_widget, area);
+}
+
+/// Scale a series to 0-100% of its own range, a constant one to 50%
+fn normalize(data: &mut [(f64, f64)]) {
+    let low = data.iter().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
+    let high = data
+        .iter()
+        .map(|(_, y)| *y)
+        .fold(f64::NEG_INFINITY, f64::max);
+    for (_, y) in data.iter_mut() {
+        *y = if high > low {
+            (*y - low) / (high - low) * 100.0
+        } else {
+            50.0
+        };
+    }
+}
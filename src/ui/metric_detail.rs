@@ -0,0 +1,254 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use std::time::Instant;
+
+use super::widgets::{centered_rect, input_spans, sparkline_line, state_color, state_strip_line};
+use crate::app::App;
+use crate::state::MetricDisplay;
+
+const MODES: [(MetricDisplay, &str); 4] = [
+    (MetricDisplay::Absolute, "a"),
+    (MetricDisplay::Log, "l"),
+    (MetricDisplay::Delta, "d"),
+    (MetricDisplay::Rate, "r"),
+];
+
+pub fn render_metric_detail(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let metrics = app.metric_tracker.get_metrics();
+    let timelines = app.state_tracker.get_timelines();
+    let block = Block::default()
+        .title(format!(
+            " Tracked Metrics ({}) ",
+            metrics.len() + timelines.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+
+    let mut lines: Vec<Line> = metrics
+        .iter()
+        .enumerate()
+        .map(|(i, metric)| {
+            let selected = i == app.metric_detail_index;
+            let style = if selected {
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let charts: Vec<&str> = app
+                .metric_tracker
+                .charts()
+                .iter()
+                .filter(|chart| chart.metrics.contains(&metric.label))
+                .map(|chart| chart.name.as_str())
+                .collect();
+            let mut spans = vec![
+                Span::styled(if selected { "▶ " } else { "  " }, style),
+                Span::styled(metric.label.clone(), style),
+                Span::styled(
+                    format!("  {}", metric.display.label()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ];
+            if !charts.is_empty() {
+                spans.push(Span::styled(
+                    format!("  ▤ {}", charts.join(", ")),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    // States follow the metrics
+    for (i, timeline) in timelines.iter().enumerate() {
+        let selected = metrics.len() + i == app.metric_detail_index;
+        let style = if selected {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let current = timeline.current().unwrap_or("---");
+        lines.push(Line::from(vec![
+            Span::styled(if selected { "▶ " } else { "  " }, style),
+            Span::styled(timeline.label.clone(), style),
+            Span::styled("  state ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                current.to_string(),
+                Style::default().fg(state_color(current)),
+            ),
+        ]));
+    }
+
+    let state_index = app.metric_detail_index.checked_sub(metrics.len());
+    if let Some(timeline) = state_index.and_then(|i| timelines.get(i)) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let value = Style::default().fg(Color::Cyan);
+        let field = if timeline.field_path.is_empty() {
+            "(whole payload)".to_string()
+        } else {
+            timeline.field_path.clone()
+        };
+        let now = Instant::now();
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Topic   ", dim),
+            Span::styled(timeline.topic_pattern.clone(), value),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Field   ", dim),
+            Span::styled(field, value),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Samples ", dim),
+            Span::styled(
+                timeline.count.to_string(),
+                Style::default().fg(Color::White),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Uptime  ", dim),
+            Span::styled(
+                timeline
+                    .uptime(now)
+                    .map(|uptime| format!("{:.1}%", uptime))
+                    .unwrap_or_else(|| "---".to_string()),
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+
+        // Share of time in each state, most common first
+        let durations = timeline.durations(now);
+        let total: f64 = durations.iter().map(|(_, d)| d.as_secs_f64()).sum();
+        let mut shares = vec![Span::styled("  Time    ", dim)];
+        for (state, spent) in &durations {
+            let share = if total > 0.0 {
+                spent.as_secs_f64() / total * 100.0
+            } else {
+                100.0
+            };
+            shares.push(Span::styled(
+                state.clone(),
+                Style::default().fg(state_color(state)),
+            ));
+            shares.push(Span::styled(format!(" {:.0}%  ", share), dim));
+        }
+        lines.push(Line::from(shares));
+
+        let width = inner.width.saturating_sub(2) as usize;
+        let mut strip = vec![Span::raw("  ")];
+        strip.extend(state_strip_line(&timeline.strip(width, now)).spans);
+        lines.push(Line::from(""));
+        lines.push(Line::from(strip));
+    } else if let Some(metric) = metrics.get(app.metric_detail_index) {
+        let dim = Style::default().fg(Color::DarkGray);
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Topic   ", dim),
+            Span::styled(
+                metric.topic_pattern.clone(),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(
+                if metric.regex.is_some() {
+                    "  Regex   "
+                } else {
+                    "  Field   "
+                },
+                dim,
+            ),
+            Span::styled(metric.field_path.clone(), Style::default().fg(Color::Cyan)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Samples ", dim),
+            Span::styled(metric.count.to_string(), Style::default().fg(Color::White)),
+        ]));
+
+        let mut modes = vec![Span::styled("  Show    ", dim)];
+        for (mode, key) in MODES {
+            let style = if mode == metric.display {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            modes.push(Span::styled(key, Style::default().fg(Color::Yellow)));
+            modes.push(Span::styled(format!(" {} ", mode.label()), style));
+            modes.push(Span::raw(" "));
+        }
+        lines.push(Line::from(modes));
+        lines.push(Line::from(vec![
+            Span::styled("  Decimals", dim),
+            Span::styled(
+                match metric.precision {
+                    Some(digits) => format!(" {}  ", digits),
+                    None => " default  ".to_string(),
+                },
+                Style::default().fg(Color::White),
+            ),
+            Span::styled("0-9", Style::default().fg(Color::Yellow)),
+            Span::styled(" set  ", dim),
+            Span::styled(".", Style::default().fg(Color::Yellow)),
+            Span::styled(" default", dim),
+        ]));
+
+        let width = inner.width.saturating_sub(2);
+        let mut sparkline = vec![Span::raw("  ")];
+        sparkline.extend(
+            sparkline_line(
+                &metric.sparkline_data(width as usize),
+                width,
+                app.config.ui.sparkline_baseline,
+                Style::default().fg(Color::Magenta),
+            )
+            .spans,
+        );
+        lines.push(Line::from(""));
+        lines.push(Line::from(sparkline));
+
+        lines.push(Line::from(""));
+        lines.push(match &app.chart_name_input {
+            Some(name) => {
+                let mut spans = vec![Span::styled("  Add to chart ✎ ", dim)];
+                spans.extend(input_spans(
+                    name,
+                    app.chart_name_cursor,
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                Line::from(spans)
+            }
+            None => Line::from(vec![
+                Span::styled("  c", Style::default().fg(Color::Yellow)),
+                Span::styled(" add to chart  ", dim),
+                Span::styled("v", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!(" view charts ({})", app.metric_tracker.charts().len()),
+                    dim,
+                ),
+            ]),
+        });
+    }
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
@@ -6,8 +6,9 @@ use ratatui::{
     Frame,
 };
 
-use super::widgets::centered_rect;
+use super::widgets::{centered_rect, input_spans, state_color};
 use crate::app::App;
+use crate::state::capture_value;
 
 pub fn render_metric_select(frame: &mut Frame, app: &App) {
     let area = centered_rect(50, 60, frame.area());
@@ -23,7 +24,7 @@ pub fn render_metric_select(frame: &mut Frame, app: &App) {
     frame.render_widget(block.clone(), area);
     let inner = block.inner(area);
 
-    if app.available_fields.is_empty() {
+    if app.available_fields.is_empty() && app.available_states.is_empty() {
         let msg = Paragraph::new("No numeric fields available");
         frame.render_widget(msg, inner);
         return;
@@ -39,8 +40,13 @@ pub fn render_metric_select(frame: &mut Frame, app: &App) {
         ])
         .split(inner);
 
+    let subject = if app.metric_select_text.is_some() {
+        "Select a number in the text to track ("
+    } else {
+        "Select a field to track ("
+    };
     let header = Paragraph::new(Line::from(vec![
-        Span::raw("Select a field to track ("),
+        Span::raw(subject),
         Span::styled("Enter", Style::default().fg(Color::Yellow)),
         Span::raw(" to confirm, "),
         Span::styled("Esc", Style::default().fg(Color::Yellow)),
@@ -48,55 +54,92 @@ pub fn render_metric_select(frame: &mut Frame, app: &App) {
     ]));
     frame.render_widget(header, chunks[0]);
 
-    // Field list
-    let items: Vec<ListItem> = app
+    // Field list, then the states
+    let row_style = |i: usize| {
+        let is_selected = i == app.metric_select_index;
+        let style = if is_selected {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        (if is_selected { "▶ " } else { "  " }, style)
+    };
+    let mut items: Vec<ListItem> = app
         .available_fields
         .iter()
         .enumerate()
         .map(|(i, (field, value))| {
-            let is_selected = i == app.metric_select_index;
-            let style = if is_selected {
-                Style::default()
-                    .fg(Color::Magenta)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            };
-
-            let prefix = if is_selected { "▶ " } else { "  " };
+            let (prefix, style) = row_style(i);
 
             let line = Line::from(vec![
                 Span::styled(prefix, style),
                 Span::styled(field.clone(), style),
                 Span::raw(" = "),
-                Span::styled(format_value(*value), Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    app.config.ui.numbers.value(*value, None),
+                    Style::default().fg(Color::Cyan),
+                ),
             ]);
 
             ListItem::new(line)
         })
         .collect();
+    items.extend(
+        app.available_states
+            .iter()
+            .enumerate()
+            .map(|(i, (field, state))| {
+                let (prefix, style) = row_style(app.available_fields.len() + i);
+                let field = if field.is_empty() { "payload" } else { field };
+                ListItem::new(Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled(field.to_string(), style),
+                    Span::raw(" = "),
+                    Span::styled(state.clone(), Style::default().fg(state_color(state))),
+                    Span::styled("  state", Style::default().fg(Color::DarkGray)),
+                ]))
+            }),
+    );
 
     let list = List::new(items);
     frame.render_widget(list, chunks[1]);
 
+    // Regex being edited, with the value it captures
+    if let (Some(regex), Some(text)) = (&app.regex_input, &app.metric_select_text) {
+        let mut input = vec![Span::styled("✎ ", Style::default().fg(Color::DarkGray))];
+        input.extend(input_spans(
+            regex,
+            app.regex_cursor,
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ));
+        let preview = match capture_value(regex, text) {
+            Ok(Some(value)) => Span::styled(
+                format!("= {}", app.config.ui.numbers.value(value, None)),
+                Style::default().fg(Color::Cyan),
+            ),
+            Ok(None) => Span::styled("no match", Style::default().fg(Color::Yellow)),
+            Err(_) => Span::styled("invalid regex", Style::default().fg(Color::Red)),
+        };
+        let footer = Paragraph::new(vec![Line::from(input), Line::from(preview)]);
+        frame.render_widget(footer, chunks[2]);
+        return;
+    }
+
     // Footer hint
-    let footer = Paragraph::new(Line::from(vec![
+    let mut hints = vec![
         Span::styled("↑↓/jk", Style::default().fg(Color::DarkGray)),
         Span::raw(" navigate  "),
         Span::styled("Enter", Style::default().fg(Color::DarkGray)),
         Span::raw(" select"),
-    ]));
-    frame.render_widget(footer, chunks[2]);
-}
-
-fn format_value(v: f64) -> String {
-    if v.abs() >= 1000000.0 {
-        format!("{:.2}M", v / 1000000.0)
-    } else if v.abs() >= 1000.0 {
-        format!("{:.2}k", v / 1000.0)
-    } else if v.fract() == 0.0 {
-        format!("{:.0}", v)
-    } else {
-        format!("{:.2}", v)
+    ];
+    if app.metric_select_text.is_some() {
+        hints.push(Span::styled("  e", Style::default().fg(Color::DarkGray)));
+        hints.push(Span::raw(" edit regex"));
     }
+    let footer = Paragraph::new(Line::from(hints));
+    frame.render_widget(footer, chunks[2]);
 }
@@ -1,13 +1,35 @@
+mod ansi;
+mod api_docs;
+mod away_batch;
 mod bookmarks;
+mod cardinality_alert;
 mod david;
+mod devices_view;
 mod filter;
 mod help;
+mod kiosk;
+mod log_view;
+mod message_columns;
 mod message_view;
+mod metric_chart;
+mod metric_detail;
 mod metric_select;
+mod note;
+mod palette;
+mod peek;
+mod pipe;
 mod publish;
+mod readout;
+mod scenarios;
 mod search;
 mod server_manager;
+mod server_switch;
+mod session;
+mod starred;
 mod stats_view;
+mod subscribe_warning;
+mod traffic_summary;
+mod tree_export;
 mod tree_view;
 pub mod widgets;
 
@@ -20,30 +42,56 @@ use ratatui::{
 };
 
 use crate::app::{App, InputMode, Panel};
+use crate::mqtt::resilience::ConnectionQuality;
+use crate::text::truncate_width;
 use widgets::key_hint;
 
+pub use ansi::{asciify_buffer, buffer_to_ansi, buffer_to_text};
+pub use api_docs::render_api_docs;
+pub use away_batch::render_away_batch;
 pub use bookmarks::render_bookmark_manager;
+pub use cardinality_alert::render_cardinality_alert;
+pub use devices_view::render_devices;
 pub use filter::render_filter;
 pub use help::render_help;
-pub use message_view::render_messages;
+pub use kiosk::render_kiosk;
+pub use log_view::render_log_view;
+pub use message_columns::render_message_columns;
+pub use message_view::{render_messages, syntax_highlight_json};
+pub use metric_chart::render_metric_chart;
+pub use metric_detail::render_metric_detail;
 pub use metric_select::render_metric_select;
+pub use note::render_note;
+pub use palette::recolor_buffer;
+pub use peek::render_peek;
+pub use pipe::render_pipe;
 pub use publish::render_publish;
+pub use readout::render_readout;
+pub use scenarios::render_scenarios;
 pub use search::render_search;
 pub use server_manager::render_server_manager;
+pub use server_switch::render_server_switch;
+pub use session::render_session_load;
+pub use starred::render_starred_picker;
 pub use stats_view::render_stats;
+pub use subscribe_warning::render_subscribe_warning;
+pub use traffic_summary::render_traffic_summary;
+pub use tree_export::render_tree_export;
 pub use tree_view::render_tree;
 
 /// Main render function
 pub fn render(frame: &mut Frame, app: &mut App) {
     let size = frame.area();
 
-    // Create main layout: header, content, footer
+    // Create main layout: header, content, footer (plus the readout line above
+    // the footer in screen reader mode)
+    let screen_reader = app.config.ui.screen_reader;
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1),
             Constraint::Min(3),
-            Constraint::Length(1),
+            Constraint::Length(if screen_reader { 2 } else { 1 }),
         ])
         .split(size);
 
@@ -52,7 +100,10 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     let show_three_panels = size.width >= 110 && size.height >= 12;
     let show_two_panels = size.width >= 80 && size.height >= 10;
 
-    if show_three_panels {
+    if app.input_mode == InputMode::Kiosk {
+        render_kiosk(frame, app, main_chunks[1]);
+        app.focused_panel_area = None;
+    } else if show_three_panels {
         let content_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -64,7 +115,17 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
         render_tree(frame, app, content_chunks[0]);
         render_messages(frame, app, content_chunks[1]);
-        render_stats(frame, app, content_chunks[2]);
+        // Devices share the right-hand column with Stats
+        if app.focused_panel == Panel::Devices {
+            render_devices(frame, app, content_chunks[2]);
+        } else {
+            render_stats(frame, app, content_chunks[2]);
+        }
+        app.focused_panel_area = Some(match app.focused_panel {
+            Panel::TopicTree => content_chunks[0],
+            Panel::Messages => content_chunks[1],
+            Panel::Stats | Panel::Devices => content_chunks[2],
+        });
     } else if show_two_panels {
         let content_chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -76,6 +137,10 @@ pub fn render(frame: &mut Frame, app: &mut App) {
                 render_messages(frame, app, content_chunks[0]);
                 render_stats(frame, app, content_chunks[1]);
             }
+            Panel::Devices => {
+                render_messages(frame, app, content_chunks[0]);
+                render_devices(frame, app, content_chunks[1]);
+            }
             Panel::Messages => {
                 render_tree(frame, app, content_chunks[0]);
                 render_messages(frame, app, content_chunks[1]);
@@ -85,15 +150,36 @@ pub fn render(frame: &mut Frame, app: &mut App) {
                 render_messages(frame, app, content_chunks[1]);
             }
         }
+        app.focused_panel_area = Some(match app.focused_panel {
+            Panel::TopicTree => content_chunks[0],
+            Panel::Messages | Panel::Stats | Panel::Devices => content_chunks[1],
+        });
     } else {
         match app.focused_panel {
             Panel::TopicTree => render_tree(frame, app, main_chunks[1]),
             Panel::Messages => render_messages(frame, app, main_chunks[1]),
             Panel::Stats => render_stats(frame, app, main_chunks[1]),
+            Panel::Devices => render_devices(frame, app, main_chunks[1]),
         }
+        app.focused_panel_area = Some(main_chunks[1]);
     }
 
-    render_footer(frame, app, main_chunks[2]);
+    if app.peek && app.focused_panel == Panel::TopicTree && app.input_mode == InputMode::Normal {
+        if let Some(area) = app.focused_panel_area {
+            render_peek(frame, app, area);
+        }
+    }
+
+    if screen_reader {
+        let [readout_area, footer_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .areas(main_chunks[2]);
+        render_readout(frame, app, readout_area);
+        render_footer(frame, app, footer_area);
+    } else {
+        render_footer(frame, app, main_chunks[2]);
+    }
 
     if app.input_mode == InputMode::Search {
         render_search(frame, app);
@@ -103,6 +189,14 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_metric_select(frame, app);
     }
 
+    if app.input_mode == InputMode::MetricDetail {
+        render_metric_detail(frame, app);
+    }
+
+    if app.input_mode == InputMode::MetricChart {
+        render_metric_chart(frame, app);
+    }
+
     if app.input_mode == InputMode::Filter {
         render_filter(frame, app);
     }
@@ -119,6 +213,62 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_bookmark_manager(frame, app);
     }
 
+    if app.input_mode == InputMode::Scenarios {
+        render_scenarios(frame, app);
+    }
+
+    if app.input_mode == InputMode::SessionLoad {
+        render_session_load(frame, app);
+    }
+
+    if app.input_mode == InputMode::TreeExport {
+        render_tree_export(frame, app);
+    }
+
+    if app.input_mode == InputMode::LogView {
+        render_log_view(frame, app);
+    }
+
+    if app.input_mode == InputMode::SubscribeWarning {
+        render_subscribe_warning(frame, app);
+    }
+
+    if app.input_mode == InputMode::CardinalityAlert {
+        render_cardinality_alert(frame, app);
+    }
+
+    if app.input_mode == InputMode::StarredPicker {
+        render_starred_picker(frame, app);
+    }
+
+    if app.input_mode == InputMode::Pipe {
+        render_pipe(frame, app);
+    }
+
+    if app.input_mode == InputMode::Note {
+        render_note(frame, app);
+    }
+
+    if app.input_mode == InputMode::ApiDocs {
+        render_api_docs(frame, app);
+    }
+
+    if app.input_mode == InputMode::TrafficSummary {
+        render_traffic_summary(frame, app);
+    }
+
+    if app.input_mode == InputMode::MessageColumns {
+        render_message_columns(frame, app);
+    }
+
+    if app.input_mode == InputMode::AwayBatch {
+        render_away_batch(frame, app);
+    }
+
+    if app.input_mode == InputMode::ServerSwitch {
+        render_server_switch(frame, app);
+    }
+
     if app.show_help {
         render_help(frame);
     }
@@ -126,6 +276,33 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     if app.show_david_easter_egg {
         david::render_david_easter_egg(frame);
     }
+
+    if app.config.ui.ascii || screen_reader {
+        asciify_buffer(frame.buffer_mut(), screen_reader);
+    }
+    recolor_buffer(frame.buffer_mut(), app.config.ui.palette);
+}
+
+/// Title badge from `[ui.header]`, by default `mqtop @ host:port`
+fn header_title(app: &App) -> String {
+    // A replayed session has no broker of its own
+    let server = app
+        .active_server_info()
+        .filter(|_| app.session_source.is_none());
+    match (&app.config.ui.header.title, server) {
+        (Some(title), server) => {
+            let (host, port, name) = server
+                .map(|s| (s.host, s.port.to_string(), s.name))
+                .unwrap_or_default();
+            title
+                .replace("{host}", &host)
+                .replace("{port}", &port)
+                .replace("{server}", &name)
+                .replace("{profile}", app.profile.as_deref().unwrap_or(""))
+        }
+        (None, Some(server)) => format!("mqtop @ {}:{}", server.host, server.port),
+        (None, None) => "mqtop".to_string(),
+    }
 }
 
 fn render_header(frame: &mut Frame, app: &App, area: Rect) {
@@ -152,7 +329,7 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
 
     let mut header_parts = vec![
         Span::styled(
-            " mqtop ",
+            format!(" {} ", header_title(app)),
             Style::default()
                 .fg(Color::Black)
                 .bg(Color::Cyan)
@@ -160,12 +337,16 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         ),
         Span::raw(" "),
         Span::styled(
-            format!("{} {}", conn_indicator, status),
+            if app.config.ui.screen_reader {
+                status.to_string()
+            } else {
+                format!("{} {}", conn_indicator, status)
+            },
             Style::default().fg(color),
         ),
         Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            format!("{}", app.topic_tree.topic_count()),
+            format!("{}", app.application_topic_count()),
             Style::default()
                 .fg(Color::White)
                 .add_modifier(Modifier::BOLD),
@@ -173,7 +354,7 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         Span::styled(" topics", Style::default().fg(Color::DarkGray)),
         Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            format_rate(rate),
+            app.config.ui.numbers.rate(rate),
             Style::default().fg(rate_color).add_modifier(Modifier::BOLD),
         ),
         Span::styled(" msg/s", Style::default().fg(Color::DarkGray)),
@@ -185,7 +366,103 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         Span::styled(" total", Style::default().fg(Color::DarkGray)),
     ];
 
-    if let Some(server) = app.active_server_info() {
+    // Link quality over the last ten minutes, after the connection status
+    let health = &app.connection_health;
+    let now = std::time::Instant::now();
+    if let Some(quality) = health.quality(now) {
+        let quality_color = match quality {
+            ConnectionQuality::Good => Color::Green,
+            ConnectionQuality::Fair => Color::Yellow,
+            ConnectionQuality::Poor => Color::Red,
+        };
+        let drops = health.recent_disconnects(now);
+        let text = if app.config.ui.screen_reader {
+            format!(" link {}", quality.label())
+        } else {
+            format!(" {}", quality.bars())
+        };
+        let mut spans = vec![Span::styled(text, Style::default().fg(quality_color))];
+        if drops > 0 {
+            spans.push(Span::styled(
+                format!(" {} drop{}", drops, if drops == 1 { "" } else { "s" }),
+                Style::default().fg(quality_color),
+            ));
+        }
+        header_parts.splice(3..3, spans);
+    }
+
+    if app.high_load {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            " HIGH LOAD ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Locked-down operator install
+    if app.config.features.role == crate::config::Role::Operator
+        && app.config.ui.header.show_read_only
+    {
+        header_parts.insert(1, Span::raw(" "));
+        header_parts.insert(
+            2,
+            Span::styled(
+                " OPERATOR ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
+
+    let starred = app.user_data.starred_topics.len();
+    if starred > 0 {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("★ {}", starred),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    // Watched topics that went silent
+    let silent = app.watchdog.silent_count();
+    if silent > 0 {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!(" ⏱ {} silent ", silent),
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if let Some(profile) = app
+        .profile
+        .as_ref()
+        .filter(|_| app.config.ui.header.show_profile)
+    {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("profile:{}", profile),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+
+    if let Some(ref source) = app.session_source {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("session:{}", source),
+            Style::default().fg(Color::Magenta),
+        ));
+    } else if let Some(server) = app
+        .active_server_info()
+        .filter(|_| app.config.ui.header.show_server)
+    {
         header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
         header_parts.push(Span::styled(
             format!("{}:{}", server.kind.label(), server.name),
@@ -193,6 +470,62 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         ));
     }
 
+    // Read-only viewers watching via --share
+    if let Some(viewers) = app.share_viewers {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("⇄ {} watching", viewers),
+            Style::default().fg(if viewers > 0 {
+                Color::Magenta
+            } else {
+                Color::DarkGray
+            }),
+        ));
+    }
+
+    // Dashboard or API served with --web / --api
+    if let Some(ref label) = app.web_label {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            label.clone(),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+
+    // Topic being piped to a file or command
+    if let Some(ref pipe) = app.topic_pipe {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("| {}", truncate_width(&pipe.topic, 30)),
+            Style::default().fg(Color::Green),
+        ));
+    }
+
+    if !app.bookmark_schedules.is_empty() {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("⏱ {}", app.bookmark_schedules.len()),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+
+    let awaiting = app.response_watcher.pending();
+    if awaiting > 0 {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("⇄ {} awaiting", awaiting),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    if let Some(run) = &app.scenario_run {
+        header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        header_parts.push(Span::styled(
+            format!("▶ {} {}/{}", run.name, run.sent(), run.total()),
+            Style::default().fg(Color::Green),
+        ));
+    }
+
     // Active filter indicator
     if let Some(ref filter) = app.topic_filter {
         header_parts.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
@@ -223,6 +556,36 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
 
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     let mode_hints: Vec<Span<'static>> = match app.input_mode {
+        InputMode::Normal if app.pending_count.is_some() => {
+            let count = app.pending_count.unwrap_or(0);
+            let mut hints = vec![Span::styled(
+                format!("{count} "),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )];
+            hints.extend(key_hint("j/k", "Move"));
+            hints.extend(key_hint("G", "Go to row"));
+            hints.extend(key_hint("PgUp/PgDn", "Pages"));
+            hints.extend(key_hint("{/}", "Siblings"));
+            hints
+        }
+        InputMode::Normal if app.focused_panel == Panel::Devices => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("↑↓", "Select"));
+            hints.extend(key_hint("s", "Star device"));
+            hints.extend(key_hint("*", "Starred only"));
+            hints.extend(key_hint("Enter", "Messages"));
+            hints.extend(key_hint("Tab", "Next panel"));
+            hints
+        }
+        InputMode::Normal if app.peek => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("↑↓", "Peek"));
+            hints.extend(key_hint("Enter", "Select"));
+            hints.extend(key_hint("v", "Stop peeking"));
+            hints
+        }
         InputMode::Normal => {
             let mut hints = Vec::new();
             hints.extend(key_hint("?", "Help"));
@@ -245,45 +608,228 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
             hints.extend(key_hint("Esc", "Cancel"));
             hints
         }
+        InputMode::MetricSelect if app.regex_input.is_some() => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("Enter", "Track"));
+            hints.extend(key_hint("Esc", "Back"));
+            hints
+        }
         InputMode::MetricSelect => {
             let mut hints = Vec::new();
             hints.extend(key_hint("Enter", "Track"));
             hints.extend(key_hint("↑↓", "Navigate"));
+            if app.metric_select_text.is_some() {
+                hints.extend(key_hint("e", "Edit regex"));
+            }
+            hints.extend(key_hint("Esc", "Cancel"));
+            hints
+        }
+        InputMode::MetricDetail if app.chart_name_input.is_some() => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("Enter", "Add to chart"));
             hints.extend(key_hint("Esc", "Cancel"));
             hints
         }
+        InputMode::MetricChart => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("←→", "Chart"));
+            hints.extend(key_hint("n", "Shared/Normalized"));
+            hints.extend(key_hint("x", "Remove chart"));
+            hints.extend(key_hint("Esc", "Back"));
+            hints
+        }
+        // A tracked state, listed after the metrics
+        InputMode::MetricDetail
+            if app.metric_detail_index >= app.metric_tracker.get_metrics().len() =>
+        {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("↑↓", "Select"));
+            hints.extend(key_hint("x", "Untrack"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::MetricDetail => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("↑↓", "Select"));
+            hints.extend(key_hint("Enter", "Next mode"));
+            hints.extend(key_hint("a/l/d/r", "Absolute/Log/Delta/Rate"));
+            hints.extend(key_hint("0-9", "Decimals"));
+            hints.extend(key_hint("c/v", "Add to/View chart"));
+            hints.extend(key_hint("x", "Untrack"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
         InputMode::Filter => {
             let mut hints = Vec::new();
             hints.extend(key_hint("Enter", "Apply"));
             hints.extend(key_hint("Esc", "Cancel"));
             hints
         }
+        InputMode::SessionLoad => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("Enter", "Open"));
+            hints.extend(key_hint("Esc", "Cancel"));
+            hints
+        }
+        InputMode::Pipe => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("Enter", "Start"));
+            hints.extend(key_hint("Esc", "Cancel"));
+            hints
+        }
+        InputMode::Note => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("Enter", "Save"));
+            hints.extend(key_hint("Esc", "Cancel"));
+            hints
+        }
+        InputMode::ApiDocs => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("↑↓", "Scroll"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::TrafficSummary => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("w", "Daily/Weekly"));
+            hints.extend(key_hint("↑↓", "Scroll"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::MessageColumns if app.column_field_input.is_some() => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("Enter", "Set field"));
+            hints.extend(key_hint("Esc", "Cancel"));
+            hints
+        }
+        InputMode::MessageColumns => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("t/q/r/s", "Timestamp/QoS/Retain/Size"));
+            hints.extend(key_hint("f", "JSON field"));
+            hints.extend(key_hint("a/z", "Times/Zone"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::Breadcrumb => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("←→", "Prefix"));
+            hints.extend(key_hint("y", "Copy"));
+            hints.extend(key_hint("f", "Filter"));
+            hints.extend(key_hint("Enter", "Go to"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::LogView => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("↑↓", "Scroll"));
+            hints.extend(key_hint("g/G", "Oldest/Newest"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::AwayBatch => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("↑↓", "Select"));
+            hints.extend(key_hint("Enter", "Go to topic"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::Kiosk => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("←→", "Page"));
+            hints.extend(key_hint("Esc", "Interactive"));
+            hints.extend(key_hint("q", "Quit"));
+            hints
+        }
+        InputMode::ServerSwitch => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("1-9", "Switch"));
+            hints.extend(key_hint("↑↓", "Select"));
+            hints.extend(key_hint("Enter", "Connect"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::StarredPicker => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("1-9", "Jump"));
+            hints.extend(key_hint("↑↓", "Select"));
+            hints.extend(key_hint("Enter", "Open"));
+            hints.extend(key_hint("Esc", "Close"));
+            hints
+        }
+        InputMode::SubscribeWarning => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("1-5", "Narrow"));
+            hints.extend(key_hint("Esc", "Keep"));
+            hints
+        }
+        InputMode::CardinalityAlert => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("c", "Collapse"));
+            hints.extend(key_hint("-", "Hide"));
+            hints.extend(key_hint("_", "Hide always"));
+            hints.extend(key_hint("Esc", "Keep"));
+            hints
+        }
+        InputMode::TreeExport => {
+            let mut hints = Vec::new();
+            hints.extend(key_hint("t", "Text"));
+            hints.extend(key_hint("j", "JSON"));
+            hints.extend(key_hint("d", "DOT"));
+            hints.extend(key_hint("s", "Schema"));
+            hints.extend(key_hint("a", "AsyncAPI"));
+            hints.extend(key_hint("Esc", "Cancel"));
+            hints
+        }
         InputMode::ServerManager => {
             let mut hints = Vec::new();
             hints.extend(key_hint("Enter", "Connect"));
             hints.extend(key_hint("e", "Edit"));
             hints.extend(key_hint("a", "Add"));
             hints.extend(key_hint("d", "Delete"));
+            hints.extend(key_hint("r", "New client ID"));
             hints.extend(key_hint("Esc", "Close"));
             hints
         }
         InputMode::Publish => {
             let mut hints = Vec::new();
-            hints.extend(key_hint("Enter", "Publish"));
-            hints.extend(key_hint("Tab", "Next"));
-            hints.extend(key_hint("^S", "Bookmark"));
-            hints.extend(key_hint("Esc", "Cancel"));
+            if app.publish_edit.preview.is_some() {
+                hints.extend(key_hint("Enter", "Send"));
+                hints.extend(key_hint("↑↓", "Scroll"));
+                hints.extend(key_hint("Esc", "Back"));
+            } else {
+                hints.extend(key_hint("Enter", "Publish"));
+                hints.extend(key_hint("Tab", "Next"));
+                hints.extend(key_hint("^S", "Bookmark"));
+                hints.extend(key_hint("^D", "Preview"));
+                hints.extend(key_hint("Esc", "Cancel"));
+            }
             hints
         }
         InputMode::BookmarkManager => {
             let mut hints = Vec::new();
-            hints.extend(key_hint("Enter", "Publish"));
+            hints.extend(key_hint("Enter", "Publish/Fold"));
             hints.extend(key_hint("e", "Edit"));
             hints.extend(key_hint("a", "Add"));
             hints.extend(key_hint("d", "Delete"));
+            hints.extend(key_hint("t", "Schedule"));
+            hints.extend(key_hint("x/i", "Export/Import"));
             hints.extend(key_hint("Esc", "Close"));
             hints
         }
+        InputMode::Scenarios => {
+            let mut hints = Vec::new();
+            if app.scenario_manager.editing.is_some() {
+                hints.extend(key_hint("^S", "Save"));
+                hints.extend(key_hint("Tab", "Name/Steps"));
+                hints.extend(key_hint("Esc", "Cancel"));
+            } else {
+                hints.extend(key_hint("Enter", "Run"));
+                hints.extend(key_hint("s", "Stop"));
+                hints.extend(key_hint("a/e/d", "Add/Edit/Delete"));
+                hints.extend(key_hint("Esc", "Close"));
+            }
+            hints
+        }
     };
 
     // Check for status message first
@@ -313,10 +859,16 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!(" {} ", truncate_str(err, 40)),
+                format!(" {} ", truncate_width(&err.summary, 40)),
                 Style::default().fg(Color::Red),
             ),
         ];
+        if let Some(hint) = err.hint {
+            parts.push(Span::styled(
+                format!("→ {} (D: log) ", truncate_width(hint, 60)),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
         parts.extend(mode_hints);
         Line::from(parts)
     } else {
@@ -350,23 +902,3 @@ pub fn bordered_block(title: &str, focused: bool) -> Block<'_> {
             },
         ))
 }
-
-fn format_rate(rate: f64) -> String {
-    if rate >= 1000.0 {
-        format!("{:.1}k", rate / 1000.0)
-    } else if rate >= 1.0 {
-        format!("{:.1}", rate)
-    } else if rate > 0.0 {
-        format!("{:.2}", rate)
-    } else {
-        "0".to_string()
-    }
-}
-
-fn truncate_str(s: &str, max_len: usize) -> &str {
-    if s.len() <= max_len {
-        s
-    } else {
-        &s[..max_len]
-    }
-}
@@ -0,0 +1,67 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::{centered_rect, input_spans};
+use crate::app::App;
+
+pub fn render_note(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Topic Note ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(block.clone(), area);
+    let inner = block.inner(area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Min(1),
+        ])
+        .split(inner);
+
+    let topic = app.selected_topic.as_deref().unwrap_or_default();
+    let instructions = Paragraph::new(vec![
+        Line::from(vec![
+            Span::raw("Note for "),
+            Span::styled(topic, Style::default().fg(Color::Cyan)),
+            Span::raw(":"),
+        ]),
+        Line::from(Span::styled(
+            "e.g. published by the legacy gateway, ignore spikes",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ]);
+    frame.render_widget(instructions, chunks[0]);
+
+    let mut spans = vec![Span::styled("✎ ", Style::default().fg(Color::Cyan))];
+    spans.extend(input_spans(
+        &app.note_input,
+        app.note_cursor,
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ));
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[1]);
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(Color::Yellow)),
+        Span::raw(" save (empty removes)  "),
+        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+        Span::raw(" cancel"),
+    ]));
+    frame.render_widget(footer, chunks[2]);
+}
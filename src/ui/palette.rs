@@ -0,0 +1,60 @@
+use ratatui::{buffer::Buffer, style::Color};
+
+use crate::config::Palette;
+
+// Okabe-Ito colors, distinguishable with red-green color blindness
+const BLUE: Color = Color::Rgb(0, 114, 178);
+const SKY_BLUE: Color = Color::Rgb(86, 180, 233);
+const VERMILLION: Color = Color::Rgb(213, 94, 0);
+const ORANGE: Color = Color::Rgb(230, 159, 0);
+const YELLOW: Color = Color::Rgb(240, 228, 66);
+
+/// Recolor a rendered frame for `palette`. Views keep using the named
+/// red/green/yellow colors, so one pass here covers every panel and overlay.
+pub fn recolor_buffer(buffer: &mut Buffer, palette: Palette) {
+    if palette == Palette::Default {
+        return;
+    }
+    for cell in buffer.content.iter_mut() {
+        cell.fg = foreground(palette, cell.fg);
+        if palette != Palette::HighContrast {
+            // High contrast leaves backgrounds alone so bright text stays readable
+            cell.bg = color_blind(palette, cell.bg);
+        }
+    }
+}
+
+fn foreground(palette: Palette, color: Color) -> Color {
+    match palette {
+        Palette::Default => color,
+        Palette::Deuteranopia | Palette::Protanopia => color_blind(palette, color),
+        Palette::HighContrast => match color {
+            Color::Red => Color::LightRed,
+            Color::Green => Color::LightGreen,
+            Color::Yellow => Color::LightYellow,
+            Color::Blue => Color::LightBlue,
+            Color::Cyan => Color::LightCyan,
+            Color::Magenta => Color::LightMagenta,
+            Color::Gray => Color::White,
+            Color::DarkGray => Color::Gray,
+            other => other,
+        },
+    }
+}
+
+/// Good becomes blue, bad becomes vermillion (orange for protanopia, where
+/// reds look dark), and warnings a yellow clear of both
+fn color_blind(palette: Palette, color: Color) -> Color {
+    let bad = if palette == Palette::Protanopia {
+        ORANGE
+    } else {
+        VERMILLION
+    };
+    match color {
+        Color::Green | Color::LightGreen => SKY_BLUE,
+        Color::Blue => BLUE,
+        Color::Red | Color::LightRed => bad,
+        Color::Yellow | Color::LightYellow => YELLOW,
+        other => other,
+    }
+}
@@ -0,0 +1,88 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::text::ellipsize;
+
+/// Payload lines shown at most
+const PEEK_LINES: usize = 6;
+
+/// Latest message at the highlighted tree row, next to the row, while peeking
+pub fn render_peek(frame: &mut Frame, app: &App, tree_area: Rect) {
+    let visible = app.get_visible_topics();
+    let Some(row) = visible.get(app.selected_topic_index) else {
+        return;
+    };
+    let screen = frame.area();
+    let x = tree_area.x + (tree_area.width / 3).min(12);
+    let width = screen.right().saturating_sub(x + 1).min(64);
+    let inner_width = width.saturating_sub(2) as usize;
+    if inner_width < 10 {
+        return;
+    }
+
+    let mut lines = Vec::new();
+    let latest = app
+        .peek_topic(&row.full_path)
+        .and_then(|topic| Some((app.message_buffer.get_latest(&topic)?, topic)));
+    match latest {
+        Some((msg, topic)) => {
+            if topic != row.full_path {
+                lines.push(Line::from(Span::styled(
+                    ellipsize(&format!("latest below: {}", topic), inner_width),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+            let mut meta = format!(
+                "{}  QoS {}  {}",
+                app.config.ui.timezone.format(msg.timestamp, "%H:%M:%S"),
+                msg.qos,
+                app.config.ui.numbers.bytes(msg.payload_size() as u64)
+            );
+            if msg.retain {
+                meta.push_str("  retained");
+            }
+            lines.push(Line::from(Span::styled(
+                meta,
+                Style::default().fg(Color::DarkGray),
+            )));
+            let payload = app.format_payload(msg);
+            lines.extend(
+                payload
+                    .lines()
+                    .take(PEEK_LINES)
+                    .map(|line| Line::from(ellipsize(line, inner_width))),
+            );
+        }
+        None => lines.push(Line::from(Span::styled(
+            "No messages yet",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+
+    // Below the highlighted row, or above it near the bottom of the screen
+    let height = lines.len() as u16 + 2;
+    let row_y = tree_area.y + 1 + app.selected_topic_index.saturating_sub(app.tree_scroll) as u16;
+    let y = if row_y + 1 + height < screen.bottom() {
+        row_y + 1
+    } else {
+        row_y.saturating_sub(height)
+    };
+    let area = Rect::new(x, y, width, height.min(screen.height));
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " {} ",
+            ellipsize(&row.segment, inner_width.saturating_sub(2))
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
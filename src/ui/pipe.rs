@@ -0,0 +1,68 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::{centered_rect, input_spans};
+use crate::app::App;
+
+pub fn render_pipe(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Pipe Topic ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(block.clone(), area);
+    let inner = block.inner(area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Min(1),
+        ])
+        .split(inner);
+
+    let topic = app.selected_topic.as_deref().unwrap_or_default();
+    let instructions = Paragraph::new(vec![
+        Line::from(vec![
+            Span::raw("Pipe new payloads of "),
+            Span::styled(topic, Style::default().fg(Color::Cyan)),
+            Span::raw(" to:"),
+        ]),
+        Line::from(Span::styled(
+            "command (e.g. jq -c . >> out.jsonl) or >> file",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ]);
+    frame.render_widget(instructions, chunks[0]);
+
+    let mut spans = vec![Span::styled("| ", Style::default().fg(Color::Yellow))];
+    spans.extend(input_spans(
+        &app.pipe_input,
+        app.pipe_cursor,
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ));
+    let input = Paragraph::new(Line::from(spans));
+    frame.render_widget(input, chunks[1]);
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(Color::Yellow)),
+        Span::raw(" start  "),
+        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+        Span::raw(" cancel"),
+    ]));
+    frame.render_widget(footer, chunks[2]);
+}
@@ -1,19 +1,20 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    text::Line,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
 use super::widgets::{
-    centered_rect, dialog_key_hint, render_multiline_field, render_qos_field, render_retain_field,
-    render_text_field,
+    centered_rect, completion_line, dialog_key_hint, format_age, render_multiline_field,
+    render_qos_field, render_retain_field, render_text_field,
 };
 use crate::app::{App, PublishField};
+use crate::lint::{DiffLine, PublishPreview, Severity};
 
 pub fn render_publish(frame: &mut Frame, app: &App) {
-    let area = centered_rect(60, 50, frame.area());
+    let area = centered_rect(60, 60, frame.area());
 
     frame.render_widget(Clear, area);
 
@@ -32,8 +33,10 @@ pub fn render_publish(frame: &mut Frame, app: &App) {
         .margin(1)
         .constraints([
             Constraint::Length(3), // Topic
+            Constraint::Length(1), // Topic completions
             Constraint::Min(5),    // Payload
             Constraint::Length(3), // QoS + Retain
+            Constraint::Length(3), // Expected response
             Constraint::Length(2), // Help text
         ])
         .split(inner);
@@ -48,6 +51,16 @@ pub fn render_publish(frame: &mut Frame, app: &App) {
         chunks[0],
     );
 
+    if app.publish_edit.field == PublishField::Topic {
+        let edit = &app.publish_edit;
+        let completions = app.topic_completions(&edit.topic, edit.cursor);
+        let separator = app.connected_broker_kind.topic_separator();
+        frame.render_widget(
+            Paragraph::new(completion_line(&edit.topic, &completions, separator)),
+            chunks[1],
+        );
+    }
+
     // Payload field (multi-line)
     render_multiline_field(
         frame,
@@ -55,14 +68,14 @@ pub fn render_publish(frame: &mut Frame, app: &App) {
         &app.publish_edit.payload,
         app.publish_edit.cursor,
         app.publish_edit.field == PublishField::Payload,
-        chunks[1],
+        chunks[2],
     );
 
     // QoS and Retain fields on same row
     let options_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(chunks[2]);
+        .split(chunks[3]);
 
     render_qos_field(
         frame,
@@ -78,11 +91,128 @@ pub fn render_publish(frame: &mut Frame, app: &App) {
         options_chunks[1],
     );
 
+    render_text_field(
+        frame,
+        "Expect response (topic pattern [timeout], optional)",
+        &app.publish_edit.response,
+        app.publish_edit.cursor,
+        app.publish_edit.field == PublishField::Response,
+        chunks[4],
+    );
+
     // Help text
     let mut hints = Vec::new();
     hints.extend(dialog_key_hint("Enter", "Publish"));
-    hints.extend(dialog_key_hint("Tab", "Next"));
+    hints.extend(dialog_key_hint("Tab", "Complete/Next"));
     hints.extend(dialog_key_hint("^S", "Bookmark"));
+    hints.extend(dialog_key_hint("^D", "Preview"));
     hints.extend(dialog_key_hint("Esc", "Cancel"));
-    frame.render_widget(Paragraph::new(Line::from(hints)), chunks[3]);
+    frame.render_widget(Paragraph::new(Line::from(hints)), chunks[5]);
+
+    if let Some(preview) = &app.publish_edit.preview {
+        render_publish_preview(frame, app, preview);
+    }
+}
+
+/// Dry-run overlay: lint findings, then the diff against the last message
+fn render_publish_preview(frame: &mut Frame, app: &App, preview: &PublishPreview) {
+    let area = centered_rect(70, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let (title, border) = if preview.has_errors() {
+        (" Preview - payload has errors ", Color::Red)
+    } else {
+        (" Preview ", Color::Cyan)
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+
+    let edit = &app.publish_edit;
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            edit.topic.trim().to_string(),
+            Style::default().fg(Color::White),
+        ),
+        Span::styled(
+            format!(
+                "  {} bytes, QoS {}{}",
+                edit.payload.len(),
+                edit.qos,
+                if edit.retain { ", retained" } else { "" }
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])];
+    lines.push(Line::from(""));
+
+    if preview.issues.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "✓ No problems found",
+            Style::default().fg(Color::Green),
+        )));
+    }
+    for issue in &preview.issues {
+        let (mark, color) = match issue.severity {
+            Severity::Error => ("✗ ", Color::Red),
+            Severity::Warning => ("! ", Color::Yellow),
+            Severity::Info => ("i ", Color::Cyan),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(mark, Style::default().fg(color)),
+            Span::styled(issue.message.clone(), Style::default().fg(color)),
+        ]));
+    }
+    lines.push(Line::from(""));
+
+    match (&preview.diff, preview.last_timestamp) {
+        (Some(diff), Some(timestamp)) => {
+            let age_ms = (chrono::Utc::now() - timestamp).num_milliseconds();
+            lines.push(Line::from(Span::styled(
+                format!("Changes from last message ({})", format_age(age_ms)),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            if diff.iter().all(|line| matches!(line, DiffLine::Same(_))) {
+                lines.push(Line::from(Span::styled(
+                    "  identical to the last message",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            for line in diff {
+                let (prefix, text, color) = match line {
+                    DiffLine::Same(text) => ("  ", text, Color::DarkGray),
+                    DiffLine::Added(text) => ("+ ", text, Color::Green),
+                    DiffLine::Removed(text) => ("- ", text, Color::Red),
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("{}{}", prefix, text),
+                    Style::default().fg(color),
+                )));
+            }
+        }
+        _ => lines.push(Line::from(Span::styled(
+            "No previous message on this topic",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+
+    let height = chunks[0].height as usize;
+    let scroll = preview.scroll.min(lines.len().saturating_sub(height));
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[0]);
+
+    let mut hints = Vec::new();
+    hints.extend(dialog_key_hint("Enter", "Send"));
+    hints.extend(dialog_key_hint("↑↓", "Scroll"));
+    hints.extend(dialog_key_hint("Esc", "Back to edit"));
+    frame.render_widget(Paragraph::new(Line::from(hints)), chunks[1]);
 }
@@ -0,0 +1,117 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::widgets::format_age;
+use crate::app::{App, InputMode, Panel};
+
+/// Characters of payload read out for the selected message
+const READOUT_PAYLOAD_CHARS: usize = 120;
+
+/// One plain sentence about the current selection, for screen readers. The
+/// terminal cursor is parked on it so readers that follow the cursor speak it.
+pub fn render_readout(frame: &mut Frame, app: &App, area: Rect) {
+    let mut text = readout(app);
+    if let Some(status) = app.get_status() {
+        text.push_str(". ");
+        text.push_str(status);
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            text,
+            Style::default().fg(Color::White),
+        ))),
+        area,
+    );
+
+    if app.input_mode == InputMode::Normal && !app.show_help {
+        frame.set_cursor_position((area.x, area.y));
+    }
+}
+
+fn readout(app: &App) -> String {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    match app.focused_panel {
+        Panel::TopicTree => {
+            let topics = app.get_visible_topics();
+            let Some(topic) = topics.get(app.selected_topic_index) else {
+                return "Topics: none yet".to_string();
+            };
+            let kind = match (topic.has_children, topic.is_expanded) {
+                (false, _) => "leaf",
+                (true, true) => "expanded",
+                (true, false) => "collapsed",
+            };
+            let mut text = format!(
+                "Topic {}, {} of {}, {}, {} messages",
+                topic.full_path,
+                app.selected_topic_index + 1,
+                topics.len(),
+                kind,
+                topic.message_count
+            );
+            if let Some(latest) = topic.latest_activity {
+                text.push_str(&format!(", last {}", format_age(now_ms - latest)));
+            }
+            if app.is_starred(&topic.full_path) {
+                text.push_str(", starred");
+            }
+            if let Some(note) = app.user_data.note(&topic.full_path) {
+                text.push_str(&format!(", note: {}", note));
+            }
+            text
+        }
+        Panel::Messages => {
+            let messages = app.get_current_messages();
+            let Some(msg) = messages.get(app.selected_message_index) else {
+                return match &app.selected_topic {
+                    Some(topic) => format!("Messages on {}: none", topic),
+                    None => "Messages: select a topic first".to_string(),
+                };
+            };
+            let payload = app
+                .format_payload(msg)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let payload: String = payload.chars().take(READOUT_PAYLOAD_CHARS).collect();
+            format!(
+                "Message {} of {} on {}, {}, {} bytes, QoS {}{}: {}",
+                app.selected_message_index + 1,
+                messages.len(),
+                msg.topic,
+                app.config.ui.timezone.format(msg.timestamp, "%H:%M:%S"),
+                msg.payload.len(),
+                msg.qos,
+                if msg.retain { ", retained" } else { "" },
+                payload
+            )
+        }
+        Panel::Stats => format!(
+            "Stats: {}, {:.1} messages per second, {} topics, {} messages total",
+            app.connection_status(),
+            app.stats.messages_per_second(),
+            app.application_topic_count(),
+            app.stats.total_messages()
+        ),
+        Panel::Devices => {
+            let devices = app.visible_devices();
+            let Some(device) = devices.get(app.device_selected_index) else {
+                return "Devices: none yet".to_string();
+            };
+            format!(
+                "Device {}, {} of {}, {}, last seen {}, {} messages",
+                device.device_id,
+                app.device_selected_index + 1,
+                devices.len(),
+                device.status.label(),
+                device.last_seen_string(),
+                device.message_count
+            )
+        }
+    }
+}
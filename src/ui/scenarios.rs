@@ -0,0 +1,209 @@
+use std::time::Instant;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+use super::widgets::{centered_rect, dialog_key_hint, render_multiline_field, render_text_field};
+use crate::app::App;
+use crate::text::ellipsize;
+
+pub fn render_scenarios(frame: &mut Frame, app: &App) {
+    if app.scenario_manager.editing.is_some() {
+        render_scenario_edit(frame, app);
+        return;
+    }
+
+    let area = centered_rect(60, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Scenarios ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let progress_height = if app.scenario_run.is_some() { 2 } else { 0 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(progress_height),
+            Constraint::Length(2),
+        ])
+        .split(inner);
+
+    let scenarios = &app.user_data.scenarios;
+    if scenarios.is_empty() {
+        let empty_msg = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "No scenarios yet",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press 'a' to add an ordered list of publishes",
+                Style::default().fg(Color::Yellow),
+            )),
+        ])
+        .alignment(ratatui::layout::Alignment::Center);
+        frame.render_widget(empty_msg, chunks[0]);
+    } else {
+        let visible = chunks[0].height as usize;
+        let selected = app.scenario_manager.selected_index;
+        let offset = (selected + 1).saturating_sub(visible);
+        let running = app.scenario_run.as_ref().map(|run| run.name.as_str());
+
+        let items: Vec<ListItem> = scenarios
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(idx, scenario)| {
+                let is_selected = idx == selected;
+                let style = if is_selected {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let mut spans = vec![
+                    Span::styled(if is_selected { "▶ " } else { "  " }, style),
+                    Span::styled(scenario.name.as_str(), style),
+                    Span::styled(
+                        format!(
+                            "  {} steps, {:.1}s",
+                            scenario.steps.len(),
+                            scenario.duration().as_secs_f64()
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ];
+                if running == Some(scenario.name.as_str()) {
+                    spans.push(Span::styled("  running", Style::default().fg(Color::Green)));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        frame.render_widget(List::new(items), chunks[0]);
+    }
+
+    if let Some(run) = &app.scenario_run {
+        let mut lines = vec![Line::from(vec![
+            Span::styled(
+                format!("▶ {} ", run.name),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{}/{} sent", run.sent(), run.total()),
+                Style::default().fg(Color::White),
+            ),
+        ])];
+        if let Some((step, wait)) = run.upcoming(Instant::now()) {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  next: {} in {:.1}s",
+                    ellipsize(&step.topic, 40),
+                    wait.as_secs_f64()
+                ),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[1]);
+    }
+
+    let mut hints = Vec::new();
+    hints.extend(dialog_key_hint("Enter", "Run"));
+    hints.extend(dialog_key_hint("s", "Stop"));
+    hints.extend(dialog_key_hint("a", "Add"));
+    hints.extend(dialog_key_hint("e", "Edit"));
+    hints.extend(dialog_key_hint("d", "Delete"));
+    hints.extend(dialog_key_hint("Esc", "Close"));
+    frame.render_widget(
+        Paragraph::new(Line::from(hints)).wrap(Wrap { trim: true }),
+        chunks[2],
+    );
+}
+
+fn render_scenario_edit(frame: &mut Frame, app: &App) {
+    let Some(editing) = &app.scenario_manager.editing else {
+        return;
+    };
+
+    let area = centered_rect(70, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let title = if editing.is_new {
+        " New Scenario "
+    } else {
+        " Edit Scenario "
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Name
+            Constraint::Min(5),    // Steps
+            Constraint::Length(2), // Syntax
+            Constraint::Length(1), // Help text
+        ])
+        .split(inner);
+
+    render_text_field(
+        frame,
+        "Name",
+        &editing.name,
+        editing.cursor,
+        !editing.on_steps,
+        chunks[0],
+    );
+    render_multiline_field(
+        frame,
+        "Steps (one publish per line)",
+        &editing.steps,
+        editing.cursor,
+        editing.on_steps,
+        chunks[1],
+    );
+
+    let syntax = vec![
+        Line::from(vec![
+            Span::styled(
+                "[+500ms] [qos=1] [retain] ",
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled("topic payload", Style::default().fg(Color::White)),
+        ]),
+        Line::from(Span::styled(
+            "Delay waits after the previous step; # starts a comment",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(syntax), chunks[2]);
+
+    let mut hints = Vec::new();
+    hints.extend(dialog_key_hint("^S", "Save"));
+    hints.extend(dialog_key_hint("Tab", "Name/Steps"));
+    hints.extend(dialog_key_hint("Enter", "New line"));
+    hints.extend(dialog_key_hint("Esc", "Cancel"));
+    frame.render_widget(Paragraph::new(Line::from(hints)), chunks[3]);
+}
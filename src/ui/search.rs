@@ -6,8 +6,9 @@ use ratatui::{
     Frame,
 };
 
-use super::widgets::centered_rect;
+use super::widgets::{centered_rect, input_spans};
 use crate::app::App;
+use crate::text::ellipsize;
 
 pub fn render_search(frame: &mut Frame, app: &App) {
     let area = centered_rect(60, 50, frame.area());
@@ -38,16 +39,13 @@ pub fn render_search(frame: &mut Frame, app: &App) {
         .borders(Borders::BOTTOM)
         .border_style(Style::default().fg(Color::DarkGray));
 
-    let input_text = Line::from(vec![
-        Span::styled("/ ", Style::default().fg(Color::Cyan)),
-        Span::raw(&app.search_query),
-        Span::styled(
-            "▌",
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::SLOW_BLINK),
-        ),
-    ]);
+    let mut spans = vec![Span::styled("/ ", Style::default().fg(Color::Cyan))];
+    spans.extend(input_spans(
+        &app.search_query,
+        app.search_cursor,
+        Style::default(),
+    ));
+    let input_text = Line::from(spans);
 
     let input = Paragraph::new(input_text).block(input_block);
     frame.render_widget(input, chunks[0]);
@@ -91,6 +89,17 @@ pub fn render_search(frame: &mut Frame, app: &App) {
                 let prefix = if is_selected { "▶ " } else { "  " };
                 let mut spans = vec![Span::styled(prefix, style)];
                 spans.extend(highlighted);
+                let query = app.search_query.to_lowercase();
+                if let Some(note) = app
+                    .user_data
+                    .note(topic)
+                    .filter(|note| note.to_lowercase().contains(&query))
+                {
+                    spans.push(Span::styled(
+                        format!("  ✎ {}", ellipsize(note, 40)),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
 
                 ListItem::new(Line::from(spans))
             })
@@ -144,11 +153,7 @@ pub fn render_search(frame: &mut Frame, app: &App) {
 }
 
 fn highlight_match(text: &str, query: &str) -> Vec<Span<'static>> {
-    let text_lower = text.to_lowercase();
-    let query_lower = query.to_lowercase();
-
-    if let Some(start) = text_lower.find(&query_lower) {
-        let end = start + query.len();
+    if let Some((start, end)) = find_case_insensitive(text, query) {
         vec![
             Span::raw(text[..start].to_string()),
             Span::styled(
@@ -163,3 +168,27 @@ fn highlight_match(text: &str, query: &str) -> Vec<Span<'static>> {
         vec![Span::raw(text.to_string())]
     }
 }
+
+/// Byte range of the first case-insensitive match of `query` in `text`.
+/// Compares char by char since lowercasing can change byte lengths.
+fn find_case_insensitive(text: &str, query: &str) -> Option<(usize, usize)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return None;
+    }
+    text.char_indices().find_map(|(start, _)| {
+        let mut matched = 0;
+        for (offset, ch) in text[start..].char_indices() {
+            for lower in ch.to_lowercase() {
+                if query.get(matched) != Some(&lower) {
+                    return None;
+                }
+                matched += 1;
+            }
+            if matched == query.len() {
+                return Some((start, start + offset + ch.len_utf8()));
+            }
+        }
+        None
+    })
+}
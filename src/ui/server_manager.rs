@@ -6,9 +6,10 @@ use ratatui::{
     Frame,
 };
 
-use super::widgets::{centered_rect, dialog_key_hint};
+use super::widgets::{centered_rect, dialog_key_hint, input_spans};
 use crate::app::{App, NatsServerField, ServerField};
 use crate::broker::BrokerKind;
+use crate::mqtt::subscription::parse_shared;
 
 pub fn render_server_manager(frame: &mut Frame, app: &App) {
     let area = centered_rect(70, 70, frame.area());
@@ -99,6 +100,22 @@ pub fn render_server_manager(frame: &mut Frame, app: &App) {
                     spans.push(Span::raw("  "));
                     spans.push(Span::styled("TLS", Style::default().fg(Color::Green)));
                 }
+                if let Some(shared) = parse_shared(&server.subscribe_topic) {
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::styled(
+                        format!("share:{}", shared.group),
+                        Style::default().fg(Color::Magenta),
+                    ));
+                }
+                // The ID in use, with the suffix generated for this connection
+                let connected = is_active && app.connected_broker_kind == BrokerKind::Mqtt;
+                if let Some(details) = app.connection_details.as_ref().filter(|_| connected) {
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::styled(
+                        format!("id:{}", details.client_id),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
                 ListItem::new(Line::from(spans))
             })
             .collect(),
@@ -201,16 +218,7 @@ fn render_server_edit(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::DarkGray),
             )];
             if is_active && !field.is_checkbox() {
-                let cursor = app.server_edit.cursor.min(value.len());
-                let (head, tail) = value.split_at(cursor);
-                spans.push(Span::styled(head.to_string(), style));
-                spans.push(Span::styled(
-                    "▌",
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::SLOW_BLINK),
-                ));
-                spans.push(Span::styled(tail.to_string(), style));
+                spans.extend(input_spans(&value, app.server_edit.cursor, style));
                 // Show placeholder hint for empty Client ID
                 if *field == ServerField::ClientId && value.is_empty() {
                     spans.push(Span::styled(
@@ -288,16 +296,7 @@ fn render_nats_server_edit(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::DarkGray),
             )];
             if is_active && !field.is_checkbox() {
-                let cursor = app.nats_server_edit.cursor.min(value.len());
-                let (head, tail) = value.split_at(cursor);
-                spans.push(Span::styled(head.to_string(), style));
-                spans.push(Span::styled(
-                    "▌",
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::SLOW_BLINK),
-                ));
-                spans.push(Span::styled(tail.to_string(), style));
+                spans.extend(input_spans(&value, app.nats_server_edit.cursor, style));
             } else {
                 spans.push(Span::styled(value, style));
                 if is_active && field.is_checkbox() {
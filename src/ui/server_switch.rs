@@ -0,0 +1,75 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::centered_rect;
+use crate::app::App;
+
+/// Quick switcher between configured servers, without the full server manager
+pub fn render_server_switch(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let servers = app.switchable_servers();
+    let active = app
+        .active_server_info()
+        .filter(|_| app.session_source.is_none());
+    let block = Block::default()
+        .title(format!(" Switch Server ({}) ", servers.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    let height = inner.height as usize;
+    let scroll = app
+        .server_switch_index
+        .saturating_sub(height.saturating_sub(1));
+
+    let lines: Vec<Line> = servers
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(height)
+        .map(|(i, (_, server))| {
+            let shortcut = if i < 9 {
+                format!(" {} ", i + 1)
+            } else {
+                "   ".to_string()
+            };
+            let is_active = active
+                .as_ref()
+                .is_some_and(|a| a.kind == server.kind && a.name == server.name);
+            let name_style = if i == app.server_switch_index {
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let detail = match app.parked_server(server.kind, &server.name) {
+                Some(parked) => format!("  {} topics kept", parked.topic_count()),
+                None => String::new(),
+            };
+            Line::from(vec![
+                Span::styled(shortcut, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    if is_active { "● " } else { "  " },
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(server.name.clone(), name_style),
+                Span::styled(
+                    format!("  {} {}:{}", server.kind.label(), server.host, server.port),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(detail, Style::default().fg(Color::Cyan)),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
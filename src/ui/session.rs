@@ -0,0 +1,63 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::{centered_rect, input_spans};
+use crate::app::App;
+
+pub fn render_session_load(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Open Session ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(block.clone(), area);
+    let inner = block.inner(area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Min(1),
+        ])
+        .split(inner);
+
+    let instructions = Paragraph::new(Line::from(vec![
+        Span::raw("Session file: "),
+        Span::styled(
+            "(live connection is closed while viewing)",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    frame.render_widget(instructions, chunks[0]);
+
+    let mut spans = vec![Span::styled("> ", Style::default().fg(Color::Yellow))];
+    spans.extend(input_spans(
+        &app.session_input,
+        app.session_cursor,
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ));
+    let input = Paragraph::new(Line::from(spans));
+    frame.render_widget(input, chunks[1]);
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(Color::Yellow)),
+        Span::raw(" open  "),
+        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+        Span::raw(" cancel"),
+    ]));
+    frame.render_widget(footer, chunks[2]);
+}
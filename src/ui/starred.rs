@@ -0,0 +1,84 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::{centered_rect, format_age};
+use crate::app::App;
+use crate::state::format_window;
+
+pub fn render_starred_picker(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 50, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let starred = app.starred_topics();
+    let block = Block::default()
+        .title(format!(" ★ Starred Topics ({}) ", starred.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(area);
+    let height = inner.height as usize;
+
+    // Keep the selection in view
+    let scroll = app
+        .starred_picker_index
+        .saturating_sub(height.saturating_sub(1));
+    let now_ms = chrono::Utc::now().timestamp_millis();
+
+    let lines: Vec<Line> = starred
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(height)
+        .map(|(i, topic)| {
+            let selected = i == app.starred_picker_index;
+            let shortcut = if i < 9 {
+                format!(" {} ", i + 1)
+            } else {
+                "   ".to_string()
+            };
+            let topic_style = if selected {
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let detail = match app.topic_tree.get_topic_stats(topic) {
+                Some((count, _, last)) => {
+                    let age = last
+                        .map(|t| format_age(now_ms - t))
+                        .unwrap_or_else(|| "-".to_string());
+                    format!("  {} msgs, {}", count, age)
+                }
+                None => "  no messages yet".to_string(),
+            };
+
+            let mut spans = vec![
+                Span::styled(shortcut, Style::default().fg(Color::Yellow)),
+                Span::styled(topic.clone(), topic_style),
+                Span::styled(detail, Style::default().fg(Color::DarkGray)),
+            ];
+            if let Some(window) = app.watchdog_for(topic) {
+                let color = if app.watchdog.is_silent(topic) {
+                    Color::Red
+                } else {
+                    Color::DarkGray
+                };
+                spans.push(Span::styled(
+                    format!("  ⏱ {}", format_window(window)),
+                    Style::default().fg(color),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -7,9 +9,20 @@ use ratatui::{
 };
 
 use super::bordered_block;
+use super::widgets::{sparkline_line, state_color, state_strip_line};
 use crate::app::{App, Panel};
 use crate::broker::BrokerKind;
-use crate::state::{render_sparkline, HealthStatus, LatencyTracker, Stats};
+use crate::state::{HealthStatus, LatencyTracker, MetricDisplay};
+use crate::text::{ellipsize, fit_width, truncate_width};
+
+/// Number of topic prefixes shown in the bandwidth breakdown
+const TOP_PREFIXES: usize = 5;
+/// Drifted topics listed under the schema baseline
+const MAX_DRIFT_TOPICS: usize = 5;
+/// Topic moves listed before "+N more"
+const MAX_TOPIC_MOVES: usize = 5;
+/// Busiest topics listed from the saved totals
+const TOP_TALKERS: usize = 5;
 
 pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
     let focused = app.focused_panel == Panel::Stats;
@@ -18,6 +31,10 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
 
     frame.render_widget(block, area);
 
+    // Sparklines span the panel, less the indent
+    let sparkline_width = inner.width.saturating_sub(2);
+    let baseline = app.config.ui.sparkline_baseline;
+    let numbers = &app.config.ui.numbers;
     let mut lines = Vec::new();
 
     // Connection info
@@ -30,6 +47,13 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
         ),
     ]));
     if let Some(server) = app.active_server_info() {
+        lines.push(Line::from(vec![
+            Span::styled("  Server  ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{}:{}", server.kind.label(), server.name),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]));
         lines.push(Line::from(vec![
             Span::styled("  Host    ", Style::default().fg(Color::DarkGray)),
             Span::styled(
@@ -37,13 +61,52 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::Cyan),
             ),
         ]));
-        lines.push(Line::from(vec![
-            Span::styled("  Server  ", Style::default().fg(Color::DarkGray)),
+    }
+    connection_detail_lines(app, inner.width as usize, &mut lines);
+    let ping = &app.ping_tracker;
+    if let Some(last) = ping.last() {
+        let color = if ping.is_degraded() {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        let mut spans = vec![
+            Span::styled("  Ping    ", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                format!("{}:{}", server.kind.label(), server.name),
-                Style::default().fg(Color::Yellow),
+                LatencyTracker::format_duration(last),
+                Style::default().fg(color),
             ),
-        ]));
+        ];
+        if let (Some(avg), Some(max)) = (ping.avg(), ping.max()) {
+            spans.push(Span::styled(
+                format!(
+                    " avg {} max {}",
+                    LatencyTracker::format_duration(avg),
+                    LatencyTracker::format_duration(max)
+                ),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        lines.push(Line::from(spans));
+        if ping.sample_count() > 1 {
+            lines.push(indented(sparkline_line(
+                &ping.sparkline_data(sparkline_width as usize),
+                sparkline_width,
+                baseline,
+                Style::default().fg(color),
+            )));
+        }
+    }
+    if ping.is_degraded() {
+        let warning = if ping.last_missed {
+            format!("  ⚠ ping missed ({} total)", ping.missed)
+        } else {
+            "  ⚠ slow broker ping".to_string()
+        };
+        lines.push(Line::from(Span::styled(
+            warning,
+            Style::default().fg(Color::Yellow),
+        )));
     }
     lines.push(Line::from(""));
 
@@ -52,7 +115,7 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(vec![
         Span::styled("  Total   ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            format_number(app.stats.total_messages()),
+            numbers.count(app.stats.total_messages()),
             Style::default()
                 .fg(Color::White)
                 .add_modifier(Modifier::BOLD),
@@ -61,10 +124,19 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(vec![
         Span::styled("  Rate    ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            Stats::format_rate(app.stats.messages_per_second()),
+            format!("{}/s", numbers.rate(app.stats.messages_per_second())),
             Style::default().fg(Color::Green),
         ),
     ]));
+    if app.stats.total_messages_out() > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("  Sent    ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                numbers.count(app.stats.total_messages_out()),
+                Style::default().fg(Color::White),
+            ),
+        ]));
+    }
     lines.push(Line::from(""));
 
     // Tracked Metrics section - placed high so it's always visible
@@ -73,12 +145,18 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
         lines.push(stats_section_colored("Tracked Metrics", Color::Magenta));
 
         for metric in metrics {
+            let shown = metric.sparkline_data(sparkline_width as usize);
             // Metric label and current value
+            let unit = if metric.display == MetricDisplay::Rate {
+                "/s"
+            } else {
+                ""
+            };
             let current = metric
-                .latest()
-                .map(format_metric_value)
+                .display_latest()
+                .map(|v| format!("{}{}", numbers.value(v, metric.precision), unit))
                 .unwrap_or_else(|| "---".to_string());
-            lines.push(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(
                     format!("  {}: ", metric.label),
                     Style::default().fg(Color::White),
@@ -89,33 +167,49 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
                         .fg(Color::Cyan)
                         .add_modifier(Modifier::BOLD),
                 ),
-            ]));
+            ];
+            if metric.display != MetricDisplay::Absolute {
+                spans.push(Span::styled(
+                    format!(" {}", metric.display.label()),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(spans));
 
             // Sparkline
-            let sparkline_width = 20;
-            let sparkline_data = metric.sparkline_data(sparkline_width);
-            let sparkline_str = render_sparkline(&sparkline_data, sparkline_width);
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(sparkline_str, Style::default().fg(Color::Magenta)),
-            ]));
-
-            // Min/Max/Avg stats on one line
-            if metric.count > 0 {
+            lines.push(indented(sparkline_line(
+                &shown,
+                sparkline_width,
+                baseline,
+                Style::default().fg(Color::Magenta),
+            )));
+
+            // Min/Max/Avg stats on one line, of the deltas or rates on screen
+            // when those are shown
+            let summary = if metric.display.transforms_values() {
+                (!shown.is_empty()).then(|| {
+                    let min = shown.iter().copied().fold(f64::INFINITY, f64::min);
+                    let max = shown.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                    (min, max, shown.iter().sum::<f64>() / shown.len() as f64)
+                })
+            } else {
+                (metric.count > 0).then(|| (metric.min, metric.max, metric.avg()))
+            };
+            if let Some((min, max, avg)) = summary {
                 lines.push(Line::from(vec![
                     Span::styled("  min:", Style::default().fg(Color::DarkGray)),
                     Span::styled(
-                        format_metric_value(metric.min),
+                        numbers.value(min, metric.precision),
                         Style::default().fg(Color::Blue),
                     ),
                     Span::styled(" max:", Style::default().fg(Color::DarkGray)),
                     Span::styled(
-                        format_metric_value(metric.max),
+                        numbers.value(max, metric.precision),
                         Style::default().fg(Color::Red),
                     ),
                     Span::styled(" avg:", Style::default().fg(Color::DarkGray)),
                     Span::styled(
-                        format_metric_value(metric.avg()),
+                        numbers.value(avg, metric.precision),
                         Style::default().fg(Color::Yellow),
                     ),
                 ]));
@@ -124,25 +218,96 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
         lines.push(Line::from(""));
     }
 
+    // Tracked states, as strips colored by state
+    let timelines = app.state_tracker.get_timelines();
+    if !timelines.is_empty() {
+        lines.push(stats_section_colored("States", Color::Magenta));
+        let now = Instant::now();
+        for timeline in timelines {
+            let current = timeline.current().unwrap_or("---");
+            let mut spans = vec![
+                Span::styled(
+                    format!("  {}: ", timeline.label),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    current.to_string(),
+                    Style::default()
+                        .fg(state_color(current))
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ];
+            if let Some(uptime) = timeline.uptime(now) {
+                spans.push(Span::styled(
+                    format!(" {:.1}% up", uptime),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(spans));
+            lines.push(indented(state_strip_line(
+                &timeline.strip(sparkline_width as usize, now),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
     // Data stats
     lines.push(stats_section("Data"));
     lines.push(Line::from(vec![
-        Span::styled("  Total   ", Style::default().fg(Color::DarkGray)),
+        Span::styled("  In      ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            Stats::format_bytes(app.stats.total_bytes()),
+            numbers.bytes(app.stats.total_bytes()),
             Style::default().fg(Color::White),
         ),
+        Span::styled(
+            format!(" {}/s", numbers.bytes(app.stats.bytes_per_second() as u64)),
+            Style::default().fg(Color::Green),
+        ),
     ]));
     lines.push(Line::from(vec![
-        Span::styled("  Rate    ", Style::default().fg(Color::DarkGray)),
+        Span::styled("  Out     ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            numbers.bytes(app.stats.total_bytes_out()),
+            Style::default().fg(Color::White),
+        ),
         Span::styled(
             format!(
-                "{}/s",
-                Stats::format_bytes(app.stats.bytes_per_second() as u64)
+                " {}/s",
+                numbers.bytes(app.stats.bytes_out_per_second() as u64)
             ),
             Style::default().fg(Color::Green),
         ),
     ]));
+
+    // Inbound bandwidth by first-level topic prefix
+    let top_prefixes = app.stats.top_prefixes(TOP_PREFIXES);
+    if top_prefixes.len() > 1 {
+        lines.push(Line::from(Span::styled(
+            "  Top prefixes:",
+            Style::default().fg(Color::DarkGray),
+        )));
+        for usage in top_prefixes {
+            let prefix = if usage.prefix.is_empty() {
+                "(root)".to_string()
+            } else {
+                usage.prefix
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}", fit_width(&prefix, 10)),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(
+                    format!(" {:>9}/s", numbers.bytes(usage.bytes_per_second as u64)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!(" {:>3.0}%", usage.share * 100.0),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+    }
     lines.push(Line::from(""));
 
     // Topic stats
@@ -150,47 +315,251 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(vec![
         Span::styled("  Unique  ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            format_number(app.topic_tree.topic_count() as u64),
+            numbers.count(app.application_topic_count() as u64),
             Style::default().fg(Color::Cyan),
         ),
     ]));
+    let system_topics = app.topic_tree.system_topic_count();
+    if system_topics > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("  System  ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(
+                    "{} $ topics{}",
+                    numbers.count(system_topics as u64),
+                    if app.show_system_topics {
+                        ""
+                    } else {
+                        " (hidden, $ shows)"
+                    }
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
     lines.push(Line::from(vec![
         Span::styled("  Buffered", Style::default().fg(Color::DarkGray)),
         Span::styled(
             format!(
                 " {}",
-                format_number(app.message_buffer.total_stored() as u64)
+                numbers.count(app.message_buffer.total_stored() as u64)
             ),
             Style::default().fg(Color::Yellow),
         ),
     ]));
+    if app.message_buffer.total_repeats() > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("  Folded  ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(
+                    "{} unchanged repeats",
+                    numbers.count(app.message_buffer.total_repeats())
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+    let (bad_payloads, bad_topics) = app.schema_tracker.parse_failure_totals();
+    if bad_payloads > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("  Bad JSON", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(
+                    " {} on {} topic{}",
+                    numbers.count(bad_payloads),
+                    bad_topics,
+                    if bad_topics == 1 { "" } else { "s" }
+                ),
+                Style::default().fg(Color::Red),
+            ),
+        ]));
+    }
+    if app.pruned_topics > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("  Pruned  ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                numbers.count(app.pruned_topics),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
     lines.push(Line::from(""));
 
+    // Topics deviating from the loaded schema baseline
+    let baseline = app.schema_tracker.baseline_len();
+    if baseline > 0 {
+        let drifted = app.schema_tracker.drifted();
+        lines.push(stats_section("Schema Baseline"));
+        lines.push(Line::from(vec![
+            Span::styled("  Drifted ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} of {} topics", drifted.len(), baseline),
+                Style::default().fg(if drifted.is_empty() {
+                    Color::Green
+                } else {
+                    Color::Red
+                }),
+            ),
+        ]));
+        for (topic, changes) in drifted.iter().take(MAX_DRIFT_TOPICS) {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", truncate_width(topic, 30)),
+                Style::default().fg(Color::Cyan),
+            )));
+            let summary = changes
+                .iter()
+                .map(|c| format!("{}{}", c.change_type, c.field_path))
+                .collect::<Vec<_>>()
+                .join(" ");
+            lines.push(Line::from(Span::styled(
+                format!("    {}", truncate_width(&summary, 40)),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        if drifted.len() > MAX_DRIFT_TOPICS {
+            lines.push(Line::from(Span::styled(
+                format!("  +{} more", drifted.len() - MAX_DRIFT_TOPICS),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    // Devices whose traffic moved to a new topic prefix
+    let move_count = app.topic_moves.move_count();
+    if move_count > 0 {
+        lines.push(stats_section("Topic Moves"));
+        for moved in app.topic_moves.moves().take(MAX_TOPIC_MOVES) {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", truncate_width(&moved.device_id, 30)),
+                Style::default().fg(Color::Cyan),
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("    {}", truncate_width(&moved.from, 40)),
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("    → {}", truncate_width(&moved.to, 38)),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        if move_count > MAX_TOPIC_MOVES {
+            lines.push(Line::from(Span::styled(
+                format!("  +{} more", move_count - MAX_TOPIC_MOVES),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    // Busiest topics over all saved sessions with this server
+    if let Some(since) = app.topic_totals.since() {
+        lines.push(stats_section("Top Talkers"));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  since {}, {} topics",
+                since.with_timezone(&chrono::Local).format("%Y-%m-%d"),
+                numbers.count(app.topic_totals.len() as u64)
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        for (topic, total) in app.topic_totals.top(TOP_TALKERS) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}", fit_width(topic, 24)),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(
+                    format!(" {:>7}", numbers.count(total.messages)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!(" {:>9}", numbers.bytes(total.bytes)),
+                    Style::default().fg(Color::Green),
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    // Observed topics missing from the loaded AsyncAPI spec
+    if app.asyncapi.is_some() {
+        let uncovered = app.spec_coverage.uncovered();
+        let observed = app.spec_coverage.observed();
+        lines.push(stats_section("AsyncAPI"));
+        lines.push(Line::from(vec![
+            Span::styled("  Covered ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} of {} topics", observed - uncovered.len(), observed),
+                Style::default().fg(if uncovered.is_empty() {
+                    Color::Green
+                } else {
+                    Color::Magenta
+                }),
+            ),
+        ]));
+        for topic in uncovered.iter().take(MAX_DRIFT_TOPICS) {
+            lines.push(Line::from(Span::styled(
+                format!("  ? {}", truncate_width(topic, 30)),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+        if uncovered.len() > MAX_DRIFT_TOPICS {
+            lines.push(Line::from(Span::styled(
+                format!("  +{} more", uncovered.len() - MAX_DRIFT_TOPICS),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    // Starred and bookmarked topics outside the active subscription
+    if let Some((checked, gaps)) = app.subscription_gaps() {
+        lines.push(stats_section("Subscription Coverage"));
+        lines.push(Line::from(vec![
+            Span::styled("  Covered ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} of {} topics", checked - gaps.len(), checked),
+                Style::default().fg(if gaps.is_empty() {
+                    Color::Green
+                } else {
+                    Color::Yellow
+                }),
+            ),
+        ]));
+        for (topic, source) in gaps.iter().take(MAX_DRIFT_TOPICS) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  ? {} ", truncate_width(topic, 30)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(source.to_string(), Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+        if gaps.len() > MAX_DRIFT_TOPICS {
+            lines.push(Line::from(Span::styled(
+                format!("  +{} more", gaps.len() - MAX_DRIFT_TOPICS),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
     // Session info
     lines.push(stats_section("Session"));
     lines.push(Line::from(vec![
         Span::styled("  Uptime  ", Style::default().fg(Color::DarkGray)),
         Span::styled(app.stats.uptime_string(), Style::default().fg(Color::White)),
     ]));
-    match app.connected_broker_kind {
-        BrokerKind::Mqtt => {
-            if let Some(server) = app.active_mqtt_server() {
-                lines.push(Line::from(vec![
-                    Span::styled("  Client  ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(server.client_id.clone(), Style::default().fg(Color::DarkGray)),
-                ]));
-            }
-        }
-        BrokerKind::Nats => {
-            if let Some(server) = app.active_nats_server() {
-                lines.push(Line::from(vec![
-                    Span::styled("  Sub     ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(
-                        server.subscribe_subject.clone(),
-                        Style::default().fg(Color::DarkGray),
-                    ),
-                ]));
-            }
+    if app.connected_broker_kind == BrokerKind::Nats {
+        if let Some(server) = app.session_nats_server() {
+            lines.push(Line::from(vec![
+                Span::styled("  Sub     ", Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    server.subscribe_subject,
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
         }
     }
 
@@ -245,6 +614,28 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
             }
         }
 
+        // Clock offset used to correct payload delays
+        if let Some((offset_ms, source)) = app.latency_tracker.clock.estimate() {
+            let sign = if offset_ms < 0 { "-" } else { "+" };
+            lines.push(Line::from(vec![
+                Span::raw("  Clock: "),
+                Span::styled(
+                    format!(
+                        "{}{}",
+                        sign,
+                        LatencyTracker::format_duration(Duration::from_millis(
+                            offset_ms.unsigned_abs()
+                        ))
+                    ),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!(" vs {} (corrected)", source.label()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+
         // Jitter
         if let Some(jitter) = app.latency_tracker.jitter() {
             lines.push(Line::from(vec![
@@ -315,9 +706,19 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::White),
             ),
         ]));
+        let offline = app.device_tracker.offline_count();
+        if offline > 0 {
+            lines.push(Line::from(vec![
+                Span::styled("  ✗ ", Style::default().fg(Color::Red)),
+                Span::styled(
+                    format!("{} offline", offline),
+                    Style::default().fg(Color::White),
+                ),
+            ]));
+        }
 
-        // Show top 3 most recent devices
-        let devices = app.device_tracker.get_devices();
+        // Show top 3 devices, starred first
+        let devices = app.devices_by_priority(false);
         if !devices.is_empty() {
             lines.push(Line::from(""));
             lines.push(Line::from(vec![Span::styled(
@@ -331,26 +732,33 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
                     HealthStatus::Warning => Color::Yellow,
                     HealthStatus::Stale => Color::Red,
                     HealthStatus::Unknown => Color::DarkGray,
+                    HealthStatus::Offline => Color::Red,
                 };
                 let status_char = match device.status {
                     HealthStatus::Healthy => "●",
                     HealthStatus::Warning => "●",
                     HealthStatus::Stale => "○",
                     HealthStatus::Unknown => "◌",
+                    HealthStatus::Offline => "✗",
+                };
+
+                let display_id = ellipsize(&device.device_id, 15);
+
+                let star = if app.user_data.is_device_starred(&device.device_id) {
+                    "★ "
+                } else {
+                    ""
                 };
 
-                // Truncate device ID for display
-                let display_id = if device.device_id.len() > 12 {
-                    format!("{}...", &device.device_id[..12])
+                let status = if app.config.ui.screen_reader {
+                    format!("  {} ", device.status.label())
                 } else {
-                    device.device_id.clone()
+                    format!("  {} ", status_char)
                 };
 
                 lines.push(Line::from(vec![
-                    Span::styled(
-                        format!("  {} ", status_char),
-                        Style::default().fg(status_color),
-                    ),
+                    Span::styled(status, Style::default().fg(status_color)),
+                    Span::styled(star, Style::default().fg(Color::Yellow)),
                     Span::styled(display_id, Style::default().fg(Color::White)),
                 ]));
                 lines.push(Line::from(vec![Span::styled(
@@ -372,6 +780,64 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
         }
     }
 
+    // Internal sizes, only with --debug
+    if app.debug {
+        let internals = &app.internals;
+        lines.push(Line::from(""));
+        lines.push(stats_section_colored("Internals", Color::DarkGray));
+        let rows = [
+            ("Topics  ", numbers.count(internals.topics as u64)),
+            (
+                "Buffered",
+                format!(
+                    "{} msgs, {}",
+                    numbers.count(internals.buffered_messages as u64),
+                    numbers.bytes(internals.buffered_bytes as u64)
+                ),
+            ),
+            (
+                "Devices ",
+                format!(
+                    "{} ({} samples)",
+                    numbers.count(internals.devices as u64),
+                    numbers.count(internals.device_samples as u64)
+                ),
+            ),
+            ("Points  ", numbers.count(internals.metric_points as u64)),
+            ("Latency ", numbers.count(internals.latency_samples as u64)),
+            ("Pruned  ", numbers.count(internals.pruned)),
+            (
+                "CPU     ",
+                app.process
+                    .cpu_percent
+                    .map_or("n/a".to_string(), |cpu| format!("{:.1}%", cpu)),
+            ),
+            (
+                "Memory  ",
+                app.process
+                    .rss_bytes
+                    .map_or("n/a".to_string(), |bytes| numbers.bytes(bytes)),
+            ),
+            (
+                "Backlog ",
+                format!("{} events", numbers.count(app.event_backlog as u64)),
+            ),
+            (
+                "Frame   ",
+                format!("{:.1} ms", app.frame_time.as_secs_f64() * 1000.0),
+            ),
+        ];
+        for (label, value) in rows {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {} ", label),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(value, Style::default().fg(Color::Gray)),
+            ]));
+        }
+    }
+
     // Add scroll indicator if content exceeds panel height
     let total_lines = lines.len();
     let visible_height = inner.height as usize;
@@ -388,6 +854,136 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, inner);
 }
 
+/// Resolved address, TLS, client ID, keep-alive and broker limits of the MQTT
+/// connection. The client ID shows the configured one until it is probed.
+fn connection_detail_lines(app: &App, width: usize, lines: &mut Vec<Line<'static>>) {
+    if app.connected_broker_kind != BrokerKind::Mqtt {
+        return;
+    }
+    let Some(server) = app.active_mqtt_server() else {
+        return;
+    };
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::DarkGray));
+    let details = app.connection_details.as_ref();
+
+    if let Some(address) = details.and_then(|d| d.address) {
+        lines.push(Line::from(vec![
+            label("  Addr    "),
+            Span::styled(address.to_string(), Style::default().fg(Color::Cyan)),
+        ]));
+    }
+    if server.use_tls {
+        // Version and cipher suite as negotiated on the live connection
+        let negotiated = details.and_then(|d| d.tls);
+        let version = negotiated.map_or(String::new(), |tls| format!("{} ", tls.version_label()));
+        let ca = match &server.ca_cert {
+            Some(path) => {
+                let name = std::path::Path::new(path)
+                    .file_name()
+                    .map_or(path.clone(), |name| name.to_string_lossy().into_owned());
+                let room = width.saturating_sub(13 + version.len());
+                format!("CA {}", ellipsize(&name, room))
+            }
+            None => "system CA".to_string(),
+        };
+        let mut spans = vec![
+            label("  TLS     "),
+            Span::styled(version, Style::default().fg(Color::White)),
+            Span::styled(ca, Style::default().fg(Color::Green)),
+        ];
+        if server.client_cert.is_some() {
+            spans.push(Span::styled(
+                " + client cert",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if server.tls_insecure {
+            spans.push(Span::styled(
+                " (unverified)",
+                Style::default().fg(Color::Red),
+            ));
+        }
+        lines.push(Line::from(spans));
+        if let Some(tls) = negotiated {
+            lines.push(Line::from(vec![
+                label("  Cipher  "),
+                Span::styled(
+                    ellipsize(&tls.cipher_label(), width.saturating_sub(10)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+    }
+    let client_id = match details {
+        Some(details) => details.client_id.clone(),
+        None => server.client_id.clone(),
+    };
+    lines.push(Line::from(vec![
+        label("  Client  "),
+        Span::styled(client_id, Style::default().fg(Color::White)),
+    ]));
+    let keep_alive = details
+        .map(|d| d.keep_alive)
+        .unwrap_or(Duration::from_secs(server.keep_alive_secs));
+    lines.push(Line::from(vec![
+        label("  Alive   "),
+        Span::styled(
+            format!("{}s keep-alive", keep_alive.as_secs()),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+
+    // Limits the broker advertised over MQTT 5
+    let Some(details) = details else {
+        return;
+    };
+    let Some(caps) = &details.capabilities else {
+        lines.push(Line::from(vec![
+            label("  Broker  "),
+            Span::styled("no MQTT 5 limits", Style::default().fg(Color::DarkGray)),
+        ]));
+        return;
+    };
+    let mut max = format!("QoS {}", caps.max_qos);
+    if let Some(size) = caps.max_packet_size {
+        max.push_str(&format!(", {}", app.config.ui.numbers.bytes(size as u64)));
+    }
+    lines.push(Line::from(vec![
+        label("  Max     "),
+        Span::styled(max, Style::default().fg(Color::White)),
+    ]));
+    if let Some(aliases) = caps.topic_alias_max.filter(|n| *n > 0) {
+        lines.push(Line::from(vec![
+            label("  Alias   "),
+            Span::styled(aliases.to_string(), Style::default().fg(Color::White)),
+        ]));
+    }
+    let feature = |name: &'static str, available: bool| {
+        let style = if available {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::CROSSED_OUT)
+        };
+        Span::styled(name, style)
+    };
+    lines.push(Line::from(vec![
+        label("  Has     "),
+        feature("retain", caps.retain_available),
+        Span::raw(" "),
+        feature("wild", caps.wildcard_available),
+        Span::raw(" "),
+        feature("share", caps.shared_available),
+    ]));
+    for conflict in caps.conflicts(server) {
+        lines.push(Line::from(Span::styled(
+            format!("  ⚠ {}", conflict),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+}
+
 fn stats_section(title: &str) -> Line<'static> {
     Line::from(vec![Span::styled(
         format!("▸ {}", title),
@@ -404,24 +1000,8 @@ fn stats_section_colored(title: &str, color: Color) -> Line<'static> {
     )])
 }
 
-fn format_number(n: u64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.2}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}k", n as f64 / 1_000.0)
-    } else {
-        n.to_string()
-    }
-}
-
-fn format_metric_value(v: f64) -> String {
-    if v.abs() >= 1_000_000.0 {
-        format!("{:.2}M", v / 1_000_000.0)
-    } else if v.abs() >= 1_000.0 {
-        format!("{:.1}k", v / 1_000.0)
-    } else if v.fract() == 0.0 {
-        format!("{:.0}", v)
-    } else {
-        format!("{:.2}", v)
-    }
+fn indented(line: Line<'static>) -> Line<'static> {
+    let mut spans = vec![Span::raw("  ")];
+    spans.extend(line.spans);
+    Line::from(spans)
 }
@@ -0,0 +1,72 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use super::widgets::centered_rect;
+use crate::app::App;
+
+pub fn render_subscribe_warning(frame: &mut Frame, app: &App) {
+    let Some(ref warning) = app.subscribe_warning else {
+        return;
+    };
+
+    let area = centered_rect(60, 50, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" ⚠ High Message Rate ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red))
+        .style(Style::default().bg(Color::Black));
+
+    let pattern = app.connected_broker_kind.default_subscribe_pattern();
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("Subscribed to "),
+            Span::styled(pattern, Style::default().fg(Color::Yellow)),
+            Span::raw(" and receiving "),
+            Span::styled(
+                format!("{}/s", app.config.ui.numbers.rate(warning.rate)),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("."),
+        ]),
+        Line::from("A narrower subscription keeps mqtop and the broker responsive."),
+        Line::from(""),
+    ];
+
+    if warning.suggestions.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No topic prefixes seen yet to suggest",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            "Busiest prefixes:",
+            Style::default().fg(Color::DarkGray),
+        )));
+        for (i, topic) in warning.suggestions.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}  ", i + 1), Style::default().fg(Color::Yellow)),
+                Span::styled(topic.clone(), Style::default().fg(Color::Cyan)),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Pick a number to resubscribe for this session, Esc to keep the current one",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
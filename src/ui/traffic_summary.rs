@@ -0,0 +1,130 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::{centered_rect, dialog_key_hint};
+use crate::app::App;
+use crate::state::PrefixTrend;
+use crate::text::{ellipsize, fit_width};
+
+pub fn render_traffic_summary(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 75, frame.area());
+    frame.render_widget(Clear, area);
+
+    let server = app
+        .active_server_info()
+        .map(|server| server.name)
+        .unwrap_or_default();
+    let block = Block::default()
+        .title(format!(" Traffic Summary: {} ", ellipsize(&server, 30)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+
+    let summary = app.traffic_summary();
+    let period = if summary.period_days == 1 {
+        "Today".to_string()
+    } else {
+        format!("Last {} days", summary.period_days)
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{} against {} earlier day{}, by share of messages",
+            period,
+            summary.baseline_days,
+            if summary.baseline_days == 1 { "" } else { "s" }
+        ),
+        Style::default().fg(Color::DarkGray),
+    ))];
+    if summary.baseline_days == 0 {
+        lines.push(Line::from(Span::styled(
+            "No earlier days saved yet; trends show once there is history",
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    lines.push(Line::from(""));
+
+    if summary.prefixes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No traffic counted yet",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {} {:>9} {:>9} {:>13}  Trend",
+                fit_width("Prefix", 20),
+                "Messages",
+                "Usual",
+                "Share"
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+    }
+    for prefix in &summary.prefixes {
+        let name = if prefix.prefix.is_empty() {
+            "(root)"
+        } else {
+            prefix.prefix.as_str()
+        };
+        let (marker, color) = match prefix.trend {
+            PrefixTrend::Vanished => ("✗", Color::Magenta),
+            PrefixTrend::New => ("+", Color::Cyan),
+            PrefixTrend::Grew => ("▲", Color::Yellow),
+            PrefixTrend::Shrank => ("▼", Color::Yellow),
+            PrefixTrend::Steady => (" ", Color::DarkGray),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", marker), Style::default().fg(color)),
+            Span::styled(fit_width(name, 20), Style::default().fg(Color::Cyan)),
+            Span::raw(format!(
+                " {:>9} {:>9}",
+                prefix.current.messages,
+                prefix.baseline.round() as u64
+            )),
+            Span::styled(
+                format!(
+                    " {:>5.1}%→{:>5.1}%",
+                    prefix.baseline_share * 100.0,
+                    prefix.share * 100.0
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("  {}", prefix.trend.label()),
+                Style::default().fg(color),
+            ),
+        ]));
+    }
+
+    let height = chunks[0].height as usize;
+    let scroll = app
+        .traffic_summary_scroll
+        .min(lines.len().saturating_sub(height));
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[0]);
+
+    let mut hints = Vec::new();
+    hints.extend(dialog_key_hint(
+        "w",
+        if summary.period_days == 1 {
+            "Weekly"
+        } else {
+            "Daily"
+        },
+    ));
+    hints.extend(dialog_key_hint("↑↓", "Scroll"));
+    hints.extend(dialog_key_hint("Esc", "Close"));
+    frame.render_widget(Paragraph::new(Line::from(hints)), chunks[1]);
+}
@@ -0,0 +1,55 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::widgets::centered_rect;
+use crate::app::{App, FilterMode};
+
+pub fn render_tree_export(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 30, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Export Topic Tree ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    let scope = match (&app.topic_filter, app.filter_mode) {
+        (Some(filter), FilterMode::Starred) => format!("starred topics matching {}", filter),
+        (Some(filter), FilterMode::All) => format!("topics matching {}", filter),
+        (None, FilterMode::Starred) => "starred topics".to_string(),
+        (None, FilterMode::All) => "all topics".to_string(),
+    };
+
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Exporting "),
+            Span::styled(scope, Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(""),
+        format_line("t", "Text outline (.txt)"),
+        format_line("j", "JSON (.json)"),
+        format_line("d", "Graphviz DOT (.dot)"),
+        format_line("s", "Schema baseline of all JSON topics (.json)"),
+        format_line("a", "AsyncAPI skeleton of all topics (.yaml)"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Esc to cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn format_line(key: &str, desc: &str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("  {}  ", key), Style::default().fg(Color::Yellow)),
+        Span::raw(desc.to_string()),
+    ])
+}
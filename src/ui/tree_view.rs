@@ -1,3 +1,4 @@
+use chrono::DateTime;
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -7,9 +8,12 @@ use ratatui::{
 };
 
 use super::bordered_block;
+use super::widgets::{format_age, freshness_color};
 use crate::app::{App, FilterMode, Panel};
-use crate::config::TopicColorRule;
-use crate::state::TopicInfo;
+use crate::config::{DisplayTimeZone, TopicColorRule};
+use crate::numbers::NumberFormat;
+use crate::state::{is_system_topic, TopicInfo};
+use crate::text;
 
 pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.focused_panel == Panel::TopicTree;
@@ -25,7 +29,23 @@ pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
         }
         FilterMode::Starred => "Topics [★]",
     };
-    let block = bordered_block(title, focused);
+    let title = if app.group_templates {
+        format!("{} [templates]", title)
+    } else {
+        title.to_string()
+    };
+    let title = if app.follow_newest {
+        format!("{} [following new]", title)
+    } else {
+        title
+    };
+    let hidden = app.hidden_topic_count();
+    let title = if hidden > 0 {
+        format!("{} [{} hidden]", title, hidden)
+    } else {
+        title
+    };
+    let block = bordered_block(&title, focused);
     let inner = block.inner(area);
 
     frame.render_widget(block, area);
@@ -48,28 +68,70 @@ pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
         return;
     }
 
-    // Calculate visible window and ensure selection is visible
-    let visible_height = inner.height as usize;
+    // Topics arriving or expiring above the highlighted row shift it; keep the
+    // highlight on the same topic unless a key moved it since the last frame
     let total = topics.len();
-    let selected = app.selected_topic_index.min(total.saturating_sub(1));
+    if let Some((index, path)) = &app.tree_anchor {
+        let shifted = *index == app.selected_topic_index
+            && topics.get(*index).map(|t| &t.full_path) != Some(path);
+        if shifted {
+            if let Some(moved) = topics.iter().position(|t| &t.full_path == path) {
+                app.selected_topic_index = moved;
+            }
+        }
+    }
+    app.selected_topic_index = app.selected_topic_index.min(total - 1);
+    let selected = app.selected_topic_index;
+    app.tree_anchor = Some((selected, topics[selected].full_path.clone()));
 
-    // Ensure scroll keeps selection in view
+    // Keep the selection in view, and the viewport filled when rows go away
+    let visible_height = inner.height as usize;
     if selected < app.tree_scroll {
         app.tree_scroll = selected;
     } else if selected >= app.tree_scroll + visible_height {
         app.tree_scroll = selected.saturating_sub(visible_height.saturating_sub(1));
     }
+    app.tree_scroll = app.tree_scroll.min(total.saturating_sub(visible_height));
 
     let color_rules = &app.config.ui.topic_colors;
+    let freshness_rows = app.config.ui.freshness_colors;
+    let plain = app.config.ui.screen_reader;
+    let width = inner.width as usize;
     let now_ms = chrono::Utc::now().timestamp_millis();
+    let ttl_cutoff = app.topic_ttl_cutoff();
 
     let items: Vec<ListItem> = topics
         .iter()
         .enumerate()
         .map(|(i, topic)| {
             let is_selected = i == app.selected_topic_index;
-            let is_starred = app.is_starred(&topic.full_path);
-            create_topic_item(topic, is_selected, focused, is_starred, color_rules, now_ms)
+            let marks = TopicMarks {
+                is_starred: app.is_starred(&topic.full_path),
+                has_note: app.user_data.note(&topic.full_path).is_some(),
+                undocumented: app.spec_coverage.is_uncovered(&topic.full_path),
+                watchdog: app
+                    .watchdog_for(&topic.full_path)
+                    .map(|_| app.watchdog.is_silent(&topic.full_path)),
+                is_dead: ttl_cutoff
+                    .zip(topic.latest_activity)
+                    .is_some_and(|(cutoff, latest)| latest < cutoff),
+                is_drifted: app.schema_tracker.is_drifted(&topic.full_path),
+                parse_failures: app.schema_tracker.parse_failures(&topic.full_path),
+                friendly_name: app.friendly_names.get(&topic.segment).map(str::to_string),
+            };
+            let row = RowStyle {
+                focused,
+                color_rules,
+                freshness_rows,
+                plain,
+                width,
+                now_ms,
+                replace_ids: app.config.ui.replace_ids,
+                absolute_times: (!app.config.ui.times.relative_in_tree())
+                    .then_some(app.config.ui.timezone),
+                numbers: &app.config.ui.numbers,
+            };
+            create_topic_item(topic, is_selected, &marks, &row)
         })
         .collect();
 
@@ -90,18 +152,53 @@ pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_stateful_widget(list, inner, &mut state);
 }
 
+/// Tree-wide rendering settings shared by every row
+struct RowStyle<'a> {
+    focused: bool,
+    color_rules: &'a [TopicColorRule],
+    /// Color names by freshness instead of the topic color rules
+    freshness_rows: bool,
+    /// Screen reader mode: words instead of color-coded marks, no activity dot
+    plain: bool,
+    width: usize,
+    now_ms: i64,
+    /// Show friendly names in place of IDs
+    replace_ids: bool,
+    /// Last activity as the time of day in this zone instead of its age
+    absolute_times: Option<DisplayTimeZone>,
+    numbers: &'a NumberFormat,
+}
+
+/// Per-topic state shown alongside the name
+struct TopicMarks {
+    is_starred: bool,
+    /// A note is attached
+    has_note: bool,
+    /// Carries messages but no AsyncAPI channel documents it
+    undocumented: bool,
+    /// Some(silent) when a watchdog is attached
+    watchdog: Option<bool>,
+    /// Past the configured TTL
+    is_dead: bool,
+    /// Schema differs from the loaded baseline
+    is_drifted: bool,
+    /// Payloads that failed JSON parsing
+    parse_failures: u64,
+    /// Friendly name for this level's ID
+    friendly_name: Option<String>,
+}
+
 fn create_topic_item(
     topic: &TopicInfo,
     is_selected: bool,
-    focused: bool,
-    is_starred: bool,
-    color_rules: &[TopicColorRule],
-    now_ms: i64,
+    marks: &TopicMarks,
+    row: &RowStyle,
 ) -> ListItem<'static> {
+    let now_ms = row.now_ms;
     let indent = "  ".repeat(topic.depth);
 
     // Star indicator
-    let star = if is_starred { "★ " } else { "" };
+    let star = if marks.is_starred { "★ " } else { "" };
 
     // Determine icon based on topic type and state
     let icon = if topic.has_children {
@@ -129,19 +226,26 @@ fn create_topic_item(
     });
 
     // Color code by topic segment using config rules
-    let segment_color = get_topic_color(&topic.segment, &topic.full_path, color_rules);
+    let segment_color = get_topic_color(&topic.segment, &topic.full_path, row.color_rules);
+
+    // Age of the newest message in this topic or below it
+    let age_ms = topic.latest_activity.map(|t| now_ms - t);
 
     // Format message count
     let count_str = if topic.message_count > 0 {
-        format!(" {}", format_count(topic.message_count))
+        format!(" {}", row.numbers.count(topic.message_count))
     } else {
         String::new()
     };
 
-    let style = if is_selected && focused {
+    let style = if is_selected && row.focused {
         Style::default()
             .fg(Color::White)
             .add_modifier(Modifier::BOLD)
+    } else if marks.is_dead {
+        Style::default().fg(Color::DarkGray)
+    } else if let Some(age_ms) = age_ms.filter(|_| row.freshness_rows) {
+        Style::default().fg(freshness_color(age_ms))
     } else {
         Style::default().fg(segment_color)
     };
@@ -150,12 +254,98 @@ fn create_topic_item(
         Span::raw(indent),
         Span::styled(star.to_string(), Style::default().fg(Color::Yellow)),
         Span::styled(icon.to_string(), Style::default().fg(Color::DarkGray)),
-        Span::styled(topic.segment.clone(), style),
-        Span::styled(count_str, Style::default().fg(Color::DarkGray)),
     ];
+    match &marks.friendly_name {
+        Some(name) if row.replace_ids => spans.push(Span::styled(name.clone(), style)),
+        Some(name) => {
+            spans.push(Span::styled(topic.segment.clone(), style));
+            spans.push(Span::styled(
+                format!(" ({})", name),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+        None => spans.push(Span::styled(topic.segment.clone(), style)),
+    }
+    // Instances folded into this level of a template
+    if topic.instances > 1 {
+        spans.push(Span::styled(
+            format!(" ×{}", row.numbers.count(topic.instances as u64)),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    spans.push(Span::styled(
+        count_str,
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    // Delivery flags: retained-only topics, redeliveries and QoS 2
+    if topic.retained_only() {
+        let mark = if row.plain { " [retained]" } else { " ®" };
+        spans.push(Span::styled(mark, Style::default().fg(Color::Yellow)));
+    }
+    if topic.flags.duplicates > 0 {
+        let count = row.numbers.count(topic.flags.duplicates);
+        spans.push(Span::styled(
+            if row.plain {
+                format!(" [{} dup]", count)
+            } else {
+                format!(" ⧉{}", count)
+            },
+            Style::default().fg(Color::LightRed),
+        ));
+    }
+    if topic.flags.max_qos == 2 {
+        let mark = if row.plain { " [QoS 2]" } else { " Q2" };
+        spans.push(Span::styled(mark, Style::default().fg(Color::Blue)));
+    }
+
+    // Watchdog marker: red when the topic has gone silent
+    if let Some(silent) = marks.watchdog {
+        let mark = match (row.plain, silent) {
+            (true, true) => " [silent]",
+            (true, false) => " [watched]",
+            (false, _) => " ⏱",
+        };
+        spans.push(Span::styled(
+            mark,
+            Style::default().fg(if silent { Color::Red } else { Color::DarkGray }),
+        ));
+    }
+
+    if marks.has_note {
+        let mark = if row.plain { " [note]" } else { " ✎" };
+        spans.push(Span::styled(mark, Style::default().fg(Color::Cyan)));
+    }
+
+    if marks.undocumented {
+        let mark = if row.plain { " [no spec]" } else { " ?" };
+        spans.push(Span::styled(mark, Style::default().fg(Color::Magenta)));
+    }
+
+    if marks.is_drifted {
+        let mark = if row.plain { " [drift]" } else { " ≠" };
+        spans.push(Span::styled(mark, Style::default().fg(Color::Red)));
+    }
+    if marks.parse_failures > 0 {
+        let count = row.numbers.count(marks.parse_failures);
+        spans.push(Span::styled(
+            if row.plain {
+                format!(" [{} bad]", count)
+            } else {
+                format!(" ✗{}", count)
+            },
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if marks.is_dead && row.plain {
+        spans.push(Span::styled(
+            " [dead]",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
 
     // Add activity dot at the end
-    if let Some((indicator, color)) = activity {
+    if let Some((indicator, color)) = activity.filter(|_| !row.plain) {
         if !indicator.is_empty() {
             spans.push(Span::raw(" "));
             spans.push(Span::styled(
@@ -165,6 +355,24 @@ fn create_topic_item(
         }
     }
 
+    // Right-aligned "3m ago" (or "12:00:05") column, dropped when the row is too narrow
+    if let Some(age_ms) = age_ms {
+        let age = match (row.absolute_times, topic.latest_activity) {
+            (Some(zone), Some(latest)) => DateTime::from_timestamp_millis(latest)
+                .map_or_else(|| format_age(age_ms), |t| zone.format(t, "%H:%M:%S")),
+            _ => format_age(age_ms),
+        };
+        let used: usize = spans.iter().map(|s| text::width(&s.content)).sum();
+        let age_width = text::width(&age);
+        if used + age_width < row.width {
+            spans.push(Span::raw(" ".repeat(row.width - used - age_width)));
+            spans.push(Span::styled(
+                age,
+                Style::default().fg(freshness_color(age_ms)),
+            ));
+        }
+    }
+
     ListItem::new(Line::from(spans))
 }
 
@@ -177,8 +385,10 @@ fn get_topic_color(segment: &str, full_path: &str, color_rules: &[TopicColorRule
         }
     }
 
-    // Fallback: UUIDs/IDs in gray, everything else white
-    if is_uuid_like(segment) {
+    // Fallback: broker `$` topics dimmed, UUIDs/IDs in gray, everything else white
+    if is_system_topic(full_path) {
+        Color::DarkGray
+    } else if is_uuid_like(segment) {
         Color::Gray
     } else {
         Color::White
@@ -192,13 +402,3 @@ fn is_uuid_like(s: &str) -> bool {
         && s.chars().all(|c| c.is_alphanumeric() || c == '-')
         && s.chars().filter(|c| c.is_numeric()).count() > 2
 }
-
-fn format_count(count: u64) -> String {
-    if count >= 1_000_000 {
-        format!("{:.1}M", count as f64 / 1_000_000.0)
-    } else if count >= 1_000 {
-        format!("{:.1}k", count as f64 / 1_000.0)
-    } else {
-        count.to_string()
-    }
-}
@@ -1,11 +1,26 @@
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Paragraph, Sparkline, Widget},
     Frame,
 };
 
+use crate::config::SparklineBaseline;
+use crate::input::InputCursor;
+use crate::state::StateKind;
+use crate::text;
+
+/// Colors for states that are neither up nor down, picked by name
+const OTHER_STATE_COLORS: [Color; 5] = [
+    Color::Yellow,
+    Color::Cyan,
+    Color::Blue,
+    Color::Magenta,
+    Color::LightYellow,
+];
+
 /// Create a centered popup rectangle within a given area
 pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -27,16 +42,184 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-/// Safely truncate a string at a valid UTF-8 character boundary
-pub fn truncate_safe(s: &str, max_bytes: usize) -> &str {
-    if s.len() <= max_bytes {
-        return s;
+/// Short relative age, e.g. `12s ago`, `3m ago`, `2h ago`
+pub fn format_age(age_ms: i64) -> String {
+    let secs = age_ms.max(0) / 1000;
+    if secs < 60 {
+        format!("{}s ago", secs)
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else {
+        format!("{}h ago", secs / 3600)
+    }
+}
+
+/// Age with tenths of a second under a minute, e.g. `2.3s ago`, then as `format_age`
+pub fn format_precise_age(age_ms: i64) -> String {
+    if age_ms < 60_000 {
+        format!("{:.1}s ago", age_ms.max(0) as f64 / 1000.0)
+    } else {
+        format_age(age_ms)
+    }
+}
+
+/// Color for how recently something was seen: green under a minute,
+/// yellow under ten minutes, grey after that
+pub fn freshness_color(age_ms: i64) -> Color {
+    if age_ms < 60_000 {
+        Color::Green
+    } else if age_ms < 600_000 {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    }
+}
+
+/// One-row sparkline of `values` (oldest first), drawn with ratatui's
+/// `Sparkline` into a line `width` cells wide so it can sit among the text
+/// of a scrolling panel. The newest values are kept when there are more than
+/// fit. Bars start at zero (or the lowest value, when that is negative) unless
+/// `baseline` is `Min`. Every sample gets at least the lowest bar, and a
+/// constant series is drawn at half height from a min baseline rather than flat.
+pub fn sparkline_line(
+    values: &[f64],
+    width: u16,
+    baseline: SparklineBaseline,
+    style: Style,
+) -> Line<'static> {
+    const LEVELS: u64 = 8;
+
+    let values = &values[values.len().saturating_sub(width as usize)..];
+    let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let floor = match baseline {
+        SparklineBaseline::Zero => low.min(0.0),
+        SparklineBaseline::Min => low,
+    };
+    let bars: Vec<u64> = values
+        .iter()
+        .map(|value| {
+            if high > floor {
+                1 + ((value - floor) / (high - floor) * (LEVELS - 1) as f64).round() as u64
+            } else if baseline == SparklineBaseline::Min {
+                LEVELS / 2
+            } else {
+                1
+            }
+        })
+        .collect();
+
+    let area = Rect::new(0, 0, width, 1);
+    let mut buffer = Buffer::empty(area);
+    Sparkline::default()
+        .data(&bars)
+        .max(LEVELS)
+        .style(style)
+        .render(area, &mut buffer);
+    let bars: String = buffer.content.iter().map(|cell| cell.symbol()).collect();
+    Line::from(Span::styled(bars, style))
+}
+
+/// Green for up states, red for down, and a stable color per name otherwise
+pub fn state_color(state: &str) -> Color {
+    match StateKind::of(state) {
+        StateKind::Up => Color::Green,
+        StateKind::Down => Color::Red,
+        StateKind::Other => {
+            let hash = state
+                .bytes()
+                .fold(0usize, |h, b| h.wrapping_mul(31) + b as usize);
+            OTHER_STATE_COLORS[hash % OTHER_STATE_COLORS.len()]
+        }
     }
-    let mut end = max_bytes;
-    while end > 0 && !s.is_char_boundary(end) {
-        end -= 1;
+}
+
+/// One-row strip of a state timeline, one colored block per column, with
+/// runs of the same state merged into one span
+pub fn state_strip_line(states: &[&str]) -> Line<'static> {
+    let mut runs: Vec<(&str, usize)> = Vec::new();
+    for state in states {
+        match runs.last_mut() {
+            Some((current, len)) if current == state => *len += 1,
+            _ => runs.push((state, 1)),
+        }
+    }
+    let spans: Vec<Span<'static>> = runs
+        .into_iter()
+        .map(|(state, len)| Span::styled("█".repeat(len), Style::default().fg(state_color(state))))
+        .collect();
+    Line::from(spans)
+}
+
+/// Spans for an input value with a blinking block cursor and the selection
+/// shown reversed
+pub fn input_spans(value: &str, cursor: InputCursor, style: Style) -> Vec<Span<'static>> {
+    let pos = text::clamp_cursor(value, cursor.pos());
+    let (start, end) = cursor.selection(value).unwrap_or((pos, pos));
+    let mark = Span::styled(
+        "\u{258c}",
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::SLOW_BLINK),
+    );
+    let selected = Span::styled(
+        value[start..end].to_string(),
+        style.add_modifier(Modifier::REVERSED),
+    );
+
+    let mut spans = vec![Span::styled(value[..start].to_string(), style)];
+    if pos == start {
+        spans.extend([mark, selected]);
+    } else {
+        spans.extend([selected, mark]);
     }
-    &s[..end]
+    spans.push(Span::styled(value[end..].to_string(), style));
+    spans
+}
+
+/// Topic completions under a topic input: the next level's names, `Tab` to accept
+pub fn completion_line(value: &str, completions: &[String], separator: char) -> Line<'static> {
+    const SHOWN: usize = 8;
+    if completions.is_empty() || completions == [value] {
+        return Line::default();
+    }
+    let mut spans = vec![Span::styled("Tab ", Style::default().fg(Color::Yellow))];
+    for completion in completions.iter().take(SHOWN) {
+        let name = completion.trim_end_matches(separator);
+        let name = name.rsplit(separator).next().unwrap_or(name);
+        let (name, color) = if completion.ends_with(separator) {
+            (format!("{}{}", name, separator), Color::Cyan)
+        } else {
+            (name.to_string(), Color::White)
+        };
+        spans.push(Span::styled(
+            format!("{}  ", name),
+            Style::default().fg(color),
+        ));
+    }
+    if completions.len() > SHOWN {
+        spans.push(Span::styled(
+            format!("+{} more", completions.len() - SHOWN),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Break spans into lines at embedded newlines, keeping each part's style
+fn split_lines(spans: Vec<Span<'static>>) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::default()];
+    for span in spans {
+        for (i, part) in span.content.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Line::default());
+            }
+            if let Some(line) = lines.last_mut() {
+                line.push_span(Span::styled(part.to_string(), span.style));
+            }
+        }
+    }
+    lines
 }
 
 /// Render a single-line text input field with a blinking block cursor
@@ -44,7 +227,7 @@ pub fn render_text_field(
     frame: &mut Frame,
     label: &str,
     value: &str,
-    cursor: usize,
+    cursor: InputCursor,
     focused: bool,
     area: Rect,
 ) {
@@ -63,18 +246,11 @@ pub fn render_text_field(
     frame.render_widget(block, area);
 
     if focused {
-        let cursor_pos = cursor.min(value.len());
-        let (before, after) = value.split_at(cursor_pos);
-        let line = Line::from(vec![
-            Span::styled(before.to_string(), Style::default().fg(Color::White)),
-            Span::styled(
-                "\u{258c}",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::SLOW_BLINK),
-            ),
-            Span::styled(after.to_string(), Style::default().fg(Color::White)),
-        ]);
+        let line = Line::from(input_spans(
+            value,
+            cursor,
+            Style::default().fg(Color::White),
+        ));
         frame.render_widget(Paragraph::new(line), inner);
     } else {
         let text = Paragraph::new(value.to_string()).style(Style::default().fg(Color::Gray));
@@ -87,7 +263,7 @@ pub fn render_multiline_field(
     frame: &mut Frame,
     label: &str,
     value: &str,
-    cursor: usize,
+    cursor: InputCursor,
     focused: bool,
     area: Rect,
 ) {
@@ -106,19 +282,19 @@ pub fn render_multiline_field(
     frame.render_widget(block, area);
 
     if focused {
-        let cursor_pos = cursor.min(value.len());
-        let (before, after) = value.split_at(cursor_pos);
-        let line = Line::from(vec![
-            Span::styled(before.to_string(), Style::default().fg(Color::White)),
-            Span::styled(
-                "\u{258c}",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::SLOW_BLINK),
-            ),
-            Span::styled(after.to_string(), Style::default().fg(Color::White)),
-        ]);
-        let paragraph = Paragraph::new(line).wrap(ratatui::widgets::Wrap { trim: false });
+        let lines = split_lines(input_spans(
+            value,
+            cursor,
+            Style::default().fg(Color::White),
+        ));
+        // Keep the cursor's line in view for long pasted payloads
+        let cursor_line = value[..text::clamp_cursor(value, cursor.pos())]
+            .matches('\n')
+            .count();
+        let scroll = cursor_line.saturating_sub(inner.height.saturating_sub(1) as usize);
+        let paragraph = Paragraph::new(lines)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .scroll((scroll as u16, 0));
         frame.render_widget(paragraph, inner);
     } else {
         let text = Paragraph::new(value.to_string())
@@ -0,0 +1,217 @@
+//! JSON endpoints under `/api/`, answered from the live `App` state
+
+use serde_json::{json, Value};
+
+use crate::app::App;
+use crate::mqtt::MqttMessage;
+use crate::state::metric_tracker::{topic_matches, MetricPoint, TrackedMetric};
+
+/// Messages listed in the dashboard's live feed
+const RECENT_MESSAGES: usize = 50;
+/// Payload bytes shown per message
+const PAYLOAD_PREVIEW_BYTES: usize = 512;
+/// Points per metric sent for the dashboard's sparklines, spanning its history
+const METRIC_POINTS: usize = 120;
+/// Window for device message rates, as in the Devices panel
+const DEVICE_RATE_WINDOW: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Answer a GET for `path` with its decoded query parameters.
+/// Returns the HTTP status code and the JSON body.
+pub fn respond(app: &App, path: &str, query: &[(String, String)]) -> (u16, Value) {
+    let param = |name: &str| {
+        query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let limit = match param("limit").map(str::parse::<usize>) {
+        Some(Ok(limit)) => Some(limit),
+        Some(Err(_)) => return error(400, "limit must be a number"),
+        None => None,
+    };
+
+    match path {
+        "/api/status" => (200, status(app)),
+        "/api/snapshot" => (200, snapshot(app)),
+        "/api/topics" => (200, topics(app, param("filter"))),
+        "/api/messages" => messages(app, param("topic"), limit),
+        "/api/devices" => devices(app, param("status")),
+        "/api/metrics" => metrics(app, param("label")),
+        _ => error(404, &format!("No endpoint {}", path)),
+    }
+}
+
+fn error(code: u16, message: &str) -> (u16, Value) {
+    (code, json!({ "error": message }))
+}
+
+fn status(app: &App) -> Value {
+    let (healthy, warning, stale, unknown) = app.device_tracker.count_by_status();
+    json!({
+        "status": app.connection_status(),
+        "server": app.active_server_info().map(|server| server.name),
+        "topics": app.topic_tree.topic_count(),
+        "messages_per_second": app.stats.messages_per_second(),
+        "bytes_per_second": app.stats.bytes_per_second(),
+        "total_messages": app.stats.total_messages(),
+        "total_bytes": app.stats.total_bytes(),
+        "uptime": app.stats.uptime_string(),
+        "devices": {
+            "ok": healthy,
+            "slow": warning,
+            "stale": stale,
+            "new": unknown,
+            "offline": app.device_tracker.offline_count(),
+        },
+        "last_error": app.last_error.as_ref().map(|error| error.summary.clone()),
+    })
+}
+
+/// Everything the dashboard shows, in one response. Hidden topics stay hidden.
+fn snapshot(app: &App) -> Value {
+    let include = |topic: &str| !app.is_hidden(topic);
+    let mut snapshot = status(app);
+    snapshot["tree"] = app.topic_tree.export_value(&include);
+    snapshot["messages"] = recent_messages(app, RECENT_MESSAGES).into();
+    snapshot["metrics"] = app
+        .metric_tracker
+        .get_metrics()
+        .into_iter()
+        .map(metric_json)
+        .collect::<Vec<_>>()
+        .into();
+    snapshot
+}
+
+fn topics(app: &App, filter: Option<&str>) -> Value {
+    app.topic_tree
+        .get_all_topics()
+        .into_iter()
+        .filter(|topic| !app.is_hidden(topic))
+        .filter(|topic| filter.is_none_or(|pattern| topic_matches(pattern, topic)))
+        .filter_map(|topic| {
+            let (messages, bytes, last) = app.topic_tree.get_topic_stats(&topic)?;
+            Some(json!({
+                "topic": topic,
+                "messages": messages,
+                "bytes": bytes,
+                "last_message": last
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|time| time.to_rfc3339()),
+                "starred": app.user_data.is_starred(&topic),
+            }))
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// Newest first: one topic's buffer, or the latest across all topics
+fn messages(app: &App, topic: Option<&str>, limit: Option<usize>) -> (u16, Value) {
+    let Some(topic) = topic else {
+        return (
+            200,
+            recent_messages(app, limit.unwrap_or(RECENT_MESSAGES)).into(),
+        );
+    };
+    if app.is_hidden(topic) || app.message_buffer.count_for_topic(topic) == 0 {
+        return error(404, &format!("No messages on {}", topic));
+    }
+    let messages: Vec<_> = app
+        .message_buffer
+        .get_entries(topic)
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|entry| {
+            let mut message = message_json(&entry.message);
+            message["repeats"] = entry.repeats.into();
+            message
+        })
+        .collect();
+    (200, messages.into())
+}
+
+fn recent_messages(app: &App, limit: usize) -> Vec<Value> {
+    app.message_buffer
+        .get_recent_all(limit.saturating_mul(2))
+        .into_iter()
+        .filter(|message| !app.is_hidden(&message.topic))
+        .take(limit)
+        .map(message_json)
+        .collect()
+}
+
+fn message_json(message: &MqttMessage) -> Value {
+    let (payload, encoding) = match message.payload_str_prefix(PAYLOAD_PREVIEW_BYTES) {
+        Some(text) => (text.to_string(), "text"),
+        None => (message.payload_hex_prefix(PAYLOAD_PREVIEW_BYTES), "hex"),
+    };
+    json!({
+        "topic": message.topic,
+        "time": message.timestamp.to_rfc3339(),
+        "qos": message.qos,
+        "retain": message.retain,
+        "size": message.payload_size(),
+        "payload": payload,
+        "encoding": encoding,
+        "truncated": message.payload_size() > PAYLOAD_PREVIEW_BYTES,
+    })
+}
+
+fn devices(app: &App, status: Option<&str>) -> (u16, Value) {
+    const STATUSES: [&str; 4] = ["ok", "slow", "stale", "new"];
+    if let Some(status) = status {
+        if !STATUSES.contains(&status) {
+            return error(400, "status must be ok, slow, stale or new");
+        }
+    }
+    let devices: Vec<_> = app
+        .devices_by_priority(false)
+        .into_iter()
+        .filter(|device| status.is_none_or(|status| device.status.label() == status))
+        .map(|device| {
+            json!({
+                "id": device.device_id,
+                "type": device.device_type,
+                "status": device.status.label(),
+                "messages": device.message_count,
+                "messages_per_minute": device.messages_per_minute(DEVICE_RATE_WINDOW),
+                "seconds_since_last": device.time_since_last().as_secs(),
+                "topics": device.topics,
+                "starred": app.user_data.is_device_starred(&device.device_id),
+            })
+        })
+        .collect();
+    (200, devices.into())
+}
+
+fn metrics(app: &App, label: Option<&str>) -> (u16, Value) {
+    let metrics = app.metric_tracker.get_metrics();
+    match label {
+        Some(label) => match metrics.into_iter().find(|metric| metric.label == label) {
+            Some(metric) => (200, metric_json(metric)),
+            None => error(404, &format!("No tracked metric {}", label)),
+        },
+        None => (200, metrics.into_iter().map(metric_json).collect()),
+    }
+}
+
+fn metric_json(metric: &TrackedMetric) -> Value {
+    let seen = metric.count > 0;
+    json!({
+        "label": metric.label,
+        "topic": metric.topic_pattern,
+        "field": metric.field_path,
+        "regex": metric.regex.is_some(),
+        "latest": metric.latest(),
+        "min": seen.then_some(metric.min),
+        "max": seen.then_some(metric.max),
+        "avg": seen.then(|| metric.avg()),
+        "count": metric.count,
+        "display": metric.display,
+        "points": metric
+            .history(METRIC_POINTS)
+            .iter()
+            .map(MetricPoint::avg)
+            .collect::<Vec<_>>(),
+    })
+}
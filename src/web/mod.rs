@@ -0,0 +1,340 @@
+mod api;
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info};
+
+use crate::app::App;
+
+/// Largest request head accepted, and how long a client gets to send it
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const INDEX_HTML: &str = include_str!("index.html");
+
+/// An `/api/` request waiting for the main loop to answer it
+struct ApiRequest {
+    path: String,
+    query: Vec<(String, String)>,
+    reply: oneshot::Sender<(u16, String)>,
+}
+
+/// Serves read-only JSON endpoints over the running session, and with
+/// `--web` a dashboard page built on them.
+///
+/// Connections are handled in the background, but `/api/` requests are
+/// queued for the main loop, which answers them from the live state in
+/// [`WebServer::answer_pending`]. Nothing a client sends can change the
+/// session.
+pub struct WebServer {
+    requests: mpsc::UnboundedReceiver<ApiRequest>,
+    local_addr: SocketAddr,
+    dashboard: bool,
+}
+
+impl WebServer {
+    /// Bind the listener and start accepting requests in the background.
+    /// Without `dashboard` only the `/api/` endpoints are served.
+    pub async fn bind(addr: &str, dashboard: bool) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind web server on {}", addr))?;
+        let local_addr = listener.local_addr()?;
+        let (queue, requests) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let queue = queue.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_request(stream, queue, dashboard).await {
+                                debug!("Web request from {} failed: {:?}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        debug!("Web accept failed: {:?}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Web server listening on {}", local_addr);
+        Ok(Self {
+            requests,
+            local_addr,
+            dashboard,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Header label, e.g. `web :8080` (or `api :8080` without the dashboard)
+    pub fn label(&self) -> String {
+        let kind = if self.dashboard { "web" } else { "api" };
+        format!("{} :{}", kind, self.local_addr.port())
+    }
+
+    /// Answer queued API requests from the current state
+    pub fn answer_pending(&mut self, app: &App) {
+        while let Ok(request) = self.requests.try_recv() {
+            let (status, body) = api::respond(app, &request.path, &request.query);
+            let _ = request.reply.send((status, body.to_string()));
+        }
+    }
+}
+
+/// `--web` and `--api` take a port, listened on at localhost only since
+/// there is no login, or a full address such as `0.0.0.0:8080` to expose it
+pub fn listen_addr(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        format!("127.0.0.1:{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+async fn serve_request(
+    mut stream: TcpStream,
+    queue: mpsc::UnboundedSender<ApiRequest>,
+    dashboard: bool,
+) -> Result<()> {
+    let head = tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream))
+        .await
+        .context("Request timed out")??;
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or("/"),
+    );
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/") | ("GET", "/index.html") if dashboard => {
+            (200, "text/html; charset=utf-8", INDEX_HTML.to_string())
+        }
+        ("GET", path) if path.starts_with("/api/") => {
+            let (reply, answer) = oneshot::channel();
+            let request = ApiRequest {
+                path: path.to_string(),
+                query: parse_query(query),
+                reply,
+            };
+            let answer = match queue.send(request) {
+                Ok(()) => tokio::time::timeout(REQUEST_TIMEOUT, answer)
+                    .await
+                    .ok()
+                    .and_then(Result::ok),
+                Err(_) => None,
+            };
+            match answer {
+                Some((status, body)) => (status, "application/json", body),
+                None => (
+                    503,
+                    "application/json",
+                    r#"{"error":"mqtop is busy"}"#.to_string(),
+                ),
+            }
+        }
+        ("GET", _) => (404, "text/plain", "Not found\n".to_string()),
+        _ => (405, "text/plain", "Read-only, use GET\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Service Unavailable",
+    }
+}
+
+/// Read up to the blank line that ends the request head
+async fn read_head(stream: &mut TcpStream) -> Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+        if head.len() > MAX_REQUEST_BYTES {
+            bail!("Request head too large");
+        }
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// `a=1&b=x%2Fy` into pairs. `+` is kept as is, since it is an MQTT wildcard.
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::mqtt::{MqttEvent, MqttMessage};
+    use crate::paths::Paths;
+
+    async fn get(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        tokio::time::timeout(Duration::from_secs(2), stream.read_to_string(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        response
+    }
+
+    fn body(response: &str) -> serde_json::Value {
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_api_answered_from_app_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths {
+            config_dir: dir.path().to_path_buf(),
+            state_dir: dir.path().to_path_buf(),
+            cache_dir: dir.path().to_path_buf(),
+            profile: None,
+        };
+        let config = Config::parse("[mqtt]\nactive_server = \"\"\n").unwrap();
+        let mut app = App::new(config, paths.config_file(), &paths);
+        for payload in ["21.5", "22.0"] {
+            app.handle_mqtt_event(MqttEvent::Message(MqttMessage::new(
+                "home/kitchen/temp".to_string(),
+                payload.as_bytes().to_vec(),
+                0,
+                false,
+            )));
+        }
+        app.handle_mqtt_event(MqttEvent::Message(MqttMessage::new(
+            "home/door".to_string(),
+            b"open".to_vec(),
+            0,
+            false,
+        )));
+
+        let mut server = WebServer::bind("127.0.0.1:0", false).await.unwrap();
+        let addr = server.local_addr();
+        let requests = [
+            "GET /api/topics?filter=home/%2B/temp HTTP/1.1\r\n\r\n",
+            "GET /api/messages?topic=home%2Fkitchen%2Ftemp&limit=1 HTTP/1.1\r\n\r\n",
+            "GET /api/messages?topic=home/garage HTTP/1.1\r\n\r\n",
+            "GET /api/status HTTP/1.1\r\n\r\n",
+            "GET / HTTP/1.1\r\n\r\n",
+        ];
+        let clients = tokio::spawn(async move {
+            let mut responses = Vec::new();
+            for request in requests {
+                responses.push(get(addr, request).await);
+            }
+            responses
+        });
+        while !clients.is_finished() {
+            server.answer_pending(&app);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let responses = clients.await.unwrap();
+
+        let topics = body(&responses[0]);
+        assert_eq!(topics.as_array().unwrap().len(), 1);
+        assert_eq!(topics[0]["topic"], "home/kitchen/temp");
+        assert_eq!(topics[0]["messages"], 2);
+
+        let messages = body(&responses[1]);
+        assert_eq!(messages.as_array().unwrap().len(), 1);
+        assert_eq!(messages[0]["payload"], "22.0");
+
+        assert!(responses[2].starts_with("HTTP/1.1 404"));
+        assert_eq!(body(&responses[3])["topics"], 2);
+        // API only: no dashboard page
+        assert!(responses[4].starts_with("HTTP/1.1 404"));
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_page_and_read_only() {
+        let server = WebServer::bind("127.0.0.1:0", true).await.unwrap();
+        let page = get(server.local_addr(), "GET / HTTP/1.1\r\nHost: x\r\n\r\n").await;
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        assert!(page.contains("/api/snapshot"));
+
+        let write = get(server.local_addr(), "POST /api/topics HTTP/1.1\r\n\r\n").await;
+        assert!(write.starts_with("HTTP/1.1 405"));
+    }
+
+    #[test]
+    fn test_query_and_listen_addr() {
+        assert_eq!(
+            parse_query("filter=a/%2B/b&limit=5&flag"),
+            [
+                ("filter".to_string(), "a/+/b".to_string()),
+                ("limit".to_string(), "5".to_string()),
+                ("flag".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(percent_decode("sensors/%23"), "sensors/#");
+        assert_eq!(percent_decode("a+b%zz%+1"), "a+b%zz%+1");
+        assert_eq!(listen_addr("8080"), "127.0.0.1:8080");
+        assert_eq!(listen_addr("0.0.0.0:8080"), "0.0.0.0:8080");
+    }
+}
@@ -1,10 +1,9 @@
 //! Integration tests for mqtop
 //!
-//! Note: These tests require a running MQTT broker for full integration testing.
-//! Unit tests for resilience logic are in the resilience module.
+//! The broker tests run against the embedded demo broker, so no external
+//! MQTT broker is needed. Unit tests for resilience logic are in the resilience module.
 
 #![allow(unused_imports)]
-#![allow(unexpected_cfgs)]
 
 /// Test configuration parsing
 mod config_tests {
@@ -72,24 +71,116 @@ mod message_tests {
     }
 }
 
-/// Resilience and backoff tests are in src/mqtt/resilience.rs
-/// These integration tests would require a real or mock broker
-
-#[cfg(feature = "integration")]
+/// Resilience and backoff tests are in src/mqtt/resilience.rs.
+/// These run the real MQTT client against the embedded demo broker.
 mod broker_tests {
-    use super::*;
+    use std::time::Duration;
+
+    use mqtop::app::App;
+    use mqtop::demo::{self, MockBroker, Simulator};
+    use mqtop::mqtt::{DeliveryStatus, MqttClient, MqttEvent, MqttMessage};
+    use mqtop::paths::Paths;
+    use rumqttc::QoS;
     use tokio::sync::mpsc;
 
-    /// This test requires MQTT_TEST_HOST environment variable
+    const WAIT: Duration = Duration::from_secs(5);
+
+    async fn connect(broker: &MockBroker) -> (MqttClient, mpsc::UnboundedReceiver<MqttEvent>) {
+        let mut config = demo::config(broker.local_addr()).unwrap();
+        let server = config.mqtt.servers.remove(0);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let client = MqttClient::connect(server, tx).await.unwrap();
+        (client, rx)
+    }
+
+    async fn next_event(rx: &mut mpsc::UnboundedReceiver<MqttEvent>) -> MqttEvent {
+        tokio::time::timeout(WAIT, rx.recv())
+            .await
+            .expect("timed out waiting for a client event")
+            .expect("client event channel closed")
+    }
+
+    async fn next_message(rx: &mut mpsc::UnboundedReceiver<MqttEvent>) -> MqttMessage {
+        loop {
+            if let MqttEvent::Message(message) = next_event(rx).await {
+                return message;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retained_then_live_messages() {
+        let broker = MockBroker::bind("127.0.0.1:0").await.unwrap();
+        broker.publish("devices/lamp/state", "ON", true);
+
+        let (_client, mut rx) = connect(&broker).await;
+        let retained = next_message(&mut rx).await;
+        assert_eq!(retained.topic, "devices/lamp/state");
+        assert_eq!(&retained.payload[..], b"ON");
+        assert!(retained.retain);
+
+        // Subscribed now, so live traffic flows too
+        broker.publish("telemetry/dev-1/meter", r#"{"power_w":120}"#, false);
+        let live = next_message(&mut rx).await;
+        assert_eq!(live.topic, "telemetry/dev-1/meter");
+        assert!(!live.retain);
+    }
+
     #[tokio::test]
-    async fn test_connection_to_broker() {
-        let host = std::env::var("MQTT_TEST_HOST").unwrap_or_else(|_| "localhost".to_string());
-        let port: u16 = std::env::var("MQTT_TEST_PORT")
-            .ok()
-            .and_then(|p| p.parse().ok())
-            .unwrap_or(1883);
-
-        // This would test actual broker connection
-        // Skipped by default to avoid CI failures without broker
+    async fn test_publish_round_trip_is_acknowledged() {
+        let broker = MockBroker::bind("127.0.0.1:0").await.unwrap();
+        broker.publish("ready", "1", true);
+        let (client, mut rx) = connect(&broker).await;
+        next_message(&mut rx).await;
+
+        client
+            .publish("commands/dev-1/reboot", b"now", QoS::AtLeastOnce, false)
+            .await
+            .unwrap();
+
+        let mut echoed = false;
+        let mut confirmed = false;
+        while !(echoed && confirmed) {
+            match next_event(&mut rx).await {
+                MqttEvent::Message(message) if message.topic == "commands/dev-1/reboot" => {
+                    assert_eq!(&message.payload[..], b"now");
+                    echoed = true;
+                }
+                MqttEvent::Delivery(delivery) => {
+                    assert_eq!(delivery.status, DeliveryStatus::Confirmed);
+                    confirmed = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulated_feed_reaches_app() {
+        let broker = MockBroker::bind("127.0.0.1:0").await.unwrap();
+        Simulator::new().spawn(broker.clone());
+        let (_client, mut rx) = connect(&broker).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths {
+            config_dir: dir.path().to_path_buf(),
+            state_dir: dir.path().to_path_buf(),
+            cache_dir: dir.path().to_path_buf(),
+            profile: None,
+        };
+        let config = demo::config(broker.local_addr()).unwrap();
+        let mut app = App::new(config, paths.config_file(), &paths);
+
+        let deadline = tokio::time::Instant::now() + WAIT;
+        while app.topic_tree.topic_count() < 20 {
+            assert!(tokio::time::Instant::now() < deadline, "feed too slow");
+            app.handle_mqtt_event(next_event(&mut rx).await);
+        }
+        assert_eq!(app.connection_status(), "Connected");
+        assert!(app
+            .topic_tree
+            .get_all_topics()
+            .iter()
+            .any(|topic| topic.starts_with("sites/plant-north/devices/")));
     }
 }
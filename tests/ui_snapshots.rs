@@ -0,0 +1,1022 @@
+//! Rendering tests: scripted broker events and key presses go into `App`, and
+//! the frame drawn on a `TestBackend` is compared against a stored snapshot.
+//!
+//! After an intended UI change, review and accept the new frames with
+//! `cargo insta review` (or `INSTA_UPDATE=always cargo test --test ui_snapshots`).
+
+use chrono::{TimeZone, Utc};
+use crossterm::event::{KeyCode, KeyModifiers};
+use mqtop::app::{App, InputMode, Panel, SubscribeWarning};
+use mqtop::config::{Config, DisplayTimeZone};
+use mqtop::friendly_names::FriendlyNames;
+use mqtop::mqtt::{
+    BrokerCapabilities, ConnectionDetails, ConnectionState, MqttEvent, MqttMessage, NegotiatedTls,
+};
+use mqtop::paths::Paths;
+use mqtop::ui;
+use ratatui::{backend::TestBackend, Terminal};
+use rumqttc::tokio_rustls::rustls::{CipherSuite, ProtocolVersion};
+use std::time::Instant;
+
+const CONFIG: &str = r##"
+[mqtt]
+active_server = "lab"
+
+[[mqtt.servers]]
+name = "lab"
+host = "broker.lab"
+port = 1883
+client_id = "mqtop-test"
+subscribe_topic = "#"
+"##;
+
+/// Snapshot a frame with wall-clock readings (ages, uptime, dates) masked, so a
+/// slow run can't turn `0s ago` into `1s ago`
+macro_rules! assert_frame {
+    ($harness:expr) => {
+        insta::with_settings!({
+            filters => vec![
+                (r"\b\d+[smh] ago", "[age] "),
+                (r"Uptime  \d+[smh]", "Uptime  [t]"),
+                (r"\d*-\d\d-\d\d, ", "[date], "),
+                (r"next in \d+s", "next in [t]"),
+            ]
+        }, {
+            insta::assert_snapshot!($harness.render());
+        })
+    };
+}
+
+struct Harness {
+    app: App,
+    terminal: Terminal<TestBackend>,
+    _dir: tempfile::TempDir,
+}
+
+impl Harness {
+    fn new(width: u16, height: u16) -> Self {
+        Self::with_config(width, height, |_| {})
+    }
+
+    fn with_config(width: u16, height: u16, adjust: impl FnOnce(&mut Config)) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths {
+            config_dir: dir.path().to_path_buf(),
+            state_dir: dir.path().to_path_buf(),
+            cache_dir: dir.path().to_path_buf(),
+            profile: None,
+        };
+        let mut config = Config::parse(CONFIG).unwrap();
+        adjust(&mut config);
+        let app = App::new(config, paths.config_file(), &paths);
+        Self {
+            app,
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+            _dir: dir,
+        }
+    }
+
+    fn connected(mut self) -> Self {
+        self.app
+            .handle_mqtt_event(MqttEvent::StateChange(ConnectionState::Connected));
+        self
+    }
+
+    /// Deliver a message stamped at a fixed time, `seconds` after 12:00:00 UTC
+    fn message(&mut self, topic: &str, payload: &str, seconds: u32) {
+        self.message_bytes(topic, payload.as_bytes(), seconds);
+    }
+
+    fn message_bytes(&mut self, topic: &str, payload: &[u8], seconds: u32) {
+        let mut message = MqttMessage::new(topic.to_string(), payload.to_vec(), 0, false);
+        message.timestamp = Utc
+            .with_ymd_and_hms(2024, 5, 1, 12, 0, seconds)
+            .single()
+            .unwrap();
+        self.app.handle_mqtt_event(MqttEvent::Message(message));
+    }
+
+    fn fleet(mut self) -> Self {
+        self.message(
+            "sites/north/devices/press-1/telemetry",
+            r#"{"rpm":1200,"temp_c":55.2}"#,
+            0,
+        );
+        self.message("sites/north/devices/press-1/status", "online", 1);
+        self.message(
+            "sites/north/devices/lathe-2/telemetry",
+            r#"{"rpm":900,"temp_c":48.9}"#,
+            2,
+        );
+        self.message(
+            "telemetry/meter-0a17/meter/power/json",
+            r#"{"power_w":1830}"#,
+            3,
+        );
+        self.message("devices/light-kitchen/state", "ON", 4);
+        self
+    }
+
+    fn key(&mut self, code: KeyCode) {
+        self.app.handle_key(code, KeyModifiers::NONE);
+    }
+
+    fn keys(&mut self, keys: &str) {
+        for ch in keys.chars() {
+            self.key(KeyCode::Char(ch));
+        }
+    }
+
+    /// Draw a frame and return it as plain text, one line per row
+    fn render(&mut self) -> String {
+        let frame = self
+            .terminal
+            .draw(|frame| ui::render(frame, &mut self.app))
+            .unwrap();
+        ui::buffer_to_text(frame.buffer, frame.area)
+    }
+}
+
+#[test]
+fn test_empty_disconnected() {
+    let mut harness = Harness::new(100, 20);
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_tree_collapsed() {
+    let mut harness = Harness::new(120, 24).connected().fleet();
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_tree_expanded_with_message() {
+    let mut harness = Harness::new(120, 24).connected().fleet();
+    // sites is the second root; open it down to press-1 and select its telemetry
+    for keys in ["j", "j", "j", "jj"] {
+        harness.keys(keys);
+        harness.key(KeyCode::Enter);
+    }
+    harness.keys("jj");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_breadcrumb_prefix() {
+    let mut harness = Harness::new(120, 24).connected().fleet();
+    for keys in ["j", "j", "j", "jj"] {
+        harness.keys(keys);
+        harness.key(KeyCode::Enter);
+    }
+    harness.keys("jjb");
+    harness.key(KeyCode::Left);
+    assert_eq!(
+        harness.app.breadcrumb_prefix().as_deref(),
+        Some("sites/north/devices/press-1")
+    );
+    assert_frame!(harness);
+
+    harness.keys("f");
+    assert_eq!(
+        harness.app.topic_filter.as_deref(),
+        Some("sites/north/devices/press-1/#")
+    );
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/north/devices/press-1/telemetry")
+    );
+}
+
+#[test]
+fn test_filter_to_subtree() {
+    let mut harness = Harness::new(120, 24).connected().fleet();
+    harness.keys("jo");
+    assert_eq!(harness.app.topic_filter.as_deref(), Some("sites/#"));
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("sites"));
+    assert_frame!(harness);
+
+    harness.keys("F");
+    assert_eq!(harness.app.topic_filter, None);
+}
+
+#[test]
+fn test_message_columns() {
+    let mut harness = Harness::new(120, 24).connected().fleet();
+    harness.message(
+        "sites/north/devices/press-1/telemetry",
+        r#"{"rpm":1250,"temp_c":56.0}"#,
+        8,
+    );
+    for keys in ["j", "j", "j", "jj"] {
+        harness.keys(keys);
+        harness.key(KeyCode::Enter);
+    }
+    harness.keys("jjItqsf");
+    harness.keys("rpm");
+    harness.key(KeyCode::Enter);
+    assert_frame!(harness);
+
+    harness.key(KeyCode::Esc);
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_timezone_offset() {
+    let mut harness = Harness::new(100, 20).connected().fleet();
+    harness.app.config.ui.timezone = DisplayTimeZone::try_from("+02:00".to_string()).unwrap();
+    for keys in ["j", "j", "j", "jj"] {
+        harness.keys(keys);
+        harness.key(KeyCode::Enter);
+    }
+    harness.keys("jj");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_header_title() {
+    let mut harness = Harness::new(100, 4).connected();
+    harness.app.config.ui.header.title = Some("Plant 7 {server} ({host})".to_string());
+    harness.app.config.ui.header.show_server = false;
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_link_quality() {
+    let mut harness = Harness::new(100, 4).connected();
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::StateChange(ConnectionState::Reconnecting));
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::StateChange(ConnectionState::Connected));
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_server_switch_keeps_data() {
+    let mut harness = Harness::with_config(100, 12, |config| {
+        let mut plant = config.mqtt.servers[0].clone();
+        plant.name = "plant".to_string();
+        plant.host = "broker.plant".to_string();
+        config.mqtt.servers.push(plant);
+    })
+    .connected()
+    .fleet();
+    let topics = harness.app.topic_tree.topic_count();
+
+    harness.keys("@2");
+    let switch = harness.app.pending_server_switch.take().unwrap();
+    harness
+        .app
+        .reset_for_server_switch(switch.kind, switch.index)
+        .unwrap();
+    assert_eq!(harness.app.topic_tree.topic_count(), 0);
+    harness.message("line/1/speed", "42", 0);
+
+    harness.keys("@");
+    assert_frame!(harness);
+
+    harness.keys("1");
+    let switch = harness.app.pending_server_switch.take().unwrap();
+    harness
+        .app
+        .reset_for_server_switch(switch.kind, switch.index)
+        .unwrap();
+    assert_eq!(harness.app.topic_tree.topic_count(), topics);
+    assert!(harness.app.parked_server(switch.kind, "plant").is_some());
+}
+
+#[test]
+fn test_tree_peek() {
+    let mut harness = Harness::new(100, 16).connected().fleet();
+    harness.keys("j");
+    let selected = harness.app.selected_topic.clone();
+    harness.keys("vjl");
+    assert_eq!(harness.app.selected_topic, selected);
+    assert_frame!(harness);
+
+    harness.keys("v");
+    assert_eq!(harness.app.selected_topic_index, 1);
+}
+
+#[test]
+fn test_follow_busiest() {
+    let mut harness = Harness::new(100, 16).connected().fleet();
+    harness.message("devices/light-kitchen/state", "OFF", 5);
+    harness.message("devices/light-kitchen/state", "ON", 6);
+    harness.keys("a");
+    harness.app.follow_busiest_topic();
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("devices/light-kitchen/state")
+    );
+    assert_frame!(harness);
+
+    harness.keys("a");
+    assert!(!harness.app.follow_busiest);
+}
+
+#[test]
+fn test_kiosk_pages() {
+    let mut harness = Harness::with_config(100, 20, |config| config.ui.kiosk = true)
+        .connected()
+        .fleet();
+    harness.message("devices/light-kitchen/state", "OFF", 5);
+    assert_frame!(harness);
+
+    // Straight to the busiest topic, whichever pages come between
+    harness.app.follow_busiest_topic();
+    harness.key(KeyCode::Left);
+    assert_frame!(harness);
+
+    harness.key(KeyCode::Esc);
+    assert!(harness.app.follow_busiest);
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("devices/light-kitchen/state")
+    );
+}
+
+#[test]
+fn test_tree_delivery_flags() {
+    let mut harness = Harness::new(80, 10).connected();
+    let mut retained = MqttMessage::new("config/lamp".to_string(), b"on".to_vec(), 1, true);
+    retained.timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).single().unwrap();
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::Message(retained.clone()));
+    retained.dup = true;
+    retained.qos = 2;
+    harness.app.handle_mqtt_event(MqttEvent::Message(retained));
+    harness.message("state/lamp", "on", 1);
+    harness.keys("lj");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_tree_keeps_selection_in_view() {
+    let mut harness = Harness::new(80, 7).connected().fleet();
+    harness.keys("G");
+    harness.render();
+
+    // New rows sort in above the highlighted topic
+    harness.message("alpha/x", "1", 5);
+    harness.message("beta/y", "2", 6);
+    assert_frame!(harness);
+    assert_eq!(harness.app.selected_topic_index, 4);
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("telemetry"));
+
+    // Collapsing the last branch leaves no blank rows at the bottom
+    harness.keys("lj");
+    harness.render();
+    assert_eq!(harness.app.tree_scroll, 3);
+    harness.keys("hh");
+    harness.render();
+    assert_eq!(harness.app.tree_scroll, 2);
+}
+
+#[test]
+fn test_follow_newest_topic() {
+    let mut harness = Harness::new(80, 10).connected().fleet();
+    harness.keys("n");
+    harness.message("sites/south/devices/pump-3/status", "online", 5);
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/south/devices/pump-3/status")
+    );
+    assert_frame!(harness);
+
+    // Known topics don't move the selection
+    harness.message("devices/light-kitchen/state", "OFF", 6);
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/south/devices/pump-3/status")
+    );
+}
+
+#[test]
+fn test_follow_newest_lands_on_old_row() {
+    let mut harness = Harness::new(80, 10).connected().fleet();
+    harness.keys("jn");
+    harness.render();
+
+    // The new topic takes the highlighted row; the highlight stays with it
+    harness.message("aardvark/x", "1", 5);
+    harness.render();
+    assert_eq!(harness.app.selected_topic_index, 1);
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("aardvark/x"));
+}
+
+#[test]
+fn test_tree_structural_motions() {
+    let mut harness = Harness::new(80, 12).connected().fleet();
+    harness.keys("})))");
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/north/devices/lathe-2")
+    );
+    harness.keys("}}");
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/north/devices/press-1")
+    );
+    assert_frame!(harness);
+
+    // Siblings skip over expanded subtrees
+    harness.keys("(((}");
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("telemetry"));
+    harness.keys("{");
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("sites"));
+}
+
+#[test]
+fn test_tree_structural_motions_filtered() {
+    let mut harness = Harness::new(80, 12).connected().fleet();
+    harness.keys("jL");
+
+    // Telemetry topics of different devices are cousins, not siblings
+    harness.app.topic_filter = Some("sites/+/devices/+/telemetry".to_string());
+    harness.keys("g}(");
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/north/devices/lathe-2/telemetry")
+    );
+    let status = harness
+        .app
+        .status_message
+        .as_ref()
+        .map(|(text, _)| text.as_str());
+    assert_eq!(status, Some("No parent"));
+    harness.keys("}");
+    assert_frame!(harness);
+
+    // Children hidden by the filter can't be entered
+    harness.app.topic_filter = Some("sites/+/devices/+".to_string());
+    harness.keys("G)");
+    assert_eq!(harness.app.selected_topic_index, 1);
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/north/devices/press-1")
+    );
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_count_prefix_motions() {
+    let mut harness = Harness::new(80, 12).connected().fleet();
+    harness.key(KeyCode::Enter);
+    harness.keys("}");
+    harness.key(KeyCode::Enter);
+
+    // A count before a motion stays in the focused panel, even 1-4
+    harness.keys("2j");
+    assert_eq!(harness.app.focused_panel, Panel::TopicTree);
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("telemetry"));
+    harness.keys("3k");
+    assert_eq!(harness.app.focused_panel, Panel::TopicTree);
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("devices/light-kitchen")
+    );
+
+    harness.keys("12");
+    assert_eq!(harness.app.focused_panel, Panel::TopicTree);
+    assert_frame!(harness);
+    harness.keys("j");
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("telemetry"));
+    harness.keys("1G");
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("devices"));
+    harness.keys("10G");
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("telemetry"));
+
+    // A lone 1-4 is held until the next key shows it is not a count
+    harness.keys("2");
+    assert_eq!(harness.app.focused_panel, Panel::TopicTree);
+    assert_eq!(harness.app.pending_count, Some(2));
+    harness.keys("?");
+    assert_eq!(harness.app.focused_panel, Panel::Messages);
+    assert_eq!(harness.app.pending_count, None);
+    assert!(harness.app.show_help);
+}
+
+#[test]
+fn test_count_prefix_long_move() {
+    let mut harness = Harness::new(80, 12).connected();
+    for room in 0..30 {
+        harness.message(&format!("room-{room:02}/temp"), "21.5", 0);
+    }
+    harness.app.focused_panel = Panel::TopicTree;
+    harness.keys("1G");
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("room-00"));
+    harness.keys("25j");
+    assert_eq!(harness.app.focused_panel, Panel::TopicTree);
+    assert_eq!(harness.app.selected_topic.as_deref(), Some("room-25"));
+    assert_eq!(harness.app.pending_count, None);
+}
+
+#[test]
+fn test_narrow_subscription_for_session() {
+    let mut harness = Harness::new(100, 20).connected().fleet();
+    harness.app.subscribe_warning = Some(SubscribeWarning {
+        rate: 2500.0,
+        suggestions: vec!["sites/#".to_string()],
+    });
+    harness.app.input_mode = InputMode::SubscribeWarning;
+    harness.keys("1");
+    let session = harness.app.session_mqtt_server().unwrap();
+    assert_eq!(session.subscribe_topic, "sites/#");
+    assert_eq!(harness.app.config.mqtt.servers[0].subscribe_topic, "#");
+    assert!(harness.app.pending_server_switch.is_some());
+}
+
+#[test]
+fn test_session_keeps_repeats() {
+    let mut harness = Harness::new(100, 20).connected().fleet();
+    harness.message("devices/light-kitchen/state", "ON", 10);
+    harness.message("devices/light-kitchen/state", "ON", 20);
+    let path = harness._dir.path().join("repeats.json");
+    harness.app.session_snapshot().save_to(&path).unwrap();
+
+    let mut reloaded = Harness::new(100, 20);
+    reloaded.app.load_session(&path).unwrap();
+    let entries = reloaded
+        .app
+        .message_buffer
+        .get_entries("devices/light-kitchen/state");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].repeats, 2);
+    assert_eq!(
+        entries[0].last_seen,
+        Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 20)
+            .single()
+            .unwrap()
+    );
+    assert_eq!(reloaded.app.message_buffer.total_repeats(), 2);
+}
+
+#[test]
+fn test_payload_sniffing() {
+    let mut harness = Harness::new(100, 14).connected();
+    // MessagePack for {"rpm": 1200, "ok": true}
+    harness.message_bytes("plc/press", b"\x82\xa3rpm\xcd\x04\xb0\xa2ok\xc3", 0);
+    harness.keys("j");
+    harness.key(KeyCode::Enter);
+    harness.keys("j");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_buffer_limit_override() {
+    let mut harness = Harness::new(100, 14).connected().fleet();
+    for keys in ["j", "j", "j", "jj"] {
+        harness.keys(keys);
+        harness.key(KeyCode::Enter);
+    }
+    harness.keys("jjKKK");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_clear_subtree() {
+    let mut harness = Harness::new(100, 14).connected().fleet();
+    harness.keys("j");
+    harness.key(KeyCode::Enter);
+    harness.keys("jLU");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_away_batch() {
+    let mut harness = Harness::new(100, 16).connected().fleet();
+    harness.keys("Z");
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::SessionPresent(false));
+    harness.app.handle_mqtt_event(MqttEvent::Subscribed);
+    assert_eq!(harness.app.connection_status(), "Away (session kept)");
+
+    harness.keys("Z");
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::SessionPresent(true));
+    harness.message("sites/north/devices/press-1/status", "offline", 30);
+    harness.message("alarms/press-1", r#"{"code":"E42"}"#, 31);
+    harness.app.handle_mqtt_event(MqttEvent::Subscribed);
+    // Queued messages stay out of the live tree
+    assert!(harness
+        .app
+        .topic_tree
+        .get_topic_stats("alarms/press-1")
+        .is_none());
+
+    let batch = harness.app.away_batch.as_mut().unwrap();
+    batch.from = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).single().unwrap();
+    batch.to = Utc
+        .with_ymd_and_hms(2024, 5, 1, 12, 40, 0)
+        .single()
+        .unwrap();
+    harness.keys("j");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_away_session_phases() {
+    let mut harness = Harness::new(100, 16).connected().fleet();
+    harness.keys("Z");
+    assert!(harness.app.pending_away_connect);
+    let away = harness.app.away_server().unwrap();
+    assert!(!away.clean_session);
+    harness.app.pending_away_connect = false;
+    harness.app.handle_mqtt_event(MqttEvent::Subscribed);
+    assert!(harness.app.pending_disconnect);
+
+    harness.keys("Z");
+    assert!(harness.app.pending_away_connect);
+    harness.app.pending_away_connect = false;
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::SessionPresent(true));
+    harness.message("alarms/press-1", r#"{"code":"E42"}"#, 31);
+    harness.app.handle_mqtt_event(MqttEvent::Subscribed);
+    assert_eq!(harness.app.away_batch.as_ref().unwrap().messages.len(), 1);
+
+    // Once collected, the same client ID connects clean to drop the session
+    assert!(harness.app.pending_away_connect);
+    let discard = harness.app.away_server().unwrap();
+    assert_eq!(discard.client_id, away.client_id);
+    assert!(discard.clean_session);
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::SessionPresent(false));
+
+    // Then back to the server's own settings
+    assert!(harness.app.away.is_none());
+    assert!(harness.app.pending_reconnect);
+}
+
+#[test]
+fn test_two_panel_layout() {
+    let mut harness = Harness::new(90, 20).connected().fleet();
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_single_panel_layout() {
+    let mut harness = Harness::new(60, 14).connected().fleet();
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_help_overlay() {
+    let mut harness = Harness::new(120, 50).connected();
+    harness.keys("?");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_server_manager_overlay() {
+    let mut harness = Harness::new(120, 30);
+    harness.app.open_server_manager();
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_publish_overlay() {
+    let mut harness = Harness::new(120, 30).connected().fleet();
+    harness.keys("P");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_filter_overlay_with_input() {
+    let mut harness = Harness::new(120, 30).connected().fleet();
+    harness.keys("fsites/+");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_filter_live_preview() {
+    let mut harness = Harness::new(120, 30).connected().fleet();
+    // System topics stay out of the count while the tree leaves them out
+    harness.message("$SYS/broker/uptime", "42", 5);
+    harness.keys("jLjjj");
+    let selected = harness.app.selected_topic.clone();
+    harness.keys("fsites/+/devices/#");
+    assert_eq!(harness.app.topic_filter, None);
+
+    // Applied once typing pauses
+    harness.app.filter_preview_at = Some(Instant::now());
+    harness.app.preview_filter();
+    assert_eq!(
+        harness.app.topic_filter.as_deref(),
+        Some("sites/+/devices/#")
+    );
+    assert_eq!(harness.app.filter_matches, (3, 5));
+    assert_frame!(harness);
+
+    harness.key(KeyCode::Esc);
+    assert_eq!(harness.app.topic_filter, None);
+    assert_eq!(harness.app.selected_topic_index, 4);
+    assert_eq!(harness.app.selected_topic, selected);
+}
+
+#[test]
+fn test_screen_reader_mode() {
+    let mut harness = Harness::with_config(120, 24, |config| config.ui.screen_reader = true)
+        .connected()
+        .fleet();
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_ascii_mode() {
+    let mut harness = Harness::with_config(120, 24, |config| config.ui.ascii = true)
+        .connected()
+        .fleet();
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_tiny_terminals_do_not_panic() {
+    for (width, height) in [(0, 0), (1, 1), (2, 5), (10, 3), (20, 2), (120, 1), (1, 40)] {
+        let mut harness = Harness::new(width, height).connected().fleet();
+        harness.render();
+        // Overlays are sized from the frame too
+        harness.keys("?");
+        harness.render();
+        harness.key(KeyCode::Esc);
+        harness.keys("P");
+        harness.render();
+    }
+}
+
+#[test]
+fn test_narrow_panels_truncate() {
+    let mut harness = Harness::new(80, 12).connected();
+    harness.message(
+        "a/very/deep/topic/path/that/keeps/going/well/past/the/edge/of/the/panel",
+        r#"{"payload":"also much wider than the panel it is shown in, by a long way"}"#,
+        0,
+    );
+    assert_frame!(harness);
+}
+
+/// A varying and a constant metric, with sparklines as wide as the Stats panel.
+/// Every sample is kept, as they all arrive within one bucket.
+fn tracked_metrics(height: u16, adjust: impl FnOnce(&mut Config)) -> Harness {
+    let mut harness = Harness::with_config(120, height, |config| {
+        config.ui.metric_points = 0;
+        adjust(config);
+    })
+    .connected();
+    harness
+        .app
+        .metric_tracker
+        .track("temp".into(), "plant/temp".into(), "c".into());
+    harness
+        .app
+        .metric_tracker
+        .track("setpoint".into(), "plant/setpoint".into(), "c".into());
+    for (i, temp) in [20.0, 21.5, 21.0, 23.0, 22.5, 24.0].iter().enumerate() {
+        harness.message("plant/temp", &format!(r#"{{"c":{}}}"#, temp), i as u32);
+        harness.message("plant/setpoint", r#"{"c":22}"#, i as u32);
+    }
+    harness
+}
+
+#[test]
+fn test_stats_connection_details() {
+    let mut harness = Harness::with_config(120, 20, |config| {
+        config.mqtt.servers[0].use_tls = true;
+        config.mqtt.servers[0].port = 8883;
+        config.mqtt.servers[0].ca_cert = Some("/etc/mqtop/lab-ca.pem".to_string());
+    })
+    .connected();
+    let details = ConnectionDetails {
+        client_id: "mqtop-test-4711".to_string(),
+        keep_alive: std::time::Duration::from_secs(30),
+        address: Some("10.0.0.5:8883".parse().unwrap()),
+        capabilities: Some(BrokerCapabilities {
+            max_packet_size: Some(65_536),
+            max_qos: 1,
+            shared_available: false,
+            ..BrokerCapabilities::default()
+        }),
+        tls: Some(NegotiatedTls {
+            version: ProtocolVersion::TLSv1_3,
+            cipher_suite: CipherSuite::TLS13_AES_256_GCM_SHA384,
+        }),
+    };
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::Connection(details));
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_regenerate_client_id() {
+    let mut harness = Harness::new(100, 10).connected();
+    let details = ConnectionDetails::new(
+        "mqtop-test-4711".to_string(),
+        std::time::Duration::from_secs(30),
+    );
+    harness
+        .app
+        .handle_mqtt_event(MqttEvent::Connection(details));
+    harness.keys("S");
+    assert_frame!(harness);
+
+    harness.keys("r");
+    assert!(harness.app.pending_reconnect);
+    assert!(harness.app.connection_details.is_none());
+}
+
+#[test]
+fn test_stats_sparklines_from_zero() {
+    let mut harness = tracked_metrics(24, |_| {});
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_stats_sparklines_from_min() {
+    let mut harness = tracked_metrics(24, |config| {
+        config.ui.sparkline_baseline = mqtop::config::SparklineBaseline::Min
+    });
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_metric_detail_delta() {
+    let mut harness = tracked_metrics(30, |_| {});
+    harness.keys("Mjd");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_metric_chart_compares_series() {
+    use mqtop::state::metric_tracker::TrackedMetric;
+    use std::time::{Duration, Instant};
+
+    let mut harness = Harness::new(120, 30).connected();
+    // A minute of samples, so the time axis doesn't depend on how fast the test runs
+    let start = Instant::now() - Duration::from_secs(60);
+    for (label, scale) in [("inverter-1", 1.0), ("inverter-2", 2.0)] {
+        let mut metric = TrackedMetric::new(label.into(), "solar/+".into(), "W".into());
+        for i in 0..7u64 {
+            let value = scale * (1000.0 + 100.0 * (i % 3) as f64);
+            let at = start + Duration::from_secs(i * 10);
+            metric.record_at(value, at, Duration::from_secs(3600), Duration::ZERO);
+        }
+        harness.app.metric_tracker.insert(metric);
+    }
+    harness.keys("Mc");
+    harness.keys("Solar");
+    harness.key(KeyCode::Enter);
+    harness.keys("jc");
+    harness.key(KeyCode::Enter);
+    harness.keys("v");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_metric_select_text_payload() {
+    let mut harness = Harness::new(120, 30).connected();
+    harness.message("legacy/boiler", "OK temp=23.4 rssi -71dBm", 0);
+    harness.key(KeyCode::Enter);
+    harness.keys("jmje");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_state_timelines() {
+    use mqtop::state::StateTimeline;
+    use std::time::{Duration, Instant};
+
+    let mut harness = Harness::new(120, 30).connected();
+    // Changes at fixed offsets, so the strips and uptime don't depend on test speed
+    let start = Instant::now() - Duration::from_secs(60);
+    let hour = Duration::from_secs(3600);
+    let mut lwt = StateTimeline::new("pump-1".into(), "pumps/1/lwt".into(), String::new());
+    lwt.record_at("online".into(), start, hour);
+    lwt.record_at("offline".into(), start + Duration::from_secs(45), hour);
+    let mut status = StateTimeline::new(
+        "status (pumps/1)".into(),
+        "pumps/1/state".into(),
+        "status".into(),
+    );
+    for (secs, state) in [(0, "idle"), (20, "running"), (50, "error")] {
+        status.record_at(state.into(), start + Duration::from_secs(secs), hour);
+    }
+    harness.app.state_tracker.insert(lwt);
+    harness.app.state_tracker.insert(status);
+    harness.keys("Mj");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_cardinality_alert() {
+    let mut harness =
+        Harness::with_config(120, 30, |config| config.ui.cardinality_limit = 5).connected();
+    for ts in 1_700_000_000..1_700_000_007 {
+        harness.message(&format!("logs/gw-7/{}", ts), "boot", 0);
+    }
+    assert_frame!(harness);
+    harness.key(KeyCode::Char('-'));
+    assert!(harness.app.is_hidden("logs/gw-7/1700000003"));
+}
+
+#[test]
+fn test_high_load_mode() {
+    let mut harness =
+        Harness::with_config(120, 30, |config| config.ui.high_load_rate = 2).connected();
+    for i in 0..30 {
+        harness.message("plant/line1/power", &format!(r#"{{"watts": {}}}"#, i), 0);
+    }
+    harness.app.update_load_mode();
+    assert!(harness.app.high_load);
+    // Open plant/line1/power: its payloads show unhighlighted, 20 listed
+    for keys in ["", "j", "j"] {
+        harness.keys(keys);
+        harness.key(KeyCode::Enter);
+    }
+    harness.key(KeyCode::Tab);
+    harness.keys(&"j".repeat(25));
+    assert_eq!(harness.app.selected_message_index, 19);
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_traffic_summary() {
+    let mut harness =
+        Harness::with_config(120, 30, |config| config.ui.persist_topic_stats = true).connected();
+    harness.app.topic_totals_path = Some(harness._dir.path().join("totals.json"));
+    let now = Utc::now();
+    let mut send = |topic: &str, count: usize, days_ago: i64| {
+        let prefix = topic.split('/').next().unwrap();
+        for _ in 0..count {
+            let at = now - chrono::Duration::days(days_ago);
+            harness.app.topic_totals.record(topic, prefix, 64, at);
+        }
+    };
+    // A steady week, then today the bridge is silent and alarms triple
+    for days_ago in 1..=7 {
+        send("meters/1/power", 80, days_ago);
+        send("alarms/1", 10, days_ago);
+        send("legacy/bridge", 10, days_ago);
+    }
+    send("meters/1/power", 40, 0);
+    send("alarms/1", 20, 0);
+    send("debug/dump", 5, 0);
+
+    harness.keys("V");
+    assert_frame!(harness);
+    harness.keys("w");
+    assert!(harness.app.traffic_summary_weekly);
+}
+
+#[test]
+fn test_system_topics() {
+    let mut harness = Harness::new(120, 24).connected().fleet();
+    harness.message("$SYS/broker/uptime", "3600 seconds", 5);
+    harness.message("$SYS/broker/clients/connected", "12", 6);
+    assert_eq!(harness.app.application_topic_count(), 5);
+    assert_eq!(harness.app.get_visible_topics().len(), 3);
+    harness.keys("$");
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_friendly_names() {
+    let mut harness = Harness::new(120, 24).connected().fleet();
+    harness.app.friendly_names.extend(FriendlyNames::parse_csv(
+        "press-1,Hydraulic press\nlathe-2,Lathe",
+    ));
+    harness.keys("jljljljjlj");
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/north/devices/press-1/status")
+    );
+    assert_frame!(harness);
+}
+
+#[test]
+fn test_topic_templates() {
+    let mut harness = Harness::new(120, 24).connected();
+    for (i, meter) in ["mtr-1001", "mtr-1002", "mtr-1003"].iter().enumerate() {
+        for field in ["power", "voltage", "current", "energy", "frequency"] {
+            harness.message(&format!("sites/{}/{}", meter, field), "1", i as u32);
+        }
+    }
+    harness.message("status/online", "true", 4);
+    harness.keys("z");
+    harness.keys("l");
+    harness.keys("j");
+    harness.keys("l");
+    harness.keys("j");
+    assert_eq!(
+        harness.app.selected_topic.as_deref(),
+        Some("sites/{siteId}/{id}")
+    );
+    assert_eq!(harness.app.get_current_messages().len(), 15);
+    assert_frame!(harness);
+}